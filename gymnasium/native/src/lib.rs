@@ -786,6 +786,7 @@ fn diagram_render_options(input: &RenderOptionsInput) -> DiagramRenderOptions {
         } else {
             DiagramBackground::Table
         },
+        ..DiagramRenderOptions::default()
     }
 }
 
@@ -801,6 +802,7 @@ fn scenario_trace_render_options(
             ..BallPathRenderOptions::default()
         },
         start_ghost_balls: input.start_ghosts,
+        start_phantom_balls: false,
         event_markers: input.event_markers,
         labels: input.labels,
         path_color_mode: parse_path_color_mode(&input.trace_color_mode)?,
@@ -1076,6 +1078,12 @@ fn ball_type_name(ball: &BallType) -> &'static str {
         BallType::Seven => "seven",
         BallType::Eight => "eight",
         BallType::Nine => "nine",
+        BallType::Ten => "ten",
+        BallType::Eleven => "eleven",
+        BallType::Twelve => "twelve",
+        BallType::Thirteen => "thirteen",
+        BallType::Fourteen => "fourteen",
+        BallType::Fifteen => "fifteen",
     }
 }
 
@@ -1091,6 +1099,12 @@ fn ball_id(ball: &BallType) -> u8 {
         BallType::Seven => 7,
         BallType::Eight => 8,
         BallType::Nine => 9,
+        BallType::Ten => 10,
+        BallType::Eleven => 11,
+        BallType::Twelve => 12,
+        BallType::Thirteen => 13,
+        BallType::Fourteen => 14,
+        BallType::Fifteen => 15,
     }
 }
 
@@ -1133,6 +1147,12 @@ fn ball_number(ball: &BallType) -> Option<u8> {
         BallType::Seven => Some(7),
         BallType::Eight => Some(8),
         BallType::Nine => Some(9),
+        BallType::Ten => Some(10),
+        BallType::Eleven => Some(11),
+        BallType::Twelve => Some(12),
+        BallType::Thirteen => Some(13),
+        BallType::Fourteen => Some(14),
+        BallType::Fifteen => Some(15),
     }
 }
 
@@ -0,0 +1,1393 @@
+//! Idealized aiming aids for position-play diagrams.
+//!
+//! Everything here uses the equal-ball, no-throw ghost-ball model from `Ball::ghost_ball`: the
+//! object ball leaves along the line of centers at impact, and the cue ball is one ball diameter
+//! behind the object ball on that line.
+
+use crate::{
+    friction_coefficient_to_acceleration, position_from_xy_inches, position_xy_inches,
+    rolling_linear_deceleration, sliding_friction_acceleration, Angle, Ball, BallType, CutAngle,
+    Diamond, EffectivePocketOpening, Inches, InchesPerSecond, OnTableMotionConfig, Pocket,
+    Position, Rail, RollingResistanceModel, Scale, Segment, SlidingFrictionModel, TableSpec,
+    ThrowModel,
+};
+
+/// Which side of the cue-ball-to-object-ball line the object ball is cut toward.
+///
+/// This matches the shooter's view: a `Right` cut sends the object ball to the right of the
+/// straight-in line, which means the cue ball strikes the left side of the object ball.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CutDirection {
+    Left,
+    Right,
+}
+
+impl CutDirection {
+    fn sign(self) -> f64 {
+        match self {
+            CutDirection::Left => -1.0,
+            CutDirection::Right => 1.0,
+        }
+    }
+}
+
+/// The classic fractional-ball aiming references.
+///
+/// Each reference is a TP A.23 ball-hit fraction `f = 1 - sin(φ)`: the fraction of the object
+/// ball covered by the cue ball as seen along the aim line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BallHitFraction {
+    Full,
+    ThreeQuarter,
+    Half,
+    Quarter,
+}
+
+impl BallHitFraction {
+    pub const ALL: [BallHitFraction; 4] = [
+        BallHitFraction::Full,
+        BallHitFraction::ThreeQuarter,
+        BallHitFraction::Half,
+        BallHitFraction::Quarter,
+    ];
+
+    pub fn fraction(self) -> Scale {
+        Scale::from_f64(self.fraction_f64())
+    }
+
+    fn fraction_f64(self) -> f64 {
+        match self {
+            BallHitFraction::Full => 1.0,
+            BallHitFraction::ThreeQuarter => 0.75,
+            BallHitFraction::Half => 0.5,
+            BallHitFraction::Quarter => 0.25,
+        }
+    }
+
+    /// Return the cut angle produced by this hit fraction (0°, ~14.5°, 30°, and ~48.6°).
+    pub fn cut_angle(self) -> CutAngle {
+        CutAngle::from_hit_fraction(self.fraction())
+    }
+
+    /// Map a required cut angle to the nearest fractional aim reference.
+    pub fn nearest(cut_angle: CutAngle) -> FractionalAim {
+        let required = cut_angle.as_degrees();
+        let fraction = Self::ALL
+            .into_iter()
+            .min_by(|a, b| {
+                let a_error = (required - a.cut_angle().as_degrees()).abs();
+                let b_error = (required - b.cut_angle().as_degrees()).abs();
+                a_error.total_cmp(&b_error)
+            })
+            .expect("fractional aim references are non-empty");
+
+        FractionalAim {
+            fraction,
+            residual_degrees: required - fraction.cut_angle().as_degrees(),
+        }
+    }
+}
+
+/// The nearest fractional aim reference for a required cut, plus what is left over.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FractionalAim {
+    pub fraction: BallHitFraction,
+    /// Required cut angle minus the reference cut angle, in degrees. Positive values mean the shot
+    /// needs to be hit slightly thinner than the reference fraction.
+    pub residual_degrees: f64,
+}
+
+/// Compute the ghost-ball center produced by aiming the cue ball at a given hit fraction.
+///
+/// The aim line from `cue_ball` passes `2R (1 - f)` from the object-ball center, offset to the
+/// side that cuts the object ball toward `direction`. The returned point is where the cue-ball
+/// center sits at first contact along that line, so drawing a ghost ball there reproduces the
+/// classic overlap picture.
+pub fn fractional_aim_ghost_ball(
+    cue_ball: &Position,
+    object_ball: &Ball,
+    fraction: Scale,
+    direction: CutDirection,
+    table_spec: &TableSpec,
+) -> Position {
+    let (cue_x, cue_y) = position_xy_inches(cue_ball, table_spec);
+    let (object_x, object_y) = position_xy_inches(&object_ball.position, table_spec);
+    let ball_diameter = object_ball.spec.radius.as_f64() * 2.0;
+    let distance = (object_x - cue_x).hypot(object_y - cue_y);
+    assert!(
+        distance > ball_diameter,
+        "cue ball must be more than one ball diameter from the object ball"
+    );
+
+    let lateral_offset = ball_diameter
+        * CutAngle::from_hit_fraction(fraction)
+            .as_degrees()
+            .to_radians()
+            .sin();
+    let straight_heading = (object_x - cue_x).atan2(object_y - cue_y);
+    let aim_heading = straight_heading - direction.sign() * (lateral_offset / distance).asin();
+    let travel = (distance * distance - lateral_offset * lateral_offset).sqrt()
+        - (ball_diameter * ball_diameter - lateral_offset * lateral_offset).sqrt();
+
+    position_from_xy_inches(
+        cue_x + travel * aim_heading.sin(),
+        cue_y + travel * aim_heading.cos(),
+        table_spec,
+    )
+}
+
+/// Return the object-ball heading after a fractional-aim hit, under the ghost-ball model.
+pub fn fractional_aim_object_ball_heading(
+    cue_ball: &Position,
+    object_ball: &Ball,
+    fraction: Scale,
+    direction: CutDirection,
+    table_spec: &TableSpec,
+) -> Angle {
+    let ghost_ball =
+        fractional_aim_ghost_ball(cue_ball, object_ball, fraction, direction, table_spec);
+    let (ghost_x, ghost_y) = position_xy_inches(&ghost_ball, table_spec);
+    let (object_x, object_y) = position_xy_inches(&object_ball.position, table_spec);
+
+    Angle::from_north(object_x - ghost_x, object_y - ghost_y)
+}
+
+const THROW_ITERATIONS: usize = 32;
+
+/// Place the ghost ball for sending `object_ball` to `destination` once throw is allowed for.
+///
+/// Throw turns the object ball off the line of centers toward the cue ball's path, so the shot has
+/// to be cut slightly thinner than the plain ghost-ball line suggests; see [`ThrowModel`]. The
+/// adjusted line is found by fixed-point iteration, which converges quickly because throw changes
+/// slowly with the cut. Returns the plain ghost ball when the cue ball sits on it.
+pub fn throw_adjusted_ghost_ball(
+    cue_ball: &Position,
+    object_ball: &Ball,
+    destination: &Position,
+    throw: &ThrowModel,
+    table_spec: &TableSpec,
+) -> Position {
+    let origin = position_xy_inches(cue_ball, table_spec);
+    let object = position_xy_inches(&object_ball.position, table_spec);
+    let target = position_xy_inches(destination, table_spec);
+    let diameter = 2.0 * object_ball.spec.radius.as_f64();
+
+    match throw_adjusted_ghost_in_inches(origin, object, target, diameter, throw) {
+        Some((x, y)) => position_from_xy_inches(x, y, table_spec),
+        None => object_ball.ghost_ball(destination, table_spec),
+    }
+}
+
+/// Solve for the ghost-ball center, `diameter` behind `object`, that throws the object ball
+/// toward `target` when struck by a ball travelling from `origin`.
+pub(crate) fn throw_adjusted_ghost_in_inches(
+    origin: (f64, f64),
+    object: (f64, f64),
+    target: (f64, f64),
+    diameter: f64,
+    throw: &ThrowModel,
+) -> Option<(f64, f64)> {
+    let desired = unit_vector(target.0 - object.0, target.1 - object.1)?;
+    let mut normal = desired;
+    for _ in 0..THROW_ITERATIONS {
+        let ghost = (
+            object.0 - diameter * normal.0,
+            object.1 - diameter * normal.1,
+        );
+        let travel = unit_vector(ghost.0 - origin.0, ghost.1 - origin.1)?;
+        let along_normal = travel.0 * normal.0 + travel.1 * normal.1;
+        if along_normal <= 0.0 {
+            return None;
+        }
+        let cut = CutAngle::new(along_normal.clamp(0.0, 1.0).acos().to_degrees());
+        // Throw turns the object ball toward the cue ball's path, so the line of centers must
+        // sit the same angle on the far side of the desired heading.
+        let side = (normal.0 * travel.1 - normal.1 * travel.0).signum();
+        let correction = -side * throw.throw_angle_degrees(cut).to_radians();
+        let (sin, cos) = correction.sin_cos();
+        normal = (
+            desired.0 * cos - desired.1 * sin,
+            desired.0 * sin + desired.1 * cos,
+        );
+    }
+
+    Some((
+        object.0 - diameter * normal.0,
+        object.1 - diameter * normal.1,
+    ))
+}
+
+/// Predict the line a stunned cue ball travels after contact: the tangent line.
+///
+/// With no follow or draw at impact, the ideal equal-mass collision transfers the whole
+/// line-of-centers velocity component to the object ball and leaves the cue ball moving along the
+/// perpendicular tangent line, on the side it was already traveling. The returned segment starts
+/// at the ghost-ball contact point and ends where the cue-ball center first reaches a cushion.
+///
+/// A full-ball hit leaves the cue ball dead at the contact point, which is returned as a
+/// zero-length segment.
+pub fn stun_tangent_line(
+    cue_ball: &Position,
+    ghost_ball: &Position,
+    object_ball: &Ball,
+    table_spec: &TableSpec,
+) -> Segment {
+    let (cue_x, cue_y) = position_xy_inches(cue_ball, table_spec);
+    let (ghost_x, ghost_y) = position_xy_inches(ghost_ball, table_spec);
+    let (object_x, object_y) = position_xy_inches(&object_ball.position, table_spec);
+
+    let (travel_x, travel_y) = unit_vector(ghost_x - cue_x, ghost_y - cue_y)
+        .expect("cue ball and ghost ball must not coincide");
+    let (normal_x, normal_y) = unit_vector(object_x - ghost_x, object_y - ghost_y)
+        .expect("ghost ball and object ball must not coincide");
+    let along_normal = travel_x * normal_x + travel_y * normal_y;
+    let start = ghost_ball.clone();
+
+    match unit_vector(
+        travel_x - along_normal * normal_x,
+        travel_y - along_normal * normal_y,
+    ) {
+        Some((tangent_x, tangent_y)) => {
+            let (end_x, end_y, _) = cushion_contact_in_inches(
+                (ghost_x, ghost_y),
+                (tangent_x, tangent_y),
+                object_ball.spec.radius.as_f64(),
+                table_spec,
+            );
+            Segment::new(start, position_from_xy_inches(end_x, end_y, table_spec))
+        }
+        None => Segment::new(start.clone(), start),
+    }
+}
+
+/// Predict the stun-shot tangent line for potting `object_ball` into `pocket`.
+pub fn stun_tangent_line_to_pocket(
+    cue_ball: &Position,
+    object_ball: &Ball,
+    pocket: Pocket,
+    table_spec: &TableSpec,
+) -> Segment {
+    let ghost_ball = object_ball.ghost_ball_to_pocket(pocket, table_spec);
+    stun_tangent_line(cue_ball, &ghost_ball, object_ball, table_spec)
+}
+
+/// Return how far a rolling cue ball ends up deflected from its original aim line after a cut.
+///
+/// This is the exact natural-roll result from TP A.4, `tan θ = sin φ cos φ / (sin² φ + 2/5)`.
+/// It stays within a few degrees of 30° for everything between a three-quarter-ball and a
+/// quarter-ball hit, peaking at about 33.7° near a half-ball hit: the basis of the 30° rule.
+pub fn natural_roll_deflection(cut_angle: CutAngle) -> CutAngle {
+    let phi = cut_angle.as_degrees().to_radians();
+    let (sin, cos) = phi.sin_cos();
+
+    CutAngle::new((sin * cos).atan2(sin * sin + 0.4).to_degrees())
+}
+
+/// Whether the 30° rule is a fair approximation for this cut, i.e. the hit is between a
+/// three-quarter-ball and a quarter-ball hit.
+pub fn thirty_degree_rule_applies(cut_angle: CutAngle) -> bool {
+    let degrees = cut_angle.as_degrees();
+    degrees >= BallHitFraction::ThreeQuarter.cut_angle().as_degrees()
+        && degrees <= BallHitFraction::Quarter.cut_angle().as_degrees()
+}
+
+const NATURAL_ROLL_SLIDING_SAMPLES: usize = 16;
+
+/// Estimate the path of a naturally rolling cue ball after it contacts the object ball.
+///
+/// The cue ball first leaves along the tangent line, then cloth friction bends it forward along a
+/// parabola until it picks up natural roll on its final, roughly 30° deflected heading (see
+/// [`natural_roll_deflection`]). From there it rolls straight until rolling resistance stops it
+/// or it reaches a cushion. `cue_ball_speed` is the speed just before impact; cut-induced throw,
+/// cling, and pocket openings are ignored.
+///
+/// The returned polyline starts at `ghost_ball`, samples the curved sliding phase, and ends at the
+/// predicted stopping point.
+pub fn natural_roll_cue_ball_path(
+    cue_ball: &Position,
+    ghost_ball: &Position,
+    object_ball: &Ball,
+    cue_ball_speed: &InchesPerSecond,
+    motion_config: &OnTableMotionConfig,
+    table_spec: &TableSpec,
+) -> Vec<Position> {
+    let (cue_x, cue_y) = position_xy_inches(cue_ball, table_spec);
+    let (ghost_x, ghost_y) = position_xy_inches(ghost_ball, table_spec);
+    let (object_x, object_y) = position_xy_inches(&object_ball.position, table_spec);
+
+    let (travel_x, travel_y) = unit_vector(ghost_x - cue_x, ghost_y - cue_y)
+        .expect("cue ball and ghost ball must not coincide");
+    let (normal_x, normal_y) = unit_vector(object_x - ghost_x, object_y - ghost_y)
+        .expect("ghost ball and object ball must not coincide");
+    let along_normal = (travel_x * normal_x + travel_y * normal_y).max(0.0);
+    let speed = cue_ball_speed.as_f64();
+    let radius = object_ball.spec.radius.as_f64();
+    let sliding = sliding_friction_acceleration(motion_config);
+
+    // The cue ball keeps only its tangential velocity, while its forward roll leaves the
+    // cloth-contact slip pointing back along the line of centers. Friction therefore accelerates
+    // the ball toward the object-ball direction until slip vanishes after `2|u| / 7a`.
+    let velocity_x = speed * (travel_x - along_normal * normal_x);
+    let velocity_y = speed * (travel_y - along_normal * normal_y);
+    let sliding_time = 2.0 * speed * along_normal / (7.0 * sliding);
+
+    let mut points = vec![ghost_ball.clone()];
+    let mut previous = (ghost_x, ghost_y);
+    for sample in 1..=NATURAL_ROLL_SLIDING_SAMPLES {
+        let t = sliding_time * sample as f64 / NATURAL_ROLL_SLIDING_SAMPLES as f64;
+        let next = (
+            ghost_x + velocity_x * t + 0.5 * sliding * normal_x * t * t,
+            ghost_y + velocity_y * t + 0.5 * sliding * normal_y * t * t,
+        );
+        if !within_cushions(next, radius, table_spec) {
+            let (x, y, _) = cushion_contact_in_inches(
+                previous,
+                (next.0 - previous.0, next.1 - previous.1),
+                radius,
+                table_spec,
+            );
+            points.push(position_from_xy_inches(x, y, table_spec));
+            return points;
+        }
+        if sliding_time > 0.0 {
+            points.push(position_from_xy_inches(next.0, next.1, table_spec));
+        }
+        previous = next;
+    }
+
+    let final_x = velocity_x + sliding * normal_x * sliding_time;
+    let final_y = velocity_y + sliding * normal_y * sliding_time;
+    let final_speed = final_x.hypot(final_y);
+    if let Some(direction) = unit_vector(final_x, final_y) {
+        let rolling_distance = final_speed * final_speed
+            / (2.0 * rolling_linear_deceleration(motion_config).max(DIRECTION_EPSILON));
+        let (cushion_x, cushion_y, _) =
+            cushion_contact_in_inches(previous, direction, radius, table_spec);
+        let cushion_distance = (cushion_x - previous.0).hypot(cushion_y - previous.1);
+        let distance = rolling_distance.min(cushion_distance);
+        points.push(position_from_xy_inches(
+            previous.0 + direction.0 * distance,
+            previous.1 + direction.1 * distance,
+            table_spec,
+        ));
+    }
+
+    points
+}
+
+const DIRECTION_EPSILON: f64 = 1e-12;
+
+fn within_cushions((x, y): (f64, f64), ball_radius: f64, table_spec: &TableSpec) -> bool {
+    let width = table_spec.diamond_to_inches(Diamond::four()).as_f64();
+    let length = table_spec.diamond_to_inches(Diamond::eight()).as_f64();
+
+    (ball_radius..=width - ball_radius).contains(&x)
+        && (ball_radius..=length - ball_radius).contains(&y)
+}
+
+fn unit_vector(x: f64, y: f64) -> Option<(f64, f64)> {
+    let length = x.hypot(y);
+    (length > DIRECTION_EPSILON).then(|| (x / length, y / length))
+}
+
+/// Follow a ball-center ray until it first touches a cushion, returning the contact center and
+/// the rail it touched.
+///
+/// The reachable region is the playfield inset by `ball_radius`, matching the rail-impact planes
+/// used by the event scheduler. Pocket openings are ignored.
+pub(crate) fn cushion_contact_in_inches(
+    origin: (f64, f64),
+    direction: (f64, f64),
+    ball_radius: f64,
+    table_spec: &TableSpec,
+) -> (f64, f64, Rail) {
+    let (x, y) = origin;
+    let (dx, dy) = direction;
+    let width = table_spec.diamond_to_inches(Diamond::four()).as_f64();
+    let length = table_spec.diamond_to_inches(Diamond::eight()).as_f64();
+
+    let mut best: Option<(f64, Rail)> = None;
+    for (rail, component, start, plane) in [
+        (Rail::Right, dx, x, width - ball_radius),
+        (Rail::Left, dx, x, ball_radius),
+        (Rail::Top, dy, y, length - ball_radius),
+        (Rail::Bottom, dy, y, ball_radius),
+    ] {
+        let heading_into_rail = match rail {
+            Rail::Right | Rail::Top => component > DIRECTION_EPSILON,
+            Rail::Left | Rail::Bottom => component < -DIRECTION_EPSILON,
+        };
+        if !heading_into_rail {
+            continue;
+        }
+
+        let t = ((plane - start) / component).max(0.0);
+        if best.is_none_or(|(best_t, _)| t < best_t) {
+            best = Some((t, rail));
+        }
+    }
+
+    let (t, rail) = best.expect("cushion contact requires a non-zero direction");
+    (x + t * dx, y + t * dy, rail)
+}
+
+/// The cue ball on a full, straight-in hit: its state at contact and how far it carries after.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawFollowCarry {
+    /// Center-of-mass speed when the cue ball reaches the object ball.
+    pub speed_at_contact: InchesPerSecond,
+    /// Forward roll at contact as a surface speed, `Rω`; negative for backspin.
+    pub spin_at_contact: InchesPerSecond,
+    /// How far past the contact point the cue ball stops: positive for follow, negative for draw,
+    /// and zero for a stun.
+    pub carry: Inches,
+}
+
+/// Estimate how far a straight-in draw, stun, or follow shot carries the cue ball.
+///
+/// The cue ball leaves the tip at `cue_ball_speed` with backspin or topspin from `tip_height`,
+/// given as a fraction of the ball radius like [`crate::CueTipContact::height_offset`], and
+/// slides, then rolls, `distance_to_object_ball` to a full hit. The hit stops the cue ball dead
+/// and leaves its spin, which the cloth then turns into draw or follow. The cloth comes from
+/// `motion_config`'s sliding friction and rolling resistance; throw, spin decay about the vertical
+/// axis, and collision losses are ignored.
+///
+/// Returns `None` when the cue ball stops before it reaches the object ball.
+pub fn draw_follow_carry(
+    cue_ball_speed: &InchesPerSecond,
+    tip_height: &Scale,
+    distance_to_object_ball: Inches,
+    motion_config: &OnTableMotionConfig,
+) -> Option<DrawFollowCarry> {
+    let sliding = sliding_friction_acceleration(motion_config);
+    let rolling = rolling_linear_deceleration(motion_config);
+    let (speed, spin) = straight_line_state_after(
+        cue_ball_speed.as_f64(),
+        2.5 * tip_height.as_f64() * cue_ball_speed.as_f64(),
+        distance_to_object_ball.as_f64(),
+        sliding,
+        rolling,
+    )?;
+
+    // From a dead stop, friction turns the spin into a roll at 2/7 of its surface speed.
+    let roll_speed = 2.0 / 7.0 * spin;
+    let carry = roll_speed.signum() * roll_speed.powi(2) / 2.0 * (1.0 / sliding + 1.0 / rolling);
+    Some(DrawFollowCarry {
+        speed_at_contact: InchesPerSecond::new(Inches::from_f64(speed)),
+        spin_at_contact: InchesPerSecond::new(Inches::from_f64(spin)),
+        carry: Inches::from_f64(carry),
+    })
+}
+
+/// Speed and forward roll `Rω` of a ball that set off straight at `speed` with roll `spin` once
+/// it has covered `distance`, or `None` if it stops first.
+fn straight_line_state_after(
+    speed: f64,
+    spin: f64,
+    distance: f64,
+    sliding: f64,
+    rolling: f64,
+) -> Option<(f64, f64)> {
+    let slip = speed - spin;
+    let direction = slip.signum();
+    let sliding_time = 2.0 * slip.abs() / (7.0 * sliding);
+    let sliding_distance = speed * sliding_time - 0.5 * direction * sliding * sliding_time.powi(2);
+
+    if slip != 0.0 && distance <= sliding_distance {
+        // Solve `speed t - direction sliding t² / 2 = distance` for the first time it is reached.
+        let discriminant = speed * speed - 2.0 * direction * sliding * distance;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let t = if direction > 0.0 {
+            (speed - discriminant.sqrt()) / sliding
+        } else {
+            (discriminant.sqrt() - speed) / sliding
+        };
+        return Some((
+            speed - direction * sliding * t,
+            spin + 2.5 * direction * sliding * t,
+        ));
+    }
+
+    let rolling_speed = speed - direction * sliding * sliding_time;
+    if rolling_speed <= 0.0 {
+        return None;
+    }
+    let remaining = distance - sliding_distance.max(0.0);
+    let squared = rolling_speed * rolling_speed - 2.0 * rolling * remaining;
+    (squared > 0.0).then(|| (squared.sqrt(), squared.sqrt()))
+}
+
+/// Carries from [`draw_follow_carry`] over a grid of tip heights and speeds, for one distance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawFollowTable {
+    pub distance_to_object_ball: Inches,
+    pub tip_heights: Vec<Scale>,
+    pub speeds: Vec<InchesPerSecond>,
+    /// One row per tip height and one column per speed; `None` where the cue ball falls short.
+    pub carries: Vec<Vec<Option<Inches>>>,
+}
+
+/// Tabulate [`draw_follow_carry`] for every pairing of `tip_heights` and `speeds`.
+pub fn draw_follow_table(
+    distance_to_object_ball: Inches,
+    tip_heights: &[Scale],
+    speeds: &[InchesPerSecond],
+    motion_config: &OnTableMotionConfig,
+) -> DrawFollowTable {
+    let carries = tip_heights
+        .iter()
+        .map(|tip_height| {
+            speeds
+                .iter()
+                .map(|speed| {
+                    draw_follow_carry(
+                        speed,
+                        tip_height,
+                        distance_to_object_ball.clone(),
+                        motion_config,
+                    )
+                    .map(|carry| carry.carry)
+                })
+                .collect()
+        })
+        .collect();
+    DrawFollowTable {
+        distance_to_object_ball,
+        tip_heights: tip_heights.to_vec(),
+        speeds: speeds.to_vec(),
+        carries,
+    }
+}
+
+/// Something measured on a real table for [`calibrate_cloth`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClothMeasurement {
+    /// A center-ball lag struck at `speed` that stopped `distance` later, short of any cushion.
+    Lag {
+        speed: InchesPerSecond,
+        distance: Inches,
+    },
+    /// A straight-in shot as in [`draw_follow_carry`], with the carry that was seen.
+    DrawFollow {
+        speed: InchesPerSecond,
+        tip_height: Scale,
+        distance_to_object_ball: Inches,
+        carry: Inches,
+    },
+}
+
+/// Cloth constants fitted by [`calibrate_cloth`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClothCalibration {
+    /// The sliding-friction coefficient, as a fraction of gravity.
+    pub sliding_friction_coefficient: f64,
+    /// The rolling-resistance coefficient, as a fraction of gravity.
+    pub rolling_resistance_coefficient: f64,
+    /// The input motion config with the fitted sliding friction and rolling resistance.
+    pub motion_config: OnTableMotionConfig,
+    /// Root-mean-square difference between the measured and predicted distances.
+    pub rms_error: Inches,
+}
+
+const CALIBRATION_SLIDING_RANGE: (f64, f64) = (0.05, 0.6);
+const CALIBRATION_ROLLING_RANGE: (f64, f64) = (0.002, 0.05);
+const CALIBRATION_GRID_STEPS: usize = 40;
+const CALIBRATION_REFINEMENTS: usize = 60;
+
+/// Fit the sliding friction and rolling resistance that best explain `measurements`, starting
+/// from and otherwise keeping `motion_config`.
+///
+/// Lags pin down rolling resistance; draw and follow carries then separate it from sliding
+/// friction, so a mix of both fits best. The fit is a least-squares search over coefficients
+/// between 0.05 and 0.6 for sliding and 0.002 and 0.05 for rolling. Returns `None` when there
+/// are no measurements.
+pub fn calibrate_cloth(
+    measurements: &[ClothMeasurement],
+    motion_config: &OnTableMotionConfig,
+) -> Option<ClothCalibration> {
+    if measurements.is_empty() {
+        return None;
+    }
+    let error = |sliding: f64, rolling: f64| {
+        let config = with_cloth_coefficients(motion_config, sliding, rolling);
+        measurements
+            .iter()
+            .map(|measurement| calibration_residual(measurement, &config).powi(2))
+            .sum::<f64>()
+    };
+
+    // Search in log space, first on a grid and then by shrinking steps around the best point.
+    let (slide_low, slide_high) = (
+        CALIBRATION_SLIDING_RANGE.0.ln(),
+        CALIBRATION_SLIDING_RANGE.1.ln(),
+    );
+    let (roll_low, roll_high) = (
+        CALIBRATION_ROLLING_RANGE.0.ln(),
+        CALIBRATION_ROLLING_RANGE.1.ln(),
+    );
+    let grid = |low: f64, high: f64, step: usize| {
+        low + (high - low) * step as f64 / (CALIBRATION_GRID_STEPS - 1) as f64
+    };
+    let mut best = (f64::INFINITY, 0.0, 0.0);
+    for slide_step in 0..CALIBRATION_GRID_STEPS {
+        for roll_step in 0..CALIBRATION_GRID_STEPS {
+            let (slide, roll) = (
+                grid(slide_low, slide_high, slide_step),
+                grid(roll_low, roll_high, roll_step),
+            );
+            let candidate = error(slide.exp(), roll.exp());
+            if candidate < best.0 {
+                best = (candidate, slide, roll);
+            }
+        }
+    }
+
+    let mut step = (
+        (slide_high - slide_low) / CALIBRATION_GRID_STEPS as f64,
+        (roll_high - roll_low) / CALIBRATION_GRID_STEPS as f64,
+    );
+    for _ in 0..CALIBRATION_REFINEMENTS {
+        let (_, slide, roll) = best;
+        let mut improved = false;
+        for (d_slide, d_roll) in [(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)] {
+            let (slide, roll) = (
+                (slide + d_slide * step.0).clamp(slide_low, slide_high),
+                (roll + d_roll * step.1).clamp(roll_low, roll_high),
+            );
+            let candidate = error(slide.exp(), roll.exp());
+            if candidate < best.0 {
+                best = (candidate, slide, roll);
+                improved = true;
+            }
+        }
+        if !improved {
+            step = (step.0 / 2.0, step.1 / 2.0);
+        }
+    }
+
+    let (squared_error, slide, roll) = best;
+    let (sliding, rolling) = (slide.exp(), roll.exp());
+    Some(ClothCalibration {
+        sliding_friction_coefficient: sliding,
+        rolling_resistance_coefficient: rolling,
+        motion_config: with_cloth_coefficients(motion_config, sliding, rolling),
+        rms_error: Inches::from_f64((squared_error / measurements.len() as f64).sqrt()),
+    })
+}
+
+/// Predicted minus measured distance for one measurement, in inches. A draw or follow shot that
+/// would not reach the object ball counts as carrying nowhere.
+fn calibration_residual(measurement: &ClothMeasurement, config: &OnTableMotionConfig) -> f64 {
+    match measurement {
+        ClothMeasurement::Lag { speed, distance } => {
+            let sliding = sliding_friction_acceleration(config);
+            let rolling = rolling_linear_deceleration(config);
+            let speed = speed.as_f64();
+            // A center-ball hit slides to 5/7 of its speed over `12 v² / 49 μs g`, then rolls out.
+            let travelled = 12.0 * speed * speed / (49.0 * sliding)
+                + (5.0 / 7.0 * speed).powi(2) / (2.0 * rolling);
+            travelled - distance.as_f64()
+        }
+        ClothMeasurement::DrawFollow {
+            speed,
+            tip_height,
+            distance_to_object_ball,
+            carry,
+        } => {
+            let predicted =
+                draw_follow_carry(speed, tip_height, distance_to_object_ball.clone(), config)
+                    .map_or(0.0, |predicted| predicted.carry.as_f64());
+            predicted - carry.as_f64()
+        }
+    }
+}
+
+fn with_cloth_coefficients(
+    motion_config: &OnTableMotionConfig,
+    sliding_friction_coefficient: f64,
+    rolling_resistance_coefficient: f64,
+) -> OnTableMotionConfig {
+    OnTableMotionConfig {
+        sliding_friction: SlidingFrictionModel::ConstantAcceleration {
+            acceleration_magnitude: friction_coefficient_to_acceleration(
+                sliding_friction_coefficient,
+            ),
+        },
+        rolling_resistance: RollingResistanceModel::ConstantDeceleration {
+            linear_deceleration: friction_coefficient_to_acceleration(
+                rolling_resistance_coefficient,
+            ),
+        },
+        ..motion_config.clone()
+    }
+}
+
+/// How the cue ball leaves the object ball in a carom plan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaromCueAction {
+    /// Stun at contact: the cue ball leaves along the tangent line.
+    Stun,
+    /// Natural roll at contact: the cue ball settles onto the deflected path from
+    /// [`natural_roll_deflection`].
+    NaturalRoll,
+}
+
+/// A planned cue-ball carom off an object ball.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaromShot {
+    pub ghost_ball: Position,
+    pub cut_angle: CutAngle,
+    pub direction: CutDirection,
+    /// The TP A.23 ball-hit fraction for `cut_angle`.
+    pub hit_fraction: Scale,
+    /// The nearest classic fractional-aim reference for `cut_angle`.
+    pub nearest_fraction: FractionalAim,
+    pub object_ball_heading: Angle,
+    /// The cue ball's heading after contact. For natural roll this is its final rolling heading.
+    pub cue_ball_heading: Angle,
+}
+
+const CAROM_SEARCH_SAMPLES: usize = 720;
+const CAROM_BISECTION_STEPS: usize = 60;
+const CAROM_MAX_OFFSET_DEGREES: f64 = 89.5;
+
+/// Plan the hits that carom the cue ball off `object_ball` toward `destination`.
+///
+/// `destination` can be another ball's center for a carom or billiard, or any point the cue ball
+/// should head for, such as a spot away from a scratch pocket. For stun the cue ball leaves along
+/// the tangent line; for natural roll the returned heading is the final rolling heading, measured
+/// from the contact point, so the short curved transition is ignored. Throw is ignored.
+///
+/// Every solution is returned, usually one cut to each side, ordered from the thickest hit to the
+/// thinnest.
+pub fn plan_carom(
+    cue_ball: &Position,
+    object_ball: &Ball,
+    destination: &Position,
+    action: CaromCueAction,
+    table_spec: &TableSpec,
+) -> Vec<CaromShot> {
+    let cue = position_xy_inches(cue_ball, table_spec);
+    let object = position_xy_inches(&object_ball.position, table_spec);
+    let target = position_xy_inches(destination, table_spec);
+    let ball_diameter = object_ball.spec.radius.as_f64() * 2.0;
+    let straight = (object.0 - cue.0).atan2(object.1 - cue.1);
+    if (object.0 - cue.0).hypot(object.1 - cue.1) <= ball_diameter {
+        return Vec::new();
+    }
+
+    // For a given object-ball heading, measure how far the cue ball's departure misses the
+    // destination, as a signed cross product of unit vectors.
+    let residual = |offset_degrees: f64| -> Option<f64> {
+        let heading = straight + offset_degrees.to_radians();
+        let normal = (heading.sin(), heading.cos());
+        let ghost = (
+            object.0 - ball_diameter * normal.0,
+            object.1 - ball_diameter * normal.1,
+        );
+        let departure = carom_departure(cue, ghost, normal, action)?;
+        let to_target = unit_vector(target.0 - ghost.0, target.1 - ghost.1)?;
+        let alignment = departure.0 * to_target.0 + departure.1 * to_target.1;
+        (alignment > 0.0).then_some(departure.0 * to_target.1 - departure.1 * to_target.0)
+    };
+
+    let step = 2.0 * CAROM_MAX_OFFSET_DEGREES / CAROM_SEARCH_SAMPLES as f64;
+    let mut shots = Vec::new();
+    let mut previous: Option<(f64, f64)> = None;
+    for sample in 0..=CAROM_SEARCH_SAMPLES {
+        let offset = -CAROM_MAX_OFFSET_DEGREES + step * sample as f64;
+        let value = residual(offset);
+        if let (Some((previous_offset, previous_value)), Some(value)) = (previous, value) {
+            if previous_value.signum() != value.signum() {
+                let (mut a, mut b) = (previous_offset, offset);
+                for _ in 0..CAROM_BISECTION_STEPS {
+                    let mid = 0.5 * (a + b);
+                    match residual(mid) {
+                        Some(mid_value) if mid_value.signum() == previous_value.signum() => a = mid,
+                        _ => b = mid,
+                    }
+                }
+                if let Some(shot) = carom_shot(
+                    cue,
+                    object,
+                    0.5 * (a + b),
+                    straight,
+                    action,
+                    ball_diameter,
+                    table_spec,
+                ) {
+                    shots.push(shot);
+                }
+            }
+        }
+        previous = value.map(|value| (offset, value));
+    }
+
+    shots.sort_by(|a, b| {
+        a.cut_angle
+            .as_degrees()
+            .total_cmp(&b.cut_angle.as_degrees())
+    });
+    shots
+}
+
+/// Predict the straight line the cue ball leaves the ghost ball on, up to the first cushion.
+///
+/// This is [`stun_tangent_line`] for [`CaromCueAction::Stun`]; for
+/// [`CaromCueAction::NaturalRoll`] it is the final rolling heading from [`plan_carom`], drawn from
+/// the contact point. A stunned full-ball hit returns a zero-length segment.
+pub fn cue_ball_departure_line(
+    cue_ball: &Position,
+    ghost_ball: &Position,
+    object_ball: &Ball,
+    action: CaromCueAction,
+    table_spec: &TableSpec,
+) -> Segment {
+    let cue = position_xy_inches(cue_ball, table_spec);
+    let ghost = position_xy_inches(ghost_ball, table_spec);
+    let object = position_xy_inches(&object_ball.position, table_spec);
+    let normal = unit_vector(object.0 - ghost.0, object.1 - ghost.1)
+        .expect("ghost ball and object ball must not coincide");
+
+    match carom_departure(cue, ghost, normal, action) {
+        Some(direction) => {
+            let (end_x, end_y, _) = cushion_contact_in_inches(
+                ghost,
+                direction,
+                object_ball.spec.radius.as_f64(),
+                table_spec,
+            );
+            Segment::new(
+                ghost_ball.clone(),
+                position_from_xy_inches(end_x, end_y, table_spec),
+            )
+        }
+        None => Segment::new(ghost_ball.clone(), ghost_ball.clone()),
+    }
+}
+
+fn carom_departure(
+    cue: (f64, f64),
+    ghost: (f64, f64),
+    normal: (f64, f64),
+    action: CaromCueAction,
+) -> Option<(f64, f64)> {
+    let travel = unit_vector(ghost.0 - cue.0, ghost.1 - cue.1)?;
+    let along_normal = travel.0 * normal.0 + travel.1 * normal.1;
+    if along_normal <= 0.0 {
+        return None;
+    }
+    let tangent = (
+        travel.0 - along_normal * normal.0,
+        travel.1 - along_normal * normal.1,
+    );
+
+    match action {
+        CaromCueAction::Stun => unit_vector(tangent.0, tangent.1),
+        // Sliding friction adds `2/7` of the line-of-centers speed back along the normal.
+        CaromCueAction::NaturalRoll => unit_vector(
+            tangent.0 + 2.0 / 7.0 * along_normal * normal.0,
+            tangent.1 + 2.0 / 7.0 * along_normal * normal.1,
+        ),
+    }
+}
+
+fn carom_shot(
+    cue: (f64, f64),
+    object: (f64, f64),
+    offset_degrees: f64,
+    straight: f64,
+    action: CaromCueAction,
+    ball_diameter: f64,
+    table_spec: &TableSpec,
+) -> Option<CaromShot> {
+    let heading = straight + offset_degrees.to_radians();
+    let normal = (heading.sin(), heading.cos());
+    let ghost = (
+        object.0 - ball_diameter * normal.0,
+        object.1 - ball_diameter * normal.1,
+    );
+    let departure = carom_departure(cue, ghost, normal, action)?;
+    let cue_heading = Angle::from_north(ghost.0 - cue.0, ghost.1 - cue.1);
+    let object_ball_heading = Angle::from_north(normal.0, normal.1);
+    let cut_angle = CutAngle::from_headings(cue_heading, object_ball_heading);
+    let direction = if offset_degrees >= 0.0 {
+        CutDirection::Right
+    } else {
+        CutDirection::Left
+    };
+
+    Some(CaromShot {
+        ghost_ball: position_from_xy_inches(ghost.0, ghost.1, table_spec),
+        hit_fraction: cut_angle.hit_fraction(),
+        nearest_fraction: BallHitFraction::nearest(cut_angle),
+        cut_angle,
+        direction,
+        object_ball_heading,
+        cue_ball_heading: Angle::from_north(departure.0, departure.1),
+    })
+}
+
+/// One ball-to-ball contact in a combination.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CombinationLink {
+    pub from: BallType,
+    pub to: BallType,
+    /// Whether the two balls are frozen together.
+    pub frozen: bool,
+    /// The cut `from` must put on `to` under the ghost-ball model.
+    pub required_cut: CutAngle,
+    /// Signed angle from the line of centers to the direction `to` must travel, in degrees,
+    /// positive clockwise. Zero means the pair is dead on; for a frozen pair this is the error
+    /// that throw would have to make up.
+    pub line_of_centers_error_degrees: f64,
+}
+
+/// The result of checking a combination into a pocket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CombinationCheck {
+    pub pocket: Pocket,
+    /// Where the cue-ball center must be at contact with the first ball of the chain.
+    pub ghost_ball: Position,
+    /// One link per consecutive pair of balls, from the first ball outward.
+    pub links: Vec<CombinationLink>,
+    /// The line each ball must travel, from the first ball to the pocket's aiming center.
+    pub aiming_lines: Vec<Segment>,
+}
+
+impl CombinationCheck {
+    /// Whether every link's line of centers points within `tolerance_degrees` of the direction the
+    /// next ball must travel.
+    pub fn is_on(&self, tolerance_degrees: f64) -> bool {
+        self.links
+            .iter()
+            .all(|link| link.line_of_centers_error_degrees.abs() <= tolerance_degrees)
+    }
+
+    /// The largest line-of-centers error across the chain, in degrees.
+    pub fn max_error_degrees(&self) -> f64 {
+        self.links
+            .iter()
+            .map(|link| link.line_of_centers_error_degrees.abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+const FROZEN_TOLERANCE_INCHES: f64 = 1e-3;
+
+/// Check a combination of `balls`, struck in order, into `pocket`.
+///
+/// Working back from the pocket, each ball's ghost-ball target is placed one ball diameter behind
+/// the next ball along that ball's required path. A frozen pair can only drive the next ball
+/// along the line of centers, so the earlier ball's path is taken to be that line.
+pub fn check_combination(
+    balls: &[Ball],
+    pocket: Pocket,
+    table_spec: &TableSpec,
+) -> CombinationCheck {
+    check_combination_with_throw(balls, pocket, None, table_spec)
+}
+
+/// Check a combination like [`check_combination`], optionally allowing for throw at each
+/// non-frozen contact.
+///
+/// With a [`ThrowModel`], the target behind each struck object ball is placed as in
+/// [`throw_adjusted_ghost_ball`], for a ball travelling from the previous ball's resting position,
+/// so the aiming lines already include the thinner cut throw calls for. Frozen pairs are unchanged,
+/// and the cue ball's ghost ball is left plain because the cue ball's position is not known.
+pub fn check_combination_with_throw(
+    balls: &[Ball],
+    pocket: Pocket,
+    throw: Option<&ThrowModel>,
+    table_spec: &TableSpec,
+) -> CombinationCheck {
+    assert!(
+        balls.len() >= 2,
+        "a combination needs at least two object balls"
+    );
+
+    let centers: Vec<(f64, f64)> = balls
+        .iter()
+        .map(|ball| position_xy_inches(&ball.position, table_spec))
+        .collect();
+    let pocket_center = position_xy_inches(&pocket.aiming_center(), table_spec);
+
+    // `targets[i]` is the point ball `i` must travel toward.
+    let mut targets = vec![pocket_center; balls.len()];
+    for index in (0..balls.len() - 1).rev() {
+        let next = centers[index + 1];
+        let diameter = balls[index].spec.radius.as_f64() + balls[index + 1].spec.radius.as_f64();
+        let (x, y) = centers[index];
+        let frozen = (next.0 - x).hypot(next.1 - y) <= diameter + FROZEN_TOLERANCE_INCHES;
+        let thrown = throw.filter(|_| !frozen).and_then(|throw| {
+            throw_adjusted_ghost_in_inches(
+                centers[index],
+                next,
+                targets[index + 1],
+                diameter,
+                throw,
+            )
+        });
+        targets[index] = if frozen {
+            next
+        } else if let Some(ghost) = thrown {
+            ghost
+        } else {
+            let direction =
+                unit_vector(targets[index + 1].0 - next.0, targets[index + 1].1 - next.1)
+                    .expect("combination balls must not sit on their targets");
+            (
+                next.0 - diameter * direction.0,
+                next.1 - diameter * direction.1,
+            )
+        };
+    }
+
+    let links = (0..balls.len() - 1)
+        .map(|index| {
+            let (from, to) = (centers[index], centers[index + 1]);
+            let diameter =
+                balls[index].spec.radius.as_f64() + balls[index + 1].spec.radius.as_f64();
+            let from_heading =
+                Angle::from_north(targets[index].0 - from.0, targets[index].1 - from.1);
+            let to_heading =
+                Angle::from_north(targets[index + 1].0 - to.0, targets[index + 1].1 - to.1);
+            let centers_heading = Angle::from_north(to.0 - from.0, to.1 - from.1);
+            let error = (to_heading.as_degrees() - centers_heading.as_degrees() + 540.0)
+                .rem_euclid(360.0)
+                - 180.0;
+
+            CombinationLink {
+                from: balls[index].ty.clone(),
+                to: balls[index + 1].ty.clone(),
+                frozen: (to.0 - from.0).hypot(to.1 - from.1) <= diameter + FROZEN_TOLERANCE_INCHES,
+                required_cut: CutAngle::from_headings(from_heading, to_heading),
+                line_of_centers_error_degrees: error,
+            }
+        })
+        .collect();
+
+    let first = &balls[0];
+    let (first_x, first_y) = centers[0];
+    let direction = unit_vector(targets[0].0 - first_x, targets[0].1 - first_y)
+        .expect("combination balls must not sit on their targets");
+    let diameter = first.spec.radius.as_f64() * 2.0;
+    let ghost_ball = position_from_xy_inches(
+        first_x - diameter * direction.0,
+        first_y - diameter * direction.1,
+        table_spec,
+    );
+
+    let aiming_lines = balls
+        .iter()
+        .zip(&targets)
+        .map(|(ball, &(x, y))| {
+            Segment::new(
+                ball.position.clone(),
+                position_from_xy_inches(x, y, table_spec),
+            )
+        })
+        .collect();
+
+    CombinationCheck {
+        pocket,
+        ghost_ball,
+        links,
+        aiming_lines,
+    }
+}
+
+/// Score how hard it is to pot `object_ball` into `pocket` with the cue ball at `cue_ball`.
+///
+/// The score is the classic `d1 * d2 / cos(φ)` index: the cue-ball-to-ghost-ball distance times
+/// the object-ball-to-pocket distance, both in diamonds, divided by the cosine of the cut. A
+/// one-diamond straight-in shot scores 1; lower is easier. Returns `None` when the cue ball sits
+/// on the ghost ball or the cut is 90° or thinner.
+pub fn shot_difficulty(
+    cue_ball: &Position,
+    object_ball: &Ball,
+    pocket: Pocket,
+    table_spec: &TableSpec,
+) -> Option<f64> {
+    let diamond = table_spec.diamond_to_inches(Diamond::one()).as_f64();
+    let ghost_ball = object_ball.ghost_ball_to_pocket(pocket, table_spec);
+    let cue = position_xy_inches(cue_ball, table_spec);
+    let ghost = position_xy_inches(&ghost_ball, table_spec);
+    let object = position_xy_inches(&object_ball.position, table_spec);
+    let target = position_xy_inches(&pocket.aiming_center(), table_spec);
+
+    let cue_direction = unit_vector(ghost.0 - cue.0, ghost.1 - cue.1)?;
+    let object_direction = unit_vector(target.0 - object.0, target.1 - object.1)?;
+    let cosine = cue_direction.0 * object_direction.0 + cue_direction.1 * object_direction.1;
+    if cosine <= DIRECTION_EPSILON {
+        return None;
+    }
+
+    let cue_distance = (ghost.0 - cue.0).hypot(ghost.1 - cue.1) / diamond;
+    let object_distance = (target.0 - object.0).hypot(target.1 - object.1) / diamond;
+    Some(cue_distance * object_distance / cosine)
+}
+
+/// How far the cue ball's aim can be off and still pot a ball, from [`aim_tolerance`].
+///
+/// Left and right are seen from the cue ball looking along the aim line, and are measured from
+/// the aim at [`Ball::ghost_ball_to_pocket_with_speed`]. That ghost ball sends the object ball at
+/// the middle of its effective window, so the two sides are close to equal even when the window
+/// sits off-center in the pocket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AimTolerance {
+    pub pocket: Pocket,
+    pub ghost_ball: Position,
+    pub cut_angle: CutAngle,
+    /// How far, in degrees, the cue ball's heading can turn left and still pot the ball.
+    pub left_degrees: f64,
+    /// How far, in degrees, the cue ball's heading can turn right and still pot the ball.
+    pub right_degrees: f64,
+    /// The object ball's window into the pocket that the tolerance was computed from.
+    pub pocket_opening: EffectivePocketOpening,
+}
+
+impl AimTolerance {
+    /// The full angular window, in degrees, inside which the cue ball's aim pots the ball.
+    pub fn total_degrees(&self) -> f64 {
+        self.left_degrees + self.right_degrees
+    }
+
+    /// The error, in degrees, the aim can take in either direction: the "±0.6°" a player reads.
+    pub fn plus_minus_degrees(&self) -> f64 {
+        self.left_degrees.min(self.right_degrees)
+    }
+}
+
+const AIM_TOLERANCE_BISECTION_STEPS: usize = 60;
+
+/// Compute the aiming error allowed at the cue ball for potting `object_ball` into `pocket`.
+///
+/// The object ball's window is [`Ball::effective_pocket_opening`] at `object_ball_speed`. Each
+/// edge of that window is carried back to the cue ball by solving the exact ghost-ball collision
+/// for a turned cue-ball heading, so a thin cut, where a small aim change swings the object ball
+/// a long way, comes out much tighter than a straight-in shot over the same distances. Throw is
+/// not included. Returns `None` under the same conditions as [`shot_difficulty`] or when the
+/// pocket is closed to this approach.
+pub fn aim_tolerance(
+    cue_ball: &Position,
+    object_ball: &Ball,
+    pocket: Pocket,
+    object_ball_speed: InchesPerSecond,
+    table_spec: &TableSpec,
+) -> Option<AimTolerance> {
+    let pocket_opening =
+        object_ball.effective_pocket_opening(pocket, object_ball_speed.clone(), table_spec);
+    if !pocket_opening.is_open() {
+        return None;
+    }
+    let ghost_ball =
+        object_ball.ghost_ball_to_pocket_with_speed(pocket, object_ball_speed, table_spec);
+    let cue = position_xy_inches(cue_ball, table_spec);
+    let ghost = position_xy_inches(&ghost_ball, table_spec);
+    let object = position_xy_inches(&object_ball.position, table_spec);
+    // The window's margins are measured from the pocket's aiming center.
+    let target = position_xy_inches(&pocket.aiming_center(), table_spec);
+    let aim = unit_vector(ghost.0 - cue.0, ghost.1 - cue.1)?;
+    let object_direction = unit_vector(target.0 - object.0, target.1 - object.1)?;
+    if aim.0 * object_direction.0 + aim.1 * object_direction.1 <= DIRECTION_EPSILON {
+        return None;
+    }
+
+    // The object ball's departure, in radians counterclockwise from the pocket center, when the
+    // cue ball's heading is turned `turn` radians counterclockwise from the aim line.
+    let contact_distance = 2.0 * object_ball.spec.radius.as_f64();
+    let departure = |turn: f64| {
+        let (sin, cos) = turn.sin_cos();
+        let heading = (aim.0 * cos - aim.1 * sin, aim.0 * sin + aim.1 * cos);
+        let travel = ray_circle_entry(cue, heading, object, contact_distance)?;
+        let contact = (cue.0 + heading.0 * travel, cue.1 + heading.1 * travel);
+        let (x, y) = unit_vector(object.0 - contact.0, object.1 - contact.1)?;
+        Some(
+            (object_direction.0 * y - object_direction.1 * x)
+                .atan2(object_direction.0 * x + object_direction.1 * y),
+        )
+    };
+
+    // Turning the aim left sends the object ball right, so the departure falls as `turn` rises
+    // across the headings that still hit the object ball.
+    let to_object = (object.0 - cue.0, object.1 - cue.1);
+    let center_turn = (aim.0 * to_object.1 - aim.1 * to_object.0)
+        .atan2(aim.0 * to_object.0 + aim.1 * to_object.1);
+    let half_width = (contact_distance / to_object.0.hypot(to_object.1))
+        .min(1.0)
+        .asin()
+        * (1.0 - 1e-9);
+    let turn_for = |bound: f64| {
+        let (mut low, mut high) = (center_turn - half_width, center_turn + half_width);
+        for _ in 0..AIM_TOLERANCE_BISECTION_STEPS {
+            let mid = 0.5 * (low + high);
+            if departure(mid).is_some_and(|departure| departure > bound) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        0.5 * (low + high)
+    };
+
+    let left_turn = turn_for(-pocket_opening.right_angular_margin_degrees.to_radians());
+    let right_turn = turn_for(pocket_opening.left_angular_margin_degrees.to_radians());
+
+    Some(AimTolerance {
+        pocket,
+        cut_angle: CutAngle::from_headings(
+            cue_ball.angle_to(&ghost_ball),
+            ghost_ball.angle_to(&object_ball.position),
+        ),
+        ghost_ball,
+        left_degrees: left_turn.to_degrees(),
+        right_degrees: -right_turn.to_degrees(),
+        pocket_opening,
+    })
+}
+
+/// The region the cue ball can be left in for an easy shot on one ball into one pocket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PositionZone {
+    pub object_ball: BallType,
+    pub pocket: Pocket,
+    pub ghost_ball: Position,
+    /// The [`shot_difficulty`] the zone was computed for.
+    pub max_difficulty: f64,
+    /// A closed polygon, without a repeated end point, starting at the ghost ball and sweeping
+    /// from the thinnest cut on one side to the thinnest cut on the other.
+    pub outline: Vec<Position>,
+}
+
+impl PositionZone {
+    /// Whether `position` lies inside the zone's outline.
+    pub fn contains(&self, position: &Position, table_spec: &TableSpec) -> bool {
+        outline_contains(&self.outline, position, table_spec)
+    }
+}
+
+/// Whether `position` lies inside the closed polygon `outline`.
+pub(crate) fn outline_contains(
+    outline: &[Position],
+    position: &Position,
+    table_spec: &TableSpec,
+) -> bool {
+    let (x, y) = position_xy_inches(position, table_spec);
+    let vertices: Vec<(f64, f64)> = outline
+        .iter()
+        .map(|vertex| position_xy_inches(vertex, table_spec))
+        .collect();
+
+    let mut inside = false;
+    for (index, &(x1, y1)) in vertices.iter().enumerate() {
+        let (x2, y2) = vertices[(index + 1) % vertices.len()];
+        if (y1 > y) != (y2 > y) && x < x1 + (y - y1) / (y2 - y1) * (x2 - x1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+const POSITION_ZONE_SAMPLES: usize = 89;
+
+/// Compute the zone from which `object_ball` can be potted into `pocket` with a
+/// [`shot_difficulty`] of at most `max_difficulty`.
+///
+/// For each cut angle up to [`crate::MAX_OPEN_POCKET_CUT_DEGREES`] either side, the cue ball's
+/// line of approach is followed back from the ghost ball until the difficulty limit, a cushion,
+/// or the first ball in `blockers` that would obstruct the cue ball's path. Because every
+/// boundary point is reached along a straight, clear line into the ghost ball, the zone is
+/// star-shaped around it. Balls of the same type as `object_ball` are not treated as blockers.
+///
+/// Returns `None` when the ghost ball is off the playfield or the object ball sits on the pocket.
+pub fn position_zone(
+    object_ball: &Ball,
+    pocket: Pocket,
+    max_difficulty: f64,
+    blockers: &[Ball],
+    table_spec: &TableSpec,
+) -> Option<PositionZone> {
+    let diamond = table_spec.diamond_to_inches(Diamond::one()).as_f64();
+    let radius = object_ball.spec.radius.as_f64();
+    let ghost_ball = object_ball.ghost_ball_to_pocket(pocket, table_spec);
+    let ghost = position_xy_inches(&ghost_ball, table_spec);
+    let object = position_xy_inches(&object_ball.position, table_spec);
+    let target = position_xy_inches(&pocket.aiming_center(), table_spec);
+    if !within_cushions(ghost, radius, table_spec) {
+        return None;
+    }
+    let heading = unit_vector(target.0 - object.0, target.1 - object.1)?;
+    let object_distance = (target.0 - object.0).hypot(target.1 - object.1) / diamond;
+
+    let blockers: Vec<((f64, f64), f64)> = blockers
+        .iter()
+        .filter(|ball| ball.ty != object_ball.ty)
+        .map(|ball| {
+            (
+                position_xy_inches(&ball.position, table_spec),
+                radius + ball.spec.radius.as_f64(),
+            )
+        })
+        .collect();
+
+    let max_cut = crate::MAX_OPEN_POCKET_CUT_DEGREES;
+    let mut outline = vec![ghost_ball.clone()];
+    for sample in 0..POSITION_ZONE_SAMPLES {
+        let cut = -max_cut + 2.0 * max_cut * sample as f64 / (POSITION_ZONE_SAMPLES - 1) as f64;
+        let (sin, cos) = cut.to_radians().sin_cos();
+        // The cue ball travels along `heading` rotated by the cut, so it approaches from the
+        // opposite direction.
+        let back = (
+            -(heading.0 * cos - heading.1 * sin),
+            -(heading.0 * sin + heading.1 * cos),
+        );
+
+        let difficulty_reach = max_difficulty.max(0.0) * cos / object_distance * diamond;
+        let cushion = cushion_contact_in_inches(ghost, back, radius, table_spec);
+        let cushion_reach = (cushion.0 - ghost.0).hypot(cushion.1 - ghost.1);
+        let blocker_reach = blockers
+            .iter()
+            .filter_map(|&(center, clearance)| ray_circle_entry(ghost, back, center, clearance))
+            .fold(f64::INFINITY, f64::min);
+
+        let reach = difficulty_reach.min(cushion_reach).min(blocker_reach);
+        outline.push(position_from_xy_inches(
+            ghost.0 + back.0 * reach,
+            ghost.1 + back.1 * reach,
+            table_spec,
+        ));
+    }
+
+    Some(PositionZone {
+        object_ball: object_ball.ty.clone(),
+        pocket,
+        ghost_ball,
+        max_difficulty,
+        outline,
+    })
+}
+
+/// Distance along a unit ray from `origin` to where it first comes within `clearance` of
+/// `center`, or `None` when it never does. A ray starting inside the clearance returns zero.
+fn ray_circle_entry(
+    origin: (f64, f64),
+    direction: (f64, f64),
+    center: (f64, f64),
+    clearance: f64,
+) -> Option<f64> {
+    let offset = (center.0 - origin.0, center.1 - origin.1);
+    let along = offset.0 * direction.0 + offset.1 * direction.1;
+    let across_squared = offset.0 * offset.0 + offset.1 * offset.1 - along * along;
+    let clearance_squared = clearance * clearance;
+    if offset.0.hypot(offset.1) < clearance {
+        return Some(0.0);
+    }
+    if along <= 0.0 || across_squared >= clearance_squared {
+        return None;
+    }
+
+    Some(along - (clearance_squared - across_squared).sqrt())
+}
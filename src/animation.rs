@@ -0,0 +1,235 @@
+//! Animations of simulated shots.
+//!
+//! Frames are drawn with the raster backend at a fixed rate from
+//! [`SimulationResult::layout_at`], so the balls move at their simulated speeds, and encoded as a
+//! looping animated GIF or PNG. GIFs are limited to 256 colors and frame delays in hundredths of a
+//! second; animated PNGs keep every color and the exact frame rate. With the `mp4` feature, shots
+//! can also be encoded as MP4 video for sharing and editing. [`render_frames`] hands out the frames
+//! themselves for other encoders.
+//!
+//! The `render_*` functions return the whole file. A long break can run to hundreds of megabytes,
+//! so the matching `write_*` functions draw and encode one frame at a time into any
+//! [`Write`](std::io::Write), such as a file or an HTTP response.
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageError, RgbaImage};
+use png::{BitDepth, ColorType, Encoder};
+use std::io::{self, Write};
+
+use crate::diagram::render_scene_to_image;
+use crate::simulation::SimulationResult;
+use crate::{DiagramRenderOptions, Seconds};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationOptions {
+    /// Frames per second of playback. GIF frame delays are whole hundredths of a second, so rates
+    /// that divide 100, like the default 25, play back at exactly the simulated speed.
+    pub fps: u32,
+    /// Simulated seconds per second of playback; below 1 for slow motion.
+    pub playback_speed: f64,
+    /// How long the last frame is held before the animation starts over, in seconds.
+    pub hold_seconds: f64,
+}
+
+impl Default for AnimationOptions {
+    fn default() -> Self {
+        Self {
+            fps: 25,
+            playback_speed: 1.0,
+            hold_seconds: 1.0,
+        }
+    }
+}
+
+impl AnimationOptions {
+    pub fn with_fps(mut self, fps: u32) -> Self {
+        self.fps = fps.max(1);
+        self
+    }
+
+    pub fn with_playback_speed(mut self, playback_speed: f64) -> Self {
+        self.playback_speed = playback_speed;
+        self
+    }
+
+    /// The simulated time of each frame, from the strike to the last event.
+    fn frame_times(&self, result: &SimulationResult) -> Vec<Seconds> {
+        let elapsed = result.elapsed().as_f64();
+        let step = self.playback_speed.max(f64::MIN_POSITIVE) / self.fps.max(1) as f64;
+        let frames = (elapsed / step).ceil() as usize;
+        (0..=frames)
+            .map(|frame| Seconds::new((frame as f64 * step).min(elapsed)))
+            .collect()
+    }
+
+    /// How long each of `count` frames is shown, in milliseconds; the last one is held.
+    fn frame_delays_ms(&self, count: usize) -> impl Iterator<Item = u32> {
+        let frame_ms = 1000 / self.fps.max(1);
+        let hold_ms = frame_ms + (self.hold_seconds.max(0.0) * 1000.0).round() as u32;
+        (0..count).map(move |index| {
+            if index + 1 == count {
+                hold_ms
+            } else {
+                frame_ms
+            }
+        })
+    }
+}
+
+/// Draw `result` at `fps` frames per second of simulated time, from the strike until the balls
+/// stop, for feeding to an encoder or overlay of your own. Frames are drawn with the default
+/// [`DiagramRenderOptions`] and made one at a time as the iterator is advanced.
+pub fn render_frames(result: &SimulationResult, fps: u32) -> impl Iterator<Item = RgbaImage> + '_ {
+    let options = DiagramRenderOptions::default();
+    AnimationOptions::default()
+        .with_fps(fps)
+        .frame_times(result)
+        .into_iter()
+        .map(move |time| render_frame(result, &options, time))
+}
+
+/// Like [`render_frames`], with each frame drawn with `options` at the rate and playback speed of
+/// `animation`. The last frame is not repeated for the hold; that is left to the encoder.
+pub fn render_frames_with_options<'a>(
+    result: &'a SimulationResult,
+    options: &'a DiagramRenderOptions,
+    animation: &AnimationOptions,
+) -> impl Iterator<Item = RgbaImage> + 'a {
+    animation
+        .frame_times(result)
+        .into_iter()
+        .map(move |time| render_frame(result, options, time))
+}
+
+fn render_frame(
+    result: &SimulationResult,
+    options: &DiagramRenderOptions,
+    time: Seconds,
+) -> RgbaImage {
+    let scene = result.layout_at(time).to_diagram_scene(options);
+    render_scene_to_image(&scene, options)
+}
+
+/// Each frame of `result` with how long it is shown, drawn as the encoder asks for it, and how
+/// many frames there are.
+fn timed_frames<'a>(
+    result: &'a SimulationResult,
+    options: &'a DiagramRenderOptions,
+    animation: &AnimationOptions,
+) -> (usize, impl Iterator<Item = (RgbaImage, u32)> + 'a) {
+    let times = animation.frame_times(result);
+    let count = times.len();
+    let frames = times
+        .into_iter()
+        .zip(animation.frame_delays_ms(count))
+        .map(move |(time, delay_ms)| (render_frame(result, options, time), delay_ms));
+    (count, frames)
+}
+
+/// Encode `result` as a looping animated GIF, each frame drawn with `options`.
+pub fn render_gif(
+    result: &SimulationResult,
+    options: &DiagramRenderOptions,
+    animation: &AnimationOptions,
+) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_gif(result, options, animation, &mut buf).expect("GIF encode failed");
+    buf
+}
+
+/// Like [`render_gif`], writing each frame to `writer` as soon as it is drawn, so a long shot
+/// never has more than one frame in memory.
+pub fn write_gif(
+    result: &SimulationResult,
+    options: &DiagramRenderOptions,
+    animation: &AnimationOptions,
+    writer: impl Write,
+) -> io::Result<()> {
+    let (_, frames) = timed_frames(result, options, animation);
+    // Speed 10 of 30 trades a little palette quality for much faster quantizing.
+    let mut encoder = GifEncoder::new_with_speed(writer, 10);
+    encoder
+        .set_repeat(Repeat::Infinite)
+        .map_err(image_to_io_error)?;
+    encoder
+        .encode_frames(frames.map(|(image, delay_ms)| {
+            Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1))
+        }))
+        .map_err(image_to_io_error)
+}
+
+/// Encode `result` as a looping animated PNG, each frame drawn with `options`.
+pub fn render_apng(
+    result: &SimulationResult,
+    options: &DiagramRenderOptions,
+    animation: &AnimationOptions,
+) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_apng(result, options, animation, &mut buf).expect("APNG encode failed");
+    buf
+}
+
+/// Like [`render_apng`], writing each frame to `writer` as soon as it is drawn.
+pub fn write_apng(
+    result: &SimulationResult,
+    options: &DiagramRenderOptions,
+    animation: &AnimationOptions,
+    writer: impl Write,
+) -> io::Result<()> {
+    let (count, frames) = timed_frames(result, options, animation);
+    let mut frames = frames.peekable();
+    let (width, height) = frames
+        .peek()
+        .map(|(first, _)| first.dimensions())
+        .expect("an animation has at least its first frame");
+
+    let mut encoder = Encoder::new(writer, width, height);
+    encoder.set_color(ColorType::Rgba);
+    encoder.set_depth(BitDepth::Eight);
+    encoder.set_animated(count as u32, 0)?;
+    let mut writer = encoder.write_header()?;
+    for (image, delay_ms) in frames {
+        writer.set_frame_delay(delay_ms.min(u16::MAX.into()) as u16, 1000)?;
+        writer.write_image_data(image.as_raw())?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Encode `result` as AV1 video in an MP4 file, each frame drawn with `options`. Video has no
+/// transparency, so the frames are drawn over `options.canvas_color` made opaque, and an odd
+/// width or height loses its last column or row.
+#[cfg(feature = "mp4")]
+pub fn render_mp4(
+    result: &SimulationResult,
+    options: &DiagramRenderOptions,
+    animation: &AnimationOptions,
+) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_mp4(result, options, animation, &mut buf).expect("MP4 encode failed");
+    buf
+}
+
+/// Like [`render_mp4`], writing the video to `writer`. Each frame is encoded as soon as it is
+/// drawn, but an MP4 file indexes its frames by their encoded sizes, so the encoded frames, far
+/// smaller than the drawn ones, are kept until the last is done and then written out.
+#[cfg(feature = "mp4")]
+pub fn write_mp4(
+    result: &SimulationResult,
+    options: &DiagramRenderOptions,
+    animation: &AnimationOptions,
+    writer: impl Write,
+) -> io::Result<()> {
+    let (_, frames) = timed_frames(result, options, animation);
+    let mut background = options.canvas_color;
+    background[3] = 255;
+    crate::mp4::encode(frames, background, writer)
+}
+
+/// The writer's own error, or the encoder's as an [`io::Error`].
+fn image_to_io_error(error: ImageError) -> io::Error {
+    match error {
+        ImageError::IoError(error) => error,
+        error => io::Error::other(error),
+    }
+}
@@ -1,5 +1,10 @@
+use crate::visualization::TableTheme;
 use crate::{BallType, Position};
 use bigdecimal::ToPrimitive;
+use image::{ImageFormat, Rgba, RgbaImage};
+use lazy_static::lazy_static;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 const TOPMOST: f32 = 110.;
 const RIGHTMOST: f32 = 968.;
@@ -36,20 +41,266 @@ pub const BALL_IMGS: [&[u8]; 10] = [
 #[allow(unused)]
 pub const TABLE_DIAGRAM: &[u8] = include_bytes!("assets/table_diagram_head_top.png");
 
-/// Retrieve the sprite for a given ball.
+lazy_static! {
+    static ref TABLE_IMAGE: RgbaImage = decode_png(TABLE_DIAGRAM);
+    static ref BALL_SPRITES: Vec<RgbaImage> = BALL_IMGS.iter().copied().map(decode_png).collect();
+}
+
+fn decode_png(png: &[u8]) -> RgbaImage {
+    image::load_from_memory_with_format(png, ImageFormat::Png)
+        .expect("broken built-in image")
+        .into_rgba8()
+}
+
+/// The built-in table image, decoded once and shared by every drawing.
+pub fn table_image() -> &'static RgbaImage {
+    &TABLE_IMAGE
+}
+
+/// The built-in sprite for `ball`, decoded once and shared by every drawing; the 10 through 15
+/// have none.
+pub fn ball_sprite(ball: &BallType) -> Option<&'static RgbaImage> {
+    sprite_index(ball).map(|index| &BALL_SPRITES[index])
+}
+
+/// Retrieve the sprite for a given ball, if there is one; the 10 through 15 have none.
 #[allow(unused)]
-pub fn ball_img(ball: BallType) -> Vec<u8> {
+pub fn ball_img(ball: BallType) -> Option<Vec<u8>> {
+    sprite_index(&ball).map(|index| BALL_IMGS[index].to_vec())
+}
+
+fn sprite_index(ball: &BallType) -> Option<usize> {
     match ball {
-        BallType::Cue => BALL_IMGS[0].to_vec(),
-        BallType::One => BALL_IMGS[1].to_vec(),
-        BallType::Two => BALL_IMGS[2].to_vec(),
-        BallType::Three => BALL_IMGS[3].to_vec(),
-        BallType::Four => BALL_IMGS[4].to_vec(),
-        BallType::Five => BALL_IMGS[5].to_vec(),
-        BallType::Six => BALL_IMGS[6].to_vec(),
-        BallType::Seven => BALL_IMGS[7].to_vec(),
-        BallType::Eight => BALL_IMGS[8].to_vec(),
-        BallType::Nine => BALL_IMGS[9].to_vec(),
+        BallType::Cue => Some(0),
+        ball => ball
+            .number()
+            .map(usize::from)
+            .filter(|&number| number < BALL_IMGS.len()),
+    }
+}
+
+/// The table image in a custom assets directory.
+pub const TABLE_FILE: &str = "table.png";
+
+/// The sidecar in a custom assets directory that says where the playing surface lies in
+/// [`TABLE_FILE`].
+pub const CALIBRATION_FILE: &str = "calibration.toml";
+
+/// Ball sprite file names, in the order of [`BALL_IMGS`]. A custom assets directory may supply
+/// any of them; the rest keep the built-in art.
+///
+/// There is no name for the 10 through 15, which are always drawn procedurally.
+pub const BALL_FILES: [&str; 10] = [
+    "ball_cue.png",
+    "ball_1.png",
+    "ball_2.png",
+    "ball_3.png",
+    "ball_4.png",
+    "ball_5.png",
+    "ball_6.png",
+    "ball_7.png",
+    "ball_8.png",
+    "ball_9.png",
+];
+
+/// Where the edges of the playing surface (the cushion noses) lie in a table image, in pixels.
+///
+/// A calibration file gives all four as `key = value` lines, for example:
+///
+/// ```toml
+/// leftmost = 110
+/// rightmost = 968
+/// topmost = 110
+/// bottommost = 1828
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TableCalibration {
+    pub leftmost_px: f32,
+    pub rightmost_px: f32,
+    pub topmost_px: f32,
+    pub bottommost_px: f32,
+}
+
+impl Default for TableCalibration {
+    /// The calibration of the built-in table image.
+    fn default() -> Self {
+        Self {
+            leftmost_px: LEFTMOST,
+            rightmost_px: RIGHTMOST,
+            topmost_px: TOPMOST,
+            bottommost_px: BOTTOMMOST,
+        }
+    }
+}
+
+impl FromStr for TableCalibration {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let [mut leftmost, mut rightmost, mut topmost, mut bottommost] = [None; 4];
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", index + 1));
+            };
+            let value: f32 = value
+                .trim()
+                .parse()
+                .map_err(|_| format!("line {}: expected a number of pixels", index + 1))?;
+            let slot = match key.trim() {
+                "leftmost" => &mut leftmost,
+                "rightmost" => &mut rightmost,
+                "topmost" => &mut topmost,
+                "bottommost" => &mut bottommost,
+                other => return Err(format!("line {}: unknown key '{other}'", index + 1)),
+            };
+            *slot = Some(value);
+        }
+
+        let require = |value: Option<f32>, key: &str| value.ok_or(format!("missing '{key}'"));
+        let calibration = Self {
+            leftmost_px: require(leftmost, "leftmost")?,
+            rightmost_px: require(rightmost, "rightmost")?,
+            topmost_px: require(topmost, "topmost")?,
+            bottommost_px: require(bottommost, "bottommost")?,
+        };
+        if calibration.leftmost_px >= calibration.rightmost_px
+            || calibration.topmost_px >= calibration.bottommost_px
+        {
+            return Err("the playing surface has no area".to_string());
+        }
+        Ok(calibration)
+    }
+}
+
+impl TableCalibration {
+    /// Read the calibration sidecar in `dir`.
+    pub fn load(dir: &Path) -> Result<Self, AssetError> {
+        let path = dir.join(CALIBRATION_FILE);
+        std::fs::read_to_string(&path)
+            .map_err(|error| AssetError::new(&path, error))?
+            .parse()
+            .map_err(|message| AssetError::new(&path, message))
+    }
+}
+
+/// A problem reading a custom assets directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl AssetError {
+    fn new(path: &Path, message: impl ToString) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for AssetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+/// Table and ball art loaded from a directory at runtime in place of the built-in assets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetPack {
+    pub table: RgbaImage,
+    pub calibration: TableCalibration,
+    balls: Vec<Option<RgbaImage>>,
+}
+
+impl AssetPack {
+    /// Load [`TABLE_FILE`] and its [`CALIBRATION_FILE`] from `dir`, along with whichever of
+    /// [`BALL_FILES`] it has.
+    pub fn load(dir: &Path) -> Result<Self, AssetError> {
+        let calibration = TableCalibration::load(dir)?;
+        let table = load_image(&dir.join(TABLE_FILE))?;
+        let balls = BALL_FILES
+            .iter()
+            .map(|name| {
+                let path = dir.join(name);
+                path.exists().then(|| load_image(&path)).transpose()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            table,
+            calibration,
+            balls,
+        })
+    }
+
+    /// The sprite for `ball`, falling back to the built-in one.
+    pub fn ball_img(&self, ball: &BallType) -> Option<RgbaImage> {
+        let index = sprite_index(ball)?;
+        Some(
+            self.balls[index]
+                .clone()
+                .unwrap_or_else(|| BALL_SPRITES[index].clone()),
+        )
+    }
+}
+
+fn load_image(path: &Path) -> Result<RgbaImage, AssetError> {
+    image::open(path)
+        .map(|image| image.into_rgba8())
+        .map_err(|error| AssetError::new(path, error))
+}
+
+/// Repaint the table image in `theme`'s colors.
+///
+/// Each pixel is matched to the blend between the classic ink and one of the classic flat
+/// colors that best reproduces it, and the same blend of the theme's colors is drawn instead, so
+/// anti-aliased outlines stay smooth.
+pub fn recolor_table(table: &mut RgbaImage, theme: &TableTheme) {
+    let classic = TableTheme::classic();
+    let pairs = [
+        (classic.cloth, theme.cloth),
+        (classic.cushion, theme.cushion),
+        (classic.rail, theme.rail),
+        (classic.corner_rail, theme.corner_rail),
+    ];
+    let channels = |color: Rgba<u8>| [color[0] as f32, color[1] as f32, color[2] as f32];
+    let (ink, new_ink) = (channels(classic.ink), channels(theme.ink));
+
+    for pixel in table.pixels_mut() {
+        if pixel[3] == 0 {
+            continue;
+        }
+
+        let color = channels(*pixel);
+        let from_ink = [color[0] - ink[0], color[1] - ink[1], color[2] - ink[2]];
+        let (t, target) = pairs
+            .iter()
+            .map(|&(key, target)| {
+                let key = channels(key);
+                let direction = [key[0] - ink[0], key[1] - ink[1], key[2] - ink[2]];
+                let length_sq: f32 = direction.iter().map(|d| d * d).sum();
+                let t = ((0..3).map(|i| from_ink[i] * direction[i]).sum::<f32>() / length_sq)
+                    .clamp(0.0, 1.0);
+                let error: f32 = (0..3)
+                    .map(|i| (from_ink[i] - t * direction[i]).powi(2))
+                    .sum();
+                (error, t, target)
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, t, target)| (t, channels(target)))
+            .expect("the theme has colors");
+
+        for i in 0..3 {
+            pixel[i] = (new_ink[i] + t * (target[i] - new_ink[i]))
+                .round()
+                .clamp(0.0, 255.0) as u8;
+        }
     }
 }
 
@@ -76,9 +327,48 @@ mod tests {
         assert_eq!(diamond_to_pixel(&TOP_RIGHT_DIAMOND), (968, 110));
     }
 
+    #[test]
+    fn given_the_classic_theme_when_recoloring_the_table_then_its_flat_colors_are_unchanged() {
+        let mut table = image::load_from_memory(TABLE_DIAGRAM).unwrap().into_rgba8();
+        let cloth = *table.get_pixel(300, 500);
+        recolor_table(&mut table, &TableTheme::classic());
+        assert_eq!(*table.get_pixel(300, 500), cloth);
+
+        recolor_table(&mut table, &TableTheme::red());
+        assert_eq!(*table.get_pixel(300, 500), TableTheme::red().cloth);
+        assert_eq!(*table.get_pixel(0, 0), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn given_repeated_lookups_when_fetching_built_in_art_then_it_is_decoded_once() {
+        assert!(std::ptr::eq(table_image(), table_image()));
+        let eight = ball_sprite(&BallType::Eight).unwrap();
+        assert!(std::ptr::eq(eight, ball_sprite(&BallType::Eight).unwrap()));
+        assert_eq!(*eight, decode_png(&ball_img(BallType::Eight).unwrap()));
+        assert!(ball_sprite(&BallType::Twelve).is_none());
+    }
+
     #[test]
     fn given_the_current_table_asset_when_computing_the_ideal_ball_size_then_the_expected_sprite_diameter_is_returned(
     ) {
         assert_eq!(ideal_ball_size_px(), 39);
     }
+
+    #[test]
+    fn given_a_calibration_sidecar_when_parsing_then_every_edge_is_required() {
+        let calibration: TableCalibration =
+            "leftmost = 110\nrightmost = 968 # nose\n\ntopmost = 110\nbottommost = 1828\n"
+                .parse()
+                .unwrap();
+        assert_eq!(calibration, TableCalibration::default());
+
+        assert_eq!(
+            "leftmost = 1\nrightmost = 2\ntopmost = 1\n".parse::<TableCalibration>(),
+            Err("missing 'bottommost'".to_string())
+        );
+        assert_eq!(
+            "left = 1".parse::<TableCalibration>(),
+            Err("line 1: unknown key 'left'".to_string())
+        );
+    }
 }
@@ -0,0 +1,409 @@
+//! Bank-shot geometry for position-play diagrams.
+//!
+//! The calculators here use the mirror system: the cushion is treated as a perfect mirror for the
+//! ball center, so a bank is found by reflecting the target across the line the ball center
+//! follows when it touches the cushion nose. Speed, spin, and cushion compression are ignored.
+
+use crate::aiming::throw_adjusted_ghost_ball;
+use crate::{
+    position_from_xy_inches, position_xy_inches, segment_point_distance_in_inches, Ball, BallType,
+    Diamond, EnglishRebound, Inches, Pocket, Position, Rail, TableSpec, ThrowModel,
+};
+
+/// One cushion contact along a bank path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BankContact {
+    pub rail: Rail,
+    /// The ball center at the moment it touches the cushion.
+    pub point: Position,
+    /// Where the ball meets the cushion, measured along `rail` in diamonds: the `x` coordinate for
+    /// `Top` and `Bottom`, and the `y` coordinate for `Left` and `Right`.
+    pub diamond: Diamond,
+}
+
+/// A bank shot solved with the mirror system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BankShot {
+    pub pocket: Pocket,
+    /// Cushion contacts, in the order the ball reaches them.
+    pub contacts: Vec<BankContact>,
+    /// The ball-center path from the object ball, through each cushion contact, to the pocket.
+    pub path: Vec<Position>,
+}
+
+/// A ball that sits in the way of one leg of a bank path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BankObstruction {
+    /// Index of the blocked leg; leg `i` runs from `path[i]` to `path[i + 1]`.
+    pub leg: usize,
+    pub ball: BallType,
+}
+
+impl BankShot {
+    /// Return the rails in the order the ball contacts them.
+    pub fn rails(&self) -> Vec<Rail> {
+        self.contacts.iter().map(|contact| contact.rail).collect()
+    }
+
+    /// Return every ball from `balls` that the banked ball would touch along the path.
+    ///
+    /// A ball obstructs a leg when its center passes within the sum of the two radii of that leg.
+    /// Balls sitting exactly on the path start, such as the object ball itself, are skipped.
+    pub fn obstructions(
+        &self,
+        balls: &[Ball],
+        moving_ball_radius: &Inches,
+        table_spec: &TableSpec,
+    ) -> Vec<BankObstruction> {
+        let points: Vec<(f64, f64)> = self
+            .path
+            .iter()
+            .map(|point| position_xy_inches(point, table_spec))
+            .collect();
+        let start = points[0];
+
+        let mut obstructions = Vec::new();
+        for ball in balls {
+            let center = position_xy_inches(&ball.position, table_spec);
+            if (center.0 - start.0).hypot(center.1 - start.1) < 1e-9 {
+                continue;
+            }
+
+            let clearance = moving_ball_radius.as_f64() + ball.spec.radius.as_f64();
+            for (leg, window) in points.windows(2).enumerate() {
+                if segment_point_distance_in_inches(window[0], window[1], center) < clearance {
+                    obstructions.push(BankObstruction {
+                        leg,
+                        ball: ball.ty.clone(),
+                    });
+                }
+            }
+        }
+
+        obstructions
+    }
+
+    /// Whether no ball from `balls` blocks any leg of the path.
+    pub fn is_clear(
+        &self,
+        balls: &[Ball],
+        moving_ball_radius: &Inches,
+        table_spec: &TableSpec,
+    ) -> bool {
+        self.obstructions(balls, moving_ball_radius, table_spec)
+            .is_empty()
+    }
+
+    /// Return where the cue ball must be at contact to send `object_ball` down the first leg.
+    ///
+    /// Without a [`ThrowModel`] this is the plain ghost ball for the first cushion contact; with
+    /// one, the ghost ball is adjusted for throw from `cue_ball` as in
+    /// [`crate::aiming::throw_adjusted_ghost_ball`].
+    pub fn ghost_ball(
+        &self,
+        cue_ball: &Position,
+        object_ball: &Ball,
+        throw: Option<&ThrowModel>,
+        table_spec: &TableSpec,
+    ) -> Position {
+        let first_contact = &self.path[1];
+        match throw {
+            Some(throw) => {
+                throw_adjusted_ghost_ball(cue_ball, object_ball, first_contact, throw, table_spec)
+            }
+            None => object_ball.ghost_ball(first_contact, table_spec),
+        }
+    }
+}
+
+/// Solve a one-rail bank of `object_ball` off `rail` into `pocket`.
+///
+/// Returns `None` when the mirror line does not exist on this table: the object ball or the
+/// pocket is not in front of the rail, or the required contact falls beyond the playable length
+/// of the cushion.
+pub fn single_rail_bank(
+    object_ball: &Ball,
+    pocket: Pocket,
+    rail: Rail,
+    table_spec: &TableSpec,
+) -> Option<BankShot> {
+    multi_rail_bank(object_ball, pocket, &[rail], table_spec)
+}
+
+/// Solve a one-rail bank like [`single_rail_bank`], optionally with a non-mirror cushion.
+///
+/// With an [`EnglishRebound`], the contact is found by searching along the rail for the point
+/// whose rebound, under that rule, reaches the pocket. Without one this is the mirror solution.
+pub fn single_rail_bank_with_rebound(
+    object_ball: &Ball,
+    pocket: Pocket,
+    rail: Rail,
+    rebound: Option<&EnglishRebound>,
+    table_spec: &TableSpec,
+) -> Option<BankShot> {
+    let Some(rebound) = rebound else {
+        return single_rail_bank(object_ball, pocket, rail, table_spec);
+    };
+
+    let (contact_x, contact_y) = rebound_contact_in_inches(
+        position_xy_inches(&object_ball.position, table_spec),
+        position_xy_inches(&pocket.aiming_center(), table_spec),
+        rail,
+        object_ball.spec.radius.as_f64(),
+        rebound,
+        table_spec,
+    )?;
+    let point = position_from_xy_inches(contact_x, contact_y, table_spec);
+    let diamond = if rail.is_horizontal() {
+        point.x.clone()
+    } else {
+        point.y.clone()
+    };
+
+    Some(BankShot {
+        pocket,
+        path: vec![
+            object_ball.position.clone(),
+            point.clone(),
+            pocket.aiming_center(),
+        ],
+        contacts: vec![BankContact {
+            rail,
+            point,
+            diamond,
+        }],
+    })
+}
+
+/// Solve every one-rail bank of `object_ball` into `pocket` that exists on this table.
+pub fn single_rail_banks(
+    object_ball: &Ball,
+    pocket: Pocket,
+    table_spec: &TableSpec,
+) -> Vec<BankShot> {
+    multi_rail_banks(object_ball, pocket, 1, table_spec)
+}
+
+/// Solve a bank of `object_ball` into `pocket` that contacts `rails` in order.
+///
+/// The pocket is reflected across each rail in reverse order, and the straight line to that
+/// final image is folded back onto the table one rail at a time. Returns `None` when any leg
+/// would miss its rail, leave from behind it, or reach a different cushion first.
+pub fn multi_rail_bank(
+    object_ball: &Ball,
+    pocket: Pocket,
+    rails: &[Rail],
+    table_spec: &TableSpec,
+) -> Option<BankShot> {
+    assert!(!rails.is_empty(), "a bank needs at least one rail");
+
+    let radius = object_ball.spec.radius.as_f64();
+    let target = position_xy_inches(&pocket.aiming_center(), table_spec);
+    let mut image = target;
+    for &rail in rails.iter().rev() {
+        image = reflect_across_rail(image, rail, radius, table_spec);
+    }
+
+    let mut current = position_xy_inches(&object_ball.position, table_spec);
+    let mut contacts = Vec::with_capacity(rails.len());
+    for &rail in rails {
+        let (contact_x, contact_y) =
+            mirror_contact_in_inches(current, image, rail, radius, table_spec)?;
+        let point = position_from_xy_inches(contact_x, contact_y, table_spec);
+        let diamond = if rail.is_horizontal() {
+            point.x.clone()
+        } else {
+            point.y.clone()
+        };
+        contacts.push(BankContact {
+            rail,
+            point,
+            diamond,
+        });
+
+        current = (contact_x, contact_y);
+        image = reflect_across_rail(image, rail, radius, table_spec);
+    }
+
+    let mut path = Vec::with_capacity(rails.len() + 2);
+    path.push(object_ball.position.clone());
+    path.extend(contacts.iter().map(|contact| contact.point.clone()));
+    path.push(pocket.aiming_center());
+
+    Some(BankShot {
+        pocket,
+        contacts,
+        path,
+    })
+}
+
+/// Solve every bank of `object_ball` into `pocket` that uses exactly `rail_count` cushions.
+///
+/// Rail sequences never repeat the same rail twice in a row. Results are ordered by rail
+/// sequence, starting with `Top`, `Bottom`, `Left`, then `Right`.
+pub fn multi_rail_banks(
+    object_ball: &Ball,
+    pocket: Pocket,
+    rail_count: usize,
+    table_spec: &TableSpec,
+) -> Vec<BankShot> {
+    let mut sequences: Vec<Vec<Rail>> = vec![Vec::new()];
+    for _ in 0..rail_count {
+        let mut extended = Vec::with_capacity(sequences.len() * 3);
+        for sequence in &sequences {
+            for rail in ALL_RAILS {
+                if sequence.last() != Some(&rail) {
+                    let mut next = sequence.clone();
+                    next.push(rail);
+                    extended.push(next);
+                }
+            }
+        }
+        sequences = extended;
+    }
+
+    sequences
+        .iter()
+        .filter(|sequence| !sequence.is_empty())
+        .filter_map(|rails| multi_rail_bank(object_ball, pocket, rails, table_spec))
+        .collect()
+}
+
+const ALL_RAILS: [Rail; 4] = [Rail::Top, Rail::Bottom, Rail::Left, Rail::Right];
+
+/// Return the coordinate of the line the ball center follows along `rail` when touching it, in
+/// inches, and the distance of `point` in front of that line.
+pub(crate) fn rail_plane_and_depth(
+    rail: Rail,
+    (x, y): (f64, f64),
+    ball_radius: f64,
+    table_spec: &TableSpec,
+) -> (f64, f64) {
+    let width = table_spec.diamond_to_inches(Diamond::four()).as_f64();
+    let length = table_spec.diamond_to_inches(Diamond::eight()).as_f64();
+
+    match rail {
+        Rail::Top => (length - ball_radius, length - ball_radius - y),
+        Rail::Bottom => (ball_radius, y - ball_radius),
+        Rail::Left => (ball_radius, x - ball_radius),
+        Rail::Right => (width - ball_radius, width - ball_radius - x),
+    }
+}
+
+pub(crate) fn reflect_across_rail(
+    point: (f64, f64),
+    rail: Rail,
+    ball_radius: f64,
+    table_spec: &TableSpec,
+) -> (f64, f64) {
+    let (plane, _) = rail_plane_and_depth(rail, point, ball_radius, table_spec);
+    if rail.is_horizontal() {
+        (point.0, 2.0 * plane - point.1)
+    } else {
+        (2.0 * plane - point.0, point.1)
+    }
+}
+
+/// Find where the straight line from `start` to the mirror image of `target` crosses the
+/// ball-center contact line for `rail`.
+pub(crate) fn mirror_contact_in_inches(
+    start: (f64, f64),
+    image: (f64, f64),
+    rail: Rail,
+    ball_radius: f64,
+    table_spec: &TableSpec,
+) -> Option<(f64, f64)> {
+    let (plane, start_depth) = rail_plane_and_depth(rail, start, ball_radius, table_spec);
+    let (_, image_depth) = rail_plane_and_depth(rail, image, ball_radius, table_spec);
+    if start_depth <= 0.0 || image_depth > 0.0 {
+        return None;
+    }
+
+    // Similar triangles: the contact splits the along-rail run in proportion to the two depths.
+    let t = start_depth / (start_depth - image_depth);
+    let contact = if rail.is_horizontal() {
+        (start.0 + t * (image.0 - start.0), plane)
+    } else {
+        (plane, start.1 + t * (image.1 - start.1))
+    };
+
+    let width = table_spec.diamond_to_inches(Diamond::four()).as_f64();
+    let length = table_spec.diamond_to_inches(Diamond::eight()).as_f64();
+    let (along, span) = if rail.is_horizontal() {
+        (contact.0, width)
+    } else {
+        (contact.1, length)
+    };
+    (ball_radius..=span - ball_radius)
+        .contains(&along)
+        .then_some(contact)
+}
+
+const REBOUND_SEARCH_SAMPLES: usize = 128;
+const REBOUND_BISECTION_STEPS: usize = 60;
+
+/// Find the ball-center contact on `rail` from which a ball arriving from `start` rebounds,
+/// under `rebound`, through `target`.
+///
+/// The contact is scanned along the playable length of the cushion and refined by bisection.
+/// When more than one contact works, the one nearest the mirror contact is returned.
+pub(crate) fn rebound_contact_in_inches(
+    start: (f64, f64),
+    target: (f64, f64),
+    rail: Rail,
+    ball_radius: f64,
+    rebound: &EnglishRebound,
+    table_spec: &TableSpec,
+) -> Option<(f64, f64)> {
+    let (plane, start_depth) = rail_plane_and_depth(rail, start, ball_radius, table_spec);
+    let (_, target_depth) = rail_plane_and_depth(rail, target, ball_radius, table_spec);
+    if start_depth <= 0.0 || target_depth < 0.0 {
+        return None;
+    }
+
+    let width = table_spec.diamond_to_inches(Diamond::four()).as_f64();
+    let length = table_spec.diamond_to_inches(Diamond::eight()).as_f64();
+    let (start_along, target_along, span) = if rail.is_horizontal() {
+        (start.0, target.0, width)
+    } else {
+        (start.1, target.1, length)
+    };
+    let residual = |along: f64| {
+        let incoming = (along - start_along).atan2(start_depth).to_degrees();
+        let outgoing = rebound.rebound_angle_degrees(rail, incoming).to_radians();
+        along + target_depth * outgoing.tan() - target_along
+    };
+    let mirror_along =
+        start_along + (target_along - start_along) * start_depth / (start_depth + target_depth);
+
+    let (low, high) = (ball_radius, span - ball_radius);
+    let step = (high - low) / REBOUND_SEARCH_SAMPLES as f64;
+    let mut best: Option<f64> = None;
+    for sample in 0..REBOUND_SEARCH_SAMPLES {
+        let (mut a, mut b) = (low + step * sample as f64, low + step * (sample + 1) as f64);
+        let value_a = residual(a);
+        if value_a.signum() == residual(b).signum() {
+            continue;
+        }
+        for _ in 0..REBOUND_BISECTION_STEPS {
+            let mid = 0.5 * (a + b);
+            if residual(mid).signum() == value_a.signum() {
+                a = mid;
+            } else {
+                b = mid;
+            }
+        }
+        let root = 0.5 * (a + b);
+        if best.is_none_or(|best| (root - mirror_along).abs() < (best - mirror_along).abs()) {
+            best = Some(root);
+        }
+    }
+
+    best.map(|along| {
+        if rail.is_horizontal() {
+            (along, plane)
+        } else {
+            (plane, along)
+        }
+    })
+}
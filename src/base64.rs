@@ -0,0 +1,78 @@
+//! Base64, padded for data URLs and unpadded with the URL-safe alphabet for links.
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+#[cfg(feature = "serde")]
+const URL_SAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// `bytes` in standard, padded base64, for data URLs.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    encode_with(bytes, STANDARD, true)
+}
+
+/// `bytes` in unpadded base64 with `-` and `_` for `+` and `/`, safe in a URL query.
+#[cfg(feature = "serde")]
+pub(crate) fn encode_url_safe(bytes: &[u8]) -> String {
+    encode_with(bytes, URL_SAFE, false)
+}
+
+/// The bytes `text` encodes with [`encode_url_safe`], or `None` if it is not URL-safe base64.
+#[cfg(feature = "serde")]
+pub(crate) fn decode_url_safe(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 4 == 1 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.as_bytes().chunks(4) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .try_fold(0u32, |group, (index, c)| {
+                let value = URL_SAFE.iter().position(|symbol| symbol == c)? as u32;
+                Some(group | value << (18 - 6 * index))
+            })?;
+        bytes.extend(&group.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(bytes)
+}
+
+fn encode_with(bytes: &[u8], alphabet: &[u8; 64], pad: bool) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | (*byte as u32) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(alphabet[(group >> (18 - 6 * index) & 63) as usize] as char);
+            } else if pad {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn url_safe_base64_round_trips_every_tail_length() {
+        let bytes: Vec<u8> = (0..=255).rev().collect();
+        for length in 0..8 {
+            let encoded = encode_url_safe(&bytes[..length]);
+            assert!(!encoded.contains(['+', '/', '=']));
+            assert_eq!(decode_url_safe(&encoded).unwrap(), &bytes[..length]);
+        }
+        assert_eq!(encode_url_safe(&[0xfb, 0xff]), "-_8");
+        assert_eq!(decode_url_safe("YW+"), None);
+        assert_eq!(decode_url_safe("Y"), None);
+    }
+
+    #[test]
+    fn standard_base64_is_padded() {
+        assert_eq!(encode(b"ab"), "YWI=");
+        assert_eq!(encode(&[0xfb, 0xff]), "+/8=");
+    }
+}
@@ -405,6 +405,7 @@ fn run_probe_case(
             } else {
                 DiagramBackground::Table
             },
+            ..DiagramRenderOptions::default()
         });
         write_png_to_file(&image, Some(&render_path));
         Some(file_name_string(&render_path))
@@ -0,0 +1,352 @@
+//! Layouts captured from outside the crate, such as by a computer-vision detector watching the
+//! table through an overhead camera.
+//!
+//! The crate depends on no vision library. A detector implements [`LayoutSource`] itself, or
+//! reports the balls it found in a frame as [`FrameDetections`] and wraps its detection function
+//! in a [`DetectorSource`], which keeps the confident detections and places them on the table.
+//!
+//! Layouts drawn in books or caught in video frames are rarely square to the camera. A
+//! [`CornerCalibration`] says where the four corners of the playing surface fall in such an
+//! image and takes a pixel in it to a table position, correcting for perspective.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+
+use crate::diagram::{DiagramViewport, ScenePoint};
+use crate::{Ball, BallSpec, BallType, Diamond, GameState, Position, TableSpec};
+
+/// The confidence a [`DetectorSource`] needs to keep a detection unless told otherwise.
+pub const DEFAULT_MIN_CONFIDENCE: f32 = 0.5;
+
+/// Anything that can report the layout on a table, such as a camera and ball detector.
+pub trait LayoutSource {
+    /// The layout on the table now.
+    fn capture(&self) -> Result<GameState, String>;
+}
+
+/// One ball a detector found in a frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetectedBall {
+    /// Which ball the detector took it for, or `None` for a ball it saw but could not identify.
+    pub ty: Option<BallType>,
+    /// The ball's center in the frame, in pixels from its top-left corner.
+    pub center: ScenePoint,
+    /// How sure the detector is that this is the `ty` ball, from 0 to 1.
+    pub confidence: f32,
+}
+
+/// The balls a detector found in one frame, and where the table lies in that frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameDetections {
+    pub balls: Vec<DetectedBall>,
+    /// The frame seen as a diagram: a top-down view with the head rail at the top, calibrated to
+    /// its cushion noses with [`DiagramViewport::calibrated`].
+    pub viewport: DiagramViewport,
+}
+
+impl FrameDetections {
+    /// The layout on `table_spec` of every identified ball detected at least `min_confidence`
+    /// sure with its center on the playing surface. A ball detected more than once is placed
+    /// where its most confident detection put it.
+    pub fn to_game_state(&self, table_spec: &TableSpec, min_confidence: f32) -> GameState {
+        let mut kept: Vec<&DetectedBall> = Vec::new();
+        for detection in self.placeable(min_confidence) {
+            match kept.iter_mut().find(|other| other.ty == detection.ty) {
+                Some(other) if other.confidence < detection.confidence => *other = detection,
+                Some(_) => {}
+                None => kept.push(detection),
+            }
+        }
+
+        let balls = kept.into_iter().filter_map(|detection| {
+            Some(Ball {
+                ty: detection.ty.clone()?,
+                position: self.viewport.scene_point_to_position(detection.center),
+                spec: BallSpec::default(),
+            })
+        });
+        GameState::with_balls(table_spec.clone(), balls)
+    }
+
+    /// The detections [`FrameDetections::to_game_state`] leaves out for being unidentified,
+    /// unsure, or off the playing surface, for a user to check by hand.
+    pub fn rejected(&self, min_confidence: f32) -> impl Iterator<Item = &DetectedBall> {
+        self.balls
+            .iter()
+            .filter(move |detection| !self.is_placeable(detection, min_confidence))
+    }
+
+    fn placeable(&self, min_confidence: f32) -> impl Iterator<Item = &DetectedBall> {
+        self.balls
+            .iter()
+            .filter(move |detection| self.is_placeable(detection, min_confidence))
+    }
+
+    fn is_placeable(&self, detection: &DetectedBall, min_confidence: f32) -> bool {
+        let position = self.viewport.scene_point_to_position(detection.center);
+        let within = |value: &BigDecimal, diamonds: u8| {
+            (BigDecimal::from(0)..=BigDecimal::from(diamonds)).contains(value)
+        };
+        detection.ty.is_some()
+            && detection.confidence >= min_confidence
+            && within(&position.x.magnitude, 4)
+            && within(&position.y.magnitude, 8)
+    }
+}
+
+/// A [`LayoutSource`] built from a detection function, which reports the balls in the latest
+/// frame each time it is called.
+#[derive(Clone, Debug)]
+pub struct DetectorSource<D> {
+    detect: D,
+    table_spec: TableSpec,
+    min_confidence: f32,
+}
+
+impl<D> DetectorSource<D>
+where
+    D: Fn() -> Result<FrameDetections, String>,
+{
+    /// Place `detect`'s balls on the default table, keeping detections at least
+    /// [`DEFAULT_MIN_CONFIDENCE`] sure.
+    pub fn new(detect: D) -> Self {
+        Self {
+            detect,
+            table_spec: TableSpec::default(),
+            min_confidence: DEFAULT_MIN_CONFIDENCE,
+        }
+    }
+
+    pub fn with_table_spec(mut self, table_spec: TableSpec) -> Self {
+        self.table_spec = table_spec;
+        self
+    }
+
+    pub fn with_min_confidence(mut self, min_confidence: f32) -> Self {
+        self.min_confidence = min_confidence;
+        self
+    }
+}
+
+impl<D> LayoutSource for DetectorSource<D>
+where
+    D: Fn() -> Result<FrameDetections, String>,
+{
+    fn capture(&self) -> Result<GameState, String> {
+        Ok((self.detect)()?.to_game_state(&self.table_spec, self.min_confidence))
+    }
+}
+
+/// Where the corners of the playing surface, the points where the cushion noses meet at the
+/// corner pockets, lie in an image of the table taken from any angle.
+///
+/// The corners are named as the table is drawn, with the head rail at the top. A calibration
+/// file gives each as `x, y` pixels on a `key = value` line, for example:
+///
+/// ```text
+/// # Measured on page 42, scanned at 300 dpi.
+/// top_left = 212, 96
+/// top_right = 868, 131
+/// bottom_right = 934, 1720
+/// bottom_left = 140, 1694
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CornerCalibration {
+    corners: [ScenePoint; 4],
+    /// Takes a point of the unit square, `(0, 0)` at the top left and `(1, 1)` at the bottom
+    /// right, to homogeneous image pixels.
+    to_image: [[f64; 3]; 3],
+    /// The inverse of `to_image`, up to scale.
+    to_square: [[f64; 3]; 3],
+}
+
+impl CornerCalibration {
+    /// The calibration with the playing surface's corners at these pixels. They must outline it
+    /// in order, going around its edge without crossing.
+    pub fn new(
+        top_left: ScenePoint,
+        top_right: ScenePoint,
+        bottom_right: ScenePoint,
+        bottom_left: ScenePoint,
+    ) -> Result<Self, String> {
+        let corners = [top_left, top_right, bottom_right, bottom_left];
+        let [x, y] = [0, 1].map(|axis| {
+            corners.map(|corner| f64::from(if axis == 0 { corner.x } else { corner.y }))
+        });
+
+        // The outline turns the same way at every corner only if it is convex and in order.
+        let turns: Vec<f64> = (0..4)
+            .map(|index| {
+                let [a, b, c] = [index, (index + 1) % 4, (index + 2) % 4];
+                (x[b] - x[a]) * (y[c] - y[b]) - (y[b] - y[a]) * (x[c] - x[b])
+            })
+            .collect();
+        if !(turns.iter().all(|turn| *turn > 0.0) || turns.iter().all(|turn| *turn < 0.0)) {
+            return Err(
+                "the corners must outline the playing surface in order: top left, top right, \
+                 bottom right, bottom left"
+                    .to_string(),
+            );
+        }
+
+        // Heckbert's square-to-quadrilateral projection.
+        let (dx1, dx2, dx3) = (x[1] - x[2], x[3] - x[2], x[0] - x[1] + x[2] - x[3]);
+        let (dy1, dy2, dy3) = (y[1] - y[2], y[3] - y[2], y[0] - y[1] + y[2] - y[3]);
+        let denominator = dx1 * dy2 - dx2 * dy1;
+        let g = (dx3 * dy2 - dx2 * dy3) / denominator;
+        let h = (dx1 * dy3 - dx3 * dy1) / denominator;
+        let to_image = [
+            [x[1] - x[0] + g * x[1], x[3] - x[0] + h * x[3], x[0]],
+            [y[1] - y[0] + g * y[1], y[3] - y[0] + h * y[3], y[0]],
+            [g, h, 1.0],
+        ];
+        Ok(Self {
+            corners,
+            to_image,
+            to_square: adjugate(&to_image),
+        })
+    }
+
+    /// The corners in the order [`CornerCalibration::new`] takes them.
+    pub fn corners(&self) -> [ScenePoint; 4] {
+        self.corners
+    }
+
+    /// The table position at `pixel`, to a ten-thousandth of a diamond. Pixels off the playing
+    /// surface give positions outside 0–4 by 0–8 diamonds. A pixel on or past the horizon of the
+    /// table's plane, which no point of the table can be seen at, gives `None`.
+    pub fn position(&self, pixel: ScenePoint) -> Option<Position> {
+        let to_square = |pixel: ScenePoint| {
+            homogeneous(&self.to_square, [f64::from(pixel.x), f64::from(pixel.y)])
+        };
+        let [u, v, w] = to_square(pixel);
+        // Every corner is in front of the horizon, so a point past it has `w` of the other sign.
+        if w * to_square(self.corners[0])[2] <= 0.0 {
+            return None;
+        }
+        let diamond = |value: f64| {
+            Some(Diamond {
+                magnitude: BigDecimal::from_f64(value)?.round(4).normalized(),
+            })
+        };
+        Some(Position::new(
+            diamond(4.0 * u / w)?,
+            diamond(8.0 * (1.0 - v / w))?,
+        ))
+    }
+
+    /// The pixel at `position`, the inverse of [`CornerCalibration::position`], for drawing over
+    /// the image to check a calibration.
+    pub fn pixel(&self, position: &Position) -> ScenePoint {
+        let [x, y] = project(
+            &self.to_image,
+            [
+                position.x.magnitude.to_f64().unwrap_or_default() / 4.0,
+                1.0 - position.y.magnitude.to_f64().unwrap_or_default() / 8.0,
+            ],
+        );
+        ScenePoint {
+            x: x as f32,
+            y: y as f32,
+        }
+    }
+
+    /// The layout on `table_spec` of balls marked at these pixels, or an error naming the first
+    /// ball marked where [`CornerCalibration::position`] finds no position.
+    pub fn layout(
+        &self,
+        table_spec: &TableSpec,
+        balls: impl IntoIterator<Item = (BallType, ScenePoint)>,
+    ) -> Result<GameState, String> {
+        let balls = balls
+            .into_iter()
+            .map(|(ty, pixel)| {
+                let position = self.position(pixel).ok_or(format!(
+                    "{ty:?} is marked at ({}, {}), on or past the horizon of the table",
+                    pixel.x, pixel.y
+                ))?;
+                Ok(Ball {
+                    ty,
+                    position,
+                    spec: BallSpec::default(),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(GameState::with_balls(table_spec.clone(), balls))
+    }
+
+    /// Read a calibration file in the shape shown [above](CornerCalibration).
+    pub fn load(path: &Path) -> Result<Self, String> {
+        std::fs::read_to_string(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?
+            .parse()
+            .map_err(|message| format!("{}: {message}", path.display()))
+    }
+}
+
+impl FromStr for CornerCalibration {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut corners = [None; 4];
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", index + 1));
+            };
+            let slot = match key.trim() {
+                "top_left" => &mut corners[0],
+                "top_right" => &mut corners[1],
+                "bottom_right" => &mut corners[2],
+                "bottom_left" => &mut corners[3],
+                other => return Err(format!("line {}: unknown key '{other}'", index + 1)),
+            };
+            let pixel = value
+                .split_once(',')
+                .and_then(|(x, y)| {
+                    Some(ScenePoint {
+                        x: x.trim().parse().ok()?,
+                        y: y.trim().parse().ok()?,
+                    })
+                })
+                .ok_or(format!("line {}: expected `x, y` in pixels", index + 1))?;
+            *slot = Some(pixel);
+        }
+
+        let [top_left, top_right, bottom_right, bottom_left] = corners;
+        let require =
+            |value: Option<ScenePoint>, key: &str| value.ok_or(format!("missing '{key}'"));
+        Self::new(
+            require(top_left, "top_left")?,
+            require(top_right, "top_right")?,
+            require(bottom_right, "bottom_right")?,
+            require(bottom_left, "bottom_left")?,
+        )
+    }
+}
+
+/// The adjugate of `m`, which inverts it up to scale.
+fn adjugate(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let cofactor = |row: usize, column: usize| {
+        let (r0, r1) = ((row + 1) % 3, (row + 2) % 3);
+        let (c0, c1) = ((column + 1) % 3, (column + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    [0, 1, 2].map(|row| [0, 1, 2].map(|column| cofactor(column, row)))
+}
+
+/// `point` taken through the homography `m`, in homogeneous coordinates.
+fn homogeneous(m: &[[f64; 3]; 3], [x, y]: [f64; 2]) -> [f64; 3] {
+    m.map(|row| row[0] * x + row[1] * y + row[2])
+}
+
+/// `point` taken through the homography `m`.
+fn project(m: &[[f64; 3]; 3], point: [f64; 2]) -> [f64; 2] {
+    let [u, v, w] = homogeneous(m, point);
+    [u / w, v / w]
+}
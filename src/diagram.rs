@@ -1,14 +1,30 @@
+use crate::assets::{AssetError, AssetPack, TableCalibration};
 use crate::visualization::{
-    DashedLineStyle, EventMarkerStyle, GhostBallStyle, LabelOverlayStyle, SmoothPolylineStyle,
+    AngleArcStyle, ArrowEnds, ArrowStyle, BallHighlightKind, BallHighlightStyle, DashedLineStyle,
+    DiamondGridStyle, EventMarkerStyle, GhostBallStyle, LabelOverlayStyle, LegendEntry,
+    LegendStyle, LegendSwatch, PhantomBallStyle, PocketHighlightStyle, SequenceMarkerStyle,
+    ShadedZoneStyle, SmoothPolylineStyle, TableTheme,
 };
 use crate::{
-    assets, drawing, BallSpec, BallType, DiagramBackground, DiagramRenderOptions, OverlayLayer,
+    assets, base64, drawing, pdf, BallSpec, BallStyle, BallType, DiagramBackground, DiagramCorner,
+    DiagramOrientation, DiagramRegion, DiagramRenderOptions, GameState, OverlayLayer, Watermark,
+    WatermarkContent,
 };
-use crate::{Position, TableSpec};
-use bigdecimal::ToPrimitive;
-use image::codecs::png::PngEncoder;
-use image::imageops::{overlay, resize, FilterType};
-use image::{ImageEncoder, ImageFormat, Rgba, RgbaImage};
+use crate::{Diamond, Inches, Pocket, PocketType, Position, TableSpec};
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+use image::codecs::jpeg::{JpegEncoder, PixelDensity};
+use image::codecs::webp::WebPEncoder;
+use image::imageops::{crop_imm, overlay, resize, rotate90, FilterType};
+use image::{ImageEncoder, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_circle_mut, draw_filled_rect_mut, draw_polygon_mut};
+use imageproc::geometric_transformations::{warp_into, Interpolation, Projection};
+use imageproc::point::Point;
+use imageproc::rect::Rect;
+use lazy_static::lazy_static;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
 
 const LEGACY_WIDTH_PX: f32 = 1089.0;
 const LEGACY_HEIGHT_PX: f32 = 1938.0;
@@ -19,7 +35,6 @@ const PLAYFIELD_BOTTOM_PX: f32 = 1828.0;
 const TABLE_DIAMONDS_X: f32 = 4.0;
 const TABLE_DIAMONDS_Y: f32 = 8.0;
 const PLAYFIELD_WIDTH_IN: f32 = 50.0;
-const PLAYFIELD_LENGTH_IN: f32 = 100.0;
 const CUSHION_WIDTH_IN: f32 = 1.9;
 const DIAMOND_SIGHT_SETBACK_IN: f32 = 3.6875;
 const DIAMOND_SIGHT_WIDTH_IN: f32 = 1.35;
@@ -31,11 +46,19 @@ const CORNER_POCKET_WELL_IN: f32 = 3.05;
 const SIDE_POCKET_LIP_IN: f32 = 1.6;
 const SIDE_POCKET_WELL_IN: f32 = 5.0;
 const CUSHION_BEVEL_IN: f32 = 1.0;
+// Proportions of the bundled art's rails, for tables drawn procedurally.
+const RAIL_WIDTH_IN: f32 = 6.4;
+const RAIL_CORNER_RADIUS_IN: f32 = 3.4;
+const CORNER_CASTING_IN: f32 = 11.4;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DiagramOutputFormat {
     Png,
     Svg,
+    Pdf,
+    Jpeg,
+    WebP,
+    Text,
 }
 
 impl DiagramOutputFormat {
@@ -43,6 +66,10 @@ impl DiagramOutputFormat {
         match self {
             Self::Png => "png",
             Self::Svg => "svg",
+            Self::Pdf => "pdf",
+            Self::Jpeg => "jpg",
+            Self::WebP => "webp",
+            Self::Text => "txt",
         }
     }
 
@@ -50,6 +77,10 @@ impl DiagramOutputFormat {
         match extension.to_ascii_lowercase().as_str() {
             "png" => Some(Self::Png),
             "svg" => Some(Self::Svg),
+            "pdf" => Some(Self::Pdf),
+            "jpg" | "jpeg" => Some(Self::Jpeg),
+            "webp" => Some(Self::WebP),
+            "txt" => Some(Self::Text),
             _ => None,
         }
     }
@@ -84,6 +115,7 @@ impl From<OverlayLayer> for DiagramLayerId {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ScenePoint {
     pub x: f32,
     pub y: f32,
@@ -113,6 +145,57 @@ impl Default for DiagramViewport {
 }
 
 impl DiagramViewport {
+    /// The viewport of a `width_px` × `height_px` table image calibrated by `calibration`.
+    pub fn calibrated(width_px: u32, height_px: u32, calibration: &TableCalibration) -> Self {
+        Self {
+            width_px: width_px as f32,
+            height_px: height_px as f32,
+            playfield_left_px: calibration.leftmost_px,
+            playfield_right_px: calibration.rightmost_px,
+            playfield_top_px: calibration.topmost_px,
+            playfield_bottom_px: calibration.bottommost_px,
+        }
+    }
+
+    /// The viewport of a table drawn from `table_spec`. The playfield keeps the bundled art's
+    /// pixels per diamond and the rails keep their width in inches, so a smaller table gets
+    /// relatively wider rails.
+    pub fn for_table_spec(table_spec: &TableSpec) -> Self {
+        let playfield_width = PLAYFIELD_RIGHT_PX - PLAYFIELD_LEFT_PX;
+        let playfield_height = PLAYFIELD_BOTTOM_PX - PLAYFIELD_TOP_PX;
+        let table = TableDimensions::of(table_spec);
+        let rail = (RAIL_WIDTH_IN * playfield_width / table.playfield_width_in).round();
+        Self {
+            width_px: playfield_width + 2.0 * rail,
+            height_px: playfield_height + 2.0 * rail,
+            playfield_left_px: rail,
+            playfield_right_px: rail + playfield_width,
+            playfield_top_px: rail,
+            playfield_bottom_px: rail + playfield_height,
+        }
+    }
+
+    /// The viewport of the table image in a custom assets directory; see
+    /// [`assets::AssetPack`].
+    pub fn from_assets_dir(dir: &Path) -> Result<Self, AssetError> {
+        let calibration = TableCalibration::load(dir)?;
+        let path = dir.join(assets::TABLE_FILE);
+        let (width, height) = image::image_dimensions(&path).map_err(|error| AssetError {
+            path,
+            message: error.to_string(),
+        })?;
+        Ok(Self::calibrated(width, height, &calibration))
+    }
+
+    /// The viewport of the table image in an already loaded asset pack.
+    pub fn for_assets(assets: &AssetPack) -> Self {
+        Self::calibrated(
+            assets.table.width(),
+            assets.table.height(),
+            &assets.calibration,
+        )
+    }
+
     pub fn position_to_scene_point(&self, position: &Position) -> ScenePoint {
         let x_diamond = position
             .x
@@ -135,17 +218,85 @@ impl DiagramViewport {
         }
     }
 
+    /// The table position drawn at `point`, the inverse of
+    /// [`DiagramViewport::position_to_scene_point`], to a ten-thousandth of a diamond, finer than
+    /// a pixel. Points off the playfield give positions outside 0–4 by 0–8 diamonds.
+    pub fn scene_point_to_position(&self, point: ScenePoint) -> Position {
+        let x_diamond = (point.x - self.playfield_left_px)
+            / (self.playfield_right_px - self.playfield_left_px)
+            * TABLE_DIAMONDS_X;
+        let y_diamond = (self.playfield_bottom_px - point.y)
+            / (self.playfield_bottom_px - self.playfield_top_px)
+            * TABLE_DIAMONDS_Y;
+        let diamond = |value: f32| Diamond {
+            magnitude: BigDecimal::from_f32(value)
+                .expect("scene points should be finite")
+                .round(4)
+                .normalized(),
+        };
+        Position::new(diamond(x_diamond), diamond(y_diamond))
+    }
+
+    /// The pixel rectangle `region` covers, as `(left, top, width, height)`, clipped to the
+    /// diagram.
+    pub fn region_px(&self, region: &DiagramRegion) -> (u32, u32, u32, u32) {
+        let a = self.position_to_scene_point(&region.corner);
+        let b = self.position_to_scene_point(&region.opposite_corner);
+        let clip = |value: f32, max: f32| value.round().clamp(0.0, max) as u32;
+        let (left, right) = (
+            clip(a.x.min(b.x), self.width_px),
+            clip(a.x.max(b.x), self.width_px),
+        );
+        let (top, bottom) = (
+            clip(a.y.min(b.y), self.height_px),
+            clip(a.y.max(b.y), self.height_px),
+        );
+        (left, top, (right - left).max(1), (bottom - top).max(1))
+    }
+
+    /// The size of the full diagram in `orientation`.
+    pub fn oriented_size_px(&self, orientation: DiagramOrientation) -> (u32, u32) {
+        let (width, height) = (self.width_px.round() as u32, self.height_px.round() as u32);
+        match orientation {
+            DiagramOrientation::Portrait => (width, height),
+            DiagramOrientation::Landscape => (height, width),
+        }
+    }
+
+    /// [`Self::region_px`] after turning the diagram to `orientation`.
+    pub fn oriented_region_px(
+        &self,
+        region: &DiagramRegion,
+        orientation: DiagramOrientation,
+    ) -> (u32, u32, u32, u32) {
+        let (left, top, width, height) = self.region_px(region);
+        match orientation {
+            DiagramOrientation::Portrait => (left, top, width, height),
+            DiagramOrientation::Landscape => (
+                (self.height_px.round() as u32).saturating_sub(top + height),
+                left,
+                height,
+                width,
+            ),
+        }
+    }
+
     pub fn ball_radius_px(&self, table_spec: &TableSpec, ball_spec: &BallSpec) -> f32 {
-        let radius_diamonds = table_spec
-            .inches_to_diamond(ball_spec.radius.clone())
+        self.length_px(table_spec, &ball_spec.radius)
+    }
+
+    /// How many pixels `length` spans on the playfield, along its shorter scale.
+    pub fn length_px(&self, table_spec: &TableSpec, length: &Inches) -> f32 {
+        let diamonds = table_spec
+            .inches_to_diamond(length.clone())
             .magnitude
             .to_f32()
-            .expect("ball radius diamond value should fit in f32");
+            .expect("diagram length diamond value should fit in f32");
         let px_per_diamond_x =
             (self.playfield_right_px - self.playfield_left_px) / TABLE_DIAMONDS_X;
         let px_per_diamond_y =
             (self.playfield_bottom_px - self.playfield_top_px) / TABLE_DIAMONDS_Y;
-        radius_diamonds * px_per_diamond_x.min(px_per_diamond_y)
+        diamonds * px_per_diamond_x.min(px_per_diamond_y)
     }
 
     fn ball_diameter_px(&self, table_spec: &TableSpec, ball_spec: &BallSpec) -> u32 {
@@ -160,9 +311,13 @@ pub struct DiagramBall {
     pub ty: BallType,
     pub position: Position,
     pub spec: BallSpec,
+    /// From 0 (invisible) to 1 (solid); below 1 for a ball dimmed to bring out others.
+    pub opacity: f32,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
 pub enum DiagramElement {
     DashedLine {
         start: Position,
@@ -175,8 +330,23 @@ pub enum DiagramElement {
     },
     GhostBall {
         center: Position,
+        spec: BallSpec,
         style: GhostBallStyle,
     },
+    /// A see-through ball, drawn with the balls and beneath them.
+    PhantomBall {
+        ty: BallType,
+        center: Position,
+        spec: BallSpec,
+        style: PhantomBallStyle,
+    },
+    /// A halo beneath a ball or an outline over it, centered where the ball was highlighted.
+    BallHighlight {
+        ty: BallType,
+        center: Position,
+        spec: BallSpec,
+        style: BallHighlightStyle,
+    },
     CircleMarker {
         center: Position,
         style: EventMarkerStyle,
@@ -186,6 +356,36 @@ pub enum DiagramElement {
         text: String,
         style: LabelOverlayStyle,
     },
+    SequenceMarker {
+        center: Position,
+        number: u32,
+        style: SequenceMarkerStyle,
+    },
+    AngleArc {
+        vertex: Position,
+        start: Position,
+        end: Position,
+        label: String,
+        style: AngleArcStyle,
+    },
+    PocketHighlight {
+        pocket: Pocket,
+        center: Position,
+        radius: Inches,
+        style: PocketHighlightStyle,
+    },
+    ShadedZone {
+        outline: Vec<Position>,
+        style: ShadedZoneStyle,
+    },
+    Legend {
+        anchor: Position,
+        entries: Vec<LegendEntry>,
+        style: LegendStyle,
+    },
+    DiamondGrid {
+        style: DiamondGridStyle,
+    },
 }
 
 impl DiagramElement {
@@ -194,8 +394,19 @@ impl DiagramElement {
             Self::DashedLine { style, .. } => style.layer.into(),
             Self::SmoothPolyline { style, .. } => style.layer.into(),
             Self::GhostBall { style, .. } => style.layer.into(),
+            Self::PhantomBall { .. } => DiagramLayerId::Balls,
+            Self::BallHighlight { style, .. } => match style.kind {
+                BallHighlightKind::Halo => DiagramLayerId::OverlaysBelowBalls,
+                BallHighlightKind::Outline => DiagramLayerId::OverlaysAboveBalls,
+            },
             Self::CircleMarker { style, .. } => style.layer.into(),
             Self::TextLabel { style, .. } => style.layer.into(),
+            Self::SequenceMarker { style, .. } => style.layer.into(),
+            Self::AngleArc { style, .. } => style.layer.into(),
+            Self::PocketHighlight { style, .. } => style.layer.into(),
+            Self::ShadedZone { style, .. } => style.layer.into(),
+            Self::Legend { style, .. } => style.layer.into(),
+            Self::DiamondGrid { style } => style.layer.into(),
         }
     }
 }
@@ -232,76 +443,534 @@ impl DiagramBackend for PngBackend {
     type Output = Vec<u8>;
 
     fn render(scene: &DiagramScene, options: &DiagramRenderOptions) -> Self::Output {
-        let table_asset: RgbaImage =
-            image::load_from_memory_with_format(assets::TABLE_DIAGRAM, ImageFormat::Png)
-                .expect("broken table asset")
-                .into_rgba8();
-        let (tw, th) = table_asset.dimensions();
-        let mut table = match scene.background {
-            DiagramBackground::Table => table_asset,
-            DiagramBackground::Transparent => RgbaImage::new(tw, th),
-        };
+        encode_png(
+            &render_scene_to_image(scene, options),
+            options.effective_dpi(),
+        )
+    }
+}
 
-        draw_raster_elements_for_layer(scene, DiagramLayerId::OverlaysBelowBalls, &mut table);
-        draw_raster_balls(scene, &mut table, tw, th);
-        draw_raster_elements_for_layer(scene, DiagramLayerId::OverlaysAboveBalls, &mut table);
+/// Encode `image` as a PNG, recording `dpi` in its pHYs chunk if given.
+pub(crate) fn encode_png(image: &RgbaImage, dpi: Option<f64>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut buf, image.width(), image.height());
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_pixel_dims(dpi.map(|dpi| {
+            // pHYs counts pixels per meter; an inch is 0.0254 m.
+            let per_meter = (dpi / 0.0254).round() as u32;
+            png::PixelDimensions {
+                xppu: per_meter,
+                yppu: per_meter,
+                unit: png::Unit::Meter,
+            }
+        }));
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(image.as_raw()))
+            .expect("PNG encode failed");
+    }
+    buf
+}
 
-        let scale_factor = options.scale_factor.max(1);
-        let output = if scale_factor == 1 {
-            table
-        } else {
-            resize(
-                &table,
-                tw * scale_factor,
-                th * scale_factor,
-                FilterType::CatmullRom,
-            )
-        };
+/// Lossy JPEG at [`DiagramRenderOptions::jpeg_quality`]. JPEG has no alpha, so transparent
+/// areas are flattened onto white.
+pub struct JpegBackend;
+
+impl DiagramBackend for JpegBackend {
+    type Output = Vec<u8>;
+
+    fn render(scene: &DiagramScene, options: &DiagramRenderOptions) -> Self::Output {
+        let output = render_scene_to_image(scene, options);
+        let mut flattened =
+            RgbaImage::from_pixel(output.width(), output.height(), Rgba([255, 255, 255, 255]));
+        overlay(&mut flattened, &output, 0, 0);
+        let rgb = image::DynamicImage::ImageRgba8(flattened).into_rgb8();
+        let (ow, oh) = rgb.dimensions();
+
+        let mut buf = Vec::new();
+        let mut encoder =
+            JpegEncoder::new_with_quality(&mut buf, options.jpeg_quality.clamp(1, 100));
+        if let Some(dpi) = options.effective_dpi() {
+            encoder.set_pixel_density(PixelDensity::dpi(dpi.round().min(u16::MAX.into()) as u16));
+        }
+        encoder
+            .write_image(&rgb, ow, oh, image::ColorType::Rgb8.into())
+            .expect("JPEG encode failed");
+        buf
+    }
+}
+
+/// Lossless WebP, keeping transparency.
+pub struct WebPBackend;
+
+impl DiagramBackend for WebPBackend {
+    type Output = Vec<u8>;
+
+    fn render(scene: &DiagramScene, options: &DiagramRenderOptions) -> Self::Output {
+        let output = render_scene_to_image(scene, options);
         let (ow, oh) = output.dimensions();
 
         let mut buf = Vec::new();
-        PngEncoder::new(&mut buf)
+        WebPEncoder::new_lossless(&mut buf)
             .write_image(&output, ow, oh, image::ColorType::Rgba8.into())
-            .expect("PNG encode failed");
+            .expect("WebP encode failed");
         buf
     }
 }
 
+/// Draw `scene` to an RGBA image, with the cropping, scaling and margin `options` ask for.
+pub fn render_scene_to_image(scene: &DiagramScene, options: &DiagramRenderOptions) -> RgbaImage {
+    let assets = options.assets.as_deref();
+    let mut table = match scene.background {
+        DiagramBackground::Table => match assets {
+            Some(assets) => {
+                let mut table = assets.table.clone();
+                if options.table_theme != TableTheme::classic() {
+                    assets::recolor_table(&mut table, &options.table_theme);
+                }
+                table
+            }
+            None => builtin_table_image(&options.table_theme),
+        },
+        DiagramBackground::Transparent => RgbaImage::new(
+            scene.viewport.width_px.round() as u32,
+            scene.viewport.height_px.round() as u32,
+        ),
+        DiagramBackground::Procedural => draw_raster_table(
+            scene.viewport,
+            TableDimensions::of(&scene.table_spec),
+            &options.table_theme,
+        ),
+    };
+    let (tw, th) = table.dimensions();
+
+    draw_raster_layer(scene, DiagramLayerId::OverlaysBelowBalls, &mut table);
+    let table = match options.orientation {
+        DiagramOrientation::Portrait => {
+            draw_raster_balls(scene, &mut table, options, assets);
+            draw_raster_layer(scene, DiagramLayerId::OverlaysAboveBalls, &mut table);
+            table
+        }
+        DiagramOrientation::Landscape => {
+            // Turn the drawing but not the ball sprites, so their numbers stay upright.
+            let mut table = rotate90(&table);
+            draw_raster_balls(scene, &mut table, options, assets);
+            let mut above_balls = RgbaImage::new(tw, th);
+            draw_raster_layer(scene, DiagramLayerId::OverlaysAboveBalls, &mut above_balls);
+            overlay(&mut table, &rotate90(&above_balls), 0, 0);
+            table
+        }
+    };
+
+    let (table, native_px) = match &options.crop {
+        Some(region) => {
+            let (left, top, width, height) = scene
+                .viewport
+                .oriented_region_px(region, options.orientation);
+            (
+                crop_imm(&table, left, top, width, height).to_image(),
+                zoomed_size_px(table.width(), (width, height)),
+            )
+        }
+        None => {
+            let size = table.dimensions();
+            (table, size)
+        }
+    };
+    let (ow, oh) = options.output_size_px(native_px);
+    let output = if (ow, oh) == table.dimensions() {
+        table
+    } else {
+        resize(&table, ow, oh, FilterType::CatmullRom)
+    };
+    let mut output = if options.canvas_color[3] == 0 && options.margin_px == 0 {
+        output
+    } else {
+        let margin = options.margin_px;
+        let mut canvas =
+            RgbaImage::from_pixel(ow + 2 * margin, oh + 2 * margin, options.canvas_color);
+        overlay(&mut canvas, &output, margin as i64, margin as i64);
+        canvas
+    };
+    if let Some(watermark) = &options.watermark {
+        let mut mark = watermark_image(watermark);
+        fade(&mut mark, watermark.opacity);
+        let (x, y) = watermark_origin(
+            watermark.corner,
+            watermark.inset_px as f32,
+            (output.width() as f32, output.height() as f32),
+            (mark.width() as f32, mark.height() as f32),
+        );
+        overlay(&mut output, &mark, x.round() as i64, y.round() as i64);
+    }
+    output
+}
+
+/// The pixels of `watermark` at full opacity.
+fn watermark_image(watermark: &Watermark) -> RgbaImage {
+    match &watermark.content {
+        WatermarkContent::Image(logo) => logo.clone(),
+        WatermarkContent::Text {
+            text,
+            color,
+            scale_px,
+        } => {
+            let mut image = RgbaImage::new(drawing::text_width_px(text, *scale_px), 7 * scale_px);
+            drawing::draw_text_at_px_mut(&mut image, 0, 0, text, *scale_px, *color);
+            image
+        }
+    }
+}
+
+/// Where the top-left corner of a watermark `size` across and down goes on a `canvas` of that
+/// many units, in `corner` and `inset` from both edges.
+fn watermark_origin(
+    corner: DiagramCorner,
+    inset: f32,
+    canvas: (f32, f32),
+    size: (f32, f32),
+) -> (f32, f32) {
+    let x = match corner {
+        DiagramCorner::TopLeft | DiagramCorner::BottomLeft => inset,
+        DiagramCorner::TopRight | DiagramCorner::BottomRight => canvas.0 - size.0 - inset,
+    };
+    let y = match corner {
+        DiagramCorner::TopLeft | DiagramCorner::TopRight => inset,
+        DiagramCorner::BottomLeft | DiagramCorner::BottomRight => canvas.1 - size.1 - inset,
+    };
+    (x, y)
+}
+
+/// One diagram on a US Letter page; see [`crate::pdf`] for other pages and multi-diagram
+/// sheets.
+pub struct PdfBackend;
+
+impl DiagramBackend for PdfBackend {
+    type Output = Vec<u8>;
+
+    fn render(scene: &DiagramScene, options: &DiagramRenderOptions) -> Self::Output {
+        pdf::diagrams_to_pdf(
+            std::slice::from_ref(scene),
+            options,
+            &pdf::PdfPageLayout::default(),
+        )
+    }
+}
+
+/// Plain text for terminals and logs: the table outline in box-drawing characters, balls as
+/// their numbers (the cue ball as `○`), lines as dots, and any legend listed underneath.
+pub struct TerminalBackend;
+
+/// Text cells per diamond. Terminal cells are about twice as tall as they are wide, so a diamond
+/// is twice as many cells across as down.
+const TEXT_COLUMNS_PER_DIAMOND: f64 = 6.0;
+const TEXT_ROWS_PER_DIAMOND: f64 = 3.0;
+
+impl DiagramBackend for TerminalBackend {
+    type Output = String;
+
+    fn render(scene: &DiagramScene, _options: &DiagramRenderOptions) -> Self::Output {
+        let mut canvas = TextCanvas::new();
+        canvas.draw_table();
+        canvas.draw_elements(scene, DiagramLayerId::OverlaysBelowBalls);
+        canvas.draw_elements(scene, DiagramLayerId::Balls);
+        for ball in &scene.balls {
+            let glyph = ball
+                .ty
+                .number()
+                .map_or_else(|| "○".to_string(), |number| number.to_string());
+            canvas.put_str(&ball.position, &glyph);
+        }
+        canvas.draw_elements(scene, DiagramLayerId::OverlaysAboveBalls);
+
+        let mut text = canvas.to_string();
+        for element in &scene.elements {
+            if let DiagramElement::Legend { entries, .. } = element {
+                for entry in entries {
+                    let swatch = match entry.swatch {
+                        LegendSwatch::Solid(_) => "───",
+                        LegendSwatch::Dashed(_) => "┄┄┄",
+                        LegendSwatch::Fill(_) => "░░░",
+                    };
+                    text.push_str(&format!("{swatch} {}\n", entry.text));
+                }
+            }
+        }
+        text
+    }
+}
+
+struct TextCanvas {
+    cells: Vec<Vec<char>>,
+}
+
+impl TextCanvas {
+    fn new() -> Self {
+        let columns = (TABLE_DIAMONDS_X as f64 * TEXT_COLUMNS_PER_DIAMOND) as usize + 3;
+        let rows = (TABLE_DIAMONDS_Y as f64 * TEXT_ROWS_PER_DIAMOND) as usize + 3;
+        Self {
+            cells: vec![vec![' '; columns]; rows],
+        }
+    }
+
+    /// The cell under `position`, counting the rails as the outermost row and column.
+    fn cell(&self, position: &Position) -> Option<(usize, usize)> {
+        let x = position.x.magnitude.to_f64()?;
+        let y = position.y.magnitude.to_f64()?;
+        let column = 1.0 + (x * TEXT_COLUMNS_PER_DIAMOND).round();
+        let row = 1.0 + ((TABLE_DIAMONDS_Y as f64 - y) * TEXT_ROWS_PER_DIAMOND).round();
+        let in_bounds = column >= 0.0
+            && row >= 0.0
+            && (row as usize) < self.cells.len()
+            && (column as usize) < self.cells[0].len();
+        in_bounds.then_some((row as usize, column as usize))
+    }
+
+    fn put(&mut self, (row, column): (usize, usize), glyph: char) {
+        if let Some(cell) = self
+            .cells
+            .get_mut(row)
+            .and_then(|cells| cells.get_mut(column))
+        {
+            *cell = glyph;
+        }
+    }
+
+    fn put_str(&mut self, position: &Position, text: &str) {
+        if let Some((row, column)) = self.cell(position) {
+            for (offset, glyph) in text.chars().enumerate() {
+                self.put((row, column + offset), glyph);
+            }
+        }
+    }
+
+    fn draw_table(&mut self) {
+        let last_row = self.cells.len() - 1;
+        let last_column = self.cells[0].len() - 1;
+        for column in 1..last_column {
+            self.put((0, column), '─');
+            self.put((last_row, column), '─');
+        }
+        for row in 1..last_row {
+            self.put((row, 0), '│');
+            self.put((row, last_column), '│');
+        }
+        let side_row = last_row / 2;
+        for cell in [
+            (0, 0),
+            (0, last_column),
+            (side_row, 0),
+            (side_row, last_column),
+            (last_row, 0),
+            (last_row, last_column),
+        ] {
+            self.put(cell, 'O');
+        }
+    }
+
+    fn draw_line(&mut self, start: &Position, end: &Position) {
+        let (Some(from), Some(to)) = (self.cell(start), self.cell(end)) else {
+            return;
+        };
+        let steps = from.0.abs_diff(to.0).max(from.1.abs_diff(to.1)).max(1);
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            let lerp = |a: usize, b: usize| (a as f64 + (b as f64 - a as f64) * t).round() as usize;
+            self.put((lerp(from.0, to.0), lerp(from.1, to.1)), '·');
+        }
+    }
+
+    fn draw_elements(&mut self, scene: &DiagramScene, layer: DiagramLayerId) {
+        for element in scene.elements_for_layer(layer) {
+            match element {
+                DiagramElement::DashedLine { start, end, .. } => self.draw_line(start, end),
+                DiagramElement::SmoothPolyline { points, .. } => {
+                    for segment in points.windows(2) {
+                        self.draw_line(&segment[0], &segment[1]);
+                    }
+                }
+                DiagramElement::GhostBall { center, .. }
+                | DiagramElement::PhantomBall { center, .. } => self.put_str(center, "◌"),
+                DiagramElement::CircleMarker { center, .. } => self.put_str(center, "•"),
+                DiagramElement::TextLabel { anchor, text, .. } => self.put_str(anchor, text),
+                DiagramElement::SequenceMarker { center, number, .. } => {
+                    self.put_str(center, &number.to_string())
+                }
+                DiagramElement::AngleArc { vertex, label, .. } => self.put_str(vertex, label),
+                DiagramElement::PocketHighlight { center, .. } => self.put_str(center, "◉"),
+                DiagramElement::ShadedZone { outline, .. } => self.shade(outline),
+                DiagramElement::DiamondGrid { .. } => {
+                    for (diamond, _, _, label) in drawing::diamond_grid_lines() {
+                        self.put_str(&label, &diamond.to_string());
+                    }
+                }
+                // A highlighted ball marks its own spot.
+                DiagramElement::BallHighlight { .. } | DiagramElement::Legend { .. } => {}
+            }
+        }
+    }
+
+    /// Mark the blank cells inside `outline` by the even-odd rule, counting cells on its edges.
+    fn shade(&mut self, outline: &[Position]) {
+        let corners: Vec<(f64, f64)> = outline
+            .iter()
+            .filter_map(|position| self.cell(position))
+            .map(|(row, column)| (column as f64, row as f64))
+            .collect();
+        if corners.len() < 3 {
+            return;
+        }
+
+        let inside = |x: f64, y: f64| {
+            let mut inside = false;
+            for (index, &(x1, y1)) in corners.iter().enumerate() {
+                let (x2, y2) = corners[(index + 1) % corners.len()];
+                if (y1 > y) != (y2 > y) && x < x1 + (y - y1) / (y2 - y1) * (x2 - x1) {
+                    inside = !inside;
+                }
+            }
+            inside
+        };
+        // Nudging the sample point each way catches cells lying exactly on an edge.
+        const NUDGE: f64 = 0.01;
+        for row in 1..self.cells.len() - 1 {
+            for column in 1..self.cells[row].len() - 1 {
+                let (x, y) = (column as f64, row as f64);
+                let on_or_inside = [
+                    (-NUDGE, -NUDGE),
+                    (-NUDGE, NUDGE),
+                    (NUDGE, -NUDGE),
+                    (NUDGE, NUDGE),
+                ]
+                .into_iter()
+                .any(|(dx, dy)| inside(x + dx, y + dy));
+                if on_or_inside && self.cells[row][column] == ' ' {
+                    self.cells[row][column] = '░';
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for TextCanvas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.cells {
+            writeln!(f, "{}", row.iter().collect::<String>().trim_end())?;
+        }
+        Ok(())
+    }
+}
+
 pub struct SvgBackend;
 
 impl DiagramBackend for SvgBackend {
     type Output = String;
 
-    fn render(scene: &DiagramScene, _options: &DiagramRenderOptions) -> Self::Output {
+    fn render(scene: &DiagramScene, options: &DiagramRenderOptions) -> Self::Output {
+        let full_px = scene.viewport.oriented_size_px(options.orientation);
+        let (view_box, native_px) = match &options.crop {
+            Some(region) => {
+                let view_box = scene
+                    .viewport
+                    .oriented_region_px(region, options.orientation);
+                (
+                    view_box,
+                    zoomed_size_px(full_px.0, (view_box.2, view_box.3)),
+                )
+            }
+            None => ((0, 0, full_px.0, full_px.1), full_px),
+        };
+        let (width, height) = options.output_size_px(native_px);
+        // The margin is in output pixels, so widen the view box by as many scene units.
+        let margin = options.margin_px as f32 * view_box.2 as f32 / width as f32;
+        let view_box = (
+            view_box.0 as f32 - margin,
+            view_box.1 as f32 - margin,
+            view_box.2 as f32 + 2.0 * margin,
+            view_box.3 as f32 + 2.0 * margin,
+        );
+        let size = (
+            width + 2 * options.margin_px,
+            height + 2 * options.margin_px,
+        );
+        let (svg_width, svg_height) = match options.effective_dpi() {
+            Some(dpi) => (
+                format!("{:.3}in", size.0 as f64 / dpi),
+                format!("{:.3}in", size.1 as f64 / dpi),
+            ),
+            None => (size.0.to_string(), size.1.to_string()),
+        };
         let mut svg = String::new();
         svg.push_str(&format!(
-            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {:.0} {:.0}\" width=\"{:.0}\" height=\"{:.0}\" role=\"img\" aria-label=\"Billiards diagram\" preserveAspectRatio=\"xMidYMid meet\">\n",
-            scene.viewport.width_px,
-            scene.viewport.height_px,
-            scene.viewport.width_px,
-            scene.viewport.height_px
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\" role=\"img\" aria-label=\"Billiards diagram\" preserveAspectRatio=\"xMidYMid meet\">\n",
+            view_box.0,
+            view_box.1,
+            view_box.2,
+            view_box.3,
+            svg_width,
+            svg_height
         ));
         svg.push_str("<style>\n");
         svg.push_str(".diagram-layer{vector-effect:non-scaling-stroke}\n");
         svg.push_str(".ball-label{font-family:Inter,Arial,sans-serif;font-weight:700;text-anchor:middle;dominant-baseline:central;pointer-events:none}\n");
         svg.push_str(".overlay-label{font-family:ui-monospace,SFMono-Regular,Menlo,Consolas,monospace;font-weight:700;dominant-baseline:central}\n");
         svg.push_str(".table-cloth{fill:url(#tournament-blue-cloth)}.table-cloth-texture{fill:url(#cloth-weave);opacity:.20}.table-rail{fill:url(#rosewood-rail)}.table-rail-grain{opacity:.62}.table-rail-grain-horizontal{fill:url(#rosewood-grain)}.table-rail-grain-vertical{fill:url(#rosewood-grain-vertical)}.table-rail-inner-shadow{fill:none;stroke:#210b08;stroke-width:10;opacity:.72}.table-cushion{fill:url(#blue-cushion)}.table-cushion-nose{stroke:#4bd2ea;stroke-width:3;stroke-linecap:round;opacity:.8}.table-cushion-back{stroke:#056a87;stroke-width:3;stroke-linecap:round;opacity:.65}.table-pocket{fill:#030202;stroke:#24211f;stroke-width:1.5}.table-pocket-facing{stroke:#1b120e;stroke-width:5;stroke-linecap:round}.table-diamond{fill:#f6f0de;stroke:#9b8c63;stroke-width:.75;opacity:.98}\n");
+        if options.table_theme != TableTheme::classic() {
+            let theme = &options.table_theme;
+            let fill = |color: Rgba<u8>| svg_color(color).0;
+            svg.push_str(&format!(
+                ".table-cloth{{fill:{}}}.table-cloth-texture{{opacity:.08}}.table-cushion{{fill:{}}}.table-rail{{fill:{}}}.table-pocket{{fill:{}}}\n",
+                fill(theme.cloth),
+                fill(theme.cushion),
+                fill(theme.rail),
+                fill(theme.ink)
+            ));
+        }
         svg.push_str("</style>\n");
         push_svg_table_defs(&mut svg);
+        if options.canvas_color[3] > 0 {
+            let (fill, opacity) = svg_color(options.canvas_color);
+            svg.push_str(&format!(
+                "<rect class=\"diagram-canvas\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"{:.3}\"/>\n",
+                view_box.0, view_box.1, view_box.2, view_box.3, fill, opacity
+            ));
+        }
 
+        if options.orientation == DiagramOrientation::Landscape {
+            svg.push_str(&format!(
+                "<g class=\"landscape\" transform=\"translate({:.3} 0) rotate(90)\">\n",
+                scene.viewport.height_px
+            ));
+        }
         svg.push_str(&format!(
             "<g class=\"diagram-layer\" id=\"layer-{}\" data-layer=\"{}\">\n",
             DiagramLayerId::Table.as_str(),
             DiagramLayerId::Table.as_str()
         ));
-        if scene.background == DiagramBackground::Table {
-            push_svg_table(&mut svg, scene.viewport);
+        match scene.background {
+            DiagramBackground::Table => {
+                push_svg_table(&mut svg, scene.viewport, TableDimensions::BUNDLED);
+            }
+            DiagramBackground::Procedural => push_svg_table(
+                &mut svg,
+                scene.viewport,
+                TableDimensions::of(&scene.table_spec),
+            ),
+            DiagramBackground::Transparent => {}
         }
         svg.push_str("</g>\n");
 
         push_svg_element_layer(&mut svg, scene, DiagramLayerId::OverlaysBelowBalls);
-        push_svg_balls(&mut svg, scene);
+        push_svg_balls(&mut svg, scene, options.orientation);
         push_svg_element_layer(&mut svg, scene, DiagramLayerId::OverlaysAboveBalls);
+        if options.orientation == DiagramOrientation::Landscape {
+            svg.push_str("</g>\n");
+        }
+        if let Some(watermark) = &options.watermark {
+            push_svg_watermark(&mut svg, watermark, view_box, view_box.2 / size.0 as f32);
+        }
 
         svg.push_str("</svg>\n");
         svg
@@ -358,9 +1027,69 @@ pub fn render_scene_to_bytes(
     match format {
         DiagramOutputFormat::Png => PngBackend::render(scene, options),
         DiagramOutputFormat::Svg => SvgBackend::render(scene, options).into_bytes(),
+        DiagramOutputFormat::Pdf => PdfBackend::render(scene, options),
+        DiagramOutputFormat::Jpeg => JpegBackend::render(scene, options),
+        DiagramOutputFormat::WebP => WebPBackend::render(scene, options),
+        DiagramOutputFormat::Text => TerminalBackend::render(scene, options).into_bytes(),
     }
 }
 
+/// Draw a PNG diagram of each of `states` with `options`, spread across all cores. The buffers
+/// come back in the order of `states`.
+pub fn render_batch(states: &[GameState], options: &DiagramRenderOptions) -> Vec<Vec<u8>> {
+    render_batch_with_format(states, DiagramOutputFormat::Png, options)
+}
+
+/// Like [`render_batch`], encoding each diagram as `format`.
+pub fn render_batch_with_format(
+    states: &[GameState],
+    format: DiagramOutputFormat,
+    options: &DiagramRenderOptions,
+) -> Vec<Vec<u8>> {
+    states
+        .par_iter()
+        .map(|state| state.render_2d_diagram_with_options(format, options))
+        .collect()
+}
+
+/// Draw one overlay layer onto `table`.
+///
+/// Overlays are sized for the built-in table image, so on a custom table they are drawn on a
+/// frame of the built-in size and stretched from its playing surface onto the custom one.
+fn draw_raster_layer(scene: &DiagramScene, layer: DiagramLayerId, table: &mut RgbaImage) {
+    let builtin = DiagramViewport::default();
+    if scene.viewport == builtin {
+        draw_raster_elements_for_layer(scene, layer, table);
+        return;
+    }
+
+    let builtin_scene = DiagramScene {
+        viewport: builtin,
+        ..scene.clone()
+    };
+    let mut overlays = RgbaImage::new(builtin.width_px as u32, builtin.height_px as u32);
+    draw_raster_elements_for_layer(&builtin_scene, layer, &mut overlays);
+
+    let custom = scene.viewport;
+    let projection = Projection::translate(custom.playfield_left_px, custom.playfield_top_px)
+        * Projection::scale(
+            (custom.playfield_right_px - custom.playfield_left_px)
+                / (builtin.playfield_right_px - builtin.playfield_left_px),
+            (custom.playfield_bottom_px - custom.playfield_top_px)
+                / (builtin.playfield_bottom_px - builtin.playfield_top_px),
+        )
+        * Projection::translate(-builtin.playfield_left_px, -builtin.playfield_top_px);
+    let mut stretched = RgbaImage::new(table.width(), table.height());
+    warp_into(
+        &overlays,
+        &projection,
+        Interpolation::Bilinear,
+        Rgba([0, 0, 0, 0]),
+        &mut stretched,
+    );
+    overlay(table, &stretched, 0, 0);
+}
+
 fn draw_raster_elements_for_layer(
     scene: &DiagramScene,
     layer: DiagramLayerId,
@@ -378,17 +1107,45 @@ fn draw_raster_elements_for_layer(
                     style.width_px,
                     style.color,
                 );
+                if let Some(arrow) = &style.arrow {
+                    for (from, tip) in arrowheads(arrow, (start, end), (start, end)) {
+                        drawing::draw_arrowhead_mut(
+                            table,
+                            from,
+                            tip,
+                            arrow.length_px,
+                            arrow.width_px,
+                            style.color,
+                        );
+                    }
+                }
             }
             DiagramElement::SmoothPolyline { points, style } => {
                 drawing::draw_smooth_polyline_mut(table, points, style.width_px, style.color);
+                if let (Some(arrow), [first, second, ..], [.., second_last, last]) =
+                    (&style.arrow, points.as_slice(), points.as_slice())
+                {
+                    for (from, tip) in arrowheads(arrow, (first, second), (second_last, last)) {
+                        drawing::draw_arrowhead_mut(
+                            table,
+                            from,
+                            tip,
+                            arrow.length_px,
+                            arrow.width_px,
+                            style.color,
+                        );
+                    }
+                }
             }
-            DiagramElement::GhostBall { center, style } => {
+            DiagramElement::GhostBall {
+                center,
+                spec,
+                style,
+            } => {
                 drawing::draw_ghost_ball_mut(
                     table,
                     center,
-                    scene
-                        .viewport
-                        .ball_diameter_px(&scene.table_spec, &BallSpec::default()),
+                    scene.viewport.ball_diameter_px(&scene.table_spec, spec),
                     style.fill_color,
                     style.outline_color,
                 );
@@ -396,6 +1153,32 @@ fn draw_raster_elements_for_layer(
             DiagramElement::CircleMarker { center, style } => {
                 drawing::draw_filled_circle_marker_mut(table, center, style.radius_px, style.color);
             }
+            DiagramElement::BallHighlight {
+                center,
+                spec,
+                style,
+                ..
+            } => {
+                let radius_px = scene.viewport.ball_radius_px(&scene.table_spec, spec);
+                match style.kind {
+                    // The ball covers the middle of the disc, leaving a band around it.
+                    BallHighlightKind::Halo => drawing::draw_filled_circle_marker_mut(
+                        table,
+                        center,
+                        radius_px + style.width_px,
+                        style.color,
+                    ),
+                    BallHighlightKind::Outline => drawing::draw_arc_mut(
+                        table,
+                        center,
+                        radius_px,
+                        0.0,
+                        std::f32::consts::TAU,
+                        style.width_px,
+                        style.color,
+                    ),
+                }
+            }
             DiagramElement::TextLabel {
                 anchor,
                 text,
@@ -411,39 +1194,552 @@ fn draw_raster_elements_for_layer(
                     style.color,
                 );
             }
+            DiagramElement::SequenceMarker {
+                center,
+                number,
+                style,
+            } => {
+                drawing::draw_circled_text_mut(
+                    table,
+                    center,
+                    &number.to_string(),
+                    style.radius_px,
+                    style.scale_px,
+                    style.fill_color,
+                    style.text_color,
+                );
+            }
+            DiagramElement::AngleArc {
+                vertex,
+                start,
+                end,
+                label,
+                style,
+            } => {
+                let arc = AngleArcGeometry::new(&scene.viewport, vertex, start, end, style);
+                drawing::draw_arc_mut(
+                    table,
+                    vertex,
+                    style.radius_px,
+                    arc.start_radians,
+                    arc.sweep_radians,
+                    style.width_px,
+                    style.color,
+                );
+                let scale = style.scale_px as i32;
+                let glyphs = label.chars().count() as i32;
+                drawing::draw_text_label_mut(
+                    table,
+                    vertex,
+                    label,
+                    (arc.label.x - arc.center.x).round() as i32 - (6 * glyphs - 1) * scale / 2,
+                    (arc.label.y - arc.center.y).round() as i32 - 7 * scale / 2,
+                    style.scale_px,
+                    style.color,
+                );
+            }
+            DiagramElement::PocketHighlight {
+                center,
+                radius,
+                style,
+                ..
+            } => {
+                let radius_px = scene.viewport.length_px(&scene.table_spec, radius);
+                drawing::draw_filled_circle_marker_mut(table, center, radius_px, style.fill_color);
+                drawing::draw_arc_mut(
+                    table,
+                    center,
+                    radius_px,
+                    0.0,
+                    std::f32::consts::TAU,
+                    style.outline_width_px,
+                    style.outline_color,
+                );
+            }
+            DiagramElement::ShadedZone { outline, style } => {
+                drawing::draw_filled_polygon_alpha_mut(table, outline, style.fill_color);
+                if let (Some(color), Some(first)) = (style.outline_color, outline.first()) {
+                    let mut closed = outline.clone();
+                    closed.push(first.clone());
+                    drawing::draw_smooth_polyline_mut(
+                        table,
+                        &closed,
+                        style.outline_width_px,
+                        color,
+                    );
+                }
+            }
+            DiagramElement::Legend {
+                anchor,
+                entries,
+                style,
+            } => {
+                drawing::draw_legend_mut(table, anchor, entries, style);
+            }
+            DiagramElement::DiamondGrid { style } => {
+                drawing::draw_diamond_grid_mut(table, style);
+            }
+            // Phantom balls are drawn with the balls, from their art.
+            DiagramElement::PhantomBall { .. } => {}
+        }
+    }
+}
+
+/// Where an angle arc sits in scene space: around `center`, from the start ray toward the end
+/// ray the short way round, with its label out along the bisector.
+struct AngleArcGeometry {
+    center: ScenePoint,
+    start_radians: f32,
+    sweep_radians: f32,
+    label: ScenePoint,
+}
+
+impl AngleArcGeometry {
+    fn new(
+        viewport: &DiagramViewport,
+        vertex: &Position,
+        start: &Position,
+        end: &Position,
+        style: &AngleArcStyle,
+    ) -> Self {
+        let center = viewport.position_to_scene_point(vertex);
+        let heading = |point: &Position| {
+            let point = viewport.position_to_scene_point(point);
+            (point.y - center.y).atan2(point.x - center.x)
+        };
+        let start_radians = heading(start);
+        let sweep_radians = (heading(end) - start_radians + std::f32::consts::PI)
+            .rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+        let bisector = start_radians + 0.5 * sweep_radians;
+        let label_distance = style.radius_px + 8.0 * style.scale_px.max(1) as f32;
+
+        Self {
+            center,
+            start_radians,
+            sweep_radians,
+            label: ScenePoint {
+                x: center.x + label_distance * bisector.cos(),
+                y: center.y + label_distance * bisector.sin(),
+            },
         }
     }
 }
 
-fn draw_raster_balls(scene: &DiagramScene, table: &mut RgbaImage, tw: u32, th: u32) {
+/// The size a cropped region of `(width, height)` pixels is drawn at before scaling: as wide as
+/// the full diagram.
+pub(crate) fn zoomed_size_px(full_width: u32, (width, height): (u32, u32)) -> (u32, u32) {
+    (
+        full_width,
+        ((height as f32 * full_width as f32 / width as f32).round() as u32).max(1),
+    )
+}
+
+fn draw_raster_balls(
+    scene: &DiagramScene,
+    table: &mut RgbaImage,
+    options: &DiagramRenderOptions,
+    assets: Option<&AssetPack>,
+) {
+    for element in scene.elements_for_layer(DiagramLayerId::Balls) {
+        if let DiagramElement::PhantomBall {
+            ty,
+            center,
+            spec,
+            style,
+        } = element
+        {
+            let mut ball_img = raster_ball_image(scene, ty, spec, options, assets);
+            fade(&mut ball_img, style.opacity);
+            place_raster_ball(scene, table, options, center, &ball_img);
+        }
+    }
     for ball in &scene.balls {
-        let ball_png = assets::ball_img(ball.ty.clone());
-        let mut ball_img: RgbaImage =
-            image::load_from_memory_with_format(&ball_png, ImageFormat::Png)
-                .expect("bad ball image")
-                .into_rgba8();
-        let ball_diameter_px = scene
-            .viewport
-            .ball_diameter_px(&scene.table_spec, &ball.spec);
-        ball_img = resize(
-            &ball_img,
+        let mut ball_img = raster_ball_image(scene, &ball.ty, &ball.spec, options, assets);
+        if ball.opacity < 1.0 {
+            fade(&mut ball_img, ball.opacity);
+        }
+        place_raster_ball(scene, table, options, &ball.position, &ball_img);
+    }
+}
+
+/// Scale the alpha of every pixel of `image` by `opacity`, from 0 to 1.
+fn fade(image: &mut RgbaImage, opacity: f32) {
+    for pixel in image.pixels_mut() {
+        pixel[3] = (pixel[3] as f32 * opacity.clamp(0.0, 1.0)).round() as u8;
+    }
+}
+
+/// The most drawings of the built-in art kept by [`builtin_table_image`] and
+/// [`raster_ball_image`] before they start over, so batch renders at a handful of sizes and
+/// themes never decode or resize the same art twice.
+const CACHED_ART_LIMIT: usize = 256;
+
+lazy_static! {
+    static ref THEMED_TABLES: Mutex<Vec<(TableTheme, RgbaImage)>> = Mutex::new(Vec::new());
+    static ref SIZED_BALLS: Mutex<HashMap<(BallType, u32, BallStyle), RgbaImage>> =
+        Mutex::new(HashMap::new());
+}
+
+/// The built-in table image in `theme`'s colors.
+fn builtin_table_image(theme: &TableTheme) -> RgbaImage {
+    if *theme == TableTheme::classic() {
+        return assets::table_image().clone();
+    }
+    let cached = THEMED_TABLES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|(cached, _)| cached == theme)
+        .map(|(_, table)| table.clone());
+    if let Some(table) = cached {
+        return table;
+    }
+    let mut table = assets::table_image().clone();
+    assets::recolor_table(&mut table, theme);
+    let mut themed = THEMED_TABLES.lock().unwrap_or_else(PoisonError::into_inner);
+    if themed.len() >= CACHED_ART_LIMIT {
+        themed.clear();
+    }
+    if !themed.iter().any(|(cached, _)| cached == theme) {
+        themed.push((*theme, table.clone()));
+    }
+    table
+}
+
+/// The art for one ball at its size on the diagram.
+fn raster_ball_image(
+    scene: &DiagramScene,
+    ty: &BallType,
+    spec: &BallSpec,
+    options: &DiagramRenderOptions,
+    assets: Option<&AssetPack>,
+) -> RgbaImage {
+    let ball_diameter_px = scene.viewport.ball_diameter_px(&scene.table_spec, spec);
+    if assets.is_some() {
+        return draw_ball_image(ty, ball_diameter_px, options.ball_style, assets);
+    }
+
+    let key = (ty.clone(), ball_diameter_px, options.ball_style);
+    let cached = SIZED_BALLS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&key)
+        .cloned();
+    if let Some(ball_img) = cached {
+        return ball_img;
+    }
+    // Draw without holding the lock, so other threads of a batch render aren't kept waiting.
+    let ball_img = draw_ball_image(ty, ball_diameter_px, options.ball_style, None);
+    let mut sized = SIZED_BALLS.lock().unwrap_or_else(PoisonError::into_inner);
+    if sized.len() >= CACHED_ART_LIMIT {
+        sized.clear();
+    }
+    sized.insert(key, ball_img.clone());
+    ball_img
+}
+
+/// Resize `ty`'s sprite to `ball_diameter_px`, or paint the ball if it has none or `style` asks.
+fn draw_ball_image(
+    ty: &BallType,
+    ball_diameter_px: u32,
+    style: BallStyle,
+    assets: Option<&AssetPack>,
+) -> RgbaImage {
+    let sprite = match style {
+        BallStyle::Sprites => match assets {
+            Some(assets) => assets.ball_img(ty),
+            None => assets::ball_sprite(ty).cloned(),
+        },
+        BallStyle::Procedural => None,
+    };
+    match sprite {
+        Some(sprite) => resize(
+            &sprite,
             ball_diameter_px,
             ball_diameter_px,
             FilterType::CatmullRom,
+        ),
+        None => {
+            let visual = ball_visual(ty);
+            drawing::draw_ball_sprite(
+                ball_diameter_px,
+                visual.color,
+                visual.striped,
+                ty.number().map(|number| number.to_string()).as_deref(),
+            )
+        }
+    }
+}
+
+/// Lay `ball_img` over `table` centered on `position`, kept upright on a landscape table.
+fn place_raster_ball(
+    scene: &DiagramScene,
+    table: &mut RgbaImage,
+    options: &DiagramRenderOptions,
+    position: &Position,
+    ball_img: &RgbaImage,
+) {
+    let (tw, th) = table.dimensions();
+    let (bw, bh) = ball_img.dimensions();
+    let center = scene.viewport.position_to_scene_point(position);
+    let (px, py) = match options.orientation {
+        DiagramOrientation::Portrait => (center.x.round() as i32, center.y.round() as i32),
+        DiagramOrientation::Landscape => (
+            tw as i32 - 1 - center.y.round() as i32,
+            center.x.round() as i32,
+        ),
+    };
+    let mut px_shifted = px - (bw as i32 / 2);
+    let mut py_shifted = py - (bh as i32 / 2);
+    px_shifted = px_shifted.clamp(0, (tw - bw) as i32);
+    py_shifted = py_shifted.clamp(0, (th - bh) as i32);
+    overlay(table, ball_img, px_shifted.into(), py_shifted.into());
+}
+
+/// Paint the table in `theme`'s flat colors, in the style of the bundled art. It is painted at
+/// twice the size and shrunk, to smooth its edges.
+fn draw_raster_table(
+    viewport: DiagramViewport,
+    table: TableDimensions,
+    theme: &TableTheme,
+) -> RgbaImage {
+    const SUPERSAMPLE: f32 = 2.0;
+    let x_in = |inches: f32| viewport.x_inches(table, inches) * SUPERSAMPLE;
+    let y_in = |inches: f32| viewport.y_inches(table, inches) * SUPERSAMPLE;
+    let w = viewport.width_px * SUPERSAMPLE;
+    let h = viewport.height_px * SUPERSAMPLE;
+    let left = viewport.playfield_left_px * SUPERSAMPLE;
+    let right = viewport.playfield_right_px * SUPERSAMPLE;
+    let top = viewport.playfield_top_px * SUPERSAMPLE;
+    let bottom = viewport.playfield_bottom_px * SUPERSAMPLE;
+    let center_y = (top + bottom) * 0.5;
+    let line = 1.5 * SUPERSAMPLE;
+    let mut image = RgbaImage::new(w.round() as u32, h.round() as u32);
+
+    // Rails, outlined, with the corner castings in their own color.
+    let radius = x_in(RAIL_CORNER_RADIUS_IN);
+    fill_rounded_rect(&mut image, (0.0, 0.0, w, h), radius, theme.ink);
+    fill_rounded_rect(
+        &mut image,
+        (line, line, w - line, h - line),
+        radius - line,
+        theme.corner_rail,
+    );
+    let (casting_x, casting_y) = (x_in(CORNER_CASTING_IN), y_in(CORNER_CASTING_IN));
+    fill_rect(
+        &mut image,
+        (casting_x, line, w - casting_x, h - line),
+        theme.ink,
+    );
+    fill_rect(
+        &mut image,
+        (line, casting_y, w - line, h - casting_y),
+        theme.ink,
+    );
+    let (inner_x, inner_y) = (casting_x + line, casting_y + line);
+    fill_rect(
+        &mut image,
+        (inner_x, line, w - inner_x, h - line),
+        theme.rail,
+    );
+    fill_rect(
+        &mut image,
+        (line, inner_y, w - line, h - inner_y),
+        theme.rail,
+    );
+
+    // Cushions, beveled back into the rails at the pocket mouths.
+    let cushion_x = x_in(CUSHION_WIDTH_IN);
+    let cushion_y = y_in(CUSHION_WIDTH_IN);
+    let bevel_x = x_in(CUSHION_BEVEL_IN);
+    let bevel_y = y_in(CUSHION_BEVEL_IN);
+    let corner_run_x = x_in(table.corner_mouth_in / 2.0_f32.sqrt());
+    let corner_run_y = y_in(table.corner_mouth_in / 2.0_f32.sqrt());
+    let side_half_mouth = y_in(table.side_mouth_in) * 0.5;
+    let mut cushions = Vec::new();
+    for (nose_y, back_y) in [(top, top - cushion_y), (bottom, bottom + cushion_y)] {
+        let (start, end) = (left + corner_run_x, right - corner_run_x);
+        cushions.push([
+            (start, nose_y),
+            (end, nose_y),
+            (end + bevel_x, back_y),
+            (start - bevel_x, back_y),
+        ]);
+    }
+    for (nose_x, back_x) in [(left, left - cushion_x), (right, right + cushion_x)] {
+        for (start, end) in [
+            (top + corner_run_y, center_y - side_half_mouth),
+            (center_y + side_half_mouth, bottom - corner_run_y),
+        ] {
+            cushions.push([
+                (nose_x, start),
+                (nose_x, end),
+                (back_x, end + bevel_y),
+                (back_x, start - bevel_y),
+            ]);
+        }
+    }
+    for cushion in &cushions {
+        fill_polygon(&mut image, cushion, theme.cushion);
+    }
+    fill_rect(&mut image, (left, top, right, bottom), theme.cloth);
+    for cushion in &cushions {
+        for (index, &start) in cushion.iter().enumerate() {
+            stroke_segment(&mut image, start, cushion[(index + 1) % 4], line, theme.ink);
+        }
+    }
+
+    // Diamond lines across the cloth and the foot spot, as the bundled art has.
+    for step in 1..4 {
+        let x = left + (right - left) * step as f32 / TABLE_DIAMONDS_X;
+        stroke_segment(&mut image, (x, top), (x, bottom), line, theme.ink);
+    }
+    for step in 1..8 {
+        let y = top + (bottom - top) * step as f32 / TABLE_DIAMONDS_Y;
+        stroke_segment(&mut image, (left, y), (right, y), line, theme.ink);
+    }
+    let foot_spot = ((left + right) * 0.5, bottom - (bottom - top) * 0.25);
+    fill_circle(&mut image, foot_spot, 4.0 * line, theme.ink);
+    fill_circle(&mut image, foot_spot, 2.5 * line, theme.cloth);
+
+    if table.corner_mouth_in > 0.0 {
+        let offset = table.corner_mouth_in * 0.13;
+        let radius = x_in(table.corner_mouth_in * 0.55);
+        for (corner_x, corner_y, x_sign, y_sign) in [
+            (left, top, -1.0, -1.0),
+            (right, top, 1.0, -1.0),
+            (left, bottom, -1.0, 1.0),
+            (right, bottom, 1.0, 1.0),
+        ] {
+            let center = (
+                corner_x + x_sign * x_in(offset),
+                corner_y + y_sign * y_in(offset),
+            );
+            fill_circle(&mut image, center, radius, theme.ink);
+        }
+    }
+    if table.side_mouth_in > 0.0 {
+        let offset = x_in(table.side_mouth_in * 0.49);
+        let radius = y_in(table.side_mouth_in * 0.47);
+        fill_circle(&mut image, (left - offset, center_y), radius, theme.ink);
+        fill_circle(&mut image, (right + offset, center_y), radius, theme.ink);
+    }
+
+    let setback_x = x_in(DIAMOND_SIGHT_SETBACK_IN);
+    let setback_y = y_in(DIAMOND_SIGHT_SETBACK_IN);
+    let half_along_x = x_in(DIAMOND_SIGHT_WIDTH_IN) * 0.5;
+    let half_along_y = y_in(DIAMOND_SIGHT_WIDTH_IN) * 0.5;
+    let half_cross_x = x_in(DIAMOND_SIGHT_HEIGHT_IN) * 0.5;
+    let half_cross_y = y_in(DIAMOND_SIGHT_HEIGHT_IN) * 0.5;
+    let sight = |image: &mut RgbaImage, (x, y): (f32, f32), (half_x, half_y): (f32, f32)| {
+        fill_polygon(
+            image,
+            &[
+                (x, y - half_y),
+                (x + half_x, y),
+                (x, y + half_y),
+                (x - half_x, y),
+            ],
+            theme.ink,
+        );
+    };
+    for step in 1..4 {
+        let x = left + (right - left) * step as f32 / TABLE_DIAMONDS_X;
+        for y in [top - setback_y, bottom + setback_y] {
+            sight(&mut image, (x, y), (half_along_x, half_cross_y));
+        }
+    }
+    for step in [1, 2, 3, 5, 6, 7] {
+        let y = top + (bottom - top) * step as f32 / TABLE_DIAMONDS_Y;
+        for x in [left - setback_x, right + setback_x] {
+            sight(&mut image, (x, y), (half_cross_x, half_along_y));
+        }
+    }
+
+    resize(
+        &image,
+        viewport.width_px.round() as u32,
+        viewport.height_px.round() as u32,
+        FilterType::Triangle,
+    )
+}
+
+fn fill_rect(image: &mut RgbaImage, (x0, y0, x1, y1): (f32, f32, f32, f32), color: Rgba<u8>) {
+    let (x0, y0) = (x0.round() as i32, y0.round() as i32);
+    let (x1, y1) = (x1.round() as i32, y1.round() as i32);
+    if x1 > x0 && y1 > y0 {
+        draw_filled_rect_mut(
+            image,
+            Rect::at(x0, y0).of_size((x1 - x0) as u32, (y1 - y0) as u32),
+            color,
         );
-        let (bw, bh) = ball_img.dimensions();
-        let center = scene.viewport.position_to_scene_point(&ball.position);
-        let px = center.x.round() as i32;
-        let py = center.y.round() as i32;
-        let mut px_shifted = px - (bw as i32 / 2);
-        let mut py_shifted = py - (bh as i32 / 2);
-        px_shifted = px_shifted.clamp(0, (tw - bw) as i32);
-        py_shifted = py_shifted.clamp(0, (th - bh) as i32);
-        overlay(&mut *table, &ball_img, px_shifted.into(), py_shifted.into());
     }
 }
 
-fn push_svg_table(svg: &mut String, viewport: DiagramViewport) {
+fn fill_rounded_rect(
+    image: &mut RgbaImage,
+    (x0, y0, x1, y1): (f32, f32, f32, f32),
+    radius: f32,
+    color: Rgba<u8>,
+) {
+    fill_rect(image, (x0 + radius, y0, x1 - radius, y1), color);
+    fill_rect(image, (x0, y0 + radius, x1, y1 - radius), color);
+    for center in [
+        (x0 + radius, y0 + radius),
+        (x1 - radius, y0 + radius),
+        (x0 + radius, y1 - radius),
+        (x1 - radius, y1 - radius),
+    ] {
+        fill_circle(image, center, radius, color);
+    }
+}
+
+fn fill_circle(image: &mut RgbaImage, (x, y): (f32, f32), radius: f32, color: Rgba<u8>) {
+    draw_filled_circle_mut(
+        image,
+        (x.round() as i32, y.round() as i32),
+        radius.round() as i32,
+        color,
+    );
+}
+
+fn fill_polygon(image: &mut RgbaImage, vertices: &[(f32, f32)], color: Rgba<u8>) {
+    let points: Vec<Point<i32>> = vertices
+        .iter()
+        .map(|&(x, y)| Point::new(x.round() as i32, y.round() as i32))
+        .collect();
+    draw_polygon_mut(image, &points, color);
+}
+
+fn stroke_segment(
+    image: &mut RgbaImage,
+    (x0, y0): (f32, f32),
+    (x1, y1): (f32, f32),
+    width: f32,
+    color: Rgba<u8>,
+) {
+    let length = (x1 - x0).hypot(y1 - y0);
+    if length == 0.0 {
+        return;
+    }
+    let (nx, ny) = (
+        (y0 - y1) / length * width * 0.5,
+        (x1 - x0) / length * width * 0.5,
+    );
+    fill_polygon(
+        image,
+        &[
+            (x0 + nx, y0 + ny),
+            (x1 + nx, y1 + ny),
+            (x1 - nx, y1 - ny),
+            (x0 - nx, y0 - ny),
+        ],
+        color,
+    );
+}
+
+fn push_svg_table(svg: &mut String, viewport: DiagramViewport, table: TableDimensions) {
     // WPA tournament dimensions used by Diamond-style 9 ft tables:
     // 100 x 50 in playing surface, sights 3 11/16 in from cushion nose,
     // 4.5 in corner mouths, 5.0 in side mouths, and cut pockets instead of
@@ -460,17 +1756,17 @@ fn push_svg_table(svg: &mut String, viewport: DiagramViewport) {
     let right_rail_w = w - right;
     let center_y = (top + bottom) * 0.5;
 
-    let cushion_x = viewport.x_inches(CUSHION_WIDTH_IN);
-    let cushion_y = viewport.y_inches(CUSHION_WIDTH_IN);
-    let corner_run_x = viewport.x_inches(CORNER_POCKET_MOUTH_IN / 2.0_f32.sqrt());
-    let corner_run_y = viewport.y_inches(CORNER_POCKET_MOUTH_IN / 2.0_f32.sqrt());
-    let corner_shelf_x = viewport.x_inches(CORNER_POCKET_SHELF_IN);
-    let corner_shelf_y = viewport.y_inches(CORNER_POCKET_SHELF_IN);
-    let side_mouth_y = viewport.y_inches(SIDE_POCKET_MOUTH_IN);
-    let side_lip_x = viewport.x_inches(SIDE_POCKET_LIP_IN);
-    let side_well_x = viewport.x_inches(SIDE_POCKET_WELL_IN);
-    let cushion_bevel_x = viewport.x_inches(CUSHION_BEVEL_IN);
-    let cushion_bevel_y = viewport.y_inches(CUSHION_BEVEL_IN);
+    let cushion_x = viewport.x_inches(table, CUSHION_WIDTH_IN);
+    let cushion_y = viewport.y_inches(table, CUSHION_WIDTH_IN);
+    let corner_run_x = viewport.x_inches(table, table.corner_mouth_in / 2.0_f32.sqrt());
+    let corner_run_y = viewport.y_inches(table, table.corner_mouth_in / 2.0_f32.sqrt());
+    let corner_shelf_x = viewport.x_inches(table, CORNER_POCKET_SHELF_IN);
+    let corner_shelf_y = viewport.y_inches(table, CORNER_POCKET_SHELF_IN);
+    let side_mouth_y = viewport.y_inches(table, table.side_mouth_in);
+    let side_lip_x = viewport.x_inches(table, SIDE_POCKET_LIP_IN);
+    let side_well_x = viewport.x_inches(table, SIDE_POCKET_WELL_IN);
+    let cushion_bevel_x = viewport.x_inches(table, CUSHION_BEVEL_IN);
+    let cushion_bevel_y = viewport.y_inches(table, CUSHION_BEVEL_IN);
     svg.push_str(&format!(
         "<rect class=\"table-rail\" x=\"0\" y=\"0\" width=\"{w:.3}\" height=\"{h:.3}\" rx=\"58\"/>\n"
     ));
@@ -545,55 +1841,100 @@ fn push_svg_table(svg: &mut String, viewport: DiagramViewport) {
         cushion_bevel_y,
     );
 
-    for (corner_x, corner_y, x_sign, y_sign) in [
-        (left, top, -1.0, -1.0),
-        (right, top, 1.0, -1.0),
-        (left, bottom, -1.0, 1.0),
-        (right, bottom, 1.0, 1.0),
-    ] {
-        push_svg_corner_pocket(
+    // A table without corner or side pockets, like a carom table, has unbroken cushions there.
+    if table.corner_mouth_in > 0.0 {
+        for (corner_x, corner_y, x_sign, y_sign) in [
+            (left, top, -1.0, -1.0),
+            (right, top, 1.0, -1.0),
+            (left, bottom, -1.0, 1.0),
+            (right, bottom, 1.0, 1.0),
+        ] {
+            push_svg_corner_pocket(
+                svg,
+                corner_x,
+                corner_y,
+                x_sign,
+                y_sign,
+                corner_run_x,
+                corner_run_y,
+                corner_shelf_x,
+                corner_shelf_y,
+            );
+        }
+    }
+    if table.side_mouth_in > 0.0 {
+        push_svg_side_pocket(
             svg,
-            corner_x,
-            corner_y,
-            x_sign,
-            y_sign,
-            corner_run_x,
-            corner_run_y,
-            corner_shelf_x,
-            corner_shelf_y,
+            left,
+            center_y,
+            -1.0,
+            side_mouth_y,
+            side_lip_x,
+            side_well_x,
+            cushion_x,
+        );
+        push_svg_side_pocket(
+            svg,
+            right,
+            center_y,
+            1.0,
+            side_mouth_y,
+            side_lip_x,
+            side_well_x,
+            cushion_x,
         );
     }
-    push_svg_side_pocket(
-        svg,
-        left,
-        center_y,
-        -1.0,
-        side_mouth_y,
-        side_lip_x,
-        side_well_x,
-        cushion_x,
-    );
-    push_svg_side_pocket(
-        svg,
-        right,
-        center_y,
-        1.0,
-        side_mouth_y,
-        side_lip_x,
-        side_well_x,
-        cushion_x,
-    );
 
-    push_svg_table_sights(svg, viewport);
+    push_svg_table_sights(svg, viewport, table);
 }
 
 impl DiagramViewport {
-    fn x_inches(self, inches: f32) -> f32 {
-        inches * (self.playfield_right_px - self.playfield_left_px) / PLAYFIELD_WIDTH_IN
+    fn x_inches(self, table: TableDimensions, inches: f32) -> f32 {
+        inches * (self.playfield_right_px - self.playfield_left_px) / table.playfield_width_in
     }
 
-    fn y_inches(self, inches: f32) -> f32 {
-        inches * (self.playfield_bottom_px - self.playfield_top_px) / PLAYFIELD_LENGTH_IN
+    fn y_inches(self, table: TableDimensions, inches: f32) -> f32 {
+        inches * (self.playfield_bottom_px - self.playfield_top_px)
+            / (2.0 * table.playfield_width_in)
+    }
+}
+
+/// The real sizes, in inches, that the table art is drawn from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct TableDimensions {
+    playfield_width_in: f32,
+    corner_mouth_in: f32,
+    side_mouth_in: f32,
+}
+
+impl TableDimensions {
+    /// The WPA tournament 9 ft table of the bundled art.
+    const BUNDLED: Self = Self {
+        playfield_width_in: PLAYFIELD_WIDTH_IN,
+        corner_mouth_in: CORNER_POCKET_MOUTH_IN,
+        side_mouth_in: SIDE_POCKET_MOUTH_IN,
+    };
+
+    fn of(table_spec: &TableSpec) -> Self {
+        let inches = |diamonds: &Diamond| {
+            table_spec
+                .diamond_to_inches(diamonds.clone())
+                .magnitude
+                .to_f32()
+                .expect("table dimensions should fit in f32")
+        };
+        let mouth = |ty: PocketType| {
+            table_spec
+                .pockets
+                .iter()
+                .find(|pocket| pocket.ty == ty)
+                .map_or(0.0, |pocket| inches(&pocket.width))
+        };
+        Self {
+            playfield_width_in: inches(&Diamond::from("4")),
+            corner_mouth_in: mouth(PocketType::Corner),
+            side_mouth_in: mouth(PocketType::Side),
+        }
     }
 }
 
@@ -717,19 +2058,19 @@ fn push_svg_side_pocket(
     ));
 }
 
-fn push_svg_table_sights(svg: &mut String, viewport: DiagramViewport) {
+fn push_svg_table_sights(svg: &mut String, viewport: DiagramViewport, table: TableDimensions) {
     let left = viewport.playfield_left_px;
     let right = viewport.playfield_right_px;
     let top = viewport.playfield_top_px;
     let bottom = viewport.playfield_bottom_px;
     let cloth_w = right - left;
     let cloth_h = bottom - top;
-    let sight_setback_x = viewport.x_inches(DIAMOND_SIGHT_SETBACK_IN);
-    let sight_setback_y = viewport.y_inches(DIAMOND_SIGHT_SETBACK_IN);
-    let sight_half_along_x = viewport.x_inches(DIAMOND_SIGHT_WIDTH_IN) * 0.5;
-    let sight_half_along_y = viewport.y_inches(DIAMOND_SIGHT_WIDTH_IN) * 0.5;
-    let sight_half_cross_x = viewport.x_inches(DIAMOND_SIGHT_HEIGHT_IN) * 0.5;
-    let sight_half_cross_y = viewport.y_inches(DIAMOND_SIGHT_HEIGHT_IN) * 0.5;
+    let sight_setback_x = viewport.x_inches(table, DIAMOND_SIGHT_SETBACK_IN);
+    let sight_setback_y = viewport.y_inches(table, DIAMOND_SIGHT_SETBACK_IN);
+    let sight_half_along_x = viewport.x_inches(table, DIAMOND_SIGHT_WIDTH_IN) * 0.5;
+    let sight_half_along_y = viewport.y_inches(table, DIAMOND_SIGHT_WIDTH_IN) * 0.5;
+    let sight_half_cross_x = viewport.x_inches(table, DIAMOND_SIGHT_HEIGHT_IN) * 0.5;
+    let sight_half_cross_y = viewport.y_inches(table, DIAMOND_SIGHT_HEIGHT_IN) * 0.5;
 
     for fraction in [0.25, 0.5, 0.75] {
         let x = left + fraction * cloth_w;
@@ -825,6 +2166,9 @@ fn push_svg_element(svg: &mut String, scene: &DiagramScene, element: &DiagramEle
                 style.dash_px,
                 style.gap_px
             ));
+            if let Some(arrow) = &style.arrow {
+                push_svg_arrowheads(svg, arrow, (start, end), (start, end), style.color);
+            }
         }
         DiagramElement::SmoothPolyline { points, style } => {
             if points.len() < 2 {
@@ -833,22 +2177,37 @@ fn push_svg_element(svg: &mut String, scene: &DiagramScene, element: &DiagramEle
             let (stroke, opacity) = svg_color(style.color);
             let points = points
                 .iter()
-                .map(|point| {
-                    let point = scene.viewport.position_to_scene_point(point);
-                    format!("{:.3},{:.3}", point.x, point.y)
-                })
-                .collect::<Vec<_>>()
-                .join(" ");
+                .map(|point| scene.viewport.position_to_scene_point(point))
+                .collect::<Vec<_>>();
             svg.push_str(&format!(
                 "<polyline class=\"overlay smooth-polyline\" points=\"{}\" stroke=\"{}\" stroke-opacity=\"{:.3}\" stroke-width=\"{:.3}\" stroke-linecap=\"round\" stroke-linejoin=\"round\" fill=\"none\"/>\n",
-                points, stroke, opacity, style.width_px
+                points
+                    .iter()
+                    .map(|point| format!("{:.3},{:.3}", point.x, point.y))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                stroke,
+                opacity,
+                style.width_px
             ));
+            if let Some(arrow) = &style.arrow {
+                let n = points.len();
+                push_svg_arrowheads(
+                    svg,
+                    arrow,
+                    (points[0], points[1]),
+                    (points[n - 2], points[n - 1]),
+                    style.color,
+                );
+            }
         }
-        DiagramElement::GhostBall { center, style } => {
+        DiagramElement::GhostBall {
+            center,
+            spec,
+            style,
+        } => {
             let center = scene.viewport.position_to_scene_point(center);
-            let radius = scene
-                .viewport
-                .ball_radius_px(&scene.table_spec, &BallSpec::default());
+            let radius = scene.viewport.ball_radius_px(&scene.table_spec, spec);
             let (fill, fill_opacity) = svg_color(style.fill_color);
             let (stroke, stroke_opacity) = svg_color(style.outline_color);
             svg.push_str(&format!(
@@ -856,6 +2215,34 @@ fn push_svg_element(svg: &mut String, scene: &DiagramScene, element: &DiagramEle
                 center.x, center.y, radius, fill, fill_opacity, stroke, stroke_opacity
             ));
         }
+        // Phantom balls are drawn with the balls.
+        DiagramElement::PhantomBall { .. } => {}
+        DiagramElement::BallHighlight {
+            ty,
+            center,
+            spec,
+            style,
+        } => {
+            let center = scene.viewport.position_to_scene_point(center);
+            let radius = scene.viewport.ball_radius_px(&scene.table_spec, spec);
+            let (color, opacity) = svg_color(style.color);
+            let class_name = ball_visual(ty).class_name;
+            match style.kind {
+                BallHighlightKind::Halo => svg.push_str(&format!(
+                    "<circle class=\"overlay ball-halo\" data-ball=\"{}\" cx=\"{:.3}\" cy=\"{:.3}\" r=\"{:.3}\" fill=\"{}\" fill-opacity=\"{:.3}\"/>\n",
+                    class_name,
+                    center.x,
+                    center.y,
+                    radius + style.width_px,
+                    color,
+                    opacity
+                )),
+                BallHighlightKind::Outline => svg.push_str(&format!(
+                    "<circle class=\"overlay ball-outline\" data-ball=\"{}\" cx=\"{:.3}\" cy=\"{:.3}\" r=\"{:.3}\" fill=\"none\" stroke=\"{}\" stroke-opacity=\"{:.3}\" stroke-width=\"{}\"/>\n",
+                    class_name, center.x, center.y, radius, color, opacity, style.width_px
+                )),
+            }
+        }
         DiagramElement::CircleMarker { center, style } => {
             let center = scene.viewport.position_to_scene_point(center);
             let (fill, opacity) = svg_color(style.color);
@@ -881,110 +2268,437 @@ fn push_svg_element(svg: &mut String, scene: &DiagramScene, element: &DiagramEle
                 escape_xml(text)
             ));
         }
+        DiagramElement::SequenceMarker {
+            center,
+            number,
+            style,
+        } => {
+            let center = scene.viewport.position_to_scene_point(center);
+            let (fill, fill_opacity) = svg_color(style.fill_color);
+            let (text_fill, text_opacity) = svg_color(style.text_color);
+            svg.push_str(&format!(
+                "<g class=\"overlay sequence-marker\" data-step=\"{}\" transform=\"translate({:.3} {:.3})\">\n",
+                number, center.x, center.y
+            ));
+            svg.push_str(&format!(
+                "<circle r=\"{:.3}\" fill=\"{}\" fill-opacity=\"{:.3}\"/>\n",
+                style.radius_px, fill, fill_opacity
+            ));
+            svg.push_str(&format!(
+                "<text class=\"ball-label\" fill=\"{}\" fill-opacity=\"{:.3}\" font-size=\"{}\">{}</text>\n",
+                text_fill,
+                text_opacity,
+                style.scale_px.max(1) * 7,
+                number
+            ));
+            svg.push_str("</g>\n");
+        }
+        DiagramElement::AngleArc {
+            vertex,
+            start,
+            end,
+            label,
+            style,
+        } => {
+            let arc = AngleArcGeometry::new(&scene.viewport, vertex, start, end, style);
+            let end_radians = arc.start_radians + arc.sweep_radians;
+            let (stroke, opacity) = svg_color(style.color);
+            svg.push_str(&format!(
+                "<path class=\"overlay angle-arc\" d=\"M {:.3} {:.3} A {:.3} {:.3} 0 0 {} {:.3} {:.3}\" stroke=\"{}\" stroke-opacity=\"{:.3}\" stroke-width=\"{:.3}\" fill=\"none\"/>\n",
+                arc.center.x + style.radius_px * arc.start_radians.cos(),
+                arc.center.y + style.radius_px * arc.start_radians.sin(),
+                style.radius_px,
+                style.radius_px,
+                u8::from(arc.sweep_radians > 0.0),
+                arc.center.x + style.radius_px * end_radians.cos(),
+                arc.center.y + style.radius_px * end_radians.sin(),
+                stroke,
+                opacity,
+                style.width_px
+            ));
+            svg.push_str(&format!(
+                "<text class=\"overlay overlay-label angle-label\" x=\"{:.3}\" y=\"{:.3}\" fill=\"{}\" fill-opacity=\"{:.3}\" font-size=\"{}\" text-anchor=\"middle\">{}</text>\n",
+                arc.label.x,
+                arc.label.y,
+                stroke,
+                opacity,
+                style.scale_px.max(1) * 7,
+                escape_xml(label)
+            ));
+        }
+        DiagramElement::PocketHighlight {
+            pocket,
+            center,
+            radius,
+            style,
+        } => {
+            let center = scene.viewport.position_to_scene_point(center);
+            let (fill, fill_opacity) = svg_color(style.fill_color);
+            let (stroke, stroke_opacity) = svg_color(style.outline_color);
+            svg.push_str(&format!(
+                "<circle class=\"overlay pocket-highlight\" data-pocket=\"{:?}\" cx=\"{:.3}\" cy=\"{:.3}\" r=\"{:.3}\" fill=\"{}\" fill-opacity=\"{:.3}\" stroke=\"{}\" stroke-opacity=\"{:.3}\" stroke-width=\"{:.3}\"/>\n",
+                pocket,
+                center.x,
+                center.y,
+                scene.viewport.length_px(&scene.table_spec, radius),
+                fill,
+                fill_opacity,
+                stroke,
+                stroke_opacity,
+                style.outline_width_px
+            ));
+        }
+        DiagramElement::ShadedZone { outline, style } => {
+            if outline.len() < 3 {
+                return;
+            }
+            let (fill, fill_opacity) = svg_color(style.fill_color);
+            let stroke = match style.outline_color {
+                Some(color) => {
+                    let (stroke, stroke_opacity) = svg_color(color);
+                    format!(
+                        "stroke=\"{}\" stroke-opacity=\"{:.3}\" stroke-width=\"{:.3}\" stroke-linejoin=\"round\"",
+                        stroke, stroke_opacity, style.outline_width_px
+                    )
+                }
+                None => "stroke=\"none\"".to_string(),
+            };
+            svg.push_str(&format!(
+                "<polygon class=\"overlay shaded-zone\" points=\"{}\" fill=\"{}\" fill-opacity=\"{:.3}\" {}/>\n",
+                outline
+                    .iter()
+                    .map(|vertex| {
+                        let point = scene.viewport.position_to_scene_point(vertex);
+                        format!("{:.3},{:.3}", point.x, point.y)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                fill,
+                fill_opacity,
+                stroke
+            ));
+        }
+        DiagramElement::Legend {
+            anchor,
+            entries,
+            style,
+        } => {
+            if entries.is_empty() {
+                return;
+            }
+            let origin = scene.viewport.position_to_scene_point(anchor);
+            let layout = drawing::LegendLayout::new(entries, style);
+            let (background, background_opacity) = svg_color(style.background_color);
+            let (text_fill, text_opacity) = svg_color(style.text_color);
+            svg.push_str(&format!(
+                "<g class=\"overlay legend\" transform=\"translate({:.3} {:.3})\">\n",
+                origin.x, origin.y
+            ));
+            svg.push_str(&format!(
+                "<rect width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"{:.3}\"/>\n",
+                layout.width_px, layout.height_px, background, background_opacity
+            ));
+            for (row, entry) in entries.iter().enumerate() {
+                let (swatch_left, row_center) = layout.row_origin(row);
+                let swatch_right = swatch_left + drawing::LEGEND_SWATCH_PX;
+                match entry.swatch {
+                    LegendSwatch::Solid(color) | LegendSwatch::Dashed(color) => {
+                        let (stroke, opacity) = svg_color(color);
+                        let dashes = if matches!(entry.swatch, LegendSwatch::Dashed(_)) {
+                            " stroke-dasharray=\"4 4\""
+                        } else {
+                            ""
+                        };
+                        svg.push_str(&format!(
+                            "<line x1=\"{swatch_left}\" y1=\"{row_center}\" x2=\"{swatch_right}\" y2=\"{row_center}\" stroke=\"{stroke}\" stroke-opacity=\"{opacity:.3}\" stroke-width=\"4\"{dashes}/>\n"
+                        ));
+                    }
+                    LegendSwatch::Fill(color) => {
+                        let (fill, opacity) = svg_color(color);
+                        let half = drawing::LEGEND_SWATCH_PX / 4;
+                        svg.push_str(&format!(
+                            "<rect x=\"{swatch_left}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{fill}\" fill-opacity=\"{opacity:.3}\"/>\n",
+                            row_center - half,
+                            drawing::LEGEND_SWATCH_PX,
+                            2 * half
+                        ));
+                    }
+                }
+                svg.push_str(&format!(
+                    "<text class=\"overlay-label\" x=\"{}\" y=\"{}\" fill=\"{}\" fill-opacity=\"{:.3}\" font-size=\"{}\">{}</text>\n",
+                    layout.text_left_px,
+                    row_center,
+                    text_fill,
+                    text_opacity,
+                    style.scale_px.max(1) * 7,
+                    escape_xml(&entry.text)
+                ));
+            }
+            svg.push_str("</g>\n");
+        }
+        DiagramElement::DiamondGrid { style } => {
+            let (stroke, stroke_opacity) = svg_color(style.line_color);
+            let (label_fill, label_fill_opacity) = svg_color(style.label_fill_color);
+            let (label_text, label_text_opacity) = svg_color(style.label_text_color);
+            let font_size = style.scale_px.max(1) * 7;
+            svg.push_str("<g class=\"overlay diamond-grid\">\n");
+            for (diamond, from, to, label) in drawing::diamond_grid_lines() {
+                let from = scene.viewport.position_to_scene_point(&from);
+                let to = scene.viewport.position_to_scene_point(&to);
+                let label = scene.viewport.position_to_scene_point(&label);
+                svg.push_str(&format!(
+                    "<line x1=\"{:.3}\" y1=\"{:.3}\" x2=\"{:.3}\" y2=\"{:.3}\" stroke=\"{}\" stroke-opacity=\"{:.3}\" stroke-width=\"{}\"/>\n",
+                    from.x, from.y, to.x, to.y, stroke, stroke_opacity, style.width_px
+                ));
+                svg.push_str(&format!(
+                    "<circle cx=\"{:.3}\" cy=\"{:.3}\" r=\"{:.3}\" fill=\"{}\" fill-opacity=\"{:.3}\"/>\n",
+                    label.x, label.y, style.label_radius_px, label_fill, label_fill_opacity
+                ));
+                svg.push_str(&format!(
+                    "<text class=\"ball-label\" x=\"{:.3}\" y=\"{:.3}\" fill=\"{}\" fill-opacity=\"{:.3}\" font-size=\"{}\">{}</text>\n",
+                    label.x, label.y, label_text, label_text_opacity, font_size, diamond
+                ));
+            }
+            svg.push_str("</g>\n");
+        }
     }
 }
 
-fn push_svg_balls(svg: &mut String, scene: &DiagramScene) {
+/// The (from, tip) pairs to draw arrowheads on, given a line's first and last segments.
+fn arrowheads<T>(arrow: &ArrowStyle, first: (T, T), last: (T, T)) -> Vec<(T, T)> {
+    match arrow.ends {
+        ArrowEnds::End => vec![last],
+        ArrowEnds::Both => vec![last, (first.1, first.0)],
+    }
+}
+
+fn push_svg_arrowheads(
+    svg: &mut String,
+    arrow: &ArrowStyle,
+    first: (ScenePoint, ScenePoint),
+    last: (ScenePoint, ScenePoint),
+    color: Rgba<u8>,
+) {
+    let (fill, opacity) = svg_color(color);
+    for (from, tip) in arrowheads(arrow, first, last) {
+        let (dx, dy) = (tip.x - from.x, tip.y - from.y);
+        let len = dx.hypot(dy);
+        if len == 0.0 {
+            continue;
+        }
+        let (ux, uy) = (dx / len, dy / len);
+        let base_x = tip.x - ux * arrow.length_px;
+        let base_y = tip.y - uy * arrow.length_px;
+        let half_w = arrow.width_px * 0.5;
+        svg.push_str(&format!(
+            "<polygon class=\"overlay arrowhead\" points=\"{:.3},{:.3} {:.3},{:.3} {:.3},{:.3}\" fill=\"{}\" fill-opacity=\"{:.3}\"/>\n",
+            tip.x,
+            tip.y,
+            base_x + uy * half_w,
+            base_y - ux * half_w,
+            base_x - uy * half_w,
+            base_y + ux * half_w,
+            fill,
+            opacity
+        ));
+    }
+}
+
+fn push_svg_balls(svg: &mut String, scene: &DiagramScene, orientation: DiagramOrientation) {
+    // Turn each ball back against a landscape table so its number reads upright.
+    let upright = match orientation {
+        DiagramOrientation::Portrait => "",
+        DiagramOrientation::Landscape => " rotate(-90)",
+    };
     svg.push_str(&format!(
         "<g class=\"diagram-layer\" id=\"layer-{}\" data-layer=\"{}\">\n",
         DiagramLayerId::Balls.as_str(),
         DiagramLayerId::Balls.as_str()
     ));
+    for element in scene.elements_for_layer(DiagramLayerId::Balls) {
+        if let DiagramElement::PhantomBall {
+            ty,
+            center,
+            spec,
+            style,
+        } = element
+        {
+            push_svg_ball(
+                svg,
+                scene,
+                (ty, center, spec),
+                upright,
+                Some(("phantom-ball", style.opacity.clamp(0.0, 1.0))),
+            );
+        }
+    }
     for ball in &scene.balls {
-        let center = scene.viewport.position_to_scene_point(&ball.position);
-        let radius = scene.viewport.ball_radius_px(&scene.table_spec, &ball.spec);
-        let visual = ball_visual(&ball.ty);
-        let label = ball_label(&ball.ty);
+        push_svg_ball(
+            svg,
+            scene,
+            (&ball.ty, &ball.position, &ball.spec),
+            upright,
+            (ball.opacity < 1.0).then(|| ("dimmed-ball", ball.opacity.clamp(0.0, 1.0))),
+        );
+    }
+    svg.push_str("</g>\n");
+}
+
+/// `watermark` in a corner of the view box, sized in output pixels of `units_per_px` each.
+fn push_svg_watermark(
+    svg: &mut String,
+    watermark: &Watermark,
+    (left, top, width, height): (f32, f32, f32, f32),
+    units_per_px: f32,
+) {
+    let place = |size: (f32, f32)| {
+        let (x, y) = watermark_origin(
+            watermark.corner,
+            watermark.inset_px as f32 * units_per_px,
+            (width, height),
+            size,
+        );
+        (left + x, top + y)
+    };
+    match &watermark.content {
+        WatermarkContent::Image(logo) => {
+            let size = (
+                logo.width() as f32 * units_per_px,
+                logo.height() as f32 * units_per_px,
+            );
+            let (x, y) = place(size);
+            svg.push_str(&format!(
+                "<image class=\"watermark\" x=\"{:.3}\" y=\"{:.3}\" width=\"{:.3}\" height=\"{:.3}\" opacity=\"{:.3}\" href=\"data:image/png;base64,{}\"/>\n",
+                x,
+                y,
+                size.0,
+                size.1,
+                watermark.opacity,
+                base64::encode(&encode_png(logo, None))
+            ));
+        }
+        WatermarkContent::Text {
+            text,
+            color,
+            scale_px,
+        } => {
+            let size = (
+                drawing::text_width_px(text, *scale_px) as f32 * units_per_px,
+                (7 * scale_px) as f32 * units_per_px,
+            );
+            let (x, y) = place(size);
+            let (fill, opacity) = svg_color(*color);
+            // Stretched to the width of the raster text, so it sits in the same place.
+            svg.push_str(&format!(
+                "<text class=\"overlay-label watermark\" x=\"{:.3}\" y=\"{:.3}\" textLength=\"{:.3}\" lengthAdjust=\"spacingAndGlyphs\" fill=\"{}\" fill-opacity=\"{:.3}\" opacity=\"{:.3}\" font-size=\"{:.3}\">{}</text>\n",
+                x,
+                y + 0.5 * size.1,
+                size.0,
+                fill,
+                opacity,
+                watermark.opacity,
+                size.1,
+                escape_xml(text)
+            ));
+        }
+    }
+}
+
+/// One ball, see-through if it is a phantom or dimmed, with that class and opacity.
+fn push_svg_ball(
+    svg: &mut String,
+    scene: &DiagramScene,
+    (ty, position, spec): (&BallType, &Position, &BallSpec),
+    upright: &str,
+    see_through: Option<(&str, f32)>,
+) {
+    let center = scene.viewport.position_to_scene_point(position);
+    let radius = scene.viewport.ball_radius_px(&scene.table_spec, spec);
+    let visual = ball_visual(ty);
+    let label = ty.number();
+    let fill = svg_color(visual.color).0;
+    let (see_through_class, opacity) = match see_through {
+        Some((class, opacity)) => (format!(" {class}"), format!(" opacity=\"{opacity:.3}\"")),
+        None => (String::new(), String::new()),
+    };
+    svg.push_str(&format!(
+        "<g class=\"ball{see_through_class} ball-{}\" data-ball=\"{}\" transform=\"translate({:.3} {:.3}){upright}\"{opacity}>\n",
+        visual.class_name, visual.class_name, center.x, center.y
+    ));
+    if visual.striped {
+        // A white ball with a band of color between two chords across its middle.
+        let band = radius * 0.55;
+        let half_chord = (radius * radius - band * band).sqrt();
         svg.push_str(&format!(
-            "<g class=\"ball ball-{}\" data-ball=\"{}\" transform=\"translate({:.3} {:.3})\">\n",
-            visual.class_name, visual.class_name, center.x, center.y
+            "<circle r=\"{radius:.3}\" fill=\"#f8f4e8\"/>\n<path class=\"ball-stripe\" d=\"M {:.3} {:.3} L {half_chord:.3} {:.3} A {radius:.3} {radius:.3} 0 0 1 {half_chord:.3} {band:.3} L {:.3} {band:.3} A {radius:.3} {radius:.3} 0 0 1 {:.3} {:.3} Z\" fill=\"{fill}\"/>\n",
+            -half_chord, -band, -band, -half_chord, -half_chord, -band
         ));
         svg.push_str(&format!(
-            "<circle r=\"{radius:.3}\" fill=\"{}\" stroke=\"#111\" stroke-width=\"1.5\"/>\n",
-            visual.fill
+            "<circle r=\"{radius:.3}\" fill=\"none\" stroke=\"#111\" stroke-width=\"1.5\"/>\n"
         ));
+    } else {
         svg.push_str(&format!(
-            "<circle r=\"{:.3}\" fill=\"none\" stroke=\"rgba(255,255,255,.45)\" stroke-width=\"2\"/>\n",
-            radius * 0.72
+            "<circle r=\"{radius:.3}\" fill=\"{fill}\" stroke=\"#111\" stroke-width=\"1.5\"/>\n"
+        ));
+    }
+    svg.push_str(&format!(
+        "<circle r=\"{:.3}\" fill=\"none\" stroke=\"rgba(255,255,255,.45)\" stroke-width=\"2\"/>\n",
+        radius * 0.72
+    ));
+    if let Some(label) = label {
+        let label_radius = (radius * 0.42).max(7.0);
+        svg.push_str(&format!(
+            "<circle r=\"{label_radius:.3}\" fill=\"#f8f4e8\" stroke=\"#111\" stroke-width=\".75\"/>\n"
+        ));
+        svg.push_str(&format!(
+            "<text class=\"ball-label\" y=\".5\" fill=\"#111\" font-size=\"{:.3}\">{}</text>\n",
+            (radius * 0.58).max(10.0),
+            label
         ));
-        if let Some(label) = label {
-            let label_radius = (radius * 0.42).max(7.0);
-            svg.push_str(&format!(
-                "<circle r=\"{label_radius:.3}\" fill=\"#f8f4e8\" stroke=\"#111\" stroke-width=\".75\"/>\n"
-            ));
-            svg.push_str(&format!(
-                "<text class=\"ball-label\" y=\".5\" fill=\"#111\" font-size=\"{:.3}\">{}</text>\n",
-                (radius * 0.58).max(10.0),
-                label
-            ));
-        }
-        svg.push_str("</g>\n");
     }
     svg.push_str("</g>\n");
 }
 
 struct BallVisual {
-    fill: &'static str,
+    color: Rgba<u8>,
+    striped: bool,
     class_name: &'static str,
 }
 
 fn ball_visual(ball_type: &BallType) -> BallVisual {
-    match ball_type {
-        BallType::Cue => BallVisual {
-            fill: "#f8f4e8",
-            class_name: "cue",
-        },
-        BallType::One | BallType::Nine => BallVisual {
-            fill: "#f1c232",
-            class_name: if matches!(ball_type, BallType::One) {
-                "one"
-            } else {
-                "nine"
-            },
-        },
-        BallType::Two => BallVisual {
-            fill: "#2458c8",
-            class_name: "two",
-        },
-        BallType::Three => BallVisual {
-            fill: "#c82828",
-            class_name: "three",
-        },
-        BallType::Four => BallVisual {
-            fill: "#6f3fa8",
-            class_name: "four",
-        },
-        BallType::Five => BallVisual {
-            fill: "#e27a22",
-            class_name: "five",
-        },
-        BallType::Six => BallVisual {
-            fill: "#25834b",
-            class_name: "six",
-        },
-        BallType::Seven => BallVisual {
-            fill: "#8f2d20",
-            class_name: "seven",
-        },
-        BallType::Eight => BallVisual {
-            fill: "#111111",
-            class_name: "eight",
-        },
-    }
-}
-
-fn ball_label(ball_type: &BallType) -> Option<&'static str> {
-    match ball_type {
-        BallType::Cue => None,
-        BallType::One => Some("1"),
-        BallType::Two => Some("2"),
-        BallType::Three => Some("3"),
-        BallType::Four => Some("4"),
-        BallType::Five => Some("5"),
-        BallType::Six => Some("6"),
-        BallType::Seven => Some("7"),
-        BallType::Eight => Some("8"),
-        BallType::Nine => Some("9"),
+    let color = match ball_type {
+        BallType::Cue => Rgba([248, 244, 232, 255]),
+        BallType::One | BallType::Nine => Rgba([241, 194, 50, 255]),
+        BallType::Two | BallType::Ten => Rgba([36, 88, 200, 255]),
+        BallType::Three | BallType::Eleven => Rgba([200, 40, 40, 255]),
+        BallType::Four | BallType::Twelve => Rgba([111, 63, 168, 255]),
+        BallType::Five | BallType::Thirteen => Rgba([226, 122, 34, 255]),
+        BallType::Six | BallType::Fourteen => Rgba([37, 131, 75, 255]),
+        BallType::Seven | BallType::Fifteen => Rgba([143, 45, 32, 255]),
+        BallType::Eight => Rgba([17, 17, 17, 255]),
+    };
+    let class_name = match ball_type {
+        BallType::Cue => "cue",
+        BallType::One => "one",
+        BallType::Two => "two",
+        BallType::Three => "three",
+        BallType::Four => "four",
+        BallType::Five => "five",
+        BallType::Six => "six",
+        BallType::Seven => "seven",
+        BallType::Eight => "eight",
+        BallType::Nine => "nine",
+        BallType::Ten => "ten",
+        BallType::Eleven => "eleven",
+        BallType::Twelve => "twelve",
+        BallType::Thirteen => "thirteen",
+        BallType::Fourteen => "fourteen",
+        BallType::Fifteen => "fifteen",
+    };
+    BallVisual {
+        color,
+        striped: ball_type.is_striped(),
+        class_name,
     }
 }
 
@@ -1,10 +1,8 @@
+use image::imageops::{resize, FilterType};
 use image::{Pixel, Rgba, RgbaImage};
-use imageproc::{
-    drawing::{draw_antialiased_line_segment_mut, draw_polygon_mut},
-    pixelops::interpolate,
-    point::Point,
-};
+use imageproc::pixelops::interpolate;
 
+use crate::visualization::{DiamondGridStyle, LegendEntry, LegendStyle, LegendSwatch};
 use crate::Position;
 
 #[inline]
@@ -14,8 +12,8 @@ fn normal(ux: f32, uy: f32) -> (f32, f32) {
 
 /// Add a scaled vector to a point
 #[inline]
-fn offset(p: (f32, f32), vx: f32, vy: f32, s: f32) -> Point<i32> {
-    Point::new((p.0 + vx * s) as i32, (p.1 + vy * s) as i32)
+fn offset(p: (f32, f32), vx: f32, vy: f32, s: f32) -> (f32, f32) {
+    (p.0 + vx * s, p.1 + vy * s)
 }
 
 /// A dashed line with adjustable thickness (via `line_width_px`)
@@ -28,11 +26,11 @@ pub fn draw_dashed_line_thick_mut(
     width_px: f32,
     color: Rgba<u8>,
 ) {
-    let (x0, y0) = crate::assets::diamond_to_pixel(a);
-    let (x1, y1) = crate::assets::diamond_to_pixel(b);
+    let (x0, y0) = pixel_center(crate::assets::diamond_to_pixel(a));
+    let (x1, y1) = pixel_center(crate::assets::diamond_to_pixel(b));
 
-    let dx = x1 as f32 - x0 as f32;
-    let dy = y1 as f32 - y0 as f32;
+    let dx = x1 - x0;
+    let dy = y1 - y0;
     let len = (dx * dx + dy * dy).sqrt();
     if len == 0.0 {
         return;
@@ -49,8 +47,8 @@ pub fn draw_dashed_line_thick_mut(
         let e = (s + dash_px).min(len);
 
         // Centre-line endpoints of this dash
-        let p0 = (x0 as f32 + ux * s, y0 as f32 + uy * s);
-        let p1 = (x0 as f32 + ux * e, y0 as f32 + uy * e);
+        let p0 = (x0 + ux * s, y0 + uy * s);
+        let p1 = (x0 + ux * e, y0 + uy * e);
 
         // Four rectangle corners = endpoints +/- half_w along the normal
         let c0 = offset(p0, nx, ny, half_w);
@@ -58,48 +56,96 @@ pub fn draw_dashed_line_thick_mut(
         let c2 = offset(p1, -nx, -ny, half_w);
         let c3 = offset(p0, -nx, -ny, half_w);
 
-        draw_polygon_mut(img, &[c0, c1, c2, c3], color);
+        fill_polygon_coverage_mut(img, &[c0, c1, c2, c3], color);
 
         s += dash_px + gap_px;
     }
 }
 
-fn draw_antialiased_thick_line_segment_mut(
+/// A filled arrowhead with its tip on `tip`, pointing away from `from`.
+pub fn draw_arrowhead_mut(
     img: &mut RgbaImage,
-    start: (i32, i32),
-    end: (i32, i32),
+    from: &Position,
+    tip: &Position,
+    length_px: f32,
     width_px: f32,
     color: Rgba<u8>,
 ) {
-    let dx = end.0 as f32 - start.0 as f32;
-    let dy = end.1 as f32 - start.1 as f32;
+    let (x0, y0) = pixel_center(crate::assets::diamond_to_pixel(from));
+    let (x1, y1) = pixel_center(crate::assets::diamond_to_pixel(tip));
+
+    let dx = x1 - x0;
+    let dy = y1 - y0;
     let len = (dx * dx + dy * dy).sqrt();
-    if len == 0.0 {
+    if len == 0.0 || length_px <= 0.0 || width_px <= 0.0 {
         return;
     }
 
     let ux = dx / len;
     let uy = dy / len;
     let (nx, ny) = normal(ux, uy);
-    let half_w = width_px * 0.5;
-    let radius = half_w.ceil() as i32;
+    let base = (x1 - ux * length_px, y1 - uy * length_px);
 
-    for offset in -radius..=radius {
-        let offset_f = offset as f32;
-        let coverage = (half_w + 0.5 - offset_f.abs()).clamp(0.0, 1.0);
-        if coverage <= 0.0 {
-            continue;
-        }
+    fill_polygon_coverage_mut(
+        img,
+        &[
+            (x1, y1),
+            offset(base, nx, ny, width_px * 0.5),
+            offset(base, -nx, -ny, width_px * 0.5),
+        ],
+        color,
+    );
+}
 
-        let sx = (start.0 as f32 + nx * offset_f).round() as i32;
-        let sy = (start.1 as f32 + ny * offset_f).round() as i32;
-        let ex = (end.0 as f32 + nx * offset_f).round() as i32;
-        let ey = (end.1 as f32 + ny * offset_f).round() as i32;
-        let alpha = ((color[3] as f32) * coverage).round().clamp(0.0, 255.0) as u8;
-        let stroke = Rgba([color[0], color[1], color[2], alpha]);
+fn draw_antialiased_thick_line_segment_mut(
+    img: &mut RgbaImage,
+    start: (i32, i32),
+    end: (i32, i32),
+    width_px: f32,
+    color: Rgba<u8>,
+) {
+    stroke_polyline_mut(
+        img,
+        &[pixel_center(start), pixel_center(end)],
+        width_px,
+        color,
+    );
+}
+
+/// The middle of the pixel at `(x, y)`, where shapes centered on that pixel are centered.
+fn pixel_center((x, y): (i32, i32)) -> (f32, f32) {
+    (x as f32 + 0.5, y as f32 + 0.5)
+}
+
+/// Stroke the polyline through `points`, in pixel coordinates, `width_px` wide with round joins.
+/// Each pixel is blended once by how much of it the stroke covers, so translucent strokes stay
+/// even where segments meet.
+fn stroke_polyline_mut(img: &mut RgbaImage, points: &[(f32, f32)], width_px: f32, color: Rgba<u8>) {
+    if points.len() < 2 || width_px <= 0.0 || color[3] == 0 {
+        return;
+    }
 
-        draw_antialiased_line_segment_mut(img, (sx, sy), (ex, ey), stroke, interpolate);
+    let half_w = width_px * 0.5;
+    let mut mask = CoverageMask::around(img, points, half_w);
+    for window in points.windows(2) {
+        let (start, end) = (window[0], window[1]);
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let len = dx.hypot(dy);
+        if len == 0.0 {
+            continue;
+        }
+        let (nx, ny) = normal(dx / len, dy / len);
+        mask.add_polygon(&[
+            offset(start, nx, ny, half_w),
+            offset(end, nx, ny, half_w),
+            offset(end, -nx, -ny, half_w),
+            offset(start, -nx, -ny, half_w),
+        ]);
     }
+    for &joint in &points[1..points.len() - 1] {
+        mask.add_disc(joint, half_w);
+    }
+    mask.blend(img, color);
 }
 
 /// Draw a smooth anti-aliased polyline with a first-pass configurable width.
@@ -113,11 +159,39 @@ pub fn draw_smooth_polyline_mut(
         return;
     }
 
-    for window in points.windows(2) {
-        let start = crate::assets::diamond_to_pixel(&window[0]);
-        let end = crate::assets::diamond_to_pixel(&window[1]);
-        draw_antialiased_thick_line_segment_mut(img, start, end, width_px, color);
+    let points: Vec<(f32, f32)> = points
+        .iter()
+        .map(|point| pixel_center(crate::assets::diamond_to_pixel(point)))
+        .collect();
+    stroke_polyline_mut(img, &points, width_px, color);
+}
+
+/// Draw an anti-aliased circular arc around a table position.
+///
+/// Angles are in radians in image space, where y grows downward; a positive sweep turns from +x
+/// toward +y.
+pub fn draw_arc_mut(
+    img: &mut RgbaImage,
+    center: &Position,
+    radius_px: f32,
+    start_radians: f32,
+    sweep_radians: f32,
+    width_px: f32,
+    color: Rgba<u8>,
+) {
+    if radius_px <= 0.0 || sweep_radians == 0.0 {
+        return;
     }
+
+    let (cx, cy) = pixel_center(crate::assets::diamond_to_pixel(center));
+    let steps = ((radius_px * sweep_radians.abs()) / 4.0).ceil().max(2.0) as usize;
+    let points: Vec<(f32, f32)> = (0..=steps)
+        .map(|step| {
+            let theta = start_radians + sweep_radians * step as f32 / steps as f32;
+            (cx + radius_px * theta.cos(), cy + radius_px * theta.sin())
+        })
+        .collect();
+    stroke_polyline_mut(img, &points, width_px, color);
 }
 
 fn blend_pixel(img: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>) {
@@ -134,6 +208,167 @@ fn blend_pixel(img: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>) {
     img.get_pixel_mut(x, y).blend(&color);
 }
 
+/// Fill a polygon of table positions, blending `color` over what is already drawn.
+///
+/// The polygon is filled by the even-odd rule, so it need not be convex.
+pub fn draw_filled_polygon_alpha_mut(img: &mut RgbaImage, vertices: &[Position], color: Rgba<u8>) {
+    let points: Vec<(f32, f32)> = vertices
+        .iter()
+        .map(|vertex| {
+            let (x, y) = crate::assets::diamond_to_pixel(vertex);
+            (x as f32, y as f32)
+        })
+        .collect();
+    fill_polygon_coverage_mut(img, &points, color);
+}
+
+/// Fill a polygon of pixel coordinates by the even-odd rule, blending `color` over each pixel in
+/// proportion to how much of the pixel the polygon covers, so slanted edges come out smooth.
+fn fill_polygon_coverage_mut(img: &mut RgbaImage, points: &[(f32, f32)], color: Rgba<u8>) {
+    if points.len() < 3 || color[3] == 0 {
+        return;
+    }
+    let mut mask = CoverageMask::around(img, points, 0.0);
+    mask.add_polygon(points);
+    mask.blend(img, color);
+}
+
+/// The smallest and largest x, then y, of `points`.
+fn bounds(points: &[(f32, f32)]) -> ((f32, f32), (f32, f32)) {
+    let range = |coordinate: fn(&(f32, f32)) -> f32| {
+        points
+            .iter()
+            .map(coordinate)
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            })
+    };
+    (range(|p| p.0), range(|p| p.1))
+}
+
+/// How much of each pixel in a box of the image some shapes cover, from 0 to 1. Overlapping
+/// shapes take the larger coverage rather than adding up, so the union is blended in once.
+struct CoverageMask {
+    left: i32,
+    top: i32,
+    width: usize,
+    coverage: Vec<f32>,
+}
+
+impl CoverageMask {
+    /// Rows of a pixel are sampled on this many lines; coverage along each line is exact.
+    const SAMPLE_ROWS: u32 = 4;
+
+    /// A mask over the part of `img` within `pad_px` of `points`.
+    fn around(img: &RgbaImage, points: &[(f32, f32)], pad_px: f32) -> Self {
+        let ((min_x, max_x), (min_y, max_y)) = bounds(points);
+        let left = ((min_x - pad_px).floor() as i32).max(0);
+        let top = ((min_y - pad_px).floor() as i32).max(0);
+        let right = ((max_x + pad_px).ceil() as i32).min(img.width() as i32);
+        let bottom = ((max_y + pad_px).ceil() as i32).min(img.height() as i32);
+        let width = (right - left).max(0) as usize;
+        let height = (bottom - top).max(0) as usize;
+        Self {
+            left,
+            top,
+            width,
+            coverage: vec![0.0; width * height],
+        }
+    }
+
+    fn height(&self) -> usize {
+        self.coverage.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    fn add_polygon(&mut self, points: &[(f32, f32)]) {
+        let ((min_x, max_x), (min_y, max_y)) = bounds(points);
+        let columns = self.columns_between(min_x, max_x);
+        let (left, right) = (
+            (self.left + columns.start as i32) as f32,
+            (self.left + columns.end as i32) as f32,
+        );
+        let mut row = vec![0.0f32; columns.len()];
+        let mut crossings = Vec::new();
+        for row_index in self.rows_between(min_y, max_y) {
+            let y = (self.top + row_index as i32) as f32;
+            row.fill(0.0);
+            for sample in 0..Self::SAMPLE_ROWS {
+                let scan_y = y + (sample as f32 + 0.5) / Self::SAMPLE_ROWS as f32;
+                crossings.clear();
+                for (index, &(x1, y1)) in points.iter().enumerate() {
+                    let (x2, y2) = points[(index + 1) % points.len()];
+                    if (y1 > scan_y) != (y2 > scan_y) {
+                        crossings.push(x1 + (scan_y - y1) / (y2 - y1) * (x2 - x1));
+                    }
+                }
+                crossings.sort_by(f32::total_cmp);
+
+                for span in crossings.chunks_exact(2) {
+                    let (start, end) = (span[0].max(left), span[1].min(right));
+                    for x in start.floor() as i32..end.ceil() as i32 {
+                        let covered = end.min(x as f32 + 1.0) - start.max(x as f32);
+                        row[(x as f32 - left) as usize] +=
+                            covered.max(0.0) / Self::SAMPLE_ROWS as f32;
+                    }
+                }
+            }
+            let mask_row = &mut self.coverage[row_index * self.width..][columns.clone()];
+            for (mask, covered) in mask_row.iter_mut().zip(&row) {
+                *mask = mask.max(*covered);
+            }
+        }
+    }
+
+    /// A disc, fading out over the last pixel inside its edge so it never spills past it.
+    fn add_disc(&mut self, (cx, cy): (f32, f32), radius_px: f32) {
+        let columns = self.columns_between(cx - radius_px, cx + radius_px);
+        for row_index in self.rows_between(cy - radius_px, cy + radius_px) {
+            let y = (self.top + row_index as i32) as f32 + 0.5;
+            for column in columns.clone() {
+                let x = (self.left + column as i32) as f32 + 0.5;
+                let covered = (radius_px - (x - cx).hypot(y - cy)).clamp(0.0, 1.0);
+                let mask = &mut self.coverage[row_index * self.width + column];
+                *mask = mask.max(covered);
+            }
+        }
+    }
+
+    /// The columns of the mask that pixels from `min_x` across to `max_x` fall in.
+    fn columns_between(&self, min_x: f32, max_x: f32) -> std::ops::Range<usize> {
+        let clamp = |x: i32| (x - self.left).clamp(0, self.width as i32) as usize;
+        clamp(min_x.floor() as i32)..clamp(max_x.ceil() as i32)
+    }
+
+    /// The rows of the mask that pixels from `min_y` down to `max_y` fall in.
+    fn rows_between(&self, min_y: f32, max_y: f32) -> std::ops::Range<usize> {
+        let clamp = |y: i32| (y - self.top).clamp(0, self.height() as i32) as usize;
+        clamp(min_y.floor() as i32)..clamp(max_y.ceil() as i32)
+    }
+
+    fn blend(&self, img: &mut RgbaImage, color: Rgba<u8>) {
+        for (index, &covered) in self.coverage.iter().enumerate() {
+            let x = self.left + (index % self.width) as i32;
+            let y = self.top + (index / self.width) as i32;
+            blend_pixel_coverage(img, x, y, color, covered);
+        }
+    }
+}
+
+/// Blend `color` over a pixel with its alpha scaled by `coverage`, from 0 to 1.
+fn blend_pixel_coverage(img: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>, coverage: f32) {
+    if coverage <= 0.0 {
+        return;
+    }
+    if coverage >= 1.0 {
+        blend_pixel(img, x, y, color);
+        return;
+    }
+    let alpha = (color[3] as f32 * coverage).round() as u8;
+    if alpha > 0 {
+        blend_pixel(img, x, y, Rgba([color[0], color[1], color[2], alpha]));
+    }
+}
+
 fn draw_filled_circle_alpha_mut(
     img: &mut RgbaImage,
     center: (i32, i32),
@@ -144,21 +379,10 @@ fn draw_filled_circle_alpha_mut(
         return;
     }
 
-    let radius_sq = radius_px * radius_px;
-    let min_x = (center.0 as f32 - radius_px).floor() as i32;
-    let max_x = (center.0 as f32 + radius_px).ceil() as i32;
-    let min_y = (center.1 as f32 - radius_px).floor() as i32;
-    let max_y = (center.1 as f32 + radius_px).ceil() as i32;
-
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            let dx = (x - center.0) as f32;
-            let dy = (y - center.1) as f32;
-            if dx * dx + dy * dy <= radius_sq {
-                blend_pixel(img, x, y, color);
-            }
-        }
-    }
+    let center = pixel_center(center);
+    let mut mask = CoverageMask::around(img, &[center], radius_px);
+    mask.add_disc(center, radius_px);
+    mask.blend(img, color);
 }
 
 pub fn draw_filled_circle_marker_mut(
@@ -175,14 +399,28 @@ pub fn draw_filled_circle_marker_mut(
     );
 }
 
-fn digit_bitmap(ch: char) -> Option<[u8; 7]> {
-    Some(match ch {
+/// A 5×7 glyph for `ch`, ignoring case; `None` for characters the font lacks, which are drawn as
+/// blank space.
+fn glyph_bitmap(ch: char) -> Option<[u8; 7]> {
+    Some(match ch.to_ascii_uppercase() {
         '(' => [
             0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010,
         ],
         ')' => [
             0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000,
         ],
+        ',' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000,
+        ],
+        '-' => [
+            0b00000, 0b00000, 0b00000, 0b01110, 0b00000, 0b00000, 0b00000,
+        ],
+        '.' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100,
+        ],
+        '/' => [
+            0b00001, 0b00010, 0b00010, 0b00100, 0b01000, 0b01000, 0b10000,
+        ],
         '0' => [
             0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110,
         ],
@@ -213,10 +451,102 @@ fn digit_bitmap(ch: char) -> Option<[u8; 7]> {
         '9' => [
             0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b11100,
         ],
+        ':' => [
+            0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000,
+        ],
+        '=' => [
+            0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000,
+        ],
+        'A' => [
+            0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+        ],
+        'B' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110,
+        ],
+        'C' => [
+            0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110,
+        ],
+        'D' => [
+            0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100,
+        ],
+        'E' => [
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111,
+        ],
+        'F' => [
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+        'G' => [
+            0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111,
+        ],
+        'H' => [
+            0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+        ],
+        'I' => [
+            0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        'J' => [
+            0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100,
+        ],
+        'K' => [
+            0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001,
+        ],
+        'L' => [
+            0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111,
+        ],
+        'M' => [
+            0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001,
+        ],
+        'N' => [
+            0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001,
+        ],
+        'O' => [
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'P' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+        'Q' => [
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101,
+        ],
+        'R' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001,
+        ],
+        'S' => [
+            0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110,
+        ],
+        'T' => [
+            0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100,
+        ],
+        'U' => [
+            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'V' => [
+            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100,
+        ],
+        'W' => [
+            0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010,
+        ],
+        'X' => [
+            0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001,
+        ],
+        'Y' => [
+            0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100,
+        ],
+        'Z' => [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111,
+        ],
+        '°' => [
+            0b01100, 0b10010, 0b10010, 0b01100, 0b00000, 0b00000, 0b00000,
+        ],
         _ => return None,
     })
 }
 
+/// How wide [`draw_text_label_mut`] draws `text` at `scale_px`.
+pub fn text_width_px(text: &str, scale_px: u32) -> u32 {
+    (6 * text.chars().count() as u32).saturating_sub(1) * scale_px
+}
+
 pub fn draw_text_label_mut(
     img: &mut RgbaImage,
     anchor: &Position,
@@ -231,15 +561,34 @@ pub fn draw_text_label_mut(
     }
 
     let (anchor_x, anchor_y) = crate::assets::diamond_to_pixel(anchor);
+    draw_text_at_px_mut(
+        img,
+        anchor_x + offset_x_px,
+        anchor_y + offset_y_px,
+        text,
+        scale_px,
+        color,
+    );
+}
+
+/// Draw `text` with its top-left corner at pixel `(x, y)`.
+pub fn draw_text_at_px_mut(
+    img: &mut RgbaImage,
+    x: i32,
+    y: i32,
+    text: &str,
+    scale_px: u32,
+    color: Rgba<u8>,
+) {
     let glyph_advance = 6 * scale_px as i32;
 
     for (index, ch) in text.chars().enumerate() {
-        let Some(bitmap) = digit_bitmap(ch) else {
+        let Some(bitmap) = glyph_bitmap(ch) else {
             continue;
         };
 
-        let glyph_x = anchor_x + offset_x_px + index as i32 * glyph_advance;
-        let glyph_y = anchor_y + offset_y_px;
+        let glyph_x = x + index as i32 * glyph_advance;
+        let glyph_y = y;
 
         for (row, bits) in bitmap.iter().enumerate() {
             for col in 0..5 {
@@ -261,6 +610,265 @@ pub fn draw_text_label_mut(
     }
 }
 
+/// A ball `diameter_px` across: a disc of `color`, or a white one banded with it when
+/// `striped`, outlined, with `label` in a white spot. It is drawn at four times the size and
+/// shrunk, to smooth its edges.
+pub fn draw_ball_sprite(
+    diameter_px: u32,
+    color: Rgba<u8>,
+    striped: bool,
+    label: Option<&str>,
+) -> RgbaImage {
+    const SUPERSAMPLE: u32 = 4;
+    const BALL_WHITE: Rgba<u8> = Rgba([248, 244, 232, 255]);
+    const INK: Rgba<u8> = Rgba([17, 17, 17, 255]);
+
+    let size = diameter_px.max(1) * SUPERSAMPLE;
+    let radius = size as f32 / 2.0;
+    let outline = (radius * 0.06).max(SUPERSAMPLE as f32);
+    let band = radius * 0.55;
+    let spot = radius * 0.42;
+    let mut sprite = RgbaImage::new(size, size);
+    for (x, y, pixel) in sprite.enumerate_pixels_mut() {
+        let (dx, dy) = (x as f32 + 0.5 - radius, y as f32 + 0.5 - radius);
+        let distance = dx.hypot(dy);
+        if distance > radius {
+            continue;
+        }
+        *pixel = if distance > radius - outline {
+            INK
+        } else if label.is_some() && distance <= spot {
+            if distance > spot - outline / 2.0 {
+                INK
+            } else {
+                BALL_WHITE
+            }
+        } else if striped && dy.abs() > band {
+            BALL_WHITE
+        } else {
+            color
+        };
+        // The same faint ring the vector balls have.
+        if (distance - radius * 0.72).abs() < radius * 0.05 {
+            *pixel = interpolate(Rgba([255, 255, 255, 255]), *pixel, 0.45);
+        }
+    }
+
+    if let Some(label) = label {
+        let scale_px = ((spot * 1.1 / 7.0).floor() as u32).max(1);
+        let center = (size / 2) as i32;
+        draw_text_at_px_mut(
+            &mut sprite,
+            center - text_width_px(label, scale_px) as i32 / 2,
+            center - 7 * scale_px as i32 / 2,
+            label,
+            scale_px,
+            INK,
+        );
+    }
+
+    resize(
+        &sprite,
+        diameter_px.max(1),
+        diameter_px.max(1),
+        FilterType::Triangle,
+    )
+}
+
+/// Draw `text` in a filled disc centered on a table position.
+pub fn draw_circled_text_mut(
+    img: &mut RgbaImage,
+    center: &Position,
+    text: &str,
+    radius_px: f32,
+    scale_px: u32,
+    fill_color: Rgba<u8>,
+    text_color: Rgba<u8>,
+) {
+    draw_filled_circle_marker_mut(img, center, radius_px, fill_color);
+
+    let text_width = text_width_px(text, scale_px) as i32;
+    let scale = scale_px as i32;
+    draw_text_label_mut(
+        img,
+        center,
+        text,
+        -text_width / 2,
+        -7 * scale / 2,
+        scale_px,
+        text_color,
+    );
+}
+
+/// Draw a legend box whose top-left corner sits on a table position: one row per entry, a
+/// swatch of the entry's line or fill followed by its text.
+pub fn draw_legend_mut(
+    img: &mut RgbaImage,
+    anchor: &Position,
+    entries: &[LegendEntry],
+    style: &LegendStyle,
+) {
+    if entries.is_empty() {
+        return;
+    }
+
+    let (left, top) = crate::assets::diamond_to_pixel(anchor);
+    let layout = LegendLayout::new(entries, style);
+    fill_rect_alpha_mut(
+        img,
+        (left, top),
+        (layout.width_px, layout.height_px),
+        style.background_color,
+    );
+
+    for (row, entry) in entries.iter().enumerate() {
+        let (swatch_left, row_center) = layout.row_origin(row);
+        let swatch_start = (left + swatch_left, top + row_center);
+        let swatch_end = (swatch_start.0 + LEGEND_SWATCH_PX, swatch_start.1);
+        match entry.swatch {
+            LegendSwatch::Solid(color) => {
+                draw_antialiased_thick_line_segment_mut(img, swatch_start, swatch_end, 4.0, color);
+            }
+            LegendSwatch::Dashed(color) => {
+                for dash in (0..LEGEND_SWATCH_PX).step_by(8) {
+                    draw_antialiased_thick_line_segment_mut(
+                        img,
+                        (swatch_start.0 + dash, swatch_start.1),
+                        (
+                            swatch_start.0 + (dash + 4).min(LEGEND_SWATCH_PX),
+                            swatch_start.1,
+                        ),
+                        3.0,
+                        color,
+                    );
+                }
+            }
+            LegendSwatch::Fill(color) => {
+                let half = LEGEND_SWATCH_PX / 4;
+                fill_rect_alpha_mut(
+                    img,
+                    (swatch_start.0, swatch_start.1 - half),
+                    (LEGEND_SWATCH_PX as u32, 2 * half as u32),
+                    color,
+                );
+            }
+        }
+        draw_text_label_mut(
+            img,
+            anchor,
+            &entry.text,
+            layout.text_left_px,
+            row_center - 7 * style.scale_px as i32 / 2,
+            style.scale_px,
+            style.text_color,
+        );
+    }
+}
+
+/// How long a legend swatch is, in pixels.
+pub const LEGEND_SWATCH_PX: i32 = 32;
+
+/// Where the pieces of a legend go, in pixels from its top-left corner.
+pub struct LegendLayout {
+    pub width_px: u32,
+    pub height_px: u32,
+    pub text_left_px: i32,
+    row_height_px: i32,
+    padding_px: i32,
+}
+
+impl LegendLayout {
+    pub fn new(entries: &[LegendEntry], style: &LegendStyle) -> Self {
+        let padding_px = style.padding_px as i32;
+        let row_height_px = 10 * style.scale_px.max(1) as i32;
+        let text_left_px = padding_px + LEGEND_SWATCH_PX + padding_px;
+        let text_width = entries
+            .iter()
+            .map(|entry| text_width_px(&entry.text, style.scale_px))
+            .max()
+            .unwrap_or(0);
+
+        Self {
+            width_px: (text_left_px + padding_px) as u32 + text_width,
+            height_px: (2 * padding_px + row_height_px * entries.len() as i32) as u32,
+            text_left_px,
+            row_height_px,
+            padding_px,
+        }
+    }
+
+    /// The left edge of the swatch and the vertical center of `row`.
+    pub fn row_origin(&self, row: usize) -> (i32, i32) {
+        (
+            self.padding_px,
+            self.padding_px + self.row_height_px * row as i32 + self.row_height_px / 2,
+        )
+    }
+}
+
+fn fill_rect_alpha_mut(img: &mut RgbaImage, origin: (i32, i32), size: (u32, u32), color: Rgba<u8>) {
+    if color[3] == 0 {
+        return;
+    }
+
+    for y in origin.1..origin.1 + size.1 as i32 {
+        for x in origin.0..origin.0 + size.0 as i32 {
+            blend_pixel(img, x, y, color);
+        }
+    }
+}
+
+/// How far outside the playfield the diamond grid's numbers sit, in diamonds.
+const DIAMOND_GRID_LABEL_MARGIN: &str = "-0.17";
+
+/// Each grid line through the diamonds as `(number, rail end, far end, label center)`: columns
+/// are numbered along the bottom rail and rows along the left rail.
+pub fn diamond_grid_lines() -> Vec<(u8, Position, Position, Position)> {
+    let columns = (0..=4u8).map(|x| {
+        (
+            x,
+            Position::new(x, 0u8),
+            Position::new(x, 8u8),
+            Position::new(x, DIAMOND_GRID_LABEL_MARGIN),
+        )
+    });
+    let rows = (0..=8u8).map(|y| {
+        (
+            y,
+            Position::new(0u8, y),
+            Position::new(4u8, y),
+            Position::new(DIAMOND_GRID_LABEL_MARGIN, y),
+        )
+    });
+    columns.chain(rows).collect()
+}
+
+/// Draw faint lines through every diamond with the diamond numbers along the bottom and left
+/// rails.
+pub fn draw_diamond_grid_mut(img: &mut RgbaImage, style: &DiamondGridStyle) {
+    let half_width = style.width_px as i32 / 2;
+    for (diamond, from, to, label) in diamond_grid_lines() {
+        let (x0, y0) = crate::assets::diamond_to_pixel(&from);
+        let (x1, y1) = crate::assets::diamond_to_pixel(&to);
+        let origin = (x0.min(x1) - half_width, y0.min(y1) - half_width);
+        let size = (
+            (x1 - x0).unsigned_abs() + style.width_px,
+            (y1 - y0).unsigned_abs() + style.width_px,
+        );
+        fill_rect_alpha_mut(img, origin, size, style.line_color);
+
+        draw_circled_text_mut(
+            img,
+            &label,
+            &diamond.to_string(),
+            style.label_radius_px,
+            style.scale_px,
+            style.label_fill_color,
+            style.label_text_color,
+        );
+    }
+}
+
 /// Draw a translucent ghost-ball marker with a dotted outline at a table position.
 pub fn draw_ghost_ball_mut(
     img: &mut RgbaImage,
@@ -347,6 +955,61 @@ mod tests {
         assert!(changed_pixel_count(&image) > 0);
     }
 
+    #[test]
+    fn given_a_slanted_overlay_line_when_drawing_then_its_edges_are_partly_covered() {
+        let mut image = RgbaImage::new(1089, 1938);
+
+        draw_dashed_line_thick_mut(
+            &mut image,
+            &Position::new(1u8, 3u8),
+            &Position::new(3u8, 4u8),
+            1000.0,
+            0.0,
+            4.0,
+            Rgba([0, 255, 0, 255]),
+        );
+
+        let alphas: Vec<u8> = image.pixels().map(|pixel| pixel[3]).collect();
+        assert!(alphas.contains(&255));
+        assert!(alphas.iter().any(|&alpha| alpha > 0 && alpha < 255));
+    }
+
+    #[test]
+    fn given_a_circle_marker_when_drawing_then_only_its_edge_is_partly_covered() {
+        let mut image = RgbaImage::new(1089, 1938);
+
+        draw_filled_circle_marker_mut(
+            &mut image,
+            &Position::new(2u8, 4u8),
+            6.5,
+            Rgba([255, 0, 0, 255]),
+        );
+
+        assert_eq!(image.get_pixel(539, 969)[3], 255);
+        assert_eq!(image.get_pixel(539 + 6, 969)[3], 128);
+        assert_eq!(image.get_pixel(539 + 7, 969)[3], 0);
+    }
+
+    #[test]
+    fn given_an_arrowhead_when_drawing_then_only_pixels_behind_the_tip_are_colored() {
+        let mut image = RgbaImage::new(1089, 1938);
+
+        draw_arrowhead_mut(
+            &mut image,
+            &Position::new(1u8, 4u8),
+            &Position::new(3u8, 4u8),
+            18.0,
+            12.0,
+            Rgba([255, 0, 0, 255]),
+        );
+
+        assert!(changed_pixel_count(&image) > 0);
+        assert!(image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| **pixel != Rgba([0, 0, 0, 0]))
+            .all(|(x, _, _)| (736..=754).contains(&x)));
+    }
+
     #[test]
     fn given_a_smooth_polyline_with_two_points_when_drawing_then_some_pixels_are_colored() {
         let mut image = RgbaImage::new(1089, 1938);
@@ -405,6 +1068,99 @@ mod tests {
         assert!(changed_pixel_count(&image) > 0);
     }
 
+    #[test]
+    fn given_circled_text_when_drawing_then_the_digits_are_centered_in_the_disc() {
+        let mut image = RgbaImage::new(1089, 1938);
+        let text_color = Rgba([255, 0, 0, 255]);
+
+        draw_circled_text_mut(
+            &mut image,
+            &Position::new(2u8, 4u8),
+            "12",
+            14.0,
+            2,
+            Rgba([255, 255, 255, 255]),
+            text_color,
+        );
+
+        let text_pixels: Vec<(u32, u32)> = image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| **pixel == text_color)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        let min_x = text_pixels.iter().map(|p| p.0).min().unwrap();
+        let max_x = text_pixels.iter().map(|p| p.0).max().unwrap();
+        let min_y = text_pixels.iter().map(|p| p.1).min().unwrap();
+        let max_y = text_pixels.iter().map(|p| p.1).max().unwrap();
+        assert!(((min_x + max_x) as i32 / 2 - 539).abs() <= 1);
+        assert!(((min_y + max_y) as i32 / 2 - 969).abs() <= 1);
+    }
+
+    #[test]
+    fn given_a_quarter_arc_when_drawing_then_only_its_quadrant_is_colored() {
+        let mut image = RgbaImage::new(1089, 1938);
+
+        draw_arc_mut(
+            &mut image,
+            &Position::new(2u8, 4u8),
+            40.0,
+            0.0,
+            std::f32::consts::FRAC_PI_2,
+            2.0,
+            Rgba([255, 0, 0, 255]),
+        );
+
+        assert!(changed_pixel_count(&image) > 0);
+        assert!(image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| **pixel != Rgba([0, 0, 0, 0]))
+            .all(|(x, y, _)| x >= 537 && y >= 967));
+    }
+
+    #[test]
+    fn given_a_filled_polygon_when_drawing_then_exactly_its_interior_is_colored() {
+        let mut image = RgbaImage::new(1089, 1938);
+
+        draw_filled_polygon_alpha_mut(
+            &mut image,
+            &[
+                Position::new(0u8, 6u8),
+                Position::new(4u8, 6u8),
+                Position::new(4u8, 8u8),
+                Position::new(0u8, 8u8),
+            ],
+            Rgba([255, 255, 0, 128]),
+        );
+
+        // The kitchen spans 858 x 430 pixels of the playfield.
+        assert_eq!(changed_pixel_count(&image), 858 * 430);
+        assert_eq!(*image.get_pixel(539, 300), Rgba([255, 255, 0, 128]));
+        assert_eq!(*image.get_pixel(539, 600), Rgba([0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn given_a_legend_when_drawing_then_its_box_fits_the_longest_entry() {
+        let mut image = RgbaImage::new(1089, 1938);
+        let entries = [
+            LegendEntry::new(LegendSwatch::Solid(Rgba([255, 0, 0, 255])), "object ball"),
+            LegendEntry::new(LegendSwatch::Dashed(Rgba([0, 0, 255, 255])), "cue ball"),
+        ];
+        let style = LegendStyle::default();
+
+        draw_legend_mut(&mut image, &Position::new(0u8, 8u8), &entries, &style);
+
+        let layout = LegendLayout::new(&entries, &style);
+        assert_eq!(
+            layout.width_px,
+            3 * style.padding_px + LEGEND_SWATCH_PX as u32 + text_width_px("object ball", 2)
+        );
+        assert_eq!(
+            changed_pixel_count(&image),
+            (layout.width_px * layout.height_px) as usize
+        );
+        assert_eq!(*image.get_pixel(110, 110), style.background_color);
+    }
+
     #[test]
     fn given_a_numeric_text_label_when_drawing_then_some_pixels_are_colored() {
         let mut image = RgbaImage::new(1089, 1938);
@@ -0,0 +1,458 @@
+//! Canned layouts for practice drills, and drills shipped as data files.
+//!
+//! The geometry comes from the idealized aids in [`crate::aiming`] and [`crate::banking`]: lines
+//! are ball-center paths, and cushion contacts are mirror-table reflections.
+
+use crate::aiming::{cue_ball_departure_line, outline_contains, CaromCueAction};
+use crate::banking::single_rail_bank;
+use crate::pdf::{self, PdfPageLayout};
+#[cfg(feature = "serde")]
+use crate::schema::{read_toml, Document};
+use crate::sheet::Sheet;
+use crate::{
+    Angle, Ball, BallSpec, BallType, CutAngle, DiagramRenderOptions, GameState, InchesPerSecond,
+    Pocket, Position, Rail, Segment, TableSpec, RACK_SPOT,
+};
+use image::{Rgba, RgbaImage};
+
+/// A practice drill: a layout with its annotations, the zones the cue ball should finish in, and
+/// what counts as success. With the `serde` feature a drill is read from TOML:
+///
+/// ```toml
+/// schema_version = 2
+/// title = "Stop shot"
+/// description = "Pot the one and leave the cue ball where the one was."
+///
+/// [[layout.balls]]
+/// ty = "Cue"
+/// position = { x = "2", y = "2" }
+///
+/// [[layout.balls]]
+/// ty = "One"
+/// position = { x = "2", y = "5" }
+///
+/// [[target_zones]]
+/// name = "stop"
+/// outline = [{ x = "1.75", y = "4.75" }, { x = "2.25", y = "4.75" }, { x = "2.25", y = "5.25" }, { x = "1.75", y = "5.25" }]
+///
+/// [[success]]
+/// goal = "pot"
+/// ball = "One"
+/// pocket = "TopLeft"
+///
+/// [[success]]
+/// goal = "cue_ball_in_zone"
+/// zone = "stop"
+///
+/// [[success]]
+/// goal = "make_rate"
+/// made = 7
+/// attempts = 10
+/// ```
+///
+/// The layout has the shape [`GameState::to_json`] writes, so it can carry annotations too.
+/// Files of an earlier [`crate::schema`] version are migrated as they are read.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Drill {
+    pub title: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub description: String,
+    pub layout: GameState,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub target_zones: Vec<TargetZone>,
+    /// Every goal must be met for an attempt to count.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub success: Vec<DrillGoal>,
+}
+
+/// A named region of the table, a closed polygon without a repeated end point.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TargetZone {
+    pub name: String,
+    pub outline: Vec<Position>,
+}
+
+impl TargetZone {
+    /// Whether `position` lies inside the zone.
+    pub fn contains(&self, position: &Position, table_spec: &TableSpec) -> bool {
+        outline_contains(&self.outline, position, table_spec)
+    }
+}
+
+/// One thing a drill asks for.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "goal", rename_all = "snake_case"))]
+pub enum DrillGoal {
+    /// Pot `ball`, in `pocket` if one is named.
+    Pot {
+        ball: BallType,
+        #[cfg_attr(feature = "serde", serde(default))]
+        pocket: Option<Pocket>,
+    },
+    /// Leave the cue ball in the target zone called `zone`.
+    CueBallInZone { zone: String },
+    /// Succeed at least `made` times in `attempts`.
+    MakeRate { made: u32, attempts: u32 },
+}
+
+impl Drill {
+    /// Read a drill from TOML, checking that its goals only name zones it has.
+    #[cfg(feature = "serde")]
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let mut drill: Self = read_toml(Document::Drill, text)?;
+        drill.finish_loading()?;
+        Ok(drill)
+    }
+
+    #[cfg(feature = "serde")]
+    fn finish_loading(&mut self) -> Result<(), String> {
+        self.layout.fill_missing_layer_names();
+        for goal in &self.success {
+            match goal {
+                DrillGoal::CueBallInZone { zone } if self.zone(zone).is_none() => {
+                    return Err(format!("goal names unknown target zone '{zone}'"));
+                }
+                DrillGoal::MakeRate { made, attempts } if made > attempts => {
+                    return Err(format!("goal asks for {made} makes in {attempts} attempts"));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the drill in the TOML file at `path`.
+    #[cfg(feature = "serde")]
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Self::from_toml(&text).map_err(|message| format!("{}: {message}", path.display()))
+    }
+
+    /// The target zone called `name`.
+    pub fn zone(&self, name: &str) -> Option<&TargetZone> {
+        self.target_zones.iter().find(|zone| zone.name == name)
+    }
+
+    /// The layout with its target zones shaded beneath the balls, ready to draw.
+    pub fn diagram(&self) -> GameState {
+        let mut state = self.layout.clone();
+        for zone in &self.target_zones {
+            state.add_shaded_zone(&zone.outline, Rgba([255, 215, 0, 72]));
+        }
+        state
+    }
+}
+
+/// A line the cue ball could follow from the ghost ball into a corner pocket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScratchLine {
+    pub pocket: Pocket,
+    /// The cushions the line reflects off, in order; empty for a direct scratch.
+    pub rails: Vec<Rail>,
+    /// The cue-ball center path from the ghost ball to the pocket's aiming center.
+    pub path: Vec<Position>,
+}
+
+impl ScratchLine {
+    /// The heading the cue ball must leave the ghost ball on to follow this line.
+    pub fn heading(&self) -> Angle {
+        self.path[0].angle_to(&self.path[1])
+    }
+}
+
+/// The classic spot shot: the object ball on the foot spot and the cue ball in the kitchen,
+/// shooting into one of the two foot corner pockets.
+#[derive(Clone, Debug)]
+pub struct SpotShot {
+    pub cue_ball: Ball,
+    pub object_ball: Ball,
+    pub pocket: Pocket,
+    pub ghost_ball: Position,
+    pub cut_angle: CutAngle,
+    /// From the cue ball to the ghost ball.
+    pub aim_line: Segment,
+    /// From the object ball to the target in the pocket that the ghost ball sends it to.
+    pub object_ball_line: Segment,
+    /// Where a stunned cue ball goes after contact, up to the first cushion.
+    pub tangent_line: Segment,
+    /// Every direct and one-long-rail line from the ghost ball into a corner pocket that the cue
+    /// ball can leave on with some mix of follow, stun, and draw.
+    pub scratch_lines: Vec<ScratchLine>,
+}
+
+impl SpotShot {
+    /// Return a layout with just the two balls, ready to annotate as a drill.
+    pub fn game_state(&self, table_spec: TableSpec) -> GameState {
+        GameState::with_balls(
+            table_spec,
+            [self.cue_ball.clone(), self.object_ball.clone()],
+        )
+    }
+
+    /// Return the scratch line whose departure from the ghost ball is closest to `heading`, with
+    /// the angle between them in degrees.
+    pub fn nearest_scratch_line(&self, heading: &Angle) -> Option<(&ScratchLine, f64)> {
+        self.scratch_lines
+            .iter()
+            .map(|line| {
+                let difference = (line.heading().as_degrees() - heading.as_degrees() + 540.0)
+                    .rem_euclid(360.0)
+                    - 180.0;
+                (line, difference.abs())
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+/// Lay out a spot shot from `cue_ball` into `pocket`, which must be a foot corner pocket.
+///
+/// The object ball is the 1 ball on [`RACK_SPOT`], the foot spot. Returns `None` when `cue_ball` is
+/// not [in the kitchen](Position::is_in_kitchen), or `pocket` is not `BottomLeft` or `BottomRight`.
+pub fn spot_shot(cue_ball: &Position, pocket: Pocket, table_spec: &TableSpec) -> Option<SpotShot> {
+    if !cue_ball.is_in_kitchen(table_spec)
+        || !matches!(pocket, Pocket::BottomLeft | Pocket::BottomRight)
+    {
+        return None;
+    }
+
+    let cue_ball = Ball {
+        ty: BallType::Cue,
+        position: cue_ball.clone(),
+        spec: BallSpec::default(),
+    };
+    let object_ball = Ball {
+        ty: BallType::One,
+        position: RACK_SPOT.clone(),
+        spec: BallSpec::default(),
+    };
+    let ghost_ball = object_ball.ghost_ball_to_pocket(pocket, table_spec);
+    let cut_angle = CutAngle::from_headings(
+        cue_ball.position.angle_to(&ghost_ball),
+        ghost_ball.angle_to(&object_ball.position),
+    );
+    let tangent_line = cue_ball_departure_line(
+        &cue_ball.position,
+        &ghost_ball,
+        &object_ball,
+        CaromCueAction::Stun,
+        table_spec,
+    );
+    let scratch_lines = scratch_lines(
+        &cue_ball.position.angle_to(&ghost_ball),
+        &ghost_ball,
+        &object_ball,
+        table_spec,
+    );
+
+    Some(SpotShot {
+        aim_line: Segment::new(cue_ball.position.clone(), ghost_ball.clone()),
+        object_ball_line: Segment::new(
+            object_ball.position.clone(),
+            object_ball.pocket_target_center(pocket, InchesPerSecond::zero(), table_spec),
+        ),
+        cue_ball,
+        object_ball,
+        pocket,
+        ghost_ball,
+        cut_angle,
+        tangent_line,
+        scratch_lines,
+    })
+}
+
+/// Drills gathered into chapters for publishing, e.g. a season of practice sessions. With the
+/// `serde` feature a book is read from TOML, each drill written as in [`Drill`] with its book
+/// metadata beside it:
+///
+/// ```toml
+/// schema_version = 2
+/// title = "Position play"
+///
+/// [[chapters]]
+/// title = "Stop and stun"
+///
+/// [[chapters.drills]]
+/// caption = "Stop it dead"
+/// difficulty = "beginner"
+/// tags = ["stop"]
+/// title = "Stop shot"
+/// layout = { balls = [{ ty = "Cue", position = { x = "2", y = "2" } }, { ty = "One", position = { x = "2", y = "5" } }] }
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DrillBook {
+    pub title: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chapter {
+    pub title: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub drills: Vec<BookDrill>,
+}
+
+/// A drill as it appears in a book.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BookDrill {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub drill: Drill,
+    /// Printed under the diagram; the drill's title when empty.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub caption: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub difficulty: Option<Difficulty>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tags: Vec<String>,
+}
+
+impl BookDrill {
+    /// The caption printed under the diagram.
+    pub fn caption(&self) -> &str {
+        if self.caption.is_empty() {
+            &self.drill.title
+        } else {
+            &self.caption
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+
+impl DrillBook {
+    /// Read a book from TOML, checking each drill as [`Drill::from_toml`] does.
+    #[cfg(feature = "serde")]
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let mut book: Self = read_toml(Document::DrillBook, text)?;
+        for chapter in &mut book.chapters {
+            for entry in &mut chapter.drills {
+                entry
+                    .drill
+                    .finish_loading()
+                    .map_err(|message| format!("drill '{}': {message}", entry.drill.title))?;
+            }
+        }
+        Ok(book)
+    }
+
+    /// Read the book in the TOML file at `path`.
+    #[cfg(feature = "serde")]
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Self::from_toml(&text).map_err(|message| format!("{}: {message}", path.display()))
+    }
+
+    /// Every drill in reading order.
+    pub fn drills(&self) -> impl Iterator<Item = &BookDrill> {
+        self.chapters.iter().flat_map(|chapter| &chapter.drills)
+    }
+
+    /// The drills tagged `tag`, in reading order.
+    pub fn tagged<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a BookDrill> {
+        self.drills()
+            .filter(move |entry| entry.tags.iter().any(|t| t == tag))
+    }
+
+    /// One sheet per chapter, `columns` diagrams wide, each drill drawn with its target zones and
+    /// captioned, and the chapter title under the grid.
+    pub fn to_sheets(&self, columns: u32, options: &DiagramRenderOptions) -> Vec<Sheet> {
+        self.chapters
+            .iter()
+            .map(|chapter| {
+                let mut sheet = Sheet::new(columns).with_caption(&chapter.title);
+                for entry in &chapter.drills {
+                    sheet.push(
+                        entry.drill.diagram().to_diagram_scene(options),
+                        entry.caption(),
+                    );
+                }
+                sheet
+            })
+            .collect()
+    }
+
+    /// The book as a PDF with one page per chapter.
+    pub fn render_to_pdf(
+        &self,
+        columns: u32,
+        options: &DiagramRenderOptions,
+        layout: &PdfPageLayout,
+    ) -> Vec<u8> {
+        let pages: Vec<RgbaImage> = self
+            .to_sheets(columns, options)
+            .iter()
+            .map(|sheet| sheet.render_to_image(options))
+            .collect();
+        pdf::images_to_pdf(&pages, layout)
+    }
+}
+
+/// Read a drill or drill book from TOML, migrated to the current schema version.
+const CORNER_POCKETS: [Pocket; 4] = [
+    Pocket::TopRight,
+    Pocket::BottomRight,
+    Pocket::BottomLeft,
+    Pocket::TopLeft,
+];
+
+fn scratch_lines(
+    aim: &Angle,
+    ghost_ball: &Position,
+    object_ball: &Ball,
+    table_spec: &TableSpec,
+) -> Vec<ScratchLine> {
+    let cue_ball = Ball {
+        ty: BallType::Cue,
+        position: ghost_ball.clone(),
+        spec: BallSpec::default(),
+    };
+    // Follow can bend the cue ball back toward its aim line and draw can pull it past the tangent
+    // line, but it can never end up heading between the aim line and the object ball, or on
+    // around the object ball's side as far as the tangent line there.
+    let signed_degrees = |degrees: f64| (degrees + 540.0).rem_euclid(360.0) - 180.0;
+    let to_object_ball =
+        signed_degrees(ghost_ball.angle_to(&object_ball.position).as_degrees() - aim.as_degrees());
+    let reachable = |path: &[Position]| {
+        let heading = signed_degrees(path[0].angle_to(&path[1]).as_degrees() - aim.as_degrees());
+        heading * to_object_ball <= 0.0 || heading.abs() >= to_object_ball.abs() + 90.0
+    };
+
+    let mut lines = Vec::new();
+    for pocket in CORNER_POCKETS {
+        let direct = vec![ghost_ball.clone(), pocket.aiming_center()];
+        if reachable(&direct) {
+            lines.push(ScratchLine {
+                pocket,
+                rails: Vec::new(),
+                path: direct,
+            });
+        }
+        for rail in [Rail::Left, Rail::Right] {
+            if let Some(bank) = single_rail_bank(&cue_ball, pocket, rail, table_spec) {
+                if reachable(&bank.path) {
+                    lines.push(ScratchLine {
+                        pocket,
+                        rails: vec![rail],
+                        path: bank.path,
+                    });
+                }
+            }
+        }
+    }
+    lines
+}
@@ -12,8 +12,8 @@ use crate::{
     simulate_n_balls_with_physics_and_pockets_on_table_until_rest, strike_resting_ball_on_table,
     trace_ball_path_with_rail_profile_on_table,
     visualization::{
-        BallPathRenderOptions, BallPathWidthMode, EventMarkerStyle, GhostBallStyle,
-        LabelOverlayStyle, PathColorMode,
+        ArrowStyle, BallPathRenderOptions, BallPathWidthMode, EventMarkerStyle, GhostBallStyle,
+        LabelOverlayStyle, PathColorMode, SmoothPolylineStyle,
     },
     Angle, Ball, BallBallCollisionConfig, BallPath, BallPathSegment, BallPathStop,
     BallSetPhysicsSpec, BallSpec, BallState, BallType, CollisionModel, CueStrikeConfig,
@@ -27,11 +27,13 @@ use crate::{
 };
 use image::Rgba;
 use winnow::ascii::{float, line_ending, till_line_ending};
-use winnow::combinator::{alt, cut_err, delimited, eof, opt, peek, preceded, repeat, terminated};
+use winnow::combinator::{
+    alt, cut_err, delimited, eof, not, opt, peek, preceded, repeat, terminated,
+};
 use winnow::error::{ErrMode, InputError};
 use winnow::prelude::*;
 use winnow::stream::{LocatingSlice, Location};
-use winnow::token::take_while;
+use winnow::token::{take_till, take_while};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct DslDoc {
@@ -50,6 +52,7 @@ pub enum DslEntry {
     Rails(RailsDef),
     Simulation(SimulationDef),
     Shot(ShotDef),
+    Annotation(AnnotationDef),
 }
 
 #[derive(Debug, Clone)]
@@ -688,6 +691,8 @@ pub struct ScenarioShotTrace {
 pub struct ScenarioTraceRenderOptions {
     pub path_render: BallPathRenderOptions,
     pub start_ghost_balls: bool,
+    /// Draw each ball that moved see-through where it started, as well as where it stopped.
+    pub start_phantom_balls: bool,
     pub event_markers: bool,
     pub labels: bool,
     pub path_color_mode: PathColorMode,
@@ -698,6 +703,7 @@ impl Default for ScenarioTraceRenderOptions {
         Self {
             path_render: BallPathRenderOptions::default(),
             start_ghost_balls: false,
+            start_phantom_balls: false,
             event_markers: false,
             labels: false,
             path_color_mode: PathColorMode::Solid,
@@ -715,6 +721,10 @@ impl ScenarioTraceRenderOptions {
     }
 }
 
+/// The named layer that [`ScenarioShotTrace::rendered_final_layout_with_trace_options`] draws the
+/// traced paths in, so the final layout can also be drawn without them.
+pub const TRACE_LAYER: &str = "trace";
+
 const SCENARIO_TRACE_TIME_DISPLAY_DECIMALS: usize = 6;
 // The current event scheduler intentionally breaks ties deterministically instead of producing a
 // composite simultaneous event, so break-style cluster contacts can arrive as back-to-back entries
@@ -774,6 +784,7 @@ impl ScenarioShotTrace {
         options: &ScenarioTraceRenderOptions,
     ) -> GameState {
         let mut game_state = scenario.game_state_for_system_states(&self.simulation.states);
+        game_state.set_named_layer(Some(TRACE_LAYER));
         for ball_trace in &self.ball_traces {
             let trace_color = ball_trace_color(&ball_trace.ball);
             let mut path_style = crate::visualization::BallPathStyle::new(trace_color)
@@ -797,6 +808,15 @@ impl ScenarioShotTrace {
             };
 
             if let Some(path) = ball_trace.as_ball_path() {
+                if options.start_phantom_balls {
+                    game_state.add_phantom_ball(
+                        ball_trace.ball.clone(),
+                        &path
+                            .initial_state
+                            .as_ball_state()
+                            .projected_position(&scenario.game_state.table_spec),
+                    );
+                }
                 game_state.add_rendered_ball_path_styled(
                     &path,
                     &self.ball_set,
@@ -831,6 +851,7 @@ impl ScenarioShotTrace {
                 }
             }
         }
+        game_state.set_named_layer(None);
         game_state
     }
 }
@@ -1187,6 +1208,12 @@ fn ball_type_name(ball: &BallType) -> &'static str {
         BallType::Seven => "seven",
         BallType::Eight => "eight",
         BallType::Nine => "nine",
+        BallType::Ten => "ten",
+        BallType::Eleven => "eleven",
+        BallType::Twelve => "twelve",
+        BallType::Thirteen => "thirteen",
+        BallType::Fourteen => "fourteen",
+        BallType::Fifteen => "fifteen",
     }
 }
 
@@ -1221,12 +1248,12 @@ fn ball_trace_color(ball: &BallType) -> Rgba<u8> {
     match ball {
         BallType::Cue => Rgba([225, 225, 225, 255]),
         BallType::One | BallType::Nine => Rgba([255, 215, 0, 255]),
-        BallType::Two => Rgba([65, 105, 225, 255]),
-        BallType::Three => Rgba([220, 20, 60, 255]),
-        BallType::Four => Rgba([138, 43, 226, 255]),
-        BallType::Five => Rgba([255, 140, 0, 255]),
-        BallType::Six => Rgba([34, 139, 34, 255]),
-        BallType::Seven => Rgba([128, 0, 0, 255]),
+        BallType::Two | BallType::Ten => Rgba([65, 105, 225, 255]),
+        BallType::Three | BallType::Eleven => Rgba([220, 20, 60, 255]),
+        BallType::Four | BallType::Twelve => Rgba([138, 43, 226, 255]),
+        BallType::Five | BallType::Thirteen => Rgba([255, 140, 0, 255]),
+        BallType::Six | BallType::Fourteen => Rgba([34, 139, 34, 255]),
+        BallType::Seven | BallType::Fifteen => Rgba([128, 0, 0, 255]),
         BallType::Eight => Rgba([32, 32, 32, 255]),
     }
 }
@@ -1368,6 +1395,36 @@ pub enum ShotMethodExpr {
     Using(String),
 }
 
+/// A mark drawn on the diagram, such as `line cue -> ghost(eight, top-right)`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnnotationDef {
+    Line {
+        from: AnnotationPoint,
+        to: AnnotationPoint,
+    },
+    Arrow {
+        from: AnnotationPoint,
+        to: AnnotationPoint,
+    },
+    Label {
+        text: String,
+        at: AnnotationPoint,
+    },
+}
+
+/// Where an annotation starts, ends or sits.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnnotationPoint {
+    Position(PositionExpr),
+    /// The center of a placed ball.
+    Ball(BallRef),
+    /// Where the cue ball must be to pot `object_ball` in `pocket`; the ghost ball is drawn too.
+    Ghost {
+        object_ball: BallRef,
+        pocket: Pocket,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq)]
 enum BallPlacementKind {
     At(PositionExpr),
@@ -1391,6 +1448,12 @@ pub enum BallRef {
     Seven,
     Eight,
     Nine,
+    Ten,
+    Eleven,
+    Twelve,
+    Thirteen,
+    Fourteen,
+    Fifteen,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1537,6 +1600,7 @@ pub enum DslBuildError {
     ShotTargetBallNotPlaced(BallRef),
     ShotAimingBallMustNotBeCueBall(BallRef),
     ShotAimingBallNotPlaced(BallRef),
+    AnnotationBallNotPlaced(BallRef),
     InvalidCueStrikeConfig {
         name: String,
         error: ShotError,
@@ -1667,6 +1731,9 @@ impl std::fmt::Display for DslBuildError {
             Self::ShotAimingBallNotPlaced(ball) => {
                 write!(f, "shot aiming ball '{ball}' is not present in the layout")
             }
+            Self::AnnotationBallNotPlaced(ball) => {
+                write!(f, "annotated ball '{ball}' is not present in the layout")
+            }
             Self::InvalidCueStrikeConfig { name, error } => {
                 write!(f, "cue_strike '{name}' is invalid: {error:?}")
             }
@@ -1736,6 +1803,7 @@ pub fn build_scenario(doc: &DslDoc) -> Result<DslScenario, DslBuildError> {
     let mut rails_defs = Vec::new();
     let mut simulation_defs = Vec::new();
     let mut shots = Vec::new();
+    let mut annotations = Vec::new();
 
     for entry in &doc.entries {
         match entry {
@@ -1778,8 +1846,10 @@ pub fn build_scenario(doc: &DslDoc) -> Result<DslScenario, DslBuildError> {
             DslEntry::Rails(def) => rails_defs.push(def.clone()),
             DslEntry::Simulation(def) => simulation_defs.push(def.clone()),
             DslEntry::Shot(def) => shots.push(def.clone()),
+            DslEntry::Annotation(def) => annotations.push(def),
         }
     }
+    add_annotations(&mut game_state, &aliases, &annotations)?;
 
     let ball_ball_configs = build_ball_ball_configs(&ball_ball_defs)?;
     let rail_responses = build_rail_responses(&rail_response_defs)?;
@@ -1825,6 +1895,59 @@ fn resolve_position_expr(
     }
 }
 
+fn add_annotations(
+    game_state: &mut GameState,
+    aliases: &HashMap<String, Position>,
+    annotations: &[&AnnotationDef],
+) -> Result<(), DslBuildError> {
+    let color = Rgba([255, 255, 255, 255]);
+    let mut ghosts = Vec::new();
+    let mut resolve = |game_state: &mut GameState, point: &AnnotationPoint| match point {
+        AnnotationPoint::Position(position) => resolve_position_expr(aliases, position),
+        AnnotationPoint::Ball(ball) => game_state
+            .select_ball(ball.to_ball_type())
+            .map(|placed| placed.position.clone())
+            .ok_or(DslBuildError::AnnotationBallNotPlaced(*ball)),
+        AnnotationPoint::Ghost {
+            object_ball,
+            pocket,
+        } => {
+            let ghost = game_state
+                .select_ball(object_ball.to_ball_type())
+                .ok_or(DslBuildError::AnnotationBallNotPlaced(*object_ball))?
+                .ghost_ball_to_pocket(*pocket, &game_state.table_spec);
+            if !ghosts.contains(&(*object_ball, *pocket)) {
+                ghosts.push((*object_ball, *pocket));
+                game_state.add_ghost_ball_styled(&ghost, GhostBallStyle::default());
+            }
+            Ok(ghost)
+        }
+    };
+
+    for annotation in annotations {
+        match annotation {
+            AnnotationDef::Line { from, to } => {
+                let (from, to) = (resolve(game_state, from)?, resolve(game_state, to)?);
+                game_state.add_solid_line(&from, &to, color);
+            }
+            AnnotationDef::Arrow { from, to } => {
+                let (from, to) = (resolve(game_state, from)?, resolve(game_state, to)?);
+                game_state.add_solid_line_styled(
+                    &from,
+                    &to,
+                    SmoothPolylineStyle::new(color).with_arrow(ArrowStyle::default()),
+                );
+            }
+            AnnotationDef::Label { text, at } => {
+                let at = resolve(game_state, at)?;
+                game_state.add_text_label_styled(&at, text, LabelOverlayStyle::enabled(color));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn build_cue_strike(def: &CueStrikeDef) -> Result<CueStrikeConfig, DslBuildError> {
     let mut cue_mass_ratio = None;
     let mut collision_energy_loss = None;
@@ -2570,6 +2693,7 @@ fn dsl_doc<'a>(input: &mut Stream<'a>) -> ParseResult<'a, DslDoc> {
                 DslStatement::Rails(def) => doc.entries.push(DslEntry::Rails(def)),
                 DslStatement::Simulation(def) => doc.entries.push(DslEntry::Simulation(def)),
                 DslStatement::Shot(def) => doc.entries.push(DslEntry::Shot(def)),
+                DslStatement::Annotation(def) => doc.entries.push(DslEntry::Annotation(def)),
                 DslStatement::Empty => {}
             },
         )
@@ -2592,6 +2716,7 @@ enum DslStatement {
     Rails(RailsDef),
     Simulation(SimulationDef),
     Shot(ShotDef),
+    Annotation(AnnotationDef),
     Empty,
 }
 
@@ -2600,6 +2725,7 @@ fn statement<'a>(input: &mut Stream<'a>) -> ParseResult<'a, DslStatement> {
     let stmt = alt((
         comment_line,
         blank_line,
+        ';'.map(|_| DslStatement::Empty),
         preceded(peek("table"), cut_err(table_stmt)),
         preceded(peek("trace"), cut_err(trace_stmt)),
         preceded(peek("pos"), cut_err(alias_stmt)),
@@ -2610,6 +2736,9 @@ fn statement<'a>(input: &mut Stream<'a>) -> ParseResult<'a, DslStatement> {
         preceded(peek("ball"), cut_err(ball_stmt)),
         preceded(peek("cue_strike"), cut_err(cue_strike_stmt)),
         preceded(peek("shot"), cut_err(shot_stmt)),
+        preceded(peek("line"), cut_err(line_stmt)),
+        preceded(peek("arrow"), cut_err(arrow_stmt)),
+        preceded(peek("label"), cut_err(label_stmt)),
     ))
     .parse_next(input)?;
     let _ = hws0.parse_next(input)?;
@@ -2732,6 +2861,70 @@ fn shot_stmt<'a>(input: &mut Stream<'a>) -> ParseResult<'a, DslStatement> {
     Ok(DslStatement::Shot(ShotDef { ball, methods }))
 }
 
+fn line_stmt<'a>(input: &mut Stream<'a>) -> ParseResult<'a, DslStatement> {
+    let _ = "line".parse_next(input)?;
+    let (from, to) = annotation_span.parse_next(input)?;
+    Ok(DslStatement::Annotation(AnnotationDef::Line { from, to }))
+}
+
+fn arrow_stmt<'a>(input: &mut Stream<'a>) -> ParseResult<'a, DslStatement> {
+    let _ = "arrow".parse_next(input)?;
+    let (from, to) = annotation_span.parse_next(input)?;
+    Ok(DslStatement::Annotation(AnnotationDef::Arrow { from, to }))
+}
+
+fn label_stmt<'a>(input: &mut Stream<'a>) -> ParseResult<'a, DslStatement> {
+    let _ = "label".parse_next(input)?;
+    let _ = ws1.parse_next(input)?;
+    let text = delimited('"', take_till(0.., ['"', '\r', '\n']), '"').parse_next(input)?;
+    let _ = (ws1, "at", ws1).parse_next(input)?;
+    let at = annotation_point.parse_next(input)?;
+    Ok(DslStatement::Annotation(AnnotationDef::Label {
+        text: text.to_string(),
+        at,
+    }))
+}
+
+fn annotation_span<'a>(
+    input: &mut Stream<'a>,
+) -> ParseResult<'a, (AnnotationPoint, AnnotationPoint)> {
+    let _ = ws1.parse_next(input)?;
+    let from = annotation_point.parse_next(input)?;
+    let _ = (hws0, "->", hws0).parse_next(input)?;
+    let to = annotation_point.parse_next(input)?;
+    Ok((from, to))
+}
+
+fn annotation_point<'a>(input: &mut Stream<'a>) -> ParseResult<'a, AnnotationPoint> {
+    let point = alt((
+        preceded(
+            "ghost",
+            delimited(
+                ('(', hws0),
+                (terminated(ball_ref, (hws0, ',', hws0)), pocket_ref),
+                (hws0, ')'),
+            ),
+        )
+        .map(|(object_ball, pocket)| AnnotationPoint::Ghost {
+            object_ball,
+            pocket,
+        }),
+        coordinate.map(|(x, y)| AnnotationPoint::Position(PositionExpr::Diamond { x, y })),
+        named_position.map(|named| AnnotationPoint::Position(PositionExpr::Named(named))),
+        terminated(
+            ball_ref,
+            not(take_while(1.., |c: char| {
+                c.is_ascii_alphanumeric() || c == '_'
+            })),
+        )
+        .map(AnnotationPoint::Ball),
+        identifier.map(|name| AnnotationPoint::Position(PositionExpr::Alias(name.to_string()))),
+    ))
+    .parse_next(input)?;
+    let _ = hws0.parse_next(input)?;
+    Ok(point)
+}
+
 fn hws0<'a>(input: &mut Stream<'a>) -> ParseResult<'a, ()> {
     take_while(0.., |c: char| c == ' ' || c == '\t')
         .void()
@@ -3290,6 +3483,8 @@ fn pocket_ref<'a>(input: &mut Stream<'a>) -> ParseResult<'a, Pocket> {
 fn ball_ref<'a>(input: &mut Stream<'a>) -> ParseResult<'a, BallRef> {
     alt((
         "cue".map(|_| BallRef::Cue),
+        // Before "four", which would otherwise match its start.
+        "fourteen".map(|_| BallRef::Fourteen),
         "one".map(|_| BallRef::One),
         "two".map(|_| BallRef::Two),
         "three".map(|_| BallRef::Three),
@@ -3299,6 +3494,34 @@ fn ball_ref<'a>(input: &mut Stream<'a>) -> ParseResult<'a, BallRef> {
         "seven".map(|_| BallRef::Seven),
         "eight".map(|_| BallRef::Eight),
         "nine".map(|_| BallRef::Nine),
+        "ten".map(|_| BallRef::Ten),
+        "eleven".map(|_| BallRef::Eleven),
+        "twelve".map(|_| BallRef::Twelve),
+        "thirteen".map(|_| BallRef::Thirteen),
+        "fifteen".map(|_| BallRef::Fifteen),
+        ball_numeral,
+    ))
+    .parse_next(input)
+}
+
+fn ball_numeral<'a>(input: &mut Stream<'a>) -> ParseResult<'a, BallRef> {
+    alt((
+        // Before "1", which would otherwise match their start.
+        "10".map(|_| BallRef::Ten),
+        "11".map(|_| BallRef::Eleven),
+        "12".map(|_| BallRef::Twelve),
+        "13".map(|_| BallRef::Thirteen),
+        "14".map(|_| BallRef::Fourteen),
+        "15".map(|_| BallRef::Fifteen),
+        "1".map(|_| BallRef::One),
+        "2".map(|_| BallRef::Two),
+        "3".map(|_| BallRef::Three),
+        "4".map(|_| BallRef::Four),
+        "5".map(|_| BallRef::Five),
+        "6".map(|_| BallRef::Six),
+        "7".map(|_| BallRef::Seven),
+        "8".map(|_| BallRef::Eight),
+        "9".map(|_| BallRef::Nine),
     ))
     .parse_next(input)
 }
@@ -3343,6 +3566,12 @@ impl std::fmt::Display for BallRef {
             BallRef::Seven => write!(f, "seven"),
             BallRef::Eight => write!(f, "eight"),
             BallRef::Nine => write!(f, "nine"),
+            BallRef::Ten => write!(f, "ten"),
+            BallRef::Eleven => write!(f, "eleven"),
+            BallRef::Twelve => write!(f, "twelve"),
+            BallRef::Thirteen => write!(f, "thirteen"),
+            BallRef::Fourteen => write!(f, "fourteen"),
+            BallRef::Fifteen => write!(f, "fifteen"),
         }
     }
 }
@@ -3375,6 +3604,12 @@ impl BallRef {
             BallRef::Seven => BallType::Seven,
             BallRef::Eight => BallType::Eight,
             BallRef::Nine => BallType::Nine,
+            BallRef::Ten => BallType::Ten,
+            BallRef::Eleven => BallType::Eleven,
+            BallRef::Twelve => BallType::Twelve,
+            BallRef::Thirteen => BallType::Thirteen,
+            BallRef::Fourteen => BallType::Fourteen,
+            BallRef::Fifteen => BallType::Fifteen,
         }
     }
 }
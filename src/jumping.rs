@@ -0,0 +1,152 @@
+//! Jump-shot feasibility for diagrams with a ball in the way.
+//!
+//! The cue ball is modelled as leaving the slate at the cue elevation and then flying a plain
+//! projectile arc: the slate rebound is treated as lossless and air drag, spin, and the cue ball's
+//! own bounces are ignored. That makes the elevations here lower bounds; a real cue ball comes off
+//! the slate somewhat flatter than the cue.
+
+use crate::{
+    position_xy_inches, Ball, BallType, Inches, InchesPerSecond, Position, TableSpec,
+    STANDARD_GRAVITY_INCHES_PER_SECOND_SQUARED,
+};
+
+/// How much of the blocker sits across the cue ball's line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JumpKind {
+    /// The line passes within one ball radius of the blocker's center, so the cue ball has to
+    /// clear most of its height.
+    FullHop,
+    /// Only the edge of the blocker overlaps the line; a lower arc squeezes past it.
+    ThinEdge,
+}
+
+impl JumpKind {
+    /// A short name for diagram annotations.
+    pub fn label(self) -> &'static str {
+        match self {
+            JumpKind::FullHop => "full hop",
+            JumpKind::ThinEdge => "thin-edge jump",
+        }
+    }
+}
+
+/// The geometry of jumping the cue ball over one blocker.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JumpAnalysis {
+    pub blocker: BallType,
+    pub kind: JumpKind,
+    /// Distance from the cue ball's center to the blocker's, measured along the line of travel.
+    pub distance_to_blocker: Inches,
+    /// Perpendicular distance from the line of travel to the blocker's center.
+    pub lateral_offset: Inches,
+    /// How far the cue ball's center must rise above its resting height while abreast of the
+    /// blocker for the two balls not to touch.
+    pub required_clearance_height: Inches,
+    /// The lowest cue elevation, in degrees, that clears the blocker at the analysed speed, or
+    /// `None` when no elevation does.
+    pub required_elevation_degrees: Option<f64>,
+    contact_distance: f64,
+}
+
+impl JumpAnalysis {
+    /// The smallest gap, in inches, between the airborne cue ball and the blocker for a jump at
+    /// `elevation_degrees` and `cue_ball_speed`. Negative values mean the balls touch.
+    pub fn clearance_at(&self, elevation_degrees: f64, cue_ball_speed: &InchesPerSecond) -> Inches {
+        Inches::from_f64(jump_clearance(
+            self.distance_to_blocker.as_f64(),
+            self.lateral_offset.as_f64(),
+            self.contact_distance,
+            elevation_degrees,
+            cue_ball_speed.as_f64(),
+        ))
+    }
+
+    /// Whether the jump clears the blocker with a cue no steeper than `max_elevation_degrees`.
+    pub fn is_plausible(&self, max_elevation_degrees: f64) -> bool {
+        self.required_elevation_degrees
+            .is_some_and(|elevation| elevation <= max_elevation_degrees)
+    }
+}
+
+const JUMP_ELEVATION_STEP_DEGREES: f64 = 0.1;
+const JUMP_CLEARANCE_SAMPLES: usize = 64;
+
+/// Analyse jumping `cue_ball` over `blocker` on the way to `target` at `cue_ball_speed`.
+///
+/// Returns `None` when `blocker` does not touch the straight line from the cue ball to `target`,
+/// so no jump is needed.
+pub fn analyze_jump(
+    cue_ball: &Ball,
+    target: &Position,
+    blocker: &Ball,
+    cue_ball_speed: &InchesPerSecond,
+    table_spec: &TableSpec,
+) -> Option<JumpAnalysis> {
+    let start = position_xy_inches(&cue_ball.position, table_spec);
+    let end = position_xy_inches(target, table_spec);
+    let center = position_xy_inches(&blocker.position, table_spec);
+    let length = (end.0 - start.0).hypot(end.1 - start.1);
+    if length <= f64::EPSILON {
+        return None;
+    }
+    let direction = ((end.0 - start.0) / length, (end.1 - start.1) / length);
+    let offset = (center.0 - start.0, center.1 - start.1);
+    let along = offset.0 * direction.0 + offset.1 * direction.1;
+    let lateral = (offset.0 * direction.1 - offset.1 * direction.0).abs();
+    let contact_distance = cue_ball.spec.radius.as_f64() + blocker.spec.radius.as_f64();
+    if along <= 0.0 || along >= length + contact_distance || lateral >= contact_distance {
+        return None;
+    }
+
+    let speed = cue_ball_speed.as_f64();
+    let steps = (90.0 / JUMP_ELEVATION_STEP_DEGREES) as usize;
+    let required_elevation_degrees = (1..steps)
+        .map(|step| step as f64 * JUMP_ELEVATION_STEP_DEGREES)
+        .find(|&elevation| {
+            jump_clearance(along, lateral, contact_distance, elevation, speed) >= 0.0
+        });
+
+    Some(JumpAnalysis {
+        blocker: blocker.ty.clone(),
+        kind: if lateral < cue_ball.spec.radius.as_f64() {
+            JumpKind::FullHop
+        } else {
+            JumpKind::ThinEdge
+        },
+        distance_to_blocker: Inches::from_f64(along),
+        lateral_offset: Inches::from_f64(lateral),
+        required_clearance_height: Inches::from_f64(
+            (contact_distance * contact_distance - lateral * lateral).sqrt(),
+        ),
+        required_elevation_degrees,
+        contact_distance,
+    })
+}
+
+/// The smallest center-to-center gap minus `contact_distance` while the cue ball passes the
+/// blocker, sampled over the stretch of the line where the two could touch.
+fn jump_clearance(
+    along: f64,
+    lateral: f64,
+    contact_distance: f64,
+    elevation_degrees: f64,
+    speed: f64,
+) -> f64 {
+    let (sin, cos) = elevation_degrees.to_radians().sin_cos();
+    let (horizontal, vertical) = (speed * cos, speed * sin);
+    let first = (along - contact_distance).max(0.0);
+    let last = along + contact_distance;
+
+    (0..=JUMP_CLEARANCE_SAMPLES)
+        .map(|sample| {
+            let x = first + (last - first) * sample as f64 / JUMP_CLEARANCE_SAMPLES as f64;
+            let height = if horizontal > f64::EPSILON {
+                let t = x / horizontal;
+                (vertical * t - 0.5 * STANDARD_GRAVITY_INCHES_PER_SECOND_SQUARED * t * t).max(0.0)
+            } else {
+                0.0
+            };
+            ((x - along).powi(2) + lateral * lateral + height * height).sqrt() - contact_distance
+        })
+        .fold(f64::INFINITY, f64::min)
+}
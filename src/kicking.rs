@@ -0,0 +1,476 @@
+//! One-rail kick geometry for position-play diagrams.
+//!
+//! A kick is solved on the mirror table: the target is reflected across the line the cue-ball
+//! center follows when it touches the cushion nose, and the cue ball is aimed straight at that
+//! image. Using the ball-center line instead of the cushion nose itself accounts for the ball
+//! radius, which is where naive "aim at the reflected ball" kicks come up short.
+
+use crate::banking::{
+    mirror_contact_in_inches, rail_plane_and_depth, rebound_contact_in_inches, reflect_across_rail,
+};
+use crate::{
+    position_from_xy_inches, position_xy_inches, Ball, Diamond, EnglishRebound, Position, Rail,
+    Segment, TableSpec,
+};
+
+/// A one-rail kick solved with the mirror-table construction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KickShot {
+    pub rail: Rail,
+    /// The cue-ball center at the moment it touches the cushion.
+    pub contact_point: Position,
+    /// Where the cue ball meets the cushion, measured along `rail` in diamonds: the `x`
+    /// coordinate for `Top` and `Bottom`, and the `y` coordinate for `Left` and `Right`.
+    pub contact_diamond: Diamond,
+    /// The target center reflected onto the mirror table. It usually lies off the playfield.
+    pub mirror_target: Position,
+    /// The aiming line from the cue ball to `mirror_target`.
+    pub aim_line: Segment,
+    /// The cue-ball center path from the cue ball, through the cushion contact, to the target.
+    pub path: Vec<Position>,
+}
+
+/// Solve a one-rail kick from `cue_ball` off `rail` to a full hit on `target_ball`.
+///
+/// Returns `None` when either ball is not in front of the rail or the contact would fall beyond
+/// the playable length of the cushion.
+pub fn one_rail_kick(
+    cue_ball: &Ball,
+    target_ball: &Ball,
+    rail: Rail,
+    table_spec: &TableSpec,
+) -> Option<KickShot> {
+    let radius = cue_ball.spec.radius.as_f64();
+    let start = position_xy_inches(&cue_ball.position, table_spec);
+    let target = position_xy_inches(&target_ball.position, table_spec);
+    let (image_x, image_y) = reflect_across_rail(target, rail, radius, table_spec);
+    let (contact_x, contact_y) =
+        mirror_contact_in_inches(start, (image_x, image_y), rail, radius, table_spec)?;
+
+    let contact_point = position_from_xy_inches(contact_x, contact_y, table_spec);
+    let contact_diamond = if rail.is_horizontal() {
+        contact_point.x.clone()
+    } else {
+        contact_point.y.clone()
+    };
+    let mirror_target = position_from_xy_inches(image_x, image_y, table_spec);
+
+    Some(KickShot {
+        rail,
+        contact_diamond,
+        aim_line: Segment::new(cue_ball.position.clone(), mirror_target.clone()),
+        mirror_target,
+        path: vec![
+            cue_ball.position.clone(),
+            contact_point.clone(),
+            target_ball.position.clone(),
+        ],
+        contact_point,
+    })
+}
+
+/// Solve a one-rail kick like [`one_rail_kick`], optionally with a non-mirror cushion.
+///
+/// With an [`EnglishRebound`], the contact is the point on the rail whose rebound under that rule
+/// reaches the target. `mirror_target` is then the point on the aiming line as far behind the
+/// cushion as the target is in front of it, so it still reads as "aim here on the mirror table".
+/// Without a rebound rule this is the mirror solution.
+pub fn one_rail_kick_with_rebound(
+    cue_ball: &Ball,
+    target_ball: &Ball,
+    rail: Rail,
+    rebound: Option<&EnglishRebound>,
+    table_spec: &TableSpec,
+) -> Option<KickShot> {
+    let Some(rebound) = rebound else {
+        return one_rail_kick(cue_ball, target_ball, rail, table_spec);
+    };
+
+    let radius = cue_ball.spec.radius.as_f64();
+    let start = position_xy_inches(&cue_ball.position, table_spec);
+    let target = position_xy_inches(&target_ball.position, table_spec);
+    let (contact_x, contact_y) =
+        rebound_contact_in_inches(start, target, rail, radius, rebound, table_spec)?;
+    let (_, start_depth) = rail_plane_and_depth(rail, start, radius, table_spec);
+    let (_, target_depth) = rail_plane_and_depth(rail, target, radius, table_spec);
+    let scale = (start_depth + target_depth) / start_depth;
+    let image_x = start.0 + (contact_x - start.0) * scale;
+    let image_y = start.1 + (contact_y - start.1) * scale;
+
+    let contact_point = position_from_xy_inches(contact_x, contact_y, table_spec);
+    let contact_diamond = if rail.is_horizontal() {
+        contact_point.x.clone()
+    } else {
+        contact_point.y.clone()
+    };
+    let mirror_target = position_from_xy_inches(image_x, image_y, table_spec);
+
+    Some(KickShot {
+        rail,
+        contact_diamond,
+        aim_line: Segment::new(cue_ball.position.clone(), mirror_target.clone()),
+        mirror_target,
+        path: vec![
+            cue_ball.position.clone(),
+            contact_point.clone(),
+            target_ball.position.clone(),
+        ],
+        contact_point,
+    })
+}
+
+/// Solve every one-rail kick from `cue_ball` to `target_ball` that exists on this table.
+pub fn one_rail_kicks(
+    cue_ball: &Ball,
+    target_ball: &Ball,
+    table_spec: &TableSpec,
+) -> Vec<KickShot> {
+    [Rail::Top, Rail::Bottom, Rail::Left, Rail::Right]
+        .into_iter()
+        .filter_map(|rail| one_rail_kick(cue_ball, target_ball, rail, table_spec))
+        .collect()
+}
+
+/// Corrections applied on top of the basic corner-5 formula, `aim = origin - target`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Corner5Corrections {
+    /// System units added to the first-rail aim number.
+    ///
+    /// The basic system assumes firm running english. With less english, or on slow cloth, the
+    /// ball comes off the second rail shorter, so players aim a little farther from the first-rail
+    /// corner: a positive offset.
+    pub aim_offset: f64,
+}
+
+impl Corner5Corrections {
+    pub fn with_aim_offset(aim_offset: f64) -> Self {
+        Self { aim_offset }
+    }
+}
+
+/// A kick solved with one of the diamond systems in this module.
+///
+/// All numbers use the pool-table scale of one unit per diamond. Each system documents which
+/// rails its origin, aim, and target numbers are read on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiamondSystemKick {
+    pub origin_number: f64,
+    pub aim_number: f64,
+    pub target_number: f64,
+    /// Where the cue-ball track line, extended backward, crosses the origin-number rail.
+    pub origin_point: Position,
+    /// The first-rail aiming diamond.
+    pub first_rail_point: Position,
+    /// The target-number point on the last rail of the system.
+    pub target_point: Position,
+    /// The predicted path from the cue ball, one segment per rail.
+    pub path: Vec<Segment>,
+}
+
+/// Solve a corner-5 three-rail kick for a cue ball at `cue_ball` arriving at the third rail
+/// level with `third_rail_target`.
+///
+/// The rails are read the same way as the three-cushion system divided by ten:
+///
+/// - origin numbers run along the third rail from 5 at the corner nearest the shooter, dropping by
+///   1/2 per diamond up to the side pocket and by 1 per diamond beyond it, and continue along the
+///   adjoining short rail as 6, 7, and 8;
+/// - aim numbers run along the first rail from 0 at the corner by the second rail;
+/// - target numbers run along the third rail from 0 at the corner by the second rail.
+///
+/// `first_rail` must be a long rail (`Left` or `Right`) and `second_rail` a short rail (`Top` or
+/// `Bottom`); the third rail is the long rail opposite `first_rail`. Diamond numbers are read on
+/// the cushion noses. The aim number is the one whose track, extended backward through the cue
+/// ball, satisfies `aim = origin - target + aim_offset`. Returns `None` when no first-rail
+/// diamond satisfies the system.
+pub fn corner5_kick(
+    cue_ball: &Position,
+    third_rail_target: &Position,
+    first_rail: Rail,
+    second_rail: Rail,
+    corrections: &Corner5Corrections,
+    table_spec: &TableSpec,
+) -> Option<DiamondSystemKick> {
+    assert!(
+        first_rail.is_vertical(),
+        "the corner-5 first rail must be a long rail"
+    );
+    assert!(
+        second_rail.is_horizontal(),
+        "the corner-5 second rail must be a short rail"
+    );
+
+    // Canonical frame: first rail `x = 4`, second rail `y = 8`, third rail `x = 0`.
+    let frame = SystemFrame::new(
+        first_rail == Rail::Left,
+        second_rail == Rail::Bottom,
+        table_spec,
+    );
+    let cue = frame.to_canonical(position_xy_inches(cue_ball, table_spec));
+    let (_, target_y) = frame.to_canonical(position_xy_inches(third_rail_target, table_spec));
+    let target_number = 8.0 - target_y;
+    if cue.0 >= 4.0 || cue.1 >= 8.0 {
+        return None;
+    }
+
+    let origin = |aim: f64| {
+        let point = backward_crossing((4.0, 8.0 - aim), cue);
+        let number = match point {
+            (x, y) if y <= 0.0 => 5.0 + x,
+            (_, y) if y <= 4.0 => 5.0 - 0.5 * y,
+            (_, y) => 3.0 - (y - 4.0),
+        };
+        (point, number)
+    };
+    let aim_number = solve_aim_number(0.0, 8.0 - cue.1, |aim| {
+        origin(aim).1 - target_number + corrections.aim_offset - aim
+    })?;
+    let (origin_point, origin_number) = origin(aim_number);
+    let first_rail_point = (4.0, 8.0 - aim_number);
+    let second_rail_point = (4.0 - 4.0 * aim_number / (aim_number + target_number), 8.0);
+    let target_point = (0.0, target_y);
+
+    Some(frame.kick(
+        cue_ball,
+        [origin_number, aim_number, target_number],
+        origin_point,
+        &[first_rail_point, second_rail_point, target_point],
+        table_spec,
+    ))
+}
+
+/// Solve a plus-system kick: short rail first, arriving on the adjoining long rail.
+///
+/// - origin numbers run along the long rail on the shooter's side of the first rail, from 0 at the
+///   corner by the first rail, and continue along the far short rail as 8 through 12;
+/// - aim numbers run along the first rail from 0 at the same corner;
+/// - target numbers run along the second rail from 0 at the corner by the first rail.
+///
+/// The system rule is `target = origin + aim`. `first_rail` must be a short rail and
+/// `second_rail` a long rail. Returns `None` when no first-rail diamond satisfies the system.
+pub fn plus_system_kick(
+    cue_ball: &Position,
+    second_rail_target: &Position,
+    first_rail: Rail,
+    second_rail: Rail,
+    table_spec: &TableSpec,
+) -> Option<DiamondSystemKick> {
+    assert!(
+        first_rail.is_horizontal(),
+        "the plus-system first rail must be a short rail"
+    );
+    assert!(
+        second_rail.is_vertical(),
+        "the plus-system second rail must be a long rail"
+    );
+
+    // Canonical frame: first rail `y = 8`, second rail `x = 4`, origin rail `x = 0`.
+    let frame = SystemFrame::new(
+        second_rail == Rail::Left,
+        first_rail == Rail::Bottom,
+        table_spec,
+    );
+    let cue = frame.to_canonical(position_xy_inches(cue_ball, table_spec));
+    let (_, target_y) = frame.to_canonical(position_xy_inches(second_rail_target, table_spec));
+    let target_number = 8.0 - target_y;
+    if cue.0 >= 4.0 || cue.1 >= 8.0 {
+        return None;
+    }
+
+    let origin = |aim: f64| {
+        let point = backward_crossing((aim, 8.0), cue);
+        let number = match point {
+            (x, y) if y <= 0.0 => 8.0 + x,
+            (_, y) => 8.0 - y,
+        };
+        (point, number)
+    };
+    // The track must run from the cue ball up and away from the origin rail.
+    let lowest_aim = cue.0 + SYSTEM_AIM_EPSILON;
+    let aim_number = solve_aim_number(lowest_aim, 4.0, |aim| origin(aim).1 + aim - target_number)?;
+    let (origin_point, origin_number) = origin(aim_number);
+
+    Some(frame.kick(
+        cue_ball,
+        [origin_number, aim_number, target_number],
+        origin_point,
+        &[(aim_number, 8.0), (4.0, target_y)],
+        table_spec,
+    ))
+}
+
+/// Solve a two-times-system kick: long rail first, arriving on the adjoining short rail.
+///
+/// - origin numbers run along the far short rail from 0 at the corner on the third-rail side, and
+///   continue up that long rail as negative numbers;
+/// - aim numbers run along the first rail from 0 at the corner by the second rail;
+/// - target numbers run along the second rail from 0 at the same corner.
+///
+/// The system rule is `aim = origin + target`, which closely tracks the mirror-table solution.
+/// With the cue ball's track starting straight across from the target (`origin = target`), the
+/// aim is two times the target number, which gives the system its name. `first_rail` must be a
+/// long rail and `second_rail` a short rail. Returns `None` when no first-rail diamond satisfies
+/// the system.
+pub fn two_times_system_kick(
+    cue_ball: &Position,
+    second_rail_target: &Position,
+    first_rail: Rail,
+    second_rail: Rail,
+    table_spec: &TableSpec,
+) -> Option<DiamondSystemKick> {
+    assert!(
+        first_rail.is_vertical(),
+        "the two-times first rail must be a long rail"
+    );
+    assert!(
+        second_rail.is_horizontal(),
+        "the two-times second rail must be a short rail"
+    );
+
+    // Canonical frame: first rail `x = 4`, second rail `y = 8`, origin rail `y = 0`.
+    let frame = SystemFrame::new(
+        first_rail == Rail::Left,
+        second_rail == Rail::Bottom,
+        table_spec,
+    );
+    let cue = frame.to_canonical(position_xy_inches(cue_ball, table_spec));
+    let (target_x, _) = frame.to_canonical(position_xy_inches(second_rail_target, table_spec));
+    let target_number = 4.0 - target_x;
+    if cue.0 >= 4.0 || cue.1 >= 8.0 {
+        return None;
+    }
+
+    let origin = |aim: f64| {
+        let point = backward_crossing((4.0, 8.0 - aim), cue);
+        let number = match point {
+            (x, y) if y <= 0.0 => x,
+            (_, y) => -y,
+        };
+        (point, number)
+    };
+    let aim_number = solve_aim_number(0.0, 8.0 - cue.1, |aim| origin(aim).1 + target_number - aim)?;
+    let (origin_point, origin_number) = origin(aim_number);
+
+    Some(frame.kick(
+        cue_ball,
+        [origin_number, aim_number, target_number],
+        origin_point,
+        &[(4.0, 8.0 - aim_number), (target_x, 8.0)],
+        table_spec,
+    ))
+}
+
+const SYSTEM_AIM_SAMPLES: usize = 64;
+const SYSTEM_BISECTION_STEPS: usize = 60;
+const SYSTEM_AIM_EPSILON: f64 = 1e-6;
+
+/// Find the aim number in `[low, high]` where `residual` changes sign, scanning from `low` and
+/// refining the first bracket by bisection.
+fn solve_aim_number(low: f64, high: f64, residual: impl Fn(f64) -> f64) -> Option<f64> {
+    if high <= low {
+        return None;
+    }
+
+    let step = (high - low) / SYSTEM_AIM_SAMPLES as f64;
+    let mut previous = (low, residual(low));
+    for sample in 1..=SYSTEM_AIM_SAMPLES {
+        let aim = low + step * sample as f64;
+        let value = residual(aim);
+        if previous.1 == 0.0 {
+            return Some(previous.0);
+        }
+        if previous.1.signum() != value.signum() {
+            let (mut a, mut b) = (previous.0, aim);
+            let a_sign = previous.1.signum();
+            for _ in 0..SYSTEM_BISECTION_STEPS {
+                let mid = 0.5 * (a + b);
+                if residual(mid).signum() == a_sign {
+                    a = mid;
+                } else {
+                    b = mid;
+                }
+            }
+            return Some(0.5 * (a + b));
+        }
+        previous = (aim, value);
+    }
+
+    (previous.1 == 0.0).then_some(previous.0)
+}
+
+/// Extend the track from `aim` back through `cue` to the `x = 0` or `y = 0` rail, in canonical
+/// diamonds.
+fn backward_crossing(aim: (f64, f64), cue: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (cue.0 - aim.0, cue.1 - aim.1);
+    let y_at_side = if dx < 0.0 {
+        aim.1 - aim.0 / dx * dy
+    } else {
+        f64::NEG_INFINITY
+    };
+    if y_at_side >= 0.0 {
+        (0.0, y_at_side)
+    } else {
+        (aim.0 - aim.1 / dy * dx, 0.0)
+    }
+}
+
+/// Maps table inches into a canonical system frame, in diamonds, by optionally mirroring either
+/// axis.
+struct SystemFrame {
+    diamond_inches: f64,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+impl SystemFrame {
+    fn new(flip_x: bool, flip_y: bool, table_spec: &TableSpec) -> Self {
+        Self {
+            diamond_inches: table_spec.diamond_to_inches(Diamond::one()).as_f64(),
+            flip_x,
+            flip_y,
+        }
+    }
+
+    fn to_canonical(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (x, y) = (x / self.diamond_inches, y / self.diamond_inches);
+        (
+            if self.flip_x { 4.0 - x } else { x },
+            if self.flip_y { 8.0 - y } else { y },
+        )
+    }
+
+    fn to_position(&self, (x, y): (f64, f64), table_spec: &TableSpec) -> Position {
+        let x = if self.flip_x { 4.0 - x } else { x };
+        let y = if self.flip_y { 8.0 - y } else { y };
+        position_from_xy_inches(x * self.diamond_inches, y * self.diamond_inches, table_spec)
+    }
+
+    fn kick(
+        &self,
+        cue_ball: &Position,
+        [origin_number, aim_number, target_number]: [f64; 3],
+        origin_point: (f64, f64),
+        rail_points: &[(f64, f64)],
+        table_spec: &TableSpec,
+    ) -> DiamondSystemKick {
+        let mut points = vec![cue_ball.clone()];
+        points.extend(
+            rail_points
+                .iter()
+                .map(|&point| self.to_position(point, table_spec)),
+        );
+
+        DiamondSystemKick {
+            origin_number,
+            aim_number,
+            target_number,
+            origin_point: self.to_position(origin_point, table_spec),
+            first_rail_point: points[1].clone(),
+            target_point: points[points.len() - 1].clone(),
+            path: points
+                .windows(2)
+                .map(|leg| Segment::new(leg[0].clone(), leg[1].clone()))
+                .collect(),
+        }
+    }
+}
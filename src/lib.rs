@@ -1,19 +1,50 @@
-mod assets;
+pub mod aiming;
+pub mod animation;
+pub mod assets;
+pub mod banking;
+mod base64;
+pub mod capture;
 pub mod diagram;
 mod drawing;
+pub mod drills;
 pub mod dsl;
+pub mod jumping;
+pub mod kicking;
+#[cfg(feature = "mp4")]
+mod mp4;
+pub mod pdf;
+pub mod planning;
+pub mod racks;
+pub mod records;
+pub mod replay;
+pub mod rules;
+#[cfg(feature = "serde")]
+pub mod schema;
+#[cfg(feature = "serde")]
+mod serde_rgba;
+pub mod sheet;
+pub mod sidecar;
+pub mod simulation;
+#[cfg(feature = "serde")]
+pub mod tables;
 pub mod visualization;
 
+use crate::assets::AssetPack;
 use crate::diagram::{
-    render_scene_to_bytes, DiagramBall, DiagramElement, DiagramOutputFormat, DiagramScene,
-    DiagramViewport,
+    render_scene_to_bytes, render_scene_to_image, DiagramBackend, DiagramBall, DiagramElement,
+    DiagramOutputFormat, DiagramScene, DiagramViewport,
 };
+use crate::sheet::Sheet;
+use crate::sidecar::DiagramSidecar;
 use crate::visualization::{
-    AimOverlayStyle, BallPathRenderOptions, BallPathStyle, BallPathWidthMode, DashedLineStyle,
-    EventMarkerStyle, GhostBallStyle, LabelOverlayStyle, SmoothPolylineStyle,
+    AimOverlayStyle, AngleArcStyle, ArrowStyle, BallHighlightStyle, BallPathRenderOptions,
+    BallPathStyle, BallPathWidthMode, DashedLineStyle, DiamondGridStyle, EventMarkerStyle,
+    GhostBallStyle, LabelOverlayStyle, LegendEntry, LegendStyle, MeasurementStyle, MeasurementUnit,
+    PhantomBallStyle, PocketHighlightStyle, SequenceMarkerStyle, ShadedZoneStyle,
+    SmoothPolylineStyle, TableTheme, DIMMED_BALL_OPACITY,
 };
 use core::fmt;
-use image::Rgba;
+use image::{Rgba, RgbaImage};
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 use std::fs::File;
@@ -21,7 +52,7 @@ use std::io::Write;
 use std::ops::{Add, Div, Mul, Neg, Sub};
 use std::path::Path;
 use std::str::FromStr;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
 use bigdecimal::BigDecimal;
 use bigdecimal::FromPrimitive;
@@ -70,6 +101,11 @@ lazy_static! {
         y: Diamond::from("4"),
         ..Default::default()
     };
+    pub static ref HEAD_SPOT: Position = Position {
+        x: Diamond::from("2"),
+        y: Diamond::from("6"),
+        ..Default::default()
+    };
     pub static ref RACK_SPOT: Position = Position {
         x: Diamond::from("2"),
         y: Diamond::from("2"),
@@ -131,6 +167,7 @@ pub fn translate_inwards(origin: &Position, dx: Diamond, dy: Diamond) -> Positio
 ///
 /// This represents an absolute table-heading direction, not a cut-angle magnitude.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Angle(f64);
 
 impl Angle {
@@ -158,6 +195,7 @@ impl fmt::Display for Angle {
 
 /// The unsigned cut-angle magnitude `φ` at ball-ball impact, in degrees.
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CutAngle(f64);
 
 impl CutAngle {
@@ -184,9 +222,27 @@ impl CutAngle {
         Self(acute_line_angle)
     }
 
+    /// Construct the cut angle produced by a TP A.23 ball-hit fraction `f = 1 - sin(φ)`.
+    ///
+    /// A fraction of `1` is a full-ball (straight) hit and `0` is the thinnest possible edge hit.
+    pub fn from_hit_fraction(fraction: Scale) -> Self {
+        let fraction = fraction.as_f64();
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "ball-hit fraction must be in [0, 1], got {fraction}"
+        );
+
+        Self::new((1.0 - fraction).asin().to_degrees().clamp(0.0, 90.0))
+    }
+
     pub fn as_degrees(&self) -> f64 {
         self.0
     }
+
+    /// Return the TP A.23 ball-hit fraction `f = 1 - sin(φ)` for this cut angle.
+    pub fn hit_fraction(&self) -> Scale {
+        Scale::from_f64((1.0 - self.0.to_radians().sin()).clamp(0.0, 1.0))
+    }
 }
 
 /// Return the Jewett "two-times fuller" aiming cut angle for a frozen cue-ball shot.
@@ -218,6 +274,8 @@ pub fn frozen_cue_ball_jewett_cut_angle(target_angle_degrees: f64) -> CutAngle {
 }
 
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 /// Represents the unit of distance on a pool table of "a diamond".
 /// Going left-to-right, a diamond is 25% of the pool tables width.
 /// Going top-down, a diamond is 1/8 (12.5%) of the tables length.
@@ -306,6 +364,8 @@ impl Default for Diamond {
 }
 
 #[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 /// A dimensionless scale factor.
 pub struct Scale {
     pub magnitude: BigDecimal,
@@ -329,6 +389,8 @@ impl Scale {
 }
 
 #[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 /// Our representation for converting to inches.
 pub struct Inches {
     pub magnitude: BigDecimal,
@@ -375,6 +437,8 @@ impl Neg for Inches {
 
 /// A measure of speed in terms of inches per second.
 #[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct InchesPerSecond {
     inches: Inches,
 }
@@ -409,6 +473,8 @@ impl InchesPerSecond {
 
 /// A measure of acceleration in terms of inches per second squared.
 #[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct InchesPerSecondSq {
     inches: Inches,
 }
@@ -435,6 +501,7 @@ impl InchesPerSecondSq {
 
 /// A measure of elapsed time in seconds.
 #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Seconds(f64);
 
 impl Seconds {
@@ -460,6 +527,7 @@ impl From<f64> for Seconds {
 
 /// A measure of angular velocity in terms of radians per second.
 #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RadiansPerSecond(f64);
 
 impl RadiansPerSecond {
@@ -488,6 +556,7 @@ impl From<f64> for RadiansPerSecond {
 
 /// A measure of angular acceleration in terms of radians per second squared.
 #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RadiansPerSecondSq(f64);
 
 impl RadiansPerSecondSq {
@@ -516,6 +585,7 @@ impl From<f64> for RadiansPerSecondSq {
 
 /// A 2D vector whose components are measured in inches.
 #[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Inches2 {
     x: Inches,
     y: Inches,
@@ -558,6 +628,7 @@ impl Inches2 {
 
 /// A 2D linear velocity vector measured in inches per second.
 #[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Velocity2 {
     x: InchesPerSecond,
     y: InchesPerSecond,
@@ -615,6 +686,7 @@ impl Velocity2 {
 
 /// A 3-axis angular velocity vector measured in radians per second.
 #[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AngularVelocity3 {
     x: RadiansPerSecond,
     y: RadiansPerSecond,
@@ -663,6 +735,7 @@ pub enum MotionPhase {
 
 /// Shared physical parameters for a set of billiard balls.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BallSetPhysicsSpec {
     pub radius: Inches,
 }
@@ -762,6 +835,7 @@ pub fn mathavan_rigid_cushion_contains_normal_speed(speed: &InchesPerSecond) ->
 /// - `effective_contact_height_ratio` is the reduced TP 7.3-style geometric `a / R` term used for
 ///   vertical-plane-spin pickup.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RailCollisionConfig {
     pub normal_restitution: Scale,
     pub tangential_friction_coefficient: Scale,
@@ -831,6 +905,7 @@ impl Default for RailCollisionConfig {
 /// smallest useful step toward table-specific rail behavior like dead rails, extra-grabby rails, or
 /// asymmetry from wear / dirt / table setup.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RailCollisionProfile {
     pub top: RailCollisionConfig,
     pub right: RailCollisionConfig,
@@ -891,6 +966,62 @@ impl RailCollisionProfile {
     }
 }
 
+/// A closed-form cushion rebound rule for the bank and kick calculators.
+///
+/// The full `RailModel::SpinAware` solve needs a complete ball state; the geometric calculators
+/// only know the angle a ball arrives at. This rule applies the same two effects in closed form
+/// for a sliding ball with side spin only. The cushion-normal speed is scaled by the rail's
+/// `normal_restitution`, which on its own makes the ball leave wider than it arrived. Friction at
+/// the cushion then acts on the along-rail contact slip, limited by
+/// `tangential_friction_coefficient` times the normal impulse and by the 2/7 no-slip cap. A ball
+/// with no english always slips forward and is shortened; running english (`running_english`, the
+/// side-spin ratio `Rω / v` toward the ball's along-rail travel) cancels that slip at `sin(θ)` and
+/// lengthens the angle beyond it, while reverse english (negative values) shortens it further.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnglishRebound {
+    pub running_english: Scale,
+    pub profile: RailCollisionProfile,
+}
+
+impl EnglishRebound {
+    pub fn new(profile: RailCollisionProfile) -> Self {
+        Self {
+            running_english: Scale::zero(),
+            profile,
+        }
+    }
+
+    pub fn with_running_english(mut self, running_english: Scale) -> Self {
+        self.running_english = running_english;
+        self
+    }
+
+    /// Return the rebound angle off `rail` for a ball arriving `incoming_angle_degrees` from the
+    /// rail normal.
+    ///
+    /// Both angles are signed the same way, so a positive result continues in the same
+    /// along-rail direction. A result with the opposite sign means reverse english has brought
+    /// the ball back the way it came.
+    pub fn rebound_angle_degrees(&self, rail: Rail, incoming_angle_degrees: f64) -> f64 {
+        assert!(
+            incoming_angle_degrees.is_finite() && incoming_angle_degrees.abs() < 90.0,
+            "incoming rail angle must be finite and within 90 degrees of the normal"
+        );
+        let config = self.profile.for_rail(rail);
+        let restitution = validated_rail_normal_restitution(config);
+        let friction = validated_rail_tangential_friction_coefficient(config);
+        let incoming = incoming_angle_degrees.abs().to_radians();
+
+        let normal = incoming.cos();
+        let along = incoming.sin();
+        let slip = along - self.running_english.as_f64();
+        let impulse = (friction * (1.0 + restitution) * normal).min(2.0 / 7.0 * slip.abs());
+        let outgoing_along = along - slip.signum() * impulse;
+
+        incoming_angle_degrees.signum() * outgoing_along.atan2(restitution * normal).to_degrees()
+    }
+}
+
 /// Reference output for the TP 7.3 vertical-plane ball-rail spin calculation.
 ///
 /// This is the compact algebraic model from Dr. Dave TP 7.3, not the richer iterative
@@ -997,6 +1128,7 @@ fn scaled_unit_interval_f64(value: f64, scale: &Scale) -> f64 {
 /// cloth age, and ball cleanliness are already being modeled from first principles. They provide a
 /// declarative way to move several existing calibration knobs together.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlayingConditions {
     pub sliding_friction_scale: Scale,
     pub rolling_resistance_scale: Scale,
@@ -2184,6 +2316,50 @@ impl BallPath {
         }
         points
     }
+
+    /// Project this path to drawable points, sampling only where the ball is sliding.
+    ///
+    /// Sliding is the only phase in which the current motion model bends a ball's path, so sliding
+    /// segments are sampled at most `max_time_step` apart and every other segment keeps just its
+    /// end vertex. This follows the slide-to-roll curve of a stun, draw, or follow shot with far
+    /// fewer points than `sampled_points(...)`.
+    pub fn curve_sampled_points(
+        &self,
+        max_time_step: Seconds,
+        ball: &BallSetPhysicsSpec,
+        motion: &OnTableMotionConfig,
+        table_spec: &TableSpec,
+    ) -> Vec<Position> {
+        let max_time_step = max_time_step.as_f64();
+        assert!(
+            max_time_step.is_finite() && max_time_step > 0.0,
+            "sampled path max_time_step must be positive and finite"
+        );
+
+        let mut points = vec![self
+            .initial_state
+            .as_ball_state()
+            .projected_position(table_spec)];
+        for segment in &self.segments {
+            let phase = classify_motion_phase(segment.start.as_ball_state(), ball, &motion.phase);
+            if phase == MotionPhase::Sliding {
+                let duration = segment.duration.as_f64();
+                let sample_count = ((duration / max_time_step).ceil() as usize).max(1);
+                for step in 1..sample_count {
+                    let t = duration * step as f64 / sample_count as f64;
+                    let sampled = advance_on_table_ball_without_event(
+                        &segment.start,
+                        Seconds::new(t),
+                        ball,
+                        motion,
+                    );
+                    points.push(sampled.as_ball_state().projected_position(table_spec));
+                }
+            }
+            points.push(segment.end.as_ball_state().projected_position(table_spec));
+        }
+        points
+    }
 }
 
 /// Thresholds used when classifying the qualitative motion phase of a ball.
@@ -2245,7 +2421,7 @@ const DR_DAVE_TYPICAL_SLIDING_FRICTION_COEFFICIENT: f64 = 0.20;
 const DR_DAVE_TYPICAL_ROLLING_RESISTANCE_COEFFICIENT: f64 = 0.01;
 const DR_DAVE_TYPICAL_SPIN_DECELERATION_RADIANS_PER_SECOND_SQ: f64 = 10.0;
 
-fn friction_coefficient_to_acceleration(coefficient: f64) -> InchesPerSecondSq {
+pub(crate) fn friction_coefficient_to_acceleration(coefficient: f64) -> InchesPerSecondSq {
     InchesPerSecondSq::new(Inches::from_f64(
         coefficient * STANDARD_GRAVITY_INCHES_PER_SECOND_SQUARED,
     ))
@@ -2419,6 +2595,7 @@ pub enum RestingOnTableStateError {
 /// `height` is measured relative to the resting on-table center plane, so an ordinary cloth-bound
 /// ball typically has `height == 0` and `vertical_velocity == 0`.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BallState {
     pub position: Inches2,
     pub height: Inches,
@@ -2759,6 +2936,9 @@ pub enum ShotError {
     NegativeCueSpeed {
         cue_speed: InchesPerSecond,
     },
+    CueElevationOutOfRange {
+        cue_elevation_degrees: f64,
+    },
     NonPositiveCueMassRatio {
         cue_mass_ratio: Scale,
     },
@@ -2852,14 +3032,19 @@ impl CueTipContact {
 
 /// A fully specified cue shot intent for striking a resting cue ball.
 ///
-/// This is a pure input description: the absolute shot heading, the cue speed at impact, and the
-/// validated cue-tip contact point on the ball. Later strike-model helpers will map this input to
-/// an immediate post-strike ball state.
+/// This is a pure input description: the absolute shot heading, the cue speed at impact, the
+/// validated cue-tip contact point on the ball, and the cue elevation above horizontal. Later
+/// strike-model helpers will map this input to an immediate post-strike ball state.
+///
+/// The strike model in [`strike_resting_ball_on_table`] still treats the cue as level. The
+/// elevation is carried for the helpers that do use it, such as [`english_aim_offset`] and
+/// [`jumping::analyze_jump`], and so a described shot round-trips intact.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Shot {
     heading: Angle,
     cue_speed: InchesPerSecond,
     tip_contact: CueTipContact,
+    cue_elevation_degrees: f64,
 }
 
 impl Shot {
@@ -2876,9 +3061,34 @@ impl Shot {
             heading,
             cue_speed,
             tip_contact,
+            cue_elevation_degrees: 0.0,
         })
     }
 
+    /// Construct a shot aimed from `cue_ball` straight at `target`, such as a ghost-ball center.
+    pub fn toward(
+        cue_ball: &Position,
+        target: &Position,
+        cue_speed: InchesPerSecond,
+        tip_contact: CueTipContact,
+    ) -> Result<Self, ShotError> {
+        Self::new(cue_ball.angle_to(target), cue_speed, tip_contact)
+    }
+
+    /// Set the cue elevation above horizontal, in degrees. It must be finite and in `[0, 90)`.
+    pub fn with_cue_elevation_degrees(
+        mut self,
+        cue_elevation_degrees: f64,
+    ) -> Result<Self, ShotError> {
+        if !cue_elevation_degrees.is_finite() || !(0.0..90.0).contains(&cue_elevation_degrees) {
+            return Err(ShotError::CueElevationOutOfRange {
+                cue_elevation_degrees,
+            });
+        }
+        self.cue_elevation_degrees = cue_elevation_degrees;
+        Ok(self)
+    }
+
     /// Construct a shot from the desired immediate cue-ball launch speed.
     ///
     /// The stored `Shot` remains the lower-level cue-stick-speed input required by the current
@@ -2908,6 +3118,10 @@ impl Shot {
         &self.tip_contact
     }
 
+    pub fn cue_elevation_degrees(&self) -> f64 {
+        self.cue_elevation_degrees
+    }
+
     pub fn human_speed_validation(
         &self,
         cue: &CueStrikeConfig,
@@ -3470,6 +3684,202 @@ pub fn cue_natural_pivot_length(
     Inches::from_f64(pivot_length)
 }
 
+/// The squirt characteristics of a cue, as used for aiming with english.
+///
+/// `cue_ball_to_endmass_ratio` is the TP B.1 `m_b / m_e` ratio that sets the squirt angle, and
+/// `tip_radius` is the radius of curvature of the tip, which enters the pivot-length relation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CueSpec {
+    pub cue_ball_to_endmass_ratio: Scale,
+    pub tip_radius: Inches,
+}
+
+impl CueSpec {
+    /// The reference tip offset, in cue-ball-radius units, used to convert a natural pivot length
+    /// into an endmass ratio. TP B.1 finds the pivot length nearly independent of offset.
+    const REFERENCE_TIP_OFFSET: f64 = 0.5;
+
+    pub fn new(cue_ball_to_endmass_ratio: Scale, tip_radius: Inches) -> Self {
+        Self {
+            cue_ball_to_endmass_ratio,
+            tip_radius,
+        }
+    }
+
+    /// Build a cue spec from one measured squirt angle at a known tip offset.
+    pub fn from_squirt(
+        squirt_angle_degrees: f64,
+        tip_offset: Inches,
+        tip_radius: Inches,
+        ball_radius: Inches,
+    ) -> Self {
+        Self::new(
+            cue_endmass_ratio_from_squirt(tip_offset, squirt_angle_degrees, ball_radius),
+            tip_radius,
+        )
+    }
+
+    /// Build a cue spec from its natural pivot length, the bridge length at which back-hand
+    /// english cancels squirt.
+    pub fn from_natural_pivot_length(
+        pivot_length: Inches,
+        tip_radius: Inches,
+        ball_radius: Inches,
+    ) -> Self {
+        let pivot_length = validated_non_negative_length(&pivot_length, "pivot length");
+        let radius = validated_positive_length(&ball_radius, "cue-ball radius");
+        let tip_offset = Self::REFERENCE_TIP_OFFSET * radius;
+        let squirt_angle_degrees = ((radius + tip_radius.as_f64()) * tip_offset
+            / (radius * (pivot_length + radius)))
+            .asin()
+            .to_degrees();
+
+        Self::from_squirt(
+            squirt_angle_degrees,
+            Inches::from_f64(tip_offset),
+            tip_radius,
+            ball_radius,
+        )
+    }
+
+    /// The unsigned squirt angle, in degrees, for a pure side-english `tip_offset`.
+    pub fn squirt_angle_degrees(&self, tip_offset: Inches, ball_radius: Inches) -> f64 {
+        cue_squirt_angle_degrees_from_endmass_ratio(
+            tip_offset,
+            self.cue_ball_to_endmass_ratio.clone(),
+            ball_radius,
+        )
+    }
+
+    /// The bridge length at which back-hand english cancels this cue's squirt for `tip_offset`.
+    pub fn natural_pivot_length(&self, tip_offset: Inches, ball_radius: Inches) -> Inches {
+        cue_natural_pivot_length(
+            self.squirt_angle_degrees(tip_offset.clone(), ball_radius.clone()),
+            tip_offset,
+            self.tip_radius.clone(),
+            ball_radius,
+        )
+    }
+}
+
+impl Default for CueSpec {
+    /// The TP B.1 regular Players cue with a dime-radius tip.
+    fn default() -> Self {
+        Self::new(
+            Scale::from_f64(CueStrikeConfig::DEFAULT_CUE_BALL_TO_ENDMASS_RATIO),
+            Inches::from_f64(0.705 / 2.0),
+        )
+    }
+}
+
+/// How side english moves the cue ball off its aim line over a given distance.
+///
+/// Lateral values are signed in the shooter's frame: positive is to the right of the aim line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnglishAimOffset {
+    /// The signed squirt angle, in degrees; right english squirts the cue ball left.
+    pub squirt_angle_degrees: f64,
+    /// How far swerve has curved the cue ball off its squirt line at the target distance.
+    pub swerve: Inches,
+    /// The net lateral miss at the target distance from squirt and swerve together.
+    pub lateral_miss: Inches,
+    /// The signed angle, in degrees, to turn the aim line so that the cue ball still arrives at
+    /// the target; positive turns the aim to the right.
+    pub aim_offset_degrees: f64,
+}
+
+/// Compute the aim correction for side english with `cue` over `distance` to the target.
+///
+/// Squirt pushes the cue ball away from the side of the tip offset as it leaves the tip (TP
+/// A.31). With an elevated cue the tilted spin axis also slips sideways on the cloth, and sliding
+/// friction curves the ball back toward the english side until it reaches natural roll; after
+/// that it travels straight. `cue_ball_speed` is the launch speed off the tip, and `distance` is
+/// measured along the aim line. Cling, throw, and cloth-speed changes from rolling resistance are
+/// ignored.
+pub fn english_aim_offset(
+    cue: &CueSpec,
+    tip_contact: &CueTipContact,
+    cue_elevation_degrees: f64,
+    cue_ball_speed: &InchesPerSecond,
+    distance: &Inches,
+    motion_config: &OnTableMotionConfig,
+) -> EnglishAimOffset {
+    assert!(
+        cue_elevation_degrees.is_finite() && (0.0..90.0).contains(&cue_elevation_degrees),
+        "cue elevation must be finite and in [0, 90) degrees"
+    );
+    let speed = cue_ball_speed.as_f64();
+    assert!(
+        speed.is_finite() && speed > 0.0,
+        "cue-ball speed must be finite and positive"
+    );
+    let distance = validated_non_negative_length(distance, "target distance");
+
+    let side = tip_contact.side_offset().as_f64();
+    let height = tip_contact.height_offset().as_f64();
+    let squirt_angle_degrees = if side.abs() <= f64::EPSILON {
+        0.0
+    } else {
+        let forward_contact_factor = (1.0 - tip_contact.offset_radius().as_f64().powi(2))
+            .max(0.0)
+            .sqrt();
+        -side.signum()
+            * cue_squirt_angle_degrees_from_contact_geometry(
+                side.abs(),
+                forward_contact_factor,
+                cue.cue_ball_to_endmass_ratio.clone(),
+            )
+    };
+
+    // Cloth-contact slip right after the strike: forward from the draw/follow offset, and sideways
+    // from the share of the side spin that the cue elevation tilts onto the horizontal axis.
+    let forward_slip = speed * (1.0 - 2.5 * height);
+    let lateral_slip = 2.5 * side * cue_elevation_degrees.to_radians().sin() * speed;
+    let slip = forward_slip.hypot(lateral_slip);
+    let friction = sliding_friction_acceleration(motion_config);
+    let swerve = if slip <= f64::EPSILON || lateral_slip.abs() <= f64::EPSILON {
+        0.0
+    } else {
+        let forward_acceleration = friction * forward_slip / slip;
+        let lateral_acceleration = friction * lateral_slip / slip;
+        let sliding_time = 2.0 * slip / (7.0 * friction);
+        let sliding_distance =
+            speed * sliding_time - 0.5 * forward_acceleration * sliding_time * sliding_time;
+
+        if distance <= sliding_distance {
+            let time = if forward_acceleration.abs() <= f64::EPSILON {
+                distance / speed
+            } else {
+                (speed
+                    - (speed * speed - 2.0 * forward_acceleration * distance)
+                        .max(0.0)
+                        .sqrt())
+                    / forward_acceleration
+            };
+            0.5 * lateral_acceleration * time * time
+        } else {
+            let forward_speed = speed - forward_acceleration * sliding_time;
+            let lateral_speed = lateral_acceleration * sliding_time;
+            0.5 * lateral_acceleration * sliding_time * sliding_time
+                + (distance - sliding_distance) * lateral_speed / forward_speed
+        }
+    };
+
+    let lateral_miss = distance * squirt_angle_degrees.to_radians().tan() + swerve;
+    let aim_offset_degrees = if distance <= f64::EPSILON {
+        0.0
+    } else {
+        -lateral_miss.atan2(distance).to_degrees()
+    };
+
+    EnglishAimOffset {
+        squirt_angle_degrees,
+        swerve: Inches::from_f64(swerve),
+        lateral_miss: Inches::from_f64(lateral_miss),
+        aim_offset_degrees,
+    }
+}
+
 fn validate_tip_contact_and_cue_for_strike(
     tip_contact: &CueTipContact,
     cue: &CueStrikeConfig,
@@ -5042,7 +5452,7 @@ fn pocket_face_coordinates_in_inches(table: &TableSpec) -> (f64, f64, f64, f64)
     )
 }
 
-fn pocket_jaw_associated_rail(pocket: Pocket, jaw: PocketJaw) -> Rail {
+pub(crate) fn pocket_jaw_associated_rail(pocket: Pocket, jaw: PocketJaw) -> Rail {
     match (pocket, jaw) {
         (Pocket::TopRight, PocketJaw::First) | (Pocket::TopLeft, PocketJaw::Second) => Rail::Top,
         (Pocket::TopRight, PocketJaw::Second)
@@ -6021,6 +6431,21 @@ fn position_xy_inches(position: &Position, table: &TableSpec) -> (f64, f64) {
     )
 }
 
+/// The center and radius of the disc spanning the mouth of `pocket` from jaw to jaw.
+fn pocket_mouth_disc(pocket: Pocket, table: &TableSpec) -> (Position, Inches) {
+    let (first_x, first_y) = pocket_jaw_reference_point_in_inches(pocket, PocketJaw::First, table);
+    let (second_x, second_y) =
+        pocket_jaw_reference_point_in_inches(pocket, PocketJaw::Second, table);
+    (
+        position_from_xy_inches(
+            0.5 * (first_x + second_x),
+            0.5 * (first_y + second_y),
+            table,
+        ),
+        Inches::from_f64(0.5 * (second_x - first_x).hypot(second_y - first_y)),
+    )
+}
+
 fn position_from_xy_inches(x: f64, y: f64, table: &TableSpec) -> Position {
     Position::new(
         table.inches_to_diamond(Inches::from_f64(x)),
@@ -6028,6 +6453,30 @@ fn position_from_xy_inches(x: f64, y: f64, table: &TableSpec) -> Position {
     )
 }
 
+/// Format a diamond or system number for a rail annotation, to one decimal place.
+fn diamond_number_label(number: f64) -> String {
+    // Avoid printing `-0` for values that round to zero.
+    let number = if number.abs() < 0.05 { 0.0 } else { number };
+    let text = format!("{number:.1}");
+    match text.strip_suffix(".0") {
+        Some(whole) => whole.to_string(),
+        None => text,
+    }
+}
+
+/// Return the distance from `point` to the segment `start..end`, all in table inches.
+fn segment_point_distance_in_inches(start: (f64, f64), end: (f64, f64), point: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared > 0.0 {
+        (((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    (start.0 + t * dx - point.0).hypot(start.1 + t * dy - point.1)
+}
+
 fn pocket_target_center_from_signed_entry_angle_in_inches(
     pocket: Pocket,
     signed_entry_angle_degrees: f64,
@@ -7694,6 +8143,121 @@ pub fn compute_next_ball_pocket_capture_on_table(
     best
 }
 
+/// Whether an on-table ball is already inside `pocket`'s capture region.
+///
+/// This is the same acceptance test `compute_next_ball_pocket_capture_on_table(...)` solves for,
+/// evaluated at the current instant, for integrators that step the table instead of predicting
+/// capture times.
+pub(crate) fn ball_is_captured_by_pocket_on_table(
+    state: &OnTableBallState,
+    pocket: Pocket,
+    ball: &BallSetPhysicsSpec,
+    table: &TableSpec,
+    config: &OnTableMotionConfig,
+) -> bool {
+    pocket_capture_gap_on_table(state, pocket, ball, table, config).is_some_and(|gap| gap <= 0.0)
+}
+
+/// How far `state` is from being captured by `pocket` as it stands, in inches: zero or less means
+/// the pocket has it. `None` when the ball is more than a mouth width and a ball diameter from the
+/// pocket, where no capture is possible and the gap means little.
+pub(crate) fn pocket_capture_gap_on_table(
+    state: &OnTableBallState,
+    pocket: Pocket,
+    ball: &BallSetPhysicsSpec,
+    table: &TableSpec,
+    config: &OnTableMotionConfig,
+) -> Option<f64> {
+    let radius = ball.radius.as_f64();
+    let (pocket_x, pocket_y) = pocket_center_in_inches(pocket, table);
+    let position = &state.as_ball_state().position;
+    let distance = (position.x().as_f64() - pocket_x).hypot(position.y().as_f64() - pocket_y);
+    if distance > pocket_mouth_width_in_inches(pocket, table) + 2.0 * radius {
+        return None;
+    }
+
+    let phase = classify_motion_phase(state.as_ball_state(), ball, &config.phase);
+    Some(pocket_capture_gap_during_current_phase_raw(
+        RawOnTableBallState::from_on_table(state),
+        phase,
+        pocket,
+        0.0,
+        radius,
+        table,
+        config,
+    ))
+}
+
+/// Resolve an on-table ball that has run into one of `pocket`'s jaws since the last step.
+///
+/// This is for integrators that step the table instead of predicting impacts. If the ball's center
+/// is inside a jaw nose's contact circle and still closing on it, it is moved back out to the
+/// circle and rebounds the way `NBallSystemEvent::BallJawImpact` does, dropping if that sends it
+/// into the pocket. Returns the impact, timed at zero, and the ball's state afterwards.
+pub(crate) fn resolve_stepped_ball_jaw_contact_on_table(
+    state: &OnTableBallState,
+    pocket: Pocket,
+    ball: &BallSetPhysicsSpec,
+    table: &TableSpec,
+    motion: &OnTableMotionConfig,
+    rail_model: RailModel,
+) -> Option<(PredictedBallJawImpact, NBallSystemState)> {
+    let radius = ball.radius.as_f64();
+    let state_ref = state.as_ball_state();
+    let (x, y) = (
+        state_ref.position.x().as_f64(),
+        state_ref.position.y().as_f64(),
+    );
+    let (vx, vy) = (
+        state_ref.velocity.x().as_f64(),
+        state_ref.velocity.y().as_f64(),
+    );
+
+    PocketJaw::ALL.into_iter().find_map(|jaw| {
+        let geometry = pocket_jaw_geometry_in_inches(pocket, jaw, table);
+        let (dx, dy) = (x - geometry.center_x, y - geometry.center_y);
+        let distance = dx.hypot(dy);
+        let contact_radius = radius + geometry.nose_radius;
+        if distance >= contact_radius || distance <= f64::EPSILON || dx * vx + dy * vy >= 0.0 {
+            return None;
+        }
+
+        let scale = contact_radius / distance;
+        let state_at_impact = build_on_table_ball_state(
+            Inches2::new(
+                Inches::from_f64(geometry.center_x + dx * scale),
+                Inches::from_f64(geometry.center_y + dy * scale),
+            ),
+            state_ref.velocity.clone(),
+            state_ref.angular_velocity.clone(),
+        );
+        let impact = PredictedBallJawImpact {
+            pocket,
+            jaw,
+            time_until_impact: Seconds::zero(),
+            state_at_impact,
+        };
+        let state_after_jaw = collide_ball_jaw_on_table_with_radius_and_profile(
+            &impact.state_at_impact,
+            &impact,
+            table,
+            ball.radius.clone(),
+            rail_model,
+            &table.cushions,
+        );
+        let state_after =
+            if should_capture_after_jaw_impact(&state_after_jaw, pocket, ball, table, motion) {
+                NBallSystemState::Pocketed {
+                    pocket,
+                    state_at_capture: state_after_jaw,
+                }
+            } else {
+                NBallSystemState::OnTable(state_after_jaw)
+            };
+        Some((impact, state_after))
+    })
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum SingleBallOnTableEvent {
     RailImpact(PredictedBallRailImpact),
@@ -10285,29 +10849,93 @@ pub fn simulate_n_ball_system_with_physics_and_pockets_on_table_until_event_limi
     rail_profile: &RailCollisionProfile,
     max_events: Option<usize>,
 ) -> NBallSystemSimulation {
-    let mut states = states.to_vec();
-    let mut elapsed = Seconds::zero();
-    let mut events = Vec::new();
-    let mut cache = PocketAwareEventCache::build(&states, ball, table, motion);
-    let mut consecutive_zero_time_events = 0usize;
-
-    loop {
-        if max_events.is_some_and(|max_events| events.len() >= max_events) {
-            break;
-        }
-
-        let Some(event) = cache.next_event() else {
-            break;
-        };
-        let step_elapsed = event.time().as_f64();
-        assert!(
-            step_elapsed >= 0.0,
-            "next pocket-aware n-ball event must not go backwards in time"
-        );
+    simulate_pocket_aware_n_ball_system(
+        states,
+        ball,
+        table,
+        motion,
+        collision_model,
+        collision_config,
+        rail_model,
+        rail_profile,
+        max_events,
+        None,
+    )
+}
 
-        let states_before = states.clone();
-        states = resolve_n_ball_system_event_with_physics_and_pockets_on_table(
-            &states,
+/// Like [`simulate_n_ball_system_with_physics_and_pockets_on_table_until_event_limit`], but
+/// far steadier across platforms.
+///
+/// Each ball-ball contact time is re-solved by bisecting the exact contact gap in `BigDecimal`,
+/// and every other event time is rounded up, to `decimal_places` places of a second. After each
+/// event every ball's position, velocity, and spin is rounded to `decimal_places` places of an
+/// inch and a radian, so last-bit differences in a platform's `f64` functions rarely reach the
+/// next event. The motion between events still uses those functions, so a difference that
+/// straddles a rounding boundary can still carry through.
+pub fn simulate_n_ball_system_with_physics_and_pockets_on_table_in_decimal(
+    states: &[NBallSystemState],
+    ball: &BallSetPhysicsSpec,
+    table: &TableSpec,
+    motion: &OnTableMotionConfig,
+    collision_model: CollisionModel,
+    collision_config: &BallBallCollisionConfig,
+    rail_model: RailModel,
+    rail_profile: &RailCollisionProfile,
+    max_events: Option<usize>,
+    decimal_places: u32,
+) -> NBallSystemSimulation {
+    simulate_pocket_aware_n_ball_system(
+        &round_n_ball_system_states(states, decimal_places),
+        ball,
+        table,
+        motion,
+        collision_model,
+        collision_config,
+        rail_model,
+        rail_profile,
+        max_events,
+        Some(decimal_places),
+    )
+}
+
+fn simulate_pocket_aware_n_ball_system(
+    states: &[NBallSystemState],
+    ball: &BallSetPhysicsSpec,
+    table: &TableSpec,
+    motion: &OnTableMotionConfig,
+    collision_model: CollisionModel,
+    collision_config: &BallBallCollisionConfig,
+    rail_model: RailModel,
+    rail_profile: &RailCollisionProfile,
+    max_events: Option<usize>,
+    decimal_places: Option<u32>,
+) -> NBallSystemSimulation {
+    let mut states = states.to_vec();
+    let mut elapsed = Seconds::zero();
+    let mut events = Vec::new();
+    let mut cache = PocketAwareEventCache::build(&states, ball, table, motion);
+    let mut consecutive_zero_time_events = 0usize;
+
+    loop {
+        if max_events.is_some_and(|max_events| events.len() >= max_events) {
+            break;
+        }
+
+        let Some(mut event) = cache.next_event() else {
+            break;
+        };
+        if let Some(decimal_places) = decimal_places {
+            event = with_decimal_event_time(&states, event, ball, motion, decimal_places);
+        }
+        let step_elapsed = event.time().as_f64();
+        assert!(
+            step_elapsed >= 0.0,
+            "next pocket-aware n-ball event must not go backwards in time"
+        );
+
+        let states_before = states.clone();
+        states = resolve_n_ball_system_event_with_physics_and_pockets_on_table(
+            &states,
             &event,
             ball,
             table,
@@ -10317,6 +10945,9 @@ pub fn simulate_n_ball_system_with_physics_and_pockets_on_table_until_event_limi
             rail_model,
             rail_profile,
         );
+        if let Some(decimal_places) = decimal_places {
+            states = round_n_ball_system_states(&states, decimal_places);
+        }
         elapsed = Seconds::new(elapsed.as_f64() + step_elapsed);
         events.push(event.clone());
 
@@ -10345,6 +10976,203 @@ pub fn simulate_n_ball_system_with_physics_and_pockets_on_table_until_event_limi
     }
 }
 
+/// Round every ball's position and velocity to `decimal_places` places of an inch, and its spin
+/// to as many places of a radian per second, as the decimal engine does after each event.
+pub fn round_n_ball_system_states(
+    states: &[NBallSystemState],
+    decimal_places: u32,
+) -> Vec<NBallSystemState> {
+    let round_on_table = |state: &OnTableBallState| {
+        let state = state.as_ball_state();
+        let inches = |value: &Inches| Inches {
+            magnitude: round_decimal(&value.magnitude, decimal_places),
+        };
+        let radians = |value: RadiansPerSecond| {
+            let rounded = round_decimal(
+                &BigDecimal::from_f64(value.as_f64()).unwrap_or_default(),
+                decimal_places,
+            );
+            rounded.to_f64().unwrap_or_default()
+        };
+        build_on_table_ball_state(
+            Inches2::new(inches(state.position.x()), inches(state.position.y())),
+            Velocity2::new(
+                inches(state.velocity.x().as_inches()),
+                inches(state.velocity.y().as_inches()),
+            ),
+            AngularVelocity3::new(
+                radians(state.angular_velocity.x()),
+                radians(state.angular_velocity.y()),
+                radians(state.angular_velocity.z()),
+            ),
+        )
+    };
+
+    states
+        .iter()
+        .map(|state| match state {
+            NBallSystemState::OnTable(on_table) => {
+                NBallSystemState::OnTable(round_on_table(on_table))
+            }
+            NBallSystemState::Pocketed {
+                pocket,
+                state_at_capture,
+            } => NBallSystemState::Pocketed {
+                pocket: *pocket,
+                state_at_capture: round_on_table(state_at_capture),
+            },
+        })
+        .collect()
+}
+
+fn round_decimal(value: &BigDecimal, decimal_places: u32) -> BigDecimal {
+    value.with_scale_round(decimal_places.into(), bigdecimal::RoundingMode::HalfEven)
+}
+
+/// `event` with its time solved or rounded in `BigDecimal`, for the decimal engine.
+///
+/// A ball-ball collision is re-solved by [`decimal_ball_ball_contact_time`] and its impact states
+/// rebuilt at the new time. Every other time is rounded up to `decimal_places`, since resolving an
+/// event a moment late only nudges the balls already in contact.
+fn with_decimal_event_time(
+    states: &[NBallSystemState],
+    mut event: NBallSystemEvent,
+    ball: &BallSetPhysicsSpec,
+    motion: &OnTableMotionConfig,
+    decimal_places: u32,
+) -> NBallSystemEvent {
+    let round_up = |time: Seconds| {
+        let time = BigDecimal::from_f64(time.as_f64())
+            .unwrap_or_default()
+            .with_scale_round(decimal_places.into(), bigdecimal::RoundingMode::Ceiling);
+        Seconds::new(time.to_f64().unwrap_or_default())
+    };
+
+    match &mut event {
+        NBallSystemEvent::BallBallCollision {
+            first_ball_index,
+            second_ball_index,
+            collision,
+        } => {
+            let (Some(a), Some(b)) = (
+                states[*first_ball_index].as_on_table(),
+                states[*second_ball_index].as_on_table(),
+            ) else {
+                return event;
+            };
+            let time = decimal_ball_ball_contact_time(
+                a,
+                b,
+                ball,
+                motion,
+                collision.time_until_impact.as_f64(),
+                decimal_places,
+            )
+            .map(|time| Seconds::new(time.to_f64().unwrap_or_default()))
+            .unwrap_or_else(|| round_up(collision.time_until_impact));
+            *collision = PredictedBallBallCollision {
+                time_until_impact: time,
+                a_at_impact: advance_on_table_ball_without_event(a, time, ball, motion),
+                b_at_impact: advance_on_table_ball_without_event(b, time, ball, motion),
+            };
+        }
+        NBallSystemEvent::SharedBallBallContact {
+            time_until_contact, ..
+        } => *time_until_contact = round_up(*time_until_contact),
+        NBallSystemEvent::BallJawImpact { impact, .. } => {
+            impact.time_until_impact = round_up(impact.time_until_impact)
+        }
+        NBallSystemEvent::BallPocketCapture { capture, .. } => {
+            capture.time_until_capture = round_up(capture.time_until_capture)
+        }
+        NBallSystemEvent::BallRailImpact { impact, .. } => {
+            impact.time_until_impact = round_up(impact.time_until_impact)
+        }
+        NBallSystemEvent::MotionTransition { transition, .. } => {
+            transition.time_until_transition = round_up(transition.time_until_transition)
+        }
+    }
+    event
+}
+
+/// Grid, in seconds, that [`decimal_ball_ball_contact_time`] snaps its search bracket to.
+const DECIMAL_CONTACT_BRACKET_SECONDS: f64 = 1e-6;
+
+/// The first time, rounded up to `decimal_places` places of a second, that `a` and `b` touch,
+/// found by bisecting the contact gap in `BigDecimal` around the `f64` `estimate`.
+///
+/// The bracket is snapped to a microsecond grid, so estimates that differ only in their last bits
+/// bisect the same interval. Each ball's acceleration for its current phase is rounded to
+/// `decimal_places` before use. Returns `None` when the bracket does not straddle the contact.
+fn decimal_ball_ball_contact_time(
+    a: &OnTableBallState,
+    b: &OnTableBallState,
+    ball: &BallSetPhysicsSpec,
+    motion: &OnTableMotionConfig,
+    estimate: f64,
+    decimal_places: u32,
+) -> Option<BigDecimal> {
+    if estimate <= 0.0 {
+        return Some(BigDecimal::from(0u8));
+    }
+    let radius = ball.radius.as_f64();
+    let acceleration = |state: &OnTableBallState| {
+        let phase = classify_motion_phase(state.as_ball_state(), ball, &motion.phase);
+        let (ax, ay) = raw_planar_acceleration_during_phase(
+            RawOnTableBallState::from_on_table(state),
+            phase,
+            radius,
+            motion,
+        );
+        let round = |value: f64| {
+            round_decimal(
+                &BigDecimal::from_f64(value).unwrap_or_default(),
+                decimal_places,
+            )
+        };
+        (round(ax), round(ay))
+    };
+    let (a_state, b_state) = (a.as_ball_state(), b.as_ball_state());
+    let ((a_ax, a_ay), (b_ax, b_ay)) = (acceleration(a), acceleration(b));
+    let half = BigDecimal::from_f64(0.5).expect("one half is a finite decimal");
+    let rx = &b_state.position.x().magnitude - &a_state.position.x().magnitude;
+    let ry = &b_state.position.y().magnitude - &a_state.position.y().magnitude;
+    let rvx =
+        &b_state.velocity.x().as_inches().magnitude - &a_state.velocity.x().as_inches().magnitude;
+    let rvy =
+        &b_state.velocity.y().as_inches().magnitude - &a_state.velocity.y().as_inches().magnitude;
+    let rax = &half * (b_ax - a_ax);
+    let ray = &half * (b_ay - a_ay);
+    let contact = ball.radius.magnitude.double();
+    let contact_squared = &contact * &contact;
+    let gap = |t: &BigDecimal| {
+        let dx = &rx + t * (&rvx + t * &rax);
+        let dy = &ry + t * (&rvy + t * &ray);
+        &dx * &dx + &dy * &dy - &contact_squared
+    };
+
+    let step = DECIMAL_CONTACT_BRACKET_SECONDS;
+    let grid = |index: f64| BigDecimal::from_f64(index * step).unwrap_or_default();
+    let index = (estimate / step).floor();
+    let mut low = grid((index - 1.0).max(0.0));
+    let mut high = grid(index + 2.0);
+    let zero = BigDecimal::from(0u8);
+    if gap(&low) <= zero || gap(&high) > zero {
+        return None;
+    }
+
+    let resolution = BigDecimal::new(1.into(), decimal_places.into());
+    while &high - &low > resolution {
+        let middle = round_decimal(&(&low + &high).half(), decimal_places + 1);
+        if gap(&middle) <= zero {
+            high = middle;
+        } else {
+            low = middle;
+        }
+    }
+    Some(high.with_scale_round(decimal_places.into(), bigdecimal::RoundingMode::Ceiling))
+}
+
 pub fn simulate_n_ball_system_with_rail_profile_and_pockets_on_table_until_rest(
     states: &[NBallSystemState],
     ball: &BallSetPhysicsSpec,
@@ -10763,6 +11591,69 @@ fn validated_ball_ball_radius(ball_radius: Inches) -> f64 {
     ball_radius
 }
 
+/// A stun-shot throw model for the aiming calculators.
+///
+/// Throw is the angle between the line of centers at impact and the object ball's actual
+/// departure. It comes from the same equal-sphere friction impulse as
+/// `CollisionModel::ThrowAware`: the contact slip is the cue ball's tangential speed `v sin(φ)`
+/// less the surface speed from side spin, and the resulting friction impulse, capped at the 1/7
+/// no-slip limit, turns the object ball toward the cue ball's path. `outside_english` is the
+/// side-spin ratio `Rω / v`, positive for outside english; at `sin(φ)` the ball is "geared" and
+/// does not throw, and beyond that it throws the other way. Follow and draw are not modelled, so
+/// this is the stun-shot maximum of cut-induced throw; [`simulation::resolve_cue_ball_hit`] solves
+/// the full contact when they matter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThrowModel {
+    pub collision: BallBallCollisionConfig,
+    pub cue_ball_speed: InchesPerSecond,
+    pub outside_english: Scale,
+}
+
+impl ThrowModel {
+    pub fn new(collision: BallBallCollisionConfig, cue_ball_speed: InchesPerSecond) -> Self {
+        Self {
+            collision,
+            cue_ball_speed,
+            outside_english: Scale::zero(),
+        }
+    }
+
+    pub fn with_outside_english(mut self, outside_english: Scale) -> Self {
+        self.outside_english = outside_english;
+        self
+    }
+
+    /// The signed throw angle, in degrees, for a cut of `cut_angle`. Positive values turn the
+    /// object ball toward the cue ball's path, making the cut thinner than the line of centers.
+    pub fn throw_angle_degrees(&self, cut_angle: CutAngle) -> f64 {
+        let speed = self.cue_ball_speed.as_f64();
+        assert!(
+            speed.is_finite() && speed >= 0.0,
+            "throw-model cue-ball speed must be finite and non-negative"
+        );
+        let phi = cut_angle.as_degrees().to_radians();
+        let normal_impulse_per_mass = 0.5
+            * (1.0 + validated_ball_ball_normal_restitution(&self.collision))
+            * speed
+            * phi.cos();
+        let tangential_contact_slip = speed * (phi.sin() - self.outside_english.as_f64());
+        let friction = validated_ball_ball_contact_friction_coefficient(
+            &self.collision,
+            tangential_contact_slip,
+        );
+        let (tangential_impulse_per_mass, _) = contact_friction_impulse_per_mass(
+            tangential_contact_slip,
+            0.0,
+            normal_impulse_per_mass,
+            friction,
+        );
+
+        tangential_impulse_per_mass
+            .atan2(normal_impulse_per_mass)
+            .to_degrees()
+    }
+}
+
 /// Predict the TP B.21 small-gap combination-throw geometry and outgoing second-ball direction.
 ///
 /// TP B.21 considers two nearly frozen object balls separated by `gap`. The first object ball
@@ -12550,10 +13441,19 @@ pub enum PolarDirection {
 ///   - The center of the table is the point (2, 4).
 ///   - The kitchen is the rectangle from (0, 8) <-> (4, 6).
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub x: Diamond,
     pub y: Diamond,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     unresolved_x_shift: Option<Inches>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     unresolved_y_shift: Option<Inches>,
 }
 
@@ -12640,6 +13540,18 @@ impl Position {
         self.angle_to_pocket(pocket).flipped()
     }
 
+    /// Whether a ball of typical size centered here sits in the kitchen: on or behind the head
+    /// string, and clear of the head and side cushions.
+    pub fn is_in_kitchen(&self, table: &TableSpec) -> bool {
+        let (x, y) = position_xy_inches(self, table);
+        let radius = TYPICAL_BALL_RADIUS.as_f64();
+        let width = table.diamond_to_inches(Diamond::four()).as_f64();
+        let head_string = table.diamond_to_inches(Diamond::six()).as_f64();
+        let head_rail = table.diamond_to_inches(Diamond::eight()).as_f64();
+
+        (radius..=width - radius).contains(&x) && (head_string..=head_rail - radius).contains(&y)
+    }
+
     pub fn zeroed() -> Self {
         Self::new(Diamond::zero(), Diamond::zero())
     }
@@ -12782,6 +13694,7 @@ pub fn gearing_english(cut_angle: CutAngle, shot_speed: InchesPerSecond) -> Radi
 
 /// A displacement indicating a direction and distance.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Displacement {
     /// The delta x component of the displacement.
     pub dx: Diamond,
@@ -12818,6 +13731,33 @@ impl Displacement {
     }
 }
 
+/// A straight table-space segment between two positions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Segment {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    pub fn displacement(&self) -> Displacement {
+        self.start.displacement(&self.end)
+    }
+
+    /// The segment length in diamonds.
+    pub fn length(&self) -> Diamond {
+        self.displacement().absolute_distance()
+    }
+
+    /// The absolute table heading from `start` toward `end`.
+    pub fn heading(&self) -> Angle {
+        self.start.angle_to(&self.end)
+    }
+}
+
 impl Sub for Diamond {
     type Output = Diamond;
 
@@ -13051,6 +13991,7 @@ impl From<&str> for Diamond {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pocket {
     TopRight,
     CenterRight,
@@ -13097,6 +14038,7 @@ impl Pocket {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A type of pocket.
 pub enum PocketType {
     /// One of the four corner pockets.
@@ -13107,12 +14049,14 @@ pub enum PocketType {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PocketJawGeometry {
     PointNoses,
     RoundedNoses { nose_radius: Inches },
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PocketShapeSpec {
     pub jaw_geometry: PocketJawGeometry,
 }
@@ -13136,6 +14080,7 @@ impl PocketShapeSpec {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Physical specifications of a pocket.
 pub struct PocketSpec {
     pub ty: PocketType,
@@ -13208,11 +14153,14 @@ pub fn pocket_mouth_throat_difference_from_facing_angle_degrees(
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Physical specifications of a pool table.
 pub struct TableSpec {
     pub pockets: [PocketSpec; 6],
     pub cushion_diamond_buffer: Diamond,
     pub diamond_length: Inches,
+    /// How each cushion rebounds a ball: its restitution and friction against the ball.
+    pub cushions: RailCollisionProfile,
 }
 
 impl Default for TableSpec {
@@ -13222,6 +14170,7 @@ impl Default for TableSpec {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Physical specifications of a pool ball.
 pub struct BallSpec {
     pub radius: Inches,
@@ -13257,6 +14206,7 @@ impl TableSpec {
                 Self::brunswick_gc4_side_pocket(diamond_length.clone()),
                 Self::brunswick_gc4_corner_pocket(diamond_length),
             ],
+            cushions: RailCollisionProfile::human_tuned(),
         }
     }
 
@@ -13309,6 +14259,11 @@ impl TableSpec {
         self
     }
 
+    pub fn with_cushions(mut self, cushions: RailCollisionProfile) -> Self {
+        self.cushions = cushions;
+        self
+    }
+
     /// For a given table, convert Diamond Units into Inches.
     /// On a typical 9ft table, 1 Diamond is equal to 12.5 inches.
     pub fn diamond_to_inches(&self, val: Diamond) -> Inches {
@@ -13325,9 +14280,10 @@ impl TableSpec {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 /// A type of ball, for example, Cue ball, the eight ball, etc.
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BallType {
     One,
     Two,
@@ -13338,16 +14294,76 @@ pub enum BallType {
     Seven,
     Eight,
     Nine,
+    Ten,
+    Eleven,
+    Twelve,
+    Thirteen,
+    Fourteen,
+    Fifteen,
     #[default]
     Cue,
 }
 
+impl BallType {
+    /// The number printed on the ball, or `None` for the cue ball.
+    pub fn number(&self) -> Option<u8> {
+        match self {
+            BallType::One => Some(1),
+            BallType::Two => Some(2),
+            BallType::Three => Some(3),
+            BallType::Four => Some(4),
+            BallType::Five => Some(5),
+            BallType::Six => Some(6),
+            BallType::Seven => Some(7),
+            BallType::Eight => Some(8),
+            BallType::Nine => Some(9),
+            BallType::Ten => Some(10),
+            BallType::Eleven => Some(11),
+            BallType::Twelve => Some(12),
+            BallType::Thirteen => Some(13),
+            BallType::Fourteen => Some(14),
+            BallType::Fifteen => Some(15),
+            BallType::Cue => None,
+        }
+    }
+
+    /// The object ball with `number` printed on it, if there is one.
+    pub fn from_number(number: u8) -> Option<Self> {
+        [
+            BallType::One,
+            BallType::Two,
+            BallType::Three,
+            BallType::Four,
+            BallType::Five,
+            BallType::Six,
+            BallType::Seven,
+            BallType::Eight,
+            BallType::Nine,
+            BallType::Ten,
+            BallType::Eleven,
+            BallType::Twelve,
+            BallType::Thirteen,
+            BallType::Fourteen,
+            BallType::Fifteen,
+        ]
+        .into_iter()
+        .nth(usize::from(number).checked_sub(1)?)
+    }
+
+    /// Whether the ball is white with a band of its color, as the 9 through 15 are.
+    pub fn is_striped(&self) -> bool {
+        matches!(self.number(), Some(9..=15))
+    }
+}
+
 #[derive(Clone, Debug)]
 /// Represents a ball on the table, incl. its position, physical spec, type.
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ball {
     pub ty: BallType,
     pub position: Position,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub spec: BallSpec,
 }
 
@@ -13408,6 +14424,56 @@ impl Ball {
         position_from_xy_inches(target_x, target_y, table_spec)
     }
 
+    /// Compute the TP 3.5-3.8 effective pocket opening for this ball rolling straight at the
+    /// aiming center of `pocket`.
+    ///
+    /// The opening accounts for the entry angle, the speed, and the pocket's jaw geometry, so an
+    /// angled approach into a corner pocket reports a much smaller margin on the rail-side jaw
+    /// than on the far jaw. Angular margins are seen from this ball's current position, which
+    /// makes longer shots proportionally harder.
+    pub fn effective_pocket_opening(
+        &self,
+        pocket: Pocket,
+        object_ball_speed: InchesPerSecond,
+        table_spec: &TableSpec,
+    ) -> EffectivePocketOpening {
+        let (object_x, object_y) = position_xy_inches(&self.position, table_spec);
+        let (pocket_x, pocket_y) = pocket_center_in_inches(pocket, table_spec);
+        let entry_angle_degrees = signed_pocket_entry_angle_from_object_to_target_degrees(
+            object_x, object_y, pocket_x, pocket_y, pocket,
+        )
+        .unwrap_or(0.0);
+        let (left, right) = pocket_target_bounds_in_inches(
+            pocket,
+            entry_angle_degrees,
+            object_ball_speed.as_f64(),
+            self.spec.radius.as_f64(),
+            table_spec,
+        );
+
+        // Margins are measured across the pocket mouth; project them onto the normal of the
+        // shot line before converting to an angle seen from the ball.
+        let distance = (pocket_x - object_x).hypot(pocket_y - object_y);
+        let projection = entry_angle_degrees.to_radians().cos().abs();
+        let angular_margin = |margin: f64| {
+            if distance <= f64::EPSILON {
+                0.0
+            } else {
+                (margin * projection).atan2(distance).to_degrees()
+            }
+        };
+
+        EffectivePocketOpening {
+            pocket,
+            entry_angle_degrees,
+            left_margin: Inches::from_f64(left),
+            right_margin: Inches::from_f64(right),
+            left_angular_margin_degrees: angular_margin(left),
+            right_angular_margin_degrees: angular_margin(right),
+            target_center: self.pocket_target_center(pocket, object_ball_speed, table_spec),
+        }
+    }
+
     /// Compute the idealized ghost-ball center for potting this object ball to a pocket at the
     /// supplied object-ball entry speed.
     pub fn ghost_ball_to_pocket_with_speed(
@@ -13462,6 +14528,45 @@ impl Ball {
     }
 }
 
+/// The effective opening of a pocket for one approach line, from
+/// [`Ball::effective_pocket_opening`].
+///
+/// Left and right are seen from the ball looking into the pocket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EffectivePocketOpening {
+    pub pocket: Pocket,
+    /// Signed angle between the approach line and the pocket's entry axis, in degrees. Positive
+    /// values mean the ball is travelling toward the left jaw.
+    pub entry_angle_degrees: f64,
+    /// How far the ball center can miss left of the pocket center, across the mouth, and drop.
+    pub left_margin: Inches,
+    /// How far the ball center can miss right of the pocket center, across the mouth, and drop.
+    pub right_margin: Inches,
+    /// The left margin as an aiming error seen from the ball.
+    pub left_angular_margin_degrees: f64,
+    /// The right margin as an aiming error seen from the ball.
+    pub right_angular_margin_degrees: f64,
+    /// The midpoint of the effective opening, as used by `Ball::pocket_target_center`.
+    pub target_center: Position,
+}
+
+impl EffectivePocketOpening {
+    /// The full effective width of the opening, across the pocket mouth.
+    pub fn width(&self) -> Inches {
+        Inches::from_f64(self.left_margin.as_f64() + self.right_margin.as_f64())
+    }
+
+    /// The full angular window, seen from the ball, inside which the shot still drops.
+    pub fn angular_width_degrees(&self) -> f64 {
+        self.left_angular_margin_degrees + self.right_angular_margin_degrees
+    }
+
+    /// Whether any approach along this line can still drop the ball.
+    pub fn is_open(&self) -> bool {
+        self.width().as_f64() > 0.0
+    }
+}
+
 /// The kinematics of a ball; all of the characteristics of its motion.
 pub struct Kinematics {
     /// Velocity of a ball: vx, vy, vz.
@@ -13474,7 +14579,8 @@ pub struct Kinematics {
     pub angular_velocity: [RadiansPerSecond; 3],
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// The type of game, e.g. Nineball, EightBall, OnePocket, etc.
 pub enum GameType {
     #[default]
@@ -13485,7 +14591,32 @@ pub enum GameType {
     Banks,
 }
 
-#[derive(Clone, Debug, Default)]
+impl GameType {
+    /// The game's code in [`GameState::to_notation`]: `9b`, `8b`, `10b`, `1p` or `bk`.
+    pub(crate) fn notation(&self) -> &'static str {
+        match self {
+            GameType::NineBall => "9b",
+            GameType::EightBall => "8b",
+            GameType::TenBall => "10b",
+            GameType::OnePocket => "1p",
+            GameType::Banks => "bk",
+        }
+    }
+
+    pub(crate) fn from_notation(code: &str) -> Option<Self> {
+        match code {
+            "9b" => Some(GameType::NineBall),
+            "8b" => Some(GameType::EightBall),
+            "10b" => Some(GameType::TenBall),
+            "1p" => Some(GameType::OnePocket),
+            "bk" => Some(GameType::Banks),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A modifier being applied to the Cueball, for example ball in hand.
 pub enum CueballModifier {
     #[default]
@@ -13495,6 +14626,28 @@ pub enum CueballModifier {
     KitchenPlacement,
 }
 
+impl CueballModifier {
+    /// The modifier's code in [`GameState::to_notation`]: `-`, `bih`, `kit` or `brk`.
+    pub(crate) fn notation(&self) -> &'static str {
+        match self {
+            CueballModifier::AsItLays => "-",
+            CueballModifier::BallInHand => "bih",
+            CueballModifier::KitchenPlacement => "kit",
+            CueballModifier::BreakPlacement => "brk",
+        }
+    }
+
+    pub(crate) fn from_notation(code: &str) -> Option<Self> {
+        match code {
+            "-" => Some(CueballModifier::AsItLays),
+            "bih" => Some(CueballModifier::BallInHand),
+            "kit" => Some(CueballModifier::KitchenPlacement),
+            "brk" => Some(CueballModifier::BreakPlacement),
+            _ => None,
+        }
+    }
+}
+
 /// How a bank / rail-approach angle is measured relative to a rail.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RailAngleReference {
@@ -13528,6 +14681,7 @@ impl RailTangentDirection {
 
 /// The rails on a pool table.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rail {
     Top,
     Bottom,
@@ -13625,6 +14779,7 @@ impl Rail {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OverlayLayer {
     BelowBalls,
     AboveBalls,
@@ -13632,61 +14787,368 @@ pub enum OverlayLayer {
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DiagramBackground {
+    /// The bundled art of a 9 ft table.
     Table,
     Transparent,
+    /// Cloth, rails, pockets and sights drawn from the scene's [`TableSpec`], so tables of any
+    /// size keep their true proportions.
+    Procedural,
+}
+
+/// How balls are drawn on raster diagrams.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BallStyle {
+    /// The bundled ball images, or drawn balls for those without one.
+    #[default]
+    Sprites,
+    /// Circles drawn at the output size in the ball's colors, with its stripe and number.
+    Procedural,
+}
+
+/// Which way up a diagram is drawn. Landscape turns the table a quarter turn clockwise, putting
+/// the foot rail on the left and the head rail on the right. Ball sprites stay upright; overlay
+/// text turns with the table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagramOrientation {
+    Portrait,
+    Landscape,
 }
 
+/// A rectangle of the table between two opposite corners, in diamonds. Corners may lie past the
+/// cushions to take in the rails and pockets.
 #[derive(Clone, Debug, PartialEq)]
-pub struct DiagramRenderOptions {
-    pub scale_factor: u32,
-    pub background: DiagramBackground,
+pub struct DiagramRegion {
+    pub corner: Position,
+    pub opposite_corner: Position,
 }
 
-impl Default for DiagramRenderOptions {
-    fn default() -> Self {
+impl DiagramRegion {
+    pub fn new(corner: Position, opposite_corner: Position) -> Self {
         Self {
-            scale_factor: 1,
-            background: DiagramBackground::Table,
+            corner,
+            opposite_corner,
         }
     }
 }
 
-#[derive(Clone, Debug)]
-enum Overlay {
-    DashedLine {
-        start: Position,
-        end: Position,
-        style: DashedLineStyle,
-    },
-    SmoothPolyline {
-        points: Vec<Position>,
-        style: SmoothPolylineStyle,
-    },
-    GhostBall {
-        center: Position,
-        style: GhostBallStyle,
-    },
-    CircleMarker {
-        center: Position,
-        style: EventMarkerStyle,
-    },
-    TextLabel {
-        anchor: Position,
+/// A corner of a diagram, as it is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagramCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// What a [`Watermark`] shows.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WatermarkContent {
+    /// A logo, drawn at its own size in output pixels.
+    Image(RgbaImage),
+    /// A line of text in the overlay font, `scale_px` pixels to a dot of its 5×7 glyphs.
+    Text {
         text: String,
-        style: LabelOverlayStyle,
+        color: Rgba<u8>,
+        scale_px: u32,
     },
 }
 
+/// A logo or line of text laid over a corner of a diagram, to brand it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Watermark {
+    pub content: WatermarkContent,
+    pub corner: DiagramCorner,
+    /// From 0 (invisible) to 1 (as solid as the logo or text color).
+    pub opacity: f32,
+    /// Pixels between the watermark and the edges of the output, margin included.
+    pub inset_px: u32,
+}
+
+impl Watermark {
+    /// `logo` in the bottom right corner at half opacity.
+    pub fn image(logo: RgbaImage) -> Self {
+        Self {
+            content: WatermarkContent::Image(logo),
+            corner: DiagramCorner::BottomRight,
+            opacity: 0.5,
+            inset_px: 16,
+        }
+    }
+
+    /// The logo in the image file at `path`; see [`Watermark::image`].
+    pub fn load(path: &Path) -> image::ImageResult<Self> {
+        Ok(Self::image(image::open(path)?.into_rgba8()))
+    }
+
+    /// `text` in `color` in the bottom right corner at half opacity.
+    pub fn text(text: impl Into<String>, color: Rgba<u8>) -> Self {
+        Self {
+            content: WatermarkContent::Text {
+                text: text.into(),
+                color,
+                scale_px: 2,
+            },
+            corner: DiagramCorner::BottomRight,
+            opacity: 0.5,
+            inset_px: 16,
+        }
+    }
+
+    pub fn in_corner(mut self, corner: DiagramCorner) -> Self {
+        self.corner = corner;
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiagramRenderOptions {
+    pub scale_factor: u32,
+    /// Shrink the output to fit within this many pixels across, keeping its aspect ratio.
+    pub max_width_px: Option<u32>,
+    /// Shrink the output to fit within this many pixels down, keeping its aspect ratio.
+    pub max_height_px: Option<u32>,
+    /// Quality from 1 to 100 for JPEG output.
+    pub jpeg_quality: u8,
+    pub background: DiagramBackground,
+    pub orientation: DiagramOrientation,
+    pub table_theme: TableTheme,
+    /// Painted behind the table, including its rounded corners, and over the margin.
+    pub canvas_color: Rgba<u8>,
+    /// Pixels of `canvas_color` added around every side of the scaled diagram.
+    pub margin_px: u32,
+    /// Render only this region of the table, enlarged to the width of the full diagram.
+    pub crop: Option<DiagramRegion>,
+    /// Draw a grid through the diamonds with their numbers, for kick and bank lessons.
+    pub diamond_grid: Option<DiamondGridStyle>,
+    /// Use this table image, calibration and ball sprites instead of the built-in art. Load the
+    /// pack once with [`assets::AssetPack::load`] and share it across every render.
+    pub assets: Option<Arc<AssetPack>>,
+    pub ball_style: BallStyle,
+    /// Pixels per inch, recorded in PNG and JPEG output and used to give SVG output a size in
+    /// inches, so diagrams print at a predictable size.
+    pub dpi: Option<f64>,
+    /// Scale the output, margin included, to exactly this many inches across at `dpi`, or at
+    /// [`DEFAULT_PRINT_DPI`] if that is unset. Takes the place of the scale factor and maximum
+    /// sizes.
+    pub print_width_in: Option<f64>,
+    /// Leave out the annotations in these named layers; see [`GameState::set_named_layer`].
+    pub hidden_layers: Vec<String>,
+    /// Brand raster and SVG output with a logo or text in one corner.
+    pub watermark: Option<Watermark>,
+}
+
+/// The pixels per inch [`DiagramRenderOptions::print_width_in`] prints at when no DPI is given.
+pub const DEFAULT_PRINT_DPI: f64 = 300.0;
+
+impl Default for DiagramRenderOptions {
+    fn default() -> Self {
+        Self {
+            scale_factor: 1,
+            max_width_px: None,
+            max_height_px: None,
+            jpeg_quality: 90,
+            background: DiagramBackground::Table,
+            orientation: DiagramOrientation::Portrait,
+            table_theme: TableTheme::classic(),
+            canvas_color: Rgba([0, 0, 0, 0]),
+            margin_px: 0,
+            crop: None,
+            diamond_grid: None,
+            assets: None,
+            ball_style: BallStyle::Sprites,
+            dpi: None,
+            print_width_in: None,
+            hidden_layers: Vec::new(),
+            watermark: None,
+        }
+    }
+}
+
+impl DiagramRenderOptions {
+    /// Options for a diagram `width_in` inches across at `dpi` pixels per inch.
+    pub fn for_print(width_in: f64, dpi: f64) -> Self {
+        Self {
+            dpi: Some(dpi),
+            print_width_in: Some(width_in),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_hidden_layer(mut self, name: impl Into<String>) -> Self {
+        self.hidden_layers.push(name.into());
+        self
+    }
+
+    pub fn with_watermark(mut self, watermark: Watermark) -> Self {
+        self.watermark = Some(watermark);
+        self
+    }
+
+    /// The pixels per inch of the output, if it has a physical size.
+    pub fn effective_dpi(&self) -> Option<f64> {
+        self.dpi
+            .or(self.print_width_in.map(|_| DEFAULT_PRINT_DPI))
+            .filter(|dpi| dpi.is_finite() && *dpi > 0.0)
+    }
+
+    /// The size of a diagram drawn at `native_px`, after applying the scale factor and then
+    /// shrinking it to fit the maximum width and height, or scaled to its print width.
+    pub fn output_size_px(&self, native_px: (u32, u32)) -> (u32, u32) {
+        let scale_factor = self.scale_factor.max(1) as f64;
+        let (width, height) = (
+            native_px.0 as f64 * scale_factor,
+            native_px.1 as f64 * scale_factor,
+        );
+        let print_width_px = self
+            .print_width_in
+            .zip(self.effective_dpi())
+            .map(|(inches, dpi)| ((inches * dpi).round() - 2.0 * self.margin_px as f64).max(1.0));
+        let fit = match print_width_px {
+            Some(print_width) => print_width / width,
+            None => [
+                self.max_width_px.map(|max| max as f64 / width),
+                self.max_height_px.map(|max| max as f64 / height),
+            ]
+            .into_iter()
+            .flatten()
+            .fold(1.0, f64::min),
+        };
+
+        (
+            ((width * fit).round() as u32).max(1),
+            ((height * fit).round() as u32).max(1),
+        )
+    }
+}
+
+/// How many straight segments [`GameState::add_arc_path`] and [`GameState::add_bezier_path`]
+/// flatten a curve into.
+const CURVE_SEGMENTS: usize = 64;
+
+/// The most JSON [`GameState::from_share_string`] unpacks from one string.
+#[cfg(feature = "serde")]
+pub const MAX_SHARE_STRING_JSON_BYTES: u64 = 1 << 20;
+
+/// The decimal places [`GameState::fingerprint`] rounds positions and sizes to; a millionth of a
+/// diamond is far below anything a diagram can show.
+pub const FINGERPRINT_DECIMAL_PLACES: i64 = 6;
+
+/// The thinnest cut, in degrees, that [`GameState::open_pockets`] still treats as makeable.
+pub const MAX_OPEN_POCKET_CUT_DEGREES: f64 = 88.0;
+
 #[derive(Clone, Debug)]
 /// The full and complete data structure describing the state of a game.
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct GameState {
     pub table_spec: TableSpec,
+    #[cfg_attr(feature = "serde", serde(rename = "balls"))]
     ball_positions: Vec<Ball>,
+    #[cfg_attr(feature = "serde", serde(rename = "game_type"))]
     pub ty: GameType,
+    #[cfg_attr(feature = "serde", serde(rename = "cue_ball"))]
     pub cueball_modifier: CueballModifier,
 
-    lines_to_draw: Vec<Overlay>,
+    #[cfg_attr(feature = "serde", serde(rename = "annotations"))]
+    lines_to_draw: Vec<DiagramElement>,
+    /// The named layer of each annotation in `lines_to_draw`, if it was added to one.
+    #[cfg_attr(feature = "serde", serde(rename = "annotation_layers"))]
+    overlay_layer_names: Vec<Option<String>>,
+    /// The named layer new annotations are added to.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    named_layer: Option<String>,
+    /// The balls drawn at full strength while the rest are dimmed, and how solid the rest are.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    ball_focus: Option<BallFocus>,
+}
+
+/// The balls a layout draws at full strength, and how solid it draws the rest.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct BallFocus {
+    balls: Vec<BallType>,
+    opacity: f32,
+}
+
+/// What changed between two layouts of the same table; see [`GameState::diff`].
+#[derive(Clone, Debug, Default)]
+pub struct LayoutDiff {
+    /// Balls on the table in both layouts but somewhere else in the second, in the first
+    /// layout's order.
+    pub moved: Vec<BallMove>,
+    /// Balls only in the second layout.
+    pub added: Vec<Ball>,
+    /// Balls only in the first layout and not known to be pocketed.
+    pub removed: Vec<Ball>,
+    /// Balls that dropped, with the pocket each went into.
+    pub pocketed: Vec<(BallType, Pocket)>,
+}
+
+/// A ball that is somewhere else in the second layout of a [`LayoutDiff`].
+#[derive(Clone, Debug)]
+pub struct BallMove {
+    pub ty: BallType,
+    pub from: Position,
+    pub to: Position,
+    pub displacement: Displacement,
+}
+
+impl LayoutDiff {
+    /// Whether the two layouts have the same balls in the same places.
+    pub fn is_empty(&self) -> bool {
+        self.moved.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.pocketed.is_empty()
+    }
+}
+
+/// One line per change: pockets first, then moves, removals and additions.
+impl fmt::Display for LayoutDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |ty: &BallType| {
+            ty.number()
+                .map_or("cue ball".to_string(), |number| format!("{number} ball"))
+        };
+        let mut lines = Vec::new();
+        for (ty, pocket) in &self.pocketed {
+            let pocket = match pocket {
+                Pocket::TopRight => "top right",
+                Pocket::CenterRight => "center right",
+                Pocket::BottomRight => "bottom right",
+                Pocket::BottomLeft => "bottom left",
+                Pocket::CenterLeft => "center left",
+                Pocket::TopLeft => "top left",
+            };
+            lines.push(format!("{} pocketed in the {pocket} pocket", name(ty)));
+        }
+        for step in &self.moved {
+            lines.push(format!(
+                "{} moved {:.2} diamonds to ({}, {})",
+                name(&step.ty),
+                step.displacement
+                    .absolute_distance()
+                    .magnitude
+                    .to_f64()
+                    .unwrap_or_default(),
+                step.to.x.magnitude.round(3).normalized(),
+                step.to.y.magnitude.round(3).normalized()
+            ));
+        }
+        for ball in &self.removed {
+            lines.push(format!("{} removed", name(&ball.ty)));
+        }
+        for ball in &self.added {
+            lines.push(format!("{} added", name(&ball.ty)));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
 }
 
 impl GameState {
@@ -13727,6 +15189,189 @@ impl GameState {
         self.ball_positions.iter().find(|b| b.ty == ball_type)
     }
 
+    /// Return every ball that a ball of `moving_ball_radius` would touch while travelling in a
+    /// straight line from `from` to `to`.
+    ///
+    /// A ball obstructs the path when its center lies within the sum of the two radii of the
+    /// segment. A ball centered exactly on `from` is the moving ball itself and is skipped.
+    pub fn path_obstructions(
+        &self,
+        from: &Position,
+        to: &Position,
+        moving_ball_radius: Inches,
+    ) -> Vec<&Ball> {
+        let start = position_xy_inches(from, &self.table_spec);
+        let end = position_xy_inches(to, &self.table_spec);
+
+        self.ball_positions
+            .iter()
+            .filter(|ball| {
+                let center = position_xy_inches(&ball.position, &self.table_spec);
+                let from_start = (center.0 - start.0).hypot(center.1 - start.1);
+                let clearance = moving_ball_radius.as_f64() + ball.spec.radius.as_f64();
+
+                from_start > 1e-9
+                    && segment_point_distance_in_inches(start, end, center) < clearance
+            })
+            .collect()
+    }
+
+    /// Whether a ball of `moving_ball_radius` can travel in a straight line from `from` to `to`
+    /// without touching any ball on the table.
+    pub fn is_path_clear(
+        &self,
+        from: &Position,
+        to: &Position,
+        moving_ball_radius: Inches,
+    ) -> bool {
+        self.path_obstructions(from, to, moving_ball_radius)
+            .is_empty()
+    }
+
+    /// Return the pockets `object_ball` can be potted into from the current layout.
+    ///
+    /// A pocket is open when the ghost ball for it lies on the playfield, the object ball's path
+    /// to the pocket is clear, and, if the state has a cue ball, the cut is thinner than
+    /// [`MAX_OPEN_POCKET_CUT_DEGREES`] and the cue ball's path to the ghost ball is clear. The cue
+    /// ball is not treated as an obstruction for the object ball, since it has moved to the ghost
+    /// position by the time the object ball leaves.
+    pub fn open_pockets(&self, object_ball: &Ball) -> Vec<Pocket> {
+        let cue_ball = self.select_ball(BallType::Cue);
+        let radius = object_ball.spec.radius.clone();
+        let width = self.table_spec.diamond_to_inches(Diamond::four()).as_f64();
+        let length = self.table_spec.diamond_to_inches(Diamond::eight()).as_f64();
+
+        Pocket::ALL
+            .into_iter()
+            .filter(|&pocket| {
+                let ghost_ball = object_ball.ghost_ball_to_pocket(pocket, &self.table_spec);
+                let (ghost_x, ghost_y) = position_xy_inches(&ghost_ball, &self.table_spec);
+                let on_playfield = (radius.as_f64()..=width - radius.as_f64()).contains(&ghost_x)
+                    && (radius.as_f64()..=length - radius.as_f64()).contains(&ghost_y);
+                let object_path_clear = self
+                    .path_obstructions(
+                        &object_ball.position,
+                        &pocket.aiming_center(),
+                        radius.clone(),
+                    )
+                    .iter()
+                    .all(|ball| ball.ty == BallType::Cue || ball.ty == object_ball.ty);
+
+                on_playfield
+                    && object_path_clear
+                    && cue_ball.is_none_or(|cue_ball| {
+                        // Signed, so a "cut" back toward the shooter is not mistaken for a thin
+                        // cut.
+                        let cut_degrees = (ghost_ball.angle_to(&object_ball.position).as_degrees()
+                            - cue_ball.position.angle_to(&ghost_ball).as_degrees()
+                            + 540.0)
+                            .rem_euclid(360.0)
+                            - 180.0;
+
+                        cut_degrees.abs() < MAX_OPEN_POCKET_CUT_DEGREES
+                            && self
+                                .path_obstructions(
+                                    &cue_ball.position,
+                                    &ghost_ball,
+                                    cue_ball.spec.radius.clone(),
+                                )
+                                .iter()
+                                .all(|ball| ball.ty == object_ball.ty)
+                    })
+            })
+            .collect()
+    }
+
+    /// Return every shot the shooter has at a legal object ball under `rules`, easiest first.
+    ///
+    /// A shot is one (object ball, pocket) pair that [`GameState::open_pockets`] reports as open,
+    /// that `rules` allows, and that has an [`aiming::shot_difficulty`]. Returns nothing when the
+    /// state has no cue ball, since difficulty depends on where the cue ball is.
+    pub fn makeable_shots(&self, rules: &dyn rules::GameRules) -> Vec<rules::MakeableShot> {
+        let Some(cue_ball) = self.select_ball(BallType::Cue) else {
+            return Vec::new();
+        };
+        let mut shots: Vec<rules::MakeableShot> = rules
+            .legal_object_balls(self)
+            .into_iter()
+            .filter_map(|ty| self.select_ball(ty))
+            .flat_map(|object_ball| {
+                self.open_pockets(object_ball)
+                    .into_iter()
+                    .filter(|&pocket| rules.is_legal_pocket(&object_ball.ty, pocket))
+                    .filter_map(|pocket| {
+                        let difficulty = aiming::shot_difficulty(
+                            &cue_ball.position,
+                            object_ball,
+                            pocket,
+                            &self.table_spec,
+                        )?;
+                        let ghost_ball = object_ball.ghost_ball_to_pocket(pocket, &self.table_spec);
+                        let cut_angle = CutAngle::from_headings(
+                            cue_ball.position.angle_to(&ghost_ball),
+                            ghost_ball.angle_to(&object_ball.position),
+                        );
+                        Some(rules::MakeableShot {
+                            object_ball: object_ball.ty.clone(),
+                            pocket,
+                            ghost_ball,
+                            cut_angle,
+                            difficulty,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        shots.sort_by(|a, b| a.difficulty.total_cmp(&b.difficulty));
+        shots
+    }
+
+    /// Return the position zones for the shot after `current_object_ball` is potted: one per
+    /// pocket `next_object_ball` can reach, covering where the cue ball must stop for that shot to
+    /// score at most `max_difficulty` on [`aiming::shot_difficulty`].
+    ///
+    /// `current_object_ball` is assumed to be gone and the cue ball to have moved, so neither
+    /// blocks the next ball's path or shrinks a zone.
+    pub fn position_zones(
+        &self,
+        current_object_ball: &Ball,
+        next_object_ball: &Ball,
+        max_difficulty: f64,
+    ) -> Vec<aiming::PositionZone> {
+        let remaining: Vec<Ball> = self
+            .ball_positions
+            .iter()
+            .filter(|ball| ball.ty != BallType::Cue && ball.ty != current_object_ball.ty)
+            .cloned()
+            .collect();
+        let start = position_xy_inches(&next_object_ball.position, &self.table_spec);
+
+        Pocket::ALL
+            .into_iter()
+            .filter(|&pocket| {
+                let end = position_xy_inches(&pocket.aiming_center(), &self.table_spec);
+                remaining
+                    .iter()
+                    .filter(|ball| ball.ty != next_object_ball.ty)
+                    .all(|ball| {
+                        let center = position_xy_inches(&ball.position, &self.table_spec);
+                        let clearance =
+                            next_object_ball.spec.radius.as_f64() + ball.spec.radius.as_f64();
+                        segment_point_distance_in_inches(start, end, center) >= clearance
+                    })
+            })
+            .filter_map(|pocket| {
+                aiming::position_zone(
+                    next_object_ball,
+                    pocket,
+                    max_difficulty,
+                    &remaining,
+                    &self.table_spec,
+                )
+            })
+            .collect()
+    }
+
     /// This is mildly hacky, but works for now to resolve all the unresolved
     /// inches adjustments.
     pub fn resolve_positions(&mut self) {
@@ -13848,6 +15493,451 @@ impl GameState {
         }
     }
 
+    /// The annotations added so far, in table coordinates and in the order they will be drawn.
+    pub fn overlays(&self) -> &[DiagramElement] {
+        &self.lines_to_draw
+    }
+
+    /// This state as JSON, for saving a layout or handing it to a web frontend;
+    /// [`GameState::from_json`] reads it back. The shape is stable:
+    ///
+    /// ```json
+    /// {
+    ///   "schema_version": 2,
+    ///   "table_spec": { "pockets": [...], "cushion_diamond_buffer": "0.295", "diamond_length": "12.5", "cushions": {...} },
+    ///   "balls": [{ "ty": "Cue", "position": { "x": "2", "y": "6" }, "spec": { "radius": "1.125" } }],
+    ///   "game_type": "NineBall",
+    ///   "cue_ball": "BallInHand",
+    ///   "annotations": [{ "kind": "text_label", "anchor": { "x": "2", "y": "4" }, "text": "SAFE", "style": {...} }],
+    ///   "annotation_layers": ["trace"]
+    /// }
+    /// ```
+    ///
+    /// Decimals are strings so they keep every digit, and colors are `#rrggbbaa`. Each annotation
+    /// is tagged with its `kind`, the snake_case name of its [`DiagramElement`] variant, and
+    /// `annotation_layers` names the layer of each annotation in turn, or holds `null`.
+    /// `schema_version` is the [`schema::SCHEMA_VERSION`] the file was written in.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&schema::Versioned::new(self))
+            .expect("a game state is always valid JSON")
+    }
+
+    /// Read a state written by [`GameState::to_json`] in this or any earlier schema version,
+    /// migrating it as [`schema::migrate`] does. Fields left out take their defaults, so
+    /// `{"balls": [...]}` alone is a layout on the default table.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let document = schema::migrate(schema::Document::Layout, serde_json::from_str(json)?)
+            .map_err(<serde_json::Error as serde::de::Error>::custom)?;
+        let mut state: Self = serde_json::from_value(document)?;
+        state.fill_missing_layer_names();
+        Ok(state)
+    }
+
+    /// This state, annotations and all, as a short URL-safe string for a link's query
+    /// parameter; [`GameState::from_share_string`] reads it back. It is the
+    /// [`GameState::to_json`] document compressed with deflate and written in unpadded base64
+    /// with the `-` and `_` alphabet, so it carries the schema version with it.
+    #[cfg(feature = "serde")]
+    pub fn to_share_string(&self) -> String {
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder
+            .write_all(self.to_json().as_bytes())
+            .and_then(|()| encoder.finish())
+            .map(|compressed| base64::encode_url_safe(&compressed))
+            .expect("compressing into memory cannot fail")
+    }
+
+    /// Read a state from a string written by [`GameState::to_share_string`]. Strings that
+    /// inflate past [`MAX_SHARE_STRING_JSON_BYTES`] are refused rather than unpacked.
+    #[cfg(feature = "serde")]
+    pub fn from_share_string(share: &str) -> Result<Self, String> {
+        use std::io::Read;
+
+        let compressed = base64::decode_url_safe(share.trim())
+            .ok_or("a share string is URL-safe base64 without padding")?;
+        let mut json = String::new();
+        flate2::read::DeflateDecoder::new(compressed.as_slice())
+            .take(MAX_SHARE_STRING_JSON_BYTES + 1)
+            .read_to_string(&mut json)
+            .map_err(|error| format!("a share string holds deflated JSON: {error}"))?;
+        if json.len() as u64 > MAX_SHARE_STRING_JSON_BYTES {
+            return Err(format!(
+                "a share string unpacks to more than {MAX_SHARE_STRING_JSON_BYTES} bytes"
+            ));
+        }
+        Self::from_json(&json).map_err(|error| error.to_string())
+    }
+
+    /// Put the annotations read without a layer name in no named layer.
+    #[cfg(feature = "serde")]
+    pub(crate) fn fill_missing_layer_names(&mut self) {
+        self.overlay_layer_names
+            .resize(self.lines_to_draw.len(), None);
+    }
+
+    /// What changed from this layout to `other`, matching balls by type; where a type appears
+    /// more than once, its balls are paired in order.
+    ///
+    /// Positions are compared after shifts are resolved, and equal decimals count as unmoved. A
+    /// layout alone cannot tell a pocketed ball from one taken off the table, so every ball
+    /// missing from `other` is `removed`;
+    /// [`SimulationResult::diff`](simulation::SimulationResult::diff) sorts out the pocketed ones.
+    pub fn diff(&self, other: &GameState) -> LayoutDiff {
+        fn resolved(state: &GameState) -> impl Iterator<Item = Ball> + '_ {
+            state.ball_positions.iter().map(|ball| {
+                let mut ball = ball.clone();
+                ball.position.resolve_shifts(&state.table_spec);
+                ball
+            })
+        }
+        let mut after = resolved(other).map(Some).collect::<Vec<_>>();
+        let mut diff = LayoutDiff::default();
+        for ball in resolved(self) {
+            let matched = after
+                .iter_mut()
+                .find(|candidate| candidate.as_ref().is_some_and(|c| c.ty == ball.ty))
+                .and_then(Option::take);
+            match matched {
+                Some(moved) if moved.position != ball.position => diff.moved.push(BallMove {
+                    displacement: ball.position.displacement(&moved.position),
+                    ty: ball.ty,
+                    from: ball.position,
+                    to: moved.position,
+                }),
+                Some(_) => {}
+                None => diff.removed.push(ball),
+            }
+        }
+        diff.added = after.into_iter().flatten().collect();
+        diff
+    }
+
+    /// A hash of the layout that stays the same across runs and platforms, for deduplicating
+    /// generated layouts and keying caches of rendered diagrams.
+    ///
+    /// It covers the table, the game, the cue ball modifier, and each ball's type, size and
+    /// position after shifts are resolved, with every decimal rounded to
+    /// [`FINGERPRINT_DECIMAL_PLACES`] places. The order the balls were added in and the
+    /// annotations are left out, so layouts that differ only in those share a fingerprint.
+    pub fn fingerprint(&self) -> u64 {
+        let decimal = |value: &BigDecimal| {
+            value
+                .round(FINGERPRINT_DECIMAL_PLACES)
+                .normalized()
+                .to_string()
+        };
+        let table = &self.table_spec;
+        let mut canonical = format!(
+            "table {} {}",
+            decimal(&table.diamond_length.magnitude),
+            decimal(&table.cushion_diamond_buffer.magnitude)
+        );
+        for pocket in &table.pockets {
+            let nose_radius = match &pocket.shape.jaw_geometry {
+                PocketJawGeometry::PointNoses => "point".to_string(),
+                PocketJawGeometry::RoundedNoses { nose_radius } => decimal(&nose_radius.magnitude),
+            };
+            canonical += &format!(
+                "|pocket {:?} {} {} {nose_radius}",
+                pocket.ty,
+                decimal(&pocket.depth.magnitude),
+                decimal(&pocket.width.magnitude)
+            );
+        }
+        for rail in [Rail::Top, Rail::Right, Rail::Bottom, Rail::Left] {
+            let cushion = table.cushions.for_rail(rail);
+            canonical += &format!(
+                "|cushion {} {} {} {}",
+                decimal(&cushion.normal_restitution.magnitude),
+                decimal(&cushion.tangential_friction_coefficient.magnitude),
+                decimal(&cushion.impact_cloth_friction_coefficient.magnitude),
+                decimal(&cushion.effective_contact_height_ratio.magnitude)
+            );
+        }
+        canonical += &format!("|game {:?} {:?}", self.ty, self.cueball_modifier);
+
+        let mut balls = self
+            .ball_positions
+            .iter()
+            .map(|ball| {
+                let mut position = ball.position.clone();
+                position.resolve_shifts(table);
+                format!(
+                    "|ball {:?} {} {} {}",
+                    ball.ty,
+                    decimal(&position.x.magnitude),
+                    decimal(&position.y.magnitude),
+                    decimal(&ball.spec.radius.magnitude)
+                )
+            })
+            .collect::<Vec<_>>();
+        balls.sort();
+        canonical.extend(balls);
+
+        // 64-bit FNV-1a: unlike the standard library's hashers it is fixed, so fingerprints can
+        // be stored.
+        canonical.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        })
+    }
+
+    /// This layout as one line of text, the way FEN writes a chess position, for pasting into
+    /// chat or a forum post; [`GameState::from_notation`] reads it back.
+    ///
+    /// The line has three fields split by spaces: the balls, the cue ball modifier and the game.
+    /// Each ball is its number, or `c` for the cue ball, then `:` and its x,y position in
+    /// diamonds, and balls are split by `/`. The modifier is `-` to play the cue ball as it
+    /// lays, `bih` for ball in hand, `kit` for kitchen placement or `brk` for break placement,
+    /// and the game is one of `9b`, `8b`, `10b`, `1p` or `bk`:
+    ///
+    /// ```text
+    /// c:2,6/1:3,4/9:3.5,7.25 bih 9b
+    /// ```
+    ///
+    /// Shifts still given in inches are resolved against the table first. Only the balls,
+    /// modifier and game are written; the table, the ball sizes and the annotations are left out.
+    pub fn to_notation(&self) -> String {
+        let balls = if self.ball_positions.is_empty() {
+            "-".to_string()
+        } else {
+            self.ball_positions
+                .iter()
+                .map(|ball| {
+                    let name = ball
+                        .ty
+                        .number()
+                        .map_or("c".to_string(), |number| number.to_string());
+                    let mut position = ball.position.clone();
+                    position.resolve_shifts(&self.table_spec);
+                    format!(
+                        "{name}:{},{}",
+                        position.x.magnitude.normalized(),
+                        position.y.magnitude.normalized()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("/")
+        };
+        format!(
+            "{balls} {} {}",
+            self.cueball_modifier.notation(),
+            self.ty.notation()
+        )
+    }
+
+    /// Read a layout written by [`GameState::to_notation`] onto the default table. The modifier
+    /// and game may be left off, as in `c:2,6/9:3,7.5`, and then the cue ball is played as it
+    /// lays in nine-ball.
+    pub fn from_notation(notation: &str) -> Result<Self, String> {
+        let mut fields = notation.split_whitespace();
+        let mut state = Self::new(TableSpec::default());
+
+        let balls = fields.next().ok_or("expected the balls, or `-` for none")?;
+        for entry in balls.split('/').filter(|_| balls != "-") {
+            let (name, position) = entry
+                .split_once(':')
+                .ok_or(format!("expected `ball:x,y`, found '{entry}'"))?;
+            let ty = match name {
+                "c" | "C" => BallType::Cue,
+                number => number
+                    .parse()
+                    .ok()
+                    .and_then(BallType::from_number)
+                    .ok_or(format!("unknown ball '{name}'"))?,
+            };
+            if state.select_ball(ty.clone()).is_some() {
+                return Err(format!("ball '{name}' is placed twice"));
+            }
+            let coordinate = |text: &str| {
+                BigDecimal::from_str(text)
+                    .map(|magnitude| Diamond { magnitude })
+                    .map_err(|_| format!("expected a number of diamonds, found '{text}'"))
+            };
+            let (x, y) = position.split_once(',').ok_or(format!(
+                "expected `x,y` after '{name}:', found '{position}'"
+            ))?;
+            state.add_ball(Ball {
+                ty,
+                position: Position::new(coordinate(x)?, coordinate(y)?),
+                spec: BallSpec::default(),
+            });
+        }
+
+        if let Some(modifier) = fields.next() {
+            state.cueball_modifier = CueballModifier::from_notation(modifier)
+                .ok_or(format!("unknown cue ball modifier '{modifier}'"))?;
+        }
+        if let Some(game) = fields.next() {
+            state.ty = GameType::from_notation(game).ok_or(format!("unknown game '{game}'"))?;
+        }
+        if let Some(extra) = fields.next() {
+            return Err(format!("unexpected '{extra}' after the game"));
+        }
+        Ok(state)
+    }
+
+    /// The balls as CSV with a `ball,x_diamonds,y_diamonds` header, or `ball,x_inches,y_inches`
+    /// for [`MeasurementUnit::Inches`], one row per ball, for charting racks in a spreadsheet.
+    /// The cue ball is `cue` and the others are their numbers, and shifts still given in inches
+    /// are resolved against the table first; [`GameState::from_csv`] reads it back.
+    pub fn to_csv(&self, unit: MeasurementUnit) -> String {
+        let mut csv = match unit {
+            MeasurementUnit::Diamonds => "ball,x_diamonds,y_diamonds\n",
+            MeasurementUnit::Inches => "ball,x_inches,y_inches\n",
+        }
+        .to_string();
+        for ball in &self.ball_positions {
+            let name = ball
+                .ty
+                .number()
+                .map_or("cue".to_string(), |number| number.to_string());
+            let mut position = ball.position.clone();
+            position.resolve_shifts(&self.table_spec);
+            let [x, y] = [&position.x, &position.y].map(|coordinate| match unit {
+                MeasurementUnit::Diamonds => coordinate.magnitude.normalized(),
+                MeasurementUnit::Inches => self
+                    .table_spec
+                    .diamond_to_inches(coordinate.clone())
+                    .magnitude
+                    .normalized(),
+            });
+            csv.push_str(&format!("{name},{x},{y}\n"));
+        }
+        csv
+    }
+
+    /// Read balls from CSV onto `table_spec`. The header names the columns, so they may come in
+    /// any order and columns besides `ball` and the x and y ones are skipped; the header picks
+    /// the unit, with `x_diamonds` and `y_diamonds` or `x_inches` and `y_inches`. Balls are
+    /// `cue` or their number, and blank rows are skipped.
+    pub fn from_csv(table_spec: TableSpec, csv: &str) -> Result<Self, String> {
+        let mut lines = csv
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let cells = |line: &str| {
+            line.split(',')
+                .map(|cell| cell.trim().trim_matches('"').trim().to_ascii_lowercase())
+                .collect::<Vec<_>>()
+        };
+
+        let (_, header) = lines.next().ok_or("expected a header row")?;
+        let header = cells(header);
+        let column = |name: &str| header.iter().position(|cell| cell == name);
+        let (x, y, unit) = match (column("x_diamonds"), column("y_diamonds")) {
+            (Some(x), Some(y)) => (x, y, MeasurementUnit::Diamonds),
+            _ => match (column("x_inches"), column("y_inches")) {
+                (Some(x), Some(y)) => (x, y, MeasurementUnit::Inches),
+                _ => {
+                    return Err(
+                        "expected `x_diamonds` and `y_diamonds` or `x_inches` and `y_inches` \
+                         columns"
+                            .to_string(),
+                    )
+                }
+            },
+        };
+        let ball = column("ball").ok_or("expected a `ball` column")?;
+
+        let mut state = Self::new(table_spec);
+        for (index, line) in lines {
+            let row = cells(line);
+            let cell = |column: usize| {
+                row.get(column).map(String::as_str).ok_or(format!(
+                    "line {}: expected {} cells",
+                    index + 1,
+                    header.len()
+                ))
+            };
+            let ty = match cell(ball)? {
+                "cue" => BallType::Cue,
+                name => name
+                    .parse()
+                    .ok()
+                    .and_then(BallType::from_number)
+                    .ok_or(format!("line {}: unknown ball '{name}'", index + 1))?,
+            };
+            if state.select_ball(ty.clone()).is_some() {
+                return Err(format!("line {}: the ball is placed twice", index + 1));
+            }
+            let coordinate = |column: usize| {
+                let text = cell(column)?;
+                let magnitude = BigDecimal::from_str(text).map_err(|_| {
+                    format!("line {}: expected a number, found '{text}'", index + 1)
+                })?;
+                Ok::<_, String>(match unit {
+                    MeasurementUnit::Diamonds => Diamond { magnitude },
+                    MeasurementUnit::Inches => {
+                        state.table_spec.inches_to_diamond(Inches { magnitude })
+                    }
+                })
+            };
+            let position = Position::new(coordinate(x)?, coordinate(y)?);
+            state.add_ball(Ball {
+                ty,
+                position,
+                spec: BallSpec::default(),
+            });
+        }
+        Ok(state)
+    }
+
+    /// Remove every annotation, leaving the balls where they are; dimmed balls are drawn solid
+    /// again.
+    pub fn clear_overlays(&mut self) {
+        self.lines_to_draw.clear();
+        self.overlay_layer_names.clear();
+        self.ball_focus = None;
+    }
+
+    /// Add the annotations made from now on to the layer called `name`, or to no named layer
+    /// with `None`. A drawing can leave named layers out with
+    /// [`DiagramRenderOptions::hidden_layers`], so one layout can be drawn both plain and
+    /// annotated; annotations in no named layer are always drawn.
+    pub fn set_named_layer(&mut self, name: Option<&str>) {
+        self.named_layer = name.map(str::to_owned);
+    }
+
+    /// Add the annotations `annotate` makes to the layer called `name`, then go back to the
+    /// layer that was current before.
+    pub fn in_named_layer(&mut self, name: &str, annotate: impl FnOnce(&mut Self)) {
+        let previous = self.named_layer.replace(name.to_owned());
+        annotate(self);
+        self.named_layer = previous;
+    }
+
+    /// The names of the layers that hold annotations, in the order they were first used.
+    pub fn named_layers(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for name in self.overlay_layer_names.iter().flatten() {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// The annotations in the layer called `name`, in the order they will be drawn.
+    pub fn overlays_in_layer<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a DiagramElement> {
+        self.lines_to_draw
+            .iter()
+            .zip(&self.overlay_layer_names)
+            .filter(move |(_, layer)| layer.as_deref() == Some(name))
+            .map(|(element, _)| element)
+    }
+
+    fn push_overlay(&mut self, element: DiagramElement) {
+        self.lines_to_draw.push(element);
+        self.overlay_layer_names.push(self.named_layer.clone());
+    }
+
     pub fn add_dotted_line(&mut self, from: &Position, to: &Position, color: Rgba<u8>) {
         self.add_dotted_line_styled(from, to, DashedLineStyle::new(color));
     }
@@ -13874,7 +15964,7 @@ impl GameState {
         let mut to = to.clone();
         to.resolve_shifts(&self.table_spec);
 
-        self.lines_to_draw.push(Overlay::DashedLine {
+        self.push_overlay(DiagramElement::DashedLine {
             start: from,
             end: to,
             style,
@@ -13901,6 +15991,83 @@ impl GameState {
         }
     }
 
+    /// Add a solid anti-aliased line overlay.
+    pub fn add_solid_line(&mut self, from: &Position, to: &Position, color: Rgba<u8>) {
+        self.add_solid_line_styled(from, to, SmoothPolylineStyle::new(color));
+    }
+
+    /// Add a solid line overlay with an arrowhead on `to`.
+    pub fn add_arrow(&mut self, from: &Position, to: &Position, color: Rgba<u8>) {
+        self.add_solid_line_styled(
+            from,
+            to,
+            SmoothPolylineStyle::new(color).with_arrow(ArrowStyle::default()),
+        );
+    }
+
+    pub fn add_solid_line_styled(
+        &mut self,
+        from: &Position,
+        to: &Position,
+        style: SmoothPolylineStyle,
+    ) {
+        self.add_smooth_polyline_styled(&[from.clone(), to.clone()], style);
+    }
+
+    /// Add a circular arc around `center` of `radius`, starting at `start_heading` from the center
+    /// and sweeping `sweep_degrees` clockwise (counterclockwise when negative), e.g. a swerve.
+    pub fn add_arc_path(
+        &mut self,
+        center: &Position,
+        radius: Inches,
+        start_heading: Angle,
+        sweep_degrees: f64,
+        style: SmoothPolylineStyle,
+    ) {
+        let (center_x, center_y) = position_xy_inches(center, &self.table_spec);
+        let radius = radius.as_f64();
+        let points: Vec<Position> = (0..=CURVE_SEGMENTS)
+            .map(|step| {
+                let heading = (start_heading.as_degrees()
+                    + sweep_degrees * step as f64 / CURVE_SEGMENTS as f64)
+                    .to_radians();
+                position_from_xy_inches(
+                    center_x + radius * heading.sin(),
+                    center_y + radius * heading.cos(),
+                    &self.table_spec,
+                )
+            })
+            .collect();
+        self.add_smooth_polyline_styled(&points, style);
+    }
+
+    /// Add a cubic Bézier curve from `start` to `end`, pulled toward `control_1` and `control_2`,
+    /// e.g. a massé curve.
+    pub fn add_bezier_path(
+        &mut self,
+        start: &Position,
+        control_1: &Position,
+        control_2: &Position,
+        end: &Position,
+        style: SmoothPolylineStyle,
+    ) {
+        let [p0, p1, p2, p3] = [start, control_1, control_2, end]
+            .map(|point| position_xy_inches(point, &self.table_spec));
+        let points: Vec<Position> = (0..=CURVE_SEGMENTS)
+            .map(|step| {
+                let t = step as f64 / CURVE_SEGMENTS as f64;
+                let u = 1.0 - t;
+                let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+                let x =
+                    weights[0] * p0.0 + weights[1] * p1.0 + weights[2] * p2.0 + weights[3] * p3.0;
+                let y =
+                    weights[0] * p0.1 + weights[1] * p1.1 + weights[2] * p2.1 + weights[3] * p3.1;
+                position_from_xy_inches(x, y, &self.table_spec)
+            })
+            .collect();
+        self.add_smooth_polyline_styled(&points, style);
+    }
+
     /// Add a smooth anti-aliased polyline overlay.
     pub fn add_smooth_polyline(&mut self, points: &[Position], color: Rgba<u8>) {
         self.add_smooth_polyline_styled(points, SmoothPolylineStyle::new(color));
@@ -13946,7 +16113,7 @@ impl GameState {
             resolved.push(point);
         }
 
-        self.lines_to_draw.push(Overlay::SmoothPolyline {
+        self.push_overlay(DiagramElement::SmoothPolyline {
             points: resolved,
             style,
         });
@@ -13987,20 +16154,93 @@ impl GameState {
     }
 
     pub fn add_ghost_ball_styled(&mut self, position: &Position, style: GhostBallStyle) {
+        self.add_ghost_ball_sized(position, &BallSpec::default(), style);
+    }
+
+    /// Add a ghost-ball marker the size of a ball built to `spec`.
+    pub fn add_ghost_ball_sized(
+        &mut self,
+        position: &Position,
+        spec: &BallSpec,
+        style: GhostBallStyle,
+    ) {
+        let mut position = position.clone();
+        position.resolve_shifts(&self.table_spec);
+
+        self.push_overlay(DiagramElement::GhostBall {
+            center: position,
+            spec: spec.clone(),
+            style,
+        });
+    }
+
+    /// Add a see-through `ty` ball at `position`, e.g. where the cue ball started or where an
+    /// object ball should end up. It is the size of the `ty` ball on the table, if there is one.
+    pub fn add_phantom_ball(&mut self, ty: BallType, position: &Position) {
+        self.add_phantom_ball_styled(ty, position, PhantomBallStyle::default());
+    }
+
+    pub fn add_phantom_ball_styled(
+        &mut self,
+        ty: BallType,
+        position: &Position,
+        style: PhantomBallStyle,
+    ) {
         let mut position = position.clone();
         position.resolve_shifts(&self.table_spec);
+        let spec = self
+            .select_ball(ty.clone())
+            .map(|ball| ball.spec.clone())
+            .unwrap_or_default();
 
-        self.lines_to_draw.push(Overlay::GhostBall {
+        self.push_overlay(DiagramElement::PhantomBall {
+            ty,
             center: position,
+            spec,
+            style,
+        });
+    }
+
+    /// Put a halo of `color` around the `ty` ball where it is now, so it stands out in a busy
+    /// layout. Nothing is drawn if there is no `ty` ball on the table.
+    pub fn highlight_ball(&mut self, ty: BallType, color: Rgba<u8>) {
+        self.highlight_ball_styled(ty, BallHighlightStyle::halo(color));
+    }
+
+    pub fn highlight_ball_styled(&mut self, ty: BallType, style: BallHighlightStyle) {
+        let Some(ball) = self.select_ball(ty.clone()) else {
+            return;
+        };
+        let (center, spec) = (ball.position.clone(), ball.spec.clone());
+
+        self.push_overlay(DiagramElement::BallHighlight {
+            ty,
+            center,
+            spec,
             style,
         });
     }
 
+    /// Draw every ball but `focus` faded, so the balls that matter to a shot stand out in a
+    /// lesson. Replaces any earlier focus.
+    pub fn dim_balls_except(&mut self, focus: &[BallType]) {
+        self.dim_balls_except_to(focus, DIMMED_BALL_OPACITY);
+    }
+
+    /// Like [`GameState::dim_balls_except`], with the other balls drawn at `opacity`, from 0
+    /// (invisible) to 1 (not dimmed).
+    pub fn dim_balls_except_to(&mut self, focus: &[BallType], opacity: f32) {
+        self.ball_focus = Some(BallFocus {
+            balls: focus.to_vec(),
+            opacity: opacity.clamp(0.0, 1.0),
+        });
+    }
+
     pub fn add_event_marker_styled(&mut self, position: &Position, style: EventMarkerStyle) {
         let mut position = position.clone();
         position.resolve_shifts(&self.table_spec);
 
-        self.lines_to_draw.push(Overlay::CircleMarker {
+        self.push_overlay(DiagramElement::CircleMarker {
             center: position,
             style,
         });
@@ -14015,13 +16255,242 @@ impl GameState {
         let mut anchor = anchor.clone();
         anchor.resolve_shifts(&self.table_spec);
 
-        self.lines_to_draw.push(Overlay::TextLabel {
+        self.push_overlay(DiagramElement::TextLabel {
             anchor,
             text: text.into(),
             style,
         });
     }
 
+    /// Add a circled `number` at `position`, marking one shot of a sequence.
+    pub fn add_sequence_marker(&mut self, position: &Position, number: u32) {
+        self.add_sequence_marker_styled(position, number, SequenceMarkerStyle::default());
+    }
+
+    pub fn add_sequence_marker_styled(
+        &mut self,
+        position: &Position,
+        number: u32,
+        style: SequenceMarkerStyle,
+    ) {
+        let mut position = position.clone();
+        position.resolve_shifts(&self.table_spec);
+
+        self.push_overlay(DiagramElement::SequenceMarker {
+            center: position,
+            number,
+            style,
+        });
+    }
+
+    /// Add a circled `number` halfway along the line from `from` to `to`, labelling that line as
+    /// one shot of a sequence.
+    pub fn add_sequence_marker_on_line(
+        &mut self,
+        from: &Position,
+        to: &Position,
+        number: u32,
+        style: SequenceMarkerStyle,
+    ) {
+        let (from_x, from_y) = position_xy_inches(from, &self.table_spec);
+        let (to_x, to_y) = position_xy_inches(to, &self.table_spec);
+        let midpoint = position_from_xy_inches(
+            0.5 * (from_x + to_x),
+            0.5 * (from_y + to_y),
+            &self.table_spec,
+        );
+        self.add_sequence_marker_styled(&midpoint, number, style);
+    }
+
+    /// Add an arc at `vertex` between the lines toward `start` and `end`, labelled with the angle
+    /// between them to the nearest degree.
+    pub fn add_angle_arc(
+        &mut self,
+        vertex: &Position,
+        start: &Position,
+        end: &Position,
+        color: Rgba<u8>,
+    ) {
+        self.add_angle_arc_styled(vertex, start, end, AngleArcStyle::new(color));
+    }
+
+    pub fn add_angle_arc_styled(
+        &mut self,
+        vertex: &Position,
+        start: &Position,
+        end: &Position,
+        style: AngleArcStyle,
+    ) {
+        let (vertex_x, vertex_y) = position_xy_inches(vertex, &self.table_spec);
+        let (start_x, start_y) = position_xy_inches(start, &self.table_spec);
+        let (end_x, end_y) = position_xy_inches(end, &self.table_spec);
+        let degrees = ((start_x - vertex_x) * (end_y - vertex_y)
+            - (start_y - vertex_y) * (end_x - vertex_x))
+            .atan2(
+                (start_x - vertex_x) * (end_x - vertex_x)
+                    + (start_y - vertex_y) * (end_y - vertex_y),
+            )
+            .to_degrees()
+            .abs();
+
+        let resolve = |position: &Position| {
+            let mut position = position.clone();
+            position.resolve_shifts(&self.table_spec);
+            position
+        };
+        self.push_overlay(DiagramElement::AngleArc {
+            vertex: resolve(vertex),
+            start: resolve(start),
+            end: resolve(end),
+            label: format!("{degrees:.0}°"),
+            style,
+        });
+    }
+
+    /// Add a line with an arrowhead on each end from `from` to `to`, labelled with the distance
+    /// between them in inches, e.g. to show how narrow a gap between two balls is.
+    pub fn add_measurement(&mut self, from: &Position, to: &Position, color: Rgba<u8>) {
+        self.add_measurement_styled(from, to, MeasurementStyle::new(color));
+    }
+
+    pub fn add_measurement_styled(
+        &mut self,
+        from: &Position,
+        to: &Position,
+        style: MeasurementStyle,
+    ) {
+        let (from_x, from_y) = position_xy_inches(from, &self.table_spec);
+        let (to_x, to_y) = position_xy_inches(to, &self.table_spec);
+        let inches = (to_x - from_x).hypot(to_y - from_y);
+        let label = match style.unit {
+            MeasurementUnit::Inches => format!("{inches:.2} in"),
+            MeasurementUnit::Diamonds => format!(
+                "{:.2} diamonds",
+                inches / self.table_spec.diamond_length.as_f64()
+            ),
+        };
+
+        self.add_solid_line_styled(
+            from,
+            to,
+            SmoothPolylineStyle {
+                width_px: style.width_px,
+                layer: style.layer,
+                ..SmoothPolylineStyle::new(style.color)
+            }
+            .with_arrow(ArrowStyle::both_ends().with_size(12.0, 8.0)),
+        );
+        let midpoint = position_from_xy_inches(
+            0.5 * (from_x + to_x),
+            0.5 * (from_y + to_y),
+            &self.table_spec,
+        );
+        // Clear of the line: centered above a line that runs across, beside one that runs along.
+        let scale_px = style.scale_px as i32;
+        let text_width_px = (6 * label.chars().count() as i32 - 1) * scale_px;
+        let (offset_x_px, offset_y_px) = if (to_x - from_x).abs() >= (to_y - from_y).abs() {
+            (-text_width_px / 2, -8 * scale_px - 4)
+        } else {
+            (8, -7 * scale_px / 2)
+        };
+        self.add_text_label_styled(
+            &midpoint,
+            label,
+            LabelOverlayStyle {
+                layer: style.layer,
+                offset_x_px,
+                offset_y_px,
+                scale_px: style.scale_px,
+                ..LabelOverlayStyle::enabled(style.color)
+            },
+        );
+    }
+
+    /// Shade and outline the mouth of `pocket`, e.g. to mark the called pocket.
+    pub fn highlight_pocket(&mut self, pocket: Pocket, color: Rgba<u8>) {
+        self.highlight_pocket_styled(pocket, PocketHighlightStyle::new(color));
+    }
+
+    /// Like [`GameState::highlight_pocket`]: a disc spanning the mouth between the jaws.
+    pub fn highlight_pocket_styled(&mut self, pocket: Pocket, style: PocketHighlightStyle) {
+        let (center, radius) = pocket_mouth_disc(pocket, &self.table_spec);
+        self.push_overlay(DiagramElement::PocketHighlight {
+            pocket,
+            center,
+            radius,
+            style,
+        });
+    }
+
+    /// Shade the polygon `outline`, in table coordinates, over the cloth and under the balls.
+    pub fn add_shaded_zone(&mut self, outline: &[Position], color: Rgba<u8>) {
+        self.add_shaded_zone_styled(outline, ShadedZoneStyle::new(color));
+    }
+
+    pub fn add_shaded_zone_styled(&mut self, outline: &[Position], style: ShadedZoneStyle) {
+        let outline = outline
+            .iter()
+            .map(|vertex| {
+                let mut vertex = vertex.clone();
+                vertex.resolve_shifts(&self.table_spec);
+                vertex
+            })
+            .collect();
+
+        self.push_overlay(DiagramElement::ShadedZone { outline, style });
+    }
+
+    /// Shade the axis-aligned rectangle with opposite corners `a` and `b`.
+    pub fn add_shaded_rectangle(&mut self, a: &Position, b: &Position, style: ShadedZoneStyle) {
+        let outline = [
+            a.clone(),
+            Position::new(b.x.clone(), a.y.clone()),
+            b.clone(),
+            Position::new(a.x.clone(), b.y.clone()),
+        ];
+        self.add_shaded_zone_styled(&outline, style);
+    }
+
+    /// Shade the kitchen, behind the head string, where the cue ball goes for a break or after a
+    /// scratch.
+    pub fn shade_kitchen(&mut self, style: ShadedZoneStyle) {
+        self.add_shaded_rectangle(
+            &Position::new(Diamond::zero(), Diamond::six()),
+            &Position::new(Diamond::four(), Diamond::eight()),
+            style,
+        );
+    }
+
+    /// Shade the outline of a [`aiming::PositionZone`].
+    pub fn add_position_zone_overlay(
+        &mut self,
+        zone: &aiming::PositionZone,
+        style: ShadedZoneStyle,
+    ) {
+        self.add_shaded_zone_styled(&zone.outline, style);
+    }
+
+    /// Add a legend keying the diagram's colors and line styles, its top-left corner at `anchor`.
+    pub fn add_legend(&mut self, anchor: &Position, entries: Vec<LegendEntry>) {
+        self.add_legend_styled(anchor, entries, LegendStyle::default());
+    }
+
+    pub fn add_legend_styled(
+        &mut self,
+        anchor: &Position,
+        entries: Vec<LegendEntry>,
+        style: LegendStyle,
+    ) {
+        let mut anchor = anchor.clone();
+        anchor.resolve_shifts(&self.table_spec);
+
+        self.push_overlay(DiagramElement::Legend {
+            anchor,
+            entries,
+            style,
+        });
+    }
+
     /// Add a dotted overlay for a traced ball path.
     pub fn add_dotted_ball_path(&mut self, path: &BallPath, color: Rgba<u8>) {
         self.add_dotted_ball_path_styled(path, &BallPathStyle::new(color));
@@ -14184,6 +16653,7 @@ impl GameState {
                         color: segment_color,
                         width_px: render.width_px_for_speed(initial_speed_ips, initial_speed_ips),
                         layer: style.line.layer,
+                        arrow: None,
                     },
                 ),
                 BallPathWidthMode::ScaleBySpeed => {
@@ -14217,6 +16687,7 @@ impl GameState {
                                 width_px: render
                                     .width_px_for_speed(initial_speed_ips, current_speed_ips),
                                 layer: style.line.layer,
+                                arrow: None,
                             },
                         );
                     }
@@ -14283,7 +16754,7 @@ impl GameState {
     ) -> Position {
         let ghost_ball = object_ball.ghost_ball(destination, &self.table_spec);
         if let Some(ghost_style) = &style.ghost_ball {
-            self.add_ghost_ball_styled(&ghost_ball, ghost_style.clone());
+            self.add_ghost_ball_sized(&ghost_ball, &object_ball.spec, ghost_style.clone());
         }
         let (line_start, line_end) = match &style.clip_endpoints_to_ball_radius {
             Some(radius) => self.clip_line_to_ball_edges(
@@ -14362,6 +16833,111 @@ impl GameState {
         self.add_dotted_aim_line_styled(object_ball, &target_center, shooting_position, style)
     }
 
+    /// Add the overlap picture for a fractional-ball hit: a ghost ball at the fractional-aim
+    /// contact point and a dotted aim line from the cue ball to it.
+    ///
+    /// Returns the ghost-ball center.
+    pub fn add_fractional_aim_overlay(
+        &mut self,
+        cue_ball: &Position,
+        object_ball: &Ball,
+        fraction: aiming::BallHitFraction,
+        direction: aiming::CutDirection,
+        style: &AimOverlayStyle,
+    ) -> Position {
+        let ghost_ball = aiming::fractional_aim_ghost_ball(
+            cue_ball,
+            object_ball,
+            fraction.fraction(),
+            direction,
+            &self.table_spec,
+        );
+        if let Some(ghost_style) = &style.ghost_ball {
+            self.add_ghost_ball_sized(&ghost_ball, &object_ball.spec, ghost_style.clone());
+        }
+        let (line_start, line_end) = match &style.clip_endpoints_to_ball_radius {
+            Some(radius) => {
+                self.clip_line_to_ball_edges(cue_ball, &ghost_ball, radius.clone(), radius.clone())
+            }
+            None => (cue_ball.clone(), ghost_ball.clone()),
+        };
+        self.add_dotted_line_styled(&line_start, &line_end, style.line.clone());
+        ghost_ball
+    }
+
+    /// Add a dotted bank-shot path, labeling each cushion contact with its diamond number when
+    /// `labels` is enabled.
+    pub fn add_bank_shot_overlay(
+        &mut self,
+        bank: &banking::BankShot,
+        line: &DashedLineStyle,
+        labels: &LabelOverlayStyle,
+    ) {
+        for leg in bank.path.windows(2) {
+            self.add_dotted_line_styled(&leg[0], &leg[1], line.clone());
+        }
+        if labels.enabled {
+            for contact in &bank.contacts {
+                let diamond = contact.diamond.magnitude.to_f64().unwrap_or_default();
+                self.add_text_label_styled(
+                    &contact.point,
+                    diamond_number_label(diamond),
+                    labels.clone(),
+                );
+            }
+        }
+    }
+
+    /// Add a spot-shot drill's aim and object-ball lines in `line`, and each of its scratch lines
+    /// in `scratch`.
+    pub fn add_spot_shot_overlay(
+        &mut self,
+        spot_shot: &drills::SpotShot,
+        line: &DashedLineStyle,
+        scratch: &DashedLineStyle,
+    ) {
+        for segment in [&spot_shot.aim_line, &spot_shot.object_ball_line] {
+            self.add_dotted_line_styled(&segment.start, &segment.end, line.clone());
+        }
+        for scratch_line in &spot_shot.scratch_lines {
+            self.add_dotted_polyline_styled(&scratch_line.path, scratch.clone());
+        }
+    }
+
+    /// Mark each stretch of a predicted path that risks a scratch, such as in red over the cue
+    /// ball's path.
+    pub fn add_scratch_risk_overlay(
+        &mut self,
+        risks: &[simulation::ScratchRisk],
+        style: &SmoothPolylineStyle,
+    ) {
+        for risk in risks {
+            self.add_smooth_polyline_styled(&risk.danger, style.clone());
+        }
+    }
+
+    /// Add a dotted diamond-system kick path, annotating the origin, aim, and target rails with
+    /// their system numbers when `labels` is enabled.
+    pub fn add_diamond_system_kick_overlay(
+        &mut self,
+        kick: &kicking::DiamondSystemKick,
+        line: &DashedLineStyle,
+        labels: &LabelOverlayStyle,
+    ) {
+        for segment in &kick.path {
+            self.add_dotted_line_styled(&segment.start, &segment.end, line.clone());
+        }
+        if labels.enabled {
+            for (point, number) in [
+                (&kick.origin_point, kick.origin_number),
+                (&kick.first_rail_point, kick.aim_number),
+                (&kick.target_point, kick.target_number),
+            ] {
+                self.add_text_label_styled(point, diamond_number_label(number), labels.clone());
+            }
+        }
+    }
+
     /// Builds a backend-neutral scene for the current `GameState`.
     pub fn to_diagram_scene(&self, options: &DiagramRenderOptions) -> DiagramScene {
         let mut resolved = self.clone();
@@ -14374,51 +16950,52 @@ impl GameState {
                 ty: ball.ty.clone(),
                 position: ball.position.clone(),
                 spec: ball.spec.clone(),
-            })
-            .collect();
-
-        let elements = resolved
-            .lines_to_draw
-            .iter()
-            .map(|overlay| match overlay {
-                Overlay::DashedLine { start, end, style } => DiagramElement::DashedLine {
-                    start: start.clone(),
-                    end: end.clone(),
-                    style: style.clone(),
-                },
-                Overlay::SmoothPolyline { points, style } => DiagramElement::SmoothPolyline {
-                    points: points.clone(),
-                    style: style.clone(),
-                },
-                Overlay::GhostBall { center, style } => DiagramElement::GhostBall {
-                    center: center.clone(),
-                    style: style.clone(),
-                },
-                Overlay::CircleMarker { center, style } => DiagramElement::CircleMarker {
-                    center: center.clone(),
-                    style: style.clone(),
-                },
-                Overlay::TextLabel {
-                    anchor,
-                    text,
-                    style,
-                } => DiagramElement::TextLabel {
-                    anchor: anchor.clone(),
-                    text: text.clone(),
-                    style: style.clone(),
+                opacity: match &resolved.ball_focus {
+                    Some(focus) if !focus.balls.contains(&ball.ty) => focus.opacity,
+                    _ => 1.0,
                 },
             })
             .collect();
 
+        let viewport = match (options.background, options.assets.as_deref()) {
+            (DiagramBackground::Procedural, _) => {
+                DiagramViewport::for_table_spec(&resolved.table_spec)
+            }
+            (_, Some(assets)) => DiagramViewport::for_assets(assets),
+            (_, None) => DiagramViewport::default(),
+        };
         DiagramScene {
             table_spec: resolved.table_spec,
-            viewport: DiagramViewport::default(),
+            viewport,
             background: options.background,
             balls,
-            elements,
+            elements: options
+                .diamond_grid
+                .clone()
+                .map(|style| DiagramElement::DiamondGrid { style })
+                .into_iter()
+                .chain(
+                    resolved
+                        .lines_to_draw
+                        .into_iter()
+                        .zip(resolved.overlay_layer_names)
+                        .filter(|(_, layer)| {
+                            layer
+                                .as_ref()
+                                .is_none_or(|name| !options.hidden_layers.contains(name))
+                        })
+                        .map(|(element, _)| element),
+                )
+                .collect(),
         }
     }
 
+    /// Where rendering this layout with `options` draws its balls, annotations and pockets, in
+    /// output pixels, for making the exported image clickable; see [`sidecar`].
+    pub fn diagram_sidecar(&self, options: &DiagramRenderOptions) -> DiagramSidecar {
+        DiagramSidecar::new(&self.to_diagram_scene(options), options)
+    }
+
     pub fn render_2d_diagram_with_options(
         &self,
         format: DiagramOutputFormat,
@@ -14437,6 +17014,44 @@ impl GameState {
         self.render_2d_diagram_with_options(DiagramOutputFormat::Png, options)
     }
 
+    /// Draws a 2D diagram of the current `GameState` with any backend, including ones defined
+    /// outside this crate.
+    pub fn render_with<B: DiagramBackend>(&self, options: &DiagramRenderOptions) -> B::Output {
+        B::render(&self.to_diagram_scene(options), options)
+    }
+
+    /// Draws a 2D diagram of the current `GameState` to an image, for callers that composite or
+    /// upload it themselves instead of decoding encoded bytes.
+    pub fn render_to_image(&self) -> RgbaImage {
+        self.render_to_image_with_options(&DiagramRenderOptions::default())
+    }
+
+    pub fn render_to_image_with_options(&self, options: &DiagramRenderOptions) -> RgbaImage {
+        render_scene_to_image(&self.to_diagram_scene(options), options)
+    }
+
+    /// Draws this layout and `after` side by side as a PNG, with an arrow from this one to
+    /// `after` and `caption` beneath both, e.g. a shot's starting layout and where the balls came
+    /// to rest.
+    pub fn draw_before_and_after_diagram(
+        &self,
+        after: &GameState,
+        caption: &str,
+        options: &DiagramRenderOptions,
+    ) -> Vec<u8> {
+        Sheet::before_and_after(
+            self.to_diagram_scene(options),
+            after.to_diagram_scene(options),
+            caption,
+        )
+        .render_to_png(options)
+    }
+
+    /// Draws a 2D diagram of the current `GameState` as a resolution-independent SVG document.
+    pub fn draw_svg_diagram(&self) -> String {
+        self.draw_2d_svg_with_options(&DiagramRenderOptions::default())
+    }
+
     pub fn draw_2d_svg_with_options(&self, options: &DiagramRenderOptions) -> String {
         String::from_utf8(self.render_2d_diagram_with_options(DiagramOutputFormat::Svg, options))
             .expect("SVG backend should emit UTF-8")
@@ -1,14 +1,17 @@
+use billiards::assets::AssetPack;
 use billiards::dsl::{parse_dsl_to_scenario, ScenarioTraceRenderOptions};
 use billiards::{
     diagram::DiagramOutputFormat,
     human_tuned_preview_motion_config,
-    visualization::{BallPathRenderOptions, PathColorMode},
-    BallSetPhysicsSpec, CollisionModel, DiagramBackground, DiagramRenderOptions,
-    OnTableMotionConfig, RailModel, Seconds,
+    visualization::{BallPathRenderOptions, DiamondGridStyle, PathColorMode, TableTheme},
+    BallSetPhysicsSpec, BallStyle, CollisionModel, DiagramBackground, DiagramCorner,
+    DiagramOrientation, DiagramRenderOptions, OnTableMotionConfig, RailModel, Seconds, Watermark,
 };
 use clap::{Parser, ValueEnum};
+use image::Rgba;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 fn shot_preview_motion_config() -> OnTableMotionConfig {
     human_tuned_preview_motion_config()
@@ -35,6 +38,10 @@ impl From<TraceColorModeArg> for PathColorMode {
 enum OutputFormatArg {
     Png,
     Svg,
+    Pdf,
+    Jpeg,
+    Webp,
+    Text,
 }
 
 impl From<OutputFormatArg> for DiagramOutputFormat {
@@ -42,10 +49,69 @@ impl From<OutputFormatArg> for DiagramOutputFormat {
         match value {
             OutputFormatArg::Png => DiagramOutputFormat::Png,
             OutputFormatArg::Svg => DiagramOutputFormat::Svg,
+            OutputFormatArg::Pdf => DiagramOutputFormat::Pdf,
+            OutputFormatArg::Jpeg => DiagramOutputFormat::Jpeg,
+            OutputFormatArg::Webp => DiagramOutputFormat::WebP,
+            OutputFormatArg::Text => DiagramOutputFormat::Text,
         }
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum TableThemeArg {
+    Classic,
+    Green,
+    Blue,
+    Red,
+    Dark,
+}
+
+impl From<TableThemeArg> for TableTheme {
+    fn from(value: TableThemeArg) -> Self {
+        match value {
+            TableThemeArg::Classic => TableTheme::classic(),
+            TableThemeArg::Green => TableTheme::green(),
+            TableThemeArg::Blue => TableTheme::blue(),
+            TableThemeArg::Red => TableTheme::red(),
+            TableThemeArg::Dark => TableTheme::dark(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum CornerArg {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl From<CornerArg> for DiagramCorner {
+    fn from(value: CornerArg) -> Self {
+        match value {
+            CornerArg::TopLeft => DiagramCorner::TopLeft,
+            CornerArg::TopRight => DiagramCorner::TopRight,
+            CornerArg::BottomLeft => DiagramCorner::BottomLeft,
+            CornerArg::BottomRight => DiagramCorner::BottomRight,
+        }
+    }
+}
+
+/// Parse a `#rrggbb` or `#rrggbbaa` color.
+fn parse_hex_color(value: &str) -> Result<Rgba<u8>, String> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 && hex.len() != 8 {
+        return Err(format!("expected #rrggbb or #rrggbbaa, got {value:?}"));
+    }
+    let channel = |index: usize| {
+        hex.get(2 * index..2 * index + 2)
+            .map(|digits| u8::from_str_radix(digits, 16))
+            .unwrap_or(Ok(255))
+            .map_err(|err| format!("invalid color {value:?}: {err}"))
+    };
+    Ok(Rgba([channel(0)?, channel(1)?, channel(2)?, channel(3)?]))
+}
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
@@ -65,6 +131,10 @@ struct Args {
     #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
     trace_start_ghosts: bool,
 
+    /// Render each moving ball see-through where it started, beside where it came to rest.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::Set)]
+    trace_start_phantoms: bool,
+
     /// Render event markers at traced segment endpoints.
     #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
     trace_event_markers: bool,
@@ -89,13 +159,94 @@ struct Args {
     #[arg(long, default_value_t = 1)]
     scale_factor: u32,
 
+    /// Shrink the export to fit within this many pixels across, e.g. for thumbnails.
+    #[arg(long)]
+    max_width: Option<u32>,
+
+    /// Shrink the export to fit within this many pixels down.
+    #[arg(long)]
+    max_height: Option<u32>,
+
     /// Render onto a transparent background instead of the table image.
     #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
     transparent_background: bool,
+
+    /// Draw the table from its specification instead of the bundled 9 ft table image.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    procedural_table: bool,
+
+    /// Draw the balls with their colors, stripes and numbers instead of the bundled ball images.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    procedural_balls: bool,
+
+    /// Draw the table with its long axis across the image.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    landscape: bool,
+
+    /// Color scheme for the table art.
+    #[arg(long, value_enum, default_value_t = TableThemeArg::Classic)]
+    theme: TableThemeArg,
+
+    /// Paint this `#rrggbb` or `#rrggbbaa` color behind the table and over the margin.
+    #[arg(long, value_parser = parse_hex_color)]
+    background_color: Option<Rgba<u8>>,
+
+    /// Pad the export with this many pixels of the background color on every side.
+    #[arg(long, default_value_t = 0)]
+    margin: u32,
+
+    /// Draw faint lines through the diamonds, numbered 0–4 and 0–8 along the rails.
+    #[arg(long, default_value_t = false, action = clap::ArgAction::SetTrue)]
+    diamond_grid: bool,
+
+    /// Record this many pixels per inch in PNG and JPEG exports, and size SVG exports in inches.
+    #[arg(long)]
+    dpi: Option<f64>,
+
+    /// Scale the export to print this many inches wide, at --dpi or 300 dpi.
+    #[arg(long)]
+    print_width: Option<f64>,
+
+    /// Leave out the annotations in this named layer, e.g. `trace`; may be repeated.
+    #[arg(long)]
+    hide_layer: Vec<String>,
+
+    /// Brand the export with the logo in this image file.
+    #[arg(long)]
+    watermark: Option<PathBuf>,
+
+    /// Brand the export with this line of white text, if no --watermark logo is given.
+    #[arg(long)]
+    watermark_text: Option<String>,
+
+    /// Which corner of the export the watermark goes in.
+    #[arg(long, value_enum, default_value_t = CornerArg::BottomRight)]
+    watermark_corner: CornerArg,
+
+    /// How solid the watermark is, from 0 to 1.
+    #[arg(long, default_value_t = 0.5)]
+    watermark_opacity: f32,
+
+    /// Directory holding a custom `table.png`, its `calibration.toml` and any `ball_*.png`
+    /// sprites to draw with instead of the built-in art.
+    #[arg(long)]
+    assets_dir: Option<PathBuf>,
+
+    /// Also write where each ball, annotation and pocket was drawn, in output pixels, to this
+    /// JSON file, so a web page can make the exported image clickable.
+    #[cfg(feature = "serde")]
+    #[arg(long)]
+    sidecar: Option<PathBuf>,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
+    let assets = args
+        .assets_dir
+        .as_deref()
+        .map(AssetPack::load)
+        .transpose()?
+        .map(Arc::new);
 
     let input_content = fs::read_to_string(&args.input)
         .map_err(|e| format!("Failed to read input file {:?}: {}", args.input, e))?;
@@ -116,6 +267,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             ..BallPathRenderOptions::default()
         },
         start_ghost_balls: args.trace_start_ghosts,
+        start_phantom_balls: args.trace_start_phantoms,
         event_markers: args.trace_event_markers,
         labels: args.trace_labels,
         path_color_mode: args.trace_color_mode.into(),
@@ -202,15 +354,62 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    let watermark = match (&args.watermark, args.watermark_text) {
+        (Some(path), _) => Some(
+            Watermark::load(path)
+                .map_err(|e| format!("Failed to read watermark {:?}: {}", path, e))?,
+        ),
+        (None, Some(text)) => Some(Watermark::text(text, Rgba([255, 255, 255, 255]))),
+        (None, None) => None,
+    }
+    .map(|watermark| {
+        watermark
+            .in_corner(args.watermark_corner.into())
+            .with_opacity(args.watermark_opacity)
+    });
+
     let render_options = DiagramRenderOptions {
         scale_factor: args.scale_factor.max(1),
+        max_width_px: args.max_width,
+        max_height_px: args.max_height,
+        jpeg_quality: 90,
         background: if args.transparent_background {
             DiagramBackground::Transparent
+        } else if args.procedural_table {
+            DiagramBackground::Procedural
         } else {
             DiagramBackground::Table
         },
+        orientation: if args.landscape {
+            DiagramOrientation::Landscape
+        } else {
+            DiagramOrientation::Portrait
+        },
+        table_theme: args.theme.into(),
+        canvas_color: args.background_color.unwrap_or(Rgba([0, 0, 0, 0])),
+        margin_px: args.margin,
+        crop: None,
+        diamond_grid: args.diamond_grid.then(DiamondGridStyle::default),
+        assets,
+        ball_style: if args.procedural_balls {
+            BallStyle::Procedural
+        } else {
+            BallStyle::Sprites
+        },
+        dpi: args.dpi,
+        print_width_in: args.print_width,
+        hidden_layers: args.hide_layer,
+        watermark,
     };
     let diagram = render_state.render_2d_diagram_with_options(output_format, &render_options);
+    #[cfg(feature = "serde")]
+    if let Some(sidecar_path) = &args.sidecar {
+        let sidecar = render_state.diagram_sidecar(&render_options).to_json();
+        fs::write(sidecar_path, sidecar).map_err(|error| {
+            format!("Failed to write sidecar file {:?}: {}", sidecar_path, error)
+        })?;
+        println!("Sidecar written to {:?}", sidecar_path);
+    }
 
     fs::write(&output_path, diagram)
         .map_err(|error| format!("Failed to write output file {:?}: {}", output_path, error))?;
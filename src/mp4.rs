@@ -0,0 +1,347 @@
+//! MP4 video of animation frames, encoded as AV1 with rav1e.
+//!
+//! The file holds one video track with every frame in a single chunk: an `ftyp` box, the encoded
+//! frames in `mdat`, then the `moov` index that players read to find them. Frames are converted to
+//! limited-range BT.709 4:2:0, the form nearly every player decodes.
+
+use image::{Rgba, RgbaImage};
+use rav1e::color::{
+    ChromaSampling, ColorDescription, ColorPrimaries, MatrixCoefficients, PixelRange,
+    TransferCharacteristics,
+};
+use rav1e::prelude::{Config, Context, EncoderConfig, EncoderStatus, FrameType, Rational};
+use std::io::{self, Write};
+
+/// Track time is counted in milliseconds.
+const TIMESCALE: u32 = 1000;
+
+const OBU_SEQUENCE_HEADER: u8 = 1;
+const OBU_TEMPORAL_DELIMITER: u8 = 2;
+
+struct Sample {
+    data: Vec<u8>,
+    duration_ms: u32,
+    keyframe: bool,
+}
+
+/// Encode `frames`, each with how long it is shown, as an MP4 file with see-through pixels drawn
+/// over `background`, and write it to `writer`. Frames are cropped to the even size of the first,
+/// since each chroma sample covers a 2×2 block, and encoded as they arrive.
+pub(crate) fn encode(
+    frames: impl Iterator<Item = (RgbaImage, u32)>,
+    background: Rgba<u8>,
+    writer: impl Write,
+) -> io::Result<()> {
+    let mut frames = frames.peekable();
+    let (width, height, first_duration_ms) = frames
+        .peek()
+        .map(|(frame, duration_ms)| (frame.width() & !1, frame.height() & !1, *duration_ms))
+        .unwrap_or((2, 2, 1));
+    let (width, height) = (width.max(2), height.max(2));
+    let config = Config::new().with_encoder_config(EncoderConfig {
+        width: width as usize,
+        height: height as usize,
+        time_base: Rational::new(u64::from(first_duration_ms.max(1)), u64::from(TIMESCALE)),
+        bit_depth: 8,
+        chroma_sampling: ChromaSampling::Cs420,
+        pixel_range: PixelRange::Limited,
+        color_description: Some(ColorDescription {
+            color_primaries: ColorPrimaries::BT709,
+            transfer_characteristics: TransferCharacteristics::BT709,
+            matrix_coefficients: MatrixCoefficients::BT709,
+        }),
+        // Frames come out in the order they went in, so samples need no reordering.
+        low_latency: true,
+        ..EncoderConfig::with_speed_preset(10)
+    });
+    let mut context: Context<u8> = config.new_context().expect("AV1 encoder config is valid");
+    let sequence_header = context.container_sequence_header();
+
+    let mut packets = Vec::new();
+    let mut durations_ms = Vec::new();
+    for (image, duration_ms) in frames {
+        let mut frame = context.new_frame();
+        let [y, u, v] = to_yuv420(&image, width, height, background);
+        frame.planes[0].copy_from_raw_u8(&y, width as usize, 1);
+        frame.planes[1].copy_from_raw_u8(&u, width as usize / 2, 1);
+        frame.planes[2].copy_from_raw_u8(&v, width as usize / 2, 1);
+        context.send_frame(frame).expect("AV1 encode failed");
+        durations_ms.push(duration_ms);
+        receive_packets(&mut context, &mut packets);
+    }
+    context.flush();
+    receive_packets(&mut context, &mut packets);
+
+    let mut config_obus = Vec::new();
+    let samples: Vec<Sample> = packets
+        .into_iter()
+        .map(|(frameno, data, keyframe)| {
+            if config_obus.is_empty() {
+                config_obus = obus_of_type(&data, OBU_SEQUENCE_HEADER);
+            }
+            Sample {
+                data: without_temporal_delimiters(&data),
+                duration_ms: durations_ms.get(frameno).copied().unwrap_or(1).max(1),
+                keyframe,
+            }
+        })
+        .collect();
+
+    let mut av1c = sequence_header;
+    av1c.extend_from_slice(&config_obus);
+    mux(&samples, width, height, &av1c, writer)
+}
+
+fn receive_packets(context: &mut Context<u8>, packets: &mut Vec<(usize, Vec<u8>, bool)>) {
+    loop {
+        match context.receive_packet() {
+            Ok(packet) => packets.push((
+                packet.input_frameno as usize,
+                packet.data,
+                packet.frame_type == FrameType::KEY,
+            )),
+            Err(EncoderStatus::Encoded) => {}
+            Err(EncoderStatus::NeedMoreData | EncoderStatus::LimitReached) => return,
+            Err(status) => panic!("AV1 encode failed: {status:?}"),
+        }
+    }
+}
+
+/// The planes of `image`, cropped to `width` by `height`, with chroma averaged over each 2×2
+/// block.
+fn to_yuv420(image: &RgbaImage, width: u32, height: u32, background: Rgba<u8>) -> [Vec<u8>; 3] {
+    let rgb = |x: u32, y: u32| {
+        let pixel = image.get_pixel(x, y);
+        let alpha = pixel[3] as f32 / 255.0;
+        let channel = |index: usize| {
+            (pixel[index] as f32 * alpha + background[index] as f32 * (1.0 - alpha)) / 255.0
+        };
+        [channel(0), channel(1), channel(2)]
+    };
+    let luma = |[r, g, b]: [f32; 3]| 0.2126 * r + 0.7152 * g + 0.0722 * b;
+
+    let mut y_plane = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            y_plane.push((16.0 + 219.0 * luma(rgb(x, y))).round() as u8);
+        }
+    }
+    let mut u_plane = Vec::with_capacity((width * height / 4) as usize);
+    let mut v_plane = Vec::with_capacity((width * height / 4) as usize);
+    for y in (0..height).step_by(2) {
+        for x in (0..width).step_by(2) {
+            let block = [rgb(x, y), rgb(x + 1, y), rgb(x, y + 1), rgb(x + 1, y + 1)];
+            let [r, g, b] =
+                [0, 1, 2].map(|index| block.iter().map(|px| px[index]).sum::<f32>() / 4.0);
+            let y = luma([r, g, b]);
+            u_plane.push((128.0 + 224.0 * (b - y) / 1.8556).round() as u8);
+            v_plane.push((128.0 + 224.0 * (r - y) / 1.5748).round() as u8);
+        }
+    }
+    [y_plane, u_plane, v_plane]
+}
+
+/// Split `data` into its OBUs as `(type, bytes)`. rav1e always writes each OBU's size.
+fn obus(data: &[u8]) -> impl Iterator<Item = (u8, &[u8])> {
+    let mut rest = data;
+    std::iter::from_fn(move || {
+        let header = *rest.first()?;
+        let obu_type = (header >> 3) & 0xf;
+        let mut offset = 1 + usize::from(header & 0b100 != 0);
+        let mut size = 0;
+        for shift in (0..8).map(|byte| 7 * byte) {
+            let byte = *rest.get(offset)?;
+            offset += 1;
+            size |= usize::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        let (obu, tail) = rest.split_at((offset + size).min(rest.len()));
+        rest = tail;
+        Some((obu_type, obu))
+    })
+}
+
+fn obus_of_type(data: &[u8], obu_type: u8) -> Vec<u8> {
+    obus(data)
+        .filter(|&(ty, _)| ty == obu_type)
+        .flat_map(|(_, obu)| obu.iter().copied())
+        .collect()
+}
+
+/// MP4 samples leave out temporal delimiters; each sample is already one temporal unit.
+fn without_temporal_delimiters(data: &[u8]) -> Vec<u8> {
+    obus(data)
+        .filter(|&(ty, _)| ty != OBU_TEMPORAL_DELIMITER)
+        .flat_map(|(_, obu)| obu.iter().copied())
+        .collect()
+}
+
+/// A box: its size, its four-character type, then `body`.
+fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(8 + body.len());
+    out.extend_from_slice(&(8 + body.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(body);
+    out
+}
+
+/// A full box, whose body starts with a version byte and three bytes of flags.
+fn full_box(kind: &[u8; 4], version: u8, flags: u32, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4 + body.len());
+    out.extend_from_slice(&((u32::from(version) << 24) | flags).to_be_bytes());
+    out.extend_from_slice(body);
+    mp4_box(kind, &out)
+}
+
+fn be_u32s(values: impl IntoIterator<Item = u32>) -> Vec<u8> {
+    values.into_iter().flat_map(u32::to_be_bytes).collect()
+}
+
+const IDENTITY_MATRIX: [u32; 9] = [0x0001_0000, 0, 0, 0, 0x0001_0000, 0, 0, 0, 0x4000_0000];
+
+/// Write the file: `ftyp`, then every sample in `mdat`, then the `moov` index.
+fn mux(
+    samples: &[Sample],
+    width: u32,
+    height: u32,
+    av1c: &[u8],
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let duration: u32 = samples.iter().map(|sample| sample.duration_ms).sum();
+    let ftyp = mp4_box(b"ftyp", b"isom\0\0\x02\0isomiso6av01mp41");
+    let mdat_size: usize = 8 + samples
+        .iter()
+        .map(|sample| sample.data.len())
+        .sum::<usize>();
+    let chunk_offset = (ftyp.len() + 8) as u32;
+
+    let mvhd = full_box(
+        b"mvhd",
+        0,
+        0,
+        &[
+            be_u32s([0, 0, TIMESCALE, duration, 0x0001_0000]),
+            vec![0x01, 0x00, 0, 0],
+            vec![0; 8],
+            be_u32s(IDENTITY_MATRIX),
+            vec![0; 24],
+            be_u32s([2]),
+        ]
+        .concat(),
+    );
+    let tkhd = full_box(
+        b"tkhd",
+        0,
+        0b11,
+        &[
+            be_u32s([0, 0, 1, 0, duration, 0, 0, 0, 0]),
+            be_u32s(IDENTITY_MATRIX),
+            be_u32s([width << 16, height << 16]),
+        ]
+        .concat(),
+    );
+    let mdhd = full_box(
+        b"mdhd",
+        0,
+        0,
+        &[be_u32s([0, 0, TIMESCALE, duration]), vec![0x55, 0xc4, 0, 0]].concat(),
+    );
+    let hdlr = full_box(
+        b"hdlr",
+        0,
+        0,
+        &[
+            be_u32s([0]),
+            b"vide".to_vec(),
+            vec![0; 12],
+            b"Video\0".to_vec(),
+        ]
+        .concat(),
+    );
+    let vmhd = full_box(b"vmhd", 0, 1, &[0; 8]);
+    let dinf = mp4_box(
+        b"dinf",
+        &full_box(
+            b"dref",
+            0,
+            0,
+            &[be_u32s([1]), full_box(b"url ", 0, 1, &[])].concat(),
+        ),
+    );
+
+    let av01 = mp4_box(
+        b"av01",
+        &[
+            vec![0, 0, 0, 0, 0, 0, 0, 1],
+            vec![0; 16],
+            vec![
+                (width >> 8) as u8,
+                width as u8,
+                (height >> 8) as u8,
+                height as u8,
+            ],
+            be_u32s([0x0048_0000, 0x0048_0000, 0]),
+            vec![0, 1],
+            vec![0; 32],
+            vec![0x00, 0x18, 0xff, 0xff],
+            mp4_box(b"av1C", av1c),
+        ]
+        .concat(),
+    );
+    let stsd = full_box(b"stsd", 0, 0, &[be_u32s([1]), av01].concat());
+    let mut runs: Vec<(u32, u32)> = Vec::new();
+    for sample in samples {
+        match runs.last_mut() {
+            Some((count, duration)) if *duration == sample.duration_ms => *count += 1,
+            _ => runs.push((1, sample.duration_ms)),
+        }
+    }
+    let stts = full_box(
+        b"stts",
+        0,
+        0,
+        &be_u32s(
+            std::iter::once(runs.len() as u32)
+                .chain(runs.iter().flat_map(|&(count, duration)| [count, duration])),
+        ),
+    );
+    let keyframes: Vec<u32> = (1..)
+        .zip(samples)
+        .filter(|(_, sample)| sample.keyframe)
+        .map(|(number, _)| number)
+        .collect();
+    let stss = full_box(
+        b"stss",
+        0,
+        0,
+        &be_u32s(std::iter::once(keyframes.len() as u32).chain(keyframes)),
+    );
+    let stsc = full_box(b"stsc", 0, 0, &be_u32s([1, 1, samples.len() as u32, 1]));
+    let stsz = full_box(
+        b"stsz",
+        0,
+        0,
+        &be_u32s(
+            [0, samples.len() as u32]
+                .into_iter()
+                .chain(samples.iter().map(|sample| sample.data.len() as u32)),
+        ),
+    );
+    let stco = full_box(b"stco", 0, 0, &be_u32s([1, chunk_offset]));
+    let stbl = mp4_box(b"stbl", &[stsd, stts, stss, stsc, stsz, stco].concat());
+    let minf = mp4_box(b"minf", &[vmhd, dinf, stbl].concat());
+    let mdia = mp4_box(b"mdia", &[mdhd, hdlr, minf].concat());
+    let trak = mp4_box(b"trak", &[tkhd, mdia].concat());
+    let moov = mp4_box(b"moov", &[mvhd, trak].concat());
+
+    writer.write_all(&ftyp)?;
+    writer.write_all(&(mdat_size as u32).to_be_bytes())?;
+    writer.write_all(b"mdat")?;
+    for sample in samples {
+        writer.write_all(&sample.data)?;
+    }
+    writer.write_all(&moov)?;
+    writer.flush()
+}
@@ -0,0 +1,334 @@
+//! Printable PDF sheets of diagrams.
+//!
+//! Each diagram is drawn with the raster backend and embedded as a lossless image, laid out in a
+//! grid of cells on each page. Diagrams can be printed at a fixed scale of the real table, so
+//! every diagram on a handout shares one scale.
+
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use image::RgbaImage;
+
+use crate::diagram::{render_scene_to_image, DiagramScene};
+use crate::{DiagramRenderOptions, Inches};
+
+pub const POINTS_PER_INCH: f64 = 72.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfPageLayout {
+    pub page_width_pt: f64,
+    pub page_height_pt: f64,
+    pub margin_pt: f64,
+    pub columns: u32,
+    pub rows: u32,
+    /// Print inches per inch of real table, e.g. `1.0 / 24.0`. `None` fits each diagram to its
+    /// cell; a scaled diagram too big for its cell is shrunk to fit.
+    pub print_scale: Option<f64>,
+}
+
+impl Default for PdfPageLayout {
+    fn default() -> Self {
+        Self::letter()
+    }
+}
+
+impl PdfPageLayout {
+    /// US Letter, one diagram per page.
+    pub fn letter() -> Self {
+        Self {
+            page_width_pt: 8.5 * POINTS_PER_INCH,
+            page_height_pt: 11.0 * POINTS_PER_INCH,
+            margin_pt: 0.5 * POINTS_PER_INCH,
+            columns: 1,
+            rows: 1,
+            print_scale: None,
+        }
+    }
+
+    /// ISO A4, one diagram per page.
+    pub fn a4() -> Self {
+        Self {
+            page_width_pt: 210.0 / 25.4 * POINTS_PER_INCH,
+            page_height_pt: 297.0 / 25.4 * POINTS_PER_INCH,
+            ..Self::letter()
+        }
+    }
+
+    pub fn with_grid(mut self, columns: u32, rows: u32) -> Self {
+        self.columns = columns.max(1);
+        self.rows = rows.max(1);
+        self
+    }
+
+    pub fn with_print_scale(mut self, print_scale: f64) -> Self {
+        self.print_scale = Some(print_scale);
+        self
+    }
+
+    fn diagrams_per_page(&self) -> usize {
+        (self.columns.max(1) * self.rows.max(1)) as usize
+    }
+
+    /// The lower-left corner and size of cell `index` on its page, in points.
+    fn cell(&self, index: usize) -> (f64, f64, f64, f64) {
+        let columns = self.columns.max(1) as usize;
+        let rows = self.rows.max(1) as usize;
+        let width = (self.page_width_pt - 2.0 * self.margin_pt) / columns as f64;
+        let height = (self.page_height_pt - 2.0 * self.margin_pt) / rows as f64;
+        let (column, row) = (index % columns, index / columns);
+        (
+            self.margin_pt + column as f64 * width,
+            self.page_height_pt - self.margin_pt - (row + 1) as f64 * height,
+            width,
+            height,
+        )
+    }
+}
+
+/// Lay `scenes` out on as many pages as they need and encode the document.
+pub fn diagrams_to_pdf(
+    scenes: &[DiagramScene],
+    options: &DiagramRenderOptions,
+    layout: &PdfPageLayout,
+) -> Vec<u8> {
+    let per_page = layout.diagrams_per_page();
+    let page_count = scenes.len().div_ceil(per_page).max(1);
+
+    // Objects 1 and 2 are the catalog and page tree; each page then takes a page and a content
+    // object, and each diagram an image and its alpha mask.
+    let page_id = |page: usize| 3 + 2 * page;
+    let image_id = |diagram: usize| 3 + 2 * page_count + 2 * diagram;
+
+    let mut pdf = PdfWriter::new();
+    pdf.object(1, "<< /Type /Catalog /Pages 2 0 R >>", None);
+    let kids: Vec<String> = (0..page_count)
+        .map(|page| format!("{} 0 R", page_id(page)))
+        .collect();
+    pdf.object(
+        2,
+        &format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            page_count
+        ),
+        None,
+    );
+
+    let images: Vec<RgbaImage> = scenes
+        .iter()
+        .map(|scene| render_scene_to_image(scene, options))
+        .collect();
+
+    for page in 0..page_count {
+        let diagrams = (page * per_page)..((page + 1) * per_page).min(scenes.len());
+        let mut content = String::new();
+        let mut resources = String::new();
+        for diagram in diagrams {
+            let (width_pt, height_pt) =
+                diagram_size_pt(&scenes[diagram], &images[diagram], options, layout);
+            let (x, y, cell_width, cell_height) = layout.cell(diagram - page * per_page);
+            content.push_str(&format!(
+                "q {:.3} 0 0 {:.3} {:.3} {:.3} cm /Im{} Do Q\n",
+                width_pt,
+                height_pt,
+                x + (cell_width - width_pt) / 2.0,
+                y + (cell_height - height_pt) / 2.0,
+                diagram
+            ));
+            resources.push_str(&format!("/Im{} {} 0 R ", diagram, image_id(diagram)));
+        }
+
+        pdf.object(
+            page_id(page),
+            &format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.3} {:.3}] /Resources << /XObject << {}>> >> /Contents {} 0 R >>",
+                layout.page_width_pt,
+                layout.page_height_pt,
+                resources,
+                page_id(page) + 1
+            ),
+            None,
+        );
+        pdf.object(page_id(page) + 1, "<< >>", Some(content.as_bytes()));
+    }
+
+    for (diagram, image) in images.iter().enumerate() {
+        pdf.image(image_id(diagram), image);
+    }
+
+    pdf.finish()
+}
+
+/// One page for each of `images`, shrunk to fit the first cell of `layout` and centered in it,
+/// e.g. [`crate::sheet::Sheet`]s already laid out.
+pub fn images_to_pdf(images: &[RgbaImage], layout: &PdfPageLayout) -> Vec<u8> {
+    let (x, y, cell_width, cell_height) = layout.cell(0);
+    let page_count = images.len();
+
+    // Objects 1 and 2 are the catalog and page tree; each page then takes a page, a content
+    // object, and its image and the image's alpha mask.
+    let page_id = |page: usize| 3 + 4 * page;
+
+    let mut pdf = PdfWriter::new();
+    pdf.object(1, "<< /Type /Catalog /Pages 2 0 R >>", None);
+    let kids: Vec<String> = (0..page_count)
+        .map(|page| format!("{} 0 R", page_id(page)))
+        .collect();
+    pdf.object(
+        2,
+        &format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            page_count
+        ),
+        None,
+    );
+
+    for (page, image) in images.iter().enumerate() {
+        let (width_px, height_px) = (image.width() as f64, image.height() as f64);
+        let points_per_px = (cell_width / width_px).min(cell_height / height_px);
+        let (width_pt, height_pt) = (width_px * points_per_px, height_px * points_per_px);
+
+        pdf.object(
+            page_id(page),
+            &format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.3} {:.3}] /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>",
+                layout.page_width_pt,
+                layout.page_height_pt,
+                page_id(page) + 2,
+                page_id(page) + 1
+            ),
+            None,
+        );
+        let content = format!(
+            "q {:.3} 0 0 {:.3} {:.3} {:.3} cm /Im0 Do Q\n",
+            width_pt,
+            height_pt,
+            x + (cell_width - width_pt) / 2.0,
+            y + (cell_height - height_pt) / 2.0
+        );
+        pdf.object(page_id(page) + 1, "<< >>", Some(content.as_bytes()));
+        pdf.image(page_id(page) + 2, image);
+    }
+    pdf.finish()
+}
+
+/// How big a diagram is printed: at the layout's scale of the real table when it has one,
+/// shrunk to fit its cell.
+fn diagram_size_pt(
+    scene: &DiagramScene,
+    image: &RgbaImage,
+    options: &DiagramRenderOptions,
+    layout: &PdfPageLayout,
+) -> (f64, f64) {
+    let (_, _, cell_width, cell_height) = layout.cell(0);
+    let (width_px, height_px) = (image.width() as f64, image.height() as f64);
+    let fit = (cell_width / width_px).min(cell_height / height_px);
+
+    let scaled = layout.print_scale.map(|print_scale| {
+        let px_per_inch = scene
+            .viewport
+            .length_px(&scene.table_spec, &Inches::from_f64(1.0)) as f64;
+        let table_width_px = match &options.crop {
+            Some(region) => {
+                scene
+                    .viewport
+                    .oriented_region_px(region, options.orientation)
+                    .2
+            }
+            None => scene.viewport.oriented_size_px(options.orientation).0,
+        } as f64;
+        let diagram_width_px = width_px - 2.0 * options.margin_px as f64;
+        let table_inches_per_px = table_width_px / px_per_inch / diagram_width_px;
+        table_inches_per_px * print_scale * POINTS_PER_INCH
+    });
+
+    let points_per_px = scaled.map_or(fit, |scaled| scaled.min(fit));
+    (width_px * points_per_px, height_px * points_per_px)
+}
+
+struct PdfWriter {
+    buf: Vec<u8>,
+    offsets: Vec<(usize, usize)>,
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        Self {
+            buf: b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec(),
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Write object `id` with its dictionary and, for streams, its deflated data; the
+    /// dictionary's closing `>>` gains the stream's filter and length.
+    fn object(&mut self, id: usize, dictionary: &str, stream: Option<&[u8]>) {
+        self.offsets.push((id, self.buf.len()));
+        match stream {
+            Some(data) => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(data).expect("writing to memory");
+                let compressed = encoder.finish().expect("writing to memory");
+                let dictionary = dictionary
+                    .strip_suffix(">>")
+                    .expect("a PDF dictionary ends with >>");
+                self.buf.extend_from_slice(
+                    format!(
+                        "{id} 0 obj\n{dictionary}/Filter /FlateDecode /Length {} >>\nstream\n",
+                        compressed.len()
+                    )
+                    .as_bytes(),
+                );
+                self.buf.extend_from_slice(&compressed);
+                self.buf.extend_from_slice(b"\nendstream\nendobj\n");
+            }
+            None => {
+                self.buf
+                    .extend_from_slice(format!("{id} 0 obj\n{dictionary}\nendobj\n").as_bytes());
+            }
+        }
+    }
+
+    /// Write `image` as object `id` and its alpha mask as object `id + 1`.
+    fn image(&mut self, id: usize, image: &RgbaImage) {
+        let (width, height) = image.dimensions();
+        let mut color = Vec::with_capacity((width * height * 3) as usize);
+        let mut alpha = Vec::with_capacity((width * height) as usize);
+        for pixel in image.pixels() {
+            color.extend_from_slice(&pixel.0[..3]);
+            alpha.push(pixel[3]);
+        }
+
+        self.object(
+            id,
+            &format!(
+                "<< /Type /XObject /Subtype /Image /Width {width} /Height {height} /ColorSpace /DeviceRGB /BitsPerComponent 8 /SMask {} 0 R >>",
+                id + 1
+            ),
+            Some(&color),
+        );
+        self.object(
+            id + 1,
+            &format!(
+                "<< /Type /XObject /Subtype /Image /Width {width} /Height {height} /ColorSpace /DeviceGray /BitsPerComponent 8 >>"
+            ),
+            Some(&alpha),
+        );
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.offsets.sort_unstable();
+        let xref = self.buf.len();
+        let size = self.offsets.len() + 1;
+        let mut table = format!("xref\n0 {size}\n0000000000 65535 f \n");
+        for (_, offset) in &self.offsets {
+            table.push_str(&format!("{offset:010} 00000 n \n"));
+        }
+        table.push_str(&format!(
+            "trailer\n<< /Size {size} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n"
+        ));
+        self.buf.extend_from_slice(table.as_bytes());
+        self.buf
+    }
+}
@@ -0,0 +1,482 @@
+//! Shot-sequence planning for lesson and commentary content.
+//!
+//! The planners here search over the geometric aids in [`crate::aiming`]: each shot is scored
+//! with [`shot_difficulty`], and the cue ball is only ever moved along the rough stun and
+//! natural-roll departure lines from [`cue_ball_departure_line`]. Speed control, throw, and
+//! scratches are not modelled, so plans are suggestions for a diagram rather than predictions.
+
+use crate::aiming::{cue_ball_departure_line, shot_difficulty, CaromCueAction};
+use crate::{
+    position_from_xy_inches, position_xy_inches, segment_point_distance_in_inches, Angle, Ball,
+    BallType, CutAngle, GameState, Pocket, Position, TableSpec,
+};
+
+/// Tuning for [`plan_nine_ball_runout`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunoutPlannerConfig {
+    /// How many partial plans survive each step of the beam search. `1` is a greedy planner.
+    pub beam_width: usize,
+    /// Shots with a [`shot_difficulty`] above this are never planned.
+    pub max_difficulty: f64,
+    /// How many evenly spaced stopping points are tried along each cue-ball departure line.
+    pub leave_samples: usize,
+}
+
+impl Default for RunoutPlannerConfig {
+    fn default() -> Self {
+        Self {
+            beam_width: 8,
+            max_difficulty: 12.0,
+            leave_samples: 6,
+        }
+    }
+}
+
+/// One shot in a planned runout.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunoutStep {
+    pub object_ball: BallType,
+    pub pocket: Pocket,
+    /// Where the cue ball is when this shot is played.
+    pub cue_ball: Position,
+    pub ghost_ball: Position,
+    pub difficulty: f64,
+    /// How the cue ball is sent to the next shot, or `None` for the last ball.
+    pub cue_action: Option<CaromCueAction>,
+    /// The rough cue-ball path: the cue ball, the ghost ball, then, unless this is the last ball,
+    /// the leave for the next shot.
+    pub cue_ball_path: Vec<Position>,
+}
+
+impl RunoutStep {
+    /// The chance-like makeability of this shot, `1 / (1 + difficulty)`.
+    pub fn makeability(&self) -> f64 {
+        1.0 / (1.0 + self.difficulty)
+    }
+}
+
+/// A proposed runout, possibly stopping short of the last ball.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunoutPlan {
+    pub steps: Vec<RunoutStep>,
+    /// Object balls the plan does not reach, lowest first.
+    pub remaining: Vec<BallType>,
+}
+
+impl RunoutPlan {
+    /// Whether the plan pots every object ball.
+    pub fn is_complete(&self) -> bool {
+        self.remaining.is_empty()
+    }
+
+    /// The product of each step's [`RunoutStep::makeability`].
+    pub fn makeability(&self) -> f64 {
+        self.steps.iter().map(RunoutStep::makeability).product()
+    }
+}
+
+#[derive(Clone)]
+struct PartialRunout {
+    cue_ball: Position,
+    remaining: Vec<Ball>,
+    steps: Vec<RunoutStep>,
+    makeability: f64,
+}
+
+/// Plan a 9-ball runout from the layout in `game_state`, taking the balls in rotation order.
+///
+/// For the lowest ball on the table, every pocket from [`GameState::open_pockets`] within
+/// `config.max_difficulty` is tried, and the cue ball is left at each sampled point on the stun
+/// and natural-roll departure lines that it can reach without touching another ball. A beam search
+/// keeps the `config.beam_width` best partial runouts by makeability after every shot.
+///
+/// The plan that pots the most balls is returned, ties broken by makeability; it is empty when
+/// the first shot has no planned pocket. Returns `None` when the layout has no cue ball.
+pub fn plan_nine_ball_runout(
+    game_state: &GameState,
+    config: &RunoutPlannerConfig,
+) -> Option<RunoutPlan> {
+    let cue_ball = game_state.select_ball(BallType::Cue)?;
+    let mut remaining: Vec<Ball> = game_state
+        .balls()
+        .iter()
+        .filter(|ball| ball.ty != BallType::Cue)
+        .cloned()
+        .collect();
+    remaining.sort_by_key(|ball| ball.ty.number());
+
+    let mut beam = vec![PartialRunout {
+        cue_ball: cue_ball.position.clone(),
+        remaining,
+        steps: Vec::new(),
+        makeability: 1.0,
+    }];
+    let mut best: Option<PartialRunout> = None;
+
+    while !beam.is_empty() {
+        let mut next_beam = Vec::new();
+        for partial in beam {
+            let expansions = expand_runout(&partial, cue_ball, config, &game_state.table_spec);
+            if expansions.is_empty() {
+                if best
+                    .as_ref()
+                    .is_none_or(|best| is_better_runout(&partial, best))
+                {
+                    best = Some(partial);
+                }
+            } else {
+                next_beam.extend(expansions);
+            }
+        }
+
+        next_beam.sort_by(|a, b| {
+            b.steps
+                .len()
+                .cmp(&a.steps.len())
+                .then(b.makeability.total_cmp(&a.makeability))
+        });
+        next_beam.truncate(config.beam_width.max(1));
+        beam = next_beam;
+    }
+
+    best.map(|partial| RunoutPlan {
+        steps: partial.steps,
+        remaining: partial.remaining.into_iter().map(|ball| ball.ty).collect(),
+    })
+}
+
+fn is_better_runout(candidate: &PartialRunout, incumbent: &PartialRunout) -> bool {
+    candidate.steps.len() > incumbent.steps.len()
+        || (candidate.steps.len() == incumbent.steps.len()
+            && candidate.makeability > incumbent.makeability)
+}
+
+fn expand_runout(
+    partial: &PartialRunout,
+    cue_ball: &Ball,
+    config: &RunoutPlannerConfig,
+    table_spec: &TableSpec,
+) -> Vec<PartialRunout> {
+    let Some((object_ball, rest)) = partial.remaining.split_first() else {
+        return Vec::new();
+    };
+    let cue_ball = Ball {
+        position: partial.cue_ball.clone(),
+        ..cue_ball.clone()
+    };
+    let layout = GameState::with_balls(
+        table_spec.clone(),
+        std::iter::once(cue_ball.clone()).chain(partial.remaining.iter().cloned()),
+    );
+
+    let mut expansions = Vec::new();
+    for pocket in layout.open_pockets(object_ball) {
+        let Some(difficulty) = shot_difficulty(&cue_ball.position, object_ball, pocket, table_spec)
+        else {
+            continue;
+        };
+        if difficulty > config.max_difficulty {
+            continue;
+        }
+        let ghost_ball = object_ball.ghost_ball_to_pocket(pocket, table_spec);
+        let makeability = partial.makeability / (1.0 + difficulty);
+        let step = |cue_action, leave: Option<&Position>| RunoutStep {
+            object_ball: object_ball.ty.clone(),
+            pocket,
+            cue_ball: cue_ball.position.clone(),
+            ghost_ball: ghost_ball.clone(),
+            difficulty,
+            cue_action,
+            cue_ball_path: [&cue_ball.position, &ghost_ball]
+                .into_iter()
+                .chain(leave)
+                .cloned()
+                .collect(),
+        };
+
+        if rest.is_empty() {
+            let mut steps = partial.steps.clone();
+            steps.push(step(None, None));
+            expansions.push(PartialRunout {
+                cue_ball: ghost_ball.clone(),
+                remaining: Vec::new(),
+                steps,
+                makeability,
+            });
+            continue;
+        }
+
+        for action in [CaromCueAction::Stun, CaromCueAction::NaturalRoll] {
+            for leave in cue_ball_leaves(
+                &cue_ball,
+                &ghost_ball,
+                object_ball,
+                action,
+                rest,
+                config.leave_samples,
+                table_spec,
+            ) {
+                let mut steps = partial.steps.clone();
+                steps.push(step(Some(action), Some(&leave)));
+                expansions.push(PartialRunout {
+                    cue_ball: leave,
+                    remaining: rest.to_vec(),
+                    steps,
+                    makeability,
+                });
+            }
+        }
+    }
+
+    expansions
+}
+
+fn cue_ball_leaves(
+    cue_ball: &Ball,
+    ghost_ball: &Position,
+    object_ball: &Ball,
+    action: CaromCueAction,
+    others: &[Ball],
+    samples: usize,
+    table_spec: &TableSpec,
+) -> Vec<Position> {
+    let line = cue_ball_departure_line(
+        &cue_ball.position,
+        ghost_ball,
+        object_ball,
+        action,
+        table_spec,
+    );
+    let start = position_xy_inches(&line.start, table_spec);
+    let end = position_xy_inches(&line.end, table_spec);
+    if (end.0 - start.0).hypot(end.1 - start.1) <= f64::EPSILON {
+        return vec![line.start];
+    }
+
+    let samples = samples.max(1);
+    (1..=samples)
+        .map(|sample| {
+            let fraction = sample as f64 / samples as f64;
+            (
+                start.0 + fraction * (end.0 - start.0),
+                start.1 + fraction * (end.1 - start.1),
+            )
+        })
+        .filter(|&leave| {
+            others.iter().all(|ball| {
+                let center = position_xy_inches(&ball.position, table_spec);
+                let clearance = cue_ball.spec.radius.as_f64() + ball.spec.radius.as_f64();
+                segment_point_distance_in_inches(start, leave, center) >= clearance
+            })
+        })
+        .map(|(x, y)| position_from_xy_inches(x, y, table_spec))
+        .collect()
+}
+
+/// Tuning for [`find_safeties`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SafetyFinderConfig {
+    /// How many contact points across the object ball are tried, evenly spaced between the
+    /// thinnest cuts to either side.
+    pub contact_samples: usize,
+    /// How many evenly spaced stopping points are tried along each cue-ball departure line.
+    pub leave_samples: usize,
+    /// How many lines of attack on the object ball are tested when scoring a leave.
+    pub visibility_samples: usize,
+    /// The most candidates returned.
+    pub max_results: usize,
+}
+
+impl Default for SafetyFinderConfig {
+    fn default() -> Self {
+        Self {
+            contact_samples: 36,
+            leave_samples: 8,
+            visibility_samples: 17,
+            max_results: 10,
+        }
+    }
+}
+
+/// A candidate safety: a hit on the object ball that leaves the cue ball hidden from it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SafetyShot {
+    pub object_ball: BallType,
+    pub ghost_ball: Position,
+    pub cut_angle: CutAngle,
+    pub cue_action: CaromCueAction,
+    /// Where the cue ball is left.
+    pub leave: Position,
+    /// The rough cue-ball path: the cue ball, the ghost ball, then the leave.
+    pub cue_ball_path: Vec<Position>,
+    /// The share of lines of attack on the object ball that are blocked from the leave, from
+    /// [`hidden_fraction`]. `1.0` is a full snooker.
+    pub hidden_fraction: f64,
+}
+
+impl SafetyShot {
+    /// Whether the opponent has no direct line to any part of the object ball.
+    pub fn is_full_snooker(&self) -> bool {
+        self.hidden_fraction >= 1.0
+    }
+}
+
+/// The thinnest cut, in degrees, tried or scored by the safety finder. Hits any thinner than this
+/// are too unreliable to call a line of attack.
+const SAFETY_MAX_CUT_DEGREES: f64 = 85.0;
+
+/// Measure how well `target` is hidden from a cue ball at `cue_ball`.
+///
+/// `samples` ghost-ball positions are spread around `target`, from the thinnest cut on one side to
+/// the thinnest cut on the other, and each straight cue-ball path to them is checked against
+/// `blockers`. The result is the share of those paths that are obstructed, so `0.0` is an open
+/// shot and `1.0` leaves no direct hit at all. Blockers of the same type as `target` and any cue
+/// ball are ignored.
+pub fn hidden_fraction(
+    cue_ball: &Position,
+    target: &Ball,
+    blockers: &[Ball],
+    samples: usize,
+    table_spec: &TableSpec,
+) -> f64 {
+    let cue = position_xy_inches(cue_ball, table_spec);
+    let object = position_xy_inches(&target.position, table_spec);
+    let radius = target.spec.radius.as_f64();
+    let straight = (object.0 - cue.0).atan2(object.1 - cue.1);
+    let samples = samples.max(1);
+
+    let blocked = (0..samples)
+        .filter(|&sample| {
+            let cut = if samples == 1 {
+                0.0
+            } else {
+                -SAFETY_MAX_CUT_DEGREES
+                    + 2.0 * SAFETY_MAX_CUT_DEGREES * sample as f64 / (samples - 1) as f64
+            };
+            let heading = straight + cut.to_radians();
+            let ghost = (
+                object.0 - 2.0 * radius * heading.sin(),
+                object.1 - 2.0 * radius * heading.cos(),
+            );
+            blockers
+                .iter()
+                .filter(|ball| ball.ty != target.ty && ball.ty != BallType::Cue)
+                .any(|ball| {
+                    let center = position_xy_inches(&ball.position, table_spec);
+                    segment_point_distance_in_inches(cue, ghost, center)
+                        < radius + ball.spec.radius.as_f64()
+                })
+        })
+        .count();
+
+    blocked as f64 / samples as f64
+}
+
+/// Search for safeties on `object_ball`, the ball the shooter must hit first.
+///
+/// Contact points across the object ball are tried for both stun and natural roll, and the cue
+/// ball is left at each sampled point on the departure line it can reach without touching
+/// another ball. The object ball is taken to finish roughly where it started, as it does for the
+/// thin, soft hits a hiding safety usually calls for. Candidates are ranked by
+/// [`hidden_fraction`] for the opponent shooting the same ball, then by how far the leave is from
+/// the object ball.
+///
+/// Returns an empty list when the layout has no cue ball.
+pub fn find_safeties(
+    game_state: &GameState,
+    object_ball: &Ball,
+    config: &SafetyFinderConfig,
+) -> Vec<SafetyShot> {
+    let Some(cue_ball) = game_state.select_ball(BallType::Cue) else {
+        return Vec::new();
+    };
+    let table_spec = &game_state.table_spec;
+    let others: Vec<Ball> = game_state
+        .balls()
+        .iter()
+        .filter(|ball| ball.ty != BallType::Cue && ball.ty != object_ball.ty)
+        .cloned()
+        .collect();
+    let cue = position_xy_inches(&cue_ball.position, table_spec);
+    let object = position_xy_inches(&object_ball.position, table_spec);
+    let diameter = 2.0 * object_ball.spec.radius.as_f64();
+    if (object.0 - cue.0).hypot(object.1 - cue.1) <= diameter {
+        return Vec::new();
+    }
+    let straight = (object.0 - cue.0).atan2(object.1 - cue.1);
+    let samples = config.contact_samples.max(2);
+
+    let mut safeties = Vec::new();
+    for sample in 0..samples {
+        let cut = -SAFETY_MAX_CUT_DEGREES
+            + 2.0 * SAFETY_MAX_CUT_DEGREES * sample as f64 / (samples - 1) as f64;
+        let heading = straight + cut.to_radians();
+        let ghost = (
+            object.0 - diameter * heading.sin(),
+            object.1 - diameter * heading.cos(),
+        );
+        let ghost_ball = position_from_xy_inches(ghost.0, ghost.1, table_spec);
+        let approach_clear = others.iter().all(|ball| {
+            let center = position_xy_inches(&ball.position, table_spec);
+            segment_point_distance_in_inches(cue, ghost, center)
+                >= cue_ball.spec.radius.as_f64() + ball.spec.radius.as_f64()
+        });
+        if !approach_clear {
+            continue;
+        }
+        let cut_angle = CutAngle::from_headings(
+            Angle::from_north(ghost.0 - cue.0, ghost.1 - cue.1),
+            Angle::from_north(heading.sin(), heading.cos()),
+        );
+
+        for action in [CaromCueAction::Stun, CaromCueAction::NaturalRoll] {
+            let leaves = cue_ball_leaves(
+                cue_ball,
+                &ghost_ball,
+                object_ball,
+                action,
+                &others,
+                config.leave_samples,
+                table_spec,
+            );
+            // The object ball has moved off by the time the cue ball passes, but the leave must
+            // not end up on top of where it rests.
+            for leave in leaves.into_iter().filter(|leave| {
+                let (x, y) = position_xy_inches(leave, table_spec);
+                (x - object.0).hypot(y - object.1) >= diameter
+            }) {
+                safeties.push(SafetyShot {
+                    object_ball: object_ball.ty.clone(),
+                    ghost_ball: ghost_ball.clone(),
+                    cut_angle,
+                    cue_action: action,
+                    cue_ball_path: vec![
+                        cue_ball.position.clone(),
+                        ghost_ball.clone(),
+                        leave.clone(),
+                    ],
+                    hidden_fraction: hidden_fraction(
+                        &leave,
+                        object_ball,
+                        &others,
+                        config.visibility_samples,
+                        table_spec,
+                    ),
+                    leave,
+                });
+            }
+        }
+    }
+
+    let distance_from_object = |safety: &SafetyShot| {
+        let (x, y) = position_xy_inches(&safety.leave, table_spec);
+        (x - object.0).hypot(y - object.1)
+    };
+    safeties.sort_by(|a, b| {
+        b.hidden_fraction
+            .total_cmp(&a.hidden_fraction)
+            .then(distance_from_object(b).total_cmp(&distance_from_object(a)))
+    });
+    safeties.truncate(config.max_results);
+    safeties
+}
@@ -0,0 +1,295 @@
+//! Rack templates: which ball goes where in a rack, and how the open spots are filled.
+//!
+//! A [`RackTemplate`] lays its rows out from the apex on the foot spot back toward the foot rail,
+//! each row frozen against the one in front of it. A slot holds a named ball or is left open for
+//! any ball, a solid or a stripe, and the open slots are filled from the rest of the set, shuffled
+//! by a seed or lowest number first. With the `serde` feature, house racks are read from TOML:
+//!
+//! ```toml
+//! schema_version = 2
+//! name = "Nine-ball, two on the apex"
+//! balls = ["One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine"]
+//! shuffle = true
+//! rows = [
+//!   ["Two"],
+//!   ["any", "any"],
+//!   ["any", "Nine", "any"],
+//!   ["any", "any"],
+//!   ["One"],
+//! ]
+//! ```
+//!
+//! `balls` may be left out to rack the one through as many balls as there are slots, and
+//! `shuffle` defaults to true.
+
+use crate::simulation::SplitMix64;
+use crate::{
+    Ball, BallSpec, BallType, GameType, Inches, TableSpec, RACK_SPOT, TYPICAL_BALL_RADIUS,
+};
+
+/// What may go in one spot of a rack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RackSlot {
+    /// Any ball left over.
+    Any,
+    /// One of the one through seven.
+    Solid,
+    /// One of the nine through fifteen.
+    Stripe,
+    /// This ball and no other, written by its name, e.g. `"Nine"`.
+    #[cfg_attr(feature = "serde", serde(untagged))]
+    Ball(BallType),
+}
+
+impl RackSlot {
+    fn admits(&self, ty: &BallType) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Solid => matches!(ty.number(), Some(1..=7)),
+            Self::Stripe => ty.is_striped(),
+            Self::Ball(ball) => ball == ty,
+        }
+    }
+}
+
+/// A rack's shape and the rules for filling it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RackTemplate {
+    pub name: String,
+    /// The rows from the apex back, each listed left to right as the diagram draws them.
+    pub rows: Vec<Vec<RackSlot>>,
+    /// The balls racked, one per slot; empty for the one through as many balls as there are
+    /// slots.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub balls: Vec<BallType>,
+    /// Fill the open slots in an order chosen by the seed, rather than lowest number first in
+    /// reading order.
+    #[cfg_attr(feature = "serde", serde(default = "shuffle_by_default"))]
+    pub shuffle: bool,
+}
+
+#[cfg(feature = "serde")]
+fn shuffle_by_default() -> bool {
+    true
+}
+
+impl RackTemplate {
+    pub fn new(name: &str, rows: Vec<Vec<RackSlot>>) -> Self {
+        Self {
+            name: name.to_string(),
+            rows,
+            balls: Vec::new(),
+            shuffle: true,
+        }
+    }
+
+    pub fn with_balls(mut self, balls: Vec<BallType>) -> Self {
+        self.balls = balls;
+        self
+    }
+
+    pub fn with_shuffle(mut self, shuffle: bool) -> Self {
+        self.shuffle = shuffle;
+        self
+    }
+
+    /// The WPA nine-ball diamond: the one on the foot spot and the nine in the middle. In order,
+    /// it racks as [`rack_9_ball`](crate::rack_9_ball) does.
+    pub fn nine_ball() -> Self {
+        use RackSlot::{Any, Ball};
+        Self::new(
+            "Nine-ball",
+            vec![
+                vec![Ball(BallType::One)],
+                vec![Any, Any],
+                vec![Any, Ball(BallType::Nine), Any],
+                vec![Any, Any],
+                vec![Any],
+            ],
+        )
+    }
+
+    /// The WPA ten-ball triangle: the one on the foot spot and the ten in the middle of the
+    /// third row.
+    pub fn ten_ball() -> Self {
+        use RackSlot::{Any, Ball};
+        Self::new(
+            "Ten-ball",
+            vec![
+                vec![Ball(BallType::One)],
+                vec![Any, Any],
+                vec![Any, Ball(BallType::Ten), Any],
+                vec![Any, Any, Any, Any],
+            ],
+        )
+    }
+
+    /// The WPA eight-ball triangle: the eight in the middle and a stripe and a solid in the back
+    /// corners.
+    pub fn eight_ball() -> Self {
+        use RackSlot::{Any, Ball, Solid, Stripe};
+        Self::new(
+            "Eight-ball",
+            vec![
+                vec![Any],
+                vec![Any, Any],
+                vec![Any, Ball(BallType::Eight), Any],
+                vec![Any, Any, Any, Any],
+                vec![Stripe, Any, Any, Any, Solid],
+            ],
+        )
+    }
+
+    /// The standard rack for `game`, if it is racked.
+    pub fn for_game(game: &GameType) -> Option<Self> {
+        match game {
+            GameType::NineBall => Some(Self::nine_ball()),
+            GameType::TenBall => Some(Self::ten_ball()),
+            GameType::EightBall => Some(Self::eight_ball()),
+            GameType::OnePocket | GameType::Banks => None,
+        }
+    }
+
+    /// The balls racked, in number order.
+    pub fn ball_set(&self) -> Vec<BallType> {
+        let mut balls = if self.balls.is_empty() {
+            (1..=self.slot_count())
+                .map_while(|number| u8::try_from(number).ok().and_then(BallType::from_number))
+                .collect()
+        } else {
+            self.balls.clone()
+        };
+        balls.sort_by_key(BallType::number);
+        balls
+    }
+
+    fn slot_count(&self) -> usize {
+        self.rows.iter().map(Vec::len).sum()
+    }
+
+    /// Check that the slots can be filled from the ball set: one object ball per slot, each
+    /// named ball in the set once, and enough solids and stripes for their slots.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut pool = self.ball_set();
+        if pool.len() != self.slot_count() {
+            return Err(format!(
+                "rack '{}' has {} slots for {} balls",
+                self.name,
+                self.slot_count(),
+                pool.len()
+            ));
+        }
+        if pool.contains(&BallType::Cue) {
+            return Err(format!("rack '{}' racks the cue ball", self.name));
+        }
+        if (1..pool.len()).any(|index| pool[index - 1] == pool[index]) {
+            return Err(format!("rack '{}' lists a ball twice", self.name));
+        }
+        for slot in self.slots() {
+            if let RackSlot::Ball(ty) = slot {
+                let index = pool.iter().position(|ball| ball == ty).ok_or(format!(
+                    "rack '{}' places {ty:?}, which is not in its balls or is placed twice",
+                    self.name
+                ))?;
+                pool.remove(index);
+            }
+        }
+        for (group, name) in [(RackSlot::Solid, "solid"), (RackSlot::Stripe, "stripe")] {
+            let slots = self.slots().filter(|slot| **slot == group).count();
+            let balls = pool.iter().filter(|ball| group.admits(ball)).count();
+            if slots > balls {
+                return Err(format!(
+                    "rack '{}' has {slots} {name} slots but {balls} {name}s left for them",
+                    self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn slots(&self) -> impl Iterator<Item = &RackSlot> {
+        self.rows.iter().flatten()
+    }
+
+    /// The balls frozen in place on the default table, apex first and then row by row, left to
+    /// right. A shuffled template racks the same way for the same seed.
+    pub fn rack(&self, seed: u64) -> Result<Vec<Ball>, String> {
+        self.validate()?;
+        let mut pool: Vec<BallType> = self.ball_set();
+        pool.retain(|ball| {
+            !self
+                .slots()
+                .any(|slot| *slot == RackSlot::Ball(ball.clone()))
+        });
+        let mut rng = SplitMix64::new(seed);
+
+        // Named balls take their slots, then solids and stripes theirs, and the rest fill in.
+        let mut filled: Vec<Option<BallType>> = self
+            .slots()
+            .map(|slot| match slot {
+                RackSlot::Ball(ty) => Some(ty.clone()),
+                RackSlot::Any | RackSlot::Solid | RackSlot::Stripe => None,
+            })
+            .collect();
+        for group in [RackSlot::Solid, RackSlot::Stripe, RackSlot::Any] {
+            for (slot, ball) in self.slots().zip(&mut filled) {
+                if *slot != group {
+                    continue;
+                }
+                let eligible: Vec<usize> = (0..pool.len())
+                    .filter(|&index| group.admits(&pool[index]))
+                    .collect();
+                let pick = if self.shuffle {
+                    eligible[(rng.next_u64() % eligible.len() as u64) as usize]
+                } else {
+                    eligible[0]
+                };
+                *ball = Some(pool.remove(pick));
+            }
+        }
+
+        let table_spec = &TableSpec::default();
+        let radius = TYPICAL_BALL_RADIUS.as_f64();
+        let row_spacing = 3.0_f64.sqrt() * radius;
+        let positions = self.rows.iter().enumerate().flat_map(|(row, slots)| {
+            (0..slots.len()).map(move |slot| {
+                let across = (2.0 * slot as f64 - (slots.len() - 1) as f64) * radius;
+                let mut position = RACK_SPOT.clone();
+                position
+                    .shift_horizontally_inches(Inches::from_f64(across))
+                    .shift_vertically_inches(Inches::from_f64(-(row as f64) * row_spacing));
+                position.resolve_shifts(table_spec);
+                position
+            })
+        });
+        Ok(filled
+            .into_iter()
+            .flatten()
+            .zip(positions)
+            .map(|(ty, position)| Ball {
+                ty,
+                position,
+                spec: BallSpec::default(),
+            })
+            .collect())
+    }
+
+    /// Read a template from TOML in the shape shown in the [module docs](self), checking that it
+    /// can be racked.
+    #[cfg(feature = "serde")]
+    pub fn from_toml(text: &str) -> Result<Self, String> {
+        let template: Self = crate::schema::read_toml(crate::schema::Document::RackTemplate, text)?;
+        template.validate()?;
+        Ok(template)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Self::from_toml(&text).map_err(|message| format!("{}: {message}", path.display()))
+    }
+}
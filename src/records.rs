@@ -0,0 +1,503 @@
+//! Matches recorded shot by shot, in a text format modeled on the PGN chess games are kept in, for
+//! league scoresheets and statistics.
+//!
+//! A record opens with `[Name "value"]` tags, then lists each rack's innings, one to a line, and
+//! closes each rack with its result:
+//!
+//! ```text
+//! [Event "Tuesday league"]
+//! [Date "2026.10.16"]
+//! [Game "9b"]
+//! [PlayerA "Ann Lee"]
+//! [PlayerB "Bo Park"]
+//!
+//! 1. A brk:1,4 2 - x
+//! 2. B 3 - s
+//! 3. A - f:scratch
+//! 4. B bih:5,6 7 8 9 w
+//! 0-1
+//!
+//! 1. B brk:- f:no-rail ; a dry break
+//! 2. A bih:1 2 - p
+//! *
+//! ```
+//!
+//! The `Game` tag holds a game code from [`GameState::to_notation`](crate::GameState::to_notation)
+//! and defaults to nine-ball; `PlayerA` and `PlayerB` are required, and any other tags are kept
+//! as they are. Each inning is numbered within its rack and names its shooter, `A` or `B`. Then
+//! come its shots, each the balls it pocketed split by `,`, or `-` for none, led by `brk:`,
+//! `bih:` or `kit:` for a break, ball in hand or a cue ball placed in the kitchen. The last token
+//! says how the inning ended: `x` for a miss, `s` a safety, `p` a push out, `w` the rack won, `l`
+//! the rack lost, or `f:` and the foul: `f:scratch`, `f:no-contact`, `f:no-rail`, or `f:wrong-3`
+//! for hitting the 3 first. Balls are their numbers, or `c` for the cue ball. A rack ends with
+//! `1-0` when A won it, `0-1` when B did, or `*` while it is unfinished, and a `;` starts a comment
+//! that runs to the end of its line. In tag values, `\"` is a quote, `\\` a backslash, and `\n`
+//! and `\r` are line breaks.
+
+use crate::rules::Foul;
+use crate::simulation::SimulationResult;
+use crate::{BallType, CueballModifier, GameType};
+
+/// One of the two players in a match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Player {
+    A,
+    B,
+}
+
+impl Player {
+    pub fn opponent(self) -> Self {
+        match self {
+            Self::A => Self::B,
+            Self::B => Self::A,
+        }
+    }
+}
+
+/// A match between two players, rack by rack.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchRecord {
+    pub game: GameType,
+    /// The players' names, A then B.
+    pub players: [String; 2],
+    /// Every other tag, such as `Event`, `Site` or `Date`, in the order it is written.
+    pub tags: Vec<(String, String)>,
+    pub racks: Vec<RackRecord>,
+}
+
+/// One rack, from the break to the inning that won or lost it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RackRecord {
+    pub innings: Vec<Inning>,
+}
+
+/// One player's turn at the table: the shots they played and how the turn ended.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Inning {
+    pub player: Player,
+    /// At least one shot; the last is the one that ended the inning.
+    pub shots: Vec<ShotRecord>,
+    pub outcome: InningOutcome,
+}
+
+/// One stroke of the cue.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShotRecord {
+    /// How the cue ball was played: as it lay, from the break, or from the player's hand.
+    pub cueball_modifier: CueballModifier,
+    /// The object balls pocketed, in the order they dropped if it is known.
+    pub pocketed: Vec<BallType>,
+}
+
+/// How an inning came to an end.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InningOutcome {
+    /// The last shot pocketed nothing.
+    Miss,
+    /// The shooter played safe and gave up the table on purpose.
+    Safety,
+    /// The shooter pushed out, leaving the opponent the choice to shoot or pass back.
+    PushOut,
+    /// The last shot fouled, giving the opponent ball in hand.
+    Foul(Foul),
+    /// The shooter won the rack.
+    Won,
+    /// The shooter lost the rack, as by pocketing the 8 early or a third foul in a row.
+    Lost,
+}
+
+impl InningOutcome {
+    fn ends_rack(&self) -> bool {
+        matches!(self, Self::Won | Self::Lost)
+    }
+}
+
+impl ShotRecord {
+    /// A shot played as the cue ball lay that pocketed `pocketed`.
+    pub fn new(pocketed: Vec<BallType>) -> Self {
+        Self {
+            cueball_modifier: CueballModifier::AsItLays,
+            pocketed,
+        }
+    }
+
+    pub fn with_cueball_modifier(mut self, cueball_modifier: CueballModifier) -> Self {
+        self.cueball_modifier = cueball_modifier;
+        self
+    }
+
+    /// The record of a simulated shot: the object balls it pocketed, in the order they dropped,
+    /// played as its starting layout's cue ball modifier allowed.
+    pub fn played(result: &SimulationResult) -> Self {
+        Self {
+            cueball_modifier: result.initial_state.cueball_modifier.clone(),
+            pocketed: result
+                .pocketed
+                .iter()
+                .map(|(ty, _)| ty.clone())
+                .filter(|ty| *ty != BallType::Cue)
+                .collect(),
+        }
+    }
+}
+
+impl RackRecord {
+    /// The player who broke, if the rack has begun.
+    pub fn breaker(&self) -> Option<Player> {
+        self.innings.first().map(|inning| inning.player)
+    }
+
+    /// The player who won the rack, or `None` while it is unfinished.
+    pub fn winner(&self) -> Option<Player> {
+        let last = self.innings.last()?;
+        match last.outcome {
+            InningOutcome::Won => Some(last.player),
+            InningOutcome::Lost => Some(last.player.opponent()),
+            _ => None,
+        }
+    }
+}
+
+impl MatchRecord {
+    /// A match of `game` between `player_a` and `player_b`, with no racks played yet.
+    pub fn new(game: GameType, player_a: &str, player_b: &str) -> Self {
+        Self {
+            game,
+            players: [player_a.to_string(), player_b.to_string()],
+            tags: Vec::new(),
+            racks: Vec::new(),
+        }
+    }
+
+    /// Add the tag called `name`, which is one word, as in `Event` or `Site`.
+    pub fn with_tag(mut self, name: &str, value: &str) -> Self {
+        self.tags.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// The value of the tag called `name`, besides `Game`, `PlayerA` and `PlayerB`.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn player_name(&self, player: Player) -> &str {
+        match player {
+            Player::A => &self.players[0],
+            Player::B => &self.players[1],
+        }
+    }
+
+    /// How many racks `player` has won.
+    pub fn racks_won(&self, player: Player) -> usize {
+        self.racks
+            .iter()
+            .filter(|rack| rack.winner() == Some(player))
+            .count()
+    }
+
+    /// This match in the format shown in the [module docs](self): the other tags first, then
+    /// `Game`, `PlayerA` and `PlayerB`, and each rack after a blank line.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let tags = self
+            .tags
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()));
+        let roster = [
+            ("Game", self.game.notation()),
+            ("PlayerA", self.players[0].as_str()),
+            ("PlayerB", self.players[1].as_str()),
+        ];
+        for (name, value) in tags.chain(roster) {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r");
+            text.push_str(&format!("[{name} \"{value}\"]\n"));
+        }
+        for rack in &self.racks {
+            text.push('\n');
+            for (index, inning) in rack.innings.iter().enumerate() {
+                let shots = inning.shots.iter().map(shot_notation);
+                let outcome = outcome_notation(&inning.outcome);
+                text.push_str(&format!(
+                    "{}. {} {}\n",
+                    index + 1,
+                    player_notation(inning.player),
+                    shots.chain([outcome]).collect::<Vec<_>>().join(" ")
+                ));
+            }
+            text.push_str(result_notation(rack.winner()));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Read a match written in the format shown in the [module docs](self). Each rack's result
+    /// must agree with how its last inning ended.
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut tags: Vec<(String, String)> = Vec::new();
+        let mut racks = Vec::new();
+        let mut innings: Vec<Inning> = Vec::new();
+        for (index, line) in text.trim_start_matches('\u{feff}').lines().enumerate() {
+            let at_line = |message: String| format!("line {}: {message}", index + 1);
+            let line = line.trim();
+            if let Some(tag) = line.strip_prefix('[') {
+                if !racks.is_empty() || !innings.is_empty() {
+                    return Err(at_line("tags must come before the first rack".to_string()));
+                }
+                let (name, value) = parse_tag(tag).map_err(at_line)?;
+                if tags.iter().any(|(other, _)| *other == name) {
+                    return Err(at_line(format!("the tag '{name}' is given twice")));
+                }
+                tags.push((name, value));
+                continue;
+            }
+
+            let line = line.split(';').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(winner) = parse_result(line) {
+                let rack = RackRecord {
+                    innings: std::mem::take(&mut innings),
+                };
+                if rack.innings.is_empty() {
+                    return Err(at_line(format!(
+                        "result '{line}' ends a rack with no innings"
+                    )));
+                }
+                if winner != rack.winner() {
+                    return Err(at_line(format!(
+                        "result '{line}' does not match the last inning, which gives '{}'",
+                        result_notation(rack.winner())
+                    )));
+                }
+                racks.push(rack);
+                continue;
+            }
+
+            let (number, inning) = parse_inning(line).map_err(at_line)?;
+            if innings.last().is_some_and(|last| last.outcome.ends_rack()) {
+                return Err(at_line(format!(
+                    "inning {number} follows the end of the rack; expected its result"
+                )));
+            }
+            if number != innings.len() + 1 {
+                return Err(at_line(format!(
+                    "expected inning {}, found {number}",
+                    innings.len() + 1
+                )));
+            }
+            innings.push(inning);
+        }
+        if !innings.is_empty() {
+            return Err("the last rack has no result; end it with `1-0`, `0-1` or `*`".to_string());
+        }
+
+        let mut take_tag = |name: &str| {
+            let index = tags.iter().position(|(tag, _)| tag == name)?;
+            Some(tags.remove(index).1)
+        };
+        let game = match take_tag("Game") {
+            Some(code) => GameType::from_notation(&code).ok_or(format!("unknown game '{code}'"))?,
+            None => GameType::NineBall,
+        };
+        let player_a = take_tag("PlayerA").ok_or("missing the `[PlayerA \"...\"]` tag")?;
+        let player_b = take_tag("PlayerB").ok_or("missing the `[PlayerB \"...\"]` tag")?;
+        Ok(Self {
+            game,
+            players: [player_a, player_b],
+            tags,
+            racks,
+        })
+    }
+
+    pub fn load(path: &std::path::Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Self::from_text(&text).map_err(|message| format!("{}: {message}", path.display()))
+    }
+}
+
+fn player_notation(player: Player) -> &'static str {
+    match player {
+        Player::A => "A",
+        Player::B => "B",
+    }
+}
+
+fn ball_notation(ball: &BallType) -> String {
+    ball.number()
+        .map_or("c".to_string(), |number| number.to_string())
+}
+
+fn parse_ball(name: &str) -> Option<BallType> {
+    match name {
+        "c" => Some(BallType::Cue),
+        _ => name.parse().ok().and_then(BallType::from_number),
+    }
+}
+
+fn shot_notation(shot: &ShotRecord) -> String {
+    let balls = if shot.pocketed.is_empty() {
+        "-".to_string()
+    } else {
+        shot.pocketed
+            .iter()
+            .map(ball_notation)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    match shot.cueball_modifier {
+        CueballModifier::AsItLays => balls,
+        ref modifier => format!("{}:{balls}", modifier.notation()),
+    }
+}
+
+fn outcome_notation(outcome: &InningOutcome) -> String {
+    match outcome {
+        InningOutcome::Miss => "x".to_string(),
+        InningOutcome::Safety => "s".to_string(),
+        InningOutcome::PushOut => "p".to_string(),
+        InningOutcome::Won => "w".to_string(),
+        InningOutcome::Lost => "l".to_string(),
+        InningOutcome::Foul(Foul::Scratch) => "f:scratch".to_string(),
+        InningOutcome::Foul(Foul::NoContact) => "f:no-contact".to_string(),
+        InningOutcome::Foul(Foul::NoRailAfterContact) => "f:no-rail".to_string(),
+        InningOutcome::Foul(Foul::WrongBallFirst(ball)) => {
+            format!("f:wrong-{}", ball_notation(ball))
+        }
+    }
+}
+
+fn result_notation(winner: Option<Player>) -> &'static str {
+    match winner {
+        Some(Player::A) => "1-0",
+        Some(Player::B) => "0-1",
+        None => "*",
+    }
+}
+
+fn parse_result(line: &str) -> Option<Option<Player>> {
+    match line {
+        "1-0" => Some(Some(Player::A)),
+        "0-1" => Some(Some(Player::B)),
+        "*" => Some(None),
+        _ => None,
+    }
+}
+
+/// The name and value of a tag, from after its opening `[`.
+fn parse_tag(tag: &str) -> Result<(String, String), String> {
+    let (name, value) = tag
+        .strip_suffix(']')
+        .and_then(|tag| tag.split_once(' '))
+        .ok_or("expected `[Name \"value\"]`")?;
+    let quoted = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or(format!("expected the value of '{name}' in double quotes"))?;
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        value.extend(match c {
+            '\\' => chars.next().map(|escaped| match escaped {
+                'n' => '\n',
+                'r' => '\r',
+                _ => escaped,
+            }),
+            _ => Some(c),
+        });
+    }
+    Ok((name.to_string(), value))
+}
+
+/// An inning and the number it was given.
+fn parse_inning(line: &str) -> Result<(usize, Inning), String> {
+    let mut tokens = line.split_whitespace();
+    let number = tokens
+        .next()
+        .and_then(|token| token.strip_suffix('.'))
+        .and_then(|number| number.parse().ok())
+        .ok_or(format!(
+            "expected an inning number such as `1.`, found '{line}'"
+        ))?;
+    let player = match tokens.next() {
+        Some("A") => Player::A,
+        Some("B") => Player::B,
+        Some(other) => return Err(format!("expected the shooter, `A` or `B`, found '{other}'")),
+        None => return Err(format!("inning {number} names no shooter")),
+    };
+    let mut tokens: Vec<&str> = tokens.collect();
+    let outcome = tokens
+        .pop()
+        .ok_or(format!("inning {number} records no shots"))?;
+    if tokens.is_empty() {
+        return Err(format!(
+            "inning {number} records no shots before '{outcome}'"
+        ));
+    }
+    Ok((
+        number,
+        Inning {
+            player,
+            shots: tokens
+                .into_iter()
+                .map(parse_shot)
+                .collect::<Result<_, _>>()?,
+            outcome: parse_outcome(outcome)?,
+        },
+    ))
+}
+
+fn parse_shot(token: &str) -> Result<ShotRecord, String> {
+    let (cueball_modifier, balls) = match token.split_once(':') {
+        Some((code, balls)) => (
+            CueballModifier::from_notation(code)
+                .filter(|modifier| *modifier != CueballModifier::AsItLays)
+                .ok_or(format!("unknown cue ball modifier '{code}'"))?,
+            balls,
+        ),
+        None => (CueballModifier::AsItLays, token),
+    };
+    let pocketed = if balls == "-" {
+        Vec::new()
+    } else {
+        balls
+            .split(',')
+            .map(|name| parse_ball(name).ok_or(format!("unknown ball '{name}' in shot '{token}'")))
+            .collect::<Result<_, _>>()?
+    };
+    Ok(ShotRecord {
+        cueball_modifier,
+        pocketed,
+    })
+}
+
+fn parse_outcome(token: &str) -> Result<InningOutcome, String> {
+    let foul = match token {
+        "x" => return Ok(InningOutcome::Miss),
+        "s" => return Ok(InningOutcome::Safety),
+        "p" => return Ok(InningOutcome::PushOut),
+        "w" => return Ok(InningOutcome::Won),
+        "l" => return Ok(InningOutcome::Lost),
+        "f:scratch" => Foul::Scratch,
+        "f:no-contact" => Foul::NoContact,
+        "f:no-rail" => Foul::NoRailAfterContact,
+        _ => token
+            .strip_prefix("f:wrong-")
+            .and_then(parse_ball)
+            .map(Foul::WrongBallFirst)
+            .ok_or(format!(
+                "expected the inning's end, one of `x`, `s`, `p`, `w`, `l` or `f:` and a foul, \
+                 found '{token}'"
+            ))?,
+    };
+    Ok(InningOutcome::Foul(foul))
+}
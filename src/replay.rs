@@ -0,0 +1,553 @@
+//! Simulated shots stored as compact binary replays.
+//!
+//! A [`Replay`] keeps what a shot needs to be drawn again later: the layout it was played from,
+//! the [`Shot`], and the [`simulation::Event`] log. [`Replay::diagram`] draws each ball's route
+//! through its events on the starting layout, and [`Replay::simulate`] runs the shot through the
+//! engine again for animation, so neither is tied to the resolution the shot was first drawn at.
+//!
+//! The bytes start with the magic `BLRP` and a little-endian `u16` format version. Decimals are
+//! written exactly, as a zigzag varint scale and a varint-prefixed little-endian two's-complement
+//! integer; times, the heading and the cue elevation are little-endian `f64`s.
+
+use bigdecimal::num_bigint::BigInt;
+use bigdecimal::BigDecimal;
+use image::Rgba;
+
+use crate::simulation::{self, Event, EventKind, SimulationConfig, SimulationError};
+use crate::{
+    angle_from_degrees, Ball, BallSpec, BallType, CueTipContact, CueballModifier, Diamond,
+    GameState, GameType, Inches, InchesPerSecond, Pocket, PocketJawGeometry, PocketShapeSpec,
+    PocketSpec, PocketType, Position, Rail, RailCollisionConfig, RailCollisionProfile, Scale,
+    Seconds, Shot, TableSpec,
+};
+
+const MAGIC: &[u8; 4] = b"BLRP";
+
+/// The format version [`Replay::to_bytes`] writes. Readers refuse newer versions rather than
+/// misread them.
+pub const REPLAY_FORMAT_VERSION: u16 = 1;
+
+const ROUTE_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+/// A played shot: where the balls started, how the cue ball was struck, and what happened.
+///
+/// The table, balls, game and cue-ball modifier of the layout are kept; its annotations are not.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub initial_state: GameState,
+    pub shot: Shot,
+    pub event_log: Vec<Event>,
+}
+
+impl Replay {
+    /// The replay of `shot` played as `result`.
+    pub fn new(result: &simulation::SimulationResult, shot: &Shot) -> Self {
+        Self {
+            initial_state: result.initial_state.clone(),
+            shot: shot.clone(),
+            event_log: result.event_log.clone(),
+        }
+    }
+
+    /// Run the shot through the engine again with `config`.
+    ///
+    /// The engine is deterministic, so with the config the replay was recorded with this repeats
+    /// the stored event log; use [`simulation::TimeSolver::Decimal`] to keep it from drifting
+    /// across platforms.
+    pub fn simulate(
+        &self,
+        config: &SimulationConfig,
+    ) -> Result<simulation::SimulationResult, SimulationError> {
+        simulation::simulate_with_config(&self.initial_state, &self.shot, config)
+    }
+
+    /// The route of the first ball of type `ball`: where it started, then where it was at each of
+    /// its events. Empty if there is no such ball.
+    pub fn route(&self, ball: &BallType) -> Vec<Position> {
+        let Some(start) = self.initial_state.select_ball(ball.clone()) else {
+            return Vec::new();
+        };
+        let mut route = vec![start.position.clone()];
+        for event in &self.event_log {
+            let position = match &event.kind {
+                EventKind::BallBallCollision {
+                    first,
+                    first_position,
+                    ..
+                } if first == ball => first_position,
+                EventKind::BallBallCollision {
+                    second,
+                    second_position,
+                    ..
+                } if second == ball => second_position,
+                EventKind::CushionContact {
+                    ball: ty, position, ..
+                }
+                | EventKind::Pocketed {
+                    ball: ty, position, ..
+                }
+                | EventKind::BallStopped { ball: ty, position } => {
+                    if ty != ball {
+                        continue;
+                    }
+                    position
+                }
+                EventKind::BallBallCollision { .. } => continue,
+            };
+            route.push(position.clone());
+        }
+        route
+    }
+
+    /// The starting layout with every ball that moved joined to its events by straight lines.
+    ///
+    /// Between events a sliding ball can curve, which the event log does not record; use
+    /// [`Replay::simulate`] for the exact paths.
+    pub fn diagram(&self) -> GameState {
+        let mut diagram = self.initial_state.clone();
+        for ball in self.initial_state.balls() {
+            let route = self.route(&ball.ty);
+            if route.len() > 1 {
+                diagram.add_dotted_polyline(&route, ROUTE_COLOR);
+            }
+        }
+        diagram
+    }
+
+    /// The replay in the binary format described in the [module docs](self).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer(MAGIC.to_vec());
+        writer.0.extend(REPLAY_FORMAT_VERSION.to_le_bytes());
+
+        let table_spec = &self.initial_state.table_spec;
+        writer.table_spec(table_spec);
+        writer.byte(match self.initial_state.ty {
+            GameType::NineBall => 0,
+            GameType::EightBall => 1,
+            GameType::TenBall => 2,
+            GameType::OnePocket => 3,
+            GameType::Banks => 4,
+        });
+        writer.byte(match self.initial_state.cueball_modifier {
+            CueballModifier::AsItLays => 0,
+            CueballModifier::BreakPlacement => 1,
+            CueballModifier::BallInHand => 2,
+            CueballModifier::KitchenPlacement => 3,
+        });
+
+        let balls = self.initial_state.balls();
+        writer.length(balls.len());
+        for ball in balls {
+            let mut position = ball.position.clone();
+            position.resolve_shifts(table_spec);
+            writer.ball(&ball.ty);
+            writer.position(&position);
+            writer.decimal(&ball.spec.radius.magnitude);
+        }
+
+        writer.float(self.shot.heading().as_degrees());
+        writer.decimal(&self.shot.cue_speed().as_inches().magnitude);
+        writer.decimal(&self.shot.tip_contact().side_offset().magnitude);
+        writer.decimal(&self.shot.tip_contact().height_offset().magnitude);
+        writer.float(self.shot.cue_elevation_degrees());
+
+        writer.length(self.event_log.len());
+        for event in &self.event_log {
+            writer.event(event);
+        }
+        writer.0
+    }
+
+    /// Read a replay written by [`Replay::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader { bytes, offset: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err("not a replay: expected the magic `BLRP`".to_string());
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version == 0 || version > REPLAY_FORMAT_VERSION {
+            return Err(format!(
+                "replay format version {version} is not supported; the newest is \
+                 {REPLAY_FORMAT_VERSION}"
+            ));
+        }
+
+        let mut initial_state = GameState::new(reader.table_spec()?);
+        initial_state.ty = match reader.byte()? {
+            0 => GameType::NineBall,
+            1 => GameType::EightBall,
+            2 => GameType::TenBall,
+            3 => GameType::OnePocket,
+            4 => GameType::Banks,
+            other => return Err(reader.error(format!("unknown game {other}"))),
+        };
+        initial_state.cueball_modifier = match reader.byte()? {
+            0 => CueballModifier::AsItLays,
+            1 => CueballModifier::BreakPlacement,
+            2 => CueballModifier::BallInHand,
+            3 => CueballModifier::KitchenPlacement,
+            other => return Err(reader.error(format!("unknown cue ball modifier {other}"))),
+        };
+        for _ in 0..reader.length()? {
+            let ty = reader.ball()?;
+            let position = reader.position()?;
+            let radius = Inches {
+                magnitude: reader.decimal()?,
+            };
+            initial_state.add_ball(Ball {
+                ty,
+                position,
+                spec: BallSpec { radius },
+            });
+        }
+
+        let heading = angle_from_degrees(reader.float()?);
+        let cue_speed = InchesPerSecond::new(Inches {
+            magnitude: reader.decimal()?,
+        });
+        let tip_contact = CueTipContact::new(reader.scale()?, reader.scale()?)
+            .map_err(|error| reader.error(format!("invalid cue tip contact: {error:?}")))?;
+        let cue_elevation_degrees = reader.float()?;
+        let shot = Shot::new(heading, cue_speed, tip_contact)
+            .and_then(|shot| shot.with_cue_elevation_degrees(cue_elevation_degrees))
+            .map_err(|error| reader.error(format!("invalid shot: {error:?}")))?;
+
+        let event_log = (0..reader.length()?)
+            .map(|_| reader.event())
+            .collect::<Result<_, _>>()?;
+        if reader.offset != bytes.len() {
+            return Err(reader.error("unexpected bytes after the event log".to_string()));
+        }
+
+        Ok(Self {
+            initial_state,
+            shot,
+            event_log,
+        })
+    }
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn byte(&mut self, byte: u8) {
+        self.0.push(byte);
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn length(&mut self, length: usize) {
+        self.varint(length as u64);
+    }
+
+    fn float(&mut self, value: f64) {
+        self.0.extend(value.to_le_bytes());
+    }
+
+    fn decimal(&mut self, value: &BigDecimal) {
+        let (digits, scale) = value.as_bigint_and_exponent();
+        self.varint(((scale << 1) ^ (scale >> 63)) as u64);
+        let digits = digits.to_signed_bytes_le();
+        self.length(digits.len());
+        self.0.extend(digits);
+    }
+
+    fn position(&mut self, position: &Position) {
+        self.decimal(&position.x.magnitude);
+        self.decimal(&position.y.magnitude);
+    }
+
+    fn ball(&mut self, ball: &BallType) {
+        self.byte(ball.number().unwrap_or(0));
+    }
+
+    fn table_spec(&mut self, table_spec: &TableSpec) {
+        self.decimal(&table_spec.diamond_length.magnitude);
+        self.decimal(&table_spec.cushion_diamond_buffer.magnitude);
+        for pocket in &table_spec.pockets {
+            self.byte(match pocket.ty {
+                PocketType::Corner => 0,
+                PocketType::Side => 1,
+            });
+            self.decimal(&pocket.depth.magnitude);
+            self.decimal(&pocket.width.magnitude);
+            match &pocket.shape.jaw_geometry {
+                PocketJawGeometry::PointNoses => self.byte(0),
+                PocketJawGeometry::RoundedNoses { nose_radius } => {
+                    self.byte(1);
+                    self.decimal(&nose_radius.magnitude);
+                }
+            }
+        }
+        let cushions = &table_spec.cushions;
+        for rail in [
+            &cushions.top,
+            &cushions.right,
+            &cushions.bottom,
+            &cushions.left,
+        ] {
+            self.decimal(&rail.normal_restitution.magnitude);
+            self.decimal(&rail.tangential_friction_coefficient.magnitude);
+            self.decimal(&rail.impact_cloth_friction_coefficient.magnitude);
+            self.decimal(&rail.effective_contact_height_ratio.magnitude);
+        }
+    }
+
+    fn event(&mut self, event: &Event) {
+        self.float(event.time.as_f64());
+        match &event.kind {
+            EventKind::BallBallCollision {
+                first,
+                second,
+                first_position,
+                second_position,
+            } => {
+                self.byte(0);
+                self.ball(first);
+                self.ball(second);
+                self.position(first_position);
+                self.position(second_position);
+            }
+            EventKind::CushionContact {
+                ball,
+                rail,
+                position,
+            } => {
+                self.byte(1);
+                self.ball(ball);
+                self.byte(match rail {
+                    Rail::Top => 0,
+                    Rail::Right => 1,
+                    Rail::Bottom => 2,
+                    Rail::Left => 3,
+                });
+                self.position(position);
+            }
+            EventKind::Pocketed {
+                ball,
+                pocket,
+                position,
+            } => {
+                self.byte(2);
+                self.ball(ball);
+                self.byte(pocket.index() as u8);
+                self.position(position);
+            }
+            EventKind::BallStopped { ball, position } => {
+                self.byte(3);
+                self.ball(ball);
+                self.position(position);
+            }
+        }
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn error(&self, message: String) -> String {
+        format!("byte {}: {message}", self.offset)
+    }
+
+    fn take(&mut self, count: usize) -> Result<&[u8], String> {
+        let end = self
+            .offset
+            .checked_add(count)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| self.error("unexpected end of replay".to_string()))?;
+        let taken = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn byte(&mut self) -> Result<u8, String> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(self.error("varint is too long".to_string()))
+    }
+
+    /// A count of items still to read. Every item takes at least a byte, so a count larger than
+    /// what is left is corrupt, and is refused before anything is allocated for it.
+    fn length(&mut self) -> Result<usize, String> {
+        let length = self.varint()?;
+        usize::try_from(length)
+            .ok()
+            .filter(|length| *length <= self.bytes.len() - self.offset)
+            .ok_or_else(|| {
+                self.error(format!(
+                    "unexpected end of replay: length {length} runs past it"
+                ))
+            })
+    }
+
+    fn float(&mut self) -> Result<f64, String> {
+        let value = f64::from_le_bytes(self.array()?);
+        if !value.is_finite() {
+            return Err(self.error(format!("expected a finite number, found {value}")));
+        }
+        Ok(value)
+    }
+
+    fn decimal(&mut self) -> Result<BigDecimal, String> {
+        let zigzag = self.varint()?;
+        let scale = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+        let length = self.length()?;
+        let digits = BigInt::from_signed_bytes_le(self.take(length)?);
+        Ok(BigDecimal::new(digits, scale))
+    }
+
+    fn scale(&mut self) -> Result<Scale, String> {
+        Ok(Scale {
+            magnitude: self.decimal()?,
+        })
+    }
+
+    fn position(&mut self) -> Result<Position, String> {
+        let x = Diamond {
+            magnitude: self.decimal()?,
+        };
+        let y = Diamond {
+            magnitude: self.decimal()?,
+        };
+        Ok(Position::new(x, y))
+    }
+
+    fn ball(&mut self) -> Result<BallType, String> {
+        match self.byte()? {
+            0 => Ok(BallType::Cue),
+            number => BallType::from_number(number)
+                .ok_or_else(|| self.error(format!("unknown ball {number}"))),
+        }
+    }
+
+    fn rail(&mut self) -> Result<Rail, String> {
+        match self.byte()? {
+            0 => Ok(Rail::Top),
+            1 => Ok(Rail::Right),
+            2 => Ok(Rail::Bottom),
+            3 => Ok(Rail::Left),
+            other => Err(self.error(format!("unknown rail {other}"))),
+        }
+    }
+
+    fn pocket(&mut self) -> Result<Pocket, String> {
+        let index = self.byte()?;
+        Pocket::ALL
+            .get(usize::from(index))
+            .copied()
+            .ok_or_else(|| self.error(format!("unknown pocket {index}")))
+    }
+
+    fn table_spec(&mut self) -> Result<TableSpec, String> {
+        let diamond_length = Inches {
+            magnitude: self.decimal()?,
+        };
+        let cushion_diamond_buffer = Diamond {
+            magnitude: self.decimal()?,
+        };
+        let mut pockets = Vec::with_capacity(6);
+        for _ in 0..6 {
+            pockets.push(self.pocket_spec()?);
+        }
+        Ok(TableSpec {
+            pockets: pockets.try_into().unwrap(),
+            cushion_diamond_buffer,
+            diamond_length,
+            cushions: RailCollisionProfile {
+                top: self.rail_collision_config()?,
+                right: self.rail_collision_config()?,
+                bottom: self.rail_collision_config()?,
+                left: self.rail_collision_config()?,
+            },
+        })
+    }
+
+    fn pocket_spec(&mut self) -> Result<PocketSpec, String> {
+        let ty = match self.byte()? {
+            0 => PocketType::Corner,
+            1 => PocketType::Side,
+            other => return Err(self.error(format!("unknown pocket type {other}"))),
+        };
+        let depth = Diamond {
+            magnitude: self.decimal()?,
+        };
+        let width = Diamond {
+            magnitude: self.decimal()?,
+        };
+        let jaw_geometry = match self.byte()? {
+            0 => PocketJawGeometry::PointNoses,
+            1 => PocketJawGeometry::RoundedNoses {
+                nose_radius: Inches {
+                    magnitude: self.decimal()?,
+                },
+            },
+            other => return Err(self.error(format!("unknown pocket jaw geometry {other}"))),
+        };
+        Ok(PocketSpec {
+            ty,
+            depth,
+            width,
+            shape: PocketShapeSpec { jaw_geometry },
+        })
+    }
+
+    fn rail_collision_config(&mut self) -> Result<RailCollisionConfig, String> {
+        Ok(RailCollisionConfig {
+            normal_restitution: self.scale()?,
+            tangential_friction_coefficient: self.scale()?,
+            impact_cloth_friction_coefficient: self.scale()?,
+            effective_contact_height_ratio: self.scale()?,
+        })
+    }
+
+    fn event(&mut self) -> Result<Event, String> {
+        let time = Seconds::new(self.float()?);
+        let kind = match self.byte()? {
+            0 => EventKind::BallBallCollision {
+                first: self.ball()?,
+                second: self.ball()?,
+                first_position: self.position()?,
+                second_position: self.position()?,
+            },
+            1 => EventKind::CushionContact {
+                ball: self.ball()?,
+                rail: self.rail()?,
+                position: self.position()?,
+            },
+            2 => EventKind::Pocketed {
+                ball: self.ball()?,
+                pocket: self.pocket()?,
+                position: self.position()?,
+            },
+            3 => EventKind::BallStopped {
+                ball: self.ball()?,
+                position: self.position()?,
+            },
+            other => return Err(self.error(format!("unknown event kind {other}"))),
+        };
+        Ok(Event { time, kind })
+    }
+}
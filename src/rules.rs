@@ -0,0 +1,186 @@
+//! Which balls the shooter may play, for tools that enumerate shots.
+//!
+//! These rules answer "what is a legal target right now" and, from a simulated shot's event log,
+//! whether the shot was a [`HitVerdict::LegalHit`] or a [`Foul`]; pushes and calls are left to the
+//! caller. [`GameState::makeable_shots`] combines a [`GameRules`] with the
+//! open-pocket and difficulty geometry in [`crate::aiming`].
+
+use crate::{
+    simulation::SimulationResult, BallType, CutAngle, GameState, GameType, Pocket, Position,
+};
+
+/// The part of a game's rules that decides which object balls and pockets the shooter may play.
+pub trait GameRules {
+    /// Return the object balls on the table that the shooter may legally pocket.
+    fn legal_object_balls(&self, state: &GameState) -> Vec<BallType>;
+
+    /// Whether `object_ball` may be pocketed in `pocket`. Every pocket counts by default.
+    fn is_legal_pocket(&self, _object_ball: &BallType, _pocket: Pocket) -> bool {
+        true
+    }
+
+    /// The foul, if any, that `result` commits when played from `before`.
+    ///
+    /// By default the cue ball must stay on the table, hit one of
+    /// [`GameRules::legal_object_balls`] first, and then drive some ball to a cushion or into a
+    /// pocket. A scratch is reported ahead of any other foul on the same shot.
+    fn foul(&self, before: &GameState, result: &SimulationResult) -> Option<Foul> {
+        if result.scratched() {
+            return Some(Foul::Scratch);
+        }
+        let Some(first_contact) = result.first_contact() else {
+            return Some(Foul::NoContact);
+        };
+        if !self.legal_object_balls(before).contains(&first_contact) {
+            return Some(Foul::WrongBallFirst(first_contact));
+        }
+        if !result.rail_after_contact() {
+            return Some(Foul::NoRailAfterContact);
+        }
+        None
+    }
+
+    /// Judge `result`, played from `before`, by [`GameRules::foul`].
+    fn verdict(&self, before: &GameState, result: &SimulationResult) -> HitVerdict {
+        match self.foul(before, result) {
+            Some(foul) => HitVerdict::Foul(foul),
+            None => HitVerdict::LegalHit,
+        }
+    }
+
+    /// Whether `ball`, pocketed on a foul from `before`, comes back up on the foot spot. Nothing is
+    /// spotted by default.
+    fn is_spotted_after_foul(&self, _before: &GameState, _ball: &BallType) -> bool {
+        false
+    }
+}
+
+/// A foul that ends the shooter's turn and gives the opponent ball in hand.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Foul {
+    /// The cue ball went into a pocket.
+    Scratch,
+    /// The cue ball touched no other ball.
+    NoContact,
+    /// The cue ball touched this ball first, which was not a legal object ball.
+    WrongBallFirst(BallType),
+    /// After the cue ball's first contact no ball reached a cushion or a pocket.
+    NoRailAfterContact,
+}
+
+/// Whether a simulated shot was legal.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HitVerdict {
+    LegalHit,
+    Foul(Foul),
+}
+
+/// The rules [`GameState::ty`] plays by: rotation for 9-ball and 10-ball, an open table for 8-ball,
+/// and any ball for the rest.
+pub fn rules_for(game: &GameType) -> Box<dyn GameRules> {
+    match game {
+        GameType::NineBall | GameType::TenBall => Box::new(NineBallRules),
+        GameType::EightBall => Box::new(EightBallRules::default()),
+        GameType::OnePocket | GameType::Banks => Box::new(AnyBallRules),
+    }
+}
+
+/// Rotation rules for 9-ball and 10-ball: only the lowest-numbered ball on the table is on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NineBallRules;
+
+impl GameRules for NineBallRules {
+    fn legal_object_balls(&self, state: &GameState) -> Vec<BallType> {
+        state
+            .balls()
+            .iter()
+            .filter(|ball| ball.ty.number().is_some())
+            .min_by_key(|ball| ball.ty.number())
+            .map(|ball| vec![ball.ty.clone()])
+            .unwrap_or_default()
+    }
+
+    /// The game ball, the highest-numbered ball on the table, is spotted if it drops on a foul.
+    fn is_spotted_after_foul(&self, before: &GameState, ball: &BallType) -> bool {
+        before
+            .balls()
+            .iter()
+            .filter_map(|ball| ball.ty.number())
+            .max()
+            .is_some_and(|game_ball| ball.number() == Some(game_ball))
+    }
+}
+
+/// Which group an 8-ball shooter is on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EightBallGroup {
+    /// The table is open: any ball except the 8 is on.
+    #[default]
+    Open,
+    /// Balls 1 through 7.
+    Solids,
+    /// Balls 9 and up.
+    Stripes,
+}
+
+/// 8-ball rules: the shooter's group, then the 8 once the group is cleared.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EightBallRules {
+    pub group: EightBallGroup,
+}
+
+impl EightBallRules {
+    pub fn new(group: EightBallGroup) -> Self {
+        Self { group }
+    }
+}
+
+impl GameRules for EightBallRules {
+    fn legal_object_balls(&self, state: &GameState) -> Vec<BallType> {
+        let in_group = |number: u8| match self.group {
+            EightBallGroup::Open => number != 8,
+            EightBallGroup::Solids => number < 8,
+            EightBallGroup::Stripes => number > 8,
+        };
+        let group_balls: Vec<BallType> = state
+            .balls()
+            .iter()
+            .filter(|ball| ball.ty.number().is_some_and(in_group))
+            .map(|ball| ball.ty.clone())
+            .collect();
+
+        if !group_balls.is_empty() || self.group == EightBallGroup::Open {
+            return group_balls;
+        }
+        state
+            .select_ball(BallType::Eight)
+            .map(|ball| vec![ball.ty.clone()])
+            .unwrap_or_default()
+    }
+}
+
+/// Any object ball into any pocket, as in straight pool or free practice.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AnyBallRules;
+
+impl GameRules for AnyBallRules {
+    fn legal_object_balls(&self, state: &GameState) -> Vec<BallType> {
+        state
+            .balls()
+            .iter()
+            .filter(|ball| ball.ty != BallType::Cue)
+            .map(|ball| ball.ty.clone())
+            .collect()
+    }
+}
+
+/// A legal object ball and pocket the shooter has a clear shot at.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MakeableShot {
+    pub object_ball: BallType,
+    pub pocket: Pocket,
+    pub ghost_ball: Position,
+    pub cut_angle: CutAngle,
+    /// The shot's [`crate::aiming::shot_difficulty`]; lower is easier.
+    pub difficulty: f64,
+}
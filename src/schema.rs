@@ -0,0 +1,128 @@
+//! Versions of the saved layout, drill, drill book, table profile and rack template formats, and
+//! the migrations that bring files written by older versions up to date.
+//!
+//! [`GameState::to_json`](crate::GameState::to_json) starts every file with `"schema_version"`,
+//! as does [`TableProfile::to_json`](crate::tables::TableProfile::to_json), and drill, drill book
+//! and rack template files give it as a top-level `schema_version = 2`. A file without one
+//! predates versioning and is read as version 1. Reading runs every migration from the file's
+//! version up to [`SCHEMA_VERSION`] on the raw document before it becomes types, so files keep
+//! loading as the types change shape; a file from a newer version is refused.
+//!
+//! | Version | Change |
+//! |---------|--------|
+//! | 1       | The first format, without a version. |
+//! | 2       | `ball_focus` is `{ "balls": [...], "opacity": 0.35 }` rather than `[[...], 0.35]`. |
+
+use serde_json::{Map, Value};
+
+/// The version files are written in, and the newest this build reads.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// The kinds of saved file, which differ in where their layouts sit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Document {
+    /// A layout, as [`GameState::to_json`](crate::GameState::to_json) writes.
+    Layout,
+    /// A [`Drill`](crate::drills::Drill), whose layout is its `layout`.
+    Drill,
+    /// A [`DrillBook`](crate::drills::DrillBook), with a layout in every drill of every chapter.
+    DrillBook,
+    /// A [`TableProfile`](crate::tables::TableProfile), which has no layout.
+    TableProfile,
+    /// A [`RackTemplate`](crate::racks::RackTemplate), which has no layout.
+    RackTemplate,
+}
+
+type LayoutMigration = fn(&mut Map<String, Value>);
+
+/// The changes to each layout in a document, the first taking version 1 to version 2.
+const LAYOUT_MIGRATIONS: [LayoutMigration; SCHEMA_VERSION as usize - 1] = [ball_focus_as_object];
+
+/// Bring `document`, read from a file of any version up to [`SCHEMA_VERSION`], up to the current
+/// version, and take its `schema_version` out so what is left deserializes into types.
+pub fn migrate(kind: Document, mut document: Value) -> Result<Value, String> {
+    let Value::Object(root) = &mut document else {
+        return Err("expected a table of fields at the top level".to_string());
+    };
+    let version = match root.remove("schema_version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .filter(|version| *version >= 1)
+            .ok_or(format!(
+                "schema_version must be a whole number from 1, found {version}"
+            ))?,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "schema version {version} is newer than {SCHEMA_VERSION}, the newest this build reads"
+        ));
+    }
+
+    for migration in &LAYOUT_MIGRATIONS[version as usize - 1..] {
+        for layout in layouts(kind, root) {
+            migration(layout);
+        }
+    }
+    Ok(document)
+}
+
+/// Read a `kind` of document from TOML, migrating it to the current version first.
+pub(crate) fn read_toml<T: serde::de::DeserializeOwned>(
+    kind: Document,
+    text: &str,
+) -> Result<T, String> {
+    let document = toml::from_str(text).map_err(|error| error.to_string())?;
+    serde_json::from_value(migrate(kind, document)?).map_err(|error| error.to_string())
+}
+
+/// `value` with its fields after a leading `schema_version` of [`SCHEMA_VERSION`].
+#[derive(serde::Serialize)]
+pub(crate) struct Versioned<'a, T> {
+    schema_version: u32,
+    #[serde(flatten)]
+    value: &'a T,
+}
+
+impl<'a, T> Versioned<'a, T> {
+    pub(crate) fn new(value: &'a T) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            value,
+        }
+    }
+}
+
+fn layouts(kind: Document, root: &mut Map<String, Value>) -> Vec<&mut Map<String, Value>> {
+    match kind {
+        Document::Layout => vec![root],
+        Document::Drill => root
+            .get_mut("layout")
+            .and_then(Value::as_object_mut)
+            .into_iter()
+            .collect(),
+        Document::DrillBook => root
+            .get_mut("chapters")
+            .and_then(Value::as_array_mut)
+            .into_iter()
+            .flatten()
+            .filter_map(|chapter| chapter.get_mut("drills")?.as_array_mut())
+            .flatten()
+            .filter_map(|drill| drill.get_mut("layout")?.as_object_mut())
+            .collect(),
+        Document::TableProfile | Document::RackTemplate => Vec::new(),
+    }
+}
+
+fn ball_focus_as_object(layout: &mut Map<String, Value>) {
+    if let Some(Value::Array(pair)) = layout.get_mut("ball_focus") {
+        if let [balls, opacity] = pair.as_mut_slice() {
+            let (balls, opacity) = (balls.take(), opacity.take());
+            layout.insert(
+                "ball_focus".to_string(),
+                serde_json::json!({ "balls": balls, "opacity": opacity }),
+            );
+        }
+    }
+}
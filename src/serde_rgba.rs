@@ -0,0 +1,51 @@
+//! Colors as `#rrggbbaa` strings, the form CSS and the command line take them in, for fields
+//! serialized with `#[serde(with = "crate::serde_rgba")]`. `#rrggbb` reads as opaque.
+
+use image::Rgba;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(color: &Rgba<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+    let [red, green, blue, alpha] = color.0;
+    serializer.collect_str(&format_args!("#{red:02x}{green:02x}{blue:02x}{alpha:02x}"))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Rgba<u8>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    parse(&text)
+        .ok_or_else(|| D::Error::custom(format!("expected #rrggbb or #rrggbbaa, got {text:?}")))
+}
+
+fn parse(text: &str) -> Option<Rgba<u8>> {
+    let hex = text.strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) {
+        return None;
+    }
+    let channel = |index: usize| match hex.get(2 * index..2 * index + 2) {
+        Some(digits) => u8::from_str_radix(digits, 16).ok(),
+        None => Some(255),
+    };
+    Some(Rgba([channel(0)?, channel(1)?, channel(2)?, channel(3)?]))
+}
+
+/// The same for optional colors, with `null` for none.
+pub mod option {
+    use image::Rgba;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Color(#[serde(with = "super")] Rgba<u8>);
+
+    pub fn serialize<S: Serializer>(
+        color: &Option<Rgba<u8>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        color.map(Color).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Rgba<u8>>, D::Error> {
+        Ok(Option::<Color>::deserialize(deserializer)?.map(|Color(color)| color))
+    }
+}
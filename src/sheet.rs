@@ -0,0 +1,264 @@
+//! Sheets of several diagrams laid out in a grid, each with a caption underneath.
+//!
+//! Every diagram on a sheet is drawn with the same [`DiagramRenderOptions`] and gets a cell the
+//! size of the largest one, so a sheet of full-table diagrams lines up like a page of a drill
+//! book. The sheet renders to one image, a PNG, or a single PDF page.
+//!
+//! [`Sheet::before_and_after`] pairs a shot's starting layout with where the balls came to rest.
+
+use image::imageops::overlay;
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_rect_mut, draw_polygon_mut};
+use imageproc::point::Point;
+use imageproc::rect::Rect;
+
+use crate::diagram::{encode_png, render_scene_to_image, DiagramScene};
+use crate::pdf::{self, PdfPageLayout};
+use crate::{drawing, DiagramRenderOptions};
+
+#[derive(Clone, Debug)]
+pub struct SheetDiagram {
+    pub scene: DiagramScene,
+    pub caption: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Sheet {
+    pub columns: u32,
+    /// Space between cells and around the edge of the sheet.
+    pub gap_px: u32,
+    pub background: Rgba<u8>,
+    pub caption_color: Rgba<u8>,
+    /// The pixel size of each dot of the caption font; captions are 7 dots tall.
+    pub caption_scale_px: u32,
+    /// A caption for the whole sheet, centered beneath the grid.
+    pub caption: String,
+    /// Draw an arrow in the gap from each diagram to the next one in its row, to read the row as
+    /// a sequence.
+    pub arrow_color: Option<Rgba<u8>>,
+    pub diagrams: Vec<SheetDiagram>,
+}
+
+impl Default for Sheet {
+    fn default() -> Self {
+        Self {
+            columns: 2,
+            gap_px: 48,
+            background: Rgba([255, 255, 255, 255]),
+            caption_color: Rgba([17, 17, 17, 255]),
+            caption_scale_px: 4,
+            caption: String::new(),
+            arrow_color: None,
+            diagrams: Vec::new(),
+        }
+    }
+}
+
+impl Sheet {
+    pub fn new(columns: u32) -> Self {
+        Self::default().with_columns(columns)
+    }
+
+    /// `before` and `after` side by side with an arrow between them and `caption` under both.
+    pub fn before_and_after(
+        before: DiagramScene,
+        after: DiagramScene,
+        caption: impl Into<String>,
+    ) -> Self {
+        let sheet = Self::new(2);
+        let ink = sheet.caption_color;
+        sheet
+            .with_diagram(before, "")
+            .with_diagram(after, "")
+            .with_caption(caption)
+            .with_arrows(ink)
+    }
+
+    pub fn with_columns(mut self, columns: u32) -> Self {
+        self.columns = columns.max(1);
+        self
+    }
+
+    pub fn with_gap(mut self, gap_px: u32) -> Self {
+        self.gap_px = gap_px;
+        self
+    }
+
+    pub fn with_background(mut self, background: Rgba<u8>) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = caption.into();
+        self
+    }
+
+    pub fn with_arrows(mut self, color: Rgba<u8>) -> Self {
+        self.arrow_color = Some(color);
+        self
+    }
+
+    /// Append `scene` in the next cell, with `caption` centered beneath it. Captions are drawn
+    /// in capitals, smaller if they would be wider than the cell; an empty caption leaves the
+    /// space blank.
+    pub fn with_diagram(mut self, scene: DiagramScene, caption: impl Into<String>) -> Self {
+        self.push(scene, caption);
+        self
+    }
+
+    pub fn push(&mut self, scene: DiagramScene, caption: impl Into<String>) {
+        self.diagrams.push(SheetDiagram {
+            scene,
+            caption: caption.into(),
+        });
+    }
+
+    fn rows(&self) -> u32 {
+        (self.diagrams.len() as u32)
+            .div_ceil(self.columns.max(1))
+            .max(1)
+    }
+
+    /// The height of the band under each diagram that holds its caption, or 0 without captions.
+    fn caption_band_px(&self) -> u32 {
+        if self
+            .diagrams
+            .iter()
+            .all(|diagram| diagram.caption.is_empty())
+        {
+            return 0;
+        }
+        self.text_band_px()
+    }
+
+    /// A line of caption text with two dots of space above it and two below.
+    fn text_band_px(&self) -> u32 {
+        11 * self.caption_scale_px
+    }
+
+    /// Draw `text` centered in the band `width` wide from `(left, top)`, shrunk if it would be
+    /// wider than the band.
+    fn draw_caption(&self, sheet: &mut RgbaImage, text: &str, (left, top): (u32, u32), width: u32) {
+        let scale = (1..=self.caption_scale_px)
+            .rev()
+            .find(|&scale| drawing::text_width_px(text, scale) <= width)
+            .unwrap_or(1);
+        let text_width = drawing::text_width_px(text, scale);
+        drawing::draw_text_at_px_mut(
+            sheet,
+            left as i32 + (width as i32 - text_width as i32) / 2,
+            top as i32 + (self.text_band_px() as i32 - 7 * scale as i32) / 2,
+            text,
+            scale,
+            self.caption_color,
+        );
+    }
+
+    /// Draw every diagram with `options` and lay them out row by row.
+    pub fn render_to_image(&self, options: &DiagramRenderOptions) -> RgbaImage {
+        let images: Vec<RgbaImage> = self
+            .diagrams
+            .iter()
+            .map(|diagram| render_scene_to_image(&diagram.scene, options))
+            .collect();
+        let cell_width = images.iter().map(RgbaImage::width).max().unwrap_or(1);
+        let cell_height = images.iter().map(RgbaImage::height).max().unwrap_or(1);
+        let caption_band = self.caption_band_px();
+        let columns = self.columns.max(1);
+        let rows = self.rows();
+        let gap = self.gap_px;
+        let sheet_caption_band = if self.caption.is_empty() {
+            0
+        } else {
+            self.text_band_px()
+        };
+
+        let mut sheet = RgbaImage::from_pixel(
+            columns * cell_width + (columns + 1) * gap,
+            rows * (cell_height + caption_band) + (rows + 1) * gap + sheet_caption_band,
+            self.background,
+        );
+        for (index, (diagram, image)) in self.diagrams.iter().zip(&images).enumerate() {
+            let (column, row) = (index as u32 % columns, index as u32 / columns);
+            let left = gap + column * (cell_width + gap);
+            let top = gap + row * (cell_height + caption_band + gap);
+
+            let image_left = left + (cell_width - image.width()) / 2;
+            let image_top = top + (cell_height - image.height()) / 2;
+            overlay(&mut sheet, image, image_left.into(), image_top.into());
+
+            self.draw_caption(
+                &mut sheet,
+                &diagram.caption,
+                (left, top + cell_height),
+                cell_width,
+            );
+
+            let has_next_in_row = column + 1 < columns && index + 1 < self.diagrams.len();
+            if let (Some(color), true) = (self.arrow_color, has_next_in_row) {
+                draw_arrow(
+                    &mut sheet,
+                    (left + cell_width, top + cell_height / 2),
+                    gap,
+                    color,
+                );
+            }
+        }
+        if !self.caption.is_empty() {
+            let (width, height) = sheet.dimensions();
+            self.draw_caption(
+                &mut sheet,
+                &self.caption,
+                (0, height - sheet_caption_band),
+                width,
+            );
+        }
+        sheet
+    }
+
+    pub fn render_to_png(&self, options: &DiagramRenderOptions) -> Vec<u8> {
+        encode_png(&self.render_to_image(options), options.effective_dpi())
+    }
+
+    /// The whole sheet on one page, shrunk to fit inside the page's margins.
+    pub fn render_to_pdf(&self, options: &DiagramRenderOptions, layout: &PdfPageLayout) -> Vec<u8> {
+        pdf::images_to_pdf(&[self.render_to_image(options)], layout)
+    }
+}
+
+/// A rightward arrow across the middle of a gap `gap_px` wide that starts at `(left, center_y)`.
+fn draw_arrow(sheet: &mut RgbaImage, (left, center_y): (u32, u32), gap_px: u32, color: Rgba<u8>) {
+    if gap_px < 8 {
+        return;
+    }
+    let gap = gap_px as f32;
+    let (start, tip) = (left as f32 + 0.2 * gap, left as f32 + 0.8 * gap);
+    let head_length = 0.3 * gap;
+    let (shaft, head) = ((gap / 8.0).max(2.0), 0.4 * gap);
+    let y = center_y as f32;
+
+    draw_filled_rect_mut(
+        sheet,
+        Rect::at(start.round() as i32, (y - shaft / 2.0).round() as i32).of_size(
+            (tip - head_length - start).round() as u32,
+            shaft.round() as u32,
+        ),
+        color,
+    );
+    draw_polygon_mut(
+        sheet,
+        &[
+            Point::new(tip.round() as i32, y.round() as i32),
+            Point::new(
+                (tip - head_length).round() as i32,
+                (y - head / 2.0).round() as i32,
+            ),
+            Point::new(
+                (tip - head_length).round() as i32,
+                (y + head / 2.0).round() as i32,
+            ),
+        ],
+        color,
+    );
+}
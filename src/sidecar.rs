@@ -0,0 +1,214 @@
+//! Where everything in a rendered diagram was drawn, so a web page can make a static PNG
+//! clickable: a hit area over each ball and pocket, and the points of each annotation.
+//!
+//! Every point and radius is in pixels of the exported image, measured from its top-left corner
+//! after the orientation, crop, scaling and margin of the [`DiagramRenderOptions`] it was drawn
+//! with. With the `serde` feature, [`DiagramSidecar::to_json`] writes it out beside the image:
+//!
+//! ```json
+//! {
+//!   "width_px": 1089,
+//!   "height_px": 1938,
+//!   "balls": [{ "ty": "Cue", "center": { "x": 324.5, "y": 1398.5 }, "radius_px": 19.3, "opacity": 1.0 }],
+//!   "annotations": [{ "kind": "dashed_line", "layer": "overlays-below-balls", "points": [...] }],
+//!   "pockets": [{ "pocket": "TopRight", "center": { "x": 940.7, "y": 137.3 }, "radius_px": 38.6 }]
+//! }
+//! ```
+
+use crate::diagram::{zoomed_size_px, DiagramElement, DiagramScene, ScenePoint};
+use crate::{pocket_mouth_disc, BallType, DiagramOrientation, DiagramRenderOptions, Pocket};
+
+/// The drawn elements of one diagram, in the pixels of its output.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DiagramSidecar {
+    /// The size of the whole output, margin included.
+    pub width_px: u32,
+    pub height_px: u32,
+    /// The balls, in the order they are drawn.
+    pub balls: Vec<SidecarBall>,
+    /// The annotations, in the order they were added, leaving out those in hidden layers.
+    pub annotations: Vec<SidecarAnnotation>,
+    /// The mouth of every pocket, as [`GameState::highlight_pocket`](crate::GameState::highlight_pocket)
+    /// shades it.
+    pub pockets: Vec<SidecarPocket>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SidecarBall {
+    pub ty: BallType,
+    pub center: ScenePoint,
+    pub radius_px: f32,
+    /// Below 1 for a ball dimmed to bring out others.
+    pub opacity: f32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SidecarAnnotation {
+    /// The annotation's kind as its JSON is tagged, e.g. `dashed_line` or `text_label`.
+    pub kind: &'static str,
+    /// The layer it is drawn in; see [`DiagramLayerId::as_str`](crate::diagram::DiagramLayerId::as_str).
+    pub layer: &'static str,
+    /// The points that place it: a line's ends, a path's points, a zone's outline, an angle's
+    /// start, vertex and end, or the center or anchor of anything else.
+    pub points: Vec<ScenePoint>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SidecarPocket {
+    pub pocket: Pocket,
+    pub center: ScenePoint,
+    pub radius_px: f32,
+}
+
+impl DiagramSidecar {
+    /// The elements of `scene` where rendering it with `options` draws them. Matches every
+    /// backend that draws pixels or sizes itself in them: PNG, JPEG, WebP and SVG.
+    pub fn new(scene: &DiagramScene, options: &DiagramRenderOptions) -> Self {
+        let to_output = OutputTransform::new(scene, options);
+        let length_px = |scene_px: f32| scene_px * to_output.scale.0;
+
+        let balls = scene
+            .balls
+            .iter()
+            .map(|ball| SidecarBall {
+                ty: ball.ty.clone(),
+                center: to_output.point(scene.viewport.position_to_scene_point(&ball.position)),
+                radius_px: length_px(scene.viewport.ball_radius_px(&scene.table_spec, &ball.spec)),
+                opacity: ball.opacity,
+            })
+            .collect();
+
+        let annotations = scene
+            .elements
+            .iter()
+            .filter_map(|element| {
+                let (kind, positions) = match element {
+                    DiagramElement::DashedLine { start, end, .. } => {
+                        ("dashed_line", vec![start, end])
+                    }
+                    DiagramElement::SmoothPolyline { points, .. } => {
+                        ("smooth_polyline", points.iter().collect())
+                    }
+                    DiagramElement::GhostBall { center, .. } => ("ghost_ball", vec![center]),
+                    DiagramElement::PhantomBall { center, .. } => ("phantom_ball", vec![center]),
+                    DiagramElement::BallHighlight { center, .. } => {
+                        ("ball_highlight", vec![center])
+                    }
+                    DiagramElement::CircleMarker { center, .. } => ("circle_marker", vec![center]),
+                    DiagramElement::TextLabel { anchor, .. } => ("text_label", vec![anchor]),
+                    DiagramElement::SequenceMarker { center, .. } => {
+                        ("sequence_marker", vec![center])
+                    }
+                    DiagramElement::AngleArc {
+                        vertex, start, end, ..
+                    } => ("angle_arc", vec![start, vertex, end]),
+                    DiagramElement::PocketHighlight { center, .. } => {
+                        ("pocket_highlight", vec![center])
+                    }
+                    DiagramElement::ShadedZone { outline, .. } => {
+                        ("shaded_zone", outline.iter().collect())
+                    }
+                    DiagramElement::Legend { anchor, .. } => ("legend", vec![anchor]),
+                    DiagramElement::DiamondGrid { .. } => return None,
+                };
+                Some(SidecarAnnotation {
+                    kind,
+                    layer: element.layer().as_str(),
+                    points: positions
+                        .into_iter()
+                        .map(|position| {
+                            to_output.point(scene.viewport.position_to_scene_point(position))
+                        })
+                        .collect(),
+                })
+            })
+            .collect();
+
+        let pockets = Pocket::ALL
+            .into_iter()
+            .map(|pocket| {
+                let (center, radius) = pocket_mouth_disc(pocket, &scene.table_spec);
+                SidecarPocket {
+                    pocket,
+                    center: to_output.point(scene.viewport.position_to_scene_point(&center)),
+                    radius_px: length_px(scene.viewport.length_px(&scene.table_spec, &radius)),
+                }
+            })
+            .collect();
+
+        Self {
+            width_px: to_output.size_px.0,
+            height_px: to_output.size_px.1,
+            balls,
+            annotations,
+            pockets,
+        }
+    }
+
+    /// This sidecar as a JSON document in the shape shown in the [module docs](self).
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a diagram sidecar is always valid JSON")
+    }
+}
+
+/// Takes points in a scene's portrait pixels to the pixels of its rendered output.
+struct OutputTransform {
+    orientation: DiagramOrientation,
+    /// The height of the portrait scene, which becomes the width of a landscape one.
+    scene_height_px: f32,
+    /// The top-left corner of the cropped region, in oriented scene pixels.
+    origin: (f32, f32),
+    /// Output pixels per oriented scene pixel, across and down.
+    scale: (f32, f32),
+    margin_px: f32,
+    size_px: (u32, u32),
+}
+
+impl OutputTransform {
+    fn new(scene: &DiagramScene, options: &DiagramRenderOptions) -> Self {
+        let full_px = scene.viewport.oriented_size_px(options.orientation);
+        let ((left, top, width, height), native_px) = match &options.crop {
+            Some(region) => {
+                let region_px = scene
+                    .viewport
+                    .oriented_region_px(region, options.orientation);
+                (
+                    region_px,
+                    zoomed_size_px(full_px.0, (region_px.2, region_px.3)),
+                )
+            }
+            None => ((0, 0, full_px.0, full_px.1), full_px),
+        };
+        let (output_width, output_height) = options.output_size_px(native_px);
+        Self {
+            orientation: options.orientation,
+            scene_height_px: scene.viewport.height_px,
+            origin: (left as f32, top as f32),
+            scale: (
+                output_width as f32 / width as f32,
+                output_height as f32 / height as f32,
+            ),
+            margin_px: options.margin_px as f32,
+            size_px: (
+                output_width + 2 * options.margin_px,
+                output_height + 2 * options.margin_px,
+            ),
+        }
+    }
+
+    fn point(&self, point: ScenePoint) -> ScenePoint {
+        let (x, y) = match self.orientation {
+            DiagramOrientation::Portrait => (point.x, point.y),
+            DiagramOrientation::Landscape => (self.scene_height_px - point.y, point.x),
+        };
+        ScenePoint {
+            x: self.margin_px + (x - self.origin.0) * self.scale.0,
+            y: self.margin_px + (y - self.origin.1) * self.scale.1,
+        }
+    }
+}
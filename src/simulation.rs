@@ -0,0 +1,2357 @@
+//! Run a shot from a [`GameState`] through the event-driven N-ball engine.
+//!
+//! The engine never steps a fixed clock: it predicts the time of the next ball-ball collision,
+//! cushion or jaw impact, pocket capture, or motion transition for every ball, advances the whole
+//! table analytically to the earliest one, resolves it, and repeats until every ball is at rest or
+//! pocketed.
+//!
+//! [`resolve_cue_ball_hit`] runs just the ball-ball contact both backends use, for aiming tools
+//! that want throw and cue-ball departure without simulating a whole shot.
+//!
+//! [`FixedStepSimulator`] is a plain integrator behind the same [`Simulator`] trait, for
+//! cross-checking the event engine and for tables with extra [`ExternalForce`]s such as a tilt or
+//! a dirty patch of cloth.
+
+use crate::{
+    advance_motion_on_table,
+    aiming::PositionZone,
+    angle_from_degrees, ball_is_captured_by_pocket_on_table, build_on_table_ball_state,
+    classify_motion_phase, collide_ball_ball_analyzed_on_table_with_config,
+    collide_ball_ball_on_table_with_radius_and_config,
+    collide_ball_rail_on_table_with_radius_and_profile, human_tuned_preview_motion_config,
+    pocket_capture_gap_on_table, pocket_jaw_associated_rail, position_from_xy_inches,
+    position_xy_inches,
+    racks::RackTemplate,
+    resolve_n_ball_system_event_with_physics_and_pockets_on_table,
+    resolve_stepped_ball_jaw_contact_on_table, round_n_ball_system_states,
+    rules::{rules_for, Foul, GameRules},
+    simulate_n_ball_system_with_physics_and_pockets_on_table_in_decimal,
+    simulate_n_ball_system_with_physics_and_pockets_on_table_until_event_limit,
+    strike_resting_ball_on_table, trace_ball_path_with_rail_profile_on_table, Angle,
+    AngularVelocity3, Ball, BallBallCollisionConfig, BallPath, BallPathStop, BallSetPhysicsSpec,
+    BallSpec, BallState, BallType, CollisionAnalysis, CollisionModel, CueStrikeConfig,
+    CueTipContact, CueballModifier, Diamond, GameState, GameType, Inches, Inches2, InchesPerSecond,
+    LayoutDiff, MeasurementUnit, MotionPhase, NBallSystemEvent, NBallSystemSimulation,
+    NBallSystemState, NextTransition, OnTableBallState, OnTableMotionConfig, Pocket, Position,
+    PredictedBallBallCollision, PredictedBallPocketCapture, PredictedBallRailImpact, Rail,
+    RailModel, RestingOnTableBallState, Scale, Seconds, Shot, ShotError, TableSpec, Velocity2,
+    HEAD_SPOT, RACK_SPOT, STANDARD_GRAVITY_INCHES_PER_SECOND_SQUARED, TYPICAL_BALL_RADIUS,
+};
+
+/// The physics a [`simulate_with_config`] run uses.
+///
+/// Cushion restitution and friction are a property of the table, so they come from the layout's
+/// [`TableSpec::cushions`] rather than from here.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationConfig {
+    pub ball_set: BallSetPhysicsSpec,
+    pub motion: OnTableMotionConfig,
+    pub cue_strike: CueStrikeConfig,
+    pub collision_model: CollisionModel,
+    pub collision_config: BallBallCollisionConfig,
+    /// How cushion rebounds are solved. `SpinAware` loses speed to restitution and turns the
+    /// rebound with side spin; like every rail model here it stays in the table plane, so a
+    /// cushion never makes a ball jump.
+    pub rail_model: RailModel,
+    /// Stop after this many events even if balls are still moving; `None` runs until rest.
+    pub max_events: Option<usize>,
+    /// How [`simulate_with_config`] solves event times.
+    pub time_solver: TimeSolver,
+}
+
+/// How the event engine solves the time of each event.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TimeSolver {
+    /// Native `f64` root finding: fast, but the last bits of a long run can differ between
+    /// platforms whose math libraries round differently.
+    #[default]
+    Float,
+    /// Solve contact times in `BigDecimal` and round every event time and ball state to
+    /// `decimal_places`, for shared replays and physics regression tests. Rounding after every
+    /// event keeps last-bit differences in a platform's math library from compounding, which
+    /// greatly reduces drift between platforms; the motion between events still uses `f64`
+    /// transcendentals, so it is not a guarantee of identical runs. At least
+    /// [`MIN_DECIMAL_PLACES`]. See
+    /// [`crate::simulate_n_ball_system_with_physics_and_pockets_on_table_in_decimal`].
+    Decimal { decimal_places: u32 },
+}
+
+/// The fewest decimal places [`TimeSolver::Decimal`] accepts: nanoseconds and billionths of an
+/// inch. Coarser rounding can snap the time to the next event to zero, so the solver stalls.
+pub const MIN_DECIMAL_PLACES: u32 = 9;
+
+impl Default for SimulationConfig {
+    /// The human-tuned preview physics with throw-aware collisions and spin-aware cushions.
+    fn default() -> Self {
+        Self {
+            ball_set: BallSetPhysicsSpec::default(),
+            motion: human_tuned_preview_motion_config(),
+            cue_strike: CueStrikeConfig::new(Scale::from_f64(1.0), Scale::from_f64(0.1))
+                .expect("default cue strike config should be valid"),
+            collision_model: CollisionModel::ThrowAware,
+            collision_config: BallBallCollisionConfig::human_tuned(),
+            rail_model: RailModel::SpinAware,
+            max_events: None,
+            time_solver: TimeSolver::Float,
+        }
+    }
+}
+
+/// Why a shot could not be simulated.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimulationError {
+    /// The game state has no cue ball to strike.
+    NoCueBall,
+    /// The cue could not deliver the shot.
+    InvalidShot(ShotError),
+    /// The game is not played from a rack, so there is nothing to break.
+    UnsupportedRack(GameType),
+    /// A break must be played with the cue ball in the kitchen.
+    CueBallOutsideKitchen(Position),
+    /// [`TimeSolver::Decimal`] was asked to round to fewer than [`MIN_DECIMAL_PLACES`] places.
+    TooFewDecimalPlaces(u32),
+}
+
+/// The outcome of a simulated shot.
+pub struct SimulationResult {
+    /// The balls still on the table, at their final positions, in the input order.
+    pub final_state: GameState,
+    /// The balls that dropped, in the order they were captured.
+    pub pocketed: Vec<(BallType, Pocket)>,
+    /// Near misses: each time a ball hit a pocket's jaws without dropping there.
+    pub rattled: Vec<(BallType, Pocket)>,
+    /// What happened, in order, by ball type and with positions.
+    pub event_log: Vec<Event>,
+    /// Each tracked ball's route from the strike to where it stopped or dropped, parallel to
+    /// `balls`, with extra points wherever the ball was sliding and so could curve.
+    pub trajectories: Vec<Vec<Position>>,
+    /// The type of each ball the engine tracked; event and state indices refer to this list.
+    pub balls: Vec<BallType>,
+    /// The engine's run: final per-ball states, total time, and every event in time order.
+    pub system: NBallSystemSimulation,
+    /// The layout the shot was played from.
+    pub initial_state: GameState,
+    /// Every tracked ball's state just after the strike, parallel to `balls`.
+    pub strike_states: Vec<NBallSystemState>,
+    /// Every tracked ball's state just after each event, parallel to `system.events`.
+    pub snapshots: Vec<Vec<NBallSystemState>>,
+    /// The ball physics the run used, to replay it between events; see [`Self::layout_at`].
+    pub ball_set: BallSetPhysicsSpec,
+    pub motion: OnTableMotionConfig,
+}
+
+impl SimulationResult {
+    /// Every resolved event, in the order it happened.
+    pub fn events(&self) -> &[NBallSystemEvent] {
+        &self.system.events
+    }
+
+    /// Total simulated time from the strike until the last event.
+    pub fn elapsed(&self) -> Seconds {
+        self.system.elapsed
+    }
+
+    /// Whether the cue ball went into a pocket.
+    pub fn scratched(&self) -> bool {
+        self.pocketed.iter().any(|(ty, _)| *ty == BallType::Cue)
+    }
+
+    /// The ball the cue ball touched first, or `None` if it touched none.
+    pub fn first_contact(&self) -> Option<BallType> {
+        self.event_log.iter().find_map(|event| match &event.kind {
+            EventKind::BallBallCollision { first, second, .. } if *first == BallType::Cue => {
+                Some(second.clone())
+            }
+            EventKind::BallBallCollision { first, second, .. } if *second == BallType::Cue => {
+                Some(first.clone())
+            }
+            _ => None,
+        })
+    }
+
+    /// Whether any ball reached a cushion or a pocket after the cue ball's first contact. Jaw
+    /// hits count as cushions.
+    pub fn rail_after_contact(&self) -> bool {
+        self.event_log
+            .iter()
+            .skip_while(|event| {
+                !matches!(
+                    &event.kind,
+                    EventKind::BallBallCollision { first, second, .. }
+                        if *first == BallType::Cue || *second == BallType::Cue
+                )
+            })
+            .any(|event| {
+                matches!(
+                    event.kind,
+                    EventKind::CushionContact { .. } | EventKind::Pocketed { .. }
+                )
+            })
+    }
+
+    /// The balls still on the table `time` after the strike, where they were at that moment.
+    ///
+    /// Each ball's motion is replayed from its state after the last event before `time`, so this
+    /// retraces the event engine's run exactly. A [`FixedStepSimulator`] run is replayed without
+    /// its external forces between events.
+    pub fn layout_at(&self, time: Seconds) -> GameState {
+        let mut states = self.strike_states.as_slice();
+        let mut since = time.as_f64().max(0.0);
+        for (event, snapshot) in self.system.events.iter().zip(&self.snapshots) {
+            let interval = event.time().as_f64();
+            if since < interval {
+                break;
+            }
+            since -= interval;
+            states = snapshot;
+        }
+
+        let table_spec = &self.initial_state.table_spec;
+        let balls = self
+            .initial_state
+            .balls()
+            .iter()
+            .zip(states)
+            .filter_map(|(ball, state)| {
+                let advanced = advance_motion_on_table(
+                    state.as_on_table()?,
+                    Seconds::new(since),
+                    &self.ball_set,
+                    &self.motion,
+                );
+                Some(Ball {
+                    ty: ball.ty.clone(),
+                    position: advanced.state.projected_position(table_spec),
+                    spec: ball.spec.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut layout = GameState::with_balls(table_spec.clone(), balls);
+        layout.ty = self.initial_state.ty.clone();
+        layout
+    }
+
+    /// What the shot changed: [`GameState::diff`] from the starting layout to where the balls
+    /// came to rest, with the balls that dropped listed as pocketed rather than removed.
+    pub fn diff(&self) -> LayoutDiff {
+        let mut diff = self.initial_state.diff(&self.final_state);
+        for (ty, _) in &self.pocketed {
+            if let Some(index) = diff.removed.iter().position(|ball| ball.ty == *ty) {
+                diff.removed.remove(index);
+            }
+        }
+        diff.pocketed = self.pocketed.clone();
+        diff
+    }
+
+    /// The event log as CSV with a header row and one row per event, for analysis in a
+    /// spreadsheet, pandas or R. Columns are `time_s`, `event` (`ball_ball_collision`,
+    /// `cushion_contact`, `pocketed` or `ball_stopped`), `ball` with its `x` and `y` in `unit`,
+    /// `other_ball` with its `other_x` and `other_y` for the second ball of a collision, `rail`
+    /// and `pocket`; cells that don't apply are left empty. Balls are `cue` or their number, as
+    /// in [`GameState::to_csv`].
+    pub fn event_log_to_csv(&self, unit: MeasurementUnit) -> String {
+        let suffix = match unit {
+            MeasurementUnit::Diamonds => "diamonds",
+            MeasurementUnit::Inches => "inches",
+        };
+        let mut csv = format!(
+            "time_s,event,ball,x_{suffix},y_{suffix},other_ball,other_x_{suffix},other_y_{suffix},rail,pocket\n"
+        );
+        let table_spec = &self.initial_state.table_spec;
+        let ball_cells = |ty: &BallType, position: &Position| {
+            let [x, y] = [&position.x, &position.y].map(|coordinate| match unit {
+                MeasurementUnit::Diamonds => coordinate.magnitude.normalized(),
+                MeasurementUnit::Inches => table_spec
+                    .diamond_to_inches(coordinate.clone())
+                    .magnitude
+                    .normalized(),
+            });
+            let name = ty
+                .number()
+                .map_or("cue".to_string(), |number| number.to_string());
+            format!("{name},{x},{y}")
+        };
+        for event in &self.event_log {
+            let (name, ball, other, rail, pocket) = match &event.kind {
+                EventKind::BallBallCollision {
+                    first,
+                    second,
+                    first_position,
+                    second_position,
+                } => (
+                    "ball_ball_collision",
+                    ball_cells(first, first_position),
+                    ball_cells(second, second_position),
+                    "",
+                    "",
+                ),
+                EventKind::CushionContact {
+                    ball,
+                    rail,
+                    position,
+                } => (
+                    "cushion_contact",
+                    ball_cells(ball, position),
+                    ",,".to_string(),
+                    match rail {
+                        Rail::Top => "top",
+                        Rail::Bottom => "bottom",
+                        Rail::Left => "left",
+                        Rail::Right => "right",
+                    },
+                    "",
+                ),
+                EventKind::Pocketed {
+                    ball,
+                    pocket,
+                    position,
+                } => (
+                    "pocketed",
+                    ball_cells(ball, position),
+                    ",,".to_string(),
+                    "",
+                    match pocket {
+                        Pocket::TopRight => "top_right",
+                        Pocket::CenterRight => "center_right",
+                        Pocket::BottomRight => "bottom_right",
+                        Pocket::BottomLeft => "bottom_left",
+                        Pocket::CenterLeft => "center_left",
+                        Pocket::TopLeft => "top_left",
+                    },
+                ),
+                EventKind::BallStopped { ball, position } => (
+                    "ball_stopped",
+                    ball_cells(ball, position),
+                    ",,".to_string(),
+                    "",
+                    "",
+                ),
+            };
+            csv.push_str(&format!(
+                "{},{name},{ball},{other},{rail},{pocket}\n",
+                event.time.as_f64()
+            ));
+        }
+        csv
+    }
+
+    /// The event log as a JSON array, one object per event with its `time` in seconds, its
+    /// `event` kind as in [`SimulationResult::event_log_to_csv`], and the fields of its
+    /// [`EventKind`]; positions are in diamonds.
+    #[cfg(feature = "serde")]
+    pub fn event_log_to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.event_log).expect("an event log is always valid JSON")
+    }
+
+    /// The route the first ball of type `ball` took, or `None` if no such ball was tracked.
+    pub fn trajectory(&self, ball: BallType) -> Option<&[Position]> {
+        let index = self.balls.iter().position(|ty| *ty == ball)?;
+        Some(&self.trajectories[index])
+    }
+}
+
+/// One entry in a [`SimulationResult::event_log`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Event {
+    /// Time since the strike.
+    pub time: Seconds,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub kind: EventKind,
+}
+
+/// What happened, with where each ball involved was when it happened.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "event", rename_all = "snake_case"))]
+pub enum EventKind {
+    /// Two balls struck each other. Simultaneous contacts within a cluster, as on a break, are
+    /// listed pair by pair.
+    BallBallCollision {
+        first: BallType,
+        second: BallType,
+        first_position: Position,
+        second_position: Position,
+    },
+    /// A ball struck a cushion, including the ends of the cushions that form a pocket's jaws.
+    CushionContact {
+        ball: BallType,
+        rail: Rail,
+        position: Position,
+    },
+    /// A ball dropped; `position` is where it was when it fell.
+    Pocketed {
+        ball: BallType,
+        pocket: Pocket,
+        position: Position,
+    },
+    BallStopped {
+        ball: BallType,
+        position: Position,
+    },
+}
+
+/// The cue ball's spin as it reaches the object ball, as ratios of surface speed `Rω` to its speed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CueBallSpin {
+    /// 1 for natural roll, 0 for stun, and negative for draw.
+    pub follow: Scale,
+    /// Positive for right english, matching the sign of a [`crate::CueTipContact`] side offset.
+    pub side: Scale,
+}
+
+impl CueBallSpin {
+    pub fn new(follow: Scale, side: Scale) -> Self {
+        Self { follow, side }
+    }
+
+    pub fn stun() -> Self {
+        Self::default()
+    }
+
+    pub fn natural_roll() -> Self {
+        Self::new(Scale::from_f64(1.0), Scale::zero())
+    }
+}
+
+/// What happens at the instant the cue ball meets the object ball.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CueBallHit {
+    pub object_ball_heading: Angle,
+    pub object_ball_speed: InchesPerSecond,
+    /// The angle between the line of centers and the object ball's departure, in degrees. Positive
+    /// values turn the object ball toward the cue ball's path, thinning the cut, as in
+    /// [`crate::ThrowModel`]; for a full hit, positive is to the striker's right.
+    pub throw_degrees: f64,
+    /// The cue ball's heading as it leaves contact, or `None` if the hit stopped it dead.
+    pub cue_ball_heading: Option<Angle>,
+    pub cue_ball_speed: InchesPerSecond,
+    /// The cue ball's heading once the cloth has turned any follow or draw into its path, or
+    /// `None` if it leaves contact already rolling or stopped.
+    pub cue_ball_heading_after_bend: Option<Angle>,
+    /// The full contact solution and cue-ball continuation it came from.
+    pub analysis: CollisionAnalysis,
+}
+
+impl CueBallHit {
+    /// Trace the cue ball on from contact until `stop`, off `table_spec`'s cushions.
+    ///
+    /// The trace follows the cue ball alone, so it runs through other balls and over pockets. Use
+    /// [`BallPath::curve_sampled_points`] to draw it with its slide-to-roll bend.
+    pub fn cue_ball_path(
+        &self,
+        stop: BallPathStop,
+        config: &SimulationConfig,
+        table_spec: &TableSpec,
+    ) -> BallPath {
+        trace_ball_path_with_rail_profile_on_table(
+            &self.analysis.outcome.a_after,
+            stop,
+            &config.ball_set,
+            table_spec,
+            &config.motion,
+            config.rail_model,
+            &table_spec.cushions,
+        )
+    }
+}
+
+/// Resolve the cue ball hitting a resting object ball with the collision model in `config`.
+///
+/// `ghost_ball` is the cue ball's center at contact; only its direction from `object_ball` matters,
+/// not its exact distance. The cue ball arrives on `heading` at `cue_ball_speed` with `spin`.
+/// Follow and draw take part in the contact friction, so they reduce throw compared with
+/// [`crate::ThrowModel`]'s stun estimate, and they are carried through the hit into the cue ball's
+/// later bend. Returns `None` if the cue ball is not moving toward the object ball.
+pub fn resolve_cue_ball_hit(
+    ghost_ball: &Position,
+    object_ball: &Position,
+    heading: &Angle,
+    cue_ball_speed: InchesPerSecond,
+    spin: &CueBallSpin,
+    config: &SimulationConfig,
+    table_spec: &TableSpec,
+) -> Option<CueBallHit> {
+    let (cue_x, cue_y) = position_xy_inches(ghost_ball, table_spec);
+    let (object_x, object_y) = position_xy_inches(object_ball, table_spec);
+    let speed = cue_ball_speed.as_f64();
+    let radians = heading.as_degrees().to_radians();
+    let (heading_x, heading_y) = (radians.sin(), radians.cos());
+    let closing = heading_x * (object_x - cue_x) + heading_y * (object_y - cue_y);
+    if speed <= 0.0 || closing <= 0.0 {
+        return None;
+    }
+
+    let radius = config.ball_set.radius.as_f64();
+    let roll = -spin.follow.as_f64() * speed / radius;
+    let cue_ball = build_on_table_ball_state(
+        Inches2::new(Inches::from_f64(cue_x), Inches::from_f64(cue_y)),
+        Velocity2::from_polar(cue_ball_speed, *heading),
+        AngularVelocity3::new(
+            heading_y * roll,
+            -heading_x * roll,
+            spin.side.as_f64() * speed / radius,
+        ),
+    );
+    let object = build_on_table_ball_state(
+        Inches2::new(Inches::from_f64(object_x), Inches::from_f64(object_y)),
+        Velocity2::zero(),
+        AngularVelocity3::zero(),
+    );
+    let analysis = collide_ball_ball_analyzed_on_table_with_config(
+        &cue_ball,
+        &object,
+        config.collision_model,
+        &config.collision_config,
+        &config.ball_set,
+        &config.motion,
+    );
+
+    let line_of_centers = Angle::from_north(object_x - cue_x, object_y - cue_y);
+    let object_after = analysis.outcome.b_after.as_ball_state();
+    let cue_after = analysis.outcome.a_after.as_ball_state();
+    let object_ball_heading = object_after
+        .velocity
+        .angle_from_north()
+        .unwrap_or(line_of_centers);
+    let signed = |degrees: f64| (degrees + 540.0).rem_euclid(360.0) - 180.0;
+    let clockwise_throw = signed(object_ball_heading.as_degrees() - line_of_centers.as_degrees());
+    let cut_side = signed(line_of_centers.as_degrees() - heading.as_degrees());
+    // Toward the cue ball's path is against the side the line of centers was cut to.
+    let throw_degrees = if cut_side.abs() < 1e-9 {
+        clockwise_throw
+    } else {
+        -cut_side.signum() * clockwise_throw
+    };
+
+    Some(CueBallHit {
+        object_ball_speed: object_after.velocity.speed(),
+        object_ball_heading,
+        throw_degrees,
+        cue_ball_heading: cue_after.velocity.angle_from_north(),
+        cue_ball_speed: cue_after.velocity.speed(),
+        cue_ball_heading_after_bend: analysis.cue_ball_bend.as_ref().and_then(|bend| {
+            bend.state_after_bend
+                .as_ball_state()
+                .velocity
+                .angle_from_north()
+        }),
+        analysis,
+    })
+}
+
+/// How often [`scratch_risks`] samples a path, in seconds.
+const SCRATCH_RISK_SAMPLE_SECONDS: f64 = 0.005;
+
+/// Capture gaps bottom out at zero once a ball is in, give or take rounding.
+const CAPTURE_GAP_TOLERANCE_INCHES: f64 = 1e-9;
+
+/// A stretch of a predicted path that runs close enough to a pocket to scratch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScratchRisk {
+    pub pocket: Pocket,
+    /// The ball's centre along the stretch, in order, for marking on a diagram.
+    pub danger: Vec<Position>,
+    /// The closest the ball came to being captured: zero means it drops.
+    pub clearance: Inches,
+    /// The ball's speed at its closest approach. Little to spare means it is dying at the pocket
+    /// and slightly less pace keeps it out; a lot means only a different line does.
+    pub speed_margin: InchesPerSecond,
+}
+
+impl ScratchRisk {
+    /// Whether the path actually drops into the pocket here.
+    pub fn scratches(&self) -> bool {
+        self.clearance.as_f64() <= CAPTURE_GAP_TOLERANCE_INCHES
+    }
+}
+
+/// Find where `path` comes within `range` of being captured by a pocket, one [`ScratchRisk`] for
+/// each stretch, in the order the ball reaches them.
+///
+/// Traced paths reflect off the cushions but run straight over the pockets, so the path is
+/// followed only until the first capture: nothing after it happens. It also ends where the ball
+/// leaves the bed of the table.
+pub fn scratch_risks(
+    path: &BallPath,
+    range: Inches,
+    config: &SimulationConfig,
+    table_spec: &TableSpec,
+) -> Vec<ScratchRisk> {
+    // Each risk with the sample it began at, so overlapping stretches keep the order they begin.
+    let mut risks: Vec<(usize, ScratchRisk)> = Vec::new();
+    let mut open: Vec<Option<(usize, ScratchRisk)>> = vec![None; Pocket::ALL.len()];
+    let mut sample = 0;
+
+    'path: for segment in &path.segments {
+        let duration = segment.duration.as_f64();
+        let steps = (duration / SCRATCH_RISK_SAMPLE_SECONDS).ceil().max(1.0) as usize;
+        for step in 0..=steps {
+            let elapsed = duration * step as f64 / steps as f64;
+            let state = advance_motion_on_table(
+                &segment.start,
+                Seconds::new(elapsed),
+                &config.ball_set,
+                &config.motion,
+            )
+            .state;
+            let Ok(state) = OnTableBallState::try_from(state) else {
+                break 'path;
+            };
+            sample += 1;
+            let ball = state.as_ball_state();
+            let speed = ball.velocity.x().as_f64().hypot(ball.velocity.y().as_f64());
+
+            let mut captured = false;
+            for (slot, pocket) in Pocket::ALL.into_iter().enumerate() {
+                let gap = pocket_capture_gap_on_table(
+                    &state,
+                    pocket,
+                    &config.ball_set,
+                    table_spec,
+                    &config.motion,
+                )
+                .filter(|gap| *gap <= range.as_f64());
+                let Some(gap) = gap else {
+                    risks.extend(open[slot].take());
+                    continue;
+                };
+                let (_, risk) = open[slot].get_or_insert_with(|| {
+                    let risk = ScratchRisk {
+                        pocket,
+                        danger: Vec::new(),
+                        clearance: Inches::from_f64(gap),
+                        speed_margin: InchesPerSecond::new(Inches::from_f64(speed)),
+                    };
+                    (sample, risk)
+                });
+                risk.danger.push(ball.projected_position(table_spec));
+                if gap < risk.clearance.as_f64() {
+                    risk.clearance = Inches::from_f64(gap);
+                    risk.speed_margin = InchesPerSecond::new(Inches::from_f64(speed));
+                }
+                captured |= gap <= CAPTURE_GAP_TOLERANCE_INCHES;
+            }
+            if captured {
+                break 'path;
+            }
+        }
+    }
+    risks.extend(open.into_iter().flatten());
+    risks.sort_by_key(|(first_sample, _)| *first_sample);
+    risks.into_iter().map(|(_, risk)| risk).collect()
+}
+
+/// A physics backend that plays a shot out from a layout.
+pub trait Simulator {
+    /// Strike the cue ball in `state` with `shot` and run the table until it settles.
+    fn simulate(&self, state: &GameState, shot: &Shot)
+        -> Result<SimulationResult, SimulationError>;
+}
+
+/// The analytic event-driven engine behind [`simulate_with_config`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventDrivenSimulator {
+    pub config: SimulationConfig,
+}
+
+impl EventDrivenSimulator {
+    pub fn new(config: SimulationConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Simulator for EventDrivenSimulator {
+    fn simulate(
+        &self,
+        state: &GameState,
+        shot: &Shot,
+    ) -> Result<SimulationResult, SimulationError> {
+        simulate_with_config(state, shot, &self.config)
+    }
+}
+
+/// A force beyond the cloth and cushions that acts on every ball on the table.
+pub trait ExternalForce {
+    /// Return the change in velocity this force gives `state` over `dt`.
+    fn velocity_change(
+        &self,
+        state: &OnTableBallState,
+        dt: Seconds,
+        table: &TableSpec,
+    ) -> Velocity2;
+}
+
+/// A table that is not level: every ball is pulled downhill by gravity along the slope.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableTilt {
+    /// The direction the bed slopes down toward, as a table heading.
+    pub downhill: Angle,
+    pub slope_degrees: f64,
+}
+
+impl TableTilt {
+    pub fn new(downhill: Angle, slope_degrees: f64) -> Self {
+        Self {
+            downhill,
+            slope_degrees,
+        }
+    }
+}
+
+impl ExternalForce for TableTilt {
+    fn velocity_change(
+        &self,
+        _state: &OnTableBallState,
+        dt: Seconds,
+        _table: &TableSpec,
+    ) -> Velocity2 {
+        let acceleration =
+            STANDARD_GRAVITY_INCHES_PER_SECOND_SQUARED * self.slope_degrees.to_radians().sin();
+        let radians = self.downhill.as_degrees().to_radians();
+        Velocity2::new(
+            Inches::from_f64(acceleration * dt.as_f64() * radians.sin()),
+            Inches::from_f64(acceleration * dt.as_f64() * radians.cos()),
+        )
+    }
+}
+
+/// A round patch of dirty or worn cloth that drags on balls rolling through it, on top of the
+/// usual cloth friction.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirtyCloth {
+    pub center: Position,
+    pub radius: Diamond,
+    /// Extra deceleration inside the patch, in inches per second squared.
+    pub deceleration: f64,
+}
+
+impl DirtyCloth {
+    pub fn new(center: Position, radius: Diamond, deceleration: f64) -> Self {
+        Self {
+            center,
+            radius,
+            deceleration,
+        }
+    }
+}
+
+impl ExternalForce for DirtyCloth {
+    fn velocity_change(
+        &self,
+        state: &OnTableBallState,
+        dt: Seconds,
+        table: &TableSpec,
+    ) -> Velocity2 {
+        let state = state.as_ball_state();
+        let x =
+            state.position.x().as_f64() - table.diamond_to_inches(self.center.x.clone()).as_f64();
+        let y =
+            state.position.y().as_f64() - table.diamond_to_inches(self.center.y.clone()).as_f64();
+        let speed = state.velocity.speed().as_f64();
+        if x.hypot(y) > table.diamond_to_inches(self.radius.clone()).as_f64() || speed == 0.0 {
+            return Velocity2::zero();
+        }
+
+        // Drag only slows the ball; it never turns it around within a step.
+        let scale = -(self.deceleration * dt.as_f64()).min(speed) / speed;
+        Velocity2::new(
+            Inches::from_f64(scale * state.velocity.x().as_f64()),
+            Inches::from_f64(scale * state.velocity.y().as_f64()),
+        )
+    }
+}
+
+/// A fixed-timestep integrator behind the same [`Simulator`] interface as the event engine.
+///
+/// Each step applies the [`ExternalForce`]s, advances every ball through the cloth model for
+/// `time_step`, then resolves whatever the step ran into: pocket jaws first, then pocket captures,
+/// then overlapping ball pairs that are still closing, then cushion planes the ball has crossed.
+/// Contacts land up to one step late, so results converge on the event engine's as the step
+/// shrinks. The event log records contacts, captures, and balls coming to rest, but no other
+/// motion transitions.
+pub struct FixedStepSimulator {
+    pub config: SimulationConfig,
+    pub time_step: Seconds,
+    /// Stop here even if balls are still moving, as they may on a tilted table.
+    pub max_duration: Seconds,
+    pub forces: Vec<Box<dyn ExternalForce>>,
+}
+
+impl FixedStepSimulator {
+    /// The default step is one millisecond, run for at most a minute.
+    pub fn new(config: SimulationConfig) -> Self {
+        Self {
+            config,
+            time_step: Seconds::new(0.001),
+            max_duration: Seconds::new(60.0),
+            forces: Vec::new(),
+        }
+    }
+
+    pub fn with_time_step(mut self, time_step: Seconds) -> Self {
+        assert!(time_step.as_f64() > 0.0, "time step must be positive");
+        self.time_step = time_step;
+        self
+    }
+
+    pub fn with_max_duration(mut self, max_duration: Seconds) -> Self {
+        self.max_duration = max_duration;
+        self
+    }
+
+    pub fn with_force<F: ExternalForce + 'static>(mut self, force: F) -> Self {
+        self.forces.push(Box::new(force));
+        self
+    }
+
+    fn phase(&self, state: &OnTableBallState) -> MotionPhase {
+        classify_motion_phase(
+            state.as_ball_state(),
+            &self.config.ball_set,
+            &self.config.motion.phase,
+        )
+    }
+
+    fn is_at_rest(&self, state: &OnTableBallState) -> bool {
+        self.phase(state) == MotionPhase::Rest
+    }
+}
+
+impl Default for FixedStepSimulator {
+    fn default() -> Self {
+        Self::new(SimulationConfig::default())
+    }
+}
+
+impl Simulator for FixedStepSimulator {
+    fn simulate(
+        &self,
+        state: &GameState,
+        shot: &Shot,
+    ) -> Result<SimulationResult, SimulationError> {
+        let config = &self.config;
+        let table = &state.table_spec;
+        let radius = config.ball_set.radius.as_f64();
+        let dt = self.time_step.as_f64();
+        let mut states = initial_system_states(state, shot, config)?;
+        let strike_states = states.clone();
+        let mut events = Vec::new();
+        let mut elapsed = 0.0;
+        let mut last_event = 0.0;
+        let event_limit_reached = |events: &Vec<NBallSystemEvent>| {
+            config.max_events.is_some_and(|limit| events.len() >= limit)
+        };
+
+        let mut snapshots = Vec::new();
+        let mut trajectories: Vec<Vec<Position>> = states
+            .iter()
+            .map(|state| vec![system_state_position(state, table)])
+            .collect();
+        let mut last_sample = 0.0;
+
+        while elapsed < self.max_duration.as_f64() && !event_limit_reached(&events) {
+            let events_before_step = events.len();
+            let phases_before: Vec<Option<MotionPhase>> = states
+                .iter()
+                .map(|state| state.as_on_table().map(|state| self.phase(state)))
+                .collect();
+            for state in states.iter_mut() {
+                let NBallSystemState::OnTable(on_table) = state else {
+                    continue;
+                };
+                let pushed = self.apply_forces(on_table, table);
+                let advanced = advance_motion_on_table(
+                    &pushed,
+                    self.time_step,
+                    &config.ball_set,
+                    &config.motion,
+                )
+                .state;
+                *on_table = OnTableBallState::try_from(advanced)
+                    .expect("advancing an on-table ball should keep it on the table");
+            }
+            elapsed += dt;
+
+            let mut since_last_event = || {
+                let time = Seconds::new(elapsed - last_event);
+                last_event = elapsed;
+                time
+            };
+
+            for (ball_index, state) in states.iter_mut().enumerate() {
+                let NBallSystemState::OnTable(on_table) = state else {
+                    continue;
+                };
+                let Some((mut impact, state_after)) = Pocket::ALL.into_iter().find_map(|pocket| {
+                    resolve_stepped_ball_jaw_contact_on_table(
+                        on_table,
+                        pocket,
+                        &config.ball_set,
+                        table,
+                        &config.motion,
+                        config.rail_model,
+                    )
+                }) else {
+                    continue;
+                };
+                impact.time_until_impact = since_last_event();
+                events.push(NBallSystemEvent::BallJawImpact { ball_index, impact });
+                *state = state_after;
+            }
+
+            for (ball_index, state) in states.iter_mut().enumerate() {
+                let NBallSystemState::OnTable(on_table) = state else {
+                    continue;
+                };
+                let Some(pocket) = Pocket::ALL.into_iter().find(|pocket| {
+                    ball_is_captured_by_pocket_on_table(
+                        on_table,
+                        *pocket,
+                        &config.ball_set,
+                        table,
+                        &config.motion,
+                    )
+                }) else {
+                    continue;
+                };
+                events.push(NBallSystemEvent::BallPocketCapture {
+                    ball_index,
+                    capture: PredictedBallPocketCapture {
+                        pocket,
+                        time_until_capture: since_last_event(),
+                        state_at_capture: on_table.clone(),
+                    },
+                });
+                *state = NBallSystemState::Pocketed {
+                    pocket,
+                    state_at_capture: on_table.clone(),
+                };
+            }
+
+            for first in 0..states.len() {
+                for second in first + 1..states.len() {
+                    let (Some(a), Some(b)) =
+                        (states[first].as_on_table(), states[second].as_on_table())
+                    else {
+                        continue;
+                    };
+                    if !balls_are_closing_in_contact(a, b, radius) {
+                        continue;
+                    }
+                    let (a_after, b_after) = collide_ball_ball_on_table_with_radius_and_config(
+                        a,
+                        b,
+                        config.ball_set.radius.clone(),
+                        config.collision_model,
+                        &config.collision_config,
+                    );
+                    events.push(NBallSystemEvent::BallBallCollision {
+                        first_ball_index: first,
+                        second_ball_index: second,
+                        collision: PredictedBallBallCollision {
+                            time_until_impact: since_last_event(),
+                            a_at_impact: a.clone(),
+                            b_at_impact: b.clone(),
+                        },
+                    });
+                    states[first] = a_after.into();
+                    states[second] = b_after.into();
+                }
+            }
+
+            for (ball_index, state) in states.iter_mut().enumerate() {
+                let NBallSystemState::OnTable(on_table) = state else {
+                    continue;
+                };
+                for rail in [Rail::Top, Rail::Right, Rail::Bottom, Rail::Left] {
+                    let Some((depth, closing_speed)) =
+                        rail_penetration(on_table, rail, radius, table)
+                    else {
+                        continue;
+                    };
+                    if closing_speed < RESTING_CONTACT_SPEED {
+                        *on_table = settled_against_rail(on_table, rail, depth);
+                        continue;
+                    }
+                    let rebounded = collide_ball_rail_on_table_with_radius_and_profile(
+                        on_table,
+                        rail,
+                        config.ball_set.radius.clone(),
+                        config.rail_model,
+                        &table.cushions,
+                    );
+                    events.push(NBallSystemEvent::BallRailImpact {
+                        ball_index,
+                        impact: PredictedBallRailImpact {
+                            rail,
+                            time_until_impact: since_last_event(),
+                            state_at_impact: on_table.clone(),
+                        },
+                    });
+                    *on_table = reflected_off_rail(&rebounded, rail, depth);
+                }
+            }
+
+            for (ball_index, (state, phase_before)) in states.iter().zip(&phases_before).enumerate()
+            {
+                let (Some(on_table), Some(phase_before)) = (state.as_on_table(), phase_before)
+                else {
+                    continue;
+                };
+                if *phase_before != MotionPhase::Rest && self.is_at_rest(on_table) {
+                    events.push(NBallSystemEvent::MotionTransition {
+                        ball_index,
+                        transition: NextTransition {
+                            phase_before: phase_before.clone(),
+                            phase_after: MotionPhase::Rest,
+                            time_until_transition: since_last_event(),
+                        },
+                    });
+                }
+            }
+            snapshots.resize(events.len(), states.clone());
+
+            if events.len() > events_before_step
+                || elapsed - last_sample >= TRAJECTORY_SAMPLE_SECONDS
+            {
+                last_sample = elapsed;
+                record_positions(&mut trajectories, &states, table);
+            }
+
+            // Checked after the forces have had a step to get a resting ball going.
+            let moving = states.iter().any(|state| {
+                state
+                    .as_on_table()
+                    .is_some_and(|state| !self.is_at_rest(state))
+            });
+            if !moving {
+                break;
+            }
+        }
+
+        record_positions(&mut trajectories, &states, table);
+        let system = NBallSystemSimulation {
+            states,
+            elapsed: Seconds::new(elapsed),
+            events,
+        };
+        Ok(result_from_system(
+            state,
+            system,
+            strike_states,
+            snapshots,
+            trajectories,
+            config,
+        ))
+    }
+}
+
+impl FixedStepSimulator {
+    fn apply_forces(&self, state: &OnTableBallState, table: &TableSpec) -> OnTableBallState {
+        if self.forces.is_empty() {
+            return state.clone();
+        }
+
+        let ball = state.as_ball_state();
+        let (mut vx, mut vy) = (ball.velocity.x().as_f64(), ball.velocity.y().as_f64());
+        for force in &self.forces {
+            let change = force.velocity_change(state, self.time_step, table);
+            vx += change.x().as_f64();
+            vy += change.y().as_f64();
+        }
+        build_on_table_ball_state(
+            ball.position.clone(),
+            Velocity2::new(Inches::from_f64(vx), Inches::from_f64(vy)),
+            ball.angular_velocity.clone(),
+        )
+    }
+}
+
+/// Whether two balls overlap and are still moving toward each other.
+fn balls_are_closing_in_contact(a: &OnTableBallState, b: &OnTableBallState, radius: f64) -> bool {
+    let (a, b) = (a.as_ball_state(), b.as_ball_state());
+    let dx = b.position.x().as_f64() - a.position.x().as_f64();
+    let dy = b.position.y().as_f64() - a.position.y().as_f64();
+    let dvx = b.velocity.x().as_f64() - a.velocity.x().as_f64();
+    let dvy = b.velocity.y().as_f64() - a.velocity.y().as_f64();
+    dx.hypot(dy) < 2.0 * radius && dx * dvx + dy * dvy < 0.0
+}
+
+/// Cushion contacts closing slower than this, in inches per second, are a ball leaning on the
+/// cushion under an external force rather than an impact.
+const RESTING_CONTACT_SPEED: f64 = 1.0;
+
+/// How far past `rail`'s contact plane a ball moving into it has gone, if it has, and how fast it is
+/// closing on the cushion.
+fn rail_penetration(
+    state: &OnTableBallState,
+    rail: Rail,
+    radius: f64,
+    table: &TableSpec,
+) -> Option<(f64, f64)> {
+    let state = state.as_ball_state();
+    let (x, y) = (state.position.x().as_f64(), state.position.y().as_f64());
+    let (vx, vy) = (state.velocity.x().as_f64(), state.velocity.y().as_f64());
+    let width = table.diamond_to_inches(Diamond::four()).as_f64();
+    let length = table.diamond_to_inches(Diamond::eight()).as_f64();
+    let (depth, closing_speed) = match rail {
+        Rail::Top => (y - (length - radius), vy),
+        Rail::Bottom => (radius - y, -vy),
+        Rail::Right => (x - (width - radius), vx),
+        Rail::Left => (radius - x, -vx),
+    };
+    (depth >= 0.0 && closing_speed > 0.0).then_some((depth, closing_speed))
+}
+
+/// Put a ball leaning on `rail` back on the contact plane with no speed into the cushion.
+fn settled_against_rail(state: &OnTableBallState, rail: Rail, depth: f64) -> OnTableBallState {
+    let state = state.as_ball_state();
+    let (x, y) = (state.position.x().as_f64(), state.position.y().as_f64());
+    let (vx, vy) = (state.velocity.x().as_f64(), state.velocity.y().as_f64());
+    let (x, y, vx, vy) = match rail {
+        Rail::Top => (x, y - depth, vx, 0.0),
+        Rail::Bottom => (x, y + depth, vx, 0.0),
+        Rail::Right => (x - depth, y, 0.0, vy),
+        Rail::Left => (x + depth, y, 0.0, vy),
+    };
+    let position = Inches2::new(Inches::from_f64(x), Inches::from_f64(y));
+    // With nothing left along the cushion either, the cushion's grip stops the ball outright.
+    if vx.hypot(vy) < RESTING_CONTACT_SPEED {
+        return build_on_table_ball_state(position, Velocity2::zero(), AngularVelocity3::zero());
+    }
+    build_on_table_ball_state(
+        position,
+        Velocity2::new(Inches::from_f64(vx), Inches::from_f64(vy)),
+        state.angular_velocity.clone(),
+    )
+}
+
+/// Move a rebounded ball back out of the cushion by the distance it had gone in.
+fn reflected_off_rail(state: &OnTableBallState, rail: Rail, depth: f64) -> OnTableBallState {
+    let state = state.as_ball_state();
+    let (x, y) = (state.position.x().as_f64(), state.position.y().as_f64());
+    let (x, y) = match rail {
+        Rail::Top => (x, y - 2.0 * depth),
+        Rail::Bottom => (x, y + 2.0 * depth),
+        Rail::Right => (x - 2.0 * depth, y),
+        Rail::Left => (x + 2.0 * depth, y),
+    };
+    build_on_table_ball_state(
+        Inches2::new(Inches::from_f64(x), Inches::from_f64(y)),
+        state.velocity.clone(),
+        state.angular_velocity.clone(),
+    )
+}
+
+/// Strike the cue ball in `state` with `shot` and run the table until every ball is at rest or
+/// pocketed, using [`SimulationConfig::default`].
+pub fn simulate(state: &GameState, shot: &Shot) -> Result<SimulationResult, SimulationError> {
+    simulate_with_config(state, shot, &SimulationConfig::default())
+}
+
+/// Like [`simulate`], with explicit physics.
+pub fn simulate_with_config(
+    state: &GameState,
+    shot: &Shot,
+    config: &SimulationConfig,
+) -> Result<SimulationResult, SimulationError> {
+    if let TimeSolver::Decimal { decimal_places } = config.time_solver {
+        if decimal_places < MIN_DECIMAL_PLACES {
+            return Err(SimulationError::TooFewDecimalPlaces(decimal_places));
+        }
+    }
+    let initial = initial_system_states(state, shot, config)?;
+    let system = match config.time_solver {
+        TimeSolver::Float => {
+            simulate_n_ball_system_with_physics_and_pockets_on_table_until_event_limit(
+                &initial,
+                &config.ball_set,
+                &state.table_spec,
+                &config.motion,
+                config.collision_model,
+                &config.collision_config,
+                config.rail_model,
+                &state.table_spec.cushions,
+                config.max_events,
+            )
+        }
+        TimeSolver::Decimal { decimal_places } => {
+            simulate_n_ball_system_with_physics_and_pockets_on_table_in_decimal(
+                &initial,
+                &config.ball_set,
+                &state.table_spec,
+                &config.motion,
+                config.collision_model,
+                &config.collision_config,
+                config.rail_model,
+                &state.table_spec.cushions,
+                config.max_events,
+                decimal_places,
+            )
+        }
+    };
+    let initial = match config.time_solver {
+        TimeSolver::Float => initial,
+        TimeSolver::Decimal { decimal_places } => {
+            round_n_ball_system_states(&initial, decimal_places)
+        }
+    };
+    let snapshots = replayed_snapshots(&initial, &system, &state.table_spec, config);
+    let trajectories =
+        replayed_trajectories(&initial, &system, &snapshots, &state.table_spec, config);
+    Ok(result_from_system(
+        state,
+        system,
+        initial,
+        snapshots,
+        trajectories,
+        config,
+    ))
+}
+
+/// Every ball's state just after each of `system`'s events, found by resolving them again in order
+/// from `initial`. Resolution is deterministic, so this retraces the run exactly, rounding as the
+/// decimal engine does when `config` asks for it.
+fn replayed_snapshots(
+    initial: &[NBallSystemState],
+    system: &NBallSystemSimulation,
+    table_spec: &TableSpec,
+    config: &SimulationConfig,
+) -> Vec<Vec<NBallSystemState>> {
+    let mut states = initial.to_vec();
+    system
+        .events
+        .iter()
+        .map(|event| {
+            states = resolve_n_ball_system_event_with_physics_and_pockets_on_table(
+                &states,
+                event,
+                &config.ball_set,
+                table_spec,
+                &config.motion,
+                config.collision_model,
+                &config.collision_config,
+                config.rail_model,
+                &table_spec.cushions,
+            );
+            if let TimeSolver::Decimal { decimal_places } = config.time_solver {
+                states = round_n_ball_system_states(&states, decimal_places);
+            }
+            states.clone()
+        })
+        .collect()
+}
+
+/// How often a trajectory is sampled while a ball may be curving, in seconds.
+///
+/// The event engine only needs the extra points while a ball slides; a rolling ball goes straight
+/// from one event to the next. The fixed-step integrator samples every moving ball this often.
+const TRAJECTORY_SAMPLE_SECONDS: f64 = 0.01;
+
+/// Every ball's route through `system`, from `initial` through each event's `snapshots`.
+///
+/// Between events each ball moves on its own, so the states after one event, advanced by the time
+/// to the next, fill in the curve of any ball that was sliding.
+fn replayed_trajectories(
+    initial: &[NBallSystemState],
+    system: &NBallSystemSimulation,
+    snapshots: &[Vec<NBallSystemState>],
+    table_spec: &TableSpec,
+    config: &SimulationConfig,
+) -> Vec<Vec<Position>> {
+    let mut trajectories: Vec<Vec<Position>> = initial
+        .iter()
+        .map(|state| vec![system_state_position(state, table_spec)])
+        .collect();
+
+    let mut before = initial;
+    for (event, after) in system.events.iter().zip(snapshots) {
+        let interval = event.time().as_f64();
+        for (path, state) in trajectories.iter_mut().zip(before) {
+            let NBallSystemState::OnTable(on_table) = state else {
+                continue;
+            };
+            let phase = classify_motion_phase(
+                on_table.as_ball_state(),
+                &config.ball_set,
+                &config.motion.phase,
+            );
+            if phase != MotionPhase::Sliding {
+                continue;
+            }
+            let steps = (interval / TRAJECTORY_SAMPLE_SECONDS).ceil() as usize;
+            for step in 1..steps {
+                let advanced = advance_motion_on_table(
+                    on_table,
+                    Seconds::new(interval * step as f64 / steps as f64),
+                    &config.ball_set,
+                    &config.motion,
+                );
+                push_distinct(path, advanced.state.projected_position(table_spec));
+            }
+        }
+        record_positions(&mut trajectories, after, table_spec);
+        before = after;
+    }
+    trajectories
+}
+
+/// Add each ball's position in `states` to the end of its trajectory, unless it has not moved.
+fn record_positions(
+    trajectories: &mut [Vec<Position>],
+    states: &[NBallSystemState],
+    table_spec: &TableSpec,
+) {
+    for (path, state) in trajectories.iter_mut().zip(states) {
+        push_distinct(path, system_state_position(state, table_spec));
+    }
+}
+
+fn push_distinct(path: &mut Vec<Position>, position: Position) {
+    if path.last() != Some(&position) {
+        path.push(position);
+    }
+}
+
+/// Where a ball is on the table, or where it was when it dropped.
+fn system_state_position(state: &NBallSystemState, table_spec: &TableSpec) -> Position {
+    let on_table = match state {
+        NBallSystemState::OnTable(on_table) => on_table,
+        NBallSystemState::Pocketed {
+            state_at_capture, ..
+        } => state_at_capture,
+    };
+    on_table.as_ball_state().projected_position(table_spec)
+}
+
+/// How far [`simulate_break`] opens the rack, in inches.
+///
+/// Neighbouring balls are first set this far apart, then each is nudged up to half as far in a
+/// random direction, so any two neighbours end up between frozen and twice this apart.
+pub const RACK_GAP_INCHES: f64 = 0.02;
+
+/// How far [`BreakShot::eight_ball_second_ball_break`] passes outside a frozen apex ball, in inches.
+const SECOND_BALL_BREAK_CLEARANCE_INCHES: f64 = 0.1;
+
+/// Where the cue ball sits for a break and how it is struck, with the placement checked against
+/// the kitchen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakShot {
+    cue_ball: Position,
+    shot: Shot,
+}
+
+impl BreakShot {
+    /// Break with `shot` from `cue_ball`, which must be [in the kitchen](Position::is_in_kitchen)
+    /// of `table_spec`.
+    pub fn new(
+        cue_ball: Position,
+        shot: Shot,
+        table_spec: &TableSpec,
+    ) -> Result<Self, SimulationError> {
+        if !cue_ball.is_in_kitchen(table_spec) {
+            return Err(SimulationError::CueBallOutsideKitchen(cue_ball));
+        }
+        Ok(Self { cue_ball, shot })
+    }
+
+    /// Center ball from the head spot, straight into the apex.
+    pub fn head_spot(speed: InchesPerSecond) -> Result<Self, SimulationError> {
+        Self::template(HEAD_SPOT.clone(), RACK_SPOT.clone(), speed)
+    }
+
+    /// The nine-ball cut break: from the head string half a diamond off the right side rail, a
+    /// center-ball three-quarter hit on the right side of the one, which sends the one off to
+    /// the left instead of back up the table.
+    pub fn nine_ball_cut_break(speed: InchesPerSecond) -> Result<Self, SimulationError> {
+        let mut aim = RACK_SPOT.clone();
+        aim.shift_horizontally_inches(Inches::from_f64(0.5 * TYPICAL_BALL_RADIUS.as_f64()));
+        aim.resolve_shifts(&TableSpec::default());
+        Self::template(Position::new("3.5", "6"), aim, speed)
+    }
+
+    /// The eight-ball second-ball break: from the head string a diamond off the right side rail,
+    /// center ball just past the right side of the apex ball and into the second-row ball
+    /// behind it.
+    pub fn eight_ball_second_ball_break(speed: InchesPerSecond) -> Result<Self, SimulationError> {
+        let table_spec = TableSpec::default();
+        let cue_ball = Position::new("3", "6");
+        let (cue_x, cue_y) = position_xy_inches(&cue_ball, &table_spec);
+        let (apex_x, apex_y) = position_xy_inches(&RACK_SPOT, &table_spec);
+        let (dx, dy) = (apex_x - cue_x, apex_y - cue_y);
+        let length = dx.hypot(dy);
+        // Aim just over a ball's width to the right of the apex, square to the line of the shot.
+        let clearance = 2.0 * TYPICAL_BALL_RADIUS.as_f64() + SECOND_BALL_BREAK_CLEARANCE_INCHES;
+        let aim = position_from_xy_inches(
+            apex_x - dy / length * clearance,
+            apex_y + dx / length * clearance,
+            &table_spec,
+        );
+        Self::template(cue_ball, aim, speed)
+    }
+
+    fn template(
+        cue_ball: Position,
+        aim: Position,
+        speed: InchesPerSecond,
+    ) -> Result<Self, SimulationError> {
+        let shot = Shot::toward(&cue_ball, &aim, speed, CueTipContact::center())
+            .map_err(SimulationError::InvalidShot)?;
+        Self::new(cue_ball, shot, &TableSpec::default())
+    }
+
+    pub fn cue_ball(&self) -> &Position {
+        &self.cue_ball
+    }
+
+    pub fn shot(&self) -> &Shot {
+        &self.shot
+    }
+}
+
+/// A simulated break: the rack as struck, the physics run, and what it made.
+pub struct BreakResult {
+    /// The loosened rack and the cue ball where the break placed it, before the shot.
+    pub rack: GameState,
+    /// The run itself; its `final_state` is the table the break left.
+    pub simulation: SimulationResult,
+    pub stats: BreakStats,
+}
+
+/// The headline numbers from a break.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakStats {
+    /// Object balls that dropped, in the order they were captured.
+    pub object_balls_pocketed: Vec<(BallType, Pocket)>,
+    /// Where the cue ball came to rest, or `None` if it scratched.
+    pub cue_ball: Option<Position>,
+    /// At least one object ball dropped.
+    pub made_ball: bool,
+    /// The ball that wins the game on the break, such as the nine in nine-ball, dropped.
+    pub made_game_ball: bool,
+    pub scratched: bool,
+}
+
+/// Rack `game` in its [standard rack](RackTemplate::for_game), open slots filled lowest number
+/// first, loosen the rack by a few hundredths of an inch chosen from `seed`, and play
+/// `break_shot`, using [`SimulationConfig::default`].
+///
+/// The same seed always racks the same way, so a break can be replayed exactly.
+pub fn simulate_break(
+    game: GameType,
+    break_shot: &BreakShot,
+    seed: u64,
+) -> Result<BreakResult, SimulationError> {
+    simulate_break_with_config(game, break_shot, seed, &SimulationConfig::default())
+}
+
+/// Like [`simulate_break`], with explicit physics.
+pub fn simulate_break_with_config(
+    game: GameType,
+    break_shot: &BreakShot,
+    seed: u64,
+    config: &SimulationConfig,
+) -> Result<BreakResult, SimulationError> {
+    let table_spec = TableSpec::default();
+    let rack = RackTemplate::for_game(&game)
+        .ok_or(SimulationError::UnsupportedRack(game.clone()))?
+        .with_shuffle(false)
+        .rack(seed)
+        .expect("the standard racks can always be racked");
+    let mut balls = loosened_rack(rack, &table_spec, &mut SplitMix64::new(seed));
+    balls.push(Ball {
+        ty: BallType::Cue,
+        position: break_shot.cue_ball.clone(),
+        spec: BallSpec::default(),
+    });
+    let mut rack = GameState::with_balls(table_spec, balls);
+    rack.ty = game.clone();
+
+    let simulation = simulate_with_config(&rack, &break_shot.shot, config)?;
+    let object_balls_pocketed: Vec<(BallType, Pocket)> = simulation
+        .pocketed
+        .iter()
+        .filter(|(ty, _)| *ty != BallType::Cue)
+        .cloned()
+        .collect();
+    let game_ball = match game {
+        GameType::NineBall => Some(BallType::Nine),
+        GameType::TenBall => Some(BallType::Ten),
+        GameType::EightBall => Some(BallType::Eight),
+        GameType::OnePocket | GameType::Banks => None,
+    };
+    let stats = BreakStats {
+        made_ball: !object_balls_pocketed.is_empty(),
+        made_game_ball: game_ball
+            .is_some_and(|game_ball| object_balls_pocketed.iter().any(|(ty, _)| *ty == game_ball)),
+        object_balls_pocketed,
+        cue_ball: simulation
+            .final_state
+            .select_ball(BallType::Cue)
+            .map(|ball| ball.position.clone()),
+        scratched: simulation.scratched(),
+    };
+    Ok(BreakResult {
+        rack,
+        simulation,
+        stats,
+    })
+}
+
+/// One shot of a [`simulate_sequence`] run.
+pub struct SequenceShot {
+    pub shot: Shot,
+    /// The shot played from the table the previous shot left.
+    pub result: SimulationResult,
+    pub foul: Option<Foul>,
+    /// Balls pocketed on the foul that the rules put back on the foot spot.
+    pub spotted: Vec<BallType>,
+}
+
+/// A chain of shots, each played from the table the one before it left.
+pub struct SequenceResult {
+    pub shots: Vec<SequenceShot>,
+    /// The table after the last shot played, with any spotted balls back up. After a foul its
+    /// [`GameState::cueball_modifier`] is ball in hand.
+    pub final_state: GameState,
+    /// How many of the requested shots were never played because a foul ended the turn.
+    pub unplayed: usize,
+}
+
+impl SequenceResult {
+    /// Balls that dropped and stayed down, in the order they dropped.
+    pub fn pocketed(&self) -> Vec<(BallType, Pocket)> {
+        self.shots
+            .iter()
+            .flat_map(|shot| {
+                shot.result
+                    .pocketed
+                    .iter()
+                    .filter(|(ty, _)| !shot.spotted.contains(ty))
+                    .cloned()
+            })
+            .collect()
+    }
+
+    /// The foul that ended the turn, if one did.
+    pub fn foul(&self) -> Option<&Foul> {
+        self.shots.last().and_then(|shot| shot.foul.as_ref())
+    }
+}
+
+/// Play `shots` one after another from `state` under the rules for `state.ty`, using
+/// [`SimulationConfig::default`].
+pub fn simulate_sequence(
+    state: &GameState,
+    shots: &[Shot],
+) -> Result<SequenceResult, SimulationError> {
+    simulate_sequence_with(
+        &EventDrivenSimulator::default(),
+        rules_for(&state.ty).as_ref(),
+        state,
+        shots,
+    )
+}
+
+/// Like [`simulate_sequence`], with an explicit backend and rules.
+///
+/// Each shot starts from the table the previous one left. Pocketed balls stay down unless `rules`
+/// spots them after a foul. A foul from [`GameRules::foul`] ends the turn, so the shots after it
+/// are not played; a legal shot that pots nothing does not.
+pub fn simulate_sequence_with(
+    simulator: &dyn Simulator,
+    rules: &dyn GameRules,
+    state: &GameState,
+    shots: &[Shot],
+) -> Result<SequenceResult, SimulationError> {
+    let mut table = state.clone();
+    let mut played = Vec::new();
+
+    for shot in shots {
+        let result = simulator.simulate(&table, shot)?;
+        let foul = rules.foul(&table, &result);
+        let mut next = result.final_state.clone();
+        next.cueball_modifier = CueballModifier::AsItLays;
+        let mut spotted = Vec::new();
+        if foul.is_some() {
+            for (ty, _) in &result.pocketed {
+                if !rules.is_spotted_after_foul(&table, ty) {
+                    continue;
+                }
+                let Some(ball) = table.balls().iter().find(|ball| ball.ty == *ty) else {
+                    continue;
+                };
+                let position = foot_spot_placement(&next);
+                next.add_ball(Ball {
+                    position,
+                    ..ball.clone()
+                });
+                spotted.push(ty.clone());
+            }
+            next.cueball_modifier = CueballModifier::BallInHand;
+        }
+
+        table = next;
+        let fouled = foul.is_some();
+        played.push(SequenceShot {
+            shot: shot.clone(),
+            result,
+            foul,
+            spotted,
+        });
+        if fouled {
+            break;
+        }
+    }
+
+    Ok(SequenceResult {
+        unplayed: shots.len() - played.len(),
+        shots: played,
+        final_state: table,
+    })
+}
+
+/// Where a spotted ball goes: the foot spot, or the nearest free point behind it toward the foot
+/// rail, or failing that in front of it.
+fn foot_spot_placement(state: &GameState) -> Position {
+    let table_spec = &state.table_spec;
+    let radius = TYPICAL_BALL_RADIUS.as_f64();
+    let (x, spot) = position_xy_inches(&RACK_SPOT, table_spec);
+    let length = table_spec.diamond_to_inches(Diamond::eight()).as_f64();
+    let occupied: Vec<(f64, f64)> = state
+        .balls()
+        .iter()
+        .map(|ball| position_xy_inches(&ball.position, table_spec))
+        .collect();
+    let is_free = |y: f64| {
+        occupied
+            .iter()
+            .all(|(bx, by)| (bx - x).hypot(by - y) >= 2.0 * radius)
+    };
+
+    let step = 0.05;
+    let behind = (0..)
+        .map(|n| spot - n as f64 * step)
+        .take_while(|y| *y >= radius);
+    let in_front = (1..)
+        .map(|n| spot + n as f64 * step)
+        .take_while(|y| *y <= length - radius);
+    let y = behind.chain(in_front).find(|y| is_free(*y)).unwrap_or(spot);
+    position_from_xy_inches(x, y, table_spec)
+}
+
+/// How accurately a player delivers a shot.
+///
+/// Each error is the standard deviation of a normal distribution centred on the intended shot, so
+/// about two strokes in three land within one of it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SkillModel {
+    /// Spread of the cue's heading about the intended heading, in degrees.
+    pub aim_error_degrees: f64,
+    /// Spread of the cue speed, as a fraction of the intended speed.
+    pub speed_error: f64,
+}
+
+impl SkillModel {
+    pub fn new(aim_error_degrees: f64, speed_error: f64) -> Self {
+        assert!(
+            aim_error_degrees >= 0.0 && speed_error >= 0.0,
+            "skill errors must be non-negative"
+        );
+        Self {
+            aim_error_degrees,
+            speed_error,
+        }
+    }
+
+    /// A player who always delivers the intended shot.
+    pub fn perfect() -> Self {
+        Self::new(0.0, 0.0)
+    }
+
+    /// The intended shot with aim and speed errors drawn from `rng`. Tip contact and elevation are
+    /// kept, and a speed error can slow the cue to a stop but never reverse it.
+    fn perturbed(&self, shot: &Shot, rng: &mut SplitMix64) -> Shot {
+        let heading = angle_from_degrees(
+            shot.heading().as_degrees() + self.aim_error_degrees * rng.next_standard_normal(),
+        );
+        let speed_scale = (1.0 + self.speed_error * rng.next_standard_normal()).max(0.0);
+        Shot::new(
+            heading,
+            InchesPerSecond::new(Inches::from_f64(shot.cue_speed().as_f64() * speed_scale)),
+            shot.tip_contact().clone(),
+        )
+        .and_then(|perturbed| perturbed.with_cue_elevation_degrees(shot.cue_elevation_degrees()))
+        .expect("perturbing a valid shot keeps it valid")
+    }
+}
+
+impl Default for SkillModel {
+    /// A steady club player: half a degree of aim error and 5% speed error. These are rough
+    /// figures, not measurements.
+    fn default() -> Self {
+        Self::new(0.5, 0.05)
+    }
+}
+
+/// How often a shot went in over a [`estimate_pot_probability`] run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PotEstimate {
+    pub samples: usize,
+    /// Strokes that dropped the object ball in the called pocket, whatever else happened.
+    pub made: usize,
+    pub scratched: usize,
+}
+
+impl PotEstimate {
+    /// The fraction of strokes that made the ball, from 0 to 1.
+    pub fn make_rate(&self) -> f64 {
+        self.rate(self.made)
+    }
+
+    /// The fraction of strokes that scratched, from 0 to 1.
+    pub fn scratch_rate(&self) -> f64 {
+        self.rate(self.scratched)
+    }
+
+    fn rate(&self, count: usize) -> f64 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            count as f64 / self.samples as f64
+        }
+    }
+}
+
+/// Shoot `shot` `samples` times through `simulator`, each stroke perturbed by `skill`, and count how
+/// often `object_ball` drops in `pocket` and how often the cue ball scratches.
+///
+/// Where [`crate::aiming::shot_difficulty`] ranks shots by geometry alone, this gives the chance
+/// that a player of a given skill makes one. The same `seed` draws the same strokes.
+pub fn estimate_pot_probability(
+    simulator: &dyn Simulator,
+    state: &GameState,
+    shot: &Shot,
+    object_ball: BallType,
+    pocket: Pocket,
+    skill: &SkillModel,
+    samples: usize,
+    seed: u64,
+) -> Result<PotEstimate, SimulationError> {
+    let mut rng = SplitMix64::new(seed);
+    let mut estimate = PotEstimate {
+        samples,
+        made: 0,
+        scratched: 0,
+    };
+    for _ in 0..samples {
+        let result = simulator.simulate(state, &skill.perturbed(shot, &mut rng))?;
+        if result.pocketed.contains(&(object_ball.clone(), pocket)) {
+            estimate.made += 1;
+        }
+        if result.scratched() {
+            estimate.scratched += 1;
+        }
+    }
+    Ok(estimate)
+}
+
+/// How many strokes [`predict_cue_ball_rest`] samples.
+pub const DEFAULT_REST_SAMPLES: usize = 32;
+
+/// Where a shot leaves the cue ball, and how far that moves under a [`SkillModel`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CueBallRestPrediction {
+    /// Where the intended stroke leaves the cue ball, or `None` if it scratches.
+    pub rest: Option<Position>,
+    /// The spread of the sampled strokes' rest positions, or `None` if fewer than two of them kept
+    /// the cue ball on the table.
+    pub spread: Option<UncertaintyEllipse>,
+    pub samples: usize,
+    /// Sampled strokes that scratched, which the spread leaves out.
+    pub scratched: usize,
+}
+
+/// A one-standard-deviation ellipse around a cloud of table positions.
+///
+/// About two in five points fall inside it; doubling both axes takes in about 86%.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UncertaintyEllipse {
+    /// The mean position.
+    pub center: Position,
+    /// The standard deviation along the long axis.
+    pub semi_major: Inches,
+    /// The standard deviation across it.
+    pub semi_minor: Inches,
+    /// The heading of the long axis, in `[0, 180)`.
+    pub major_axis: Angle,
+}
+
+impl UncertaintyEllipse {
+    /// Fit an ellipse to the sample covariance of points given in inches. Needs at least two.
+    fn from_points(points: &[(f64, f64)], table_spec: &TableSpec) -> Option<Self> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        let count = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+        let (mut xx, mut xy, mut yy) = (0.0, 0.0, 0.0);
+        for (x, y) in points {
+            let (dx, dy) = (x - mean_x, y - mean_y);
+            xx += dx * dx;
+            xy += dx * dy;
+            yy += dy * dy;
+        }
+        let (xx, xy, yy) = (xx / (count - 1.0), xy / (count - 1.0), yy / (count - 1.0));
+
+        let mid = 0.5 * (xx + yy);
+        let half_gap = (0.5 * (xx - yy)).hypot(xy);
+        // The long axis, measured counterclockwise from +x.
+        let theta = 0.5 * (2.0 * xy).atan2(xx - yy);
+        Some(Self {
+            center: position_from_xy_inches(mean_x, mean_y, table_spec),
+            semi_major: Inches::from_f64((mid + half_gap).sqrt()),
+            semi_minor: Inches::from_f64((mid - half_gap).max(0.0).sqrt()),
+            major_axis: angle_from_degrees(
+                Angle::from_north(theta.cos(), theta.sin()).as_degrees() % 180.0,
+            ),
+        })
+    }
+}
+
+/// Predict where `shot` leaves the cue ball, with the spread a [`SkillModel::default`] player
+/// would see over [`DEFAULT_REST_SAMPLES`] strokes on the event engine.
+pub fn predict_cue_ball_rest(
+    state: &GameState,
+    shot: &Shot,
+) -> Result<CueBallRestPrediction, SimulationError> {
+    predict_cue_ball_rest_with_skill(
+        &EventDrivenSimulator::default(),
+        state,
+        shot,
+        &SkillModel::default(),
+        DEFAULT_REST_SAMPLES,
+        0,
+    )
+}
+
+/// Like [`predict_cue_ball_rest`], with an explicit simulator, player, and sampling. The same
+/// `seed` draws the same strokes.
+pub fn predict_cue_ball_rest_with_skill(
+    simulator: &dyn Simulator,
+    state: &GameState,
+    shot: &Shot,
+    skill: &SkillModel,
+    samples: usize,
+    seed: u64,
+) -> Result<CueBallRestPrediction, SimulationError> {
+    let cue_ball_rest = |result: &SimulationResult| {
+        result
+            .final_state
+            .select_ball(BallType::Cue)
+            .map(|ball| ball.position.clone())
+    };
+
+    let rest = cue_ball_rest(&simulator.simulate(state, shot)?);
+    let mut rng = SplitMix64::new(seed);
+    let mut points = Vec::with_capacity(samples);
+    let mut scratched = 0;
+    for _ in 0..samples {
+        let result = simulator.simulate(state, &skill.perturbed(shot, &mut rng))?;
+        match cue_ball_rest(&result) {
+            Some(position) => points.push(position_xy_inches(&position, &state.table_spec)),
+            None => scratched += 1,
+        }
+    }
+
+    Ok(CueBallRestPrediction {
+        rest,
+        spread: UncertaintyEllipse::from_points(&points, &state.table_spec),
+        samples,
+        scratched,
+    })
+}
+
+/// Where position play wants the cue ball to stop.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RestTarget {
+    /// Anywhere in the zone for the next shot.
+    Zone(PositionZone),
+    /// Within `radius` of a spot.
+    Spot { center: Position, radius: Inches },
+}
+
+impl RestTarget {
+    pub fn contains(&self, position: &Position, table_spec: &TableSpec) -> bool {
+        match self {
+            RestTarget::Zone(zone) => zone.contains(position, table_spec),
+            RestTarget::Spot { radius, .. } => {
+                self.distance_from_middle(position, table_spec) <= radius.as_f64()
+            }
+        }
+    }
+
+    /// How far `position` is from the middle of the target, in inches: from the spot, or from the
+    /// average of the zone's outline.
+    pub fn distance_from_middle(&self, position: &Position, table_spec: &TableSpec) -> f64 {
+        let middle = match self {
+            RestTarget::Zone(zone) => {
+                let count = zone.outline.len().max(1) as f64;
+                zone.outline
+                    .iter()
+                    .map(|vertex| position_xy_inches(vertex, table_spec))
+                    .fold((0.0, 0.0), |(x, y), (vx, vy)| {
+                        (x + vx / count, y + vy / count)
+                    })
+            }
+            RestTarget::Spot { center, .. } => position_xy_inches(center, table_spec),
+        };
+        let (x, y) = position_xy_inches(position, table_spec);
+        (x - middle.0).hypot(y - middle.1)
+    }
+}
+
+/// The strokes [`solve_required_speed`] tries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeedSearch {
+    pub min_speed: InchesPerSecond,
+    pub max_speed: InchesPerSecond,
+    /// How many evenly spaced cue speeds to try from `min_speed` to `max_speed` inclusive. One
+    /// tries `min_speed` alone.
+    pub speed_steps: usize,
+    /// Tip heights to try at every speed, as fractions of the ball's radius: positive for follow
+    /// and negative for draw. Empty keeps the shot's own tip.
+    pub tip_heights: Vec<Scale>,
+    /// How many of the best strokes to return.
+    pub candidates: usize,
+}
+
+impl Default for SpeedSearch {
+    /// Cue speeds from a soft touch to a firm stroke, 20 to 300 in/s, with the shot's own tip.
+    fn default() -> Self {
+        Self {
+            min_speed: InchesPerSecond::new(Inches::from_f64(20.0)),
+            max_speed: InchesPerSecond::new(Inches::from_f64(300.0)),
+            speed_steps: 15,
+            tip_heights: Vec::new(),
+            candidates: 3,
+        }
+    }
+}
+
+/// One stroke [`solve_required_speed`] tried, and where it left the cue ball.
+pub struct PositionPlayCandidate {
+    pub shot: Shot,
+    /// Where the cue ball stopped, or `None` if it scratched.
+    pub rest: Option<Position>,
+    pub in_target: bool,
+    /// Inches from the rest to the middle of the target, or infinity on a scratch.
+    pub distance_from_middle: f64,
+    /// The cue ball's route from the strike to its rest; see [`SimulationResult::trajectory`].
+    pub cue_ball_path: Vec<Position>,
+    pub result: SimulationResult,
+}
+
+/// Search the speeds and tip heights in `search` for strokes along `shot`'s heading that leave
+/// the cue ball in `target`, and return the best few.
+///
+/// Strokes that stop in the target come first, then the rest; within each, those nearer the
+/// middle of the target come first. Side spin and cue elevation are kept from `shot`.
+pub fn solve_required_speed(
+    simulator: &dyn Simulator,
+    state: &GameState,
+    shot: &Shot,
+    target: &RestTarget,
+    search: &SpeedSearch,
+) -> Result<Vec<PositionPlayCandidate>, SimulationError> {
+    let table_spec = &state.table_spec;
+    let tip_heights = if search.tip_heights.is_empty() {
+        vec![shot.tip_contact().height_offset().clone()]
+    } else {
+        search.tip_heights.clone()
+    };
+    let (min_speed, max_speed) = (search.min_speed.as_f64(), search.max_speed.as_f64());
+
+    let mut candidates = Vec::new();
+    for tip_height in &tip_heights {
+        let tip_contact =
+            CueTipContact::new(shot.tip_contact().side_offset().clone(), tip_height.clone())
+                .map_err(SimulationError::InvalidShot)?;
+        for step in 0..search.speed_steps {
+            let fraction = step as f64 / search.speed_steps.saturating_sub(1).max(1) as f64;
+            let speed = min_speed + (max_speed - min_speed) * fraction;
+            let stroke = Shot::new(
+                shot.heading(),
+                InchesPerSecond::new(Inches::from_f64(speed)),
+                tip_contact.clone(),
+            )
+            .and_then(|stroke| stroke.with_cue_elevation_degrees(shot.cue_elevation_degrees()))
+            .map_err(SimulationError::InvalidShot)?;
+
+            let result = simulator.simulate(state, &stroke)?;
+            let rest = result
+                .final_state
+                .select_ball(BallType::Cue)
+                .map(|ball| ball.position.clone());
+            candidates.push(PositionPlayCandidate {
+                shot: stroke,
+                in_target: rest
+                    .as_ref()
+                    .is_some_and(|rest| target.contains(rest, table_spec)),
+                distance_from_middle: rest.as_ref().map_or(f64::INFINITY, |rest| {
+                    target.distance_from_middle(rest, table_spec)
+                }),
+                cue_ball_path: result
+                    .trajectory(BallType::Cue)
+                    .map(<[Position]>::to_vec)
+                    .unwrap_or_default(),
+                rest,
+                result,
+            });
+        }
+    }
+
+    candidates.sort_by(|a, b| {
+        b.in_target
+            .cmp(&a.in_target)
+            .then(a.distance_from_middle.total_cmp(&b.distance_from_middle))
+    });
+    candidates.truncate(search.candidates);
+    Ok(candidates)
+}
+
+/// Spread a frozen rack about its apex so neighbours sit [`RACK_GAP_INCHES`] apart, then move each
+/// ball a random distance of up to half that in a random direction.
+fn loosened_rack(rack: Vec<Ball>, table_spec: &TableSpec, rng: &mut SplitMix64) -> Vec<Ball> {
+    let Some(apex) = rack
+        .first()
+        .map(|ball| position_xy_inches(&ball.position, table_spec))
+    else {
+        return rack;
+    };
+    let spread = RACK_GAP_INCHES / (2.0 * TYPICAL_BALL_RADIUS.as_f64());
+
+    rack.into_iter()
+        .map(|mut ball| {
+            let (x, y) = position_xy_inches(&ball.position, table_spec);
+            let direction = std::f64::consts::TAU * rng.next_f64();
+            let distance = 0.5 * RACK_GAP_INCHES * rng.next_f64().sqrt();
+            ball.position
+                .shift_horizontally_inches(Inches::from_f64(
+                    (x - apex.0) * spread + distance * direction.cos(),
+                ))
+                .shift_vertically_inches(Inches::from_f64(
+                    (y - apex.1) * spread + distance * direction.sin(),
+                ));
+            ball.position.resolve_shifts(table_spec);
+            ball
+        })
+        .collect()
+}
+
+/// A small seeded generator, so seeded runs repeat without pulling in a random-number crate.
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform on `[0, 1)`.
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Normal with mean 0 and standard deviation 1, by the Box-Muller transform.
+    pub(crate) fn next_standard_normal(&mut self) -> f64 {
+        let radius = (-2.0 * (1.0 - self.next_f64()).ln()).sqrt();
+        radius * (std::f64::consts::TAU * self.next_f64()).cos()
+    }
+}
+
+/// Return the engine's starting states: the cue ball struck by `shot`, every other ball resting.
+pub(crate) fn initial_system_states(
+    state: &GameState,
+    shot: &Shot,
+    config: &SimulationConfig,
+) -> Result<Vec<NBallSystemState>, SimulationError> {
+    if state.select_ball(BallType::Cue).is_none() {
+        return Err(SimulationError::NoCueBall);
+    }
+
+    state
+        .balls()
+        .iter()
+        .map(|ball| {
+            let resting = RestingOnTableBallState::try_from(BallState::from_position(
+                &ball.position,
+                &state.table_spec,
+            ))
+            .expect(
+                "game-state ball placements should always correspond to resting on-table states",
+            );
+            if ball.ty == BallType::Cue {
+                strike_resting_ball_on_table(&resting, shot, &config.cue_strike, &config.ball_set)
+                    .map(NBallSystemState::from)
+                    .map_err(SimulationError::InvalidShot)
+            } else {
+                Ok(resting.into_on_table_ball_state().into())
+            }
+        })
+        .collect()
+}
+
+/// Read the engine's final states back into a [`GameState`] on the same table.
+///
+/// `strike_states` holds every ball's state just after the strike, `snapshots` every ball's state
+/// just after each event, parallel to `system.events`, and `trajectories` each ball's route,
+/// parallel to the balls.
+pub(crate) fn result_from_system(
+    state: &GameState,
+    system: NBallSystemSimulation,
+    strike_states: Vec<NBallSystemState>,
+    snapshots: Vec<Vec<NBallSystemState>>,
+    trajectories: Vec<Vec<Position>>,
+    config: &SimulationConfig,
+) -> SimulationResult {
+    let table_spec = &state.table_spec;
+    let mut on_table = Vec::new();
+    for (ball, final_state) in state.balls().iter().zip(&system.states) {
+        if let Some(final_state) = final_state.as_on_table() {
+            on_table.push(Ball {
+                ty: ball.ty.clone(),
+                position: final_state.as_ball_state().projected_position(table_spec),
+                spec: ball.spec.clone(),
+            });
+        }
+    }
+    let ball_type = |index: usize| state.balls()[index].ty.clone();
+    let pocketed = pocketed_in_capture_order(&system)
+        .into_iter()
+        .map(|(index, pocket)| (ball_type(index), pocket))
+        .collect();
+    let rattled = rattled_pockets(&system)
+        .into_iter()
+        .map(|(index, pocket)| (ball_type(index), pocket))
+        .collect();
+    let event_log = typed_event_log(state, &system, &snapshots);
+
+    let mut final_state = GameState::with_balls(table_spec.clone(), on_table);
+    final_state.ty = state.ty.clone();
+    SimulationResult {
+        final_state,
+        pocketed,
+        rattled,
+        event_log,
+        trajectories,
+        balls: state.balls().iter().map(|ball| ball.ty.clone()).collect(),
+        system,
+        initial_state: state.clone(),
+        strike_states,
+        snapshots,
+        ball_set: config.ball_set.clone(),
+        motion: config.motion.clone(),
+    }
+}
+
+/// Each pocketed ball's index and pocket, ordered by the event that dropped it.
+///
+/// A ball drops either at a capture event or straight off a jaw that turned it into the pocket, so
+/// this reads the final states rather than the capture events alone.
+fn pocketed_in_capture_order(system: &NBallSystemSimulation) -> Vec<(usize, Pocket)> {
+    let mut pocketed: Vec<(usize, usize, Pocket)> = system
+        .states
+        .iter()
+        .enumerate()
+        .filter_map(|(index, state)| match state {
+            NBallSystemState::Pocketed { pocket, .. } => {
+                let dropped_at = system.events.iter().rposition(|event| {
+                    matches!(
+                        event,
+                        NBallSystemEvent::BallJawImpact { .. }
+                            | NBallSystemEvent::BallPocketCapture { .. }
+                    ) && event.primary_ball() == Some(index)
+                });
+                Some((dropped_at.unwrap_or(usize::MAX), index, *pocket))
+            }
+            NBallSystemState::OnTable(_) => None,
+        })
+        .collect();
+    pocketed.sort_by_key(|(dropped_at, index, _)| (*dropped_at, *index));
+    pocketed
+        .into_iter()
+        .map(|(_, index, pocket)| (index, pocket))
+        .collect()
+}
+
+/// Each visit a ball made to a pocket's jaws without dropping there, as its index and the pocket.
+///
+/// Consecutive jaw hits at one pocket, including any cushion contacts between them, count as a
+/// single visit. The visit ends when the ball drops, touches another ball, or reaches other jaws.
+fn rattled_pockets(system: &NBallSystemSimulation) -> Vec<(usize, Pocket)> {
+    let mut rattled = Vec::new();
+    let mut visiting: Vec<Option<Pocket>> = vec![None; system.states.len()];
+    let mut leave = |visiting: &mut Option<Pocket>, dropped_in: Option<Pocket>, index: usize| {
+        if let Some(pocket) = visiting.take() {
+            if dropped_in != Some(pocket) {
+                rattled.push((index, pocket));
+            }
+        }
+    };
+
+    for event in &system.events {
+        match event {
+            NBallSystemEvent::BallJawImpact { ball_index, impact } => {
+                if visiting[*ball_index] != Some(impact.pocket) {
+                    leave(&mut visiting[*ball_index], None, *ball_index);
+                    visiting[*ball_index] = Some(impact.pocket);
+                }
+            }
+            NBallSystemEvent::BallPocketCapture {
+                ball_index,
+                capture,
+            } => leave(
+                &mut visiting[*ball_index],
+                Some(capture.pocket),
+                *ball_index,
+            ),
+            NBallSystemEvent::BallBallCollision {
+                first_ball_index,
+                second_ball_index,
+                ..
+            } => {
+                for index in [*first_ball_index, *second_ball_index] {
+                    leave(&mut visiting[index], None, index);
+                }
+            }
+            NBallSystemEvent::SharedBallBallContact { ball_indices, .. } => {
+                for index in ball_indices {
+                    leave(&mut visiting[*index], None, *index);
+                }
+            }
+            NBallSystemEvent::BallRailImpact { .. } | NBallSystemEvent::MotionTransition { .. } => {
+            }
+        }
+    }
+
+    for (index, state) in system.states.iter().enumerate() {
+        let dropped_in = match state {
+            NBallSystemState::Pocketed { pocket, .. } => Some(*pocket),
+            NBallSystemState::OnTable(_) => None,
+        };
+        leave(&mut visiting[index], dropped_in, index);
+    }
+    rattled
+}
+
+/// Translate the engine's events into [`Event`]s, reading positions the events lack from
+/// `snapshots`.
+fn typed_event_log(
+    state: &GameState,
+    system: &NBallSystemSimulation,
+    snapshots: &[Vec<NBallSystemState>],
+) -> Vec<Event> {
+    let table_spec = &state.table_spec;
+    let ball_type = |index: usize| state.balls()[index].ty.clone();
+    let position_of =
+        |state: &OnTableBallState| state.as_ball_state().projected_position(table_spec);
+    let position_in = |snapshot: &[NBallSystemState], index: usize| {
+        system_state_position(&snapshot[index], table_spec)
+    };
+
+    let mut log = Vec::new();
+    let mut time = 0.0;
+    for (event, snapshot) in system.events.iter().zip(snapshots) {
+        time += event.time().as_f64();
+        let mut push = |kind| {
+            log.push(Event {
+                time: Seconds::new(time),
+                kind,
+            })
+        };
+        match event {
+            NBallSystemEvent::BallBallCollision {
+                first_ball_index,
+                second_ball_index,
+                collision,
+            } => push(EventKind::BallBallCollision {
+                first: ball_type(*first_ball_index),
+                second: ball_type(*second_ball_index),
+                first_position: position_of(&collision.a_at_impact),
+                second_position: position_of(&collision.b_at_impact),
+            }),
+            NBallSystemEvent::SharedBallBallContact {
+                ball_ball_pairs, ..
+            } => {
+                for (first, second) in ball_ball_pairs {
+                    push(EventKind::BallBallCollision {
+                        first: ball_type(*first),
+                        second: ball_type(*second),
+                        first_position: position_in(snapshot, *first),
+                        second_position: position_in(snapshot, *second),
+                    });
+                }
+            }
+            NBallSystemEvent::BallJawImpact { ball_index, impact } => {
+                push(EventKind::CushionContact {
+                    ball: ball_type(*ball_index),
+                    rail: pocket_jaw_associated_rail(impact.pocket, impact.jaw),
+                    position: position_of(&impact.state_at_impact),
+                });
+                // A jaw can turn the ball straight into the pocket without a separate capture.
+                if let NBallSystemState::Pocketed {
+                    pocket,
+                    state_at_capture,
+                } = &snapshot[*ball_index]
+                {
+                    push(EventKind::Pocketed {
+                        ball: ball_type(*ball_index),
+                        pocket: *pocket,
+                        position: position_of(state_at_capture),
+                    });
+                }
+            }
+            NBallSystemEvent::BallPocketCapture {
+                ball_index,
+                capture,
+            } => push(EventKind::Pocketed {
+                ball: ball_type(*ball_index),
+                pocket: capture.pocket,
+                position: position_of(&capture.state_at_capture),
+            }),
+            NBallSystemEvent::BallRailImpact { ball_index, impact } => {
+                push(EventKind::CushionContact {
+                    ball: ball_type(*ball_index),
+                    rail: impact.rail,
+                    position: position_of(&impact.state_at_impact),
+                })
+            }
+            NBallSystemEvent::MotionTransition {
+                ball_index,
+                transition,
+            } => {
+                if transition.phase_after == MotionPhase::Rest {
+                    push(EventKind::BallStopped {
+                        ball: ball_type(*ball_index),
+                        position: position_in(snapshot, *ball_index),
+                    });
+                }
+            }
+        }
+    }
+    log
+}
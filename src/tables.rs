@@ -0,0 +1,165 @@
+//! Named tables saved as standalone JSON documents, so a club table's quirks are measured once
+//! and shared between tools.
+//!
+//! A [`TableProfile`] is a [`TableSpec`], with its pockets and cushions, plus the
+//! [`PlayingConditions`] its cloth plays in. A [`TableLibrary`] keeps profiles in a directory,
+//! one file per table, and loads them by name:
+//!
+//! ```json
+//! {
+//!   "schema_version": 2,
+//!   "name": "Table 7",
+//!   "table_spec": { "pockets": [...], "cushion_diamond_buffer": "0.295", "diamond_length": "12.5", "cushions": {...} },
+//!   "conditions": { "sliding_friction_scale": "1.2", "rolling_resistance_scale": "1.15", ... }
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::schema::{self, Document};
+use crate::simulation::SimulationConfig;
+use crate::{PlayingConditions, TableSpec};
+
+/// A named table and the conditions it plays in.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TableProfile {
+    pub name: String,
+    pub table_spec: TableSpec,
+    /// How the cloth, balls and cushions play against the table's spec; neutral when left out.
+    #[serde(default)]
+    pub conditions: PlayingConditions,
+}
+
+impl TableProfile {
+    /// `table_spec` called `name`, in neutral conditions.
+    pub fn new(name: &str, table_spec: TableSpec) -> Self {
+        Self {
+            name: name.to_string(),
+            table_spec,
+            conditions: PlayingConditions::neutral(),
+        }
+    }
+
+    pub fn with_conditions(mut self, conditions: PlayingConditions) -> Self {
+        self.conditions = conditions;
+        self
+    }
+
+    /// The table spec with its cushions scaled by the conditions, for laying out and simulating
+    /// shots on this table.
+    pub fn conditioned_table_spec(&self) -> TableSpec {
+        let mut table_spec = self.table_spec.clone();
+        table_spec.cushions = table_spec.cushions.applying_conditions(&self.conditions);
+        table_spec
+    }
+
+    /// The default physics with the cloth and ball-ball contact scaled by the conditions. Pair
+    /// it with [`TableProfile::conditioned_table_spec`].
+    pub fn simulation_config(&self) -> SimulationConfig {
+        let config = SimulationConfig::default();
+        SimulationConfig {
+            motion: config.motion.applying_conditions(&self.conditions),
+            collision_config: config
+                .collision_config
+                .applying_conditions(&self.conditions),
+            ..config
+        }
+    }
+
+    /// This profile as a JSON document in the shape shown in the [module docs](self).
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&schema::Versioned::new(self))
+            .expect("a table profile is always valid JSON")
+    }
+
+    /// Read a profile written by [`TableProfile::to_json`] in this or any earlier schema version.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let document = schema::migrate(Document::TableProfile, serde_json::from_str(json)?)
+            .map_err(<serde_json::Error as serde::de::Error>::custom)?;
+        serde_json::from_value(document)
+    }
+}
+
+/// A directory of [`TableProfile`] files, each named for its table: `Table 7` is kept in
+/// `table-7.json`.
+#[derive(Clone, Debug)]
+pub struct TableLibrary {
+    dir: PathBuf,
+}
+
+impl TableLibrary {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The file the table called `name` is kept in. Letters and digits are kept, lowercased, and
+    /// every other run of characters becomes one `-`. A name without any letters or digits has no
+    /// file.
+    pub fn path_for(&self, name: &str) -> Result<PathBuf, String> {
+        let mut stem = String::new();
+        for c in name.chars() {
+            if c.is_alphanumeric() {
+                stem.extend(c.to_lowercase());
+            } else if !stem.is_empty() && !stem.ends_with('-') {
+                stem.push('-');
+            }
+        }
+        let stem = stem.trim_end_matches('-');
+        if stem.is_empty() {
+            return Err(format!("table name '{name}' has no letters or digits"));
+        }
+        Ok(self.dir.join(format!("{stem}.json")))
+    }
+
+    /// Write `profile` to its file, creating the directory if needed, and return the path. A
+    /// different table already kept in that file is not overwritten.
+    pub fn save(&self, profile: &TableProfile) -> Result<PathBuf, String> {
+        let path = self.path_for(&profile.name)?;
+        if path.exists() {
+            let existing = load_profile(&path)?.name;
+            if existing != profile.name {
+                return Err(format!(
+                    "{}: already holds table '{existing}', so '{}' can't be saved there",
+                    path.display(),
+                    profile.name
+                ));
+            }
+        }
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|error| format!("{}: {error}", self.dir.display()))?;
+        std::fs::write(&path, profile.to_json())
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Ok(path)
+    }
+
+    /// The table called `name`.
+    pub fn load(&self, name: &str) -> Result<TableProfile, String> {
+        load_profile(&self.path_for(name)?)
+    }
+
+    /// The names of every table in the library, in order.
+    pub fn names(&self) -> Result<Vec<String>, String> {
+        let entries = std::fs::read_dir(&self.dir)
+            .map_err(|error| format!("{}: {error}", self.dir.display()))?;
+        let mut names = Vec::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|error| format!("{}: {error}", self.dir.display()))?
+                .path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                names.push(load_profile(&path)?.name);
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+fn load_profile(path: &Path) -> Result<TableProfile, String> {
+    let json =
+        std::fs::read_to_string(path).map_err(|error| format!("{}: {error}", path.display()))?;
+    TableProfile::from_json(&json).map_err(|error| format!("{}: {error}", path.display()))
+}
@@ -2,12 +2,15 @@ use crate::{Inches, OverlayLayer, TYPICAL_BALL_RADIUS};
 use image::Rgba;
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DashedLineStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
     pub color: Rgba<u8>,
     pub dash_px: f32,
     pub gap_px: f32,
     pub width_px: f32,
     pub layer: OverlayLayer,
+    pub arrow: Option<ArrowStyle>,
 }
 
 impl DashedLineStyle {
@@ -18,6 +21,7 @@ impl DashedLineStyle {
             gap_px: 12.0,
             width_px: 2.0,
             layer: OverlayLayer::BelowBalls,
+            arrow: None,
         }
     }
 
@@ -25,13 +29,21 @@ impl DashedLineStyle {
         self.layer = layer;
         self
     }
+
+    pub fn with_arrow(mut self, arrow: ArrowStyle) -> Self {
+        self.arrow = Some(arrow);
+        self
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SmoothPolylineStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
     pub color: Rgba<u8>,
     pub width_px: f32,
     pub layer: OverlayLayer,
+    pub arrow: Option<ArrowStyle>,
 }
 
 impl SmoothPolylineStyle {
@@ -40,6 +52,7 @@ impl SmoothPolylineStyle {
             color,
             width_px: 4.0,
             layer: OverlayLayer::BelowBalls,
+            arrow: None,
         }
     }
 
@@ -47,11 +60,61 @@ impl SmoothPolylineStyle {
         self.layer = layer;
         self
     }
+
+    pub fn with_arrow(mut self, arrow: ArrowStyle) -> Self {
+        self.arrow = Some(arrow);
+        self
+    }
+}
+
+/// Which ends of a line carry an arrowhead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ArrowEnds {
+    End,
+    Both,
 }
 
+/// A filled triangular arrowhead drawn in the line's color, its tip on the line's endpoint.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArrowStyle {
+    pub ends: ArrowEnds,
+    pub length_px: f32,
+    pub width_px: f32,
+}
+
+impl Default for ArrowStyle {
+    fn default() -> Self {
+        Self {
+            ends: ArrowEnds::End,
+            length_px: 18.0,
+            width_px: 12.0,
+        }
+    }
+}
+
+impl ArrowStyle {
+    pub fn both_ends() -> Self {
+        Self {
+            ends: ArrowEnds::Both,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_size(mut self, length_px: f32, width_px: f32) -> Self {
+        self.length_px = length_px;
+        self.width_px = width_px;
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GhostBallStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
     pub fill_color: Rgba<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
     pub outline_color: Rgba<u8>,
     pub layer: OverlayLayer,
 }
@@ -73,7 +136,72 @@ impl GhostBallStyle {
     }
 }
 
+/// A ball drawn see-through, where it was before the shot or will be after it.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhantomBallStyle {
+    /// From 0 (invisible) to 1 (as solid as the balls on the table).
+    pub opacity: f32,
+}
+
+impl Default for PhantomBallStyle {
+    fn default() -> Self {
+        Self { opacity: 0.4 }
+    }
+}
+
+impl PhantomBallStyle {
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// How a highlighted ball is marked.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BallHighlightKind {
+    /// A band of color around the ball, beneath it.
+    Halo,
+    /// A ring over the ball's edge.
+    Outline,
+}
+
+/// A mark picking one ball out of a layout, e.g. the object ball among fifteen.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BallHighlightStyle {
+    pub kind: BallHighlightKind,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub color: Rgba<u8>,
+    /// How far a halo reaches past the ball, or how thick an outline is.
+    pub width_px: f32,
+}
+
+impl BallHighlightStyle {
+    pub fn halo(color: Rgba<u8>) -> Self {
+        Self {
+            kind: BallHighlightKind::Halo,
+            color,
+            width_px: 10.0,
+        }
+    }
+
+    pub fn outline(color: Rgba<u8>) -> Self {
+        Self {
+            kind: BallHighlightKind::Outline,
+            color,
+            width_px: 4.0,
+        }
+    }
+}
+
+/// How solid the balls left out of [`crate::GameState::dim_balls_except`] are drawn, from 0
+/// (invisible) to 1 (not dimmed at all).
+pub const DIMMED_BALL_OPACITY: f32 = 0.35;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PathColorMode {
     Solid,
     FadeByTime,
@@ -81,8 +209,10 @@ pub enum PathColorMode {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LabelOverlayStyle {
     pub enabled: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
     pub color: Rgba<u8>,
     pub layer: OverlayLayer,
     pub offset_x_px: i32,
@@ -114,8 +244,10 @@ impl LabelOverlayStyle {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventMarkerStyle {
     pub enabled: bool,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
     pub color: Rgba<u8>,
     pub radius_px: f32,
     pub layer: OverlayLayer,
@@ -142,7 +274,346 @@ impl EventMarkerStyle {
     }
 }
 
+/// A circled number marking one step of a multi-shot diagram.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SequenceMarkerStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub fill_color: Rgba<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub text_color: Rgba<u8>,
+    pub radius_px: f32,
+    pub scale_px: u32,
+    pub layer: OverlayLayer,
+}
+
+impl Default for SequenceMarkerStyle {
+    fn default() -> Self {
+        Self {
+            fill_color: Rgba([255, 255, 255, 230]),
+            text_color: Rgba([0, 0, 0, 255]),
+            radius_px: 14.0,
+            scale_px: 2,
+            layer: OverlayLayer::AboveBalls,
+        }
+    }
+}
+
+impl SequenceMarkerStyle {
+    pub fn new(fill_color: Rgba<u8>, text_color: Rgba<u8>) -> Self {
+        Self {
+            fill_color,
+            text_color,
+            ..Self::default()
+        }
+    }
+
+    pub fn on_layer(mut self, layer: OverlayLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+}
+
+/// An arc swept between two lines at their vertex, labelled with the angle in degrees.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AngleArcStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub color: Rgba<u8>,
+    pub radius_px: f32,
+    pub width_px: f32,
+    pub scale_px: u32,
+    pub layer: OverlayLayer,
+}
+
+impl AngleArcStyle {
+    pub fn new(color: Rgba<u8>) -> Self {
+        Self {
+            color,
+            radius_px: 48.0,
+            width_px: 2.0,
+            scale_px: 2,
+            layer: OverlayLayer::AboveBalls,
+        }
+    }
+
+    pub fn on_layer(mut self, layer: OverlayLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+}
+
+/// The unit a measurement is labelled in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MeasurementUnit {
+    Inches,
+    Diamonds,
+}
+
+/// A line with an arrowhead on each end, labelled at its midpoint with its length.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeasurementStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub color: Rgba<u8>,
+    pub width_px: f32,
+    pub unit: MeasurementUnit,
+    pub scale_px: u32,
+    pub layer: OverlayLayer,
+}
+
+impl MeasurementStyle {
+    pub fn new(color: Rgba<u8>) -> Self {
+        Self {
+            color,
+            width_px: 2.0,
+            unit: MeasurementUnit::Inches,
+            scale_px: 2,
+            layer: OverlayLayer::AboveBalls,
+        }
+    }
+
+    pub fn in_diamonds(mut self) -> Self {
+        self.unit = MeasurementUnit::Diamonds;
+        self
+    }
+
+    pub fn on_layer(mut self, layer: OverlayLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+}
+
+/// A translucent disc with an outline ring drawn over a pocket mouth.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PocketHighlightStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub fill_color: Rgba<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub outline_color: Rgba<u8>,
+    pub outline_width_px: f32,
+    pub layer: OverlayLayer,
+}
+
+impl PocketHighlightStyle {
+    /// Outline in `color`, filled with `color` at a third of its opacity.
+    pub fn new(color: Rgba<u8>) -> Self {
+        Self {
+            fill_color: Rgba([color[0], color[1], color[2], color[3] / 3]),
+            outline_color: color,
+            outline_width_px: 3.0,
+            layer: OverlayLayer::BelowBalls,
+        }
+    }
+
+    pub fn on_layer(mut self, layer: OverlayLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+}
+
+/// A translucent fill over a region of the cloth, optionally outlined.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShadedZoneStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub fill_color: Rgba<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba::option"))]
+    pub outline_color: Option<Rgba<u8>>,
+    pub outline_width_px: f32,
+    pub layer: OverlayLayer,
+}
+
+impl ShadedZoneStyle {
+    pub fn new(fill_color: Rgba<u8>) -> Self {
+        Self {
+            fill_color,
+            outline_color: None,
+            outline_width_px: 2.0,
+            layer: OverlayLayer::BelowBalls,
+        }
+    }
+
+    pub fn with_outline(mut self, color: Rgba<u8>) -> Self {
+        self.outline_color = Some(color);
+        self
+    }
+
+    pub fn on_layer(mut self, layer: OverlayLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+}
+
+/// Colors for the table art. The raster table is recolored from its own flat colors, so
+/// [`TableTheme::classic`] leaves it as drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableTheme {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub cloth: Rgba<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub cushion: Rgba<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub rail: Rgba<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub corner_rail: Rgba<u8>,
+    /// Outlines and pockets.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub ink: Rgba<u8>,
+}
+
+impl Default for TableTheme {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl TableTheme {
+    /// The colors of the bundled table image.
+    pub const fn classic() -> Self {
+        Self {
+            cloth: Rgba([196, 211, 125, 255]),
+            cushion: Rgba([146, 185, 71, 255]),
+            rail: Rgba([193, 177, 153, 255]),
+            corner_rail: Rgba([170, 93, 85, 255]),
+            ink: Rgba([34, 31, 31, 255]),
+        }
+    }
+
+    pub const fn green() -> Self {
+        Self {
+            cloth: Rgba([38, 128, 72, 255]),
+            cushion: Rgba([26, 100, 54, 255]),
+            rail: Rgba([104, 66, 42, 255]),
+            corner_rail: Rgba([78, 48, 32, 255]),
+            ..Self::classic()
+        }
+    }
+
+    pub const fn blue() -> Self {
+        Self {
+            cloth: Rgba([5, 141, 188, 255]),
+            cushion: Rgba([10, 110, 150, 255]),
+            rail: Rgba([110, 50, 36, 255]),
+            corner_rail: Rgba([74, 28, 22, 255]),
+            ..Self::classic()
+        }
+    }
+
+    pub const fn red() -> Self {
+        Self {
+            cloth: Rgba([158, 38, 44, 255]),
+            cushion: Rgba([122, 28, 34, 255]),
+            rail: Rgba([82, 54, 40, 255]),
+            corner_rail: Rgba([56, 36, 28, 255]),
+            ..Self::classic()
+        }
+    }
+
+    /// Muted slate tones for dark slides and pages.
+    pub const fn dark() -> Self {
+        Self {
+            cloth: Rgba([46, 62, 78, 255]),
+            cushion: Rgba([34, 48, 62, 255]),
+            rail: Rgba([52, 54, 62, 255]),
+            corner_rail: Rgba([66, 68, 78, 255]),
+            ink: Rgba([8, 8, 10, 255]),
+        }
+    }
+}
+
+/// Faint lines through every diamond, numbered along the bottom and left rails.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiamondGridStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub line_color: Rgba<u8>,
+    pub width_px: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub label_fill_color: Rgba<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub label_text_color: Rgba<u8>,
+    pub label_radius_px: f32,
+    pub scale_px: u32,
+    pub layer: OverlayLayer,
+}
+
+impl Default for DiamondGridStyle {
+    fn default() -> Self {
+        Self {
+            line_color: Rgba([0, 0, 0, 72]),
+            width_px: 1,
+            label_fill_color: Rgba([32, 32, 32, 208]),
+            label_text_color: Rgba([255, 255, 255, 255]),
+            label_radius_px: 12.0,
+            scale_px: 2,
+            layer: OverlayLayer::BelowBalls,
+        }
+    }
+}
+
+impl DiamondGridStyle {
+    pub fn on_layer(mut self, layer: OverlayLayer) -> Self {
+        self.layer = layer;
+        self
+    }
+}
+
+/// What a legend row shows beside its text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LegendSwatch {
+    Solid(#[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))] Rgba<u8>),
+    Dashed(#[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))] Rgba<u8>),
+    Fill(#[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))] Rgba<u8>),
+}
+
+/// One row of a legend, e.g. a dashed swatch and "cue ball path".
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LegendEntry {
+    pub swatch: LegendSwatch,
+    pub text: String,
+}
+
+impl LegendEntry {
+    pub fn new(swatch: LegendSwatch, text: impl Into<String>) -> Self {
+        Self {
+            swatch,
+            text: text.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LegendStyle {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub background_color: Rgba<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_rgba"))]
+    pub text_color: Rgba<u8>,
+    pub scale_px: u32,
+    pub padding_px: u32,
+    pub layer: OverlayLayer,
+}
+
+impl Default for LegendStyle {
+    fn default() -> Self {
+        Self {
+            background_color: Rgba([255, 255, 255, 208]),
+            text_color: Rgba([0, 0, 0, 255]),
+            scale_px: 2,
+            padding_px: 10,
+            layer: OverlayLayer::AboveBalls,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AimOverlayStyle {
     pub line: DashedLineStyle,
     pub ghost_ball: Option<GhostBallStyle>,
@@ -165,12 +636,14 @@ impl AimOverlayStyle {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BallPathWidthMode {
     Fixed,
     ScaleBySpeed,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BallPathRenderOptions {
     pub max_time_step: crate::Seconds,
     pub width_px: f32,
@@ -220,6 +693,7 @@ impl BallPathRenderOptions {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BallPathStyle {
     pub line: DashedLineStyle,
     pub start_ghost_ball: Option<GhostBallStyle>,
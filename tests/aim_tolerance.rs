@@ -0,0 +1,134 @@
+use billiards::aiming::aim_tolerance;
+use billiards::{Ball, BallSpec, BallType, InchesPerSecond, Pocket, Position, TableSpec};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn a_straight_in_side_pocket_shot_is_equally_forgiving_both_ways() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::One, "2", "4");
+
+    let tolerance = aim_tolerance(
+        &Position::new("1", "4"),
+        &object_ball,
+        Pocket::CenterRight,
+        InchesPerSecond::zero(),
+        &table,
+    )
+    .expect("straight-in shot should have a tolerance");
+
+    assert!(tolerance.left_degrees > 0.0);
+    assert_close_with_tolerance(tolerance.left_degrees, tolerance.right_degrees, 1e-6);
+    assert_close_with_tolerance(tolerance.cut_angle.as_degrees(), 0.0, 1e-9);
+}
+
+#[test]
+fn a_straight_in_shot_scales_the_pocket_window_by_diameter_over_distance() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::One, "2", "4");
+
+    let tolerance = aim_tolerance(
+        &Position::new("1", "4"),
+        &object_ball,
+        Pocket::CenterRight,
+        InchesPerSecond::zero(),
+        &table,
+    )
+    .unwrap();
+
+    // Turning the cue ball by δ moves its line by about 12.5δ inches at the object ball. The
+    // object ball leaves along the line of centers, which swings by that over one ball diameter,
+    // less the δ the cue ball itself turned: 10.25δ / 2.25 in all.
+    let window = tolerance.pocket_opening.right_angular_margin_degrees;
+    assert_close_with_tolerance(tolerance.left_degrees, window * 2.25 / 10.25, 0.02 * window);
+}
+
+#[test]
+fn cuts_and_longer_shots_leave_less_room_for_error() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::One, "2", "4");
+    let tolerance_from = |x: &str, y: &str| {
+        aim_tolerance(
+            &Position::new(x, y),
+            &object_ball,
+            Pocket::CenterRight,
+            InchesPerSecond::zero(),
+            &table,
+        )
+        .unwrap()
+        .plus_minus_degrees()
+    };
+
+    let straight = tolerance_from("1", "4");
+    let long_straight = tolerance_from("0.5", "4");
+    // One diamond from the ghost ball at a 45° cut.
+    let cut = tolerance_from("1.113", "3.293");
+
+    assert!(long_straight < straight);
+    assert!(cut < straight);
+}
+
+#[test]
+fn an_angled_corner_shot_splits_its_lopsided_window_evenly() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::One, "3.8", "4");
+
+    let tolerance = aim_tolerance(
+        &Position::new("3.8", "2"),
+        &object_ball,
+        Pocket::TopRight,
+        InchesPerSecond::zero(),
+        &table,
+    )
+    .unwrap();
+
+    // The rail-side jaw leaves the object ball far less room on the right, but the ghost ball
+    // already aims at the middle of the effective window.
+    let opening = &tolerance.pocket_opening;
+    assert!(opening.right_angular_margin_degrees < 0.7 * opening.left_angular_margin_degrees);
+    assert_close_with_tolerance(
+        tolerance.left_degrees,
+        tolerance.right_degrees,
+        0.05 * tolerance.total_degrees(),
+    );
+    assert_close_with_tolerance(
+        tolerance.total_degrees(),
+        opening.angular_width_degrees() * 2.25 / 22.75,
+        0.05 * tolerance.total_degrees(),
+    );
+    assert_eq!(
+        tolerance.plus_minus_degrees(),
+        tolerance.left_degrees.min(tolerance.right_degrees)
+    );
+}
+
+#[test]
+fn a_back_cut_has_no_tolerance() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::One, "2", "4");
+
+    assert_eq!(
+        aim_tolerance(
+            &Position::new("3", "4"),
+            &object_ball,
+            Pocket::CenterRight,
+            InchesPerSecond::zero(),
+            &table,
+        ),
+        None
+    );
+}
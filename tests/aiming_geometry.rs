@@ -134,3 +134,61 @@ fn over_limit_fast_side_pocket_target_falls_back_to_geometric_center() {
         diamond_value(&pocket_center.y),
     );
 }
+
+#[test]
+fn a_straight_in_corner_shot_has_a_symmetric_effective_opening() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("3", "7");
+
+    let opening =
+        object_ball.effective_pocket_opening(Pocket::TopRight, InchesPerSecond::zero(), &table);
+
+    assert_close(opening.entry_angle_degrees, 0.0);
+    assert_close(opening.left_margin.as_f64(), opening.right_margin.as_f64());
+    assert!(opening.is_open());
+    assert_eq!(
+        opening.target_center,
+        object_ball.pocket_target_center(Pocket::TopRight, InchesPerSecond::zero(), &table)
+    );
+}
+
+#[test]
+fn an_angled_corner_approach_tightens_the_rail_side_jaw() {
+    let table = TableSpec::default();
+    let straight_in = object_ball_at("3", "7").effective_pocket_opening(
+        Pocket::TopRight,
+        InchesPerSecond::zero(),
+        &table,
+    );
+
+    // Running up the right rail toward the top-right pocket.
+    let along_rail = object_ball_at("3.7", "4").effective_pocket_opening(
+        Pocket::TopRight,
+        InchesPerSecond::zero(),
+        &table,
+    );
+
+    // Looking into the pocket, the right rail's jaw is on the right.
+    assert!(along_rail.entry_angle_degrees > 30.0);
+    assert!(along_rail.right_margin.as_f64() < straight_in.right_margin.as_f64());
+    assert!(along_rail.right_margin.as_f64() < along_rail.left_margin.as_f64());
+    assert!(along_rail.right_angular_margin_degrees < along_rail.left_angular_margin_degrees);
+}
+
+#[test]
+fn the_angular_window_shrinks_with_distance_along_the_same_line() {
+    let table = TableSpec::default();
+    let near = object_ball_at("3", "7").effective_pocket_opening(
+        Pocket::TopRight,
+        InchesPerSecond::zero(),
+        &table,
+    );
+    let far = object_ball_at("2", "6").effective_pocket_opening(
+        Pocket::TopRight,
+        InchesPerSecond::zero(),
+        &table,
+    );
+
+    assert_close(near.width().as_f64(), far.width().as_f64());
+    assert!(far.angular_width_degrees() < near.angular_width_degrees());
+}
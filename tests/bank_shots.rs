@@ -0,0 +1,175 @@
+use billiards::banking::{
+    multi_rail_bank, multi_rail_banks, single_rail_bank, single_rail_banks, BankObstruction,
+};
+use billiards::visualization::{DashedLineStyle, LabelOverlayStyle};
+use billiards::{
+    Ball, BallSpec, BallType, DiagramRenderOptions, GameState, Inches, Pocket, Position, Rail,
+    TableSpec, TYPICAL_BALL_RADIUS,
+};
+use image::Rgba;
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn as_f64(value: &billiards::Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn object_ball_at(x: &str, y: &str) -> Ball {
+    Ball {
+        ty: BallType::Nine,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn a_cross_side_bank_from_the_middle_of_the_table_hits_the_opposite_side_diamond() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("1", "4");
+
+    let bank = single_rail_bank(&object_ball, Pocket::CenterRight, Rail::Left, &table)
+        .expect("cross-side bank should exist");
+
+    assert_eq!(bank.rails(), vec![Rail::Left]);
+    assert_close_with_tolerance(as_f64(&bank.contacts[0].diamond), 4.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&bank.contacts[0].point.x), 1.125 / 12.5, 1e-9);
+    assert_eq!(bank.path.len(), 3);
+    assert_eq!(bank.path[0], object_ball.position);
+    assert_eq!(bank.path[2], Pocket::CenterRight.aiming_center());
+}
+
+#[test]
+fn the_bank_leaves_the_cushion_at_the_same_angle_it_arrives() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("1.5", "2.5");
+
+    let bank = single_rail_bank(&object_ball, Pocket::TopLeft, Rail::Right, &table)
+        .expect("one-rail bank should exist");
+
+    let contact = &bank.contacts[0];
+    let incoming = object_ball.position.angle_to(&contact.point);
+    let outgoing = contact.point.angle_to(&Pocket::TopLeft.aiming_center());
+    // Off the right rail, the x-velocity flips and the y-velocity is preserved.
+    assert_close_with_tolerance(incoming.as_degrees(), 360.0 - outgoing.as_degrees(), 1e-6);
+    assert!(as_f64(&contact.diamond) > 2.5);
+    assert!(as_f64(&contact.diamond) < 8.0);
+}
+
+#[test]
+fn a_pocket_behind_the_banking_rail_has_no_mirror_solution() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("2", "2");
+
+    assert_eq!(
+        single_rail_bank(&object_ball, Pocket::CenterRight, Rail::Right, &table),
+        None
+    );
+}
+
+#[test]
+fn all_single_rail_banks_lists_only_solvable_rails() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("1", "4");
+
+    let banks = single_rail_banks(&object_ball, Pocket::CenterRight, &table);
+
+    assert!(banks.iter().any(|bank| bank.rails() == vec![Rail::Left]));
+    assert!(banks.iter().all(|bank| bank.rails() != vec![Rail::Right]));
+}
+
+#[test]
+fn a_cross_corner_double_reflects_off_both_side_rails() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("1", "2");
+
+    let bank = multi_rail_bank(
+        &object_ball,
+        Pocket::TopRight,
+        &[Rail::Right, Rail::Left],
+        &table,
+    )
+    .expect("two-rail bank should exist");
+
+    assert_eq!(bank.rails(), vec![Rail::Right, Rail::Left]);
+    assert_eq!(bank.path.len(), 4);
+    for (index, contact) in bank.contacts.iter().enumerate() {
+        let incoming = bank.path[index].angle_to(&contact.point);
+        let outgoing = contact.point.angle_to(&bank.path[index + 2]);
+        assert_close_with_tolerance(incoming.as_degrees(), 360.0 - outgoing.as_degrees(), 1e-6);
+    }
+    assert!(as_f64(&bank.contacts[0].diamond) < as_f64(&bank.contacts[1].diamond));
+}
+
+#[test]
+fn multi_rail_banks_never_repeat_a_rail_back_to_back() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("1.5", "3");
+
+    let banks = multi_rail_banks(&object_ball, Pocket::BottomRight, 3, &table);
+
+    assert!(!banks.is_empty());
+    for bank in &banks {
+        let rails = bank.rails();
+        assert_eq!(rails.len(), 3);
+        assert!(rails.windows(2).all(|pair| pair[0] != pair[1]));
+    }
+}
+
+#[test]
+fn a_ball_sitting_on_a_leg_obstructs_that_leg() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("1", "4");
+    let bank = single_rail_bank(&object_ball, Pocket::CenterRight, Rail::Left, &table).unwrap();
+    let blocker = Ball {
+        ty: BallType::Three,
+        position: Position::new("2", "4"),
+        spec: BallSpec::default(),
+    };
+    let bystander = Ball {
+        ty: BallType::Five,
+        position: Position::new("2", "7"),
+        spec: BallSpec::default(),
+    };
+    let balls = [object_ball, blocker, bystander];
+
+    let obstructions = bank.obstructions(&balls, &TYPICAL_BALL_RADIUS, &table);
+
+    assert_eq!(
+        obstructions,
+        vec![BankObstruction {
+            leg: 1,
+            ball: BallType::Three,
+        }]
+    );
+    assert!(!bank.is_clear(&balls, &Inches::from_f64(1.125), &table));
+    assert!(bank.is_clear(&balls[..1], &TYPICAL_BALL_RADIUS, &table));
+}
+
+#[test]
+fn the_bank_overlay_draws_each_leg_and_labels_each_contact() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("1", "2");
+    let bank = multi_rail_bank(
+        &object_ball,
+        Pocket::TopRight,
+        &[Rail::Right, Rail::Left],
+        &table,
+    )
+    .unwrap();
+    let mut game_state = GameState::new(table);
+
+    game_state.add_bank_shot_overlay(
+        &bank,
+        &DashedLineStyle::new(Rgba([255, 255, 255, 255])),
+        &LabelOverlayStyle::enabled(Rgba([255, 255, 0, 255])),
+    );
+
+    let scene = game_state.to_diagram_scene(&DiagramRenderOptions::default());
+    assert_eq!(scene.elements.len(), 5);
+}
@@ -0,0 +1,258 @@
+use billiards::racks::RackTemplate;
+use billiards::simulation::{
+    simulate_break, simulate_break_with_config, BreakShot, SimulationConfig, SimulationError,
+    RACK_GAP_INCHES,
+};
+use billiards::{
+    rack_9_ball, Angle, BallType, CueTipContact, Diamond, GameState, GameType, InchesPerSecond,
+    Pocket, Position, Shot, TableSpec, HEAD_SPOT, RACK_SPOT, TYPICAL_BALL_RADIUS,
+};
+
+const INCHES_PER_DIAMOND: f64 = 12.5;
+
+fn as_f64(value: &Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn distance_inches(a: &Position, b: &Position) -> f64 {
+    (as_f64(&a.x) - as_f64(&b.x)).hypot(as_f64(&a.y) - as_f64(&b.y)) * INCHES_PER_DIAMOND
+}
+
+fn break_shot(speed: &str) -> BreakShot {
+    BreakShot::head_spot(InchesPerSecond::new(speed)).expect("the head spot is in the kitchen")
+}
+
+fn straight_down(speed: &str) -> Shot {
+    Shot::new(
+        Angle::from_north(0.0, -1.0),
+        InchesPerSecond::new(speed),
+        CueTipContact::center(),
+    )
+    .expect("shot should validate")
+}
+
+/// Stop right after the cue ball reaches the rack, which is all the rack tests need.
+fn rack_only_config() -> SimulationConfig {
+    SimulationConfig {
+        max_events: Some(1),
+        ..SimulationConfig::default()
+    }
+}
+
+fn rack_for_seed(seed: u64) -> GameState {
+    simulate_break_with_config(
+        GameType::NineBall,
+        &break_shot("300"),
+        seed,
+        &rack_only_config(),
+    )
+    .expect("nine-ball should rack")
+    .rack
+}
+
+fn positions(state: &GameState) -> Vec<(BallType, Position)> {
+    state
+        .balls()
+        .iter()
+        .map(|ball| (ball.ty.clone(), ball.position.clone()))
+        .collect()
+}
+
+#[test]
+fn the_same_seed_racks_the_same_way_and_another_seed_does_not() {
+    assert_eq!(positions(&rack_for_seed(7)), positions(&rack_for_seed(7)));
+    assert_ne!(positions(&rack_for_seed(7)), positions(&rack_for_seed(8)));
+}
+
+#[test]
+fn a_loosened_rack_keeps_every_ball_near_its_spot_without_overlaps() {
+    let frozen = rack_9_ball();
+    let diameter = 2.0 * TYPICAL_BALL_RADIUS.as_f64();
+
+    for seed in 0..10 {
+        let rack = rack_for_seed(seed);
+        let balls = rack.balls();
+        assert_eq!(balls.len(), frozen.len() + 1);
+        assert_eq!(rack.ty, GameType::NineBall);
+        assert_eq!(
+            rack.select_ball(BallType::Cue).unwrap().position,
+            *HEAD_SPOT
+        );
+
+        for (ball, spot) in balls.iter().zip(&frozen) {
+            assert_eq!(ball.ty, spot.ty);
+            // The spread grows with distance from the apex: the back ball, four radii times root
+            // three out, spreads by 2√3 gaps, plus up to half a gap of jitter.
+            assert!(
+                distance_inches(&ball.position, &spot.position) < 4.0 * RACK_GAP_INCHES,
+                "seed {seed}: {:?} moved too far",
+                ball.ty
+            );
+        }
+        for (index, a) in balls.iter().enumerate() {
+            for b in &balls[index + 1..] {
+                assert!(
+                    distance_inches(&a.position, &b.position) >= diameter - 1e-9,
+                    "seed {seed}: {:?} and {:?} overlap",
+                    a.ty,
+                    b.ty
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn a_loosened_rack_is_no_longer_frozen() {
+    let rack = rack_for_seed(3);
+    let balls = rack.balls();
+    let diameter = 2.0 * TYPICAL_BALL_RADIUS.as_f64();
+
+    let apex = &balls[0];
+    let widest_gap = balls[1..3]
+        .iter()
+        .map(|ball| distance_inches(&apex.position, &ball.position) - diameter)
+        .fold(0.0, f64::max);
+    assert!(widest_gap > 0.0);
+    assert!(widest_gap <= 2.0 * RACK_GAP_INCHES + 1e-9);
+}
+
+#[test]
+fn games_without_a_rack_cannot_break() {
+    for game in [GameType::OnePocket, GameType::Banks] {
+        assert_eq!(
+            simulate_break(game.clone(), &break_shot("300"), 0).err(),
+            Some(SimulationError::UnsupportedRack(game))
+        );
+    }
+}
+
+#[test]
+fn a_break_reports_what_it_made_and_where_the_cue_ball_stopped() {
+    let result = simulate_break(GameType::NineBall, &break_shot("350"), 4).unwrap();
+
+    let stats = &result.stats;
+    assert_eq!(
+        stats.object_balls_pocketed,
+        vec![(BallType::Four, Pocket::BottomLeft)]
+    );
+    assert!(stats.made_ball);
+    assert!(!stats.made_game_ball);
+    assert!(!stats.scratched);
+    assert_eq!(
+        stats.cue_ball.as_ref(),
+        result
+            .simulation
+            .final_state
+            .select_ball(BallType::Cue)
+            .map(|ball| &ball.position)
+    );
+    assert!(result
+        .simulation
+        .final_state
+        .select_ball(BallType::Four)
+        .is_none());
+    assert_eq!(result.simulation.final_state.balls().len(), 9);
+}
+
+#[test]
+fn the_head_spot_break_goes_straight_down_the_table() {
+    let head_spot = break_shot("300");
+
+    assert_eq!(head_spot.cue_ball(), &*HEAD_SPOT);
+    assert_eq!(
+        head_spot.shot().heading().as_degrees(),
+        straight_down("300").heading().as_degrees()
+    );
+}
+
+#[test]
+fn a_break_must_start_in_the_kitchen() {
+    let table = TableSpec::default();
+
+    for cue_ball in [
+        Position::new("2", "5.9"),
+        Position::new("0", "7"),
+        Position::new("4", "7"),
+        Position::new("2", "8"),
+    ] {
+        assert_eq!(
+            BreakShot::new(cue_ball.clone(), straight_down("300"), &table),
+            Err(SimulationError::CueBallOutsideKitchen(cue_ball))
+        );
+    }
+    for cue_ball in [Position::new("0.1", "6"), Position::new("3.9", "7.9")] {
+        assert!(BreakShot::new(cue_ball, straight_down("300"), &table).is_ok());
+    }
+}
+
+#[test]
+fn the_cut_break_comes_from_the_side_rail_into_the_side_of_the_one() {
+    let cut = BreakShot::nine_ball_cut_break(InchesPerSecond::new("300")).unwrap();
+    let cue_ball = cut.cue_ball();
+
+    assert!(as_f64(&cue_ball.x) > 3.0);
+    assert_eq!(as_f64(&cue_ball.y), 6.0);
+    let straight_at_the_one = cue_ball.angle_to(&RACK_SPOT).as_degrees();
+    let aim = cut.shot().heading().as_degrees();
+    assert!(
+        (aim - straight_at_the_one).abs() > 0.1,
+        "{aim} vs {straight_at_the_one}"
+    );
+
+    let result = simulate_break(GameType::NineBall, &cut, 0).unwrap();
+    let apex_contact = result
+        .simulation
+        .first_contact()
+        .expect("the cue ball should reach the rack");
+    assert_eq!(apex_contact, BallType::One);
+    assert_eq!(
+        result.rack.select_ball(BallType::Cue).unwrap().position,
+        *cue_ball
+    );
+}
+
+#[test]
+fn the_second_ball_break_slips_past_the_apex_into_the_second_row() {
+    let second_ball = BreakShot::eight_ball_second_ball_break(InchesPerSecond::new("300")).unwrap();
+
+    assert_eq!(second_ball.cue_ball(), &Position::new("3", "6"));
+    let result =
+        simulate_break_with_config(GameType::NineBall, &second_ball, 0, &rack_only_config())
+            .unwrap();
+    // The second-row ball on the right of a nine-ball rack.
+    assert_eq!(result.simulation.first_contact(), Some(BallType::Three));
+}
+
+#[test]
+fn eight_and_ten_ball_break_from_their_own_racks() {
+    let second_ball = BreakShot::eight_ball_second_ball_break(InchesPerSecond::new("300")).unwrap();
+    let eight_ball =
+        simulate_break_with_config(GameType::EightBall, &second_ball, 0, &rack_only_config())
+            .unwrap();
+
+    assert_eq!(eight_ball.rack.ty, GameType::EightBall);
+    assert_eq!(eight_ball.rack.balls().len(), 16);
+    let eight = &eight_ball
+        .rack
+        .select_ball(BallType::Eight)
+        .unwrap()
+        .position;
+    let expected = RackTemplate::eight_ball().rack(0).unwrap()[4]
+        .position
+        .clone();
+    assert!(distance_inches(eight, &expected) < 4.0 * RACK_GAP_INCHES);
+    // The second-row ball on the right of the triangle, filled lowest number first.
+    assert_eq!(eight_ball.simulation.first_contact(), Some(BallType::Four));
+
+    let ten_ball = simulate_break(GameType::TenBall, &break_shot("300"), 0).unwrap();
+    assert_eq!(ten_ball.rack.balls().len(), 11);
+    assert_eq!(
+        ten_ball.stats.made_game_ball,
+        ten_ball
+            .stats
+            .object_balls_pocketed
+            .iter()
+            .any(|(ty, _)| *ty == BallType::Ten)
+    );
+}
@@ -0,0 +1,100 @@
+use billiards::aiming::{natural_roll_deflection, plan_carom, CaromCueAction, CutDirection};
+use billiards::{Ball, BallSpec, BallType, CutAngle, Position, TableSpec};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn object_ball_at(x: &str, y: &str) -> Ball {
+    Ball {
+        ty: BallType::Eight,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn a_stun_carom_sends_the_cue_ball_along_the_tangent_line_to_the_destination() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new(2u8, 2u8);
+    let object_ball = object_ball_at("2", "5");
+    let destination = Position::new("0.5", "5.5");
+
+    let shots = plan_carom(
+        &cue_ball,
+        &object_ball,
+        &destination,
+        CaromCueAction::Stun,
+        &table,
+    );
+
+    assert_eq!(shots.len(), 1);
+    let shot = &shots[0];
+    assert_eq!(shot.direction, CutDirection::Right);
+    assert_close_with_tolerance(
+        shot.cue_ball_heading.as_degrees(),
+        shot.ghost_ball.angle_to(&destination).as_degrees(),
+        1e-6,
+    );
+    assert_close_with_tolerance(
+        CutAngle::from_headings(shot.cue_ball_heading, shot.object_ball_heading).as_degrees(),
+        90.0,
+        1e-6,
+    );
+    assert_close_with_tolerance(
+        shot.hit_fraction.as_f64(),
+        shot.cut_angle.hit_fraction().as_f64(),
+        1e-12,
+    );
+}
+
+#[test]
+fn a_natural_roll_carom_uses_the_rolling_deflection_from_the_aim_line() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new(2u8, 2u8);
+    let object_ball = object_ball_at("2", "5");
+    let destination = Position::new("3.5", "7");
+
+    let shots = plan_carom(
+        &cue_ball,
+        &object_ball,
+        &destination,
+        CaromCueAction::NaturalRoll,
+        &table,
+    );
+
+    assert!(!shots.is_empty());
+    for shot in &shots {
+        let aim = cue_ball.angle_to(&shot.ghost_ball);
+        assert_close_with_tolerance(
+            CutAngle::from_headings(aim, shot.cue_ball_heading).as_degrees(),
+            natural_roll_deflection(shot.cut_angle).as_degrees(),
+            1e-6,
+        );
+        assert_close_with_tolerance(
+            shot.cue_ball_heading.as_degrees(),
+            shot.ghost_ball.angle_to(&destination).as_degrees(),
+            1e-6,
+        );
+    }
+    assert_eq!(shots[0].direction, CutDirection::Left);
+}
+
+#[test]
+fn a_stunned_cue_ball_cannot_carom_backward() {
+    let table = TableSpec::default();
+
+    let shots = plan_carom(
+        &Position::new(2u8, 2u8),
+        &object_ball_at("2", "5"),
+        &Position::new("2", "0.5"),
+        CaromCueAction::Stun,
+        &table,
+    );
+
+    assert!(shots.is_empty());
+}
@@ -0,0 +1,89 @@
+use billiards::aiming::check_combination;
+use billiards::{Ball, BallSpec, BallType, Pocket, Position, TableSpec};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn as_f64(value: &billiards::Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn a_lined_up_combination_is_on_with_a_straight_ghost_ball() {
+    let table = TableSpec::default();
+    let balls = [
+        ball_at(BallType::One, "2", "4"),
+        ball_at(BallType::Nine, "3", "4"),
+    ];
+
+    let check = check_combination(&balls, Pocket::CenterRight, &table);
+
+    assert!(check.is_on(1e-6));
+    assert_close_with_tolerance(check.max_error_degrees(), 0.0, 1e-6);
+    assert_eq!(check.links.len(), 1);
+    assert!(!check.links[0].frozen);
+    assert_close_with_tolerance(check.links[0].required_cut.as_degrees(), 0.0, 1e-6);
+    assert_close_with_tolerance(as_f64(&check.ghost_ball.x), 2.0 - 2.25 / 12.5, 1e-9);
+    assert_close_with_tolerance(as_f64(&check.ghost_ball.y), 4.0, 1e-9);
+}
+
+#[test]
+fn a_frozen_pair_pointing_away_from_the_pocket_is_off_by_its_line_of_centers_error() {
+    let table = TableSpec::default();
+    let balls = [
+        ball_at(BallType::One, "2", "4"),
+        ball_at(BallType::Nine, "2", "4.18"),
+    ];
+
+    let check = check_combination(&balls, Pocket::CenterRight, &table);
+
+    let link = &check.links[0];
+    assert!(link.frozen);
+    assert!(!check.is_on(5.0));
+    assert!(link.line_of_centers_error_degrees > 80.0);
+    assert_eq!(check.aiming_lines[0].end, balls[1].position);
+}
+
+#[test]
+fn a_three_ball_chain_draws_one_aiming_line_per_ball_ending_at_the_pocket() {
+    let table = TableSpec::default();
+    let balls = [
+        ball_at(BallType::Two, "1.5", "3"),
+        ball_at(BallType::Five, "2.2", "4.5"),
+        ball_at(BallType::Nine, "3", "6"),
+    ];
+
+    let check = check_combination(&balls, Pocket::TopRight, &table);
+
+    assert_eq!(check.links.len(), 2);
+    assert_eq!(check.aiming_lines.len(), 3);
+    assert_eq!(check.aiming_lines[2].end, Pocket::TopRight.aiming_center());
+    for (line, ball) in check.aiming_lines.iter().zip(&balls) {
+        assert_eq!(line.start, ball.position);
+    }
+    // Each ball's path ends one ball diameter short of the next ball's center.
+    let diameter_in_diamonds = 2.25 / 12.5;
+    assert_close_with_tolerance(
+        as_f64(
+            &check.aiming_lines[0]
+                .end
+                .displacement(&balls[1].position)
+                .absolute_distance(),
+        ),
+        diameter_in_diamonds,
+        1e-9,
+    );
+}
@@ -0,0 +1,210 @@
+use billiards::simulation::{resolve_cue_ball_hit, CueBallHit, CueBallSpin, SimulationConfig};
+use billiards::{
+    Angle, BallPathStop, Diamond, InchesPerSecond, Position, Scale, Seconds, TableSpec,
+};
+
+fn as_f64(value: &Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+/// The farthest any point strays from the straight line between the first and last points.
+fn max_offset_from_chord(points: &[Position]) -> f64 {
+    let xy = |point: &Position| (as_f64(&point.x), as_f64(&point.y));
+    let (x0, y0) = xy(&points[0]);
+    let (x1, y1) = xy(points.last().unwrap());
+    let length = (x1 - x0).hypot(y1 - y0);
+    points
+        .iter()
+        .map(|point| {
+            let (x, y) = xy(point);
+            ((x - x0) * (y1 - y0) - (y - y0) * (x1 - x0)).abs() / length
+        })
+        .fold(0.0, f64::max)
+}
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn signed_degrees(from: &Angle, to: &Angle) -> f64 {
+    (to.as_degrees() - from.as_degrees() + 540.0).rem_euclid(360.0) - 180.0
+}
+
+fn north() -> Angle {
+    Angle::from_north(0.0, 1.0)
+}
+
+/// Hit the object ball on (2, 4) heading north at 60 in/s, with the line of centers cut 30° to
+/// the right.
+fn thirty_degree_cut(spin: CueBallSpin) -> CueBallHit {
+    resolve_cue_ball_hit(
+        &Position::new("1.91", "3.844115"),
+        &Position::new("2", "4"),
+        &north(),
+        InchesPerSecond::new("60"),
+        &spin,
+        &SimulationConfig::default(),
+        &TableSpec::default(),
+    )
+    .expect("the cue ball is heading into the object ball")
+}
+
+#[test]
+fn a_full_stun_hit_sends_the_object_ball_straight_and_stops_the_cue_ball() {
+    let hit = resolve_cue_ball_hit(
+        &Position::new("2", "3.82"),
+        &Position::new("2", "4"),
+        &north(),
+        InchesPerSecond::new("60"),
+        &CueBallSpin::stun(),
+        &SimulationConfig::default(),
+        &TableSpec::default(),
+    )
+    .unwrap();
+
+    assert_close_with_tolerance(hit.throw_degrees, 0.0, 1e-9);
+    assert_close_with_tolerance(
+        signed_degrees(&north(), &hit.object_ball_heading),
+        0.0,
+        1e-9,
+    );
+    assert!(hit.object_ball_speed.as_f64() > 0.9 * 60.0);
+    assert!(hit.cue_ball_speed.as_f64() < 0.05 * 60.0);
+}
+
+#[test]
+fn a_stun_cut_throws_the_object_ball_toward_the_cue_balls_path() {
+    let hit = thirty_degree_cut(CueBallSpin::stun());
+
+    assert!(
+        (1.0..5.0).contains(&hit.throw_degrees),
+        "{}",
+        hit.throw_degrees
+    );
+    // The object ball leaves a little left of the 30° line of centers.
+    assert_close_with_tolerance(
+        signed_degrees(&north(), &hit.object_ball_heading),
+        30.0 - hit.throw_degrees,
+        1e-3,
+    );
+    // And the stunned cue ball leaves along the tangent line, give or take the throw.
+    let cue_heading = hit.cue_ball_heading.unwrap();
+    assert_close_with_tolerance(signed_degrees(&north(), &cue_heading), -60.0, 5.0);
+    // Contact friction leaves it a little spin, but nothing like follow or draw.
+    if let Some(after_bend) = hit.cue_ball_heading_after_bend {
+        assert_close_with_tolerance(signed_degrees(&cue_heading, &after_bend), 0.0, 3.0);
+    }
+}
+
+#[test]
+fn follow_and_draw_throw_less_than_stun() {
+    let stun = thirty_degree_cut(CueBallSpin::stun()).throw_degrees;
+    let follow = thirty_degree_cut(CueBallSpin::natural_roll()).throw_degrees;
+    let draw =
+        thirty_degree_cut(CueBallSpin::new(Scale::from_f64(-1.0), Scale::zero())).throw_degrees;
+
+    assert!(
+        0.0 < follow && follow < stun,
+        "follow {follow}, stun {stun}"
+    );
+    assert!(0.0 < draw && draw < stun, "draw {draw}, stun {stun}");
+}
+
+#[test]
+fn follow_bends_the_cue_ball_forward_and_draw_bends_it_back() {
+    let tangent = signed_degrees(
+        &north(),
+        &thirty_degree_cut(CueBallSpin::stun())
+            .cue_ball_heading
+            .unwrap(),
+    );
+    let after_bend = |spin: CueBallSpin| {
+        signed_degrees(
+            &north(),
+            &thirty_degree_cut(spin).cue_ball_heading_after_bend.unwrap(),
+        )
+    };
+
+    let follow = after_bend(CueBallSpin::natural_roll());
+    let draw = after_bend(CueBallSpin::new(Scale::from_f64(-1.0), Scale::zero()));
+
+    assert!(
+        follow > tangent + 10.0,
+        "follow {follow}, tangent {tangent}"
+    );
+    assert!(draw < tangent - 10.0, "draw {draw}, tangent {tangent}");
+}
+
+#[test]
+fn geared_outside_english_cancels_cut_throw() {
+    // Outside english on a cut to the right is left english, geared at sin(30°).
+    let geared = thirty_degree_cut(CueBallSpin::new(Scale::zero(), Scale::from_f64(-0.5)));
+    let overspun = thirty_degree_cut(CueBallSpin::new(Scale::zero(), Scale::from_f64(-0.9)));
+
+    assert_close_with_tolerance(geared.throw_degrees, 0.0, 0.05);
+    assert!(overspun.throw_degrees < 0.0);
+}
+
+#[test]
+fn a_cue_ball_moving_away_does_not_hit() {
+    assert!(resolve_cue_ball_hit(
+        &Position::new("2", "3.82"),
+        &Position::new("2", "4"),
+        &Angle::from_north(0.0, -1.0),
+        InchesPerSecond::new("60"),
+        &CueBallSpin::stun(),
+        &SimulationConfig::default(),
+        &TableSpec::default(),
+    )
+    .is_none());
+}
+
+#[test]
+fn follow_and_draw_curve_the_cue_ball_path_where_stun_stays_straight() {
+    let config = SimulationConfig::default();
+    let table = TableSpec::default();
+    let path_points = |spin: CueBallSpin| {
+        thirty_degree_cut(spin)
+            .cue_ball_path(BallPathStop::Duration(Seconds::new(0.5)), &config, &table)
+            .curve_sampled_points(
+                Seconds::new(0.005),
+                &config.ball_set,
+                &config.motion,
+                &table,
+            )
+    };
+
+    let stun = path_points(CueBallSpin::stun());
+    let follow = path_points(CueBallSpin::natural_roll());
+    let draw = path_points(CueBallSpin::new(Scale::from_f64(-1.0), Scale::zero()));
+
+    assert!(max_offset_from_chord(&stun) < 0.02);
+    assert!(max_offset_from_chord(&follow) > 0.03);
+    assert!(max_offset_from_chord(&draw) > 0.03);
+    // Follow carries the cue ball on up the table; draw pulls it back.
+    let end_y = |points: &[Position]| as_f64(&points.last().unwrap().y);
+    assert!(end_y(&follow) > end_y(&stun) && end_y(&stun) > end_y(&draw));
+}
+
+#[test]
+fn curve_sampling_only_densifies_the_sliding_part_of_the_path() {
+    let config = SimulationConfig::default();
+    let table = TableSpec::default();
+    let path = thirty_degree_cut(CueBallSpin::new(Scale::from_f64(-1.0), Scale::zero()))
+        .cue_ball_path(BallPathStop::UntilRest, &config, &table);
+    let step = Seconds::new(0.005);
+
+    let curve = path.curve_sampled_points(step, &config.ball_set, &config.motion, &table);
+    let uniform = path.sampled_points(step, &config.ball_set, &config.motion, &table);
+    let vertices = path.projected_points(&table);
+
+    assert!(curve.len() > vertices.len() + 10);
+    assert!(curve.len() < uniform.len());
+    assert_eq!(curve.first(), vertices.first());
+    assert_eq!(curve.last(), vertices.last());
+    assert_close_with_tolerance(as_f64(&curve[0].x), 1.91, 1e-9);
+}
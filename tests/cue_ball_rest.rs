@@ -0,0 +1,129 @@
+use billiards::simulation::{
+    predict_cue_ball_rest, predict_cue_ball_rest_with_skill, simulate, CueBallRestPrediction,
+    EventDrivenSimulator, SkillModel, DEFAULT_REST_SAMPLES,
+};
+use billiards::{
+    Angle, Ball, BallSpec, BallType, CueTipContact, Diamond, GameState, InchesPerSecond, Position,
+    Shot, TableSpec,
+};
+
+fn as_f64(value: &Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "expected {expected}, got {actual}"
+    );
+}
+
+fn cue_ball_at(x: &str, y: &str) -> GameState {
+    GameState::with_balls(
+        TableSpec::default(),
+        [Ball {
+            ty: BallType::Cue,
+            position: Position::new(x, y),
+            spec: BallSpec::default(),
+        }],
+    )
+}
+
+/// A soft stroke up the table that stops well short of the head rail.
+fn lag_up_the_table() -> (GameState, Shot) {
+    let shot = Shot::new(
+        Angle::from_north(0.0, 1.0),
+        InchesPerSecond::new("40"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+    (cue_ball_at("2", "2"), shot)
+}
+
+fn predict(state: &GameState, shot: &Shot, skill: &SkillModel) -> CueBallRestPrediction {
+    predict_cue_ball_rest_with_skill(&EventDrivenSimulator::default(), state, shot, skill, 8, 3)
+        .expect("shot should simulate")
+}
+
+#[test]
+fn the_predicted_rest_is_where_the_intended_stroke_stops() {
+    let (state, shot) = lag_up_the_table();
+
+    let prediction = predict(&state, &shot, &SkillModel::perfect());
+
+    let simulated = simulate(&state, &shot).unwrap();
+    let rest = prediction.rest.expect("cue ball should stay on the table");
+    assert_eq!(
+        Some(&rest),
+        simulated
+            .final_state
+            .select_ball(BallType::Cue)
+            .map(|ball| &ball.position)
+    );
+    assert_close_with_tolerance(as_f64(&rest.x), 2.0, 1e-9);
+    assert!(as_f64(&rest.y) > 2.5 && as_f64(&rest.y) < 7.5);
+
+    let spread = prediction.spread.expect("every stroke stayed on the table");
+    assert_close_with_tolerance(spread.semi_major.as_f64(), 0.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&spread.center.y), as_f64(&rest.y), 1e-9);
+}
+
+#[test]
+fn speed_error_stretches_the_spread_along_the_line_of_the_shot() {
+    let (state, shot) = lag_up_the_table();
+
+    let spread = predict(&state, &shot, &SkillModel::new(0.0, 0.1))
+        .spread
+        .unwrap();
+
+    assert!(spread.semi_major.as_f64() > 1.0, "{spread:?}");
+    assert!(spread.semi_minor.as_f64() < 1e-6, "{spread:?}");
+    assert_close_with_tolerance(spread.major_axis.as_degrees(), 0.0, 1e-6);
+}
+
+#[test]
+fn aim_error_stretches_the_spread_across_the_line_of_the_shot() {
+    let (state, shot) = lag_up_the_table();
+
+    let spread = predict(&state, &shot, &SkillModel::new(2.0, 0.0))
+        .spread
+        .unwrap();
+
+    assert!(
+        spread.semi_major.as_f64() > 2.0 * spread.semi_minor.as_f64(),
+        "{spread:?}"
+    );
+    assert_close_with_tolerance(spread.major_axis.as_degrees(), 90.0, 2.0);
+}
+
+#[test]
+fn scratched_strokes_have_no_rest_and_no_spread() {
+    let state = cue_ball_at("1", "4");
+    let shot = Shot::toward(
+        &Position::new("1", "4"),
+        &Position::new("4", "4"),
+        InchesPerSecond::new("150"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+
+    let prediction = predict(&state, &shot, &SkillModel::perfect());
+
+    assert_eq!(prediction.rest, None);
+    assert_eq!(prediction.spread, None);
+    assert_eq!(prediction.samples, 8);
+    assert_eq!(prediction.scratched, 8);
+}
+
+#[test]
+fn the_default_prediction_samples_a_club_player() {
+    let (state, shot) = lag_up_the_table();
+
+    let prediction = predict_cue_ball_rest(&state, &shot).unwrap();
+
+    assert_eq!(prediction.samples, DEFAULT_REST_SAMPLES);
+    assert_eq!(prediction.scratched, 0);
+    let spread = prediction.spread.unwrap();
+    assert!(spread.semi_major.as_f64() > 0.0);
+    assert!(spread.semi_minor.as_f64() > 0.0);
+}
@@ -0,0 +1,145 @@
+use bigdecimal::{BigDecimal, RoundingMode};
+use billiards::simulation::{
+    simulate_with_config, SimulationConfig, SimulationError, SimulationResult, TimeSolver,
+    MIN_DECIMAL_PLACES,
+};
+use billiards::{
+    round_n_ball_system_states, Ball, BallSpec, BallType, CueTipContact, Diamond, GameState,
+    InchesPerSecond, NBallSystemState, Position, Shot, TableSpec,
+};
+
+const DECIMAL_PLACES: u32 = 12;
+
+fn as_f64(value: &Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+/// A cut into the one with a second object ball in the cue ball's way afterwards.
+fn cut_and_carom() -> (GameState, Shot) {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "2"),
+            ball_at(BallType::One, "2", "4"),
+            ball_at(BallType::Two, "0.8", "4.6"),
+        ],
+    );
+    let shot = Shot::toward(
+        &Position::new("1", "2"),
+        &Position::new("1.92", "4"),
+        InchesPerSecond::new("90"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+    (state, shot)
+}
+
+fn simulate_in(time_solver: TimeSolver) -> SimulationResult {
+    let (state, shot) = cut_and_carom();
+    let config = SimulationConfig {
+        time_solver,
+        ..SimulationConfig::default()
+    };
+    simulate_with_config(&state, &shot, &config).unwrap()
+}
+
+fn is_rounded(value: &BigDecimal) -> bool {
+    *value == value.with_scale_round(DECIMAL_PLACES.into(), RoundingMode::HalfEven)
+}
+
+fn on_table_positions(states: &[NBallSystemState]) -> Vec<(BigDecimal, BigDecimal)> {
+    states
+        .iter()
+        .filter_map(|state| state.as_on_table())
+        .map(|state| {
+            let position = &state.as_ball_state().position;
+            (
+                position.x().magnitude.clone(),
+                position.y().magnitude.clone(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn a_decimal_run_leaves_every_ball_on_the_rounding_grid() {
+    let result = simulate_in(TimeSolver::Decimal {
+        decimal_places: DECIMAL_PLACES,
+    });
+
+    assert!(result.events().len() > 2);
+    for (x, y) in on_table_positions(&result.system.states) {
+        assert!(is_rounded(&x) && is_rounded(&y), "({x}, {y})");
+    }
+    for event in result.events() {
+        let scaled = event.time().as_f64() * 10f64.powi(DECIMAL_PLACES as i32);
+        assert!((scaled - scaled.round()).abs() < 1e-3, "{event:?}");
+    }
+}
+
+#[test]
+fn decimal_runs_repeat_exactly() {
+    let solver = TimeSolver::Decimal {
+        decimal_places: DECIMAL_PLACES,
+    };
+
+    let first = simulate_in(solver);
+    let second = simulate_in(solver);
+
+    assert_eq!(first.system.states, second.system.states);
+    assert_eq!(first.events(), second.events());
+}
+
+#[test]
+fn a_decimal_run_plays_the_same_shot_as_the_float_engine() {
+    let float = simulate_in(TimeSolver::Float);
+    let decimal = simulate_in(TimeSolver::Decimal {
+        decimal_places: DECIMAL_PLACES,
+    });
+
+    assert_eq!(float.pocketed, decimal.pocketed);
+    assert_eq!(float.events().len(), decimal.events().len());
+    for ball in [BallType::Cue, BallType::One, BallType::Two] {
+        let (Some(float_ball), Some(decimal_ball)) = (
+            float.final_state.select_ball(ball.clone()),
+            decimal.final_state.select_ball(ball),
+        ) else {
+            continue;
+        };
+        assert!((as_f64(&float_ball.position.x) - as_f64(&decimal_ball.position.x)).abs() < 1e-6);
+        assert!((as_f64(&float_ball.position.y) - as_f64(&decimal_ball.position.y)).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn rounding_states_twice_changes_nothing() {
+    let result = simulate_in(TimeSolver::Float);
+
+    let once = round_n_ball_system_states(&result.system.states, 6);
+    let twice = round_n_ball_system_states(&once, 6);
+
+    assert_eq!(once, twice);
+}
+
+#[test]
+fn the_decimal_solver_refuses_to_round_coarser_than_a_nanosecond() {
+    let (state, shot) = cut_and_carom();
+    let config = |decimal_places| SimulationConfig {
+        time_solver: TimeSolver::Decimal { decimal_places },
+        ..SimulationConfig::default()
+    };
+
+    assert!(matches!(
+        simulate_with_config(&state, &shot, &config(MIN_DECIMAL_PLACES - 1)),
+        Err(SimulationError::TooFewDecimalPlaces(places)) if places == MIN_DECIMAL_PLACES - 1
+    ));
+    assert!(simulate_with_config(&state, &shot, &config(MIN_DECIMAL_PLACES)).is_ok());
+}
@@ -0,0 +1,156 @@
+use billiards::diagram::render_batch;
+use billiards::pdf::PdfPageLayout;
+use billiards::sheet::Sheet;
+use billiards::{Ball, BallSpec, BallType, DiagramRenderOptions, GameState, Position, TableSpec};
+use image::{load_from_memory, Rgba};
+
+fn cue_ball_at(x: &str, y: &str) -> GameState {
+    GameState::with_balls(
+        TableSpec::default(),
+        [Ball {
+            ty: BallType::Cue,
+            position: Position::new(x, y),
+            spec: BallSpec::default(),
+        }],
+    )
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[test]
+fn a_sheet_lays_diagrams_out_in_a_grid_with_captions_beneath() {
+    let options = DiagramRenderOptions {
+        max_width_px: Some(200),
+        ..DiagramRenderOptions::default()
+    };
+    let diagram = cue_ball_at("2", "4").render_to_image_with_options(&options);
+    let (width, height) = diagram.dimensions();
+    let sheet =
+        ["ONE", "TWO", "THREE"]
+            .into_iter()
+            .fold(Sheet::new(2).with_gap(10), |sheet, caption| {
+                sheet.with_diagram(cue_ball_at("2", "4").to_diagram_scene(&options), caption)
+            });
+
+    let image = sheet.render_to_image(&options);
+
+    // Captions take a band 11 dots of the 4 px caption font tall under each row.
+    let band = 44;
+    assert_eq!(
+        image.dimensions(),
+        (2 * width + 3 * 10, 2 * (height + band) + 3 * 10)
+    );
+    assert_eq!(image.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+    let (x, y) = (width / 2, height / 4);
+    assert_eq!(
+        image.get_pixel(10 + width + 10 + x, 10 + y),
+        diagram.get_pixel(x, y)
+    );
+    let second_row = 10 + height + band + 10;
+    assert_eq!(
+        image.get_pixel(10 + x, second_row + y),
+        diagram.get_pixel(x, y)
+    );
+
+    let inked = |left: u32, top: u32| {
+        (left..left + width)
+            .flat_map(|x| (top..top + band).map(move |y| (x, y)))
+            .filter(|&(x, y)| image.get_pixel(x, y)[0] < 100)
+            .count()
+    };
+    assert!(inked(10, 10 + height) > 0);
+    assert!(inked(10, second_row + height) > inked(10, 10 + height));
+    assert_eq!(inked(10 + width + 10, second_row + height), 0);
+}
+
+#[test]
+fn a_sheet_without_captions_leaves_no_caption_band() {
+    let options = DiagramRenderOptions::default();
+    let sheet = Sheet::new(3)
+        .with_gap(0)
+        .with_diagram(cue_ball_at("1", "4").to_diagram_scene(&options), "")
+        .with_diagram(cue_ball_at("3", "4").to_diagram_scene(&options), "");
+
+    let image = sheet.render_to_image(&options);
+
+    assert_eq!(image.dimensions(), (3 * 1089, 1938));
+}
+
+#[test]
+fn a_sheet_prints_as_one_pdf_page() {
+    let options = DiagramRenderOptions {
+        max_width_px: Some(200),
+        ..DiagramRenderOptions::default()
+    };
+    let sheet = Sheet::new(2)
+        .with_diagram(cue_ball_at("1", "4").to_diagram_scene(&options), "A")
+        .with_diagram(cue_ball_at("3", "4").to_diagram_scene(&options), "B");
+    let (width, height) = sheet.render_to_image(&options).dimensions();
+
+    let pdf = sheet.render_to_pdf(&options, &PdfPageLayout::a4());
+
+    assert!(pdf.starts_with(b"%PDF-1.4\n") && pdf.ends_with(b"%%EOF\n"));
+    assert!(find(&pdf, b"/Type /Pages /Kids [3 0 R] /Count 1").is_some());
+    assert!(find(&pdf, format!("/Width {width} /Height {height}").as_bytes()).is_some());
+    let png = load_from_memory(&sheet.render_to_png(&options)).expect("png decode");
+    assert_eq!((png.width(), png.height()), (width, height));
+}
+
+#[test]
+fn before_and_after_diagrams_share_a_caption_with_an_arrow_between_them() {
+    let options = DiagramRenderOptions {
+        max_width_px: Some(200),
+        ..DiagramRenderOptions::default()
+    };
+    let (before, after) = (cue_ball_at("2", "2"), cue_ball_at("2", "6"));
+    let after_image = after.render_to_image_with_options(&options);
+    let (width, height) = after_image.dimensions();
+
+    let png = before.draw_before_and_after_diagram(&after, "STOP SHOT", &options);
+    let image = load_from_memory(&png).expect("png decode").into_rgba8();
+
+    // Default 48 px gaps, and one 44 px caption band under the pair.
+    assert_eq!(
+        image.dimensions(),
+        (2 * width + 3 * 48, height + 2 * 48 + 44)
+    );
+    let (x, y) = (width / 2, height / 4);
+    assert_eq!(
+        image.get_pixel(48 + width + 48 + x, 48 + y),
+        after_image.get_pixel(x, y)
+    );
+    let ink = Rgba([17, 17, 17, 255]);
+    assert_eq!(image.get_pixel(48 + width + 24, 48 + height / 2), &ink);
+    assert_eq!(
+        image.get_pixel(48 + width + 24, 48 + height / 4),
+        &Rgba([255; 4])
+    );
+    let caption_band = image.height() - 44..image.height();
+    assert!(caption_band
+        .flat_map(|y| (0..image.width()).map(move |x| (x, y)))
+        .any(|(x, y)| image.get_pixel(x, y) == &ink));
+}
+
+#[test]
+fn a_batch_of_diagrams_comes_back_in_order_and_matches_rendering_each_alone() {
+    let options = DiagramRenderOptions {
+        max_width_px: Some(100),
+        ..DiagramRenderOptions::default()
+    };
+    let states: Vec<GameState> = ["1", "2", "3", "4", "5", "6", "7"]
+        .into_iter()
+        .map(|y| cue_ball_at("2", y))
+        .collect();
+
+    let pngs = render_batch(&states, &options);
+
+    assert_eq!(pngs.len(), states.len());
+    for (state, png) in states.iter().zip(&pngs) {
+        assert_eq!(png, &state.draw_2d_diagram_with_options(&options));
+    }
+    assert_ne!(pngs[0], pngs[6]);
+}
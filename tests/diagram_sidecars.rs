@@ -0,0 +1,120 @@
+use billiards::diagram::ScenePoint;
+use billiards::{
+    Ball, BallSpec, BallStyle, BallType, DiagramBackground, DiagramOrientation,
+    DiagramRenderOptions, GameState, Pocket, Position, TableSpec,
+};
+use image::Rgba;
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn annotated_layout() -> GameState {
+    let mut state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "2"),
+            ball_at(BallType::Nine, "3", "6"),
+        ],
+    );
+    state.add_dotted_line(
+        &Position::new("1", "2"),
+        &Position::new("3", "6"),
+        Rgba([255, 255, 255, 255]),
+    );
+    state.in_named_layer("notes", |state| {
+        state.add_sequence_marker(&Position::new("2", "4"), 1);
+    });
+    state
+}
+
+fn assert_near(point: ScenePoint, x: f32, y: f32) {
+    assert!(
+        (point.x - x).abs() < 0.01 && (point.y - y).abs() < 0.01,
+        "{point:?} is not ({x}, {y})"
+    );
+}
+
+#[test]
+fn a_sidecar_places_elements_where_the_diagram_draws_them() {
+    let state = annotated_layout();
+    let options = DiagramRenderOptions::default();
+    let scene = state.to_diagram_scene(&options);
+    let cue = scene
+        .viewport
+        .position_to_scene_point(&Position::new("1", "2"));
+
+    let sidecar = state.diagram_sidecar(&options);
+
+    assert_eq!((sidecar.width_px, sidecar.height_px), (1089, 1938));
+    assert_eq!(sidecar.balls[0].ty, BallType::Cue);
+    assert_near(sidecar.balls[0].center, cue.x, cue.y);
+    let kinds: Vec<_> = sidecar.annotations.iter().map(|a| a.kind).collect();
+    assert_eq!(kinds, ["dashed_line", "sequence_marker"]);
+    assert_near(sidecar.annotations[0].points[0], cue.x, cue.y);
+    assert_eq!(sidecar.pockets.len(), 6);
+    let top_left = &sidecar.pockets[5];
+    assert_eq!(top_left.pocket, Pocket::TopLeft);
+    assert!(top_left.center.x < 150.0 && top_left.center.y < 150.0);
+    assert_near(sidecar.pockets[4].center, 110.0, 969.0);
+
+    let hidden = state.diagram_sidecar(&options.with_hidden_layer("notes"));
+    assert_eq!(hidden.annotations.len(), 1);
+}
+
+#[test]
+fn a_sidecar_follows_the_orientation_scale_and_margin_of_the_output() {
+    let state = annotated_layout();
+    let options = DiagramRenderOptions {
+        scale_factor: 2,
+        orientation: DiagramOrientation::Landscape,
+        margin_px: 10,
+        background: DiagramBackground::Transparent,
+        ball_style: BallStyle::Procedural,
+        ..DiagramRenderOptions::default()
+    };
+    let portrait = state.diagram_sidecar(&DiagramRenderOptions::default());
+
+    let sidecar = state.diagram_sidecar(&options);
+    let image = state.render_to_image_with_options(&options);
+
+    assert_eq!(
+        (sidecar.width_px, sidecar.height_px),
+        (2 * 1938 + 20, 2 * 1089 + 20)
+    );
+    assert_eq!(image.dimensions(), (sidecar.width_px, sidecar.height_px));
+    let nine = &sidecar.balls[1];
+    let drawn = portrait.balls[1].center;
+    assert_near(
+        nine.center,
+        10.0 + 2.0 * (1938.0 - drawn.y),
+        10.0 + 2.0 * drawn.x,
+    );
+    assert!((nine.radius_px - 2.0 * portrait.balls[1].radius_px).abs() < 0.01);
+    let pixel = |x: f32, y: f32| image.get_pixel(x.round() as u32, y.round() as u32)[3];
+    assert!(pixel(nine.center.x, nine.center.y) > 0);
+    assert_eq!(
+        pixel(nine.center.x + 1.5 * nine.radius_px, nine.center.y),
+        0
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn a_sidecar_writes_out_as_json() {
+    let json = annotated_layout()
+        .diagram_sidecar(&DiagramRenderOptions::default())
+        .to_json();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(value["width_px"], 1089);
+    assert_eq!(value["balls"][1]["ty"], "Nine");
+    assert_eq!(value["annotations"][1]["kind"], "sequence_marker");
+    assert_eq!(value["annotations"][1]["layer"], "overlays-above-balls");
+    assert_eq!(value["pockets"][0]["pocket"], "TopRight");
+    assert!(value["balls"][0]["center"]["x"].is_f64());
+}
@@ -0,0 +1,145 @@
+use billiards::aiming::{
+    calibrate_cloth, draw_follow_carry, draw_follow_table, ClothMeasurement, DrawFollowCarry,
+};
+use billiards::{
+    human_tuned_preview_motion_config, Inches, InchesPerSecond, InchesPerSecondSq,
+    MotionTransitionConfig, RollingResistanceModel, Scale, SlidingFrictionModel,
+    STANDARD_GRAVITY_INCHES_PER_SECOND_SQUARED,
+};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "expected {expected}, got {actual}"
+    );
+}
+
+fn ips(speed: f64) -> InchesPerSecond {
+    InchesPerSecond::new(Inches::from_f64(speed))
+}
+
+fn carry(speed: f64, tip_height: f64, distance: f64) -> Option<DrawFollowCarry> {
+    draw_follow_carry(
+        &ips(speed),
+        &Scale::from_f64(tip_height),
+        Inches::from_f64(distance),
+        &human_tuned_preview_motion_config(),
+    )
+}
+
+#[test]
+fn a_center_ball_hit_from_point_blank_is_a_stun() {
+    let stun = carry(60.0, 0.0, 0.0).unwrap();
+
+    assert_close_with_tolerance(stun.spin_at_contact.as_f64(), 0.0, 1e-12);
+    assert_close_with_tolerance(stun.carry.as_f64(), 0.0, 1e-12);
+}
+
+#[test]
+fn a_naturally_rolling_cue_ball_follows_by_the_closed_form_distance() {
+    let gravity = STANDARD_GRAVITY_INCHES_PER_SECOND_SQUARED;
+    let follow = carry(80.0, 0.0, 60.0).unwrap();
+
+    let speed = follow.speed_at_contact.as_f64();
+    assert_close_with_tolerance(follow.spin_at_contact.as_f64(), speed, 1e-9);
+    let roll = 2.0 / 7.0 * speed;
+    let expected = roll * roll / 2.0 * (1.0 / (0.2 * gravity) + 1.0 / (0.01 * gravity));
+    assert_close_with_tolerance(follow.carry.as_f64(), expected, 1e-9);
+}
+
+#[test]
+fn draw_comes_back_and_carries_further_with_speed() {
+    let table = draw_follow_table(
+        Inches::from_f64(24.0),
+        &[Scale::from_f64(-0.4), Scale::from_f64(0.4)],
+        &[ips(120.0), ips(180.0), ips(240.0)],
+        &human_tuned_preview_motion_config(),
+    );
+
+    let [draw, follow] = table.carries.as_slice() else {
+        panic!("expected two rows");
+    };
+    let draw: Vec<f64> = draw
+        .iter()
+        .map(|carry| carry.clone().unwrap().as_f64())
+        .collect();
+    let follow: Vec<f64> = follow
+        .iter()
+        .map(|carry| carry.clone().unwrap().as_f64())
+        .collect();
+    assert!(draw.iter().all(|carry| *carry < 0.0), "{draw:?}");
+    assert!(follow.iter().all(|carry| *carry > 0.0), "{follow:?}");
+    assert!(draw.windows(2).all(|pair| pair[1] < pair[0]), "{draw:?}");
+    assert!(
+        follow.windows(2).all(|pair| pair[1] > pair[0]),
+        "{follow:?}"
+    );
+}
+
+#[test]
+fn a_cue_ball_that_stops_short_has_no_carry() {
+    assert_eq!(carry(10.0, 0.0, 100.0), None);
+}
+
+#[test]
+fn calibration_recovers_the_cloth_behind_a_set_of_measurements() {
+    let gravity = STANDARD_GRAVITY_INCHES_PER_SECOND_SQUARED;
+    let (sliding, rolling) = (0.25, 0.014);
+    let truth = MotionTransitionConfig {
+        sliding_friction: SlidingFrictionModel::ConstantAcceleration {
+            acceleration_magnitude: InchesPerSecondSq::new(Inches::from_f64(sliding * gravity)),
+        },
+        rolling_resistance: RollingResistanceModel::ConstantDeceleration {
+            linear_deceleration: InchesPerSecondSq::new(Inches::from_f64(rolling * gravity)),
+        },
+        ..human_tuned_preview_motion_config()
+    };
+    let lag = |speed: f64| {
+        let travelled = 12.0 * speed * speed / (49.0 * sliding * gravity)
+            + (5.0 / 7.0 * speed).powi(2) / (2.0 * rolling * gravity);
+        ClothMeasurement::Lag {
+            speed: ips(speed),
+            distance: Inches::from_f64(travelled),
+        }
+    };
+    let shot = |speed: f64, tip_height: f64, distance: f64| {
+        let seen = draw_follow_carry(
+            &ips(speed),
+            &Scale::from_f64(tip_height),
+            Inches::from_f64(distance),
+            &truth,
+        )
+        .unwrap();
+        ClothMeasurement::DrawFollow {
+            speed: ips(speed),
+            tip_height: Scale::from_f64(tip_height),
+            distance_to_object_ball: Inches::from_f64(distance),
+            carry: seen.carry,
+        }
+    };
+
+    let calibration = calibrate_cloth(
+        &[
+            lag(40.0),
+            lag(60.0),
+            shot(120.0, -0.4, 36.0),
+            shot(150.0, -0.3, 12.0),
+            shot(100.0, 0.4, 24.0),
+        ],
+        &human_tuned_preview_motion_config(),
+    )
+    .unwrap();
+
+    assert_close_with_tolerance(calibration.sliding_friction_coefficient, sliding, 0.01);
+    assert_close_with_tolerance(calibration.rolling_resistance_coefficient, rolling, 0.0005);
+    assert!(calibration.rms_error.as_f64() < 0.5, "{calibration:?}");
+    assert_eq!(calibration.motion_config.phase, truth.phase);
+}
+
+#[test]
+fn calibration_needs_a_measurement() {
+    assert_eq!(
+        calibrate_cloth(&[], &human_tuned_preview_motion_config()),
+        None
+    );
+}
@@ -0,0 +1,193 @@
+#![cfg(feature = "serde")]
+
+use billiards::diagram::DiagramElement;
+use billiards::drills::{Difficulty, Drill, DrillBook, DrillGoal};
+use billiards::pdf::PdfPageLayout;
+use billiards::schema;
+use billiards::{BallType, DiagramRenderOptions, Pocket, Position};
+
+const STOP_SHOT: &str = r##"
+title = "Stop shot"
+description = "Pot the one and leave the cue ball where the one was."
+
+[[layout.balls]]
+ty = "Cue"
+position = { x = "2", y = "2" }
+
+[[layout.balls]]
+ty = "One"
+position = { x = "2", y = "5" }
+
+[[layout.annotations]]
+kind = "text_label"
+anchor = { x = "2", y = "1" }
+text = "STOP"
+style = { enabled = true, color = "#ffffffff", layer = "AboveBalls", offset_x_px = 8, offset_y_px = -8, scale_px = 2 }
+
+[[target_zones]]
+name = "stop"
+outline = [{ x = "1.75", y = "4.75" }, { x = "2.25", y = "4.75" }, { x = "2.25", y = "5.25" }, { x = "1.75", y = "5.25" }]
+
+[[success]]
+goal = "pot"
+ball = "One"
+pocket = "TopLeft"
+
+[[success]]
+goal = "cue_ball_in_zone"
+zone = "stop"
+
+[[success]]
+goal = "make_rate"
+made = 7
+attempts = 10
+"##;
+
+#[test]
+fn drills_load_from_toml_into_typed_layouts_zones_and_goals() {
+    let drill = Drill::from_toml(STOP_SHOT).unwrap();
+
+    assert_eq!(drill.title, "Stop shot");
+    assert_eq!(drill.layout.balls().len(), 2);
+    assert_eq!(
+        drill.success,
+        [
+            DrillGoal::Pot {
+                ball: BallType::One,
+                pocket: Some(Pocket::TopLeft),
+            },
+            DrillGoal::CueBallInZone {
+                zone: "stop".to_string(),
+            },
+            DrillGoal::MakeRate {
+                made: 7,
+                attempts: 10,
+            },
+        ]
+    );
+    let zone = drill.zone("stop").unwrap();
+    let table_spec = &drill.layout.table_spec;
+    assert!(zone.contains(&Position::new(2u8, 5u8), table_spec));
+    assert!(!zone.contains(&Position::new(2u8, 2u8), table_spec));
+
+    let diagram = drill.diagram();
+    assert!(matches!(
+        diagram.overlays(),
+        [
+            DiagramElement::TextLabel { .. },
+            DiagramElement::ShadedZone { .. }
+        ]
+    ));
+    assert!(!diagram.draw_2d_diagram().is_empty());
+}
+
+#[test]
+fn drills_name_what_is_wrong_with_them() {
+    for (toml, error) in [
+        (
+            "title = \"Empty\"\nlayout = {}\n[[success]]\ngoal = \"cue_ball_in_zone\"\nzone = \"spot\"\n",
+            "goal names unknown target zone 'spot'",
+        ),
+        (
+            "title = \"Greedy\"\nlayout = {}\n[[success]]\ngoal = \"make_rate\"\nmade = 11\nattempts = 10\n",
+            "goal asks for 11 makes in 10 attempts",
+        ),
+    ] {
+        assert_eq!(Drill::from_toml(toml).unwrap_err(), error);
+    }
+    assert!(Drill::from_toml("layout = {}")
+        .unwrap_err()
+        .contains("missing field `title`"));
+}
+
+const BOOK: &str = r##"
+title = "Position play"
+
+[[chapters]]
+title = "Stop and stun"
+
+[[chapters.drills]]
+caption = "Stop it dead"
+difficulty = "beginner"
+tags = ["stop"]
+title = "Stop shot"
+layout = { balls = [{ ty = "Cue", position = { x = "2", y = "2" } }, { ty = "One", position = { x = "2", y = "5" } }] }
+target_zones = [{ name = "stop", outline = [{ x = "1.75", y = "4.75" }, { x = "2.25", y = "4.75" }, { x = "2.25", y = "5.25" }] }]
+success = [{ goal = "cue_ball_in_zone", zone = "stop" }]
+
+[[chapters.drills]]
+difficulty = "intermediate"
+tags = ["stun", "stop"]
+title = "Stun across"
+layout = { balls = [{ ty = "Cue", position = { x = "1", y = "2" } }, { ty = "Two", position = { x = "2", y = "5" } }] }
+
+[[chapters]]
+title = "Follow"
+
+[[chapters.drills]]
+title = "Follow to the rail"
+layout = { balls = [{ ty = "Cue", position = { x = "2", y = "2" } }] }
+"##;
+
+#[test]
+fn drill_books_keep_chapter_order_and_metadata_and_print_a_page_per_chapter() {
+    let book = DrillBook::from_toml(BOOK).unwrap();
+
+    let captions: Vec<_> = book.drills().map(|entry| entry.caption()).collect();
+    assert_eq!(
+        captions,
+        ["Stop it dead", "Stun across", "Follow to the rail"]
+    );
+    let stops: Vec<_> = book
+        .tagged("stop")
+        .map(|entry| (entry.drill.title.as_str(), entry.difficulty))
+        .collect();
+    assert_eq!(
+        stops,
+        [
+            ("Stop shot", Some(Difficulty::Beginner)),
+            ("Stun across", Some(Difficulty::Intermediate)),
+        ]
+    );
+
+    let options = DiagramRenderOptions::default();
+    let sheets = book.to_sheets(2, &options);
+    assert_eq!(sheets.len(), 2);
+    assert_eq!(sheets[0].caption, "Stop and stun");
+    assert_eq!(sheets[0].diagrams[0].caption, "Stop it dead");
+    assert_eq!(sheets[1].diagrams.len(), 1);
+
+    let pdf = book.render_to_pdf(2, &options, &PdfPageLayout::letter());
+    let pdf = String::from_utf8_lossy(&pdf);
+    assert!(pdf.contains("/Type /Pages /Kids [3 0 R 7 0 R] /Count 2"));
+    assert!(pdf.ends_with("%%EOF\n"));
+}
+
+#[test]
+fn a_bad_drill_in_a_book_is_named() {
+    let error = DrillBook::from_toml(
+        "title = \"Book\"\n[[chapters]]\ntitle = \"One\"\n[[chapters.drills]]\ntitle = \"Lost\"\nlayout = {}\nsuccess = [{ goal = \"cue_ball_in_zone\", zone = \"spot\" }]\n",
+    )
+    .unwrap_err();
+    assert_eq!(error, "drill 'Lost': goal names unknown target zone 'spot'");
+}
+
+#[test]
+fn drill_books_from_before_schema_versions_are_migrated_and_newer_ones_refused() {
+    let old = "title = \"Old\"\n[[chapters]]\ntitle = \"One\"\n[[chapters.drills]]\ntitle = \"Dimmed\"\nlayout = { balls = [{ ty = \"Cue\", position = { x = \"2\", y = \"2\" } }], ball_focus = [[\"One\"], 0.5] }\n";
+
+    let book = DrillBook::from_toml(old).unwrap();
+
+    let layout = &book.drills().next().unwrap().drill.layout;
+    let scene = layout.to_diagram_scene(&DiagramRenderOptions::default());
+    assert_eq!(scene.balls[0].opacity, 0.5);
+
+    let newer = format!(
+        "schema_version = {}\n{STOP_SHOT}",
+        schema::SCHEMA_VERSION + 1
+    );
+    let error = Drill::from_toml(&newer).unwrap_err();
+    assert!(error.contains("is newer than"), "{error}");
+    let current = format!("schema_version = {}\n{STOP_SHOT}", schema::SCHEMA_VERSION);
+    assert_eq!(Drill::from_toml(&current).unwrap().title, "Stop shot");
+}
@@ -1,7 +1,8 @@
 use bigdecimal::ToPrimitive;
+use billiards::diagram::DiagramElement;
 use billiards::dsl::{
-    parse_dsl, parse_dsl_to_game_state, parse_dsl_to_scenario, CoordinateAxis, DslBuildError,
-    DslError, DslParseError, RailSide, ScenarioTraceRenderOptions,
+    parse_dsl, parse_dsl_to_game_state, parse_dsl_to_scenario, BallRef, CoordinateAxis,
+    DslBuildError, DslError, DslParseError, RailSide, ScenarioTraceRenderOptions,
 };
 use billiards::{
     advance_to_next_n_ball_system_event_with_physics_and_pockets_on_table,
@@ -85,6 +86,28 @@ fn given_comments_blank_lines_aliases_and_frozen_balls_when_building_then_positi
     assert_close(eight.position.y.magnitude.to_f64().expect("eight y"), 6.0);
 }
 
+#[test]
+fn given_striped_ball_names_when_building_then_fifteen_ball_layouts_are_accepted() {
+    let state = parse_dsl_to_game_state(
+        "ball one at (1.0, 2.0)\n\
+         ball fourteen at (2.0, 6.0)\n\
+         ball fifteen at (3.0, 6.0)\n",
+    )
+    .expect("expected DSL to build");
+
+    let one = state.select_ball(BallType::One).expect("one ball");
+    let fourteen = state
+        .select_ball(BallType::Fourteen)
+        .expect("fourteen ball");
+    assert!(!one.ty.is_striped() && fourteen.ty.is_striped());
+    assert_eq!(fourteen.ty.number(), Some(14));
+    assert_close(
+        fourteen.position.x.magnitude.to_f64().expect("fourteen x"),
+        2.0,
+    );
+    assert!(state.select_ball(BallType::Fifteen).is_some());
+}
+
 #[test]
 fn given_an_invalid_second_statement_when_parsing_then_the_error_offset_points_at_the_bad_token() {
     let err = parse_dsl("ball cue at center\nball nine nope").expect_err("expected parse failure");
@@ -937,6 +960,27 @@ fn shot_scenarios_can_build_a_typed_trace_and_render_the_final_layout_with_ball_
         render_png(&rendered),
         render_png(&rendered_with_rich_overlays)
     );
+    let rendered_with_start_phantoms = trace.rendered_final_layout_with_trace_options(
+        &scenario,
+        &ScenarioTraceRenderOptions {
+            start_phantom_balls: true,
+            ..ScenarioTraceRenderOptions::default()
+        },
+    );
+    let svg = String::from_utf8(rendered_with_start_phantoms.render_2d_diagram_with_options(
+        billiards::diagram::DiagramOutputFormat::Svg,
+        &billiards::DiagramRenderOptions::default(),
+    ))
+    .expect("svg should be utf-8");
+    assert_eq!(
+        svg.matches("class=\"ball phantom-ball ball-cue\"").count(),
+        1
+    );
+    assert_eq!(svg.matches("phantom-ball").count(), 1);
+    assert_eq!(
+        rendered_with_start_phantoms.named_layers(),
+        [billiards::dsl::TRACE_LAYER]
+    );
 
     assert!(matches!(
         trace.event_log.as_slice(),
@@ -1127,3 +1171,45 @@ fn rejects_duplicate_shot_methods() {
         DslError::Build(DslBuildError::DuplicateShotMethod { method }) if method == "heading"
     ));
 }
+
+#[test]
+fn annotations_draw_lines_arrows_ghosts_and_labels_between_placed_balls() {
+    let state = parse_dsl_to_game_state(
+        "ball cue at (2, 4); ball 9 at (3.5, 7.5)\n\
+         line cue -> ghost(nine, top-right)\n\
+         arrow 9 -> (4, 8); label \"SAFE\" at ghost(9, top-right)\n",
+    )
+    .expect("expected DSL to build");
+
+    let cue = state.select_ball(BallType::Cue).expect("cue ball");
+    let nine = state.select_ball(BallType::Nine).expect("nine ball");
+    let ghost = nine.ghost_ball_to_pocket(Pocket::TopRight, &state.table_spec);
+    let [ghost_ball, line, arrow, label] = state.overlays() else {
+        panic!("unexpected overlays: {:?}", state.overlays());
+    };
+    assert!(matches!(ghost_ball, DiagramElement::GhostBall { center, .. } if center == &ghost));
+    assert!(matches!(
+        line,
+        DiagramElement::SmoothPolyline { points, .. } if points == &[cue.position.clone(), ghost.clone()]
+    ));
+    assert!(matches!(
+        arrow,
+        DiagramElement::SmoothPolyline { points, style } if points[0] == nine.position && style.arrow.is_some()
+    ));
+    assert!(matches!(
+        label,
+        DiagramElement::TextLabel { anchor, text, .. } if anchor == &ghost && text == "SAFE"
+    ));
+}
+
+#[test]
+fn annotations_must_name_balls_in_the_layout() {
+    let err = parse_dsl_to_game_state("ball cue at center\nline cue -> ghost(seven, top-left)")
+        .expect_err("expected build failure");
+    assert_eq!(
+        err,
+        DslError::Build(DslBuildError::AnnotationBallNotPlaced(BallRef::Seven))
+    );
+    assert_parse_error("ball cue at center\nline cue ghost(seven, top-left)");
+    assert_parse_error("label SAFE at center");
+}
@@ -0,0 +1,162 @@
+use billiards::{
+    english_aim_offset, human_tuned_preview_motion_config, CueSpec, CueTipContact, Inches,
+    InchesPerSecond, Scale,
+};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn ball_radius() -> Inches {
+    Inches::from_f64(2.25 / 2.0)
+}
+
+fn side_english(side: f64) -> CueTipContact {
+    CueTipContact::new(Scale::from_f64(side), Scale::zero()).unwrap()
+}
+
+fn speed() -> InchesPerSecond {
+    InchesPerSecond::new(Inches::from_f64(60.0))
+}
+
+#[test]
+fn the_default_cue_spec_is_the_tp_b1_players_cue() {
+    let cue = CueSpec::default();
+
+    assert_close_with_tolerance(
+        cue.squirt_angle_degrees(Inches::from_f64(0.51), ball_radius()),
+        2.5,
+        0.001,
+    );
+    assert_close_with_tolerance(
+        cue.natural_pivot_length(Inches::from_f64(0.51), ball_radius())
+            .as_f64(),
+        14.231,
+        0.001,
+    );
+}
+
+#[test]
+fn a_cue_spec_round_trips_through_its_natural_pivot_length() {
+    let cue = CueSpec::from_natural_pivot_length(
+        Inches::from_f64(20.0),
+        Inches::from_f64(0.705 / 2.0),
+        ball_radius(),
+    );
+
+    let pivot_length = cue.natural_pivot_length(Inches::from_f64(0.5625), ball_radius());
+
+    assert_close_with_tolerance(pivot_length.as_f64(), 20.0, 1e-9);
+}
+
+#[test]
+fn a_center_ball_hit_needs_no_aim_correction() {
+    let offset = english_aim_offset(
+        &CueSpec::default(),
+        &CueTipContact::center(),
+        5.0,
+        &speed(),
+        &Inches::from_f64(50.0),
+        &human_tuned_preview_motion_config(),
+    );
+
+    assert_eq!(offset.squirt_angle_degrees, 0.0);
+    assert_eq!(offset.swerve.as_f64(), 0.0);
+    assert_eq!(offset.aim_offset_degrees, 0.0);
+}
+
+#[test]
+fn with_a_level_cue_right_english_squirts_left_so_the_aim_moves_right() {
+    let distance = Inches::from_f64(50.0);
+
+    let offset = english_aim_offset(
+        &CueSpec::default(),
+        &side_english(0.5),
+        0.0,
+        &speed(),
+        &distance,
+        &human_tuned_preview_motion_config(),
+    );
+
+    assert!(offset.squirt_angle_degrees < 0.0);
+    assert_eq!(offset.swerve.as_f64(), 0.0);
+    assert_close_with_tolerance(
+        offset.lateral_miss.as_f64(),
+        50.0 * offset.squirt_angle_degrees.to_radians().tan(),
+        1e-9,
+    );
+    assert_close_with_tolerance(
+        offset.aim_offset_degrees,
+        -offset.squirt_angle_degrees,
+        0.01,
+    );
+}
+
+#[test]
+fn an_elevated_cue_swerves_back_toward_the_english_side_more_over_a_longer_shot() {
+    let motion = human_tuned_preview_motion_config();
+    let level = english_aim_offset(
+        &CueSpec::default(),
+        &side_english(0.5),
+        0.0,
+        &speed(),
+        &Inches::from_f64(50.0),
+        &motion,
+    );
+    let short = english_aim_offset(
+        &CueSpec::default(),
+        &side_english(0.5),
+        8.0,
+        &speed(),
+        &Inches::from_f64(20.0),
+        &motion,
+    );
+    let long = english_aim_offset(
+        &CueSpec::default(),
+        &side_english(0.5),
+        8.0,
+        &speed(),
+        &Inches::from_f64(50.0),
+        &motion,
+    );
+
+    assert!(short.swerve.as_f64() > 0.0);
+    assert!(long.swerve.as_f64() > short.swerve.as_f64());
+    assert!(long.lateral_miss.as_f64() > level.lateral_miss.as_f64());
+}
+
+#[test]
+fn a_low_squirt_cue_needs_less_aim_correction() {
+    let motion = human_tuned_preview_motion_config();
+    let distance = Inches::from_f64(50.0);
+    let low_squirt = CueSpec::from_natural_pivot_length(
+        Inches::from_f64(20.199),
+        Inches::from_f64(0.705 / 2.0),
+        ball_radius(),
+    );
+
+    let regular = english_aim_offset(
+        &CueSpec::default(),
+        &side_english(-0.4),
+        0.0,
+        &speed(),
+        &distance,
+        &motion,
+    );
+    let low = english_aim_offset(
+        &low_squirt,
+        &side_english(-0.4),
+        0.0,
+        &speed(),
+        &distance,
+        &motion,
+    );
+
+    assert!(regular.aim_offset_degrees < 0.0);
+    assert!(low.aim_offset_degrees < 0.0);
+    assert!(low.aim_offset_degrees.abs() < regular.aim_offset_degrees.abs());
+}
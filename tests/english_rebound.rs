@@ -0,0 +1,184 @@
+use billiards::banking::{single_rail_bank, single_rail_bank_with_rebound};
+use billiards::kicking::{one_rail_kick, one_rail_kick_with_rebound};
+use billiards::{
+    Ball, BallSpec, BallType, EnglishRebound, Pocket, Position, Rail, RailCollisionConfig,
+    RailCollisionProfile, Scale, TableSpec,
+};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn as_f64(value: &billiards::Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn rebound(restitution: f64, friction: f64) -> EnglishRebound {
+    EnglishRebound::new(RailCollisionProfile::uniform(RailCollisionConfig::new(
+        Scale::from_f64(restitution),
+        Scale::from_f64(friction),
+    )))
+}
+
+/// The signed angles, from the normal of the left rail, at which a path arrives at and leaves
+/// its contact point.
+fn left_rail_angles(from: &Position, contact: &Position, to: &Position) -> (f64, f64) {
+    let (fx, fy) = (as_f64(&from.x), as_f64(&from.y));
+    let (cx, cy) = (as_f64(&contact.x), as_f64(&contact.y));
+    let (tx, ty) = (as_f64(&to.x), as_f64(&to.y));
+    (
+        (cy - fy).atan2(fx - cx).to_degrees(),
+        (ty - cy).atan2(tx - cx).to_degrees(),
+    )
+}
+
+#[test]
+fn geared_english_on_a_fully_elastic_rail_rebounds_like_a_mirror() {
+    let incoming = 35.0_f64;
+    let geared =
+        rebound(1.0, 0.2).with_running_english(Scale::from_f64(incoming.to_radians().sin()));
+
+    assert_close_with_tolerance(
+        geared.rebound_angle_degrees(Rail::Left, incoming),
+        incoming,
+        1e-9,
+    );
+    assert_close_with_tolerance(
+        geared.rebound_angle_degrees(Rail::Left, -incoming),
+        -incoming,
+        1e-9,
+    );
+}
+
+#[test]
+fn cushion_friction_shortens_a_ball_without_english() {
+    let incoming = 30.0_f64;
+    let restitution_only = (incoming.to_radians().tan() / 0.8).atan().to_degrees();
+
+    let frictionless = rebound(0.8, 0.0).rebound_angle_degrees(Rail::Top, incoming);
+    let gripping = rebound(0.8, 0.2).rebound_angle_degrees(Rail::Top, incoming);
+
+    assert_close_with_tolerance(frictionless, restitution_only, 1e-9);
+    assert!(frictionless > incoming);
+    assert!(gripping < restitution_only);
+}
+
+#[test]
+fn running_english_lengthens_and_reverse_english_shortens_the_rebound() {
+    let incoming = 30.0;
+    let base = rebound(0.8, 0.2);
+
+    let plain = base.rebound_angle_degrees(Rail::Right, incoming);
+    let running = base
+        .clone()
+        .with_running_english(Scale::from_f64(0.8))
+        .rebound_angle_degrees(Rail::Right, incoming);
+    let reverse = base
+        .with_running_english(Scale::from_f64(-0.8))
+        .rebound_angle_degrees(Rail::Right, incoming);
+
+    assert!(running > plain);
+    assert!(running > incoming);
+    assert!(reverse < plain);
+}
+
+#[test]
+fn each_cushion_uses_its_own_rail_config() {
+    let lively = RailCollisionConfig::new(Scale::from_f64(0.9), Scale::from_f64(0.1));
+    let dead = RailCollisionConfig::new(Scale::from_f64(0.6), Scale::from_f64(0.3));
+    let rule = EnglishRebound::new(RailCollisionProfile::uniform(lively).with_left(dead));
+
+    let top = rule.rebound_angle_degrees(Rail::Top, 40.0);
+    let left = rule.rebound_angle_degrees(Rail::Left, 40.0);
+
+    assert!((top - left).abs() > 1.0);
+}
+
+#[test]
+fn solving_without_a_rebound_rule_matches_the_mirror_solvers() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::One, "1.5", "2.5");
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+    let target = ball_at(BallType::One, "3", "6");
+
+    assert_eq!(
+        single_rail_bank_with_rebound(&object_ball, Pocket::TopLeft, Rail::Right, None, &table),
+        single_rail_bank(&object_ball, Pocket::TopLeft, Rail::Right, &table)
+    );
+    assert_eq!(
+        one_rail_kick_with_rebound(&cue_ball, &target, Rail::Right, None, &table),
+        one_rail_kick(&cue_ball, &target, Rail::Right, &table)
+    );
+}
+
+#[test]
+fn a_bank_with_running_english_hits_the_rail_where_its_rebound_reaches_the_pocket() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::One, "2", "2.5");
+    let rule = rebound(0.8, 0.2).with_running_english(Scale::from_f64(0.6));
+
+    let mirror = single_rail_bank(&object_ball, Pocket::CenterRight, Rail::Left, &table).unwrap();
+    let bank = single_rail_bank_with_rebound(
+        &object_ball,
+        Pocket::CenterRight,
+        Rail::Left,
+        Some(&rule),
+        &table,
+    )
+    .expect("english bank should exist");
+
+    let (incoming, outgoing) =
+        left_rail_angles(&bank.path[0], &bank.contacts[0].point, &bank.path[2]);
+    assert_close_with_tolerance(
+        rule.rebound_angle_degrees(Rail::Left, incoming),
+        outgoing,
+        1e-6,
+    );
+    assert!(as_f64(&bank.contacts[0].diamond) < as_f64(&mirror.contacts[0].diamond) - 0.05);
+    assert_eq!(bank.path[2], Pocket::CenterRight.aiming_center());
+}
+
+#[test]
+fn a_kick_with_english_keeps_its_mirror_target_on_the_aiming_line() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+    let target = ball_at(BallType::One, "2", "6");
+    let rule = rebound(0.8, 0.2);
+
+    let kick = one_rail_kick_with_rebound(&cue_ball, &target, Rail::Left, Some(&rule), &table)
+        .expect("english kick should exist");
+
+    let (incoming, outgoing) = left_rail_angles(&kick.path[0], &kick.contact_point, &kick.path[2]);
+    assert_close_with_tolerance(
+        rule.rebound_angle_degrees(Rail::Left, incoming),
+        outgoing,
+        1e-6,
+    );
+    let (cx, cy) = (as_f64(&cue_ball.position.x), as_f64(&cue_ball.position.y));
+    let (px, py) = (
+        as_f64(&kick.contact_point.x) - cx,
+        as_f64(&kick.contact_point.y) - cy,
+    );
+    let (mx, my) = (
+        as_f64(&kick.mirror_target.x) - cx,
+        as_f64(&kick.mirror_target.y) - cy,
+    );
+    assert_close_with_tolerance(px * my - py * mx, 0.0, 1e-9);
+    assert_close_with_tolerance(
+        as_f64(&kick.mirror_target.x),
+        -(2.0 - 2.0 * 1.125 / 12.5),
+        1e-9,
+    );
+}
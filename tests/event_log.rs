@@ -0,0 +1,264 @@
+use billiards::simulation::{simulate, EventKind, FixedStepSimulator, SimulationResult, Simulator};
+use billiards::visualization::MeasurementUnit;
+use billiards::{
+    Angle, Ball, BallSpec, BallType, CueTipContact, Diamond, GameState, InchesPerSecond, Pocket,
+    Position, Rail, Seconds, Shot, TableSpec,
+};
+
+fn as_f64(value: &Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "expected {expected}, got {actual}"
+    );
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn straight_in() -> (GameState, Shot) {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "2", "4"),
+        ],
+    );
+    let shot = Shot::toward(
+        &Position::new("1", "4"),
+        &Position::new("2", "4"),
+        InchesPerSecond::new("80"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+    (state, shot)
+}
+
+fn final_cue_ball(result: &SimulationResult) -> Position {
+    result
+        .final_state
+        .select_ball(BallType::Cue)
+        .unwrap()
+        .position
+        .clone()
+}
+
+#[test]
+fn a_straight_in_shot_logs_the_hit_the_drop_and_the_stop() {
+    let (state, shot) = straight_in();
+
+    let result = simulate(&state, &shot).unwrap();
+
+    let kinds: Vec<&EventKind> = result.event_log.iter().map(|event| &event.kind).collect();
+    let [EventKind::BallBallCollision {
+        first,
+        second,
+        first_position,
+        second_position,
+    }, EventKind::Pocketed {
+        ball: pocketed,
+        pocket,
+        ..
+    }, EventKind::BallStopped {
+        ball: stopped,
+        position: stopped_at,
+    }] = kinds.as_slice()
+    else {
+        panic!("unexpected event log: {kinds:?}");
+    };
+
+    assert_eq!((first, second), (&BallType::Cue, &BallType::One));
+    assert_close_with_tolerance(as_f64(&first_position.x), 2.0 - 2.25 / 12.5, 1e-6);
+    assert_eq!(second_position, &Position::new("2", "4"));
+    assert_eq!((pocketed, pocket), (&BallType::One, &Pocket::CenterRight));
+    assert_eq!(stopped, &BallType::Cue);
+    assert_eq!(stopped_at, &final_cue_ball(&result));
+}
+
+#[test]
+fn event_times_run_from_the_strike_to_the_end_of_the_shot() {
+    let (state, shot) = straight_in();
+
+    let result = simulate(&state, &shot).unwrap();
+
+    let times: Vec<f64> = result
+        .event_log
+        .iter()
+        .map(|event| event.time.as_f64())
+        .collect();
+    assert!(times[0] > 0.0);
+    assert!(times.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert_close_with_tolerance(*times.last().unwrap(), result.elapsed().as_f64(), 1e-9);
+}
+
+#[test]
+fn a_cushion_contact_names_the_rail_and_where_the_ball_touched_it() {
+    let state = GameState::with_balls(TableSpec::default(), [ball_at(BallType::Cue, "2", "6")]);
+    let shot = Shot::new(
+        Angle::from_north(0.0, 1.0),
+        InchesPerSecond::new("60"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+
+    let result = simulate(&state, &shot).unwrap();
+
+    let Some(EventKind::CushionContact {
+        ball,
+        rail,
+        position,
+    }) = result.event_log.first().map(|event| &event.kind)
+    else {
+        panic!("expected a cushion contact first: {:?}", result.event_log);
+    };
+    assert_eq!((ball, rail), (&BallType::Cue, &Rail::Top));
+    assert_close_with_tolerance(as_f64(&position.x), 2.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&position.y), 8.0 - 1.125 / 12.5, 1e-6);
+}
+
+#[test]
+fn a_rattle_is_logged_as_contacts_with_the_cushions_either_side_of_the_pocket() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [ball_at(BallType::Cue, "3.78", "1.5")],
+    );
+    let shot = Shot::new(
+        Angle::from_north(0.0, -1.0),
+        InchesPerSecond::new("40"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+
+    let result = simulate(&state, &shot).unwrap();
+
+    let rails: Vec<Rail> = result
+        .event_log
+        .iter()
+        .filter_map(|event| match &event.kind {
+            EventKind::CushionContact { rail, .. } => Some(*rail),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(rails[..2], [Rail::Bottom, Rail::Right]);
+    assert!(!result
+        .event_log
+        .iter()
+        .any(|event| matches!(event.kind, EventKind::Pocketed { .. })));
+}
+
+#[test]
+fn the_fixed_step_backend_logs_the_same_kinds_of_events() {
+    let (state, shot) = straight_in();
+
+    let result = FixedStepSimulator::default()
+        .with_time_step(Seconds::new(0.002))
+        .simulate(&state, &shot)
+        .unwrap();
+
+    assert!(matches!(
+        result
+            .event_log
+            .iter()
+            .map(|event| &event.kind)
+            .collect::<Vec<_>>()
+            .as_slice(),
+        [
+            EventKind::BallBallCollision { .. },
+            EventKind::Pocketed {
+                ball: BallType::One,
+                pocket: Pocket::CenterRight,
+                ..
+            },
+            EventKind::BallStopped {
+                ball: BallType::Cue,
+                ..
+            },
+        ]
+    ));
+    let Some(EventKind::BallStopped { position, .. }) =
+        result.event_log.last().map(|event| &event.kind)
+    else {
+        unreachable!();
+    };
+    assert_eq!(position, &final_cue_ball(&result));
+}
+
+#[test]
+fn the_event_log_exports_as_csv_with_one_row_per_event() {
+    let (state, shot) = straight_in();
+    let result = simulate(&state, &shot).unwrap();
+
+    let csv = result.event_log_to_csv(MeasurementUnit::Diamonds);
+
+    let rows: Vec<Vec<&str>> = csv.lines().map(|line| line.split(',').collect()).collect();
+    assert_eq!(
+        rows[0],
+        [
+            "time_s",
+            "event",
+            "ball",
+            "x_diamonds",
+            "y_diamonds",
+            "other_ball",
+            "other_x_diamonds",
+            "other_y_diamonds",
+            "rail",
+            "pocket"
+        ]
+    );
+    assert_eq!(rows.len(), result.event_log.len() + 1);
+    assert!(rows.iter().all(|row| row.len() == 10));
+    assert_eq!(
+        rows[1][1..3]
+            .iter()
+            .chain(&rows[1][5..])
+            .collect::<Vec<_>>(),
+        [&"ball_ball_collision", &"cue", &"1", &"2", &"4", &"", &""]
+    );
+    assert_close_with_tolerance(
+        rows[1][0].parse().unwrap(),
+        result.event_log[0].time.as_f64(),
+        1e-12,
+    );
+    assert_eq!(rows[2][1..3], ["pocketed", "1"]);
+    assert_eq!(rows[2][5..], ["", "", "", "", "center_right"]);
+    assert_eq!(rows[3][1..3], ["ball_stopped", "cue"]);
+
+    let inches = result.event_log_to_csv(MeasurementUnit::Inches);
+    let collision: Vec<&str> = inches.lines().nth(1).unwrap().split(',').collect();
+    assert_eq!(collision[6..8], ["25", "50"]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn the_event_log_exports_as_json_tagged_by_event() {
+    let (state, shot) = straight_in();
+    let result = simulate(&state, &shot).unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&result.event_log_to_json()).unwrap();
+
+    let events = json.as_array().unwrap();
+    assert_eq!(events.len(), result.event_log.len());
+    assert_eq!(events[0]["event"], "ball_ball_collision");
+    assert_eq!(events[0]["first"], "Cue");
+    assert_eq!(events[0]["second_position"]["x"], "2");
+    assert_eq!(
+        events[0]["time"].as_f64(),
+        Some(result.event_log[0].time.as_f64())
+    );
+    assert_eq!(events[1]["event"], "pocketed");
+    assert_eq!(events[1]["pocket"], "CenterRight");
+    let read: Vec<billiards::simulation::Event> = serde_json::from_value(json.clone()).unwrap();
+    for (read, event) in read.iter().zip(&result.event_log) {
+        assert_eq!(read.kind, event.kind);
+        assert_close_with_tolerance(read.time.as_f64(), event.time.as_f64(), 1e-12);
+    }
+}
@@ -0,0 +1,123 @@
+use billiards::aiming::{
+    fractional_aim_ghost_ball, fractional_aim_object_ball_heading, BallHitFraction, CutDirection,
+};
+use billiards::visualization::AimOverlayStyle;
+use billiards::{
+    Ball, BallSpec, BallType, CutAngle, DiagramRenderOptions, GameState, Position, Scale, TableSpec,
+};
+use image::Rgba;
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn object_ball_at(x: &str, y: &str) -> Ball {
+    Ball {
+        ty: BallType::Nine,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn fractional_references_map_to_their_classic_cut_angles() {
+    assert_close_with_tolerance(BallHitFraction::Full.cut_angle().as_degrees(), 0.0, 1e-9);
+    assert_close_with_tolerance(
+        BallHitFraction::ThreeQuarter.cut_angle().as_degrees(),
+        14.477_512_185_929_925,
+        1e-9,
+    );
+    assert_close_with_tolerance(BallHitFraction::Half.cut_angle().as_degrees(), 30.0, 1e-9);
+    assert_close_with_tolerance(
+        BallHitFraction::Quarter.cut_angle().as_degrees(),
+        48.590_377_890_729_14,
+        1e-9,
+    );
+}
+
+#[test]
+fn hit_fraction_and_cut_angle_round_trip() {
+    let cut = CutAngle::new(22.0);
+
+    let round_tripped = CutAngle::from_hit_fraction(cut.hit_fraction());
+
+    assert_close_with_tolerance(round_tripped.as_degrees(), 22.0, 1e-9);
+}
+
+#[test]
+fn a_required_cut_maps_to_the_nearest_fraction_with_a_signed_residual() {
+    let thicker_than_half = BallHitFraction::nearest(CutAngle::new(27.0));
+    let thinner_than_quarter = BallHitFraction::nearest(CutAngle::new(60.0));
+
+    assert_eq!(thicker_than_half.fraction, BallHitFraction::Half);
+    assert_close_with_tolerance(thicker_than_half.residual_degrees, -3.0, 1e-9);
+    assert_eq!(thinner_than_quarter.fraction, BallHitFraction::Quarter);
+    assert!(thinner_than_quarter.residual_degrees > 11.0);
+}
+
+#[test]
+fn a_half_ball_hit_produces_a_thirty_degree_cut_on_the_requested_side() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new(2u8, 2u8);
+    let object_ball = object_ball_at("2", "5");
+
+    for (direction, expected_side) in [(CutDirection::Right, 1.0), (CutDirection::Left, -1.0)] {
+        let fraction = BallHitFraction::Half.fraction();
+        let ghost =
+            fractional_aim_ghost_ball(&cue_ball, &object_ball, fraction.clone(), direction, &table);
+        let object_heading = fractional_aim_object_ball_heading(
+            &cue_ball,
+            &object_ball,
+            fraction,
+            direction,
+            &table,
+        );
+        let cue_heading = cue_ball.angle_to(&ghost);
+
+        assert_close_with_tolerance(
+            CutAngle::from_headings(cue_heading, object_heading).as_degrees(),
+            30.0,
+            1e-6,
+        );
+        let signed_offset = (object_heading.as_degrees() + 180.0).rem_euclid(360.0) - 180.0;
+        assert_eq!(signed_offset.signum(), expected_side);
+    }
+}
+
+#[test]
+fn a_full_ball_hit_places_the_ghost_ball_straight_behind_the_object_ball() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("2", "5");
+
+    let ghost = fractional_aim_ghost_ball(
+        &Position::new(2u8, 2u8),
+        &object_ball,
+        Scale::from_f64(1.0),
+        CutDirection::Right,
+        &table,
+    );
+
+    assert_close_with_tolerance(ghost.x.magnitude.to_string().parse().unwrap(), 2.0, 1e-9);
+    assert_close_with_tolerance(ghost.y.magnitude.to_string().parse().unwrap(), 4.82, 1e-9);
+}
+
+#[test]
+fn the_fractional_aim_overlay_draws_a_ghost_ball_and_an_aim_line() {
+    let mut game_state = GameState::new(TableSpec::default());
+    let object_ball = object_ball_at("2", "5");
+
+    game_state.add_fractional_aim_overlay(
+        &Position::new(2u8, 2u8),
+        &object_ball,
+        BallHitFraction::Quarter,
+        CutDirection::Left,
+        &AimOverlayStyle::new(Rgba([255, 255, 255, 255])),
+    );
+
+    let scene = game_state.to_diagram_scene(&DiagramRenderOptions::default());
+    assert_eq!(scene.elements.len(), 2);
+}
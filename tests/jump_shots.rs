@@ -0,0 +1,143 @@
+use billiards::jumping::{analyze_jump, JumpKind};
+use billiards::{Ball, BallSpec, BallType, Inches, InchesPerSecond, Position, TableSpec};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn jump_speed() -> InchesPerSecond {
+    InchesPerSecond::new(Inches::from_f64(200.0))
+}
+
+#[test]
+fn a_ball_off_the_line_needs_no_jump() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+
+    let analysis = analyze_jump(
+        &cue_ball,
+        &Position::new("2", "6"),
+        &ball_at(BallType::Three, "2.5", "3"),
+        &jump_speed(),
+        &table,
+    );
+
+    assert_eq!(analysis, None);
+}
+
+#[test]
+fn a_blocker_dead_on_the_line_is_a_full_hop_over_its_whole_height() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+
+    let analysis = analyze_jump(
+        &cue_ball,
+        &Position::new("2", "6"),
+        &ball_at(BallType::Three, "2", "3"),
+        &jump_speed(),
+        &table,
+    )
+    .unwrap();
+
+    assert_eq!(analysis.kind, JumpKind::FullHop);
+    assert_eq!(analysis.blocker, BallType::Three);
+    assert_close_with_tolerance(analysis.distance_to_blocker.as_f64(), 12.5, 1e-9);
+    assert_close_with_tolerance(analysis.lateral_offset.as_f64(), 0.0, 1e-9);
+    assert_close_with_tolerance(analysis.required_clearance_height.as_f64(), 2.25, 1e-9);
+    let elevation = analysis.required_elevation_degrees.unwrap();
+    assert!(analysis.clearance_at(elevation, &jump_speed()).as_f64() >= 0.0);
+    assert!(
+        analysis
+            .clearance_at(elevation - 1.0, &jump_speed())
+            .as_f64()
+            < 0.0
+    );
+}
+
+#[test]
+fn a_thin_edge_jump_needs_less_elevation_than_a_full_hop() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+    let target = Position::new("2", "6");
+
+    let full = analyze_jump(
+        &cue_ball,
+        &target,
+        &ball_at(BallType::Three, "2", "3"),
+        &jump_speed(),
+        &table,
+    )
+    .unwrap();
+    let edge = analyze_jump(
+        &cue_ball,
+        &target,
+        &ball_at(BallType::Three, "2.144", "3"),
+        &jump_speed(),
+        &table,
+    )
+    .unwrap();
+
+    assert_eq!(edge.kind, JumpKind::ThinEdge);
+    assert_eq!(edge.kind.label(), "thin-edge jump");
+    assert!(edge.required_elevation_degrees.unwrap() < full.required_elevation_degrees.unwrap());
+    assert!(edge.required_clearance_height.as_f64() < full.required_clearance_height.as_f64());
+}
+
+#[test]
+fn a_closer_blocker_needs_a_steeper_cue() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+    let target = Position::new("2", "6");
+
+    let far = analyze_jump(
+        &cue_ball,
+        &target,
+        &ball_at(BallType::Three, "2", "3"),
+        &jump_speed(),
+        &table,
+    )
+    .unwrap();
+    let near = analyze_jump(
+        &cue_ball,
+        &target,
+        &ball_at(BallType::Three, "2", "2.5"),
+        &jump_speed(),
+        &table,
+    )
+    .unwrap();
+
+    let far_elevation = far.required_elevation_degrees.unwrap();
+    assert!(near.required_elevation_degrees.unwrap() > far_elevation);
+    assert!(far.is_plausible(far_elevation));
+    assert!(!near.is_plausible(far_elevation));
+}
+
+#[test]
+fn a_soft_stroke_cannot_clear_the_blocker() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+
+    let analysis = analyze_jump(
+        &cue_ball,
+        &Position::new("2", "6"),
+        &ball_at(BallType::Three, "2", "3"),
+        &InchesPerSecond::new(Inches::from_f64(10.0)),
+        &table,
+    )
+    .unwrap();
+
+    assert_eq!(analysis.required_elevation_degrees, None);
+    assert!(!analysis.is_plausible(90.0));
+}
@@ -0,0 +1,264 @@
+use billiards::diagram::DiagramElement;
+use billiards::kicking::{
+    corner5_kick, one_rail_kick, one_rail_kicks, plus_system_kick, two_times_system_kick,
+    Corner5Corrections,
+};
+use billiards::visualization::{DashedLineStyle, LabelOverlayStyle};
+use billiards::{
+    Ball, BallSpec, BallType, DiagramRenderOptions, GameState, Position, Rail, TableSpec,
+};
+use image::Rgba;
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn as_f64(value: &billiards::Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn a_symmetric_kick_contacts_the_rail_halfway_between_the_balls() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+    let target = ball_at(BallType::One, "2", "6");
+
+    let kick = one_rail_kick(&cue_ball, &target, Rail::Right, &table).expect("kick should exist");
+
+    assert_close_with_tolerance(as_f64(&kick.contact_diamond), 4.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.contact_point.x), 4.0 - 1.125 / 12.5, 1e-9);
+    assert_eq!(
+        kick.path,
+        vec![
+            cue_ball.position,
+            kick.contact_point.clone(),
+            target.position
+        ]
+    );
+}
+
+#[test]
+fn the_mirror_target_is_reflected_across_the_ball_center_line_not_the_cushion_nose() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+    let target = ball_at(BallType::One, "3", "6");
+
+    let kick = one_rail_kick(&cue_ball, &target, Rail::Right, &table).unwrap();
+
+    let radius_in_diamonds = 1.125 / 12.5;
+    assert_close_with_tolerance(
+        as_f64(&kick.mirror_target.x),
+        2.0 * (4.0 - radius_in_diamonds) - 3.0,
+        1e-9,
+    );
+    assert_eq!(kick.aim_line.start, cue_ball.position);
+    assert_eq!(kick.aim_line.end, kick.mirror_target);
+    let aim = kick.aim_line.heading().as_degrees();
+    let to_contact = cue_ball.position.angle_to(&kick.contact_point).as_degrees();
+    assert_close_with_tolerance(aim, to_contact, 1e-6);
+}
+
+#[test]
+fn a_cue_ball_frozen_to_a_rail_cannot_kick_off_that_rail() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "0.09", "2");
+    let target = ball_at(BallType::One, "3", "6");
+
+    let kicks = one_rail_kicks(&cue_ball, &target, &table);
+
+    let rails: Vec<Rail> = kicks.iter().map(|kick| kick.rail).collect();
+    assert_eq!(rails, vec![Rail::Top, Rail::Bottom, Rail::Right]);
+}
+
+#[test]
+fn a_corner_five_origin_aimed_for_target_three_plays_to_diamond_two() {
+    let table = TableSpec::default();
+    // On the track from the corner-5 origin to the second diamond of the first rail.
+    let cue_ball = Position::new("0.5", "0.75");
+
+    let kick = corner5_kick(
+        &cue_ball,
+        &Position::new("0", "5"),
+        Rail::Right,
+        Rail::Top,
+        &Corner5Corrections::default(),
+        &table,
+    )
+    .expect("corner-5 kick should exist");
+
+    assert_close_with_tolerance(kick.origin_number, 5.0, 1e-9);
+    assert_close_with_tolerance(kick.target_number, 3.0, 1e-9);
+    assert_close_with_tolerance(kick.aim_number, 2.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.first_rail_point.x), 4.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.first_rail_point.y), 6.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.origin_point.y), 0.0, 1e-9);
+}
+
+#[test]
+fn the_corner_five_path_has_one_segment_per_rail_and_ends_at_the_target() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new("0.5", "0.75");
+
+    let kick = corner5_kick(
+        &cue_ball,
+        &Position::new("0", "5"),
+        Rail::Right,
+        Rail::Top,
+        &Corner5Corrections::default(),
+        &table,
+    )
+    .unwrap();
+
+    assert_eq!(kick.path.len(), 3);
+    assert_eq!(kick.path[0].start, cue_ball);
+    assert_close_with_tolerance(as_f64(&kick.path[1].end.x), 2.4, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.path[1].end.y), 8.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.path[2].end.x), 0.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.path[2].end.y), 5.0, 1e-9);
+}
+
+#[test]
+fn the_corner_five_system_mirrors_to_the_other_rails() {
+    let table = TableSpec::default();
+
+    let kick = corner5_kick(
+        &Position::new("3.5", "7.25"),
+        &Position::new("4", "3"),
+        Rail::Left,
+        Rail::Bottom,
+        &Corner5Corrections::default(),
+        &table,
+    )
+    .unwrap();
+
+    assert_close_with_tolerance(kick.aim_number, 2.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.first_rail_point.x), 0.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.first_rail_point.y), 2.0, 1e-9);
+}
+
+#[test]
+fn a_running_english_correction_shifts_the_aim_number() {
+    let table = TableSpec::default();
+
+    let kick = corner5_kick(
+        &Position::new(0u8, 0u8),
+        &Position::new("0", "5"),
+        Rail::Right,
+        Rail::Top,
+        &Corner5Corrections::with_aim_offset(0.5),
+        &table,
+    )
+    .unwrap();
+
+    assert_close_with_tolerance(kick.aim_number, 2.5, 1e-9);
+}
+
+#[test]
+fn origin_numbers_continue_onto_the_short_rail_behind_the_shooter() {
+    let table = TableSpec::default();
+    // On the track from the first short-rail diamond (origin 6) to first-rail diamond 3.
+    let cue_ball = Position::new("2.5", "2.5");
+
+    let kick = corner5_kick(
+        &cue_ball,
+        &Position::new("0", "5"),
+        Rail::Right,
+        Rail::Top,
+        &Corner5Corrections::default(),
+        &table,
+    )
+    .unwrap();
+
+    assert_close_with_tolerance(kick.origin_number, 6.0, 1e-9);
+    assert_close_with_tolerance(kick.aim_number, 3.0, 1e-9);
+}
+
+#[test]
+fn the_plus_system_adds_origin_and_aim_to_reach_the_target() {
+    let table = TableSpec::default();
+    // On the track from origin 4 (left rail, y = 4) to first-rail diamond 1 on the top rail.
+    let cue_ball = Position::new("0.5", "6");
+
+    let kick = plus_system_kick(
+        &cue_ball,
+        &Position::new("4", "3"),
+        Rail::Top,
+        Rail::Right,
+        &table,
+    )
+    .expect("plus-system kick should exist");
+
+    assert_close_with_tolerance(kick.origin_number, 4.0, 1e-9);
+    assert_close_with_tolerance(kick.aim_number, 1.0, 1e-9);
+    assert_close_with_tolerance(kick.target_number, 5.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.first_rail_point.x), 1.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.first_rail_point.y), 8.0, 1e-9);
+    assert_eq!(kick.path.len(), 2);
+    assert_eq!(kick.path[1].end, kick.target_point);
+}
+
+#[test]
+fn the_two_times_system_aims_at_twice_the_target_when_lined_up_across() {
+    let table = TableSpec::default();
+    // On the track from far-short-rail origin 1 to first-rail diamond 2.
+    let cue_ball = Position::new("2.5", "3");
+
+    let kick = two_times_system_kick(
+        &cue_ball,
+        &Position::new("3", "8"),
+        Rail::Right,
+        Rail::Top,
+        &table,
+    )
+    .expect("two-times kick should exist");
+
+    assert_close_with_tolerance(kick.origin_number, 1.0, 1e-9);
+    assert_close_with_tolerance(kick.target_number, 1.0, 1e-9);
+    assert_close_with_tolerance(kick.aim_number, 2.0, 1e-9);
+    assert_close_with_tolerance(as_f64(&kick.first_rail_point.y), 6.0, 1e-9);
+}
+
+#[test]
+fn the_system_kick_overlay_annotates_the_rails_with_system_numbers() {
+    let table = TableSpec::default();
+    let kick = corner5_kick(
+        &Position::new("0.5", "0.75"),
+        &Position::new("0", "5"),
+        Rail::Right,
+        Rail::Top,
+        &Corner5Corrections::default(),
+        &table,
+    )
+    .unwrap();
+    let mut game_state = GameState::new(table);
+
+    game_state.add_diamond_system_kick_overlay(
+        &kick,
+        &DashedLineStyle::new(Rgba([255, 255, 255, 255])),
+        &LabelOverlayStyle::enabled(Rgba([255, 255, 0, 255])),
+    );
+
+    let scene = game_state.to_diagram_scene(&DiagramRenderOptions::default());
+    let labels: Vec<&str> = scene
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            DiagramElement::TextLabel { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(labels, vec!["5", "2", "3"]);
+    assert_eq!(scene.elements.len(), 6);
+}
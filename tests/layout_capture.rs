@@ -0,0 +1,228 @@
+use billiards::assets::TableCalibration;
+use billiards::capture::{
+    CornerCalibration, DetectedBall, DetectorSource, FrameDetections, LayoutSource,
+};
+use billiards::diagram::{DiagramViewport, ScenePoint};
+use billiards::{BallType, Position, TableSpec};
+
+/// A 1000 × 1800 frame whose playing surface spans 100..900 by 100..1700, 200 px per diamond.
+fn frame(balls: Vec<DetectedBall>) -> FrameDetections {
+    let calibration = TableCalibration {
+        leftmost_px: 100.0,
+        rightmost_px: 900.0,
+        topmost_px: 100.0,
+        bottommost_px: 1700.0,
+    };
+    FrameDetections {
+        balls,
+        viewport: DiagramViewport::calibrated(1000, 1800, &calibration),
+    }
+}
+
+fn detected(ty: Option<BallType>, x: f32, y: f32, confidence: f32) -> DetectedBall {
+    DetectedBall {
+        ty,
+        center: ScenePoint { x, y },
+        confidence,
+    }
+}
+
+#[test]
+fn confident_detections_on_the_playing_surface_become_the_layout() {
+    let frame = frame(vec![
+        detected(Some(BallType::Cue), 500.0, 500.0, 0.95),
+        detected(Some(BallType::Nine), 300.0, 1300.0, 0.6),
+        detected(Some(BallType::Nine), 310.0, 1310.0, 0.9),
+        detected(Some(BallType::Two), 700.0, 1000.0, 0.2),
+        detected(None, 600.0, 600.0, 0.99),
+        detected(Some(BallType::Eight), 950.0, 1000.0, 0.99),
+    ]);
+
+    let state = frame.to_game_state(&TableSpec::default(), 0.5);
+
+    let balls: Vec<_> = state
+        .balls()
+        .iter()
+        .map(|ball| (ball.ty.clone(), ball.position.clone()))
+        .collect();
+    assert_eq!(
+        balls,
+        [
+            (BallType::Cue, Position::new(2u8, 6u8)),
+            (BallType::Nine, Position::new("1.05", "1.95")),
+        ]
+    );
+    let rejected: Vec<_> = frame.rejected(0.5).map(|ball| ball.ty.clone()).collect();
+    assert_eq!(rejected, [Some(BallType::Two), None, Some(BallType::Eight)]);
+}
+
+#[test]
+fn a_detector_function_plugs_in_as_a_layout_source() {
+    let source = DetectorSource::new(|| {
+        Ok(frame(vec![
+            detected(Some(BallType::Cue), 500.0, 500.0, 0.95),
+            detected(Some(BallType::One), 500.0, 1300.0, 0.4),
+        ]))
+    })
+    .with_min_confidence(0.3);
+    let failing = DetectorSource::new(|| Err("camera unplugged".to_string()));
+
+    let state = source.capture().unwrap();
+
+    assert_eq!(
+        state.select_ball(BallType::One).unwrap().position,
+        Position::new(2u8, 2u8)
+    );
+    assert_eq!(failing.capture().unwrap_err(), "camera unplugged");
+    let sources: [&dyn LayoutSource; 2] = [&source, &failing];
+    assert_eq!(sources.iter().filter(|s| s.capture().is_ok()).count(), 1);
+}
+
+fn point(x: f32, y: f32) -> ScenePoint {
+    ScenePoint { x, y }
+}
+
+fn diamonds(position: &Position) -> (f64, f64) {
+    let value = |diamond: &billiards::Diamond| diamond.magnitude.to_string().parse().unwrap();
+    (value(&position.x), value(&position.y))
+}
+
+#[test]
+fn a_square_on_corner_calibration_agrees_with_the_viewport() {
+    let calibration = CornerCalibration::new(
+        point(100.0, 100.0),
+        point(900.0, 100.0),
+        point(900.0, 1700.0),
+        point(100.0, 1700.0),
+    )
+    .unwrap();
+    let viewport = frame(Vec::new()).viewport;
+
+    for pixel in [
+        point(500.0, 500.0),
+        point(310.0, 1310.0),
+        point(950.0, 40.0),
+    ] {
+        assert_eq!(
+            calibration.position(pixel),
+            Some(viewport.scene_point_to_position(pixel))
+        );
+    }
+}
+
+#[test]
+fn a_corner_calibration_corrects_for_perspective() {
+    // Shot from beyond the foot rail, so the head rail at the top looks half as wide.
+    let calibration: CornerCalibration = "
+        # Measured by hand.
+        top_left = 300, 100
+        top_right = 700, 100
+        bottom_right = 900, 1700
+        bottom_left = 100, 1700
+    "
+    .parse()
+    .unwrap();
+
+    assert_eq!(
+        calibration.position(point(300.0, 100.0)),
+        Some(Position::new(0u8, 8u8))
+    );
+    assert_eq!(
+        calibration.position(point(900.0, 1700.0)),
+        Some(Position::new(4u8, 0u8))
+    );
+    // The diagonals cross over the center spot, a third of the way down the image.
+    let (x, y) = diamonds(
+        &calibration
+            .position(point(500.0, 100.0 + 1600.0 / 3.0))
+            .unwrap(),
+    );
+    assert!(
+        (x - 2.0).abs() < 1e-3 && (y - 4.0).abs() < 1e-3,
+        "({x}, {y})"
+    );
+
+    let pixel = calibration.pixel(&Position::new(1u8, "2.5"));
+    let (x, y) = diamonds(&calibration.position(pixel).unwrap());
+    assert!(
+        (x - 1.0).abs() < 1e-3 && (y - 2.5).abs() < 1e-3,
+        "({x}, {y})"
+    );
+
+    let state = calibration
+        .layout(
+            &TableSpec::default(),
+            [
+                (BallType::Cue, point(500.0, 1700.0)),
+                (BallType::Nine, point(700.0, 100.0)),
+            ],
+        )
+        .unwrap();
+    assert_eq!(
+        state.select_ball(BallType::Cue).unwrap().position,
+        Position::new(2u8, 0u8)
+    );
+    assert_eq!(
+        state.select_ball(BallType::Nine).unwrap().position,
+        Position::new(4u8, 8u8)
+    );
+}
+
+#[test]
+fn pixels_on_or_past_the_horizon_have_no_position() {
+    // The side rails run toward a vanishing point at (500, -1500), so the horizon is y = -1500.
+    let calibration = CornerCalibration::new(
+        point(300.0, 100.0),
+        point(700.0, 100.0),
+        point(900.0, 1700.0),
+        point(100.0, 1700.0),
+    )
+    .unwrap();
+
+    assert_eq!(calibration.position(point(500.0, -1500.0)), None);
+    assert_eq!(calibration.position(point(200.0, -1500.0)), None);
+    assert_eq!(calibration.position(point(500.0, -2000.0)), None);
+    let (_, y) = diamonds(&calibration.position(point(500.0, -1000.0)).unwrap());
+    assert!(y > 8.0, "{y}");
+
+    assert_eq!(
+        calibration
+            .layout(
+                &TableSpec::default(),
+                [
+                    (BallType::Cue, point(500.0, 900.0)),
+                    (BallType::Nine, point(500.0, -1500.0)),
+                ],
+            )
+            .unwrap_err(),
+        "Nine is marked at (500, -1500), on or past the horizon of the table"
+    );
+}
+
+#[test]
+fn corner_calibration_files_name_what_they_cannot_read() {
+    let corners = "top_left = 0, 0\ntop_right = 10, 0\nbottom_right = 10, 20\n";
+    for (text, error) in [
+        (corners.to_string(), "missing 'bottom_left'"),
+        (
+            format!("{corners}bottom_left = 0"),
+            "line 4: expected `x, y` in pixels",
+        ),
+        (
+            format!("{corners}bottom_left 0, 20"),
+            "line 4: expected `key = value`",
+        ),
+        (
+            format!("{corners}middle = 5, 10"),
+            "line 4: unknown key 'middle'",
+        ),
+        (
+            "top_left = 0, 0\ntop_right = 10, 20\nbottom_right = 10, 0\nbottom_left = 0, 20"
+                .to_string(),
+            "the corners must outline the playing surface in order: top left, top right, \
+             bottom right, bottom left",
+        ),
+    ] {
+        assert_eq!(text.parse::<CornerCalibration>().unwrap_err(), error);
+    }
+}
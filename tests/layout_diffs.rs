@@ -0,0 +1,79 @@
+use billiards::simulation::simulate;
+use billiards::{
+    Ball, BallSpec, BallType, CueTipContact, GameState, Inches, InchesPerSecond, Pocket, Position,
+    Shot, TableSpec,
+};
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn layout(balls: Vec<Ball>) -> GameState {
+    GameState::with_balls(TableSpec::default(), balls)
+}
+
+#[test]
+fn a_diff_lists_moved_added_and_removed_balls() {
+    let before = layout(vec![
+        ball_at(BallType::Cue, "2", "6"),
+        ball_at(BallType::One, "1", "2"),
+        ball_at(BallType::Nine, "3", "3"),
+    ]);
+    let mut shifted = Position::new("0", "2");
+    shifted.shift_horizontally_inches(Inches::from_f64(12.5));
+    let after = layout(vec![
+        ball_at(BallType::Two, "2", "4"),
+        Ball {
+            ty: BallType::One,
+            position: shifted,
+            spec: BallSpec::default(),
+        },
+        ball_at(BallType::Cue, "2.5", "4"),
+    ]);
+
+    let diff = before.diff(&after);
+
+    assert_eq!(diff.moved.len(), 1);
+    let step = &diff.moved[0];
+    assert_eq!(step.ty, BallType::Cue);
+    assert_eq!(step.to, Position::new("2.5", "4"));
+    assert_eq!(step.displacement.dy.magnitude, (-2).into());
+    let removed: Vec<_> = diff.removed.iter().map(|ball| ball.ty.clone()).collect();
+    assert_eq!(removed, [BallType::Nine]);
+    let added: Vec<_> = diff.added.iter().map(|ball| ball.ty.clone()).collect();
+    assert_eq!(added, [BallType::Two]);
+    assert!(diff.pocketed.is_empty());
+    assert_eq!(
+        diff.to_string(),
+        "cue ball moved 2.06 diamonds to (2.5, 4)\n9 ball removed\n2 ball added"
+    );
+    assert!(before.diff(&before.clone()).is_empty());
+}
+
+#[test]
+fn a_simulated_shot_diffs_its_drops_as_pocketed() {
+    let state = layout(vec![
+        ball_at(BallType::Cue, "1", "4"),
+        ball_at(BallType::One, "2", "4"),
+    ]);
+    let shot = Shot::toward(
+        &Position::new("1", "4"),
+        &Position::new("2", "4"),
+        InchesPerSecond::new("80"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+
+    let diff = simulate(&state, &shot).unwrap().diff();
+
+    assert_eq!(diff.pocketed, [(BallType::One, Pocket::CenterRight)]);
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.moved[0].ty, BallType::Cue);
+    assert!(diff
+        .to_string()
+        .starts_with("1 ball pocketed in the center right pocket\ncue ball moved"));
+}
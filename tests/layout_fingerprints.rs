@@ -0,0 +1,76 @@
+use billiards::{Ball, BallSpec, BallType, GameState, GameType, Inches, Position, TableSpec};
+
+fn ball_at(ty: BallType, position: Position) -> Ball {
+    Ball {
+        ty,
+        position,
+        spec: BallSpec::default(),
+    }
+}
+
+fn layout(balls: Vec<Ball>) -> GameState {
+    GameState::with_balls(TableSpec::default(), balls)
+}
+
+#[test]
+fn equal_layouts_share_a_fingerprint_whatever_the_ball_order_or_annotations() {
+    let state = layout(vec![
+        ball_at(BallType::Cue, Position::new("2", "6")),
+        ball_at(BallType::Nine, Position::new("3.5", "7.25")),
+    ]);
+    let mut reordered = layout(vec![
+        ball_at(BallType::Nine, Position::new("3.50", "7.2500000001")),
+        ball_at(BallType::Cue, Position::new(2u8, 6u8)),
+    ]);
+    reordered.add_arrow(
+        &Position::new("2", "6"),
+        &Position::new("3.5", "7.25"),
+        image::Rgba([255, 255, 255, 255]),
+    );
+
+    assert_eq!(state.fingerprint(), reordered.fingerprint());
+    // Pinned, so a change that would invalidate stored fingerprints shows up here.
+    assert_eq!(state.fingerprint(), 9974636320151522769);
+}
+
+#[test]
+fn fingerprints_tell_apart_positions_games_ball_sizes_and_tables() {
+    let state = layout(vec![ball_at(BallType::Cue, Position::new("2", "6"))]);
+    let moved = layout(vec![ball_at(BallType::Cue, Position::new("2", "6.001"))]);
+    let mut eight_ball = state.clone();
+    eight_ball.ty = GameType::EightBall;
+    let big_ball = layout(vec![Ball {
+        spec: BallSpec {
+            radius: Inches::from_f64(1.1875),
+        },
+        ..ball_at(BallType::Cue, Position::new("2", "6"))
+    }]);
+    let mut small_table = state.clone();
+    small_table.table_spec.diamond_length = Inches::from_f64(11.0);
+
+    let fingerprints = [
+        state.fingerprint(),
+        moved.fingerprint(),
+        eight_ball.fingerprint(),
+        big_ball.fingerprint(),
+        small_table.fingerprint(),
+    ];
+
+    for (i, a) in fingerprints.iter().enumerate() {
+        for b in &fingerprints[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+}
+
+#[test]
+fn a_fingerprint_is_taken_after_shifts_are_resolved() {
+    let mut shifted = Position::new("1", "6");
+    shifted.shift_horizontally_inches(Inches::from_f64(12.5));
+    let state = layout(vec![ball_at(BallType::Cue, Position::new("2", "6"))]);
+
+    assert_eq!(
+        layout(vec![ball_at(BallType::Cue, shifted)]).fingerprint(),
+        state.fingerprint()
+    );
+}
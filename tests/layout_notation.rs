@@ -0,0 +1,86 @@
+use billiards::{
+    Ball, BallSpec, BallType, CueballModifier, GameState, GameType, Inches, Position, TableSpec,
+};
+
+#[test]
+fn layouts_round_trip_through_one_line_of_notation() {
+    let mut state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            Ball {
+                ty: BallType::Cue,
+                position: Position::new(2u8, 6u8),
+                spec: BallSpec::default(),
+            },
+            Ball {
+                ty: BallType::Nine,
+                position: Position::new("3.50", "7.25"),
+                spec: BallSpec::default(),
+            },
+            Ball {
+                ty: BallType::Fifteen,
+                position: Position::new(0u8, "0.5"),
+                spec: BallSpec::default(),
+            },
+        ],
+    );
+    state.cueball_modifier = CueballModifier::BallInHand;
+    state.ty = GameType::TenBall;
+
+    let notation = state.to_notation();
+    assert_eq!(notation, "c:2,6/9:3.5,7.25/15:0,0.5 bih 10b");
+
+    let restored = GameState::from_notation(&notation).unwrap();
+    assert_eq!(restored.to_notation(), notation);
+    assert_eq!(
+        restored.select_ball(BallType::Nine).unwrap().position,
+        Position::new("3.5", "7.25")
+    );
+    assert!(matches!(restored.ty, GameType::TenBall));
+    assert!(matches!(
+        restored.cueball_modifier,
+        CueballModifier::BallInHand
+    ));
+    assert_eq!(GameState::default().to_notation(), "- - 9b");
+}
+
+#[test]
+fn notation_defaults_the_trailing_fields_and_names_what_it_cannot_read() {
+    let state = GameState::from_notation("  C:1,2/8:2,2 ").unwrap();
+    assert_eq!(state.balls().len(), 2);
+    assert!(matches!(state.ty, GameType::NineBall));
+    assert!(matches!(state.cueball_modifier, CueballModifier::AsItLays));
+    assert!(GameState::from_notation("-").unwrap().balls().is_empty());
+
+    for (notation, error) in [
+        ("", "expected the balls, or `-` for none"),
+        ("16:1,1", "unknown ball '16'"),
+        ("c:1,1/c:2,2", "ball 'c' is placed twice"),
+        ("8:1", "expected `x,y` after '8:', found '1'"),
+        ("8:1,left", "expected a number of diamonds, found 'left'"),
+        ("8@1,1", "expected `ball:x,y`, found '8@1,1'"),
+        ("8:1,1 spot", "unknown cue ball modifier 'spot'"),
+        ("8:1,1 - 7b", "unknown game '7b'"),
+        ("8:1,1 - 8b extra", "unexpected 'extra' after the game"),
+    ] {
+        assert_eq!(GameState::from_notation(notation).unwrap_err(), error);
+    }
+}
+
+#[test]
+fn notation_writes_shifts_given_in_inches_where_they_put_the_ball() {
+    let mut position = Position::new(1u8, 2u8);
+    position
+        .shift_horizontally_inches(Inches::from_f64(12.5))
+        .shift_vertically_inches(Inches::from_f64(25.0));
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [Ball {
+            ty: BallType::Cue,
+            position,
+            spec: BallSpec::default(),
+        }],
+    );
+
+    assert_eq!(state.to_notation(), "c:2,4 - 9b");
+}
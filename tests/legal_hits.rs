@@ -0,0 +1,112 @@
+use billiards::rules::{EightBallRules, Foul, GameRules, HitVerdict, NineBallRules};
+use billiards::simulation::simulate;
+use billiards::{
+    Ball, BallSpec, BallType, CueTipContact, GameState, InchesPerSecond, Position, Shot, TableSpec,
+};
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+/// The cue ball on (1, 4) with `balls`, struck toward `toward` at `speed`.
+fn play(balls: Vec<Ball>, toward: (&str, &str), speed: &str) -> (GameState, Shot) {
+    let mut all = vec![ball_at(BallType::Cue, "1", "4")];
+    all.extend(balls);
+    let state = GameState::with_balls(TableSpec::default(), all);
+    let shot = Shot::toward(
+        &Position::new("1", "4"),
+        &Position::new(toward.0, toward.1),
+        InchesPerSecond::new(speed),
+        CueTipContact::center(),
+    )
+    .unwrap();
+    (state, shot)
+}
+
+fn verdict(rules: &dyn GameRules, state: &GameState, shot: &Shot) -> HitVerdict {
+    let result = simulate(state, shot).unwrap();
+    rules.verdict(state, &result)
+}
+
+#[test]
+fn potting_the_lowest_ball_is_a_legal_hit() {
+    let (state, shot) = play(
+        vec![
+            ball_at(BallType::One, "2", "4"),
+            ball_at(BallType::Two, "1", "7"),
+        ],
+        ("2", "4"),
+        "80",
+    );
+
+    let result = simulate(&state, &shot).unwrap();
+
+    assert_eq!(result.first_contact(), Some(BallType::One));
+    assert!(result.rail_after_contact());
+    assert_eq!(NineBallRules.verdict(&state, &result), HitVerdict::LegalHit);
+}
+
+#[test]
+fn hitting_a_higher_ball_first_in_nine_ball_is_a_foul() {
+    let (state, shot) = play(
+        vec![
+            ball_at(BallType::Two, "2", "4"),
+            ball_at(BallType::One, "1", "7"),
+        ],
+        ("2", "4"),
+        "80",
+    );
+
+    assert_eq!(
+        verdict(&NineBallRules, &state, &shot),
+        HitVerdict::Foul(Foul::WrongBallFirst(BallType::Two))
+    );
+}
+
+#[test]
+fn missing_everything_is_a_foul() {
+    let (state, shot) = play(vec![ball_at(BallType::One, "3", "7")], ("1", "0"), "30");
+
+    let result = simulate(&state, &shot).unwrap();
+
+    assert_eq!(result.first_contact(), None);
+    assert_eq!(
+        NineBallRules.verdict(&state, &result),
+        HitVerdict::Foul(Foul::NoContact)
+    );
+}
+
+#[test]
+fn a_touch_that_sends_nothing_to_a_cushion_is_a_foul() {
+    let (state, shot) = play(vec![ball_at(BallType::One, "1.5", "4")], ("1.5", "4"), "15");
+
+    let result = simulate(&state, &shot).unwrap();
+
+    assert_eq!(result.first_contact(), Some(BallType::One));
+    assert!(!result.rail_after_contact());
+    assert_eq!(
+        NineBallRules.verdict(&state, &result),
+        HitVerdict::Foul(Foul::NoRailAfterContact)
+    );
+}
+
+#[test]
+fn hitting_the_eight_first_on_an_open_table_is_a_foul() {
+    let (state, shot) = play(
+        vec![
+            ball_at(BallType::Eight, "2", "4"),
+            ball_at(BallType::Three, "1", "7"),
+        ],
+        ("2", "4"),
+        "80",
+    );
+
+    assert_eq!(
+        verdict(&EightBallRules::default(), &state, &shot),
+        HitVerdict::Foul(Foul::WrongBallFirst(BallType::Eight))
+    );
+}
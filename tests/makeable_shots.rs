@@ -0,0 +1,168 @@
+use billiards::aiming::shot_difficulty;
+use billiards::rules::{
+    AnyBallRules, EightBallGroup, EightBallRules, GameRules, MakeableShot, NineBallRules,
+};
+use billiards::{Ball, BallSpec, BallType, GameState, Pocket, Position, TableSpec};
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn object_balls(shots: &[MakeableShot]) -> Vec<BallType> {
+    let mut balls: Vec<BallType> = Vec::new();
+    for shot in shots {
+        if !balls.contains(&shot.object_ball) {
+            balls.push(shot.object_ball.clone());
+        }
+    }
+    balls
+}
+
+/// Only the side pockets count, as a stand-in for a game that restricts pockets.
+struct SidePocketsOnly;
+
+impl GameRules for SidePocketsOnly {
+    fn legal_object_balls(&self, state: &GameState) -> Vec<BallType> {
+        AnyBallRules.legal_object_balls(state)
+    }
+
+    fn is_legal_pocket(&self, _object_ball: &BallType, pocket: Pocket) -> bool {
+        matches!(pocket, Pocket::CenterLeft | Pocket::CenterRight)
+    }
+}
+
+#[test]
+fn nine_ball_only_offers_shots_on_the_lowest_numbered_ball() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "2", "2"),
+            ball_at(BallType::Three, "2", "4"),
+            ball_at(BallType::Five, "1", "7"),
+            ball_at(BallType::Nine, "3", "7"),
+        ],
+    );
+
+    let shots = state.makeable_shots(&NineBallRules);
+
+    assert!(!shots.is_empty());
+    assert_eq!(object_balls(&shots), vec![BallType::Three]);
+}
+
+#[test]
+fn shots_are_sorted_easiest_first_and_carry_their_difficulty() {
+    let table = TableSpec::default();
+    let cue_ball = ball_at(BallType::Cue, "2", "2");
+    let object_ball = ball_at(BallType::One, "2", "4");
+    let state = GameState::with_balls(table.clone(), [cue_ball.clone(), object_ball.clone()]);
+
+    let shots = state.makeable_shots(&NineBallRules);
+
+    assert!(shots.len() >= 2);
+    assert!(shots
+        .windows(2)
+        .all(|pair| pair[0].difficulty <= pair[1].difficulty));
+    for shot in &shots {
+        assert_eq!(
+            Some(shot.difficulty),
+            shot_difficulty(&cue_ball.position, &object_ball, shot.pocket, &table)
+        );
+        assert_eq!(
+            shot.ghost_ball,
+            object_ball.ghost_ball_to_pocket(shot.pocket, &table)
+        );
+    }
+}
+
+#[test]
+fn a_blocked_pocket_is_not_makeable() {
+    let open = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "2", "4"),
+        ],
+    );
+    let blocked = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "2", "4"),
+            ball_at(BallType::Two, "3", "4"),
+        ],
+    );
+
+    let pockets = |state: &GameState| -> Vec<Pocket> {
+        state
+            .makeable_shots(&NineBallRules)
+            .into_iter()
+            .map(|shot| shot.pocket)
+            .collect()
+    };
+
+    assert!(pockets(&open).contains(&Pocket::CenterRight));
+    assert!(!pockets(&blocked).contains(&Pocket::CenterRight));
+}
+
+#[test]
+fn eight_ball_plays_the_group_then_the_eight() {
+    let table = TableSpec::default();
+    let with_solid = GameState::with_balls(
+        table.clone(),
+        [
+            ball_at(BallType::Cue, "2", "2"),
+            ball_at(BallType::Two, "1", "5"),
+            ball_at(BallType::Eight, "2", "4"),
+            ball_at(BallType::Nine, "3", "5"),
+        ],
+    );
+    let solids_cleared = GameState::with_balls(
+        table,
+        [
+            ball_at(BallType::Cue, "2", "2"),
+            ball_at(BallType::Eight, "2", "4"),
+            ball_at(BallType::Nine, "3", "5"),
+        ],
+    );
+    let solids = EightBallRules::new(EightBallGroup::Solids);
+
+    assert_eq!(solids.legal_object_balls(&with_solid), vec![BallType::Two]);
+    assert_eq!(
+        EightBallRules::default().legal_object_balls(&with_solid),
+        vec![BallType::Two, BallType::Nine]
+    );
+    assert_eq!(
+        object_balls(&solids_cleared.makeable_shots(&solids)),
+        vec![BallType::Eight]
+    );
+}
+
+#[test]
+fn rules_can_restrict_the_pockets() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "3"),
+            ball_at(BallType::One, "2", "4"),
+            ball_at(BallType::Two, "1", "6"),
+        ],
+    );
+
+    let shots = state.makeable_shots(&SidePocketsOnly);
+
+    assert!(!shots.is_empty());
+    assert!(shots
+        .iter()
+        .all(|shot| matches!(shot.pocket, Pocket::CenterLeft | Pocket::CenterRight)));
+}
+
+#[test]
+fn without_a_cue_ball_nothing_is_makeable() {
+    let state = GameState::with_balls(TableSpec::default(), [ball_at(BallType::One, "2", "4")]);
+
+    assert!(state.makeable_shots(&AnyBallRules).is_empty());
+}
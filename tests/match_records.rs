@@ -0,0 +1,285 @@
+use billiards::records::{Inning, InningOutcome, MatchRecord, Player, RackRecord, ShotRecord};
+use billiards::rules::Foul;
+use billiards::simulation::simulate;
+use billiards::{
+    Ball, BallSpec, BallType, CueTipContact, CueballModifier, GameState, GameType, InchesPerSecond,
+    Position, Shot, TableSpec,
+};
+
+const RECORD: &str = "\
+[Event \"Tuesday league\"]
+[Date \"2026.10.16\"]
+[Game \"9b\"]
+[PlayerA \"Ann \\\"The Hammer\\\" Lee\"]
+[PlayerB \"Bo Park\"]
+
+1. A brk:1,4 2 - x
+2. B 3 - s
+3. A - f:scratch
+4. B bih:5,6 7 8 9 w
+0-1
+
+1. B brk:- f:no-rail ; a dry break
+2. A bih:1 2 - p
+3. B - f:wrong-4
+*
+";
+
+#[test]
+fn match_records_read_racks_innings_shots_and_fouls() {
+    let record = MatchRecord::from_text(RECORD).unwrap();
+
+    assert!(matches!(record.game, GameType::NineBall));
+    assert_eq!(record.player_name(Player::A), "Ann \"The Hammer\" Lee");
+    assert_eq!(record.player_name(Player::B), "Bo Park");
+    assert_eq!(record.tag("Event"), Some("Tuesday league"));
+    assert_eq!(record.tag("Game"), None);
+    assert_eq!(record.racks.len(), 2);
+
+    let first = &record.racks[0];
+    assert_eq!(first.breaker(), Some(Player::A));
+    assert_eq!(first.winner(), Some(Player::B));
+    assert_eq!(
+        first.innings[0].shots,
+        [
+            ShotRecord::new(vec![BallType::One, BallType::Four])
+                .with_cueball_modifier(CueballModifier::BreakPlacement),
+            ShotRecord::new(vec![BallType::Two]),
+            ShotRecord::new(Vec::new()),
+        ]
+    );
+    assert_eq!(first.innings[0].outcome, InningOutcome::Miss);
+    assert_eq!(first.innings[1].outcome, InningOutcome::Safety);
+    assert_eq!(first.innings[2].outcome, InningOutcome::Foul(Foul::Scratch));
+    assert_eq!(
+        first.innings[3].shots[0].cueball_modifier,
+        CueballModifier::BallInHand
+    );
+
+    let second = &record.racks[1];
+    assert_eq!(second.breaker(), Some(Player::B));
+    assert_eq!(second.winner(), None);
+    assert_eq!(
+        second.innings[0].outcome,
+        InningOutcome::Foul(Foul::NoRailAfterContact)
+    );
+    assert_eq!(second.innings[1].outcome, InningOutcome::PushOut);
+    assert_eq!(
+        second.innings[2].outcome,
+        InningOutcome::Foul(Foul::WrongBallFirst(BallType::Four))
+    );
+    assert_eq!(record.racks_won(Player::A), 0);
+    assert_eq!(record.racks_won(Player::B), 1);
+
+    let written = record.to_text();
+    assert!(written.starts_with("[Event \"Tuesday league\"]\n"));
+    assert!(written.contains("\n\n1. B brk:- f:no-rail\n2. A bih:1 2 - p\n"));
+    assert_eq!(MatchRecord::from_text(&written).unwrap(), record);
+}
+
+#[test]
+fn match_records_are_built_in_code_and_from_simulated_shots() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            Ball {
+                ty: BallType::Cue,
+                position: Position::new(1u8, 4u8),
+                spec: BallSpec::default(),
+            },
+            Ball {
+                ty: BallType::Nine,
+                position: Position::new(2u8, 4u8),
+                spec: BallSpec::default(),
+            },
+        ],
+    );
+    let shot = Shot::toward(
+        &Position::new(1u8, 4u8),
+        &Position::new(2u8, 4u8),
+        InchesPerSecond::new("80"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+    let played = ShotRecord::played(&simulate(&state, &shot).unwrap());
+    assert_eq!(played, ShotRecord::new(vec![BallType::Nine]));
+
+    let mut record = MatchRecord::new(GameType::TenBall, "Ann", "Bo").with_tag("Round", "3");
+    record.racks.push(RackRecord {
+        innings: vec![Inning {
+            player: Player::B,
+            shots: vec![played],
+            outcome: InningOutcome::Lost,
+        }],
+    });
+    assert_eq!(
+        record.to_text(),
+        "[Round \"3\"]\n[Game \"10b\"]\n[PlayerA \"Ann\"]\n[PlayerB \"Bo\"]\n\n1. B 9 l\n1-0\n"
+    );
+    assert_eq!(record.racks_won(Player::A), 1);
+}
+
+#[test]
+fn match_records_name_the_line_they_cannot_read() {
+    let header = "[PlayerA \"Ann\"]\n[PlayerB \"Bo\"]\n";
+    for (body, error) in [
+        (
+            "1. A brk:1 w\n",
+            "the last rack has no result; end it with `1-0`, `0-1` or `*`",
+        ),
+        (
+            "1. A brk:1 w\n1-0\n[Game \"8b\"]\n",
+            "line 5: tags must come before the first rack",
+        ),
+        (
+            "1. A brk:1 w\n0-1\n",
+            "line 4: result '0-1' does not match the last inning, which gives '1-0'",
+        ),
+        ("2. A brk:1 x\n", "line 3: expected inning 1, found 2"),
+        (
+            "1. C brk:1 x\n",
+            "line 3: expected the shooter, `A` or `B`, found 'C'",
+        ),
+        ("1. A x\n", "line 3: inning 1 records no shots before 'x'"),
+        ("1. A 16 x\n", "line 3: unknown ball '16' in shot '16'"),
+        ("1. A pos:1 x\n", "line 3: unknown cue ball modifier 'pos'"),
+        (
+            "1. A 1 w\n2. B 2 x\n",
+            "line 4: inning 2 follows the end of the rack; expected its result",
+        ),
+        ("*\n", "line 3: result '*' ends a rack with no innings"),
+    ] {
+        assert_eq!(
+            MatchRecord::from_text(&format!("{header}{body}")).unwrap_err(),
+            error
+        );
+    }
+    assert!(
+        MatchRecord::from_text(&format!("{header}1. A 1 f:double-hit\n"))
+            .unwrap_err()
+            .ends_with("or `f:` and a foul, found 'f:double-hit'")
+    );
+    assert_eq!(
+        MatchRecord::from_text("[PlayerA \"Ann\"]\n").unwrap_err(),
+        "missing the `[PlayerB \"...\"]` tag"
+    );
+    assert_eq!(
+        MatchRecord::from_text("[PlayerA \"Ann\"]\n[PlayerA \"Bo\"]\n").unwrap_err(),
+        "line 2: the tag 'PlayerA' is given twice"
+    );
+    assert_eq!(
+        MatchRecord::from_text("[Game \"7b\"]\n").unwrap_err(),
+        "unknown game '7b'"
+    );
+}
+
+/// A small xorshift generator, so the round trip below sees the same records on every run.
+struct Random(u64);
+
+impl Random {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+
+    fn pick<T: Clone>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())].clone()
+    }
+
+    fn text(&mut self) -> String {
+        (0..self.below(8))
+            .map(|_| {
+                self.pick(&[
+                    'a', 'Z', ' ', '"', '\\', '\n', '\r', ';', ']', '[', 'é', 'n',
+                ])
+            })
+            .collect()
+    }
+
+    fn ball(&mut self) -> BallType {
+        match self.below(16) {
+            0 => BallType::Cue,
+            number => BallType::from_number(number as u8).unwrap(),
+        }
+    }
+
+    fn shot(&mut self) -> ShotRecord {
+        let modifier = self.pick(&[
+            CueballModifier::AsItLays,
+            CueballModifier::BreakPlacement,
+            CueballModifier::BallInHand,
+            CueballModifier::KitchenPlacement,
+        ]);
+        ShotRecord::new((0..self.below(4)).map(|_| self.ball()).collect())
+            .with_cueball_modifier(modifier)
+    }
+
+    fn outcome(&mut self, ends_rack: bool) -> InningOutcome {
+        match self.below(if ends_rack { 9 } else { 7 }) {
+            0 => InningOutcome::Miss,
+            1 => InningOutcome::Safety,
+            2 => InningOutcome::PushOut,
+            3 => InningOutcome::Foul(Foul::Scratch),
+            4 => InningOutcome::Foul(Foul::NoContact),
+            5 => InningOutcome::Foul(Foul::NoRailAfterContact),
+            6 => InningOutcome::Foul(Foul::WrongBallFirst(self.ball())),
+            7 => InningOutcome::Won,
+            _ => InningOutcome::Lost,
+        }
+    }
+
+    fn record(&mut self) -> MatchRecord {
+        let game = self.pick(&[
+            GameType::NineBall,
+            GameType::EightBall,
+            GameType::TenBall,
+            GameType::OnePocket,
+            GameType::Banks,
+        ]);
+        let mut record = MatchRecord::new(game, &self.text(), &self.text());
+        for name in ["Event", "Site", "Date"] {
+            if self.below(2) == 0 {
+                record = record.with_tag(name, &self.text());
+            }
+        }
+        for _ in 0..self.below(4) {
+            let count = 1 + self.below(5);
+            let innings = (0..count)
+                .map(|index| Inning {
+                    player: self.pick(&[Player::A, Player::B]),
+                    shots: (0..1 + self.below(3)).map(|_| self.shot()).collect(),
+                    outcome: self.outcome(index + 1 == count),
+                })
+                .collect();
+            record.racks.push(RackRecord { innings });
+        }
+        record
+    }
+}
+
+#[test]
+fn every_match_record_reads_back_from_the_text_it_writes() {
+    let mut random = Random(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..500 {
+        let record = random.record();
+        let text = record.to_text();
+
+        assert_eq!(MatchRecord::from_text(&text), Ok(record), "{text}");
+    }
+
+    let cue_only = ShotRecord::new(vec![BallType::Cue]);
+    let mut record = MatchRecord::new(GameType::NineBall, "Ann\nLee", "Bo");
+    record.racks.push(RackRecord {
+        innings: vec![Inning {
+            player: Player::A,
+            shots: vec![cue_only],
+            outcome: InningOutcome::Foul(Foul::WrongBallFirst(BallType::Cue)),
+        }],
+    });
+    assert_eq!(
+        record.to_text(),
+        "[Game \"9b\"]\n[PlayerA \"Ann\\nLee\"]\n[PlayerB \"Bo\"]\n\n1. A c f:wrong-c\n*\n"
+    );
+}
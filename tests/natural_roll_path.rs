@@ -0,0 +1,139 @@
+use billiards::aiming::{
+    fractional_aim_ghost_ball, natural_roll_cue_ball_path, natural_roll_deflection,
+    thirty_degree_rule_applies, BallHitFraction, CutDirection,
+};
+use billiards::{
+    human_tuned_preview_motion_config, Ball, BallSpec, BallType, CutAngle, InchesPerSecond,
+    Position, TableSpec,
+};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn as_f64(value: &billiards::Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn object_ball_at(x: &str, y: &str) -> Ball {
+    Ball {
+        ty: BallType::Nine,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn natural_roll_deflection_stays_near_thirty_degrees_between_quarter_and_three_quarter_hits() {
+    for fraction in [
+        BallHitFraction::ThreeQuarter,
+        BallHitFraction::Half,
+        BallHitFraction::Quarter,
+    ] {
+        let cut = fraction.cut_angle();
+        let deflection = natural_roll_deflection(cut).as_degrees();
+
+        assert!(thirty_degree_rule_applies(cut));
+        assert!(
+            (27.0..=34.0).contains(&deflection),
+            "{fraction:?} deflected {deflection} degrees"
+        );
+    }
+    assert_close_with_tolerance(
+        natural_roll_deflection(CutAngle::new(30.0)).as_degrees(),
+        33.670_496_508_315_11,
+        1e-9,
+    );
+}
+
+#[test]
+fn full_and_very_thin_hits_fall_outside_the_thirty_degree_rule() {
+    assert!(!thirty_degree_rule_applies(CutAngle::new(0.0)));
+    assert!(!thirty_degree_rule_applies(CutAngle::new(70.0)));
+    assert_close_with_tolerance(
+        natural_roll_deflection(CutAngle::new(0.0)).as_degrees(),
+        0.0,
+        1e-9,
+    );
+}
+
+#[test]
+fn the_rolling_path_ends_on_the_deflected_heading() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new(2u8, 1u8);
+    let object_ball = object_ball_at("2", "4");
+    let ghost = fractional_aim_ghost_ball(
+        &cue_ball,
+        &object_ball,
+        BallHitFraction::Half.fraction(),
+        CutDirection::Right,
+        &table,
+    );
+
+    let path = natural_roll_cue_ball_path(
+        &cue_ball,
+        &ghost,
+        &object_ball,
+        &InchesPerSecond::new(30u8),
+        &human_tuned_preview_motion_config(),
+        &table,
+    );
+
+    assert_eq!(path.first(), Some(&ghost));
+    assert!(path.len() > 2);
+    let final_leg = path[path.len() - 2].angle_to(&path[path.len() - 1]);
+    let deflection = CutAngle::from_headings(cue_ball.angle_to(&ghost), final_leg);
+    assert_close_with_tolerance(deflection.as_degrees(), 33.6705, 1e-3);
+    // A right cut sends the cue ball left of its original line.
+    assert!(as_f64(&path[path.len() - 1].x) < as_f64(&ghost.x));
+}
+
+#[test]
+fn a_full_ball_rolling_hit_follows_straight_through() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("2", "4");
+    let ghost = Position::new("2", "3.82");
+
+    let path = natural_roll_cue_ball_path(
+        &Position::new(2u8, 1u8),
+        &ghost,
+        &object_ball,
+        &InchesPerSecond::new(30u8),
+        &human_tuned_preview_motion_config(),
+        &table,
+    );
+
+    let end = path.last().unwrap();
+    assert_close_with_tolerance(as_f64(&end.x), 2.0, 1e-9);
+    assert!(as_f64(&end.y) > 3.82);
+}
+
+#[test]
+fn a_hard_hit_rolling_path_is_clipped_at_the_cushion() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new(2u8, 1u8);
+    let object_ball = object_ball_at("2", "4");
+    let ghost = fractional_aim_ghost_ball(
+        &cue_ball,
+        &object_ball,
+        BallHitFraction::Half.fraction(),
+        CutDirection::Left,
+        &table,
+    );
+
+    let path = natural_roll_cue_ball_path(
+        &cue_ball,
+        &ghost,
+        &object_ball,
+        &InchesPerSecond::from_mph(20.0),
+        &human_tuned_preview_motion_config(),
+        &table,
+    );
+
+    let end = path.last().unwrap();
+    assert_close_with_tolerance(as_f64(&end.x), 4.0 - 1.125 / 12.5, 1e-9);
+}
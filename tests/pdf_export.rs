@@ -0,0 +1,89 @@
+use std::io::Read;
+
+use billiards::diagram::DiagramOutputFormat;
+use billiards::pdf::{diagrams_to_pdf, PdfPageLayout};
+use billiards::{Ball, BallSpec, BallType, DiagramRenderOptions, GameState, Position, TableSpec};
+use flate2::read::ZlibDecoder;
+
+fn cue_ball_at(x: &str, y: &str) -> GameState {
+    GameState::with_balls(
+        TableSpec::default(),
+        [Ball {
+            ty: BallType::Cue,
+            position: Position::new(x, y),
+            spec: BallSpec::default(),
+        }],
+    )
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|offset| from + offset)
+}
+
+/// The inflated stream of object `id`.
+fn stream(pdf: &[u8], id: usize) -> Vec<u8> {
+    let object = find(pdf, format!("\n{id} 0 obj\n").as_bytes(), 0).expect("object");
+    let start = find(pdf, b"stream\n", object).expect("stream") + b"stream\n".len();
+    let end = find(pdf, b"\nendstream", start).expect("endstream");
+    let mut inflated = Vec::new();
+    ZlibDecoder::new(&pdf[start..end])
+        .read_to_end(&mut inflated)
+        .expect("deflated stream");
+    inflated
+}
+
+#[test]
+fn a_single_diagram_pdf_is_a_well_formed_one_page_document() {
+    let pdf = cue_ball_at("2", "4")
+        .render_2d_diagram_with_options(DiagramOutputFormat::Pdf, &DiagramRenderOptions::default());
+
+    assert!(pdf.starts_with(b"%PDF-1.4\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+    assert!(find(&pdf, b"/Type /Pages /Kids [3 0 R] /Count 1", 0).is_some());
+    assert!(find(&pdf, b"/MediaBox [0 0 612.000 792.000]", 0).is_some());
+
+    let xref = find(&pdf, b"\nxref\n", 0).expect("xref") + 1;
+    let offsets = String::from_utf8_lossy(&pdf[xref..]);
+    for (id, line) in offsets.lines().skip(3).take(6).enumerate() {
+        let offset: usize = line[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with(format!("{} 0 obj", id + 1).as_bytes()));
+    }
+
+    let pixels = stream(&pdf, 5);
+    assert_eq!(pixels.len(), 1089 * 1938 * 3);
+}
+
+#[test]
+fn a_drill_sheet_lays_diagrams_out_in_a_grid_across_pages() {
+    let scenes: Vec<_> = ["1", "2", "3"]
+        .into_iter()
+        .map(|x| cue_ball_at(x, "4").to_diagram_scene(&DiagramRenderOptions::default()))
+        .collect();
+    let layout = PdfPageLayout::letter().with_grid(2, 1);
+
+    let pdf = diagrams_to_pdf(&scenes, &DiagramRenderOptions::default(), &layout);
+
+    assert!(find(&pdf, b"/Kids [3 0 R 5 0 R] /Count 2", 0).is_some());
+    let first_page = String::from_utf8(stream(&pdf, 4)).unwrap();
+    assert_eq!(first_page.matches(" Do Q").count(), 2);
+    assert!(first_page.contains("/Im0 Do") && first_page.contains("/Im1 Do"));
+    let second_page = String::from_utf8(stream(&pdf, 6)).unwrap();
+    assert!(second_page.contains("/Im2 Do"));
+}
+
+#[test]
+fn a_print_scale_sizes_the_diagram_from_the_real_table() {
+    let scene = cue_ball_at("2", "4").to_diagram_scene(&DiagramRenderOptions::default());
+    let layout = PdfPageLayout::letter().with_print_scale(1.0 / 24.0);
+
+    let pdf = diagrams_to_pdf(&[scene], &DiagramRenderOptions::default(), &layout);
+
+    // The 50 in playfield spans 858 px of the 1089 px diagram, so the whole diagram covers
+    // 63.47 in of table: 2.64 in, or 190.4 pt, at 1:24.
+    let content = String::from_utf8(stream(&pdf, 4)).unwrap();
+    let width: f64 = content.split_whitespace().nth(1).unwrap().parse().unwrap();
+    assert!((width - 190.4).abs() < 0.5, "{content}");
+}
@@ -0,0 +1,110 @@
+use billiards::visualization::MeasurementUnit;
+use billiards::{Ball, BallSpec, BallType, GameState, Inches, Position, TableSpec};
+
+fn layout() -> GameState {
+    GameState::with_balls(
+        TableSpec::default(),
+        [
+            Ball {
+                ty: BallType::Cue,
+                position: Position::new(2u8, 6u8),
+                spec: BallSpec::default(),
+            },
+            Ball {
+                ty: BallType::Eleven,
+                position: Position::new("0.8", "7.25"),
+                spec: BallSpec::default(),
+            },
+        ],
+    )
+}
+
+#[test]
+fn ball_positions_round_trip_through_csv_in_diamonds_and_inches() {
+    let state = layout();
+
+    let diamonds = state.to_csv(MeasurementUnit::Diamonds);
+    assert_eq!(
+        diamonds,
+        "ball,x_diamonds,y_diamonds\ncue,2,6\n11,0.8,7.25\n"
+    );
+    let inches = state.to_csv(MeasurementUnit::Inches);
+    assert_eq!(inches, "ball,x_inches,y_inches\ncue,25,75\n11,10,90.625\n");
+
+    for csv in [diamonds, inches] {
+        let restored = GameState::from_csv(TableSpec::default(), &csv).unwrap();
+        assert_eq!(
+            restored.to_csv(MeasurementUnit::Diamonds),
+            state.to_csv(MeasurementUnit::Diamonds)
+        );
+    }
+}
+
+#[test]
+fn csv_from_a_spreadsheet_is_read_by_its_header() {
+    let state = GameState::from_csv(
+        TableSpec::default(),
+        "\u{feff}Frame,\"Y_Inches\",Ball,X_Inches\r\n\r\n12,50,cue,25\r\n12,100,9,37.5\r\n",
+    )
+    .unwrap();
+    assert_eq!(
+        state.select_ball(BallType::Nine).unwrap().position,
+        Position::new(3u8, 8u8)
+    );
+    assert_eq!(state.balls().len(), 2);
+
+    for (csv, error) in [
+        ("", "expected a header row"),
+        (
+            "ball,x,y\n",
+            "expected `x_diamonds` and `y_diamonds` or `x_inches` and `y_inches` columns",
+        ),
+        ("x_diamonds,y_diamonds\n", "expected a `ball` column"),
+        (
+            "ball,x_diamonds,y_diamonds\n8,1\n",
+            "line 2: expected 3 cells",
+        ),
+        (
+            "ball,x_diamonds,y_diamonds\n\n16,1,1\n",
+            "line 3: unknown ball '16'",
+        ),
+        (
+            "ball,x_diamonds,y_diamonds\n8,1,one\n",
+            "line 2: expected a number, found 'one'",
+        ),
+        (
+            "ball,x_diamonds,y_diamonds\n8,1,1\n8,2,2\n",
+            "line 3: the ball is placed twice",
+        ),
+    ] {
+        assert_eq!(
+            GameState::from_csv(TableSpec::default(), csv).unwrap_err(),
+            error
+        );
+    }
+}
+
+#[test]
+fn csv_writes_shifts_given_in_inches_where_they_put_the_ball() {
+    let mut position = Position::new(1u8, 2u8);
+    position
+        .shift_horizontally_inches(Inches::from_f64(12.5))
+        .shift_vertically_inches(Inches::from_f64(25.0));
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [Ball {
+            ty: BallType::Nine,
+            position,
+            spec: BallSpec::default(),
+        }],
+    );
+
+    assert_eq!(
+        state.to_csv(MeasurementUnit::Diamonds),
+        "ball,x_diamonds,y_diamonds\n9,2,4\n"
+    );
+    assert_eq!(
+        state.to_csv(MeasurementUnit::Inches),
+        "ball,x_inches,y_inches\n9,25,50\n"
+    );
+}
@@ -1,6 +1,6 @@
 use bigdecimal::ToPrimitive;
 use billiards::{
-    translate_inwards, Angle, Ball, BallSpec, BallType, Diamond, GameState, Position, Rail,
+    translate_inwards, Angle, Ball, BallSpec, BallType, Diamond, GameState, Pocket, Position, Rail,
     TableSpec, TOP_RIGHT_DIAMOND, TYPICAL_BALL_RADIUS,
 };
 
@@ -127,3 +127,100 @@ fn given_each_rail_when_freezing_a_ball_then_the_ball_center_sits_one_radius_in_
     assert_close(diamond_value(&left.position.x), 0.09);
     assert_close(diamond_value(&left.position.y), 5.5);
 }
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn given_a_ball_inside_the_corridor_when_checking_a_path_then_it_is_reported_as_blocking() {
+    let mut state = GameState::new(TableSpec::default());
+    state.add_balls([
+        ball_at(BallType::Cue, "2", "1"),
+        ball_at(BallType::Three, "2.1", "4"),
+        ball_at(BallType::Five, "3", "4"),
+    ]);
+    let from = Position::new("2", "1");
+    let to = Position::new("2", "7");
+
+    let obstructions = state.path_obstructions(&from, &to, TYPICAL_BALL_RADIUS.clone());
+
+    assert_eq!(obstructions.len(), 1);
+    assert_eq!(obstructions[0].ty, BallType::Three);
+    assert!(!state.is_path_clear(&from, &to, TYPICAL_BALL_RADIUS.clone()));
+}
+
+#[test]
+fn given_a_ball_just_outside_the_corridor_when_checking_a_path_then_the_path_is_clear() {
+    let mut state = GameState::new(TableSpec::default());
+    // Two ball diameters is 0.18 diamonds on the default table.
+    state.add_ball(ball_at(BallType::Three, "2.19", "4"));
+
+    assert!(state.is_path_clear(
+        &Position::new("2", "1"),
+        &Position::new("2", "7"),
+        TYPICAL_BALL_RADIUS.clone(),
+    ));
+}
+
+#[test]
+fn given_a_ball_beyond_the_segment_end_when_checking_a_path_then_it_does_not_block() {
+    let mut state = GameState::new(TableSpec::default());
+    state.add_ball(ball_at(BallType::Three, "2", "6"));
+
+    assert!(state.is_path_clear(
+        &Position::new("2", "1"),
+        &Position::new("2", "5"),
+        TYPICAL_BALL_RADIUS.clone(),
+    ));
+    assert!(!state.is_path_clear(
+        &Position::new("2", "1"),
+        &Position::new("2", "5.9"),
+        TYPICAL_BALL_RADIUS.clone(),
+    ));
+}
+
+#[test]
+fn given_a_cue_ball_below_the_object_ball_when_listing_open_pockets_then_only_forward_cuts_remain()
+{
+    let mut state = GameState::new(TableSpec::default());
+    state.add_balls([
+        ball_at(BallType::Cue, "2", "2"),
+        ball_at(BallType::Nine, "2", "4"),
+    ]);
+    let nine = state.select_ball(BallType::Nine).unwrap().clone();
+
+    let pockets = state.open_pockets(&nine);
+
+    assert_eq!(pockets, vec![Pocket::TopRight, Pocket::TopLeft]);
+}
+
+#[test]
+fn given_a_blocker_on_the_object_ball_path_when_listing_open_pockets_then_that_pocket_is_closed() {
+    let mut state = GameState::new(TableSpec::default());
+    state.add_balls([
+        ball_at(BallType::Cue, "2", "2"),
+        ball_at(BallType::Nine, "2", "4"),
+        ball_at(BallType::Four, "3", "6"),
+    ]);
+    let nine = state.select_ball(BallType::Nine).unwrap().clone();
+
+    let pockets = state.open_pockets(&nine);
+
+    assert_eq!(pockets, vec![Pocket::TopLeft]);
+}
+
+#[test]
+fn given_no_cue_ball_when_listing_open_pockets_then_only_object_ball_paths_are_checked() {
+    let mut state = GameState::new(TableSpec::default());
+    state.add_ball(ball_at(BallType::Nine, "2", "4"));
+    let nine = state.select_ball(BallType::Nine).unwrap().clone();
+
+    let pockets = state.open_pockets(&nine);
+
+    assert_eq!(pockets.len(), 6);
+}
@@ -0,0 +1,129 @@
+use billiards::aiming::{position_zone, shot_difficulty};
+use billiards::{Ball, BallSpec, BallType, GameState, Pocket, Position, TableSpec};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn a_straight_in_shot_scores_the_product_of_its_distances() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::Nine, "2", "4");
+
+    let difficulty = shot_difficulty(
+        &Position::new("1", "4"),
+        &object_ball,
+        Pocket::CenterRight,
+        &table,
+    )
+    .unwrap();
+
+    // Ghost ball at x = 2 - 2.25 / 12.5 = 1.82; the object ball travels two diamonds.
+    assert_close_with_tolerance(difficulty, 0.82 * 2.0, 1e-9);
+}
+
+#[test]
+fn a_cut_is_harder_than_a_straight_in_shot_from_the_same_distance() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::Nine, "2", "4");
+
+    let straight = shot_difficulty(
+        &Position::new("1", "4"),
+        &object_ball,
+        Pocket::CenterRight,
+        &table,
+    )
+    .unwrap();
+    let cut = shot_difficulty(
+        &Position::new("1.41", "3.29"),
+        &object_ball,
+        Pocket::CenterRight,
+        &table,
+    )
+    .unwrap();
+    let behind = shot_difficulty(
+        &Position::new("3", "4"),
+        &object_ball,
+        Pocket::CenterRight,
+        &table,
+    );
+
+    assert!(cut > 1.9 * straight);
+    assert_eq!(behind, None);
+}
+
+#[test]
+fn the_zone_reaches_further_behind_a_straight_in_shot_than_at_a_thin_cut() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::Nine, "2", "4");
+
+    let zone = position_zone(&object_ball, Pocket::CenterRight, 2.0, &[], &table).unwrap();
+
+    assert_eq!(zone.outline[0], zone.ghost_ball);
+    assert!(zone.contains(&Position::new("1", "4"), &table));
+    assert!(!zone.contains(&Position::new("0.5", "4"), &table));
+    assert!(!zone.contains(&Position::new("1.41", "3.29"), &table));
+    assert!(!zone.contains(&Position::new("2.5", "4"), &table));
+}
+
+#[test]
+fn a_blocking_ball_cuts_the_zone_off_behind_it() {
+    let table = TableSpec::default();
+    let object_ball = ball_at(BallType::Nine, "2", "4");
+    let blocker = ball_at(BallType::Three, "1.3", "4");
+
+    let zone = position_zone(
+        &object_ball,
+        Pocket::CenterRight,
+        2.0,
+        &[blocker, object_ball.clone()],
+        &table,
+    )
+    .unwrap();
+
+    assert!(zone.contains(&Position::new("1.7", "4"), &table));
+    assert!(!zone.contains(&Position::new("1", "4"), &table));
+}
+
+#[test]
+fn position_zones_skip_blocked_pockets_and_ignore_the_ball_being_potted() {
+    let table = TableSpec::default();
+    let current = ball_at(BallType::One, "3", "4");
+    let next = ball_at(BallType::Two, "2", "4");
+    let game_state = GameState::with_balls(
+        table,
+        [
+            ball_at(BallType::Cue, "1", "1"),
+            current.clone(),
+            next.clone(),
+            ball_at(BallType::Five, "3", "2"),
+        ],
+    );
+
+    let pockets: Vec<Pocket> = game_state
+        .position_zones(&current, &next, 3.0)
+        .iter()
+        .map(|zone| zone.pocket)
+        .collect();
+    let blocked_by_current: Vec<Pocket> = game_state
+        .position_zones(&ball_at(BallType::Nine, "0", "0"), &next, 3.0)
+        .iter()
+        .map(|zone| zone.pocket)
+        .collect();
+
+    assert!(pockets.contains(&Pocket::CenterRight));
+    assert!(!pockets.contains(&Pocket::BottomRight));
+    assert!(!blocked_by_current.contains(&Pocket::CenterRight));
+}
@@ -0,0 +1,131 @@
+use billiards::simulation::{
+    estimate_pot_probability, EventDrivenSimulator, PotEstimate, SkillModel,
+};
+use billiards::{
+    Ball, BallSpec, BallType, CueTipContact, GameState, InchesPerSecond, Pocket, Position, Scale,
+    Shot, TableSpec,
+};
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+/// A one-diamond straight-in shot on the one into the right side pocket.
+fn straight_in_layout() -> GameState {
+    GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "2", "4"),
+        ],
+    )
+}
+
+fn straight_in_shot(tip_height: f64) -> Shot {
+    Shot::toward(
+        &Position::new("1", "4"),
+        &Position::new("2", "4"),
+        InchesPerSecond::new("80"),
+        CueTipContact::new(Scale::zero(), Scale::from_f64(tip_height)).unwrap(),
+    )
+    .expect("shot should validate")
+}
+
+fn estimate(shot: &Shot, skill: &SkillModel, samples: usize, seed: u64) -> PotEstimate {
+    estimate_pot_probability(
+        &EventDrivenSimulator::default(),
+        &straight_in_layout(),
+        shot,
+        BallType::One,
+        Pocket::CenterRight,
+        skill,
+        samples,
+        seed,
+    )
+    .expect("shot should simulate")
+}
+
+#[test]
+fn a_perfect_player_makes_a_straight_in_stun_shot_every_time() {
+    let estimate = estimate(&straight_in_shot(0.0), &SkillModel::perfect(), 3, 0);
+
+    assert_eq!(
+        estimate,
+        PotEstimate {
+            samples: 3,
+            made: 3,
+            scratched: 0,
+        }
+    );
+    assert_eq!(estimate.make_rate(), 1.0);
+    assert_eq!(estimate.scratch_rate(), 0.0);
+}
+
+#[test]
+fn sending_the_cue_ball_at_the_pocket_scratches_without_making_the_ball() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "1", "1"),
+        ],
+    );
+    let shot = Shot::toward(
+        &Position::new("1", "4"),
+        &Position::new("4", "4"),
+        InchesPerSecond::new("150"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+
+    let estimate = estimate_pot_probability(
+        &EventDrivenSimulator::default(),
+        &state,
+        &shot,
+        BallType::One,
+        Pocket::CenterRight,
+        &SkillModel::perfect(),
+        2,
+        0,
+    )
+    .unwrap();
+
+    assert_eq!(estimate.make_rate(), 0.0);
+    assert_eq!(estimate.scratch_rate(), 1.0);
+}
+
+#[test]
+fn an_unsteady_aim_misses_what_a_perfect_player_makes() {
+    let estimate = estimate(&straight_in_shot(0.0), &SkillModel::new(3.0, 0.05), 8, 1);
+
+    assert!(estimate.made > 0, "{estimate:?}");
+    assert!(estimate.made < estimate.samples / 2, "{estimate:?}");
+}
+
+#[test]
+fn the_same_seed_draws_the_same_strokes() {
+    let skill = SkillModel::new(1.0, 0.05);
+
+    let first = estimate(&straight_in_shot(0.0), &skill, 4, 42);
+    let again = estimate(&straight_in_shot(0.0), &skill, 4, 42);
+
+    assert_eq!(first, again);
+}
+
+#[test]
+fn an_empty_run_reports_zero_rates() {
+    let estimate = estimate(&straight_in_shot(0.0), &SkillModel::new(1.0, 0.05), 0, 0);
+
+    assert_eq!(estimate.make_rate(), 0.0);
+    assert_eq!(estimate.scratch_rate(), 0.0);
+}
+
+#[test]
+#[should_panic(expected = "skill errors must be non-negative")]
+fn skill_errors_cannot_be_negative() {
+    SkillModel::new(-1.0, 0.0);
+}
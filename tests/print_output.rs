@@ -0,0 +1,74 @@
+use billiards::diagram::DiagramOutputFormat;
+use billiards::{Ball, BallSpec, BallType, DiagramRenderOptions, GameState, Position, TableSpec};
+
+fn cue_ball_layout() -> GameState {
+    GameState::with_balls(
+        TableSpec::default(),
+        [Ball {
+            ty: BallType::Cue,
+            position: Position::new("2", "4"),
+            spec: BallSpec::default(),
+        }],
+    )
+}
+
+#[test]
+fn a_diagram_for_print_is_as_many_pixels_wide_as_its_inches_at_its_dpi() {
+    let options = DiagramRenderOptions::for_print(2.0, 300.0);
+
+    let png = cue_ball_layout().render_2d_diagram_with_options(DiagramOutputFormat::Png, &options);
+
+    let reader = png::Decoder::new(std::io::Cursor::new(png))
+        .read_info()
+        .expect("png decode");
+    let info = reader.info();
+    assert_eq!(info.width, 600);
+    // 1089 by 1938 native pixels, scaled to 600 across.
+    assert_eq!(info.height, 1068);
+    let dims = info.pixel_dims.expect("a pHYs chunk");
+    assert_eq!(
+        (dims.xppu, dims.yppu, dims.unit),
+        (11811, 11811, png::Unit::Meter)
+    );
+}
+
+#[test]
+fn the_print_width_includes_the_margin_and_defaults_to_300_dpi() {
+    let options = DiagramRenderOptions {
+        print_width_in: Some(1.0),
+        margin_px: 20,
+        max_width_px: Some(100),
+        ..DiagramRenderOptions::default()
+    };
+
+    let image = cue_ball_layout().render_to_image_with_options(&options);
+
+    assert_eq!(image.width(), 300);
+}
+
+#[test]
+fn dpi_is_recorded_in_jpeg_output_and_sizes_svg_output_in_inches() {
+    let options = DiagramRenderOptions {
+        dpi: Some(150.0),
+        max_width_px: Some(300),
+        ..DiagramRenderOptions::default()
+    };
+    let state = cue_ball_layout();
+
+    let jpeg = state.render_2d_diagram_with_options(DiagramOutputFormat::Jpeg, &options);
+    // The JFIF header: units of dots per inch, then the horizontal and vertical density.
+    let jfif = jpeg
+        .windows(5)
+        .position(|window| window == b"JFIF\0")
+        .expect("a JFIF header");
+    assert_eq!(&jpeg[jfif + 7..jfif + 12], &[1, 0, 150, 0, 150]);
+
+    let svg =
+        String::from_utf8(state.render_2d_diagram_with_options(DiagramOutputFormat::Svg, &options))
+            .unwrap();
+    assert!(svg.contains("width=\"2.000in\""), "{}", &svg[..300]);
+
+    let png = state.render_2d_diagram_with_options(DiagramOutputFormat::Png, &options);
+    let image = image::load_from_memory(&png).expect("png decode");
+    assert_eq!(image.width(), 300);
+}
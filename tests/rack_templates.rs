@@ -0,0 +1,127 @@
+use bigdecimal::ToPrimitive;
+use billiards::racks::{RackSlot, RackTemplate};
+use billiards::{rack_9_ball, BallType, GameType, Position};
+
+fn diamonds(position: &Position) -> (f64, f64) {
+    (
+        position.x.magnitude.to_f64().unwrap(),
+        position.y.magnitude.to_f64().unwrap(),
+    )
+}
+
+fn types(template: &RackTemplate, seed: u64) -> Vec<BallType> {
+    template
+        .rack(seed)
+        .unwrap()
+        .into_iter()
+        .map(|ball| ball.ty)
+        .collect()
+}
+
+#[test]
+fn the_nine_ball_template_racks_in_order_like_rack_9_ball_and_shuffles_by_seed() {
+    let in_order = RackTemplate::nine_ball()
+        .with_shuffle(false)
+        .rack(0)
+        .unwrap();
+
+    let frozen = rack_9_ball();
+    assert_eq!(in_order.len(), frozen.len());
+    for (ball, expected) in in_order.iter().zip(&frozen) {
+        assert_eq!(ball.ty, expected.ty);
+        let ((x, y), (expected_x, expected_y)) =
+            (diamonds(&ball.position), diamonds(&expected.position));
+        assert!((x - expected_x).abs() < 1e-9 && (y - expected_y).abs() < 1e-9);
+    }
+
+    let template = RackTemplate::nine_ball();
+    let shuffled = types(&template, 7);
+    assert_eq!(shuffled, types(&template, 7));
+    assert!((0..20).any(|seed| types(&template, seed) != shuffled));
+    assert_eq!(
+        (&shuffled[0], &shuffled[4]),
+        (&BallType::One, &BallType::Nine)
+    );
+    let mut numbers: Vec<_> = shuffled.iter().filter_map(BallType::number).collect();
+    numbers.sort();
+    assert_eq!(numbers, (1..=9).collect::<Vec<_>>());
+}
+
+#[test]
+fn open_slots_for_solids_and_stripes_only_take_their_group() {
+    let template = RackTemplate::for_game(&GameType::EightBall).unwrap();
+
+    for seed in 0..10 {
+        let rack = types(&template, seed);
+        assert_eq!(rack.len(), 15);
+        assert_eq!(rack[4], BallType::Eight);
+        assert!(rack[10].is_striped());
+        assert!(matches!(rack[14].number(), Some(1..=7)));
+    }
+    assert!(RackTemplate::for_game(&GameType::Banks).is_none());
+
+    let short = RackTemplate::new("Short", vec![vec![RackSlot::Any], vec![RackSlot::Any]])
+        .with_balls(vec![BallType::One]);
+    assert_eq!(
+        short.validate().unwrap_err(),
+        "rack 'Short' has 2 slots for 1 balls"
+    );
+    let all_stripes = RackTemplate::new(
+        "Stripes",
+        vec![
+            vec![RackSlot::Stripe],
+            vec![RackSlot::Stripe, RackSlot::Any],
+        ],
+    );
+    assert_eq!(
+        all_stripes.rack(0).unwrap_err(),
+        "rack 'Stripes' has 2 stripe slots but 0 stripes left for them"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn house_racks_load_from_toml() {
+    let template = RackTemplate::from_toml(
+        r#"
+schema_version = 2
+name = "Nine-ball, two on the apex"
+shuffle = false
+rows = [
+  ["Two"],
+  ["any", "any"],
+  ["any", "Nine", "any"],
+  ["any", "any"],
+  ["One"],
+]
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(template.rows[2][1], RackSlot::Ball(BallType::Nine));
+    assert_eq!(template.ball_set().len(), 9);
+    let rack = types(&template, 0);
+    assert_eq!(
+        rack,
+        [
+            BallType::Two,
+            BallType::Three,
+            BallType::Four,
+            BallType::Five,
+            BallType::Nine,
+            BallType::Six,
+            BallType::Seven,
+            BallType::Eight,
+            BallType::One,
+        ]
+    );
+
+    let unknown = RackTemplate::from_toml(
+        "name = \"Odd\"\nballs = [\"One\", \"Two\"]\nrows = [[\"Three\"], [\"any\"]]",
+    )
+    .unwrap_err();
+    assert!(unknown.contains("places Three"), "{unknown}");
+    let newer =
+        RackTemplate::from_toml("schema_version = 99\nname = \"Future\"\nrows = []").unwrap_err();
+    assert!(newer.contains("is newer than"), "{newer}");
+}
@@ -1,18 +1,25 @@
-use billiards::diagram::{DiagramLayerId, DiagramOutputFormat};
+use billiards::assets::AssetPack;
+use billiards::diagram::{
+    DiagramElement, DiagramLayerId, DiagramOutputFormat, DiagramViewport, TerminalBackend,
+};
 use billiards::{
     trace_ball_path_with_rails_on_table,
     visualization::{
-        AimOverlayStyle, BallPathRenderOptions, BallPathStyle, BallPathWidthMode, EventMarkerStyle,
-        GhostBallStyle, LabelOverlayStyle,
+        AimOverlayStyle, ArrowStyle, BallHighlightStyle, BallPathRenderOptions, BallPathStyle,
+        BallPathWidthMode, DashedLineStyle, DiamondGridStyle, EventMarkerStyle, GhostBallStyle,
+        LabelOverlayStyle, LegendEntry, LegendSwatch, MeasurementStyle, SequenceMarkerStyle,
+        ShadedZoneStyle, SmoothPolylineStyle, TableTheme, DIMMED_BALL_OPACITY,
     },
-    Angle, AngularVelocity3, Ball, BallPathStop, BallSetPhysicsSpec, BallSpec, BallState, BallType,
-    DiagramBackground, DiagramRenderOptions, Diamond, GameState, Inches, Inches2, InchesPerSecond,
-    InchesPerSecondSq, MotionPhaseConfig, MotionTransitionConfig, OnTableBallState,
-    OnTableMotionConfig, OverlayLayer, Pocket, Position, RadiansPerSecondSq, Rail,
-    RailAngleReference, RailModel, RailTangentDirection, RollingResistanceModel, Seconds,
-    SlidingFrictionModel, SpinDecayModel, TableSpec, Velocity2, TYPICAL_BALL_RADIUS,
+    Angle, AngularVelocity3, Ball, BallPathStop, BallSetPhysicsSpec, BallSpec, BallState,
+    BallStyle, BallType, DiagramBackground, DiagramCorner, DiagramOrientation, DiagramRegion,
+    DiagramRenderOptions, Diamond, GameState, Inches, Inches2, InchesPerSecond, InchesPerSecondSq,
+    MotionPhaseConfig, MotionTransitionConfig, OnTableBallState, OnTableMotionConfig, OverlayLayer,
+    Pocket, Position, RadiansPerSecondSq, Rail, RailAngleReference, RailModel,
+    RailTangentDirection, RollingResistanceModel, Seconds, SlidingFrictionModel, SpinDecayModel,
+    TableSpec, Velocity2, Watermark, TYPICAL_BALL_RADIUS,
 };
-use image::{load_from_memory, RgbaImage};
+use image::{load_from_memory, Rgba, RgbaImage};
+use std::sync::Arc;
 
 fn render(state: &GameState) -> RgbaImage {
     load_from_memory(&state.draw_2d_diagram())
@@ -238,6 +245,31 @@ fn drawing_with_scale_factor_two_doubles_the_output_dimensions() {
     assert_eq!(scaled.height(), baseline.height() * 2);
 }
 
+#[test]
+fn drawing_with_a_maximum_size_shrinks_the_diagram_to_fit_keeping_its_aspect_ratio() {
+    let state = cue_ball_at("2", "4");
+    let thumbnail = DiagramRenderOptions {
+        max_width_px: Some(200),
+        max_height_px: Some(300),
+        ..DiagramRenderOptions::default()
+    };
+
+    let image = render_with_options(&state, &thumbnail);
+    assert_eq!(image.dimensions(), (169, 300));
+    let center = image.get_pixel(84, 150);
+    assert!(center[0] > 200 && center[1] > 200 && center[2] > 200);
+
+    let svg = render_svg_with_options(&state, &thumbnail);
+    assert!(svg.contains("viewBox=\"0 0 1089 1938\" width=\"169\" height=\"300\""));
+
+    let print = DiagramRenderOptions {
+        scale_factor: 4,
+        max_width_px: Some(3000),
+        ..DiagramRenderOptions::default()
+    };
+    assert_eq!(print.output_size_px((1089, 1938)), (3000, 5339));
+}
+
 #[test]
 fn drawing_with_a_transparent_background_leaves_an_empty_table_fully_transparent() {
     let rendered = render_with_options(
@@ -251,6 +283,82 @@ fn drawing_with_a_transparent_background_leaves_an_empty_table_fully_transparent
     assert!(rendered.pixels().all(|pixel| pixel[3] == 0));
 }
 
+#[test]
+fn drawing_on_a_colored_canvas_pads_the_diagram_with_a_margin() {
+    let state = cue_ball_at("2", "4");
+    let slide = DiagramRenderOptions {
+        canvas_color: image::Rgba([24, 24, 32, 255]),
+        margin_px: 20,
+        ..DiagramRenderOptions::default()
+    };
+
+    let plain = render(&state);
+    let padded = render_with_options(&state, &slide);
+    assert_eq!(padded.dimensions(), (1089 + 40, 1938 + 40));
+    assert_eq!(*padded.get_pixel(5, 5), image::Rgba([24, 24, 32, 255]));
+    assert_eq!(
+        *padded.get_pixel(1128, 1977),
+        image::Rgba([24, 24, 32, 255])
+    );
+    assert_eq!(
+        padded.get_pixel(539 + 20, 969 + 20),
+        plain.get_pixel(539, 969)
+    );
+    assert_eq!(
+        padded.get_pixel(300 + 20, 500 + 20),
+        plain.get_pixel(300, 500)
+    );
+
+    let svg = render_svg_with_options(&state, &slide);
+    assert!(svg.contains("viewBox=\"-20 -20 1129 1978\" width=\"1129\" height=\"1978\""));
+    assert!(svg.contains("class=\"diagram-canvas\""));
+    assert!(svg.contains("fill=\"#181820\""));
+}
+
+#[test]
+fn a_watermark_is_laid_over_its_corner_at_its_opacity() {
+    let state = cue_ball_at("2", "4");
+    let canvas = image::Rgba([24, 24, 32, 255]);
+    let logo = RgbaImage::from_pixel(10, 6, image::Rgba([255, 0, 0, 255]));
+    let branded = DiagramRenderOptions {
+        canvas_color: canvas,
+        margin_px: 20,
+        ..DiagramRenderOptions::default()
+    }
+    .with_watermark(
+        Watermark::image(logo)
+            .in_corner(DiagramCorner::TopLeft)
+            .with_opacity(0.5),
+    );
+
+    let image = render_with_options(&state, &branded);
+    assert_eq!(*image.get_pixel(15, 16), canvas);
+    assert_eq!(*image.get_pixel(16, 16), image::Rgba([139, 11, 15, 254]));
+    assert_eq!(*image.get_pixel(25, 21), image::Rgba([139, 11, 15, 254]));
+    assert_eq!(*image.get_pixel(26, 21), canvas);
+
+    let svg = render_svg_with_options(&state, &branded);
+    assert!(svg.contains(
+        "<image class=\"watermark\" x=\"-4.000\" y=\"-4.000\" width=\"10.000\" height=\"6.000\" opacity=\"0.500\" href=\"data:image/png;base64,iVBORw0KGgo"
+    ));
+
+    let signed = DiagramRenderOptions::default().with_watermark(Watermark::text(
+        "CUE CLUB",
+        image::Rgba([255, 255, 255, 255]),
+    ));
+    let (min_x, min_y, max_x, max_y) =
+        diff_bbox(&render(&state), &render_with_options(&state, &signed))
+            .expect("the text should change pixels");
+    // Eight 5×7 glyphs at 2 px a dot, 16 px in from the bottom right.
+    assert!((1089 - 20..=1089 - 17).contains(&max_x), "{max_x}");
+    assert_eq!(max_y, 1938 - 17);
+    assert!(
+        min_x >= 1089 - 16 - 94 && min_y >= 1938 - 16 - 14,
+        "{min_x}, {min_y}"
+    );
+    assert!(render_svg_with_options(&state, &signed).contains(">CUE CLUB</text>"));
+}
+
 #[test]
 fn drawing_with_a_transparent_background_still_renders_visible_balls() {
     let rendered = render_with_options(
@@ -299,6 +407,49 @@ fn svg_backend_emits_layered_scalable_markup_for_a_ball_layout() {
     assert!(svg.contains("class=\"ball ball-cue\""));
 }
 
+#[test]
+fn draw_svg_diagram_emits_balls_and_annotations_as_vector_shapes() {
+    let mut state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            Ball {
+                ty: BallType::Cue,
+                position: Position::new(1u8, 2u8),
+                spec: BallSpec::default(),
+            },
+            Ball {
+                ty: BallType::Nine,
+                position: Position::new(3u8, 6u8),
+                spec: BallSpec::default(),
+            },
+        ],
+    );
+    let white = image::Rgba([255, 255, 255, 255]);
+    state.add_arrow(&Position::new(1u8, 2u8), &Position::new(3u8, 6u8), white);
+    state.add_dotted_line(&Position::new(3u8, 6u8), &Position::new(4u8, 8u8), white);
+    state.add_ghost_ball(&Position::new("2.9", "5.8"), white, white);
+    state.highlight_pocket(Pocket::TopRight, white);
+
+    let svg = state.draw_svg_diagram();
+    assert_eq!(
+        svg,
+        state.draw_2d_svg_with_options(&DiagramRenderOptions::default())
+    );
+    assert!(!svg.contains("<image"), "no embedded raster art");
+    assert!(svg.contains("class=\"table-pocket\""));
+    assert!(svg.contains("class=\"ball ball-nine\""));
+    assert!(svg.contains(">9</text>"));
+    for class in [
+        "smooth-polyline",
+        "arrowhead",
+        "dashed-line",
+        "ghost-ball",
+        "pocket-highlight",
+    ] {
+        assert!(svg.contains(class), "missing {class}");
+    }
+}
+
 #[test]
 fn svg_table_uses_cut_pockets_eighteen_sights_and_diamond_style_materials() {
     let svg = render_svg_with_options(&cue_ball_at("2", "4"), &DiagramRenderOptions::default());
@@ -491,6 +642,27 @@ fn adding_a_ghost_ball_renders_a_ball_sized_overlay_centered_on_the_requested_po
     assert_eq!((min_y + max_y) / 2, 969);
 }
 
+#[test]
+fn a_ghost_ball_takes_its_size_from_the_ball_spec() {
+    let empty = render(&GameState::default());
+    let mut ghosted = GameState::new(TableSpec::default());
+    ghosted.add_ghost_ball_sized(
+        &Position::new(2u8, 4u8),
+        &BallSpec {
+            radius: Inches::from_f64(1.5),
+        },
+        GhostBallStyle::default(),
+    );
+
+    let (min_x, min_y, max_x, max_y) =
+        diff_bbox(&empty, &render(&ghosted)).expect("ghost ball diff bbox");
+
+    assert_eq!(max_x - min_x + 1, 51);
+    assert_eq!(max_y - min_y + 1, 51);
+    assert_eq!((min_x + max_x) / 2, 539);
+    assert_eq!((min_y + max_y) / 2, 969);
+}
+
 #[test]
 fn overlays_can_be_drawn_above_balls_when_requested() {
     let baseline = cue_ball_at("2", "4");
@@ -680,6 +852,7 @@ fn rendered_ball_paths_can_use_one_shared_renderer_for_fixed_and_speed_scaled_wi
     let transparent = DiagramRenderOptions {
         scale_factor: 1,
         background: DiagramBackground::Transparent,
+        ..DiagramRenderOptions::default()
     };
     let empty = render_with_options(&GameState::new(table_spec.clone()), &transparent);
 
@@ -730,3 +903,854 @@ fn rendered_ball_paths_can_use_one_shared_renderer_for_fixed_and_speed_scaled_wi
         "expected the faster early cue-ball path to render thicker than the slower late path; got fast row width {fast_width} and slow row width {slow_width}"
     );
 }
+
+#[test]
+fn overlays_persist_on_the_state_until_cleared() {
+    let mut state = cue_ball_at("2", "4");
+    let mut from = Position::new(1u8, 4u8);
+    from.shift_horizontally_inches(Inches::from_f64(6.25));
+    state.add_dotted_line(
+        &from,
+        &Position::new(3u8, 4u8),
+        image::Rgba([255, 0, 0, 255]),
+    );
+    let copy = state.clone();
+
+    match copy.overlays() {
+        [DiagramElement::DashedLine { start, end, style }] => {
+            assert_eq!(*start, Position::new("1.5", "4"));
+            assert_eq!(*end, Position::new(3u8, 4u8));
+            assert_eq!(style.layer, OverlayLayer::BelowBalls);
+        }
+        other => panic!("expected one dashed line, got {other:?}"),
+    }
+
+    state.clear_overlays();
+    assert!(state.overlays().is_empty());
+    assert_eq!(state.balls().len(), 1);
+    assert!(diff_bbox(&render(&state), &render(&cue_ball_at("2", "4"))).is_none());
+}
+
+#[test]
+fn annotations_in_a_hidden_named_layer_are_left_out_of_the_drawing() {
+    let mut state = cue_ball_at("2", "4");
+    state.add_solid_line(
+        &Position::new(1u8, 2u8),
+        &Position::new(3u8, 2u8),
+        image::Rgba([255, 255, 255, 255]),
+    );
+    let annotated_only_by_hand = state.clone();
+    state.in_named_layer("aiming", |state| {
+        state.add_arrow(
+            &Position::new(2u8, 4u8),
+            &Position::new(2u8, 7u8),
+            image::Rgba([255, 0, 0, 255]),
+        );
+    });
+    state.set_named_layer(Some("labels"));
+    state.add_text_label_styled(
+        &Position::new(2u8, 6u8),
+        "A",
+        LabelOverlayStyle::enabled(image::Rgba([0, 0, 0, 255])),
+    );
+    state.set_named_layer(None);
+
+    assert_eq!(state.named_layers(), ["aiming", "labels"]);
+    assert_eq!(state.overlays_in_layer("aiming").count(), 1);
+    assert_eq!(state.overlays().len(), 3);
+    let clean = DiagramRenderOptions::default()
+        .with_hidden_layer("aiming")
+        .with_hidden_layer("labels");
+    assert!(diff_bbox(
+        &render_with_options(&state, &clean),
+        &render(&annotated_only_by_hand)
+    )
+    .is_none());
+    let without_labels = DiagramRenderOptions::default().with_hidden_layer("labels");
+    assert!(diff_bbox(
+        &render_with_options(&state, &without_labels),
+        &render(&annotated_only_by_hand),
+    )
+    .is_some());
+}
+
+#[test]
+fn a_solid_line_has_no_gaps_where_a_dotted_line_does() {
+    let transparent = DiagramRenderOptions {
+        background: DiagramBackground::Transparent,
+        ..DiagramRenderOptions::default()
+    };
+    let (from, to) = (Position::new(1u8, 4u8), Position::new(3u8, 4u8));
+    let color = image::Rgba([255, 255, 255, 255]);
+
+    let mut solid = GameState::new(TableSpec::default());
+    solid.add_solid_line(&from, &to, color);
+    let mut dotted = GameState::new(TableSpec::default());
+    dotted.add_dotted_line(&from, &to, color);
+
+    let solid_image = render_with_options(&solid, &transparent);
+    let dotted_image = render_with_options(&dotted, &transparent);
+    let covered = |image: &RgbaImage| (330..750).all(|x| image.get_pixel(x, 969)[3] > 0);
+    assert!(covered(&solid_image));
+    assert!(!covered(&dotted_image));
+    assert_eq!(visible_pixel_count_in_row(&solid_image, 1100), 0);
+}
+
+#[test]
+fn arrowheads_mark_the_direction_of_a_line() {
+    let transparent = DiagramRenderOptions {
+        background: DiagramBackground::Transparent,
+        ..DiagramRenderOptions::default()
+    };
+    let (from, to) = (Position::new(1u8, 4u8), Position::new(3u8, 4u8));
+    let color = image::Rgba([255, 255, 255, 255]);
+
+    let mut plain = GameState::new(TableSpec::default());
+    plain.add_solid_line(&from, &to, color);
+    let mut arrow = GameState::new(TableSpec::default());
+    arrow.add_arrow(&from, &to, color);
+
+    let (min_x, min_y, max_x, max_y) = diff_bbox(
+        &render_with_options(&plain, &transparent),
+        &render_with_options(&arrow, &transparent),
+    )
+    .expect("the arrowhead should add pixels");
+    assert!(min_x > 700 && max_x <= 754, "{min_x}..={max_x}");
+    assert!(min_y < 969 && max_y > 969);
+
+    let mut both = GameState::new(TableSpec::default());
+    both.add_dotted_line_styled(
+        &from,
+        &to,
+        DashedLineStyle::new(color).with_arrow(ArrowStyle::both_ends().with_size(24.0, 16.0)),
+    );
+    let svg = render_svg_with_options(&both, &DiagramRenderOptions::default());
+    assert_eq!(svg.matches("class=\"overlay arrowhead\"").count(), 2);
+}
+
+#[test]
+fn sequence_markers_number_each_shot_of_a_runout() {
+    let mut state = cue_ball_at("2", "2");
+    let (cue, first, second) = (
+        Position::new(2u8, 2u8),
+        Position::new(1u8, 5u8),
+        Position::new(3u8, 7u8),
+    );
+    state.add_solid_line(&cue, &first, image::Rgba([255, 255, 255, 255]));
+    state.add_sequence_marker_on_line(&cue, &first, 1, SequenceMarkerStyle::default());
+    state.add_solid_line(&first, &second, image::Rgba([255, 255, 255, 255]));
+    state.add_sequence_marker_on_line(&first, &second, 2, SequenceMarkerStyle::default());
+    state.add_sequence_marker(&Position::new("0.5", "7.5"), 3);
+
+    let scene = state.to_diagram_scene(&DiagramRenderOptions::default());
+    let markers: Vec<(Position, u32)> = scene
+        .elements_for_layer(DiagramLayerId::OverlaysAboveBalls)
+        .filter_map(|element| match element {
+            DiagramElement::SequenceMarker { center, number, .. } => {
+                Some((center.clone(), *number))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        markers,
+        vec![
+            (Position::new("1.5", "3.5"), 1),
+            (Position::new(2u8, 6u8), 2),
+            (Position::new("0.5", "7.5"), 3),
+        ]
+    );
+
+    let svg = render_svg_with_options(&state, &DiagramRenderOptions::default());
+    assert!(svg.contains("data-step=\"3\""));
+    assert!(diff_bbox(&render(&cue_ball_at("2", "2")), &render(&state)).is_some());
+}
+
+#[test]
+fn an_angle_arc_reads_out_the_angle_between_two_lines() {
+    let mut state = GameState::new(TableSpec::default());
+    let vertex = Position::new(2u8, 4u8);
+    state.add_angle_arc(
+        &vertex,
+        &Position::new(2u8, 7u8),
+        &Position::new(4u8, 6u8),
+        image::Rgba([255, 255, 0, 255]),
+    );
+    state.add_angle_arc(
+        &vertex,
+        &Position::new(3u8, 4u8),
+        &Position::new(2u8, 2u8),
+        image::Rgba([255, 255, 0, 255]),
+    );
+
+    let labels: Vec<&str> = state
+        .overlays()
+        .iter()
+        .filter_map(|element| match element {
+            DiagramElement::AngleArc { label, .. } => Some(label.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(labels, ["45°", "90°"]);
+
+    let svg = render_svg_with_options(&state, &DiagramRenderOptions::default());
+    assert_eq!(svg.matches("class=\"overlay angle-arc\"").count(), 2);
+    assert!(svg.contains(">45°</text>"));
+    assert!(diff_bbox(&render(&GameState::default()), &render(&state)).is_some());
+}
+
+#[test]
+fn a_measurement_is_labelled_with_its_length_in_inches_or_diamonds() {
+    let mut state = GameState::new(TableSpec::default());
+    let red = image::Rgba([255, 0, 0, 255]);
+    state.add_measurement(&Position::new(1u8, 4u8), &Position::new(3u8, 4u8), red);
+    state.add_measurement_styled(
+        &Position::new(2u8, 1u8),
+        &Position::new("2", "2.5"),
+        MeasurementStyle::new(red).in_diamonds(),
+    );
+
+    let labels: Vec<(&str, &Position)> = state
+        .overlays()
+        .iter()
+        .filter_map(|element| match element {
+            DiagramElement::TextLabel { text, anchor, .. } => Some((text.as_str(), anchor)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            ("25.00 in", &Position::new(2u8, 4u8)),
+            ("1.50 diamonds", &Position::new("2", "1.75"))
+        ]
+    );
+    assert!(state.overlays().iter().any(|element| matches!(
+        element,
+        DiagramElement::SmoothPolyline { style, .. } if style.arrow == Some(ArrowStyle::both_ends().with_size(12.0, 8.0))
+    )));
+    assert!(diff_bbox(&render(&GameState::default()), &render(&state)).is_some());
+}
+
+#[test]
+fn a_highlighted_ball_gets_a_halo_beneath_it_or_an_outline_over_it() {
+    let yellow = image::Rgba([255, 220, 0, 255]);
+    let mut halo = cue_ball_at("2", "4");
+    halo.highlight_ball(BallType::Cue, yellow);
+    halo.highlight_ball(BallType::Eight, yellow);
+    assert_eq!(halo.overlays().len(), 1);
+
+    let (min_x, min_y, max_x, max_y) = diff_bbox(&render(&cue_ball_at("2", "4")), &render(&halo))
+        .expect("the halo should change pixels");
+    // 10 px past the 19.3 px ball on every side of (539, 969).
+    assert!((57..=60).contains(&(max_x - min_x)), "{min_x}..{max_x}");
+    assert!((57..=60).contains(&(max_y - min_y)), "{min_y}..{max_y}");
+    assert_eq!(
+        render(&halo).get_pixel(539, 969),
+        render(&cue_ball_at("2", "4")).get_pixel(539, 969)
+    );
+
+    let mut outline = cue_ball_at("2", "4");
+    outline.highlight_ball_styled(BallType::Cue, BallHighlightStyle::outline(yellow));
+    let svg = render_svg_with_options(&outline, &DiagramRenderOptions::default());
+    let ball = svg.find("data-ball=\"cue\" transform").unwrap();
+    let ring = svg
+        .find("class=\"overlay ball-outline\" data-ball=\"cue\"")
+        .unwrap();
+    assert!(ring > ball, "the outline should be drawn over the ball");
+}
+
+#[test]
+fn balls_left_out_of_the_focus_are_dimmed() {
+    let mut state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            Ball {
+                ty: BallType::Cue,
+                position: Position::new(2u8, 2u8),
+                spec: BallSpec::default(),
+            },
+            Ball {
+                ty: BallType::Eight,
+                position: Position::new(2u8, 6u8),
+                spec: BallSpec::default(),
+            },
+        ],
+    );
+    let solid = render(&state);
+    state.dim_balls_except(&[BallType::Cue]);
+
+    let opacities: Vec<f32> = state
+        .to_diagram_scene(&DiagramRenderOptions::default())
+        .balls
+        .iter()
+        .map(|ball| ball.opacity)
+        .collect();
+    assert_eq!(opacities, [1.0, DIMMED_BALL_OPACITY]);
+    let (_, min_y, _, max_y) = diff_bbox(&solid, &render(&state)).expect("the eight should fade");
+    assert!(
+        max_y < 969,
+        "only the eight, up the table, changes: {min_y}..{max_y}"
+    );
+    let svg = render_svg_with_options(&state, &DiagramRenderOptions::default());
+    assert!(svg.contains("class=\"ball dimmed-ball ball-eight\""));
+
+    state.clear_overlays();
+    assert_eq!(render(&state), solid);
+}
+
+#[test]
+fn highlighting_a_pocket_marks_its_mouth() {
+    let mut state = GameState::new(TableSpec::default());
+    state.highlight_pocket(Pocket::TopRight, image::Rgba([255, 0, 0, 255]));
+
+    let (min_x, min_y, max_x, max_y) = diff_bbox(&render(&GameState::default()), &render(&state))
+        .expect("the highlight should change pixels");
+    let (center_x, center_y) = ((min_x + max_x) / 2, (min_y + max_y) / 2);
+    assert!((900..968).contains(&center_x), "{center_x}");
+    assert!((110..180).contains(&center_y), "{center_y}");
+
+    let svg = render_svg_with_options(&state, &DiagramRenderOptions::default());
+    assert!(svg.contains("class=\"overlay pocket-highlight\" data-pocket=\"TopRight\""));
+}
+
+#[test]
+fn shaded_zones_sit_over_the_cloth_and_under_the_balls() {
+    let yellow = image::Rgba([255, 255, 0, 96]);
+    let mut kitchen = cue_ball_at("2", "7");
+    kitchen.shade_kitchen(ShadedZoneStyle::new(yellow));
+
+    let (min_x, min_y, max_x, max_y) =
+        diff_bbox(&render(&cue_ball_at("2", "7")), &render(&kitchen))
+            .expect("the kitchen should be shaded");
+    assert_eq!((min_x, min_y, max_x, max_y), (110, 110, 967, 539));
+
+    let svg = render_svg_with_options(&kitchen, &DiagramRenderOptions::default());
+    let below = svg.find("data-layer=\"overlays-below-balls\"").unwrap();
+    let zone = svg.find("class=\"overlay shaded-zone\"").unwrap();
+    let balls = svg.find("data-layer=\"balls\"").unwrap();
+    assert!(below < zone && zone < balls);
+}
+
+#[test]
+fn a_position_zone_can_be_shaded_from_its_outline() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            Ball {
+                ty: BallType::One,
+                position: Position::new(1u8, 6u8),
+                spec: BallSpec::default(),
+            },
+            Ball {
+                ty: BallType::Two,
+                position: Position::new(3u8, 2u8),
+                spec: BallSpec::default(),
+            },
+        ],
+    );
+    let zones = state.position_zones(
+        state.select_ball(BallType::One).unwrap(),
+        state.select_ball(BallType::Two).unwrap(),
+        0.5,
+    );
+    let zone = zones.first().expect("the two should have a position zone");
+
+    let mut shaded = state.clone();
+    shaded.add_position_zone_overlay(
+        zone,
+        ShadedZoneStyle::new(image::Rgba([0, 255, 0, 96]))
+            .with_outline(image::Rgba([0, 96, 0, 255])),
+    );
+
+    match shaded.overlays() {
+        [DiagramElement::ShadedZone { outline, .. }] => assert_eq!(outline, &zone.outline),
+        other => panic!("expected one shaded zone, got {other:?}"),
+    }
+    assert!(diff_bbox(&render(&state), &render(&shaded)).is_some());
+}
+
+#[test]
+fn curved_paths_are_flattened_into_polylines_through_their_ends() {
+    let table = TableSpec::default();
+    let close = |a: &Position, b: &Position| {
+        let a = (
+            table.diamond_to_inches(a.x.clone()).as_f64(),
+            table.diamond_to_inches(a.y.clone()).as_f64(),
+        );
+        let b = (
+            table.diamond_to_inches(b.x.clone()).as_f64(),
+            table.diamond_to_inches(b.y.clone()).as_f64(),
+        );
+        (a.0 - b.0).hypot(a.1 - b.1) < 1e-6
+    };
+    let color = image::Rgba([255, 255, 255, 255]);
+    let mut state = GameState::new(table.clone());
+
+    // A quarter circle from due north of the center round to due east.
+    state.add_arc_path(
+        &Position::new(2u8, 4u8),
+        Inches::from_f64(12.5),
+        Angle::from_north(0.0, 1.0),
+        90.0,
+        SmoothPolylineStyle::new(color),
+    );
+    state.add_bezier_path(
+        &Position::new(1u8, 1u8),
+        &Position::new(1u8, 3u8),
+        &Position::new(3u8, 3u8),
+        &Position::new(3u8, 1u8),
+        SmoothPolylineStyle::new(color).with_arrow(ArrowStyle::default()),
+    );
+
+    let [DiagramElement::SmoothPolyline { points: arc, .. }, DiagramElement::SmoothPolyline { points: bezier, .. }] =
+        state.overlays()
+    else {
+        panic!("expected two polylines, got {:?}", state.overlays());
+    };
+    assert!(close(&arc[0], &Position::new(2u8, 5u8)));
+    let diagonal = 12.5 * 0.5f64.sqrt();
+    assert!(close(
+        &arc[arc.len() / 2],
+        &Position::new(
+            table.inches_to_diamond(Inches::from_f64(25.0 + diagonal)),
+            table.inches_to_diamond(Inches::from_f64(50.0 + diagonal)),
+        )
+    ));
+    assert!(close(arc.last().unwrap(), &Position::new(3u8, 4u8)));
+    assert!(close(&bezier[0], &Position::new(1u8, 1u8)));
+    assert!(close(&bezier[bezier.len() / 2], &Position::new(2u8, "2.5")));
+    assert!(close(bezier.last().unwrap(), &Position::new(3u8, 1u8)));
+
+    assert!(diff_bbox(&render(&GameState::default()), &render(&state)).is_some());
+}
+
+#[test]
+fn a_legend_keys_each_line_style_in_a_box_anchored_at_its_corner() {
+    let mut state = GameState::new(TableSpec::default());
+    state.add_legend(
+        &Position::new(0u8, 8u8),
+        vec![
+            LegendEntry::new(
+                LegendSwatch::Solid(image::Rgba([255, 0, 0, 255])),
+                "Object ball",
+            ),
+            LegendEntry::new(
+                LegendSwatch::Dashed(image::Rgba([0, 0, 255, 255])),
+                "Cue ball",
+            ),
+        ],
+    );
+
+    let (min_x, min_y, _, _) = diff_bbox(
+        &render(&GameState::new(TableSpec::default())),
+        &render(&state),
+    )
+    .expect("the legend should be drawn");
+    assert_eq!((min_x, min_y), (110, 110));
+
+    let svg = render_svg_with_options(&state, &DiagramRenderOptions::default());
+    assert!(svg.contains("class=\"overlay legend\""));
+    assert!(svg.contains(">Object ball</text>"));
+    assert!(svg.contains("stroke-dasharray=\"4 4\""));
+}
+
+#[test]
+fn the_diamond_grid_option_draws_numbered_lines_through_every_diamond() {
+    let state = GameState::new(TableSpec::default());
+    let gridded = DiagramRenderOptions {
+        diamond_grid: Some(DiamondGridStyle::default()),
+        ..DiagramRenderOptions::default()
+    };
+
+    let plain = render(&state);
+    let grid = render_with_options(&state, &gridded);
+    let (min_x, _, max_x, max_y) = diff_bbox(&plain, &grid).expect("the grid should be drawn");
+    assert!(min_x < 110, "row numbers sit on the left rail");
+    assert!(max_x >= 968, "the grid spans the playfield");
+    assert!(max_y > 1828, "column numbers sit on the bottom rail");
+    assert_ne!(plain.get_pixel(539, 500), grid.get_pixel(539, 500));
+    assert_eq!(plain.get_pixel(300, 500), grid.get_pixel(300, 500));
+
+    let svg = render_svg_with_options(&state, &gridded);
+    assert!(svg.contains("class=\"overlay diamond-grid\""));
+    assert_eq!(svg.matches("font-size=\"14\">8</text>").count(), 1);
+    assert!(
+        !render_svg_with_options(&state, &DiagramRenderOptions::default()).contains("diamond-grid")
+    );
+}
+
+#[test]
+fn cropping_to_a_region_enlarges_it_to_the_full_diagram_width() {
+    let corner = DiagramRenderOptions {
+        crop: Some(DiagramRegion::new(
+            Position::new("-0.25", "-0.25"),
+            Position::new(1u8, 1u8),
+        )),
+        ..DiagramRenderOptions::default()
+    };
+    let empty = render_with_options(&GameState::new(TableSpec::default()), &corner);
+    let zoomed = render_with_options(&cue_ball_at("0.5", "0.5"), &corner);
+
+    let (left, top, width, height) =
+        DiagramViewport::default().region_px(corner.crop.as_ref().unwrap());
+    assert_eq!((left, top, width, height), (56, 1613, 269, 269));
+    assert_eq!(zoomed.dimensions(), (1089, 1089));
+
+    let zoom = 1089.0 / 269.0;
+    let (min_x, min_y, max_x, max_y) =
+        diff_bbox(&empty, &zoomed).expect("the ball should be drawn");
+    let center = ((min_x + max_x) as f64 / 2.0, (min_y + max_y) as f64 / 2.0);
+    assert!((center.0 - (217.0 - 56.0) * zoom).abs() < 4.0, "{center:?}");
+    assert!(
+        (center.1 - (1721.0 - 1613.0) * zoom).abs() < 4.0,
+        "{center:?}"
+    );
+    // Resampling softens the sprite's edge by about a source pixel on each side.
+    let diameter = (max_x - min_x + 1) as f64;
+    assert!(
+        diameter >= 39.0 * zoom && diameter <= 42.0 * zoom,
+        "{diameter}"
+    );
+
+    let svg = render_svg_with_options(&cue_ball_at("0.5", "0.5"), &corner);
+    assert!(svg.contains("viewBox=\"56 1613 269 269\" width=\"1089\" height=\"1089\""));
+}
+
+#[test]
+fn a_table_theme_recolors_the_cloth_and_rails() {
+    let state = cue_ball_at("2", "4");
+    let blue = DiagramRenderOptions {
+        table_theme: TableTheme::blue(),
+        ..DiagramRenderOptions::default()
+    };
+
+    let classic = render(&state);
+    let themed = render_with_options(&state, &blue);
+    assert_eq!(*classic.get_pixel(300, 500), TableTheme::classic().cloth);
+    assert_eq!(*themed.get_pixel(300, 500), TableTheme::blue().cloth);
+    assert_eq!(*themed.get_pixel(40, 1000), TableTheme::blue().rail);
+    assert_eq!(classic.get_pixel(539, 969), themed.get_pixel(539, 969));
+
+    let svg = render_svg_with_options(&state, &blue);
+    assert!(svg.contains(".table-cloth{fill:#058dbc}"));
+    assert!(
+        !render_svg_with_options(&state, &DiagramRenderOptions::default())
+            .contains(".table-cloth{fill:#")
+    );
+}
+
+#[test]
+fn a_landscape_diagram_turns_the_table_a_quarter_turn_clockwise() {
+    let landscape = DiagramRenderOptions {
+        orientation: DiagramOrientation::Landscape,
+        ..DiagramRenderOptions::default()
+    };
+    let empty = render_with_options(&GameState::new(TableSpec::default()), &landscape);
+    let with_ball = render_with_options(&cue_ball_at("1", "2"), &landscape);
+    assert_eq!(with_ball.dimensions(), (1938, 1089));
+
+    let portrait = diff_bbox(
+        &render(&GameState::default()),
+        &render(&cue_ball_at("1", "2")),
+    )
+    .expect("portrait ball");
+    let (min_x, min_y, max_x, max_y) = diff_bbox(&empty, &with_ball).expect("landscape ball");
+    assert_eq!((max_x - min_x + 1, max_y - min_y + 1), (39, 39));
+    assert_eq!(
+        ((min_x + max_x) / 2, (min_y + max_y) / 2),
+        (
+            1937 - (portrait.1 + portrait.3) / 2,
+            (portrait.0 + portrait.2) / 2
+        )
+    );
+
+    let svg = render_svg_with_options(&cue_ball_at("1", "2"), &landscape);
+    assert!(svg.contains("viewBox=\"0 0 1938 1089\" width=\"1938\" height=\"1089\""));
+    assert!(svg.contains("transform=\"translate(1938.000 0) rotate(90)\""));
+    assert!(svg.contains("rotate(-90)\">"));
+}
+
+#[test]
+fn jpeg_and_webp_exports_decode_to_the_same_diagram_as_png() {
+    let state = cue_ball_at("2", "4");
+    let options = DiagramRenderOptions::default();
+    let png = render(&state);
+
+    let webp = load_from_memory(
+        &state.render_2d_diagram_with_options(DiagramOutputFormat::WebP, &options),
+    )
+    .expect("valid WebP")
+    .into_rgba8();
+    assert_eq!(webp, png);
+
+    let jpeg = image::load_from_memory_with_format(
+        &state.render_2d_diagram_with_options(DiagramOutputFormat::Jpeg, &options),
+        image::ImageFormat::Jpeg,
+    )
+    .expect("valid JPEG")
+    .into_rgba8();
+    assert_eq!(jpeg.dimensions(), png.dimensions());
+    for (x, y) in [(539, 969), (300, 500), (40, 1000)] {
+        let (a, b) = (jpeg.get_pixel(x, y), png.get_pixel(x, y));
+        assert!((0..3).all(|i| a[i].abs_diff(b[i]) < 12), "{a:?} vs {b:?}");
+    }
+
+    assert_eq!(
+        DiagramOutputFormat::from_extension("JPEG"),
+        Some(DiagramOutputFormat::Jpeg)
+    );
+    assert_eq!(DiagramOutputFormat::WebP.extension(), "webp");
+}
+
+#[test]
+fn render_to_image_returns_the_composed_diagram_without_encoding_it() {
+    let state = cue_ball_at("2", "4");
+    assert_eq!(state.render_to_image(), render(&state));
+
+    let scaled = DiagramRenderOptions {
+        scale_factor: 2,
+        ..DiagramRenderOptions::default()
+    };
+    assert_eq!(
+        state.render_to_image_with_options(&scaled),
+        render_with_options(&state, &scaled)
+    );
+}
+
+#[test]
+fn the_terminal_backend_sketches_the_table_as_text() {
+    let mut state = cue_ball_at("2", "1");
+    state.add_solid_line(
+        &Position::new("2", "1"),
+        &Position::new("2", "6"),
+        Rgba([0, 0, 0, 255]),
+    );
+
+    let text = state.render_with::<TerminalBackend>(&DiagramRenderOptions::default());
+
+    let rows: Vec<&str> = text.lines().collect();
+    assert_eq!(rows.len(), 27);
+    assert!(rows[0].starts_with('O') && rows[0].ends_with('O'));
+    assert!(rows.iter().all(|row| row.chars().count() == 27));
+    // Eight diamonds of three rows each between the rails; the cue ball sits one diamond up.
+    assert_eq!(rows[1 + 3 * 7].chars().nth(13), Some('○'));
+    assert_eq!(rows[1 + 3 * 4].chars().nth(13), Some('·'));
+
+    assert_eq!(
+        state.render_2d_diagram_with_options(
+            DiagramOutputFormat::Text,
+            &DiagramRenderOptions::default()
+        ),
+        text.into_bytes()
+    );
+}
+
+#[test]
+fn custom_assets_from_disk_replace_the_table_and_ball_art_at_their_calibrated_scale() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("custom_assets");
+    std::fs::create_dir_all(&dir).unwrap();
+    let felt = Rgba([20, 90, 40, 255]);
+    RgbaImage::from_pixel(600, 1000, felt)
+        .save(dir.join("table.png"))
+        .unwrap();
+    RgbaImage::from_pixel(64, 64, Rgba([220, 0, 0, 255]))
+        .save(dir.join("ball_cue.png"))
+        .unwrap();
+    std::fs::write(
+        dir.join("calibration.toml"),
+        "# nose of each cushion\nleftmost = 100\nrightmost = 500\ntopmost = 100\nbottommost = 900\n",
+    )
+    .unwrap();
+
+    let mut state = cue_ball_at("2", "4");
+    state.add_ball(Ball {
+        ty: BallType::One,
+        position: Position::new("1", "2"),
+        spec: BallSpec::default(),
+    });
+    state.add_solid_line(
+        &Position::new("0", "6"),
+        &Position::new("4", "6"),
+        Rgba([0, 0, 0, 255]),
+    );
+    let options = DiagramRenderOptions {
+        assets: Some(Arc::new(AssetPack::load(&dir).unwrap())),
+        ..DiagramRenderOptions::default()
+    };
+    let image = render_with_options(&state, &options);
+
+    assert_eq!(image.dimensions(), (600, 1000));
+    assert_eq!(*image.get_pixel(20, 20), felt);
+    // The 50 in playfield spans 400 px, so the 2.25 in cue ball is 18 px across.
+    let red = |x: u32| image.get_pixel(x, 500)[0] > 150;
+    let diameter = (280..320).filter(|&x| red(x)).count();
+    assert!((17..=19).contains(&diameter), "{diameter}");
+    // Balls without a custom sprite keep the built-in art.
+    assert_ne!(*image.get_pixel(200, 700), felt);
+    // Overlays follow the calibrated playing surface.
+    assert!(image.get_pixel(300, 300)[1] < 60);
+    assert_eq!(*image.get_pixel(300, 320), felt);
+}
+
+#[test]
+fn a_procedural_table_keeps_the_proportions_of_smaller_tables() {
+    let mut seven_foot = TableSpec {
+        diamond_length: Inches::from_f64(9.75),
+        ..TableSpec::default()
+    };
+    let state = GameState::with_balls(
+        seven_foot.clone(),
+        [Ball {
+            ty: BallType::Cue,
+            position: Position::new("2", "2"),
+            spec: BallSpec::default(),
+        }],
+    );
+    let options = DiagramRenderOptions {
+        background: DiagramBackground::Procedural,
+        ..DiagramRenderOptions::default()
+    };
+    let image = render_with_options(&state, &options);
+
+    // The playfield keeps its 858 px width; the 6.4 in rails and the balls grow with the
+    // 39 in playfield.
+    assert_eq!(image.dimensions(), (858 + 2 * 141, 1718 + 2 * 141));
+    let theme = TableTheme::classic();
+    assert_eq!(*image.get_pixel(300, 700), theme.cloth);
+    assert_eq!(*image.get_pixel(50, 700), theme.rail);
+    assert_eq!(*image.get_pixel(40, 40), theme.corner_rail);
+    let ball_y = 141 + 1718 - 1718 / 4;
+    let diameter = (450..700)
+        .filter(|&x| image.get_pixel(x, ball_y)[2] > 200)
+        .count();
+    assert!((48..=52).contains(&diameter), "{diameter}");
+
+    // A carom table has no pockets, so its cushions run unbroken into the corners.
+    for pocket in seven_foot.pockets.iter_mut() {
+        pocket.width = Diamond::from("0");
+    }
+    let carom = GameState::new(seven_foot);
+    let svg = carom.render_2d_diagram_with_options(DiagramOutputFormat::Svg, &options);
+    let svg = String::from_utf8(svg).unwrap();
+    assert!(svg.contains("viewBox=\"0 0 1140 2000\""));
+    assert!(svg.contains("class=\"table-cushion\"") && !svg.contains("class=\"table-pocket\""));
+    let image = render_with_options(&carom, &options);
+    assert_ne!(*image.get_pixel(141 - 10, 141 - 10), theme.ink);
+}
+
+#[test]
+fn balls_without_artwork_are_drawn_with_their_color_stripe_and_number() {
+    let ball_at_center = |ty: BallType| {
+        GameState::with_balls(
+            TableSpec::default(),
+            [Ball {
+                ty,
+                position: Position::new("2", "4"),
+                spec: BallSpec::default(),
+            }],
+        )
+    };
+    let (x, y) = (539, 970);
+
+    // The twelve has no bundled image, so it is drawn: a purple band between white caps.
+    let twelve = render(&ball_at_center(BallType::Twelve));
+    let band = twelve.get_pixel(x + 10, y);
+    let cap = twelve.get_pixel(x, y - 16);
+    assert!(band[0] < 150 && band[2] > 130, "{band:?}");
+    assert!(cap[0] > 200 && cap[1] > 200 && cap[2] > 200, "{cap:?}");
+
+    // The procedural style draws the four the same purple all the way round.
+    let procedural = DiagramRenderOptions {
+        ball_style: BallStyle::Procedural,
+        ..DiagramRenderOptions::default()
+    };
+    let four = render_with_options(&ball_at_center(BallType::Four), &procedural);
+    assert_eq!(four.get_pixel(x + 10, y), band);
+    let cap = four.get_pixel(x, y - 16);
+    assert!(cap[0] < 150 && cap[2] > 130, "{cap:?}");
+
+    let svg = render_svg_with_options(&ball_at_center(BallType::Twelve), &procedural);
+    assert!(svg.contains("class=\"ball-stripe\""));
+    let svg = render_svg_with_options(&ball_at_center(BallType::Four), &procedural);
+    assert!(!svg.contains("class=\"ball-stripe\""));
+}
+
+#[test]
+fn a_phantom_ball_shows_a_ball_see_through_beneath_the_balls() {
+    let ball = |y: &str| Ball {
+        ty: BallType::Cue,
+        position: Position::new("2", y),
+        spec: BallSpec::default(),
+    };
+    let empty = render(&GameState::new(TableSpec::default()));
+    let solid = render(&GameState::with_balls(TableSpec::default(), [ball("4")]));
+    let mut state = GameState::with_balls(TableSpec::default(), [ball("2")]);
+    state.add_phantom_ball(BallType::Cue, &Position::new("2", "4"));
+    let phantom = render(&state);
+
+    // The default 40% opacity lands the center between the cloth and the cue ball.
+    let (cloth, cue, blend) = (
+        empty.get_pixel(539, 970),
+        solid.get_pixel(539, 970),
+        phantom.get_pixel(539, 970),
+    );
+    for channel in 0..3 {
+        let expected = cloth[channel] as f32 + 0.4 * (cue[channel] as f32 - cloth[channel] as f32);
+        assert!(
+            (blend[channel] as f32 - expected).abs() <= 2.0,
+            "{blend:?} {cloth:?} {cue:?}"
+        );
+    }
+
+    let svg = render_svg_with_options(&state, &DiagramRenderOptions::default());
+    let phantom_at = svg
+        .find("<g class=\"ball phantom-ball ball-cue\"")
+        .expect("phantom ball group");
+    assert!(svg[phantom_at..].contains("opacity=\"0.400\""));
+    assert!(phantom_at < svg.find("<g class=\"ball ball-cue\"").unwrap());
+}
+
+#[test]
+fn annotation_edges_are_blended_by_coverage_and_stay_crisp_on_pixel_boundaries() {
+    let mut state = cue_ball_at("0.5", "7.5");
+    let color = Rgba([0, 0, 0, 255]);
+    let width = |width_px| SmoothPolylineStyle {
+        width_px,
+        ..SmoothPolylineStyle::new(color)
+    };
+    // Three pixels wide through a pixel center, both edges fall between rows; four pixels wide,
+    // both fall halfway across a row.
+    state.add_solid_line_styled(
+        &Position::new("1", "4"),
+        &Position::new("3", "4"),
+        width(3.0),
+    );
+    state.add_solid_line_styled(
+        &Position::new("1", "6"),
+        &Position::new("3", "6"),
+        width(4.0),
+    );
+    state.add_solid_line_styled(
+        &Position::new("1", "1"),
+        &Position::new("3", "2"),
+        width(3.0),
+    );
+    let options = DiagramRenderOptions {
+        background: DiagramBackground::Transparent,
+        ..DiagramRenderOptions::default()
+    };
+    let image = render_with_options(&state, &options);
+    let column =
+        |rows: std::ops::Range<u32>| rows.map(|y| image.get_pixel(539, y)[3]).collect::<Vec<_>>();
+
+    assert_eq!(column(965..974), [0, 0, 0, 255, 255, 255, 0, 0, 0]);
+    assert_eq!(column(536..545), [0, 0, 128, 255, 255, 255, 128, 0, 0]);
+    let slanted: Vec<u8> = image
+        .enumerate_pixels()
+        .filter(|(x, y, _)| (325..755).contains(x) && (1399..1614).contains(y))
+        .map(|(_, _, pixel)| pixel[3])
+        .collect();
+    assert!(slanted.contains(&255));
+    assert!(slanted.iter().any(|&alpha| alpha > 0 && alpha < 255));
+}
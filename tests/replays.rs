@@ -0,0 +1,96 @@
+use billiards::replay::{Replay, REPLAY_FORMAT_VERSION};
+use billiards::simulation::{simulate, EventKind, SimulationConfig};
+use billiards::{
+    Ball, BallSpec, BallType, CueTipContact, GameState, GameType, InchesPerSecond, Position, Scale,
+    Shot, TableSpec,
+};
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn played_replay() -> Replay {
+    let mut state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "2", "4"),
+            ball_at(BallType::Nine, "3", "6.5"),
+        ],
+    );
+    state.ty = GameType::TenBall;
+    let shot = Shot::toward(
+        &Position::new("1", "4"),
+        &Position::new("2", "4"),
+        InchesPerSecond::new("80"),
+        CueTipContact::new(Scale::from_f64(0.25), Scale::from_f64(-0.3)).unwrap(),
+    )
+    .unwrap()
+    .with_cue_elevation_degrees(5.0)
+    .unwrap();
+    let result = simulate(&state, &shot).unwrap();
+    Replay::new(&result, &shot)
+}
+
+#[test]
+fn a_replay_round_trips_through_bytes_and_plays_back_the_same_events() {
+    let replay = played_replay();
+
+    let bytes = replay.to_bytes();
+    let read = Replay::from_bytes(&bytes).unwrap();
+
+    assert_eq!(&bytes[..4], b"BLRP");
+    assert_eq!(read.shot, replay.shot);
+    assert_eq!(read.event_log, replay.event_log);
+    assert_eq!(
+        read.initial_state.to_notation(),
+        "c:1,4/1:2,4/9:3,6.5 - 10b"
+    );
+    assert_eq!(
+        read.initial_state.table_spec.pockets,
+        TableSpec::default().pockets
+    );
+    assert!(matches!(
+        read.event_log[0].kind,
+        EventKind::BallBallCollision {
+            first: BallType::Cue,
+            second: BallType::One,
+            ..
+        }
+    ));
+    let rerun = read.simulate(&SimulationConfig::default()).unwrap();
+    assert_eq!(rerun.event_log, replay.event_log);
+
+    let cue_route = read.route(&BallType::Cue);
+    assert_eq!(cue_route[0], Position::new("1", "4"));
+    assert!(cue_route.len() > 2);
+    assert_eq!(read.route(&BallType::Nine), [Position::new("3", "6.5")]);
+    assert!(read.route(&BallType::Eight).is_empty());
+}
+
+#[test]
+fn reading_a_replay_rejects_other_files_newer_versions_and_truncation() {
+    let bytes = played_replay().to_bytes();
+
+    let not_a_replay = Replay::from_bytes(b"PK\x03\x04 zip").unwrap_err();
+    assert!(not_a_replay.contains("not a replay"), "{not_a_replay}");
+
+    let mut newer = bytes.clone();
+    newer[4..6].copy_from_slice(&(REPLAY_FORMAT_VERSION + 1).to_le_bytes());
+    let newer = Replay::from_bytes(&newer).unwrap_err();
+    assert!(newer.contains("version 2 is not supported"), "{newer}");
+
+    let truncated = Replay::from_bytes(&bytes[..bytes.len() - 3]).unwrap_err();
+    assert!(
+        truncated.contains("unexpected end of replay"),
+        "{truncated}"
+    );
+
+    let mut trailing = bytes;
+    trailing.push(0);
+    assert!(Replay::from_bytes(&trailing).is_err());
+}
@@ -0,0 +1,171 @@
+use billiards::aiming::position_zone;
+use billiards::simulation::{solve_required_speed, EventDrivenSimulator, RestTarget, SpeedSearch};
+use billiards::{
+    Angle, Ball, BallSpec, BallType, CueTipContact, Diamond, GameState, Inches, InchesPerSecond,
+    Pocket, Position, Scale, Shot, TableSpec,
+};
+
+fn as_f64(value: &Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn speeds(min: f64, max: f64, steps: usize) -> SpeedSearch {
+    SpeedSearch {
+        min_speed: InchesPerSecond::new(Inches::from_f64(min)),
+        max_speed: InchesPerSecond::new(Inches::from_f64(max)),
+        speed_steps: steps,
+        ..SpeedSearch::default()
+    }
+}
+
+fn lag_up_the_table() -> (GameState, Shot) {
+    let state = GameState::with_balls(TableSpec::default(), [ball_at(BallType::Cue, "2", "2")]);
+    let shot = Shot::new(
+        Angle::from_north(0.0, 1.0),
+        InchesPerSecond::new("40"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+    (state, shot)
+}
+
+#[test]
+fn the_best_speed_stops_the_cue_ball_on_the_spot() {
+    let (state, shot) = lag_up_the_table();
+    let target = RestTarget::Spot {
+        center: Position::new("2", "5"),
+        radius: Inches::from_f64(4.0),
+    };
+
+    let candidates = solve_required_speed(
+        &EventDrivenSimulator::default(),
+        &state,
+        &shot,
+        &target,
+        &speeds(20.0, 80.0, 13),
+    )
+    .unwrap();
+
+    assert_eq!(candidates.len(), 3);
+    let best = &candidates[0];
+    assert!(best.in_target);
+    let rest = best.rest.as_ref().unwrap();
+    assert!((as_f64(&rest.y) - 5.0).abs() * 12.5 <= 4.0);
+    assert!(candidates
+        .windows(2)
+        .all(|pair| pair[0].distance_from_middle <= pair[1].distance_from_middle));
+    assert_eq!(best.shot.heading(), shot.heading());
+
+    assert_eq!(best.cue_ball_path.first(), Some(&Position::new("2", "2")));
+    assert_eq!(best.cue_ball_path.last(), Some(rest));
+}
+
+#[test]
+fn strokes_that_miss_the_target_still_rank_by_how_close_they_come() {
+    let (state, shot) = lag_up_the_table();
+    let target = RestTarget::Spot {
+        center: Position::new("2", "7"),
+        radius: Inches::from_f64(1.0),
+    };
+
+    let candidates = solve_required_speed(
+        &EventDrivenSimulator::default(),
+        &state,
+        &shot,
+        &target,
+        &speeds(20.0, 30.0, 2),
+    )
+    .unwrap();
+
+    assert_eq!(candidates.len(), 2);
+    assert!(candidates.iter().all(|candidate| !candidate.in_target));
+    assert!(candidates[0].shot.cue_speed().as_f64() > candidates[1].shot.cue_speed().as_f64());
+}
+
+#[test]
+fn a_single_speed_step_tries_the_minimum_speed_alone() {
+    let (state, shot) = lag_up_the_table();
+    let target = RestTarget::Spot {
+        center: Position::new("2", "7"),
+        radius: Inches::from_f64(1.0),
+    };
+
+    let candidates = solve_required_speed(
+        &EventDrivenSimulator::default(),
+        &state,
+        &shot,
+        &target,
+        &speeds(25.0, 30.0, 1),
+    )
+    .unwrap();
+
+    assert_eq!(candidates.len(), 1);
+    assert!((candidates[0].shot.cue_speed().as_f64() - 25.0).abs() < 1e-9);
+}
+
+#[test]
+fn draw_is_chosen_to_bring_the_cue_ball_back_up_the_line() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "2", "4"),
+        ],
+    );
+    let shot = Shot::toward(
+        &Position::new("1", "4"),
+        &Position::new("2", "4"),
+        InchesPerSecond::new("80"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+    let target = RestTarget::Spot {
+        center: Position::new("0.7", "4"),
+        radius: Inches::from_f64(3.0),
+    };
+    let search = SpeedSearch {
+        tip_heights: vec![Scale::zero(), Scale::from_f64(-0.5)],
+        ..speeds(60.0, 120.0, 3)
+    };
+
+    let candidates = solve_required_speed(
+        &EventDrivenSimulator::default(),
+        &state,
+        &shot,
+        &target,
+        &search,
+    )
+    .unwrap();
+
+    let best = &candidates[0];
+    assert!(best.in_target, "best rest {:?}", best.rest);
+    assert_eq!(best.shot.tip_contact().height_offset().as_f64(), -0.5);
+    assert!(best
+        .result
+        .pocketed
+        .contains(&(BallType::One, Pocket::CenterRight)));
+    // Out to the object ball, then back to the rest.
+    assert!(best.cue_ball_path.len() >= 3);
+}
+
+#[test]
+fn a_zone_target_is_the_zone_outline() {
+    let table_spec = TableSpec::default();
+    let next_ball = ball_at(BallType::Two, "1", "1");
+    let zone = position_zone(&next_ball, Pocket::BottomLeft, 3.0, &[], &table_spec).unwrap();
+    let ghost_ball = zone.ghost_ball.clone();
+    let target = RestTarget::Zone(zone);
+
+    let behind_the_ghost_ball = Position::new("1.5", "1.5");
+    assert!(target.contains(&behind_the_ghost_ball, &table_spec));
+    assert!(!target.contains(&Position::new("3", "7"), &table_spec));
+    assert!(target.distance_from_middle(&ghost_ball, &table_spec) > 0.0);
+}
@@ -0,0 +1,101 @@
+use billiards::planning::{plan_nine_ball_runout, RunoutPlannerConfig};
+use billiards::{Ball, BallSpec, BallType, GameState, Position, TableSpec};
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn open_layout() -> GameState {
+    GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "2", "2"),
+            ball_at(BallType::Nine, "3", "6.5"),
+            ball_at(BallType::Two, "1", "5"),
+            ball_at(BallType::One, "2.5", "3.5"),
+        ],
+    )
+}
+
+#[test]
+fn an_open_layout_is_run_in_rotation_order() {
+    let game_state = open_layout();
+
+    let plan = plan_nine_ball_runout(&game_state, &RunoutPlannerConfig::default()).unwrap();
+
+    assert!(plan.is_complete());
+    let order: Vec<BallType> = plan
+        .steps
+        .iter()
+        .map(|step| step.object_ball.clone())
+        .collect();
+    assert_eq!(order, vec![BallType::One, BallType::Two, BallType::Nine]);
+    assert!(plan.makeability() > 0.0 && plan.makeability() <= 1.0);
+}
+
+#[test]
+fn each_shot_is_played_from_the_previous_leave() {
+    let game_state = open_layout();
+
+    let plan = plan_nine_ball_runout(&game_state, &RunoutPlannerConfig::default()).unwrap();
+
+    assert_eq!(
+        plan.steps[0].cue_ball,
+        game_state.select_ball(BallType::Cue).unwrap().position
+    );
+    for pair in plan.steps.windows(2) {
+        assert!(pair[0].cue_action.is_some());
+        assert_eq!(pair[0].cue_ball_path.len(), 3);
+        assert_eq!(pair[0].cue_ball_path[1], pair[0].ghost_ball);
+        assert_eq!(pair[0].cue_ball_path[2], pair[1].cue_ball);
+    }
+    let last = plan.steps.last().unwrap();
+    assert_eq!(last.cue_action, None);
+    assert_eq!(
+        last.cue_ball_path,
+        vec![last.cue_ball.clone(), last.ghost_ball.clone()]
+    );
+}
+
+#[test]
+fn a_greedy_planner_still_finds_a_runout() {
+    let config = RunoutPlannerConfig {
+        beam_width: 1,
+        ..RunoutPlannerConfig::default()
+    };
+
+    let plan = plan_nine_ball_runout(&open_layout(), &config).unwrap();
+
+    assert!(plan.is_complete());
+}
+
+#[test]
+fn a_shot_over_the_difficulty_limit_stops_the_plan_before_it() {
+    let config = RunoutPlannerConfig {
+        max_difficulty: 0.01,
+        ..RunoutPlannerConfig::default()
+    };
+
+    let plan = plan_nine_ball_runout(&open_layout(), &config).unwrap();
+
+    assert!(plan.steps.is_empty());
+    assert_eq!(
+        plan.remaining,
+        vec![BallType::One, BallType::Two, BallType::Nine]
+    );
+}
+
+#[test]
+fn a_layout_without_a_cue_ball_has_no_plan() {
+    let game_state =
+        GameState::with_balls(TableSpec::default(), [ball_at(BallType::Nine, "2", "4")]);
+
+    assert_eq!(
+        plan_nine_ball_runout(&game_state, &RunoutPlannerConfig::default()),
+        None
+    );
+}
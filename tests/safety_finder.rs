@@ -0,0 +1,96 @@
+use billiards::planning::{find_safeties, hidden_fraction, SafetyFinderConfig};
+use billiards::{Ball, BallSpec, BallType, GameState, Position, TableSpec};
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn an_open_ball_is_not_hidden_at_all() {
+    let table = TableSpec::default();
+    let target = ball_at(BallType::One, "2", "5");
+
+    let hidden = hidden_fraction(&Position::new("2", "1"), &target, &[], 17, &table);
+
+    assert_eq!(hidden, 0.0);
+}
+
+#[test]
+fn a_ball_dead_in_line_is_a_full_snooker_and_one_off_to_the_side_is_partial() {
+    let table = TableSpec::default();
+    let target = ball_at(BallType::One, "2", "5");
+    let cue_ball = Position::new("2", "1");
+
+    let full = hidden_fraction(
+        &cue_ball,
+        &target,
+        &[ball_at(BallType::Five, "2", "3")],
+        17,
+        &table,
+    );
+    let partial = hidden_fraction(
+        &cue_ball,
+        &target,
+        &[ball_at(BallType::Five, "2.2", "3")],
+        17,
+        &table,
+    );
+
+    assert_eq!(full, 1.0);
+    assert!(partial > 0.0 && partial < 1.0, "got {partial}");
+}
+
+#[test]
+fn the_cue_ball_and_the_target_itself_never_hide_the_target() {
+    let table = TableSpec::default();
+    let target = ball_at(BallType::One, "2", "5");
+
+    let hidden = hidden_fraction(
+        &Position::new("2", "1"),
+        &target,
+        &[ball_at(BallType::Cue, "2", "3"), target.clone()],
+        17,
+        &table,
+    );
+
+    assert_eq!(hidden, 0.0);
+}
+
+#[test]
+fn safeties_are_ranked_by_how_well_they_hide_the_object_ball() {
+    let object_ball = ball_at(BallType::One, "2", "4");
+    let game_state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "2", "2"),
+            object_ball.clone(),
+            ball_at(BallType::Five, "1", "5"),
+            ball_at(BallType::Six, "3.6", "5.2"),
+        ],
+    );
+
+    let safeties = find_safeties(&game_state, &object_ball, &SafetyFinderConfig::default());
+
+    assert!(!safeties.is_empty());
+    assert!(safeties[0].hidden_fraction > 0.5);
+    assert!(safeties
+        .windows(2)
+        .all(|pair| pair[0].hidden_fraction >= pair[1].hidden_fraction));
+    for safety in &safeties {
+        assert_eq!(safety.object_ball, BallType::One);
+        assert_eq!(safety.cue_ball_path[1], safety.ghost_ball);
+        assert_eq!(safety.cue_ball_path[2], safety.leave);
+    }
+}
+
+#[test]
+fn a_layout_without_a_cue_ball_has_no_safeties() {
+    let object_ball = ball_at(BallType::One, "2", "4");
+    let game_state = GameState::with_balls(TableSpec::default(), [object_ball.clone()]);
+
+    assert!(find_safeties(&game_state, &object_ball, &SafetyFinderConfig::default()).is_empty());
+}
@@ -140,6 +140,7 @@ fn professional_manual_check_diagrams_parse_simulate_and_render_with_debug_overl
                     ..BallPathRenderOptions::default()
                 },
                 start_ghost_balls: true,
+                start_phantom_balls: false,
                 event_markers: true,
                 labels: true,
                 path_color_mode: PathColorMode::MotionPhase,
@@ -148,6 +149,7 @@ fn professional_manual_check_diagrams_parse_simulate_and_render_with_debug_overl
         let image = rendered.draw_2d_diagram_with_options(&DiagramRenderOptions {
             scale_factor: 1,
             background: DiagramBackground::Transparent,
+            ..DiagramRenderOptions::default()
         });
         assert!(!image.is_empty(), "{scenario_path}: empty render");
     }
@@ -0,0 +1,133 @@
+use billiards::diagram::DiagramElement;
+use billiards::simulation::{scratch_risks, ScratchRisk, SimulationConfig};
+use billiards::visualization::SmoothPolylineStyle;
+use billiards::{
+    strike_resting_ball_on_table, trace_ball_path_with_rail_profile_on_table, Ball, BallPath,
+    BallPathStop, BallSpec, BallState, BallType, CueTipContact, DiagramRenderOptions, GameState,
+    Inches, InchesPerSecond, Pocket, Position, RestingOnTableBallState, Shot, TableSpec,
+};
+use image::Rgba;
+
+/// Trace the cue ball struck from `from` toward `toward` until it stops.
+fn cue_ball_path(from: &Position, toward: &Position, speed: &str) -> BallPath {
+    let config = SimulationConfig::default();
+    let table = TableSpec::default();
+    let resting =
+        RestingOnTableBallState::try_from(BallState::from_position(from, &table)).unwrap();
+    let shot = Shot::toward(
+        from,
+        toward,
+        InchesPerSecond::new(speed),
+        CueTipContact::center(),
+    )
+    .unwrap();
+    let struck =
+        strike_resting_ball_on_table(&resting, &shot, &config.cue_strike, &config.ball_set)
+            .unwrap();
+    trace_ball_path_with_rail_profile_on_table(
+        &struck,
+        BallPathStop::UntilRest,
+        &config.ball_set,
+        &table,
+        &config.motion,
+        config.rail_model,
+        &table.cushions,
+    )
+}
+
+fn risks_within(path: &BallPath, inches: f64) -> Vec<ScratchRisk> {
+    scratch_risks(
+        path,
+        Inches::from_f64(inches),
+        &SimulationConfig::default(),
+        &TableSpec::default(),
+    )
+}
+
+#[test]
+fn a_cue_ball_sent_at_the_side_pocket_scratches_there_and_goes_no_further() {
+    // Fast enough that the traced path, which ignores pockets, would carry on to the far side.
+    let path = cue_ball_path(&Position::new("1", "4"), &Position::new("4", "4"), "60");
+
+    let risks = risks_within(&path, 3.0);
+
+    assert_eq!(risks.len(), 1, "{risks:?}");
+    let risk = &risks[0];
+    assert_eq!(risk.pocket, Pocket::CenterRight);
+    assert!(risk.scratches());
+    assert!(!risk.danger.is_empty());
+    assert!(risk.speed_margin.as_f64() > 10.0);
+}
+
+#[test]
+fn a_ball_that_slides_past_a_corner_is_a_near_miss() {
+    let path = cue_ball_path(
+        &Position::new("3.75", "2"),
+        &Position::new("3.75", "6"),
+        "60",
+    );
+
+    let risks = risks_within(&path, 3.0);
+
+    assert_eq!(risks.len(), 1, "{risks:?}");
+    let risk = &risks[0];
+    assert_eq!(risk.pocket, Pocket::TopRight);
+    assert!(!risk.scratches());
+    assert!((0.0..1.0).contains(&risk.clearance.as_f64()));
+    assert!(risks_within(&path, 0.1).is_empty());
+}
+
+#[test]
+fn a_path_down_the_middle_of_the_table_has_no_scratch_risk() {
+    let path = cue_ball_path(&Position::new("2", "2"), &Position::new("2", "6"), "60");
+
+    assert!(risks_within(&path, 3.0).is_empty());
+}
+
+#[test]
+fn the_scratch_risk_overlay_marks_each_danger_stretch() {
+    let from = Position::new("1", "4");
+    let path = cue_ball_path(&from, &Position::new("4", "4"), "60");
+    let risks = risks_within(&path, 3.0);
+    let mut game_state = GameState::with_balls(
+        TableSpec::default(),
+        [Ball {
+            ty: BallType::Cue,
+            position: from,
+            spec: BallSpec::default(),
+        }],
+    );
+    let red = Rgba([220, 30, 30, 255]);
+
+    game_state.add_scratch_risk_overlay(&risks, &SmoothPolylineStyle::new(red));
+
+    let scene = game_state.to_diagram_scene(&DiagramRenderOptions::default());
+    let marked: Vec<&Vec<Position>> = scene
+        .elements
+        .iter()
+        .filter_map(|element| match element {
+            DiagramElement::SmoothPolyline { points, style } if style.color == red => Some(points),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(marked, vec![&risks[0].danger]);
+}
+
+#[test]
+fn risks_come_in_the_order_the_ball_reaches_them() {
+    // Down the long rail, wide enough of the side pocket, from beside one corner to the other.
+    let path = cue_ball_path(
+        &Position::new("3.7", "0.3"),
+        &Position::new("3.7", "7.7"),
+        "90",
+    );
+
+    let risks = risks_within(&path, 3.0);
+
+    let pockets: Vec<Pocket> = risks.iter().map(|risk| risk.pocket).collect();
+    assert_eq!(
+        pockets,
+        [Pocket::TopRight, Pocket::BottomRight],
+        "{risks:?}"
+    );
+}
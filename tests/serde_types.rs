@@ -0,0 +1,167 @@
+#![cfg(feature = "serde")]
+
+use billiards::diagram::DiagramElement;
+use billiards::schema;
+use billiards::visualization::{LabelOverlayStyle, ShadedZoneStyle};
+use billiards::{
+    Ball, BallSpec, BallType, CueballModifier, DiagramRenderOptions, GameState, Inches, Position,
+    TableSpec,
+};
+use serde_json::json;
+
+#[test]
+fn decimals_are_written_as_strings_so_they_keep_every_digit() {
+    let position = Position::new("2.125", "4");
+
+    assert_eq!(
+        serde_json::to_value(&position).unwrap(),
+        json!({ "x": "2.125", "y": "4" })
+    );
+    assert_eq!(
+        serde_json::from_value::<Position>(json!({ "x": "2.125", "y": "4" })).unwrap(),
+        position
+    );
+    assert_eq!(
+        serde_json::to_value(Inches::from_f64(1.125)).unwrap(),
+        json!("1.125")
+    );
+}
+
+#[test]
+fn a_game_state_round_trips_with_its_balls_table_and_annotations() {
+    let mut state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            Ball {
+                ty: BallType::Cue,
+                position: Position::new(2u8, 6u8),
+                spec: BallSpec::default(),
+            },
+            Ball {
+                ty: BallType::Eight,
+                position: Position::new("1.5", "2"),
+                spec: BallSpec::default(),
+            },
+        ],
+    );
+    state.add_shaded_zone_styled(
+        &[
+            Position::new(0u8, 0u8),
+            Position::new(1u8, 0u8),
+            Position::new(1u8, 1u8),
+        ],
+        ShadedZoneStyle::new(image::Rgba([255, 0, 0, 96])),
+    );
+
+    let json = serde_json::to_string(&state).unwrap();
+    let restored: GameState = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+    assert_eq!(restored.table_spec, TableSpec::default());
+    assert_eq!(
+        restored.select_ball(BallType::Eight).unwrap().position,
+        Position::new("1.5", "2")
+    );
+    match &restored.overlays()[0] {
+        DiagramElement::ShadedZone { style, .. } => {
+            assert_eq!(style.fill_color, image::Rgba([255, 0, 0, 96]))
+        }
+        other => panic!("expected a shaded zone, got {other:?}"),
+    }
+    assert!(json.contains("\"fill_color\":\"#ff000060\""), "{json}");
+}
+
+#[test]
+fn game_states_save_to_and_load_from_json_in_a_stable_shape() {
+    let mut state = GameState::with_balls(
+        TableSpec::default(),
+        [Ball {
+            ty: BallType::Cue,
+            position: Position::new(2u8, 6u8),
+            spec: BallSpec::default(),
+        }],
+    );
+    state.cueball_modifier = CueballModifier::BallInHand;
+    state.in_named_layer("notes", |state| {
+        state.add_text_label_styled(
+            &Position::new(2u8, 4u8),
+            "SAFE",
+            LabelOverlayStyle::enabled(image::Rgba([0, 0, 0, 255])),
+        )
+    });
+
+    let json: serde_json::Value = serde_json::from_str(&state.to_json()).unwrap();
+    assert_eq!(
+        json["balls"],
+        json!([{ "ty": "Cue", "position": { "x": "2", "y": "6" }, "spec": { "radius": "1.125" } }])
+    );
+    assert_eq!(json["game_type"], "NineBall");
+    assert_eq!(json["cue_ball"], "BallInHand");
+    assert_eq!(json["annotations"][0]["kind"], "text_label");
+    assert_eq!(json["annotations"][0]["style"]["color"], "#000000ff");
+    assert_eq!(json["annotation_layers"], json!(["notes"]));
+    assert_eq!(json["table_spec"]["diamond_length"], "12.5");
+
+    let restored = GameState::from_json(&state.to_json()).unwrap();
+    assert_eq!(restored.to_json(), state.to_json());
+    assert_eq!(restored.named_layers(), ["notes"]);
+}
+
+#[test]
+fn a_json_layout_needs_only_its_balls() {
+    let state = GameState::from_json(
+        r##"{
+            "balls": [{ "ty": "Nine", "position": { "x": "3", "y": "7.5" }, "spec": { "radius": "1.125" } }],
+            "annotations": [{ "kind": "circle_marker", "center": { "x": "1", "y": "1" },
+                "style": { "enabled": true, "color": "#ff0000", "radius_px": 6.0, "layer": "AboveBalls" } }]
+        }"##,
+    )
+    .unwrap();
+
+    assert_eq!(state.table_spec, TableSpec::default());
+    assert_eq!(
+        state.select_ball(BallType::Nine).unwrap().position,
+        Position::new("3", "7.5")
+    );
+    let scene = state.to_diagram_scene(&DiagramRenderOptions::default());
+    assert_eq!(scene.elements.len(), 1);
+    assert!(GameState::from_json(r#"{ "balls": [{ "ty": "Seventeen" }] }"#).is_err());
+}
+
+#[test]
+fn layouts_saved_before_schema_versions_are_migrated_as_they_load() {
+    let unversioned = r#"{
+        "balls": [
+            { "ty": "Cue", "position": { "x": "2", "y": "6" } },
+            { "ty": "Nine", "position": { "x": "3", "y": "7.5" } }
+        ],
+        "ball_focus": [["Cue"], 0.25]
+    }"#;
+
+    let state = GameState::from_json(unversioned).unwrap();
+
+    let scene = state.to_diagram_scene(&DiagramRenderOptions::default());
+    let opacities: Vec<f32> = scene.balls.iter().map(|ball| ball.opacity).collect();
+    assert_eq!(opacities, [1.0, 0.25]);
+    let json: serde_json::Value = serde_json::from_str(&state.to_json()).unwrap();
+    assert_eq!(json["schema_version"], schema::SCHEMA_VERSION);
+    assert_eq!(
+        json["ball_focus"],
+        json!({ "balls": ["Cue"], "opacity": 0.25 })
+    );
+    assert!(state.to_json().starts_with("{\"schema_version\":"));
+}
+
+#[test]
+fn files_from_a_newer_schema_version_are_refused() {
+    let newer = format!(
+        r#"{{ "schema_version": {}, "balls": [] }}"#,
+        schema::SCHEMA_VERSION + 1
+    );
+
+    let error = GameState::from_json(&newer).unwrap_err().to_string();
+
+    assert!(error.contains("is newer than"), "{error}");
+    assert!(GameState::from_json(r#"{ "schema_version": "two" }"#).is_err());
+    assert!(schema::migrate(schema::Document::Layout, json!([])).is_err());
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "serde")]
+
+use billiards::visualization::LabelOverlayStyle;
+use billiards::{
+    Ball, BallSpec, BallType, CueballModifier, GameState, Position, TableSpec,
+    MAX_SHARE_STRING_JSON_BYTES,
+};
+
+#[test]
+fn a_share_string_is_url_safe_and_carries_the_whole_diagram() {
+    let mut state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            Ball {
+                ty: BallType::Cue,
+                position: Position::new("1.25", "6"),
+                spec: BallSpec::default(),
+            },
+            Ball {
+                ty: BallType::Nine,
+                position: Position::new("3.5", "7.25"),
+                spec: BallSpec::default(),
+            },
+        ],
+    );
+    state.cueball_modifier = CueballModifier::BallInHand;
+    state.add_arrow(
+        &Position::new("1.25", "6"),
+        &Position::new("3.5", "7.25"),
+        image::Rgba([255, 255, 255, 255]),
+    );
+
+    let share = state.to_share_string();
+    let restored = GameState::from_share_string(&share).unwrap();
+
+    assert!(share
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert!(share.len() < state.to_json().len(), "{share}");
+    assert_eq!(restored.to_json(), state.to_json());
+    assert_eq!(restored.overlays().len(), 1);
+}
+
+#[test]
+fn broken_and_oversized_share_strings_are_refused() {
+    assert!(GameState::from_share_string("not base64!")
+        .unwrap_err()
+        .contains("URL-safe base64"));
+    assert!(GameState::from_share_string("____")
+        .unwrap_err()
+        .contains("deflated JSON"));
+
+    let mut huge = GameState::new(TableSpec::default());
+    huge.add_text_label_styled(
+        &Position::new(2u8, 4u8),
+        "x".repeat(MAX_SHARE_STRING_JSON_BYTES as usize),
+        LabelOverlayStyle::enabled(image::Rgba([0, 0, 0, 255])),
+    );
+    let error = GameState::from_share_string(&huge.to_share_string()).unwrap_err();
+    assert!(error.contains("more than"), "{error}");
+}
@@ -0,0 +1,225 @@
+use billiards::animation::{
+    render_apng, render_frames, render_frames_with_options, render_gif, write_apng, write_gif,
+    AnimationOptions,
+};
+use billiards::simulation::{simulate, SimulationResult};
+use billiards::{
+    Ball, BallSpec, BallType, CueTipContact, DiagramRenderOptions, GameState, InchesPerSecond,
+    Position, Shot, TableSpec,
+};
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::AnimationDecoder;
+use std::io::{self, Write};
+
+/// The cue ball rolled two diamonds up the table, slowly enough to stop in a second or two.
+fn slow_roll() -> SimulationResult {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [Ball {
+            ty: BallType::Cue,
+            position: Position::new("2", "2"),
+            spec: BallSpec::default(),
+        }],
+    );
+    let shot = Shot::toward(
+        &Position::new("2", "2"),
+        &Position::new("2", "4"),
+        InchesPerSecond::new("20"),
+        CueTipContact::center(),
+    )
+    .expect("shot should validate");
+    simulate(&state, &shot).unwrap()
+}
+
+fn thumbnail() -> DiagramRenderOptions {
+    DiagramRenderOptions {
+        max_width_px: Some(60),
+        ..DiagramRenderOptions::default()
+    }
+}
+
+fn delay_ms(frame: &image::Frame) -> u32 {
+    let (numer, denom) = frame.delay().numer_denom_ms();
+    numer / denom
+}
+
+#[test]
+fn a_simulated_shot_animates_as_a_looping_gif_at_the_chosen_frame_rate() {
+    let result = slow_roll();
+    let options = thumbnail();
+
+    let gif = render_gif(&result, &options, &AnimationOptions::default().with_fps(4));
+
+    let frames = GifDecoder::new(std::io::Cursor::new(gif))
+        .expect("gif decode")
+        .into_frames()
+        .collect_frames()
+        .expect("gif frames");
+    let elapsed = result.elapsed().as_f64();
+    assert_eq!(frames.len(), (elapsed * 4.0).ceil() as usize + 1);
+    assert_eq!(delay_ms(&frames[0]), 250);
+    assert_eq!(delay_ms(frames.last().unwrap()), 1250);
+    assert_eq!(frames[0].buffer().width(), 60);
+    assert_ne!(frames[0].buffer(), frames.last().unwrap().buffer());
+}
+
+#[test]
+fn frames_of_a_simulated_shot_can_be_drawn_one_by_one() {
+    let result = slow_roll();
+    let options = thumbnail();
+
+    let first = render_frames(&result, 4).next().expect("a first frame");
+    assert_eq!(first, result.initial_state.render_to_image());
+
+    let frames: Vec<_> =
+        render_frames_with_options(&result, &options, &AnimationOptions::default().with_fps(4))
+            .collect();
+    assert_eq!(
+        frames.len(),
+        (result.elapsed().as_f64() * 4.0).ceil() as usize + 1
+    );
+    assert_eq!(
+        frames.last(),
+        Some(
+            &result
+                .layout_at(result.elapsed())
+                .render_to_image_with_options(&options)
+        )
+    );
+}
+
+#[test]
+fn a_simulated_shot_animates_as_a_full_color_png() {
+    let result = slow_roll();
+    let options = thumbnail();
+    let still = result
+        .layout_at(result.elapsed())
+        .render_to_image_with_options(&options);
+
+    let apng = render_apng(&result, &options, &AnimationOptions::default().with_fps(4));
+
+    let frames = PngDecoder::new(std::io::Cursor::new(apng))
+        .expect("png decode")
+        .apng()
+        .expect("animated png")
+        .into_frames()
+        .collect_frames()
+        .expect("png frames");
+    assert_eq!(
+        frames.len(),
+        (result.elapsed().as_f64() * 4.0).ceil() as usize + 1
+    );
+    assert_eq!(delay_ms(&frames[0]), 250);
+    assert_eq!(delay_ms(frames.last().unwrap()), 1250);
+    assert_eq!(frames.last().unwrap().buffer(), &still);
+}
+
+/// Takes up to `limit` bytes, counting the writes, then fails.
+struct LimitedWriter {
+    bytes: Vec<u8>,
+    writes: usize,
+    limit: usize,
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.bytes.len() + buf.len() > self.limit {
+            return Err(io::Error::new(io::ErrorKind::StorageFull, "disk full"));
+        }
+        self.bytes.extend_from_slice(buf);
+        self.writes += 1;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn animations_stream_frame_by_frame_into_a_writer() {
+    let result = slow_roll();
+    let options = thumbnail();
+    let animation = AnimationOptions::default().with_fps(4);
+    let frames = (result.elapsed().as_f64() * 4.0).ceil() as usize + 1;
+
+    let mut apng = LimitedWriter {
+        bytes: Vec::new(),
+        writes: 0,
+        limit: usize::MAX,
+    };
+    write_apng(&result, &options, &animation, &mut apng).unwrap();
+    assert_eq!(apng.bytes, render_apng(&result, &options, &animation));
+    assert!(apng.writes > frames, "{} writes", apng.writes);
+
+    let mut gif = Vec::new();
+    write_gif(&result, &options, &animation, &mut gif).unwrap();
+    assert_eq!(gif, render_gif(&result, &options, &animation));
+
+    let mut full = LimitedWriter {
+        bytes: Vec::new(),
+        writes: 0,
+        limit: 1000,
+    };
+    let error = write_gif(&result, &options, &animation, &mut full).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+}
+
+#[cfg(feature = "mp4")]
+#[test]
+fn a_simulated_shot_encodes_as_av1_in_an_mp4_file() {
+    use billiards::animation::render_mp4;
+
+    let result = slow_roll();
+    let options = thumbnail();
+    let (width, height) = result
+        .layout_at(result.elapsed())
+        .render_to_image_with_options(&options)
+        .dimensions();
+
+    let mp4 = render_mp4(&result, &options, &AnimationOptions::default().with_fps(4));
+
+    let find = |needle: &[u8]| {
+        mp4.windows(needle.len())
+            .position(|window| window == needle)
+            .unwrap_or_else(|| panic!("no {:?} box", String::from_utf8_lossy(needle)))
+    };
+    assert_eq!(&mp4[4..12], b"ftypisom");
+    let frames = (result.elapsed().as_f64() * 4.0).ceil() as u32 + 1;
+    let u32_at = |offset: usize| u32::from_be_bytes(mp4[offset..offset + 4].try_into().unwrap());
+    // mvhd: version and flags, two timestamps, then a millisecond timescale and the duration.
+    let mvhd = find(b"mvhd") + 4;
+    assert_eq!(u32_at(mvhd + 12), 1000);
+    assert_eq!(u32_at(mvhd + 16), (frames - 1) * 250 + 1250);
+    // The first sample entry, whose body opens with 24 bytes ahead of the frame size.
+    let av01 = find(b"stsd") + 20;
+    assert_eq!(&mp4[av01 - 4..av01], b"av01");
+    assert_eq!(
+        &mp4[av01 + 24..av01 + 28],
+        &[
+            (width >> 8) as u8,
+            (width & !1) as u8,
+            (height >> 8) as u8,
+            (height & !1) as u8
+        ]
+    );
+    // av1C: the version 1 marker, then the sequence header OBU after the 4-byte record.
+    let av1c = find(b"av1C") + 4;
+    assert_eq!((mp4[av1c], mp4[av1c + 4]), (0x81, 0x0a));
+    assert_eq!(u32_at(find(b"stsz") + 12), frames);
+    let chunk_offset = u32_at(find(b"stco") + 12) as usize;
+    assert_eq!(&mp4[chunk_offset - 4..chunk_offset], b"mdat");
+    let mdat_size = u32_at(chunk_offset - 8) as usize;
+    assert_eq!(&mp4[chunk_offset + mdat_size - 8 + 4..][..4], b"moov");
+
+    let mut written = Vec::new();
+    billiards::animation::write_mp4(
+        &result,
+        &options,
+        &AnimationOptions::default().with_fps(4),
+        &mut written,
+    )
+    .unwrap();
+    assert_eq!(written.len(), mp4.len());
+}
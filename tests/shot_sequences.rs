@@ -0,0 +1,168 @@
+use billiards::rules::{AnyBallRules, Foul};
+use billiards::simulation::{simulate_sequence, simulate_sequence_with, EventDrivenSimulator};
+use billiards::{
+    Ball, BallSpec, BallType, CueTipContact, CueballModifier, GameState, GameType, InchesPerSecond,
+    Pocket, Position, Scale, Shot, TableSpec,
+};
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn shot_toward(from: &Position, toward: &Position, speed: &str, tip: CueTipContact) -> Shot {
+    Shot::toward(from, toward, InchesPerSecond::new(speed), tip).unwrap()
+}
+
+/// Straight at the right side pocket with nothing in the way, fast enough to drop.
+fn scratch_shot() -> Shot {
+    shot_toward(
+        &Position::new("1", "4"),
+        &Position::new("4", "4"),
+        "60",
+        CueTipContact::center(),
+    )
+}
+
+#[test]
+fn each_shot_starts_from_the_table_the_last_one_left() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "2", "4"),
+            ball_at(BallType::Two, "1", "7"),
+        ],
+    );
+    let make_the_one = shot_toward(
+        &Position::new("1", "4"),
+        &Position::new("2", "4"),
+        "80",
+        CueTipContact::center(),
+    );
+    // From about where the stun leaves the cue ball, at the two.
+    let hit_the_two = shot_toward(
+        &Position::new("1.82", "4"),
+        &Position::new("1", "7"),
+        "80",
+        CueTipContact::center(),
+    );
+
+    let sequence = simulate_sequence(&state, &[make_the_one, hit_the_two]).unwrap();
+
+    assert_eq!(sequence.shots.len(), 2);
+    assert_eq!(sequence.unplayed, 0);
+    assert_eq!(sequence.foul(), None);
+    assert_eq!(
+        sequence.pocketed(),
+        vec![(BallType::One, Pocket::CenterRight)]
+    );
+    let first_rest = sequence.shots[0]
+        .result
+        .final_state
+        .select_ball(BallType::Cue)
+        .unwrap()
+        .position
+        .clone();
+    let second = &sequence.shots[1].result;
+    assert_eq!(second.trajectory(BallType::Cue).unwrap()[0], first_rest);
+    assert_eq!(second.trajectory(BallType::One), None);
+    assert_eq!(second.first_contact(), Some(BallType::Two));
+    assert!(sequence.final_state.select_ball(BallType::One).is_none());
+}
+
+#[test]
+fn a_scratch_ends_the_turn_with_ball_in_hand() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "1", "7"),
+        ],
+    );
+
+    let sequence = simulate_sequence(&state, &[scratch_shot(), scratch_shot()]).unwrap();
+
+    assert_eq!(sequence.shots.len(), 1);
+    assert_eq!(sequence.unplayed, 1);
+    assert_eq!(sequence.foul(), Some(&Foul::Scratch));
+    assert!(sequence.final_state.select_ball(BallType::Cue).is_none());
+    assert!(matches!(
+        sequence.final_state.cueball_modifier,
+        CueballModifier::BallInHand
+    ));
+}
+
+#[test]
+fn nine_ball_spots_the_nine_when_it_drops_on_a_scratch() {
+    let mut state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::Nine, "2", "4"),
+            ball_at(BallType::One, "0.5", "7"),
+        ],
+    );
+    state.ty = GameType::NineBall;
+    let follow_both_in = shot_toward(
+        &Position::new("1", "4"),
+        &Position::new("2", "4"),
+        "150",
+        CueTipContact::new(Scale::zero(), Scale::from_f64(0.45)).unwrap(),
+    );
+
+    let sequence = simulate_sequence(&state, &[follow_both_in]).unwrap();
+
+    let shot = &sequence.shots[0];
+    assert_eq!(shot.foul, Some(Foul::Scratch));
+    assert!(shot
+        .result
+        .pocketed
+        .iter()
+        .any(|(ty, _)| *ty == BallType::Nine));
+    assert_eq!(shot.spotted, vec![BallType::Nine]);
+    assert!(sequence
+        .pocketed()
+        .iter()
+        .all(|(ty, _)| *ty != BallType::Nine));
+    assert_eq!(
+        sequence
+            .final_state
+            .select_ball(BallType::Nine)
+            .unwrap()
+            .position,
+        Position::new("2", "2")
+    );
+}
+
+#[test]
+fn rules_without_spotting_leave_balls_made_on_a_foul_down() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::Nine, "2", "4"),
+        ],
+    );
+    let follow_both_in = shot_toward(
+        &Position::new("1", "4"),
+        &Position::new("2", "4"),
+        "150",
+        CueTipContact::new(Scale::zero(), Scale::from_f64(0.45)).unwrap(),
+    );
+
+    let sequence = simulate_sequence_with(
+        &EventDrivenSimulator::default(),
+        &AnyBallRules,
+        &state,
+        &[follow_both_in],
+    )
+    .unwrap();
+
+    assert_eq!(sequence.foul(), Some(&Foul::Scratch));
+    assert!(sequence.shots[0].spotted.is_empty());
+    assert!(sequence.final_state.select_ball(BallType::Nine).is_none());
+}
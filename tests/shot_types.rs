@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use billiards::{
     format_shot_speed, strike_resting_ball_on_table, BallSetPhysicsSpec, BallState,
-    CueStrikeConfig, CueTipContact, HumanShotSpeedBand, Inches2, InchesPerSecond,
+    CueStrikeConfig, CueTipContact, HumanShotSpeedBand, Inches2, InchesPerSecond, Position,
     RestingOnTableBallState, Scale, Shot, ShotError, ShotSpeedPreset,
 };
 
@@ -209,6 +209,57 @@ fn shot_rejects_negative_cue_speed() {
     }
 }
 
+#[test]
+fn shot_defaults_to_a_level_cue_and_accepts_an_elevation() {
+    let shot = Shot::new(
+        billiards::Angle::from_north(0.0, 1.0),
+        InchesPerSecond::new("18"),
+        CueTipContact::center(),
+    )
+    .expect("shot should validate");
+
+    assert_close(shot.cue_elevation_degrees(), 0.0);
+    let elevated = shot
+        .clone()
+        .with_cue_elevation_degrees(12.5)
+        .expect("elevation should validate");
+    assert_close(elevated.cue_elevation_degrees(), 12.5);
+    assert_eq!(elevated.heading(), shot.heading());
+    assert_ne!(elevated, shot);
+}
+
+#[test]
+fn shot_rejects_a_cue_elevation_outside_zero_to_ninety_degrees() {
+    let shot = Shot::new(
+        billiards::Angle::from_north(0.0, 1.0),
+        InchesPerSecond::new("18"),
+        CueTipContact::center(),
+    )
+    .expect("shot should validate");
+
+    for elevation in [-1.0, 90.0, f64::NAN] {
+        match shot.clone().with_cue_elevation_degrees(elevation) {
+            Err(ShotError::CueElevationOutOfRange {
+                cue_elevation_degrees,
+            }) => assert!(cue_elevation_degrees.is_nan() || cue_elevation_degrees == elevation),
+            other => panic!("expected cue elevation error, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn shot_toward_a_target_aims_along_the_line_between_them() {
+    let shot = Shot::toward(
+        &Position::new("1", "1"),
+        &Position::new("2", "2"),
+        InchesPerSecond::new("60"),
+        CueTipContact::center(),
+    )
+    .expect("shot should validate");
+
+    assert_close(shot.heading().as_degrees(), 45.0);
+}
+
 #[test]
 fn cue_strike_config_accepts_positive_mass_ratio_and_unit_interval_energy_loss() {
     let config = CueStrikeConfig::new(Scale::from_f64(3.0), Scale::from_f64(0.2))
@@ -0,0 +1,446 @@
+use billiards::simulation::{
+    simulate, simulate_with_config, DirtyCloth, EventDrivenSimulator, FixedStepSimulator,
+    SimulationConfig, SimulationError, SimulationResult, Simulator, TableTilt,
+};
+use billiards::{
+    Angle, Ball, BallSpec, BallType, CueTipContact, Diamond, GameState, InchesPerSecond,
+    MotionPhase, NBallSystemEvent, NextTransition, Pocket, Position, RailCollisionConfig,
+    RailCollisionProfile, Scale, Seconds, Shot, TableSpec,
+};
+
+fn as_f64(value: &Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn side_pocket_layout() -> GameState {
+    GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "2", "4"),
+            ball_at(BallType::Two, "1", "1"),
+        ],
+    )
+}
+
+fn cue_ball_only_layout() -> GameState {
+    GameState::with_balls(TableSpec::default(), [ball_at(BallType::Cue, "1", "4")])
+}
+
+fn shot_at(target: &Position, speed: &str) -> Shot {
+    Shot::toward(
+        &Position::new("1", "4"),
+        target,
+        InchesPerSecond::new(speed),
+        CueTipContact::center(),
+    )
+    .expect("shot should validate")
+}
+
+#[test]
+fn a_result_replays_the_layout_at_any_moment_of_the_shot() {
+    let state = side_pocket_layout();
+    let result = simulate(&state, &shot_at(&Position::new("2", "4"), "80")).unwrap();
+    let x_of = |layout: &GameState, ty: BallType| {
+        layout.select_ball(ty).map(|ball| as_f64(&ball.position.x))
+    };
+
+    let start = result.layout_at(Seconds::new(0.0));
+    assert_eq!(start.balls().len(), 3);
+    assert!((x_of(&start, BallType::Cue).unwrap() - 1.0).abs() < 1e-9);
+
+    // Just before the contact the cue ball is almost a ball's width from the one ball, which
+    // hasn't moved yet.
+    let contact = result.event_log[0].time.as_f64();
+    let approaching = result.layout_at(Seconds::new(contact * 0.99));
+    let cue_x = x_of(&approaching, BallType::Cue).unwrap();
+    assert!(cue_x > 1.8 && cue_x < 1.82, "{cue_x}");
+    assert_eq!(x_of(&approaching, BallType::One), Some(2.0));
+
+    let end = result.layout_at(result.elapsed());
+    assert_eq!(x_of(&end, BallType::One), None);
+    for ball in result.final_state.balls() {
+        let replayed = end.select_ball(ball.ty.clone()).unwrap();
+        assert!((as_f64(&replayed.position.x) - as_f64(&ball.position.x)).abs() < 1e-6);
+        assert!((as_f64(&replayed.position.y) - as_f64(&ball.position.y)).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn a_straight_in_shot_pockets_the_object_ball() {
+    let state = side_pocket_layout();
+
+    let result = simulate(&state, &shot_at(&Position::new("2", "4"), "80")).unwrap();
+
+    assert_eq!(result.pocketed, vec![(BallType::One, Pocket::CenterRight)]);
+    assert!(!result.scratched());
+    assert!(result.final_state.select_ball(BallType::One).is_none());
+    let balls: Vec<BallType> = result
+        .final_state
+        .balls()
+        .iter()
+        .map(|ball| ball.ty.clone())
+        .collect();
+    assert_eq!(balls, vec![BallType::Cue, BallType::Two]);
+    assert_eq!(
+        result
+            .final_state
+            .select_ball(BallType::Two)
+            .unwrap()
+            .position,
+        Position::new("1", "1")
+    );
+}
+
+#[test]
+fn the_event_log_is_in_time_order_and_starts_with_the_hit() {
+    let state = side_pocket_layout();
+
+    let result = simulate(&state, &shot_at(&Position::new("2", "4"), "80")).unwrap();
+
+    let first_contact = result
+        .events()
+        .iter()
+        .position(|event| matches!(event, NBallSystemEvent::BallBallCollision { .. }))
+        .expect("the cue ball should hit the object ball");
+    assert!(result.events()[..first_contact]
+        .iter()
+        .all(|event| matches!(
+            event,
+            NBallSystemEvent::MotionTransition { ball_index: 0, .. }
+        )));
+    let total: f64 = result
+        .events()
+        .iter()
+        .map(|event| event.time().as_f64())
+        .sum();
+    assert!((total - result.elapsed().as_f64()).abs() < 1e-9);
+    assert!(result
+        .events()
+        .iter()
+        .all(|event| event.time().as_f64() >= 0.0));
+    assert_eq!(
+        result.balls,
+        vec![BallType::Cue, BallType::One, BallType::Two]
+    );
+}
+
+#[test]
+fn a_miss_leaves_every_ball_at_rest_on_the_table() {
+    let state = side_pocket_layout();
+
+    let result = simulate(&state, &shot_at(&Position::new("0.5", "7"), "60")).unwrap();
+
+    assert!(result.pocketed.is_empty());
+    assert_eq!(result.final_state.balls().len(), 3);
+    assert_eq!(
+        result
+            .final_state
+            .select_ball(BallType::One)
+            .unwrap()
+            .position,
+        Position::new("2", "4")
+    );
+    assert_ne!(
+        result
+            .final_state
+            .select_ball(BallType::Cue)
+            .unwrap()
+            .position,
+        Position::new("1", "4")
+    );
+}
+
+#[test]
+fn the_event_limit_stops_the_run_early() {
+    let state = side_pocket_layout();
+    let config = SimulationConfig {
+        max_events: Some(1),
+        ..SimulationConfig::default()
+    };
+
+    let result =
+        simulate_with_config(&state, &shot_at(&Position::new("2", "4"), "80"), &config).unwrap();
+
+    assert_eq!(result.events().len(), 1);
+    assert!(result.pocketed.is_empty());
+}
+
+#[test]
+fn a_layout_without_a_cue_ball_cannot_be_simulated() {
+    let state = GameState::with_balls(TableSpec::default(), [ball_at(BallType::One, "2", "4")]);
+
+    assert!(matches!(
+        simulate(&state, &shot_at(&Position::new("2", "4"), "80")),
+        Err(SimulationError::NoCueBall)
+    ));
+}
+
+#[test]
+fn both_backends_play_the_straight_in_shot_the_same_way() {
+    let state = side_pocket_layout();
+    let shot = shot_at(&Position::new("2", "4"), "80");
+    let simulators: [&dyn Simulator; 2] = [
+        &EventDrivenSimulator::default(),
+        &FixedStepSimulator::default(),
+    ];
+
+    let results: Vec<_> = simulators
+        .iter()
+        .map(|simulator| simulator.simulate(&state, &shot).unwrap())
+        .collect();
+
+    for result in &results {
+        assert_eq!(result.pocketed, vec![(BallType::One, Pocket::CenterRight)]);
+    }
+    let cue_x = |index: usize| {
+        as_f64(
+            &results[index]
+                .final_state
+                .select_ball(BallType::Cue)
+                .unwrap()
+                .position
+                .x,
+        )
+    };
+    assert!(
+        (cue_x(0) - cue_x(1)).abs() < 0.05,
+        "{} vs {}",
+        cue_x(0),
+        cue_x(1)
+    );
+    assert!((results[0].elapsed().as_f64() - results[1].elapsed().as_f64()).abs() < 0.05);
+}
+
+#[test]
+fn the_fixed_step_event_log_records_contacts_captures_and_stops() {
+    let state = side_pocket_layout();
+
+    let result = FixedStepSimulator::default()
+        .with_time_step(Seconds::new(0.002))
+        .simulate(&state, &shot_at(&Position::new("2", "4"), "80"))
+        .unwrap();
+
+    assert!(matches!(
+        result.events(),
+        [
+            NBallSystemEvent::BallBallCollision {
+                first_ball_index: 0,
+                second_ball_index: 1,
+                ..
+            },
+            NBallSystemEvent::BallPocketCapture { ball_index: 1, .. },
+            NBallSystemEvent::MotionTransition {
+                ball_index: 0,
+                transition: NextTransition {
+                    phase_after: MotionPhase::Rest,
+                    ..
+                },
+            },
+        ]
+    ));
+}
+
+#[test]
+fn a_tilted_table_pulls_the_cue_ball_downhill() {
+    let state = cue_ball_only_layout();
+    let shot = shot_at(&Position::new("0.5", "7"), "80");
+    let cue_x = |simulator: FixedStepSimulator| {
+        let result = simulator
+            .with_time_step(Seconds::new(0.002))
+            .simulate(&state, &shot)
+            .unwrap();
+        as_f64(
+            &result
+                .final_state
+                .select_ball(BallType::Cue)
+                .unwrap()
+                .position
+                .x,
+        )
+    };
+
+    let level = cue_x(FixedStepSimulator::default());
+    let tilted = cue_x(
+        FixedStepSimulator::default().with_force(TableTilt::new(Angle::from_north(1.0, 0.0), 0.5)),
+    );
+
+    assert!(tilted > level + 1.0, "level {level}, tilted {tilted}");
+}
+
+#[test]
+fn balls_settle_against_the_low_cushion_of_a_steep_tilt() {
+    let state = side_pocket_layout();
+    let simulator = FixedStepSimulator::default()
+        .with_time_step(Seconds::new(0.002))
+        .with_force(TableTilt::new(Angle::from_north(1.0, 0.0), 2.0))
+        .with_max_duration(Seconds::new(20.0));
+
+    let result = simulator
+        .simulate(&state, &shot_at(&Position::new("0.5", "7"), "80"))
+        .unwrap();
+
+    assert!(result.elapsed().as_f64() < 20.0);
+    for ty in [BallType::Cue, BallType::Two] {
+        let ball = result.final_state.select_ball(ty).unwrap();
+        assert!((as_f64(&ball.position.x) - 3.91).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn a_dirty_patch_of_cloth_stops_the_cue_ball_sooner() {
+    let state = cue_ball_only_layout();
+    let shot = shot_at(&Position::new("0.5", "7"), "80");
+    let settle_time = |simulator: FixedStepSimulator| {
+        simulator
+            .with_time_step(Seconds::new(0.002))
+            .simulate(&state, &shot)
+            .unwrap()
+            .elapsed()
+            .as_f64()
+    };
+
+    let clean = settle_time(FixedStepSimulator::default());
+    let dirty = settle_time(FixedStepSimulator::default().with_force(DirtyCloth::new(
+        Position::new("1", "6"),
+        Diamond::one(),
+        60.0,
+    )));
+
+    assert!(dirty < 0.6 * clean, "clean {clean}, dirty {dirty}");
+}
+
+#[test]
+fn dead_cushions_on_the_table_spec_take_speed_off_every_rebound() {
+    let shot = shot_at(&Position::new("0.5", "7"), "80");
+    let dead_cushions = RailCollisionProfile::uniform(RailCollisionConfig {
+        normal_restitution: Scale::from_f64(0.4),
+        ..RailCollisionConfig::human_tuned()
+    });
+    let dead_table = TableSpec::default().with_cushions(dead_cushions);
+    let elapsed = |table: TableSpec, simulator: &dyn Simulator| {
+        let state = GameState::with_balls(table, [ball_at(BallType::Cue, "1", "4")]);
+        simulator
+            .simulate(&state, &shot)
+            .unwrap()
+            .elapsed()
+            .as_f64()
+    };
+
+    let fixed_step = FixedStepSimulator::default().with_time_step(Seconds::new(0.002));
+    for simulator in [
+        &EventDrivenSimulator::default() as &dyn Simulator,
+        &fixed_step,
+    ] {
+        let lively = elapsed(TableSpec::default(), simulator);
+        let dead = elapsed(dead_table.clone(), simulator);
+        assert!(dead < 0.8 * lively, "lively {lively}, dead {dead}");
+    }
+}
+
+#[test]
+fn side_spin_turns_a_straight_on_cushion_rebound() {
+    let state = GameState::with_balls(TableSpec::default(), [ball_at(BallType::Cue, "2", "6")]);
+    let final_x = |side: f64| {
+        let shot = Shot::toward(
+            &Position::new("2", "6"),
+            &Position::new("2", "8"),
+            InchesPerSecond::new("60"),
+            CueTipContact::new(Scale::from_f64(side), Scale::zero()).unwrap(),
+        )
+        .unwrap();
+        let result = simulate(&state, &shot).unwrap();
+        as_f64(
+            &result
+                .final_state
+                .select_ball(BallType::Cue)
+                .unwrap()
+                .position
+                .x,
+        )
+    };
+
+    let right = final_x(0.4);
+    let center = final_x(0.0);
+    let left = final_x(-0.4);
+
+    assert!((center - 2.0).abs() < 1e-6, "{center}");
+    assert!(
+        (right - 2.0) * (left - 2.0) < 0.0,
+        "right {right}, left {left}"
+    );
+    assert!((right - 2.0).abs() > 0.1 && (left - 2.0).abs() > 0.1);
+}
+
+fn down_the_right_rail(x: &str) -> (GameState, Shot) {
+    let state = GameState::with_balls(TableSpec::default(), [ball_at(BallType::Cue, x, "1.5")]);
+    let shot = Shot::toward(
+        &Position::new(x, "1.5"),
+        &Position::new(x, "0"),
+        InchesPerSecond::new("40"),
+        CueTipContact::center(),
+    )
+    .expect("shot should validate");
+    (state, shot)
+}
+
+fn jaw_impacts(result: &SimulationResult) -> Vec<Pocket> {
+    result
+        .events()
+        .iter()
+        .filter_map(|event| match event {
+            NBallSystemEvent::BallJawImpact { impact, .. } => Some(impact.pocket),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn a_ball_that_rattles_between_the_jaws_is_a_near_miss_not_a_make() {
+    let (state, shot) = down_the_right_rail("3.78");
+
+    let result = simulate(&state, &shot).unwrap();
+
+    assert_eq!(
+        jaw_impacts(&result),
+        vec![Pocket::BottomRight, Pocket::BottomRight]
+    );
+    assert!(result.pocketed.is_empty());
+    assert_eq!(result.rattled, vec![(BallType::Cue, Pocket::BottomRight)]);
+    assert!(result.final_state.select_ball(BallType::Cue).is_some());
+}
+
+#[test]
+fn a_clean_make_is_not_reported_as_a_rattle() {
+    let (state, shot) = down_the_right_rail("3.85");
+
+    let result = simulate(&state, &shot).unwrap();
+
+    assert!(jaw_impacts(&result).is_empty());
+    assert_eq!(result.pocketed, vec![(BallType::Cue, Pocket::BottomRight)]);
+    assert!(result.rattled.is_empty());
+}
+
+#[test]
+fn the_fixed_step_backend_bounces_balls_off_the_jaws() {
+    let (state, shot) = down_the_right_rail("3.75");
+    let simulator = FixedStepSimulator::default().with_time_step(Seconds::new(0.002));
+
+    let fixed = simulator.simulate(&state, &shot).unwrap();
+    let event_driven = EventDrivenSimulator::default()
+        .simulate(&state, &shot)
+        .unwrap();
+
+    assert_eq!(jaw_impacts(&fixed), vec![Pocket::BottomRight]);
+    assert!(fixed.pocketed.is_empty());
+    assert_eq!(fixed.rattled, event_driven.rattled);
+    assert_eq!(fixed.rattled, vec![(BallType::Cue, Pocket::BottomRight)]);
+}
@@ -0,0 +1,111 @@
+use billiards::diagram::DiagramElement;
+use billiards::drills::spot_shot;
+use billiards::visualization::DashedLineStyle;
+use billiards::{
+    BallType, DiagramRenderOptions, GameState, Pocket, Position, Rail, TableSpec, RACK_SPOT,
+};
+use image::Rgba;
+
+fn as_f64(value: &billiards::Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+#[test]
+fn a_spot_shot_from_the_middle_of_the_kitchen_is_about_a_half_ball_cut() {
+    let table = TableSpec::default();
+
+    let shot = spot_shot(&Position::new("2", "7"), Pocket::BottomRight, &table)
+        .expect("spot shot should exist");
+
+    assert_eq!(shot.object_ball.ty, BallType::One);
+    assert_eq!(shot.object_ball.position, *RACK_SPOT);
+    assert_eq!(shot.cue_ball.ty, BallType::Cue);
+    assert_eq!(shot.aim_line.end, shot.ghost_ball);
+    assert!((40.0..50.0).contains(&shot.cut_angle.as_degrees()));
+}
+
+#[test]
+fn a_spot_shot_needs_the_cue_ball_in_the_kitchen_and_a_foot_corner() {
+    let table = TableSpec::default();
+
+    assert!(spot_shot(&Position::new("2", "5"), Pocket::BottomRight, &table).is_none());
+    assert!(spot_shot(&Position::new("2", "7"), Pocket::CenterRight, &table).is_none());
+    assert!(spot_shot(&Position::new("2", "7"), Pocket::TopLeft, &table).is_none());
+    assert!(spot_shot(&Position::new("1", "6"), Pocket::BottomLeft, &table).is_some());
+}
+
+#[test]
+fn scratch_lines_run_from_the_ghost_ball_into_a_corner_pocket() {
+    let table = TableSpec::default();
+
+    let shot = spot_shot(&Position::new("1", "7"), Pocket::BottomRight, &table).unwrap();
+
+    assert!(!shot.scratch_lines.is_empty());
+    for line in &shot.scratch_lines {
+        assert!(!matches!(
+            line.pocket,
+            Pocket::CenterLeft | Pocket::CenterRight
+        ));
+        assert_eq!(line.path[0], shot.ghost_ball);
+        assert_eq!(*line.path.last().unwrap(), line.pocket.aiming_center());
+        assert_eq!(line.path.len(), line.rails.len() + 2);
+        assert!(line
+            .rails
+            .iter()
+            .all(|rail| matches!(rail, Rail::Left | Rail::Right)));
+    }
+    // Straight into the pocket being shot means going through the object ball.
+    assert!(!shot
+        .scratch_lines
+        .iter()
+        .any(|line| line.pocket == shot.pocket && line.rails.is_empty()));
+}
+
+#[test]
+fn the_stun_tangent_line_heads_for_the_other_foot_corner() {
+    let table = TableSpec::default();
+
+    let shot = spot_shot(&Position::new("2", "7"), Pocket::BottomRight, &table).unwrap();
+    let heading = shot.tangent_line.start.angle_to(&shot.tangent_line.end);
+    let (nearest, degrees_off) = shot
+        .nearest_scratch_line(&heading)
+        .expect("some scratch line should exist");
+
+    assert_eq!(nearest.pocket, Pocket::BottomLeft);
+    assert!(nearest.rails.is_empty());
+    assert!(degrees_off < 5.0, "{degrees_off}");
+    assert!(as_f64(&shot.tangent_line.end.x) < 0.5);
+}
+
+#[test]
+fn the_spot_shot_overlay_draws_the_aim_object_and_scratch_lines() {
+    let table = TableSpec::default();
+    let shot = spot_shot(&Position::new("2", "7"), Pocket::BottomRight, &table).unwrap();
+    let mut game_state: GameState = shot.game_state(table);
+    let scratch_color = Rgba([255, 0, 0, 255]);
+
+    game_state.add_spot_shot_overlay(
+        &shot,
+        &DashedLineStyle::new(Rgba([255, 255, 255, 255])),
+        &DashedLineStyle::new(scratch_color),
+    );
+
+    assert_eq!(game_state.balls().len(), 2);
+    let scene = game_state.to_diagram_scene(&DiagramRenderOptions::default());
+    let dashed = |color: Rgba<u8>| {
+        scene
+            .elements
+            .iter()
+            .filter(|element| {
+                matches!(element, DiagramElement::DashedLine { style, .. } if style.color == color)
+            })
+            .count()
+    };
+    let scratch_legs: usize = shot
+        .scratch_lines
+        .iter()
+        .map(|line| line.path.len() - 1)
+        .sum();
+    assert_eq!(dashed(Rgba([255, 255, 255, 255])), 2);
+    assert_eq!(dashed(scratch_color), scratch_legs);
+}
@@ -0,0 +1,107 @@
+use billiards::aiming::{
+    fractional_aim_ghost_ball, stun_tangent_line, stun_tangent_line_to_pocket, BallHitFraction,
+    CutDirection,
+};
+use billiards::{Ball, BallSpec, BallType, CutAngle, Pocket, Position, TableSpec};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn as_f64(value: &billiards::Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn object_ball_at(x: &str, y: &str) -> Ball {
+    Ball {
+        ty: BallType::Nine,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+#[test]
+fn the_stun_tangent_line_is_perpendicular_to_the_object_ball_path() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new(2u8, 2u8);
+    let object_ball = object_ball_at("2", "5");
+    let ghost = fractional_aim_ghost_ball(
+        &cue_ball,
+        &object_ball,
+        BallHitFraction::Half.fraction(),
+        CutDirection::Right,
+        &table,
+    );
+
+    let tangent = stun_tangent_line(&cue_ball, &ghost, &object_ball, &table);
+    let object_heading = ghost.angle_to(&object_ball.position);
+
+    assert_eq!(tangent.start, ghost);
+    assert_close_with_tolerance(
+        CutAngle::from_headings(tangent.heading(), object_heading).as_degrees(),
+        90.0,
+        1e-6,
+    );
+}
+
+#[test]
+fn the_cue_ball_leaves_on_the_side_opposite_the_cut() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new(2u8, 2u8);
+    let object_ball = object_ball_at("2", "5");
+    let ghost = fractional_aim_ghost_ball(
+        &cue_ball,
+        &object_ball,
+        BallHitFraction::Half.fraction(),
+        CutDirection::Right,
+        &table,
+    );
+
+    let tangent = stun_tangent_line(&cue_ball, &ghost, &object_ball, &table);
+
+    assert!(as_f64(&tangent.end.x) < as_f64(&tangent.start.x));
+}
+
+#[test]
+fn the_stun_tangent_line_is_clipped_to_the_cushion_limit_of_the_ball_center() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("2", "5");
+    let ghost = Position::new("1.82", "5");
+
+    // A 45-degree approach into a ball directly to the right sends the cue ball straight up.
+    let tangent = stun_tangent_line(&Position::new("0.82", "4"), &ghost, &object_ball, &table);
+    let ball_radius_in_diamonds = 1.125 / 12.5;
+
+    assert_close_with_tolerance(as_f64(&tangent.end.x), 1.82, 1e-9);
+    assert_close_with_tolerance(as_f64(&tangent.end.y), 8.0 - ball_radius_in_diamonds, 1e-9);
+}
+
+#[test]
+fn a_full_ball_stun_shot_leaves_the_cue_ball_at_the_contact_point() {
+    let table = TableSpec::default();
+    let object_ball = object_ball_at("2", "5");
+    let ghost = Position::new("2", "4.82");
+
+    let tangent = stun_tangent_line(&Position::new(2u8, 2u8), &ghost, &object_ball, &table);
+
+    assert_eq!(tangent.start, tangent.end);
+}
+
+#[test]
+fn the_pocket_variant_uses_the_ghost_ball_for_that_pocket() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new(2u8, 2u8);
+    let object_ball = object_ball_at("3", "6");
+
+    let tangent = stun_tangent_line_to_pocket(&cue_ball, &object_ball, Pocket::TopRight, &table);
+
+    assert_eq!(
+        tangent.start,
+        object_ball.ghost_ball_to_pocket(Pocket::TopRight, &table)
+    );
+    assert!(as_f64(&tangent.length()) > 0.0);
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "serde")]
+
+use billiards::tables::{TableLibrary, TableProfile};
+use billiards::{Inches, PlayingConditions, PocketShapeSpec, Scale, TableSpec};
+
+fn table_seven() -> TableProfile {
+    let mut table_spec = TableSpec::default();
+    table_spec.pockets[1] = table_spec.pockets[1]
+        .clone()
+        .with_shape(PocketShapeSpec::rounded_noses(Inches::from_f64(0.25)));
+    table_spec.cushions.left.normal_restitution = Scale::from_f64(0.7);
+    TableProfile::new("Table 7", table_spec).with_conditions(PlayingConditions::humid_dirty())
+}
+
+#[test]
+fn a_table_profile_round_trips_through_json_with_its_pockets_and_conditions() {
+    let profile = table_seven();
+
+    let json = profile.to_json();
+    let restored = TableProfile::from_json(&json).unwrap();
+
+    assert_eq!(restored, profile);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["name"], "Table 7");
+    let sliding: f64 = value["conditions"]["sliding_friction_scale"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(sliding, 1.2);
+
+    let neutral = TableProfile::from_json(
+        r#"{ "name": "Stock", "table_spec": TABLE }"#
+            .replace(
+                "TABLE",
+                &serde_json::to_string(&TableSpec::default()).unwrap(),
+            )
+            .as_str(),
+    )
+    .unwrap();
+    assert_eq!(neutral.conditions, PlayingConditions::neutral());
+    assert_eq!(neutral.conditioned_table_spec(), TableSpec::default());
+    assert!(
+        profile.simulation_config().motion != neutral.simulation_config().motion,
+        "dirty cloth should slow the balls"
+    );
+    assert!(
+        profile
+            .conditioned_table_spec()
+            .cushions
+            .left
+            .normal_restitution
+            .as_f64()
+            < 0.7
+    );
+}
+
+#[test]
+fn a_table_library_saves_and_loads_tables_by_name() {
+    let dir = std::env::temp_dir().join(format!("billiards-tables-{}", std::process::id()));
+    let library = TableLibrary::new(&dir);
+
+    let path = library.save(&table_seven()).unwrap();
+    library
+        .save(&TableProfile::new("Bar box", TableSpec::default()))
+        .unwrap();
+
+    assert_eq!(path, dir.join("table-7.json"));
+    assert_eq!(library.load("Table 7").unwrap(), table_seven());
+    assert_eq!(library.load("table 7").unwrap().name, "Table 7");
+    assert_eq!(library.names().unwrap(), ["Bar box", "Table 7"]);
+    let missing = library.load("Table 9").unwrap_err();
+    assert!(missing.contains("table-9.json"), "{missing}");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_table_library_refuses_names_without_a_file_of_their_own() {
+    let dir = std::env::temp_dir().join(format!("billiards-clashes-{}", std::process::id()));
+    let library = TableLibrary::new(&dir);
+
+    assert_eq!(
+        library.path_for("?!").unwrap_err(),
+        "table name '?!' has no letters or digits"
+    );
+    assert!(library
+        .save(&TableProfile::new("--", TableSpec::default()))
+        .is_err());
+
+    library.save(&table_seven()).unwrap();
+    library.save(&table_seven()).unwrap();
+    let clash = library
+        .save(&TableProfile::new("Table-7", TableSpec::default()))
+        .unwrap_err();
+    assert!(
+        clash.ends_with("already holds table 'Table 7', so 'Table-7' can't be saved there"),
+        "{clash}"
+    );
+    assert_eq!(library.load("Table 7").unwrap(), table_seven());
+    assert_eq!(library.names().unwrap(), ["Table 7"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
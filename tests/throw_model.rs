@@ -0,0 +1,132 @@
+use billiards::aiming::{
+    check_combination, check_combination_with_throw, throw_adjusted_ghost_ball,
+};
+use billiards::banking::single_rail_bank;
+use billiards::{
+    Ball, BallBallCollisionConfig, BallSpec, BallType, CutAngle, Inches, InchesPerSecond, Pocket,
+    Position, Rail, Scale, TableSpec, ThrowModel,
+};
+
+fn assert_close_with_tolerance(actual: f64, expected: f64, tolerance: f64) {
+    let delta = (actual - expected).abs();
+    assert!(
+        delta <= tolerance,
+        "expected {expected} +/- {tolerance}, got {actual} (delta {delta})"
+    );
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn throw_model(speed: f64) -> ThrowModel {
+    ThrowModel::new(
+        BallBallCollisionConfig::human_tuned(),
+        InchesPerSecond::new(Inches::from_f64(speed)),
+    )
+}
+
+fn signed_degrees(degrees: f64) -> f64 {
+    (degrees + 540.0).rem_euclid(360.0) - 180.0
+}
+
+#[test]
+fn a_straight_in_shot_does_not_throw() {
+    assert_close_with_tolerance(
+        throw_model(60.0).throw_angle_degrees(CutAngle::new(0.0)),
+        0.0,
+        1e-12,
+    );
+}
+
+#[test]
+fn a_half_ball_cut_throws_a_few_degrees_and_more_when_slow() {
+    let half_ball = CutAngle::new(30.0);
+
+    let slow = throw_model(20.0).throw_angle_degrees(half_ball);
+    let fast = throw_model(150.0).throw_angle_degrees(half_ball);
+
+    assert!(fast > 0.0);
+    assert!(slow > fast);
+    assert!(slow < 6.0);
+}
+
+#[test]
+fn geared_outside_english_cancels_throw_and_more_reverses_it() {
+    let half_ball = CutAngle::new(30.0);
+
+    let geared = throw_model(60.0)
+        .with_outside_english(Scale::from_f64(0.5))
+        .throw_angle_degrees(half_ball);
+    let overspun = throw_model(60.0)
+        .with_outside_english(Scale::from_f64(0.8))
+        .throw_angle_degrees(half_ball);
+
+    assert_close_with_tolerance(geared, 0.0, 1e-9);
+    assert!(overspun < 0.0);
+}
+
+#[test]
+fn the_throw_adjusted_ghost_ball_throws_the_object_ball_into_the_pocket() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new("1", "2");
+    let object_ball = ball_at(BallType::Nine, "2", "5");
+    let pocket = Pocket::TopRight.aiming_center();
+    let model = throw_model(40.0);
+
+    let plain = object_ball.ghost_ball(&pocket, &table);
+    let adjusted = throw_adjusted_ghost_ball(&cue_ball, &object_ball, &pocket, &model, &table);
+
+    let line_of_centers = adjusted.angle_to(&object_ball.position).as_degrees();
+    let cut = CutAngle::from_headings(
+        cue_ball.angle_to(&adjusted),
+        adjusted.angle_to(&object_ball.position),
+    );
+    let desired = object_ball.position.angle_to(&pocket).as_degrees();
+    assert_close_with_tolerance(
+        signed_degrees(desired - line_of_centers).abs(),
+        model.throw_angle_degrees(cut),
+        1e-6,
+    );
+    let plain_cut = CutAngle::from_headings(
+        cue_ball.angle_to(&plain),
+        plain.angle_to(&object_ball.position),
+    );
+    assert!(cut.as_degrees() > plain_cut.as_degrees());
+}
+
+#[test]
+fn a_bank_ghost_ball_only_moves_when_a_throw_model_is_given() {
+    let table = TableSpec::default();
+    let cue_ball = Position::new("2.5", "2");
+    let object_ball = ball_at(BallType::Nine, "1", "4");
+    let bank = single_rail_bank(&object_ball, Pocket::CenterRight, Rail::Left, &table).unwrap();
+
+    let plain = bank.ghost_ball(&cue_ball, &object_ball, None, &table);
+    let thrown = bank.ghost_ball(&cue_ball, &object_ball, Some(&throw_model(40.0)), &table);
+
+    assert_eq!(plain, object_ball.ghost_ball(&bank.path[1], &table));
+    assert_ne!(thrown, plain);
+}
+
+#[test]
+fn a_combination_checked_without_throw_matches_the_plain_check() {
+    let table = TableSpec::default();
+    let balls = [
+        ball_at(BallType::One, "1.5", "4"),
+        ball_at(BallType::Two, "2.5", "5.5"),
+    ];
+
+    let plain = check_combination(&balls, Pocket::TopRight, &table);
+    let unthrown = check_combination_with_throw(&balls, Pocket::TopRight, None, &table);
+    let thrown =
+        check_combination_with_throw(&balls, Pocket::TopRight, Some(&throw_model(40.0)), &table);
+
+    assert_eq!(unthrown, plain);
+    assert_ne!(thrown.aiming_lines[0], plain.aiming_lines[0]);
+    assert_eq!(thrown.aiming_lines[1], plain.aiming_lines[1]);
+}
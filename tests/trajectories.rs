@@ -0,0 +1,163 @@
+use billiards::simulation::{simulate, EventKind, FixedStepSimulator, SimulationResult, Simulator};
+use billiards::{
+    Ball, BallSpec, BallType, CueTipContact, Diamond, GameState, InchesPerSecond, Position, Scale,
+    Shot, TableSpec,
+};
+
+fn as_f64(value: &Diamond) -> f64 {
+    value.magnitude.to_string().parse().unwrap()
+}
+
+fn xy(position: &Position) -> (f64, f64) {
+    (as_f64(&position.x), as_f64(&position.y))
+}
+
+fn ball_at(ty: BallType, x: &str, y: &str) -> Ball {
+    Ball {
+        ty,
+        position: Position::new(x, y),
+        spec: BallSpec::default(),
+    }
+}
+
+fn straight_in() -> (GameState, Shot) {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "4"),
+            ball_at(BallType::One, "2", "4"),
+            ball_at(BallType::Nine, "1", "7"),
+        ],
+    );
+    let shot = Shot::toward(
+        &Position::new("1", "4"),
+        &Position::new("2", "4"),
+        InchesPerSecond::new("80"),
+        CueTipContact::center(),
+    )
+    .unwrap();
+    (state, shot)
+}
+
+fn final_position(result: &SimulationResult, ball: BallType) -> Position {
+    result
+        .final_state
+        .select_ball(ball)
+        .unwrap()
+        .position
+        .clone()
+}
+
+/// Diamonds from `point` to the line through `start` and `end`.
+fn distance_from_chord(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    ((point.0 - start.0) * dy - (point.1 - start.1) * dx).abs() / dx.hypot(dy)
+}
+
+#[test]
+fn a_straight_shot_traces_each_ball_from_its_start_to_where_it_stopped_or_dropped() {
+    let (state, shot) = straight_in();
+
+    let result = simulate(&state, &shot).unwrap();
+
+    let cue = result.trajectory(BallType::Cue).unwrap();
+    assert_eq!(cue.first(), Some(&Position::new("1", "4")));
+    assert_eq!(cue.last(), Some(&final_position(&result, BallType::Cue)));
+    for point in cue {
+        assert!(
+            (as_f64(&point.y) - 4.0).abs() < 1e-9,
+            "{point:?} left the line"
+        );
+    }
+
+    let one = result.trajectory(BallType::One).unwrap();
+    let Some(EventKind::Pocketed { position, .. }) = result
+        .event_log
+        .iter()
+        .map(|event| &event.kind)
+        .find(|kind| matches!(kind, EventKind::Pocketed { .. }))
+    else {
+        panic!("the one should drop");
+    };
+    assert_eq!(one.first(), Some(&Position::new("2", "4")));
+    assert_eq!(one.last(), Some(position));
+}
+
+#[test]
+fn a_ball_that_never_moves_has_a_single_point_trajectory() {
+    let (state, shot) = straight_in();
+
+    let result = simulate(&state, &shot).unwrap();
+
+    assert_eq!(
+        result.trajectory(BallType::Nine),
+        Some([Position::new("1", "7")].as_slice())
+    );
+    assert_eq!(result.trajectory(BallType::Eight), None);
+    assert_eq!(result.trajectories.len(), result.balls.len());
+}
+
+#[test]
+fn a_cut_with_follow_is_densified_along_the_curve_after_contact() {
+    let state = GameState::with_balls(
+        TableSpec::default(),
+        [
+            ball_at(BallType::Cue, "1", "2"),
+            ball_at(BallType::One, "2", "4"),
+        ],
+    );
+    let shot = Shot::toward(
+        &Position::new("1", "2"),
+        &Position::new("1.92", "4"),
+        InchesPerSecond::new("100"),
+        CueTipContact::new(Scale::zero(), Scale::from_f64(0.5)).unwrap(),
+    )
+    .unwrap();
+
+    let result = simulate(&state, &shot).unwrap();
+
+    let contact = result
+        .event_log
+        .iter()
+        .find_map(|event| match &event.kind {
+            EventKind::BallBallCollision { first_position, .. } => Some(xy(first_position)),
+            _ => None,
+        })
+        .expect("the cue ball should hit the one");
+    let cue = result.trajectory(BallType::Cue).unwrap();
+    let after_contact: Vec<(f64, f64)> = cue
+        .iter()
+        .map(xy)
+        .skip_while(|point| (point.0 - contact.0).hypot(point.1 - contact.1) > 1e-9)
+        .collect();
+    let end = *after_contact.last().unwrap();
+    let bend = after_contact
+        .iter()
+        .map(|point| distance_from_chord(*point, contact, end))
+        .fold(0.0, f64::max);
+
+    assert!(
+        after_contact.len() > 5,
+        "only {} points after contact",
+        after_contact.len()
+    );
+    assert!(bend > 0.02, "the follow should bend the path, got {bend}");
+}
+
+#[test]
+fn the_fixed_step_backend_traces_the_same_ends() {
+    let (state, shot) = straight_in();
+
+    let result = FixedStepSimulator::default()
+        .simulate(&state, &shot)
+        .unwrap();
+
+    let cue = result.trajectory(BallType::Cue).unwrap();
+    assert_eq!(cue.first(), Some(&Position::new("1", "4")));
+    assert_eq!(cue.last(), Some(&final_position(&result, BallType::Cue)));
+    assert!(cue.len() > 2);
+    assert_eq!(
+        result.trajectory(BallType::Nine),
+        Some([Position::new("1", "7")].as_slice())
+    );
+}
@@ -306,6 +306,7 @@ fn render_scenario(
             ..BallPathRenderOptions::default()
         },
         start_ghost_balls: true,
+        start_phantom_balls: false,
         event_markers: true,
         labels: true,
         path_color_mode: PathColorMode::MotionPhase,
@@ -390,6 +391,7 @@ fn render_scenario(
         } else {
             DiagramBackground::Table
         },
+        ..DiagramRenderOptions::default()
     };
     let mut image_file_name = String::new();
     let mut extra_file_names = Vec::new();